@@ -0,0 +1,940 @@
+// Admin CLI for campaign operations. Builds a transaction for any program
+// instruction, signs it with local keypair files and emits it as base64, as a
+// JSON summary, or submits it to an RPC URL.
+//
+// Campaign create and update inputs are read from TOML or JSON spec files,
+// see specs/ for examples, and validated locally before anything is emitted.
+
+mod output;
+mod spec;
+
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use campaign_treasury_manager::{assert_campaign_end_time_is_valid, CrankReward};
+use campaign_treasury_manager_client::*;
+use clap::{Parser, Subcommand};
+use output::{emit_transaction, OutputMode};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+};
+use spec::{
+    read_spec, validate_crank_reward, validate_payout_phases, CreateCampaignSpec,
+    UpdateCampaignSpec,
+};
+
+#[derive(Parser)]
+#[clap(version, about = "Formfunction campaign treasury manager admin CLI")]
+struct Cli {
+    /// Keypair file of the fee payer, which is also the payer account for
+    /// instructions which have one.
+    #[clap(long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    /// Additional signer keypair files, e.g. the authority or depositor.
+    #[clap(long = "signer", global = true)]
+    signers: Vec<String>,
+    #[clap(long, global = true, default_value = "http://localhost:8899")]
+    rpc_url: String,
+    #[clap(long, global = true, arg_enum, default_value = "json")]
+    output: OutputMode,
+    /// Use this blockhash instead of fetching the latest one, which allows
+    /// building transactions without RPC access.
+    #[clap(long, global = true)]
+    recent_blockhash: Option<String>,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    CreateEscrow {
+        #[clap(long)]
+        spec: PathBuf,
+    },
+    UpdateEscrow {
+        #[clap(long)]
+        spec: PathBuf,
+    },
+    CreateDepositEscrow {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        #[clap(long)]
+        deposit_escrow_mint: Pubkey,
+    },
+    CloseDepositEscrow {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        #[clap(long)]
+        deposit_escrow_mint: Pubkey,
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        receiver: Option<Pubkey>,
+    },
+    DisableDepositEscrow {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        #[clap(long)]
+        deposit_escrow_mint: Pubkey,
+    },
+    SetDepositEscrowPriceOracle {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        #[clap(long)]
+        deposit_escrow_mint: Pubkey,
+        /// A Pyth price account, quoting the deposit_escrow_mint in the
+        /// treasury mint.
+        #[clap(long)]
+        price_oracle: Pubkey,
+    },
+    MigrateCampaignEscrow {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+    },
+    MigrateDepositRecords {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        /// The collection of the campaign NFTs, for legacy campaigns which do
+        /// not have a collection_mint yet.
+        #[clap(long)]
+        collection_mint: Option<Pubkey>,
+        /// A legacy DepositRecord to migrate, as DEPOSITOR:NFT_MINT. Can be
+        /// repeated.
+        #[clap(long = "deposit", required = true, value_parser = parse_deposit)]
+        deposits: Vec<(Pubkey, Pubkey)>,
+    },
+    CloseEscrow {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        #[clap(long)]
+        creator: Pubkey,
+        /// Defaults to the receiver for the on-chain surplus policy, which
+        /// requires RPC access.
+        #[clap(long)]
+        remainder_receiver: Option<Pubkey>,
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        receiver: Option<Pubkey>,
+    },
+    CreateDeposit {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        depositor: Option<Pubkey>,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        deposit_escrow_mint: Pubkey,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+        #[clap(long)]
+        deposit_amount: u64,
+        /// Required if the DepositEscrow has a price oracle.
+        #[clap(long)]
+        price_oracle: Option<Pubkey>,
+        /// Required if the campaign has reward tiers.
+        #[clap(long)]
+        reward_tier_index: Option<u8>,
+        /// Required if the campaign has a matching pool.
+        #[clap(long)]
+        has_matching_pool: bool,
+    },
+    Donate {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        donation_uuid: String,
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        donor: Option<Pubkey>,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+        #[clap(long)]
+        donation_amount: u64,
+        #[clap(long)]
+        refundable: bool,
+        #[clap(long)]
+        has_vote_weight: bool,
+    },
+    FundMatchingPool {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        sponsor: Option<Pubkey>,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+        /// Match per deposit in basis points, e.g. 10000 matches 1:1.
+        #[clap(long)]
+        match_basis_points: u16,
+        /// Maximum total match, which the sponsor funds up front.
+        #[clap(long)]
+        match_cap: u64,
+    },
+    CloseMatchingPool {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        sponsor: Option<Pubkey>,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+    },
+    ProcessDeposit {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        /// The original depositor of the DepositRecord.
+        #[clap(long)]
+        depositor: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        deposit_escrow_mint: Pubkey,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+        /// The swapped deposit amount in the treasury currency, which the
+        /// authority transfers to the treasury escrow.
+        #[clap(long)]
+        processed_deposit_amount: u64,
+    },
+    CloseDepositRecord {
+        #[clap(long)]
+        campaign_uuid: String,
+    },
+    CloseDepositRecords {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// A DepositRecord to close, as DEPOSITOR:NFT_MINT[:NFT_HOLDER]. The
+        /// NFT holder defaults to the depositor. Can be repeated.
+        #[clap(long = "deposit", required = true, value_parser = parse_deposit_record_to_close)]
+        deposit_records: Vec<DepositRecordToClose>,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+    },
+    PayOutFunds {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        payout_wallet: Pubkey,
+        /// Defaults to the fee payer, or its ATA for token treasuries. Not
+        /// passed if the campaign has no crank reward.
+        #[clap(long)]
+        crank_reward_receiver: Option<Pubkey>,
+        /// Pay out every payout phase which is due, instead of only the
+        /// current one.
+        #[clap(long)]
+        all_due: bool,
+    },
+    ClaimStreamedPayout {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        payout_wallet: Pubkey,
+    },
+    ProcessFullRefund {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// The current NFT holder, which must sign and receives the refund.
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        nft_holder: Option<Pubkey>,
+        /// The original depositor of the DepositRecord.
+        #[clap(long)]
+        depositor: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+    },
+    ProcessPartialRefund {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// The current NFT holder, which must sign and receives the refund.
+        /// Defaults to the fee payer.
+        #[clap(long)]
+        nft_holder: Option<Pubkey>,
+        /// The original depositor of the DepositRecord.
+        #[clap(long)]
+        depositor: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+    },
+    ProcessDonationRefund {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        donation_uuid: String,
+        /// The donor of the DonationRecord, which must sign and receives the
+        /// refund. Defaults to the fee payer.
+        #[clap(long)]
+        donor: Option<Pubkey>,
+        #[clap(long)]
+        treasury_mint: Pubkey,
+    },
+    VetoPayoutPhase {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        authority: Option<Pubkey>,
+        #[clap(long)]
+        payout_phase_index: u8,
+    },
+    VoteToVetoPayoutPhase {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// The current NFT holder, which must sign. Defaults to the fee payer.
+        #[clap(long)]
+        nft_holder: Option<Pubkey>,
+        /// The original depositor of the DepositRecord.
+        #[clap(long)]
+        depositor: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        payout_phase_index: u8,
+    },
+    VoteToVetoPayoutPhaseWithDonation {
+        #[clap(long)]
+        campaign_uuid: String,
+        #[clap(long)]
+        donation_uuid: String,
+        /// The donor of the DonationRecord, which must sign. Defaults to the
+        /// fee payer.
+        #[clap(long)]
+        donor: Option<Pubkey>,
+        #[clap(long)]
+        payout_phase_index: u8,
+    },
+    ReconcileTreasury {
+        #[clap(long)]
+        campaign_uuid: String,
+        /// Defaults to the receiver for the on-chain surplus policy, which
+        /// requires RPC access.
+        #[clap(long)]
+        surplus_receiver: Option<Pubkey>,
+    },
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(relative_path) => {
+            let home = std::env::var("HOME").context("HOME is not set")?;
+            PathBuf::from(home).join(relative_path)
+        }
+        None => PathBuf::from(path),
+    };
+
+    read_keypair_file(&path)
+        .map_err(|e| anyhow!("Failed to read keypair file {}: {}", path.display(), e))
+}
+
+fn parse_deposit(value: &str) -> Result<(Pubkey, Pubkey), String> {
+    let (depositor, mint) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Expected DEPOSITOR:NFT_MINT, received {}", value))?;
+    let parse_pubkey =
+        |pubkey: &str| Pubkey::from_str(pubkey).map_err(|e| format!("{}: {}", pubkey, e));
+
+    Ok((parse_pubkey(depositor)?, parse_pubkey(mint)?))
+}
+
+fn parse_deposit_record_to_close(value: &str) -> Result<DepositRecordToClose, String> {
+    let parse_pubkey =
+        |pubkey: &str| Pubkey::from_str(pubkey).map_err(|e| format!("{}: {}", pubkey, e));
+    let (depositor, mint, nft_holder) = match value.split(':').collect::<Vec<_>>()[..] {
+        [depositor, mint] => (depositor, mint, depositor),
+        [depositor, mint, nft_holder] => (depositor, mint, nft_holder),
+        _ => {
+            return Err(format!(
+                "Expected DEPOSITOR:NFT_MINT[:NFT_HOLDER], received {}",
+                value
+            ))
+        }
+    };
+
+    Ok(DepositRecordToClose {
+        depositor: parse_pubkey(depositor)?,
+        mint: parse_pubkey(mint)?,
+        nft_holder: parse_pubkey(nft_holder)?,
+    })
+}
+
+fn get_unix_timestamp() -> Result<i64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64)
+}
+
+fn validate_campaign_end_time(campaign_end_time: i64) -> Result<()> {
+    assert_campaign_end_time_is_valid(get_unix_timestamp()?, campaign_end_time)
+        .map_err(|e| anyhow!("Invalid campaign_end_time: {}", e))
+}
+
+// Validates the spec and returns the instruction. Payout phase updates which
+// do not also set the campaign_end_time are validated against the on-chain
+// campaign_end_time, which requires RPC access.
+fn build_update_escrow_ix(
+    rpc_client: &RpcClient,
+    spec: UpdateCampaignSpec,
+    fee_payer: &Pubkey,
+) -> Result<Instruction> {
+    let campaign_uuid = convert_uuid_to_pda_seed(&spec.campaign_uuid);
+    let accounts = UpdateEscrowAccounts {
+        payer: *fee_payer,
+        authority: spec.authority,
+        creator: spec.creator,
+        payout_wallet_owner: spec.payout_wallet_owner,
+        treasury_mint: spec.treasury_mint,
+    };
+    let input = spec.into_input()?;
+
+    if let Some(campaign_end_time) = input.campaign_end_time {
+        validate_campaign_end_time(campaign_end_time)?;
+    }
+
+    if let Some(crank_reward) = &input.crank_reward {
+        validate_crank_reward(crank_reward)?;
+    }
+
+    if let Some(payout_phases) = &input.payout_phases {
+        let campaign_end_time = match input.campaign_end_time {
+            Some(campaign_end_time) => campaign_end_time,
+            None => {
+                fetch_campaign_escrow(rpc_client, &campaign_uuid)
+                    .context("Failed to fetch the CampaignEscrow to validate payout phases")?
+                    .campaign_end_time
+            }
+        };
+        validate_payout_phases(payout_phases, campaign_end_time)?;
+    }
+
+    Ok(update_escrow_ix(accounts, &campaign_uuid, input))
+}
+
+fn build_instruction(
+    rpc_client: &RpcClient,
+    command: Command,
+    fee_payer: &Pubkey,
+) -> Result<(&'static str, Instruction)> {
+    let payer = *fee_payer;
+
+    let result = match command {
+        Command::CreateEscrow { spec } => {
+            let spec: CreateCampaignSpec = read_spec(&spec)?;
+            let campaign_uuid = convert_uuid_to_pda_seed(&spec.campaign_uuid);
+            let accounts = CreateEscrowAccounts {
+                payer,
+                authority: spec.authority,
+                creator: spec.creator,
+                payout_wallet_owner: spec.payout_wallet_owner,
+                treasury_mint: spec.treasury_mint,
+            };
+            let input = spec.into_input();
+            validate_campaign_end_time(input.campaign_end_time)?;
+            validate_payout_phases(&input.payout_phases, input.campaign_end_time)?;
+            validate_crank_reward(&input.crank_reward)?;
+
+            (
+                "create_escrow",
+                create_escrow_ix(accounts, &campaign_uuid, input),
+            )
+        }
+        Command::UpdateEscrow { spec } => (
+            "update_escrow",
+            build_update_escrow_ix(rpc_client, read_spec(&spec)?, fee_payer)?,
+        ),
+        Command::CreateDepositEscrow {
+            campaign_uuid,
+            authority,
+            deposit_escrow_mint,
+        } => (
+            "create_deposit_escrow",
+            create_deposit_escrow_ix(
+                CreateDepositEscrowAccounts {
+                    authority: authority.unwrap_or(payer),
+                    deposit_escrow_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::CloseDepositEscrow {
+            campaign_uuid,
+            authority,
+            deposit_escrow_mint,
+            receiver,
+        } => (
+            "close_deposit_escrow",
+            close_deposit_escrow_ix(
+                CloseDepositEscrowAccounts {
+                    authority: authority.unwrap_or(payer),
+                    deposit_escrow_mint,
+                    receiver: receiver.unwrap_or(payer),
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::DisableDepositEscrow {
+            campaign_uuid,
+            authority,
+            deposit_escrow_mint,
+        } => (
+            "disable_deposit_escrow",
+            disable_deposit_escrow_ix(
+                DisableDepositEscrowAccounts {
+                    authority: authority.unwrap_or(payer),
+                    deposit_escrow_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::SetDepositEscrowPriceOracle {
+            campaign_uuid,
+            authority,
+            deposit_escrow_mint,
+            price_oracle,
+        } => (
+            "set_deposit_escrow_price_oracle",
+            set_deposit_escrow_price_oracle_ix(
+                SetDepositEscrowPriceOracleAccounts {
+                    authority: authority.unwrap_or(payer),
+                    deposit_escrow_mint,
+                    price_oracle,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::MigrateCampaignEscrow {
+            campaign_uuid,
+            authority,
+        } => (
+            "migrate_campaign_escrow",
+            migrate_campaign_escrow_ix(
+                MigrateCampaignEscrowAccounts {
+                    payer,
+                    authority: authority.unwrap_or(payer),
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::MigrateDepositRecords {
+            campaign_uuid,
+            authority,
+            collection_mint,
+            deposits,
+        } => (
+            "migrate_deposit_records",
+            migrate_deposit_records_ix(
+                MigrateDepositRecordsAccounts {
+                    authority: authority.unwrap_or(payer),
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                collection_mint,
+                &deposits,
+            ),
+        ),
+        Command::CloseEscrow {
+            campaign_uuid,
+            authority,
+            creator,
+            remainder_receiver,
+            receiver,
+        } => {
+            let campaign_uuid = convert_uuid_to_pda_seed(&campaign_uuid);
+            let remainder_receiver = match remainder_receiver {
+                Some(remainder_receiver) => remainder_receiver,
+                None => fetch_campaign_escrow(rpc_client, &campaign_uuid)
+                    .context("Failed to fetch the CampaignEscrow to find the remainder receiver")?
+                    .get_remainder_receiver(),
+            };
+
+            (
+                "close_escrow",
+                close_escrow_ix(
+                    CloseEscrowAccounts {
+                        payer,
+                        authority: authority.unwrap_or(payer),
+                        creator,
+                        remainder_receiver,
+                        receiver: receiver.unwrap_or(payer),
+                    },
+                    &campaign_uuid,
+                ),
+            )
+        }
+        Command::CreateDeposit {
+            campaign_uuid,
+            depositor,
+            mint,
+            deposit_escrow_mint,
+            treasury_mint,
+            deposit_amount,
+            price_oracle,
+            reward_tier_index,
+            has_matching_pool,
+        } => (
+            "create_deposit",
+            create_deposit_ix(
+                CreateDepositAccounts {
+                    depositor: depositor.unwrap_or(payer),
+                    mint,
+                    deposit_escrow_mint,
+                    treasury_mint,
+                    price_oracle,
+                    has_matching_pool,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                deposit_amount,
+                reward_tier_index,
+            ),
+        ),
+        Command::Donate {
+            campaign_uuid,
+            donation_uuid,
+            donor,
+            treasury_mint,
+            donation_amount,
+            refundable,
+            has_vote_weight,
+        } => (
+            "donate",
+            donate_ix(
+                DonateAccounts {
+                    donor: donor.unwrap_or(payer),
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                &convert_uuid_to_pda_seed(&donation_uuid),
+                donation_amount,
+                refundable,
+                has_vote_weight,
+            ),
+        ),
+        Command::FundMatchingPool {
+            campaign_uuid,
+            sponsor,
+            treasury_mint,
+            match_basis_points,
+            match_cap,
+        } => (
+            "fund_matching_pool",
+            fund_matching_pool_ix(
+                FundMatchingPoolAccounts {
+                    sponsor: sponsor.unwrap_or(payer),
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                match_basis_points,
+                match_cap,
+            ),
+        ),
+        Command::CloseMatchingPool {
+            campaign_uuid,
+            sponsor,
+            treasury_mint,
+        } => (
+            "close_matching_pool",
+            close_matching_pool_ix(
+                CloseMatchingPoolAccounts {
+                    sponsor: sponsor.unwrap_or(payer),
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::ProcessDeposit {
+            campaign_uuid,
+            authority,
+            depositor,
+            mint,
+            deposit_escrow_mint,
+            treasury_mint,
+            processed_deposit_amount,
+        } => (
+            "process_deposit",
+            process_deposit_ix(
+                ProcessDepositAccounts {
+                    authority: authority.unwrap_or(payer),
+                    depositor,
+                    mint,
+                    deposit_escrow_mint,
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                processed_deposit_amount,
+            ),
+        ),
+        Command::CloseDepositRecord { campaign_uuid } => (
+            "close_deposit_record",
+            close_deposit_record_ix(
+                CloseDepositRecordAccounts { payer },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::CloseDepositRecords {
+            campaign_uuid,
+            deposit_records,
+            treasury_mint,
+        } => (
+            "close_deposit_records",
+            close_deposit_records_ix(
+                CloseDepositRecordsAccounts {
+                    payer,
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                &deposit_records,
+            ),
+        ),
+        Command::PayOutFunds {
+            campaign_uuid,
+            payout_wallet,
+            crank_reward_receiver,
+            all_due,
+        } => {
+            let campaign_uuid = convert_uuid_to_pda_seed(&campaign_uuid);
+            let crank_reward_receiver = match crank_reward_receiver {
+                Some(crank_reward_receiver) => Some(crank_reward_receiver),
+                None => {
+                    let campaign_escrow = fetch_campaign_escrow(rpc_client, &campaign_uuid)
+                        .context(
+                            "Failed to fetch the CampaignEscrow to find the crank reward receiver",
+                        )?;
+                    match campaign_escrow.crank_reward {
+                        CrankReward::None => None,
+                        _ => Some(get_payment_account(
+                            &payer,
+                            &campaign_escrow.treasury.treasury_mint,
+                        )),
+                    }
+                }
+            };
+
+            let accounts = PayOutFundsAccounts {
+                payer,
+                payout_wallet,
+                crank_reward_receiver,
+            };
+
+            if all_due {
+                (
+                    "pay_out_all_due_funds",
+                    pay_out_all_due_funds_ix(accounts, &campaign_uuid),
+                )
+            } else {
+                ("pay_out_funds", pay_out_funds_ix(accounts, &campaign_uuid))
+            }
+        }
+        Command::ClaimStreamedPayout {
+            campaign_uuid,
+            payout_wallet,
+        } => (
+            "claim_streamed_payout",
+            claim_streamed_payout_ix(
+                ClaimStreamedPayoutAccounts {
+                    payer,
+                    payout_wallet,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::ProcessFullRefund {
+            campaign_uuid,
+            nft_holder,
+            depositor,
+            mint,
+            treasury_mint,
+        } => (
+            "process_full_refund",
+            process_full_refund_ix(
+                ProcessFullRefundAccounts {
+                    payer,
+                    nft_holder: nft_holder.unwrap_or(payer),
+                    depositor,
+                    mint,
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::ProcessPartialRefund {
+            campaign_uuid,
+            nft_holder,
+            depositor,
+            mint,
+            treasury_mint,
+        } => (
+            "process_partial_refund",
+            process_partial_refund_ix(
+                ProcessPartialRefundAccounts {
+                    payer,
+                    nft_holder: nft_holder.unwrap_or(payer),
+                    depositor,
+                    mint,
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+            ),
+        ),
+        Command::ProcessDonationRefund {
+            campaign_uuid,
+            donation_uuid,
+            donor,
+            treasury_mint,
+        } => (
+            "process_donation_refund",
+            process_donation_refund_ix(
+                ProcessDonationRefundAccounts {
+                    donor: donor.unwrap_or(payer),
+                    treasury_mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                &convert_uuid_to_pda_seed(&donation_uuid),
+            ),
+        ),
+        Command::VetoPayoutPhase {
+            campaign_uuid,
+            authority,
+            payout_phase_index,
+        } => (
+            "veto_payout_phase",
+            veto_payout_phase_ix(
+                VetoPayoutPhaseAccounts {
+                    payer,
+                    authority: authority.unwrap_or(payer),
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                payout_phase_index,
+            ),
+        ),
+        Command::VoteToVetoPayoutPhase {
+            campaign_uuid,
+            nft_holder,
+            depositor,
+            mint,
+            payout_phase_index,
+        } => (
+            "vote_to_veto_payout_phase",
+            vote_to_veto_payout_phase_ix(
+                VoteToVetoPayoutPhaseAccounts {
+                    nft_holder: nft_holder.unwrap_or(payer),
+                    depositor,
+                    mint,
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                payout_phase_index,
+            ),
+        ),
+        Command::VoteToVetoPayoutPhaseWithDonation {
+            campaign_uuid,
+            donation_uuid,
+            donor,
+            payout_phase_index,
+        } => (
+            "vote_to_veto_payout_phase_with_donation",
+            vote_to_veto_payout_phase_with_donation_ix(
+                VoteToVetoPayoutPhaseWithDonationAccounts {
+                    donor: donor.unwrap_or(payer),
+                },
+                &convert_uuid_to_pda_seed(&campaign_uuid),
+                &convert_uuid_to_pda_seed(&donation_uuid),
+                payout_phase_index,
+            ),
+        ),
+        Command::ReconcileTreasury {
+            campaign_uuid,
+            surplus_receiver,
+        } => {
+            let campaign_uuid = convert_uuid_to_pda_seed(&campaign_uuid);
+            let surplus_receiver = match surplus_receiver {
+                Some(surplus_receiver) => surplus_receiver,
+                // The receiver is unused if the surplus is held for supporters.
+                None => fetch_campaign_escrow(rpc_client, &campaign_uuid)
+                    .context("Failed to fetch the CampaignEscrow to find the surplus receiver")?
+                    .get_surplus_receiver()
+                    .unwrap_or(payer),
+            };
+
+            (
+                "reconcile_treasury",
+                reconcile_treasury_ix(
+                    ReconcileTreasuryAccounts {
+                        payer,
+                        surplus_receiver,
+                    },
+                    &campaign_uuid,
+                ),
+            )
+        }
+    };
+
+    Ok(result)
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+
+    let fee_payer = read_keypair(&cli.keypair)?;
+    let additional_signers = cli
+        .signers
+        .iter()
+        .map(|path| read_keypair(path))
+        .collect::<Result<Vec<Keypair>>>()?;
+    let signers: Vec<&Keypair> = std::iter::once(&fee_payer)
+        .chain(additional_signers.iter())
+        .collect();
+
+    let recent_blockhash = cli
+        .recent_blockhash
+        .as_deref()
+        .map(Hash::from_str)
+        .transpose()
+        .map_err(|e| anyhow!("Invalid recent_blockhash: {}", e))?;
+
+    let rpc_client = RpcClient::new_with_commitment(cli.rpc_url, CommitmentConfig::confirmed());
+
+    let (instruction_name, instruction) =
+        build_instruction(&rpc_client, cli.command, &fee_payer.pubkey())?;
+
+    emit_transaction(
+        cli.output,
+        &rpc_client,
+        instruction_name,
+        instruction,
+        &fee_payer,
+        &signers,
+        recent_blockhash,
+    )
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,176 @@
+// Builds, signs and emits transactions in one of the supported output modes.
+
+use anyhow::{bail, Result};
+use clap::ArgEnum;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Print the bincode serialized transaction as base64.
+    Base64,
+    /// Print a JSON summary of the transaction, including the base64 transaction.
+    Json,
+    /// Submit the transaction to the RPC URL and wait for confirmation.
+    Submit,
+}
+
+#[derive(Serialize)]
+struct AccountSummary {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+#[derive(Serialize)]
+struct TransactionSummary<'a> {
+    instruction: &'a str,
+    program_id: String,
+    fee_payer: String,
+    recent_blockhash: String,
+    accounts: Vec<AccountSummary>,
+    signers: Vec<String>,
+    missing_signers: Vec<String>,
+    transaction: String,
+}
+
+// Signs with whichever of the required signers are available. Transactions
+// which are missing signatures can still be emitted so that they can be
+// signed elsewhere, but cannot be submitted.
+fn build_transaction(
+    instruction: &Instruction,
+    fee_payer: &Keypair,
+    signers: &[&Keypair],
+    recent_blockhash: Hash,
+) -> (Transaction, Vec<String>) {
+    let mut transaction =
+        Transaction::new_with_payer(std::slice::from_ref(instruction), Some(&fee_payer.pubkey()));
+
+    let required_signers = &transaction.message.account_keys
+        [..transaction.message.header.num_required_signatures as usize];
+    let available_signers: Vec<&Keypair> = signers
+        .iter()
+        .copied()
+        .filter(|signer| required_signers.contains(&signer.pubkey()))
+        .collect();
+    let missing_signers: Vec<String> = required_signers
+        .iter()
+        .filter(|pubkey| {
+            !available_signers
+                .iter()
+                .any(|signer| signer.pubkey() == **pubkey)
+        })
+        .map(|pubkey| pubkey.to_string())
+        .collect();
+
+    transaction.partial_sign(&available_signers, recent_blockhash);
+
+    (transaction, missing_signers)
+}
+
+fn serialize_transaction(transaction: &Transaction) -> Result<String> {
+    Ok(base64::encode(bincode::serialize(transaction)?))
+}
+
+pub fn emit_transaction(
+    output_mode: OutputMode,
+    rpc_client: &RpcClient,
+    instruction_name: &str,
+    instruction: Instruction,
+    fee_payer: &Keypair,
+    signers: &[&Keypair],
+    recent_blockhash: Option<Hash>,
+) -> Result<()> {
+    let recent_blockhash = match recent_blockhash {
+        Some(recent_blockhash) => recent_blockhash,
+        None => rpc_client.get_latest_blockhash()?,
+    };
+
+    let (transaction, missing_signers) =
+        build_transaction(&instruction, fee_payer, signers, recent_blockhash);
+
+    match output_mode {
+        OutputMode::Base64 => {
+            println!("{}", serialize_transaction(&transaction)?);
+        }
+        OutputMode::Json => {
+            let summary = TransactionSummary {
+                instruction: instruction_name,
+                program_id: instruction.program_id.to_string(),
+                fee_payer: fee_payer.pubkey().to_string(),
+                recent_blockhash: recent_blockhash.to_string(),
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|account| AccountSummary {
+                        pubkey: account.pubkey.to_string(),
+                        is_signer: account.is_signer,
+                        is_writable: account.is_writable,
+                    })
+                    .collect(),
+                signers: signers
+                    .iter()
+                    .map(|signer| signer.pubkey().to_string())
+                    .filter(|pubkey| !missing_signers.contains(pubkey))
+                    .collect(),
+                missing_signers,
+                transaction: serialize_transaction(&transaction)?,
+            };
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        OutputMode::Submit => {
+            if !missing_signers.is_empty() {
+                bail!(
+                    "Cannot submit {} transaction, missing signatures for: {}",
+                    instruction_name,
+                    missing_signers.join(", ")
+                );
+            }
+
+            let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+            println!("{}", signature);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+
+    use super::*;
+
+    #[test]
+    fn test_build_transaction_reports_missing_signers() {
+        let fee_payer = Keypair::new();
+        let authority = Keypair::new();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![
+                AccountMeta::new(fee_payer.pubkey(), true),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        );
+
+        let (transaction, missing_signers) =
+            build_transaction(&instruction, &fee_payer, &[&fee_payer], Hash::default());
+        assert_eq!(missing_signers, vec![authority.pubkey().to_string()]);
+        assert!(!transaction.is_signed());
+
+        let (transaction, missing_signers) = build_transaction(
+            &instruction,
+            &fee_payer,
+            &[&fee_payer, &authority],
+            Hash::default(),
+        );
+        assert!(missing_signers.is_empty());
+        assert!(transaction.is_signed());
+        assert!(transaction.verify().is_ok());
+    }
+}
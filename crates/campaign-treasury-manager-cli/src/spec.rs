@@ -0,0 +1,506 @@
+// Campaign specs, read from TOML or JSON files. These map onto the
+// CreateCampaignEscrowInput and UpdateCampaignEscrowInput instruction inputs,
+// plus the accounts needed to build the instructions.
+
+use std::{fs, path::Path, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result};
+use campaign_treasury_manager::{
+    CrankReward, CreateCampaignEscrowInput, PayoutPhaseInput, PayoutPhases, PayoutStreamInput,
+    RewardTierInput, StretchGoal, SurplusPolicy, UpdateCampaignEscrowInput, VotingConfigInput,
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VotingConfigSpec {
+    pub voting_start_time: i64,
+    pub vote_basis_points_veto_threshold: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StretchGoalSpec {
+    pub raise_threshold: u64,
+    pub is_active_when_reached: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PayoutStreamSpec {
+    pub stream_end_time: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PayoutPhaseSpec {
+    pub payout_basis_points: u16,
+    pub payout_time: i64,
+    pub refund_deadline: i64,
+    #[serde(default)]
+    pub description: String,
+    pub stretch_goal: Option<StretchGoalSpec>,
+    pub stream: Option<PayoutStreamSpec>,
+    pub voting_config: Option<VotingConfigSpec>,
+}
+
+impl From<PayoutPhaseSpec> for PayoutPhaseInput {
+    fn from(spec: PayoutPhaseSpec) -> Self {
+        PayoutPhaseInput {
+            payout_basis_points: spec.payout_basis_points,
+            payout_time: spec.payout_time,
+            refund_deadline: spec.refund_deadline,
+            description: spec.description,
+            stretch_goal: spec.stretch_goal.map(|stretch_goal| StretchGoal {
+                raise_threshold: stretch_goal.raise_threshold,
+                is_active_when_reached: stretch_goal.is_active_when_reached,
+            }),
+            stream: spec.stream.map(|stream| PayoutStreamInput {
+                stream_end_time: stream.stream_end_time,
+            }),
+            voting_config: spec.voting_config.map(|voting_config| VotingConfigInput {
+                voting_start_time: voting_config.voting_start_time,
+                vote_basis_points_veto_threshold: voting_config.vote_basis_points_veto_threshold,
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RewardTierSpec {
+    pub min_deposit_amount: u64,
+    pub max_supply: u32,
+    pub metadata_uri: String,
+}
+
+impl From<RewardTierSpec> for RewardTierInput {
+    fn from(spec: RewardTierSpec) -> Self {
+        RewardTierInput {
+            min_deposit_amount: spec.min_deposit_amount,
+            max_supply: spec.max_supply,
+            metadata_uri: spec.metadata_uri,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SurplusPolicySpec {
+    Creator,
+    Supporters,
+    Authority,
+}
+
+impl From<SurplusPolicySpec> for SurplusPolicy {
+    fn from(spec: SurplusPolicySpec) -> Self {
+        match spec {
+            SurplusPolicySpec::Creator => SurplusPolicy::Creator,
+            SurplusPolicySpec::Supporters => SurplusPolicy::Supporters,
+            SurplusPolicySpec::Authority => SurplusPolicy::Authority,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CrankRewardSpec {
+    #[default]
+    None,
+    Fixed {
+        amount: u64,
+    },
+    BasisPoints {
+        basis_points: u16,
+    },
+}
+
+impl From<CrankRewardSpec> for CrankReward {
+    fn from(spec: CrankRewardSpec) -> Self {
+        match spec {
+            CrankRewardSpec::None => CrankReward::None,
+            CrankRewardSpec::Fixed { amount } => CrankReward::Fixed { amount },
+            CrankRewardSpec::BasisPoints { basis_points } => {
+                CrankReward::BasisPoints { basis_points }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CreateCampaignSpec {
+    // Either the hyphenated UUID or its PDA seed form.
+    pub campaign_uuid: String,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub authority: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub creator: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub payout_wallet_owner: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub treasury_mint: Pubkey,
+    pub campaign_end_time: i64,
+    pub payout_phases: Vec<PayoutPhaseSpec>,
+    pub surplus_policy: SurplusPolicySpec,
+    #[serde(default)]
+    pub crank_reward: CrankRewardSpec,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub collection_mint: Pubkey,
+    pub fundraising_start_time: Option<i64>,
+    #[serde(default)]
+    pub deposit_grace_period: i64,
+    #[serde(default)]
+    pub reward_tiers: Vec<RewardTierSpec>,
+    pub hard_cap: Option<u64>,
+    #[serde(default)]
+    pub auto_end_at_hard_cap: bool,
+}
+
+impl CreateCampaignSpec {
+    pub fn into_input(self) -> CreateCampaignEscrowInput {
+        CreateCampaignEscrowInput {
+            campaign_end_time: self.campaign_end_time,
+            payout_phases: self.payout_phases.into_iter().map(Into::into).collect(),
+            surplus_policy: self.surplus_policy.into(),
+            crank_reward: self.crank_reward.into(),
+            collection_mint: self.collection_mint,
+            fundraising_start_time: self.fundraising_start_time,
+            deposit_grace_period: self.deposit_grace_period,
+            reward_tiers: self.reward_tiers.into_iter().map(Into::into).collect(),
+            hard_cap: self.hard_cap,
+            auto_end_at_hard_cap: self.auto_end_at_hard_cap,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateCampaignSpec {
+    // Either the hyphenated UUID or its PDA seed form.
+    pub campaign_uuid: String,
+    // The current authority, which must sign.
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub authority: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub creator: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub payout_wallet_owner: Pubkey,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub treasury_mint: Pubkey,
+    #[serde(default, deserialize_with = "deserialize_optional_pubkey")]
+    pub new_authority: Option<Pubkey>,
+    #[serde(default, deserialize_with = "deserialize_optional_pubkey")]
+    pub new_creator: Option<Pubkey>,
+    pub campaign_end_time: Option<i64>,
+    pub payout_phases: Option<Vec<PayoutPhaseSpec>>,
+    pub surplus_policy: Option<SurplusPolicySpec>,
+    pub crank_reward: Option<CrankRewardSpec>,
+    #[serde(default, deserialize_with = "deserialize_optional_pubkey")]
+    pub collection_mint: Option<Pubkey>,
+    pub deposit_escrow_infos_capacity: Option<u8>,
+    pub fundraising_start_time: Option<i64>,
+    /// Clears the fundraising_start_time, which is only allowed before the
+    /// first deposit.
+    #[serde(default)]
+    pub clear_fundraising_start_time: bool,
+    pub deposit_grace_period: Option<i64>,
+    pub reward_tiers: Option<Vec<RewardTierSpec>>,
+    pub hard_cap: Option<u64>,
+    /// Clears the hard_cap, which is only allowed before the first deposit.
+    #[serde(default)]
+    pub clear_hard_cap: bool,
+    pub auto_end_at_hard_cap: Option<bool>,
+}
+
+impl UpdateCampaignSpec {
+    pub fn into_input(self) -> Result<UpdateCampaignEscrowInput> {
+        Ok(UpdateCampaignEscrowInput {
+            authority: self.new_authority,
+            creator: self.new_creator,
+            campaign_end_time: self.campaign_end_time,
+            payout_phases: self
+                .payout_phases
+                .map(|payout_phases| payout_phases.into_iter().map(Into::into).collect()),
+            surplus_policy: self.surplus_policy.map(Into::into),
+            crank_reward: self.crank_reward.map(Into::into),
+            collection_mint: self.collection_mint,
+            deposit_escrow_infos_capacity: self.deposit_escrow_infos_capacity,
+            fundraising_start_time: get_clearable_update(
+                "fundraising_start_time",
+                self.fundraising_start_time,
+                self.clear_fundraising_start_time,
+            )?,
+            deposit_grace_period: self.deposit_grace_period,
+            reward_tiers: self
+                .reward_tiers
+                .map(|reward_tiers| reward_tiers.into_iter().map(Into::into).collect()),
+            hard_cap: get_clearable_update("hard_cap", self.hard_cap, self.clear_hard_cap)?,
+            auto_end_at_hard_cap: self.auto_end_at_hard_cap,
+        })
+    }
+}
+
+// Optional fields which an update can also clear take a separate clear flag,
+// since TOML has no null value.
+fn get_clearable_update<T>(name: &str, value: Option<T>, clear: bool) -> Result<Option<Option<T>>> {
+    match (value, clear) {
+        (Some(_), true) => bail!("{} cannot be both set and cleared", name),
+        (None, true) => Ok(Some(None)),
+        (value, false) => Ok(value.map(Some)),
+    }
+}
+
+// Validates payout phases with the same checks the program runs, which
+// includes PayoutPhases::assert_is_valid.
+pub fn validate_payout_phases(
+    payout_phases: &[PayoutPhaseInput],
+    campaign_end_time: i64,
+) -> Result<()> {
+    PayoutPhases::new(payout_phases.to_vec(), campaign_end_time)
+        .map(|_| ())
+        .map_err(|e| anyhow!("Invalid payout phases: {}", e))
+}
+
+pub fn read_spec<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read spec file {}", path.display()))?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => parse_toml_spec(&contents),
+        Some("json") => parse_json_spec(&contents),
+        _ => bail!(
+            "Unsupported spec file {}, expected a .toml or .json file",
+            path.display()
+        ),
+    }
+    .with_context(|| format!("Failed to parse spec file {}", path.display()))
+}
+
+pub fn validate_crank_reward(crank_reward: &CrankReward) -> Result<()> {
+    crank_reward
+        .assert_is_valid()
+        .map_err(|e| anyhow!("Invalid crank reward: {}", e))
+}
+
+fn parse_toml_spec<T: DeserializeOwned>(contents: &str) -> Result<T> {
+    Ok(toml::from_str(contents)?)
+}
+
+fn parse_json_spec<T: DeserializeOwned>(contents: &str) -> Result<T> {
+    Ok(serde_json::from_str(contents)?)
+}
+
+fn deserialize_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Pubkey::from_str(&value).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_optional_pubkey<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Pubkey>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| Pubkey::from_str(&value).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_IN_SECONDS: i64 = 24 * 60 * 60;
+    const CAMPAIGN_END_TIME: i64 = 1_700_000_000;
+
+    fn get_create_campaign_spec_toml(second_payout_basis_points: u16) -> String {
+        format!(
+            r#"
+campaign_uuid = "2c6e5e8f-4a9f-4b4c-9d62-7d7a2e1c5b3a"
+authority = "{authority}"
+creator = "{creator}"
+payout_wallet_owner = "{creator}"
+treasury_mint = "So11111111111111111111111111111111111111112"
+campaign_end_time = {campaign_end_time}
+surplus_policy = "creator"
+crank_reward = {{ type = "basis_points", basis_points = 50 }}
+collection_mint = "{collection_mint}"
+deposit_grace_period = {deposit_grace_period}
+hard_cap = 100000000000
+auto_end_at_hard_cap = true
+
+[[payout_phases]]
+payout_basis_points = 5000
+payout_time = {first_payout_time}
+refund_deadline = {first_refund_deadline}
+description = "Initial payout"
+
+[[payout_phases]]
+payout_basis_points = {second_payout_basis_points}
+payout_time = {second_payout_time}
+refund_deadline = {second_refund_deadline}
+
+[payout_phases.voting_config]
+voting_start_time = {voting_start_time}
+vote_basis_points_veto_threshold = 8000
+
+[[reward_tiers]]
+min_deposit_amount = 1000000000
+max_supply = 50
+metadata_uri = "https://arweave.net/supporter-tier"
+"#,
+            authority = Pubkey::new_unique(),
+            creator = Pubkey::new_unique(),
+            collection_mint = Pubkey::new_unique(),
+            campaign_end_time = CAMPAIGN_END_TIME,
+            deposit_grace_period = DAY_IN_SECONDS,
+            first_payout_time = CAMPAIGN_END_TIME + 7 * DAY_IN_SECONDS,
+            first_refund_deadline = CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+            second_payout_basis_points = second_payout_basis_points,
+            second_payout_time = CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+            second_refund_deadline = CAMPAIGN_END_TIME + 67 * DAY_IN_SECONDS,
+            voting_start_time = CAMPAIGN_END_TIME + 23 * DAY_IN_SECONDS,
+        )
+    }
+
+    #[test]
+    fn test_create_campaign_spec() {
+        let spec: CreateCampaignSpec =
+            parse_toml_spec(&get_create_campaign_spec_toml(5000)).unwrap();
+        let input = spec.into_input();
+        assert_eq!(input.surplus_policy, SurplusPolicy::Creator);
+        assert_ne!(input.collection_mint, Pubkey::default());
+        assert_eq!(
+            input.crank_reward,
+            CrankReward::BasisPoints { basis_points: 50 }
+        );
+        assert!(validate_crank_reward(&input.crank_reward).is_ok());
+        assert_eq!(input.fundraising_start_time, None);
+        assert_eq!(input.deposit_grace_period, DAY_IN_SECONDS);
+        assert_eq!(input.hard_cap, Some(100_000_000_000));
+        assert!(input.auto_end_at_hard_cap);
+        assert_eq!(
+            input.reward_tiers,
+            vec![RewardTierInput {
+                min_deposit_amount: 1_000_000_000,
+                max_supply: 50,
+                metadata_uri: String::from("https://arweave.net/supporter-tier"),
+            }]
+        );
+        assert_eq!(input.payout_phases.len(), 2);
+        assert!(input.payout_phases[0].voting_config.is_none());
+        assert_eq!(
+            input.payout_phases[1]
+                .voting_config
+                .as_ref()
+                .unwrap()
+                .vote_basis_points_veto_threshold,
+            8000
+        );
+        assert!(validate_payout_phases(&input.payout_phases, input.campaign_end_time).is_ok());
+
+        let spec: CreateCampaignSpec =
+            parse_toml_spec(&get_create_campaign_spec_toml(4000)).unwrap();
+        let input = spec.into_input();
+        assert!(validate_payout_phases(&input.payout_phases, input.campaign_end_time).is_err());
+    }
+
+    #[test]
+    fn test_example_create_campaign_spec() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("specs/create-campaign.toml");
+        let spec: CreateCampaignSpec = read_spec(&path).unwrap();
+        let input = spec.into_input();
+        assert_eq!(input.payout_phases.len(), 4);
+        assert_eq!(
+            input.payout_phases[2].stretch_goal,
+            Some(StretchGoal {
+                raise_threshold: 500_000_000_000,
+                is_active_when_reached: true,
+            })
+        );
+        assert_eq!(
+            input.payout_phases[2].stream,
+            Some(PayoutStreamInput {
+                stream_end_time: 1_901_836_800,
+            })
+        );
+        assert!(validate_payout_phases(&input.payout_phases, input.campaign_end_time).is_ok());
+    }
+
+    #[test]
+    fn test_update_campaign_spec() {
+        let new_authority = Pubkey::new_unique();
+        let spec: UpdateCampaignSpec = parse_json_spec(&format!(
+            r#"{{
+                "campaign_uuid": "2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a",
+                "authority": "{}",
+                "creator": "{}",
+                "payout_wallet_owner": "{}",
+                "treasury_mint": "So11111111111111111111111111111111111111112",
+                "new_authority": "{}",
+                "campaign_end_time": {},
+                "surplus_policy": "supporters",
+                "crank_reward": {{ "type": "fixed", "amount": 5000 }},
+                "deposit_escrow_infos_capacity": 8,
+                "fundraising_start_time": {}
+            }}"#,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            new_authority,
+            CAMPAIGN_END_TIME,
+            CAMPAIGN_END_TIME - 30 * DAY_IN_SECONDS
+        ))
+        .unwrap();
+        let input = spec.into_input().unwrap();
+        assert_eq!(input.authority, Some(new_authority));
+        assert_eq!(input.creator, None);
+        assert_eq!(input.campaign_end_time, Some(CAMPAIGN_END_TIME));
+        assert!(input.payout_phases.is_none());
+        assert_eq!(input.surplus_policy, Some(SurplusPolicy::Supporters));
+        assert_eq!(
+            input.crank_reward,
+            Some(CrankReward::Fixed { amount: 5000 })
+        );
+        assert_eq!(input.collection_mint, None);
+        assert_eq!(input.deposit_escrow_infos_capacity, Some(8));
+        assert_eq!(
+            input.fundraising_start_time,
+            Some(Some(CAMPAIGN_END_TIME - 30 * DAY_IN_SECONDS))
+        );
+        assert_eq!(input.deposit_grace_period, None);
+        assert!(input.reward_tiers.is_none());
+        assert_eq!(input.hard_cap, None);
+    }
+
+    #[test]
+    fn test_update_campaign_spec_clears_fields() {
+        let get_spec = |fields: &str| -> UpdateCampaignSpec {
+            parse_json_spec(&format!(
+                r#"{{
+                    "campaign_uuid": "2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a",
+                    "authority": "{}",
+                    "creator": "{}",
+                    "payout_wallet_owner": "{}",
+                    "treasury_mint": "So11111111111111111111111111111111111111112",
+                    {}
+                }}"#,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                fields
+            ))
+            .unwrap()
+        };
+
+        let input = get_spec(r#""clear_fundraising_start_time": true, "clear_hard_cap": true"#)
+            .into_input()
+            .unwrap();
+        assert_eq!(input.fundraising_start_time, Some(None));
+        assert_eq!(input.hard_cap, Some(None));
+
+        let spec = get_spec(
+            r#""fundraising_start_time": 1700000000, "clear_fundraising_start_time": true"#,
+        );
+        assert!(spec.into_input().is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use campaign_treasury_manager::{CampaignEscrow, DepositRecord, DonationRecord, MatchingPool, ID};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+
+use crate::{
+    find_campaign_escrow_pda, find_deposit_record_pda, find_donation_record_pda,
+    find_matching_pool_pda, CampaignTreasuryManagerClientError, Result,
+};
+
+// Decodes an Anchor account owned by the program. This checks the account
+// owner and discriminator.
+pub fn decode_account<T: AccountDeserialize>(address: &Pubkey, account: &Account) -> Result<T> {
+    if account.owner != ID {
+        return Err(CampaignTreasuryManagerClientError::IncorrectOwner(*address));
+    }
+
+    T::try_deserialize(&mut account.data.as_slice())
+        .map_err(|e| CampaignTreasuryManagerClientError::AccountDecode(*address, e))
+}
+
+pub fn decode_campaign_escrow(address: &Pubkey, account: &Account) -> Result<CampaignEscrow> {
+    decode_account(address, account)
+}
+
+pub fn decode_deposit_record(address: &Pubkey, account: &Account) -> Result<DepositRecord> {
+    decode_account(address, account)
+}
+
+pub fn decode_donation_record(address: &Pubkey, account: &Account) -> Result<DonationRecord> {
+    decode_account(address, account)
+}
+
+pub fn decode_matching_pool(address: &Pubkey, account: &Account) -> Result<MatchingPool> {
+    decode_account(address, account)
+}
+
+pub fn fetch_account<T: AccountDeserialize>(rpc_client: &RpcClient, address: &Pubkey) -> Result<T> {
+    let account = rpc_client
+        .get_account_with_commitment(address, rpc_client.commitment())?
+        .value
+        .ok_or(CampaignTreasuryManagerClientError::AccountNotFound(
+            *address,
+        ))?;
+
+    decode_account(address, &account)
+}
+
+// Takes the campaign_uuid in its PDA seed form, see convert_uuid_to_pda_seed.
+pub fn fetch_campaign_escrow(
+    rpc_client: &RpcClient,
+    campaign_uuid: &str,
+) -> Result<CampaignEscrow> {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    fetch_account(rpc_client, &campaign_escrow)
+}
+
+pub fn fetch_deposit_record(
+    rpc_client: &RpcClient,
+    depositor: &Pubkey,
+    mint: &Pubkey,
+) -> Result<DepositRecord> {
+    let (deposit_record, _) = find_deposit_record_pda(depositor, mint);
+    fetch_account(rpc_client, &deposit_record)
+}
+
+// Takes the campaign_uuid and donation_uuid in their PDA seed form, see
+// convert_uuid_to_pda_seed.
+pub fn fetch_donation_record(
+    rpc_client: &RpcClient,
+    campaign_uuid: &str,
+    donation_uuid: &str,
+) -> Result<DonationRecord> {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (donation_record, _) = find_donation_record_pda(&campaign_escrow, donation_uuid);
+    fetch_account(rpc_client, &donation_record)
+}
+
+// Takes the campaign_uuid in its PDA seed form, see convert_uuid_to_pda_seed.
+pub fn fetch_matching_pool(rpc_client: &RpcClient, campaign_uuid: &str) -> Result<MatchingPool> {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (matching_pool, _) = find_matching_pool_pda(&campaign_escrow);
+    fetch_account(rpc_client, &matching_pool)
+}
+
+// Fetches all program accounts of the given type, using the account
+// discriminator as a filter. Accounts which fail to decode (e.g. legacy
+// CampaignEscrow accounts which have not been migrated yet) are skipped.
+pub fn fetch_all_accounts<T: AccountDeserialize + Discriminator>(
+    rpc_client: &RpcClient,
+) -> Result<Vec<(Pubkey, T)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &T::discriminator(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc_client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc_client.get_program_accounts_with_config(&ID, config)?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            decode_account(&address, &account)
+                .ok()
+                .map(|decoded| (address, decoded))
+        })
+        .collect())
+}
+
+pub fn fetch_all_campaign_escrows(rpc_client: &RpcClient) -> Result<Vec<(Pubkey, CampaignEscrow)>> {
+    fetch_all_accounts(rpc_client)
+}
+
+pub fn fetch_all_deposit_records(rpc_client: &RpcClient) -> Result<Vec<(Pubkey, DepositRecord)>> {
+    fetch_all_accounts(rpc_client)
+}
+
+pub fn fetch_all_donation_records(rpc_client: &RpcClient) -> Result<Vec<(Pubkey, DonationRecord)>> {
+    fetch_all_accounts(rpc_client)
+}
+
+pub fn fetch_all_matching_pools(rpc_client: &RpcClient) -> Result<Vec<(Pubkey, MatchingPool)>> {
+    fetch_all_accounts(rpc_client)
+}
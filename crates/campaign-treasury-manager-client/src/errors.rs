@@ -0,0 +1,17 @@
+use solana_client::client_error::ClientError;
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CampaignTreasuryManagerClientError {
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] ClientError),
+    #[error("Account {0} was not found")]
+    AccountNotFound(Pubkey),
+    #[error("Account {0} is not owned by the campaign treasury manager program")]
+    IncorrectOwner(Pubkey),
+    #[error("Failed to decode account {0}: {1}")]
+    AccountDecode(Pubkey, anchor_lang::error::Error),
+}
+
+pub type Result<T> = std::result::Result<T, CampaignTreasuryManagerClientError>;
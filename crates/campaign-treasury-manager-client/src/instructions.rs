@@ -0,0 +1,921 @@
+// Instruction builders for every campaign treasury manager entrypoint. Each
+// builder takes the accounts which cannot be derived and derives the rest
+// (PDAs, ATAs, programs and sysvars), like the TypeScript SDK instructions.
+//
+// All builders take the campaign_uuid in its PDA seed form, see
+// convert_uuid_to_pda_seed.
+
+use anchor_lang::{
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use campaign_treasury_manager::{
+    accounts, instruction, is_native_mint, CreateCampaignEscrowInput, UpdateCampaignEscrowInput, ID,
+};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    find_campaign_escrow_pda, find_deposit_escrow_pda, find_deposit_record_pda,
+    find_donation_record_pda, find_matching_pool_escrow_pda, find_matching_pool_pda,
+    find_metadata_pda, find_treasury_escrow_pda, find_vote_record_pda,
+};
+
+fn build_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// The payout wallet is the owner for native treasuries, and the owner's ATA
+// for token treasuries.
+pub fn get_payout_wallet(payout_wallet_owner: &Pubkey, treasury_mint: &Pubkey) -> Pubkey {
+    if is_native_mint(treasury_mint) {
+        *payout_wallet_owner
+    } else {
+        get_associated_token_address(payout_wallet_owner, treasury_mint)
+    }
+}
+
+// The account funds are paid from is the wallet itself for native payments,
+// and the wallet's ATA for token payments.
+pub fn get_payment_account(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    if is_native_mint(mint) {
+        *wallet
+    } else {
+        get_associated_token_address(wallet, mint)
+    }
+}
+
+pub struct CreateEscrowAccounts {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+    pub creator: Pubkey,
+    pub payout_wallet_owner: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+pub fn create_escrow_ix(
+    accounts: CreateEscrowAccounts,
+    campaign_uuid: &str,
+    campaign_escrow_input: CreateCampaignEscrowInput,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, treasury_bump) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::CreateEscrow {
+            campaign_escrow,
+            payer: accounts.payer,
+            authority: accounts.authority,
+            creator: accounts.creator,
+            treasury_mint: accounts.treasury_mint,
+            treasury_escrow,
+            payout_wallet: get_payout_wallet(
+                &accounts.payout_wallet_owner,
+                &accounts.treasury_mint,
+            ),
+            payout_wallet_owner: accounts.payout_wallet_owner,
+            token_program: spl_token::id(),
+            ata_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        },
+        instruction::CreateEscrow {
+            campaign_uuid: campaign_uuid.to_string(),
+            treasury_bump,
+            campaign_escrow_input,
+        },
+    )
+}
+
+pub struct CreateDepositEscrowAccounts {
+    pub authority: Pubkey,
+    pub deposit_escrow_mint: Pubkey,
+}
+
+pub fn create_deposit_escrow_ix(
+    accounts: CreateDepositEscrowAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (deposit_escrow, deposit_escrow_bump) =
+        find_deposit_escrow_pda(campaign_uuid, &accounts.deposit_escrow_mint);
+
+    build_instruction(
+        accounts::CreateDepositEscrow {
+            campaign_escrow,
+            authority: accounts.authority,
+            deposit_escrow,
+            deposit_escrow_mint: accounts.deposit_escrow_mint,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        },
+        instruction::CreateDepositEscrow {
+            deposit_escrow_bump,
+        },
+    )
+}
+
+pub struct CloseDepositEscrowAccounts {
+    pub authority: Pubkey,
+    pub deposit_escrow_mint: Pubkey,
+    pub receiver: Pubkey,
+}
+
+pub fn close_deposit_escrow_ix(
+    accounts: CloseDepositEscrowAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (deposit_escrow, _) = find_deposit_escrow_pda(campaign_uuid, &accounts.deposit_escrow_mint);
+
+    build_instruction(
+        accounts::CloseDepositEscrow {
+            campaign_escrow,
+            deposit_escrow,
+            deposit_escrow_mint: accounts.deposit_escrow_mint,
+            receiver: accounts.receiver,
+            authority: accounts.authority,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::CloseDepositEscrow {},
+    )
+}
+
+pub struct DisableDepositEscrowAccounts {
+    pub authority: Pubkey,
+    pub deposit_escrow_mint: Pubkey,
+}
+
+pub fn disable_deposit_escrow_ix(
+    accounts: DisableDepositEscrowAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::DisableDepositEscrow {
+            campaign_escrow,
+            deposit_escrow_mint: accounts.deposit_escrow_mint,
+            authority: accounts.authority,
+        },
+        instruction::DisableDepositEscrow {},
+    )
+}
+
+pub struct SetDepositEscrowPriceOracleAccounts {
+    pub authority: Pubkey,
+    pub deposit_escrow_mint: Pubkey,
+    pub price_oracle: Pubkey,
+}
+
+pub fn set_deposit_escrow_price_oracle_ix(
+    accounts: SetDepositEscrowPriceOracleAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::SetDepositEscrowPriceOracle {
+            campaign_escrow,
+            deposit_escrow_mint: accounts.deposit_escrow_mint,
+            price_oracle: accounts.price_oracle,
+            authority: accounts.authority,
+        },
+        instruction::SetDepositEscrowPriceOracle {},
+    )
+}
+
+pub struct UpdateEscrowAccounts {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+    pub creator: Pubkey,
+    pub payout_wallet_owner: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+pub fn update_escrow_ix(
+    accounts: UpdateEscrowAccounts,
+    campaign_uuid: &str,
+    campaign_escrow_input: UpdateCampaignEscrowInput,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, treasury_bump) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::UpdateEscrow {
+            campaign_escrow,
+            payer: accounts.payer,
+            authority: accounts.authority,
+            creator: accounts.creator,
+            treasury_mint: accounts.treasury_mint,
+            treasury_escrow,
+            payout_wallet: get_payout_wallet(
+                &accounts.payout_wallet_owner,
+                &accounts.treasury_mint,
+            ),
+            payout_wallet_owner: accounts.payout_wallet_owner,
+            token_program: spl_token::id(),
+            ata_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        },
+        instruction::UpdateEscrow {
+            campaign_uuid: campaign_uuid.to_string(),
+            treasury_bump,
+            campaign_escrow_input,
+        },
+    )
+}
+
+pub struct MigrateCampaignEscrowAccounts {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+}
+
+pub fn migrate_campaign_escrow_ix(
+    accounts: MigrateCampaignEscrowAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::MigrateCampaignEscrow {
+            campaign_escrow,
+            payer: accounts.payer,
+            authority: accounts.authority,
+            system_program: system_program::id(),
+        },
+        instruction::MigrateCampaignEscrow {
+            campaign_uuid: campaign_uuid.to_string(),
+        },
+    )
+}
+
+pub struct MigrateDepositRecordsAccounts {
+    pub authority: Pubkey,
+}
+
+// Each deposit is the depositor and campaign NFT mint of a legacy
+// DepositRecord. The NFT metadata is passed to verify the NFT is in the
+// campaign collection. The collection_mint is only passed for legacy
+// CampaignEscrows without one.
+pub fn migrate_deposit_records_ix(
+    accounts: MigrateDepositRecordsAccounts,
+    campaign_uuid: &str,
+    collection_mint: Option<Pubkey>,
+    deposits: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+
+    let mut instruction = build_instruction(
+        accounts::MigrateDepositRecords {
+            campaign_escrow,
+            authority: accounts.authority,
+        },
+        instruction::MigrateDepositRecords { collection_mint },
+    );
+    for (depositor, mint) in deposits {
+        let (deposit_record, _) = find_deposit_record_pda(depositor, mint);
+        let (metadata, _) = find_metadata_pda(mint);
+        instruction
+            .accounts
+            .push(AccountMeta::new(deposit_record, false));
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(metadata, false));
+    }
+
+    instruction
+}
+
+pub struct CloseEscrowAccounts {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+    pub creator: Pubkey,
+    // The account remaining treasury funds are routed to, see
+    // CampaignEscrow::get_remainder_receiver. Any account can be passed if the
+    // treasury holds no remaining funds.
+    pub remainder_receiver: Pubkey,
+    pub receiver: Pubkey,
+}
+
+pub fn close_escrow_ix(accounts: CloseEscrowAccounts, campaign_uuid: &str) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::CloseEscrow {
+            campaign_escrow,
+            treasury_escrow,
+            remainder_receiver: accounts.remainder_receiver,
+            receiver: accounts.receiver,
+            payer: accounts.payer,
+            authority: accounts.authority,
+            creator: accounts.creator,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::CloseEscrow {},
+    )
+}
+
+pub struct CreateDepositAccounts {
+    pub depositor: Pubkey,
+    // The campaign NFT mint.
+    pub mint: Pubkey,
+    // The sale currency mint.
+    pub deposit_escrow_mint: Pubkey,
+    // The CampaignEscrow treasury mint, used to determine which escrow
+    // account receives the deposit.
+    pub treasury_mint: Pubkey,
+    // The DepositEscrow price_oracle, required if one is set for the
+    // deposit_escrow_mint.
+    pub price_oracle: Option<Pubkey>,
+    // Must match the CampaignEscrow has_matching_pool, in which case the
+    // MatchingPool accounts are passed.
+    pub has_matching_pool: bool,
+}
+
+pub fn create_deposit_ix(
+    accounts: CreateDepositAccounts,
+    campaign_uuid: &str,
+    deposit_amount: u64,
+    reward_tier_index: Option<u8>,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (deposit_record, _) = find_deposit_record_pda(&accounts.depositor, &accounts.mint);
+
+    let deposit_escrow = if accounts.deposit_escrow_mint == accounts.treasury_mint {
+        find_treasury_escrow_pda(campaign_uuid).0
+    } else {
+        find_deposit_escrow_pda(campaign_uuid, &accounts.deposit_escrow_mint).0
+    };
+
+    let mut instruction = build_instruction(
+        accounts::CreateDeposit {
+            campaign_escrow,
+            deposit_record,
+            deposit_escrow,
+            deposit_escrow_mint: accounts.deposit_escrow_mint,
+            depositor: accounts.depositor,
+            depositor_payment_account: get_payment_account(
+                &accounts.depositor,
+                &accounts.deposit_escrow_mint,
+            ),
+            mint: accounts.mint,
+            metadata: find_metadata_pda(&accounts.mint).0,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            instruction_sysvar_account: sysvar::instructions::id(),
+        },
+        instruction::CreateDeposit {
+            deposit_amount,
+            reward_tier_index,
+        },
+    );
+    if accounts.has_matching_pool {
+        let (matching_pool, _) = find_matching_pool_pda(&campaign_escrow);
+        let (matching_pool_escrow, _) = find_matching_pool_escrow_pda(campaign_uuid);
+        let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+        instruction.accounts.extend([
+            AccountMeta::new(matching_pool, false),
+            AccountMeta::new(matching_pool_escrow, false),
+            AccountMeta::new(treasury_escrow, false),
+        ]);
+    }
+    if let Some(price_oracle) = accounts.price_oracle {
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(price_oracle, false));
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(accounts.treasury_mint, false));
+    }
+
+    instruction
+}
+
+pub struct DonateAccounts {
+    pub donor: Pubkey,
+    // The CampaignEscrow treasury mint, donations are made in the treasury
+    // currency.
+    pub treasury_mint: Pubkey,
+}
+
+pub fn donate_ix(
+    accounts: DonateAccounts,
+    campaign_uuid: &str,
+    donation_uuid: &str,
+    donation_amount: u64,
+    refundable: bool,
+    has_vote_weight: bool,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+    let (donation_record, _) = find_donation_record_pda(&campaign_escrow, donation_uuid);
+
+    build_instruction(
+        accounts::Donate {
+            campaign_escrow,
+            donation_record,
+            treasury_escrow,
+            treasury_mint: accounts.treasury_mint,
+            donor: accounts.donor,
+            donor_payment_account: get_payment_account(&accounts.donor, &accounts.treasury_mint),
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+        },
+        instruction::Donate {
+            donation_uuid: donation_uuid.to_string(),
+            donation_amount,
+            refundable,
+            has_vote_weight,
+        },
+    )
+}
+
+pub struct FundMatchingPoolAccounts {
+    pub sponsor: Pubkey,
+    // The CampaignEscrow treasury mint, the pool is funded in the treasury
+    // currency.
+    pub treasury_mint: Pubkey,
+}
+
+pub fn fund_matching_pool_ix(
+    accounts: FundMatchingPoolAccounts,
+    campaign_uuid: &str,
+    match_basis_points: u16,
+    match_cap: u64,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (matching_pool, _) = find_matching_pool_pda(&campaign_escrow);
+    let (matching_pool_escrow, _) = find_matching_pool_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::FundMatchingPool {
+            campaign_escrow,
+            matching_pool,
+            matching_pool_escrow,
+            treasury_mint: accounts.treasury_mint,
+            sponsor: accounts.sponsor,
+            sponsor_payment_account: get_payment_account(
+                &accounts.sponsor,
+                &accounts.treasury_mint,
+            ),
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        },
+        instruction::FundMatchingPool {
+            match_basis_points,
+            match_cap,
+        },
+    )
+}
+
+pub struct CloseMatchingPoolAccounts {
+    pub sponsor: Pubkey,
+    // The CampaignEscrow treasury mint, which determines the sponsor payment
+    // account.
+    pub treasury_mint: Pubkey,
+}
+
+pub fn close_matching_pool_ix(
+    accounts: CloseMatchingPoolAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (matching_pool, _) = find_matching_pool_pda(&campaign_escrow);
+    let (matching_pool_escrow, _) = find_matching_pool_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::CloseMatchingPool {
+            campaign_escrow,
+            matching_pool,
+            matching_pool_escrow,
+            sponsor: accounts.sponsor,
+            sponsor_payment_account: get_payment_account(
+                &accounts.sponsor,
+                &accounts.treasury_mint,
+            ),
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+        },
+        instruction::CloseMatchingPool {},
+    )
+}
+
+pub struct ProcessDepositAccounts {
+    pub authority: Pubkey,
+    // The depositor and campaign NFT mint of the DepositRecord.
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+    pub deposit_escrow_mint: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+pub fn process_deposit_ix(
+    accounts: ProcessDepositAccounts,
+    campaign_uuid: &str,
+    processed_deposit_amount: u64,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (deposit_record, _) = find_deposit_record_pda(&accounts.depositor, &accounts.mint);
+    let (deposit_escrow, _) = find_deposit_escrow_pda(campaign_uuid, &accounts.deposit_escrow_mint);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::ProcessDeposit {
+            campaign_escrow,
+            deposit_record,
+            deposit_escrow,
+            deposit_escrow_mint: accounts.deposit_escrow_mint,
+            treasury_escrow,
+            treasury_mint: accounts.treasury_mint,
+            authority: accounts.authority,
+            authority_deposit_escrow_account: get_payment_account(
+                &accounts.authority,
+                &accounts.deposit_escrow_mint,
+            ),
+            authority_payment_account: get_payment_account(
+                &accounts.authority,
+                &accounts.treasury_mint,
+            ),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::ProcessDeposit {
+            processed_deposit_amount,
+        },
+    )
+}
+
+pub struct CloseDepositRecordAccounts {
+    pub payer: Pubkey,
+}
+
+pub fn close_deposit_record_ix(
+    accounts: CloseDepositRecordAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::CloseDepositRecord {
+            campaign_escrow,
+            treasury_escrow,
+            payer: accounts.payer,
+        },
+        instruction::CloseDepositRecord {},
+    )
+}
+
+pub struct CloseDepositRecordsAccounts {
+    pub payer: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DepositRecordToClose {
+    pub depositor: Pubkey,
+    // The NFT mint the deposit was made for.
+    pub mint: Pubkey,
+    // Current holder of the NFT, who receives the supporter surplus.
+    pub nft_holder: Pubkey,
+}
+
+// The number of DepositRecords which fit in one transaction is limited by the
+// transaction size, about 7 without address lookup tables, or more if the
+// depositors still hold their NFTs.
+pub fn close_deposit_records_ix(
+    accounts: CloseDepositRecordsAccounts,
+    campaign_uuid: &str,
+    deposit_records: &[DepositRecordToClose],
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    let mut instruction = build_instruction(
+        accounts::CloseDepositRecords {
+            campaign_escrow,
+            treasury_escrow,
+            payer: accounts.payer,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::CloseDepositRecords {},
+    );
+    for DepositRecordToClose {
+        depositor,
+        mint,
+        nft_holder,
+    } in deposit_records
+    {
+        let (deposit_record, _) = find_deposit_record_pda(depositor, mint);
+        instruction
+            .accounts
+            .push(AccountMeta::new(deposit_record, false));
+        instruction
+            .accounts
+            .push(AccountMeta::new(*depositor, false));
+        instruction.accounts.push(AccountMeta::new_readonly(
+            get_associated_token_address(nft_holder, mint),
+            false,
+        ));
+        instruction.accounts.push(AccountMeta::new(
+            get_payment_account(nft_holder, &accounts.treasury_mint),
+            false,
+        ));
+    }
+
+    instruction
+}
+
+pub struct PayOutFundsAccounts {
+    pub payer: Pubkey,
+    // The CampaignEscrow payout_wallet.
+    pub payout_wallet: Pubkey,
+    // Receives the crank reward, see get_payment_account. Only required if the
+    // CampaignEscrow has a crank reward, and must exist for token treasuries.
+    pub crank_reward_receiver: Option<Pubkey>,
+}
+
+fn build_pay_out_funds_ix(
+    accounts: PayOutFundsAccounts,
+    campaign_uuid: &str,
+    data: impl InstructionData,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    let mut instruction = build_instruction(
+        accounts::PayOutFunds {
+            campaign_escrow,
+            treasury_escrow,
+            payer: accounts.payer,
+            payout_wallet: accounts.payout_wallet,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        data,
+    );
+    if let Some(crank_reward_receiver) = accounts.crank_reward_receiver {
+        instruction
+            .accounts
+            .push(AccountMeta::new(crank_reward_receiver, false));
+    }
+
+    instruction
+}
+
+pub fn pay_out_funds_ix(accounts: PayOutFundsAccounts, campaign_uuid: &str) -> Instruction {
+    build_pay_out_funds_ix(accounts, campaign_uuid, instruction::PayOutFunds {})
+}
+
+// Pays out every payout phase which is due, see pay_out_funds_ix.
+pub fn pay_out_all_due_funds_ix(accounts: PayOutFundsAccounts, campaign_uuid: &str) -> Instruction {
+    build_pay_out_funds_ix(accounts, campaign_uuid, instruction::PayOutAllDueFunds {})
+}
+
+pub struct ClaimStreamedPayoutAccounts {
+    pub payer: Pubkey,
+    // The CampaignEscrow payout_wallet, which receives the claim.
+    pub payout_wallet: Pubkey,
+}
+
+pub fn claim_streamed_payout_ix(
+    accounts: ClaimStreamedPayoutAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::ClaimStreamedPayout {
+            campaign_escrow,
+            treasury_escrow,
+            payer: accounts.payer,
+            payout_wallet: accounts.payout_wallet,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::ClaimStreamedPayout {},
+    )
+}
+
+pub struct ProcessFullRefundAccounts {
+    pub payer: Pubkey,
+    // The current holder of the NFT, who signs and receives the refund.
+    pub nft_holder: Pubkey,
+    // The depositor and NFT mint of the DepositRecord.
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+pub fn process_full_refund_ix(
+    accounts: ProcessFullRefundAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+    let (deposit_record, _) = find_deposit_record_pda(&accounts.depositor, &accounts.mint);
+
+    build_instruction(
+        accounts::ProcessFullRefund {
+            campaign_escrow,
+            treasury_escrow,
+            deposit_record,
+            depositor: accounts.depositor,
+            nft_holder: accounts.nft_holder,
+            nft_mint: accounts.mint,
+            nft_token_account: get_associated_token_address(&accounts.nft_holder, &accounts.mint),
+            refund_receiver: get_payment_account(&accounts.nft_holder, &accounts.treasury_mint),
+            payer: accounts.payer,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::ProcessFullRefund {},
+    )
+}
+
+pub struct ProcessPartialRefundAccounts {
+    pub payer: Pubkey,
+    // The current holder of the NFT, who signs and receives the refund.
+    pub nft_holder: Pubkey,
+    // The depositor and NFT mint of the DepositRecord.
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+pub fn process_partial_refund_ix(
+    accounts: ProcessPartialRefundAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+    let (deposit_record, _) = find_deposit_record_pda(&accounts.depositor, &accounts.mint);
+
+    build_instruction(
+        accounts::ProcessPartialRefund {
+            campaign_escrow,
+            treasury_escrow,
+            deposit_record,
+            depositor: accounts.depositor,
+            nft_holder: accounts.nft_holder,
+            nft_token_account: get_associated_token_address(&accounts.nft_holder, &accounts.mint),
+            refund_receiver: get_payment_account(&accounts.nft_holder, &accounts.treasury_mint),
+            payer: accounts.payer,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::ProcessPartialRefund {},
+    )
+}
+
+pub struct ProcessDonationRefundAccounts {
+    // The donor of the DonationRecord, who signs and receives the refund.
+    pub donor: Pubkey,
+    pub treasury_mint: Pubkey,
+}
+
+pub fn process_donation_refund_ix(
+    accounts: ProcessDonationRefundAccounts,
+    campaign_uuid: &str,
+    donation_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+    let (donation_record, _) = find_donation_record_pda(&campaign_escrow, donation_uuid);
+
+    build_instruction(
+        accounts::ProcessDonationRefund {
+            campaign_escrow,
+            treasury_escrow,
+            donation_record,
+            donor: accounts.donor,
+            refund_receiver: get_payment_account(&accounts.donor, &accounts.treasury_mint),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::ProcessDonationRefund {},
+    )
+}
+
+pub struct VetoPayoutPhaseAccounts {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+}
+
+pub fn veto_payout_phase_ix(
+    accounts: VetoPayoutPhaseAccounts,
+    campaign_uuid: &str,
+    payout_phase_index: u8,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::VetoPayoutPhase {
+            campaign_escrow,
+            payer: accounts.payer,
+            authority: accounts.authority,
+        },
+        instruction::VetoPayoutPhase { payout_phase_index },
+    )
+}
+
+pub struct VoteToVetoPayoutPhaseAccounts {
+    // The current holder of the NFT, who signs and pays for the VoteRecord.
+    pub nft_holder: Pubkey,
+    // The depositor and NFT mint of the DepositRecord.
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+}
+
+pub fn vote_to_veto_payout_phase_ix(
+    accounts: VoteToVetoPayoutPhaseAccounts,
+    campaign_uuid: &str,
+    payout_phase_index: u8,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (deposit_record, _) = find_deposit_record_pda(&accounts.depositor, &accounts.mint);
+    let (vote_record, _) = find_vote_record_pda(&deposit_record, payout_phase_index);
+
+    build_instruction(
+        accounts::VoteToVetoPayoutPhase {
+            campaign_escrow,
+            deposit_record,
+            vote_record,
+            nft_holder: accounts.nft_holder,
+            nft_token_account: get_associated_token_address(&accounts.nft_holder, &accounts.mint),
+            system_program: system_program::id(),
+        },
+        instruction::VoteToVetoPayoutPhase { payout_phase_index },
+    )
+}
+
+pub struct VoteToVetoPayoutPhaseWithDonationAccounts {
+    // The donor of the DonationRecord, who signs and pays for the VoteRecord.
+    pub donor: Pubkey,
+}
+
+pub fn vote_to_veto_payout_phase_with_donation_ix(
+    accounts: VoteToVetoPayoutPhaseWithDonationAccounts,
+    campaign_uuid: &str,
+    donation_uuid: &str,
+    payout_phase_index: u8,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (donation_record, _) = find_donation_record_pda(&campaign_escrow, donation_uuid);
+    let (vote_record, _) = find_vote_record_pda(&donation_record, payout_phase_index);
+
+    build_instruction(
+        accounts::VoteToVetoPayoutPhaseWithDonation {
+            campaign_escrow,
+            donation_record,
+            vote_record,
+            donor: accounts.donor,
+            system_program: system_program::id(),
+        },
+        instruction::VoteToVetoPayoutPhaseWithDonation { payout_phase_index },
+    )
+}
+
+pub struct ReconcileTreasuryAccounts {
+    pub payer: Pubkey,
+    // The account surplus funds are routed to, see
+    // CampaignEscrow::get_surplus_receiver. Any account can be passed if the
+    // surplus is held for supporters.
+    pub surplus_receiver: Pubkey,
+}
+
+pub fn reconcile_treasury_ix(
+    accounts: ReconcileTreasuryAccounts,
+    campaign_uuid: &str,
+) -> Instruction {
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, _) = find_treasury_escrow_pda(campaign_uuid);
+
+    build_instruction(
+        accounts::ReconcileTreasury {
+            campaign_escrow,
+            treasury_escrow,
+            surplus_receiver: accounts.surplus_receiver,
+            payer: accounts.payer,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        },
+        instruction::ReconcileTreasury {},
+    )
+}
@@ -0,0 +1,16 @@
+// Rust client for the campaign treasury manager program. This mirrors the
+// TypeScript SDK in src/sdk: PDA derivation, instruction builders and account
+// fetch/decode helpers.
+
+pub mod accounts;
+pub mod errors;
+pub mod instructions;
+pub mod pdas;
+
+pub use campaign_treasury_manager;
+pub use campaign_treasury_manager::ID as PROGRAM_ID;
+
+pub use accounts::*;
+pub use errors::*;
+pub use instructions::*;
+pub use pdas::*;
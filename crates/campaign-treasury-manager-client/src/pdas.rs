@@ -0,0 +1,148 @@
+use campaign_treasury_manager::{
+    constants::PROGRAM_PREFIX, CampaignEscrow, DepositEscrow, DepositRecord, DonationRecord,
+    MatchingPool, MatchingPoolEscrow, TreasuryEscrow, VoteRecord, ID,
+};
+use solana_program::pubkey::Pubkey;
+
+// Converts a campaign UUID to the form used on-chain for PDA seeds and the
+// campaign_uuid instruction arguments. A hyphenated UUID is 36 bytes, which is
+// longer than the 32 byte seed limit, so the hyphens are removed. This matches
+// convertUuidToPdaSeed in the TypeScript SDK.
+pub fn convert_uuid_to_pda_seed(campaign_uuid: &str) -> String {
+    campaign_uuid.replace('-', "")
+}
+
+// Note: all of the following take the campaign_uuid in its PDA seed form, see
+// convert_uuid_to_pda_seed.
+
+pub fn find_campaign_escrow_pda(campaign_uuid: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CampaignEscrow::PREFIX.as_bytes(), campaign_uuid.as_bytes()],
+        &ID,
+    )
+}
+
+pub fn find_treasury_escrow_pda(campaign_uuid: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PROGRAM_PREFIX.as_bytes(),
+            campaign_uuid.as_bytes(),
+            TreasuryEscrow::PREFIX.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_deposit_escrow_pda(campaign_uuid: &str, deposit_escrow_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DepositEscrow::PREFIX.as_bytes(),
+            campaign_uuid.as_bytes(),
+            deposit_escrow_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_deposit_record_pda(depositor: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DepositRecord::PREFIX.as_bytes(),
+            depositor.as_ref(),
+            mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+// The donation_uuid is also in its PDA seed form, see convert_uuid_to_pda_seed.
+pub fn find_donation_record_pda(campaign_escrow: &Pubkey, donation_uuid: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DonationRecord::PREFIX.as_bytes(),
+            campaign_escrow.as_ref(),
+            donation_uuid.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_matching_pool_pda(campaign_escrow: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MatchingPool::PREFIX.as_bytes(), campaign_escrow.as_ref()],
+        &ID,
+    )
+}
+
+pub fn find_matching_pool_escrow_pda(campaign_uuid: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MatchingPoolEscrow::PREFIX.as_bytes(),
+            campaign_uuid.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+// Also used for donation votes, with the DonationRecord as the deposit_record.
+pub fn find_vote_record_pda(deposit_record: &Pubkey, payout_phase_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VoteRecord::PREFIX.as_bytes(),
+            deposit_record.as_ref(),
+            &[payout_phase_index],
+        ],
+        &ID,
+    )
+}
+
+// The Metaplex metadata account of an NFT mint, which CreateDeposit checks for
+// membership in the campaign collection.
+pub fn find_metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::pda::find_metadata_account(mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAMPAIGN_UUID: &str = "2c6e5e8f-4a9f-4b4c-9d62-7d7a2e1c5b3a";
+
+    #[test]
+    fn test_convert_uuid_to_pda_seed() {
+        let seed = convert_uuid_to_pda_seed(CAMPAIGN_UUID);
+        assert_eq!(seed, "2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a");
+        assert_eq!(seed.len(), 32);
+    }
+
+    #[test]
+    fn test_pdas_match_program_seeds() {
+        let campaign_uuid = convert_uuid_to_pda_seed(CAMPAIGN_UUID);
+        let deposit_escrow_mint = Pubkey::new_unique();
+
+        let (treasury_escrow, treasury_bump) = find_treasury_escrow_pda(&campaign_uuid);
+        let treasury_seeds = TreasuryEscrow::get_seeds(&campaign_uuid, &treasury_bump);
+        assert_eq!(
+            Pubkey::create_program_address(&treasury_seeds, &ID).unwrap(),
+            treasury_escrow
+        );
+
+        let (deposit_escrow, deposit_escrow_bump) =
+            find_deposit_escrow_pda(&campaign_uuid, &deposit_escrow_mint);
+        let deposit_escrow_seeds =
+            DepositEscrow::get_seeds(&campaign_uuid, &deposit_escrow_mint, &deposit_escrow_bump);
+        assert_eq!(
+            Pubkey::create_program_address(&deposit_escrow_seeds, &ID).unwrap(),
+            deposit_escrow
+        );
+
+        let (matching_pool_escrow, matching_pool_escrow_bump) =
+            find_matching_pool_escrow_pda(&campaign_uuid);
+        let matching_pool_escrow_seeds =
+            MatchingPoolEscrow::get_seeds(&campaign_uuid, &matching_pool_escrow_bump);
+        assert_eq!(
+            Pubkey::create_program_address(&matching_pool_escrow_seeds, &ID).unwrap(),
+            matching_pool_escrow
+        );
+    }
+}
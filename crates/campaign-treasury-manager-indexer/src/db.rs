@@ -0,0 +1,803 @@
+// SQLite schema and writes. Accounts are upserted by address, so re-indexing
+// the same data is idempotent.
+
+use std::path::Path;
+
+use anyhow::Result;
+use campaign_treasury_manager::{
+    CampaignEscrow, CrankReward, DepositRecord, DonationRecord, MatchingPool, SurplusPolicy,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{DecodedEvent, DecodedTransaction, ProgramEvent};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS campaigns (
+    address TEXT PRIMARY KEY,
+    campaign_uuid TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    creator TEXT NOT NULL,
+    payout_wallet TEXT NOT NULL,
+    treasury_mint TEXT NOT NULL,
+    treasury_escrow TEXT NOT NULL,
+    total_funds INTEGER NOT NULL,
+    total_paid_out INTEGER NOT NULL,
+    total_refunded INTEGER NOT NULL,
+    total_surplus INTEGER NOT NULL,
+    supporter_surplus INTEGER NOT NULL,
+    total_crank_rewards INTEGER NOT NULL,
+    pending_estimated_funds INTEGER NOT NULL,
+    total_donations INTEGER NOT NULL,
+    non_voting_donations INTEGER NOT NULL,
+    non_refundable_donations INTEGER NOT NULL,
+    total_matched INTEGER NOT NULL,
+    campaign_end_time INTEGER NOT NULL,
+    fundraising_start_time INTEGER,
+    deposit_grace_period INTEGER NOT NULL,
+    hard_cap INTEGER,
+    auto_end_at_hard_cap INTEGER NOT NULL,
+    -- Set if fundraising auto ended when the hard_cap was reached.
+    hard_cap_reached_at INTEGER,
+    -- Set once the stretch goals are settled by the first payout.
+    settled_raise INTEGER,
+    has_matching_pool INTEGER NOT NULL,
+    deposit_count INTEGER NOT NULL,
+    processed_deposit_count INTEGER NOT NULL,
+    closed_deposit_record_count INTEGER NOT NULL,
+    payouts_ready INTEGER NOT NULL,
+    surplus_policy TEXT NOT NULL,
+    -- At most one of these is set, depending on the crank reward type.
+    crank_reward_amount INTEGER,
+    crank_reward_basis_points INTEGER,
+    collection_mint TEXT NOT NULL,
+    is_closed INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS payout_phases (
+    campaign TEXT NOT NULL REFERENCES campaigns(address) ON DELETE CASCADE,
+    payout_phase_index INTEGER NOT NULL,
+    payout_basis_points INTEGER NOT NULL,
+    payout_time INTEGER NOT NULL,
+    refund_deadline INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    is_paid_out INTEGER NOT NULL,
+    paid_amount INTEGER NOT NULL,
+    is_vetoed_by_authority INTEGER NOT NULL,
+    is_deactivated INTEGER NOT NULL,
+    -- Both are set if the payout phase has a stretch goal.
+    stretch_goal_raise_threshold INTEGER,
+    stretch_goal_is_active_when_reached INTEGER,
+    -- Set if the payout phase streams its payout. The veto time is set once
+    -- the authority vetoes the stream.
+    stream_end_time INTEGER,
+    stream_veto_time INTEGER,
+    voting_start_time INTEGER,
+    veto_votes INTEGER,
+    vote_basis_points_veto_threshold INTEGER,
+    PRIMARY KEY (campaign, payout_phase_index)
+);
+
+CREATE TABLE IF NOT EXISTS reward_tiers (
+    campaign TEXT NOT NULL REFERENCES campaigns(address) ON DELETE CASCADE,
+    reward_tier_index INTEGER NOT NULL,
+    min_deposit_amount INTEGER NOT NULL,
+    max_supply INTEGER NOT NULL,
+    sold_count INTEGER NOT NULL,
+    metadata_uri TEXT NOT NULL,
+    PRIMARY KEY (campaign, reward_tier_index)
+);
+
+CREATE TABLE IF NOT EXISTS deposit_escrows (
+    campaign TEXT NOT NULL REFERENCES campaigns(address) ON DELETE CASCADE,
+    mint TEXT NOT NULL,
+    closed INTEGER NOT NULL,
+    disabled INTEGER NOT NULL,
+    -- NULL if the currency has no price oracle.
+    price_oracle TEXT,
+    PRIMARY KEY (campaign, mint)
+);
+
+-- Older DepositRecord accounts do not store their campaign, so campaign is also
+-- filled in from CreateDeposit instructions. The account fields are null until
+-- the account itself has been indexed.
+CREATE TABLE IF NOT EXISTS deposit_records (
+    address TEXT PRIMARY KEY,
+    campaign TEXT,
+    mint TEXT,
+    depositor TEXT,
+    deposit_escrow_mint TEXT,
+    initial_deposit_amount INTEGER,
+    processed_deposit_amount INTEGER,
+    deposit_processed INTEGER,
+    estimated_deposit_amount INTEGER,
+    reward_tier_index INTEGER,
+    is_closed INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS deposit_records_campaign ON deposit_records(campaign);
+
+CREATE TABLE IF NOT EXISTS donation_records (
+    address TEXT PRIMARY KEY,
+    campaign TEXT NOT NULL,
+    donation_uuid TEXT NOT NULL,
+    donor TEXT NOT NULL,
+    donation_amount INTEGER NOT NULL,
+    refundable INTEGER NOT NULL,
+    has_vote_weight INTEGER NOT NULL,
+    donation_time INTEGER NOT NULL,
+    is_closed INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS donation_records_campaign ON donation_records(campaign);
+
+CREATE TABLE IF NOT EXISTS matching_pools (
+    address TEXT PRIMARY KEY,
+    campaign TEXT NOT NULL,
+    sponsor TEXT NOT NULL,
+    match_basis_points INTEGER NOT NULL,
+    match_cap INTEGER NOT NULL,
+    total_matched INTEGER NOT NULL,
+    is_closed INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS matching_pools_campaign ON matching_pools(campaign);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    error TEXT
+);
+
+CREATE TABLE IF NOT EXISTS instructions (
+    signature TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    instruction_index INTEGER NOT NULL,
+    -- -1 for top level instructions.
+    inner_instruction_index INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    campaign TEXT,
+    deposit_record TEXT,
+    accounts TEXT NOT NULL,
+    PRIMARY KEY (signature, instruction_index, inner_instruction_index)
+);
+
+CREATE INDEX IF NOT EXISTS instructions_campaign ON instructions(campaign);
+
+CREATE TABLE IF NOT EXISTS program_logs (
+    signature TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    log_index INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+
+-- Rows decoded from program events of successful transactions. log_index is
+-- the index of the event in program_logs.
+CREATE TABLE IF NOT EXISTS payouts (
+    signature TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    log_index INTEGER NOT NULL,
+    campaign TEXT NOT NULL,
+    payout_phase_index INTEGER NOT NULL,
+    -- Includes the crank_reward_amount, which is 0 for streamed payouts.
+    amount INTEGER NOT NULL,
+    crank_reward_amount INTEGER NOT NULL,
+    is_streamed INTEGER NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS payouts_campaign ON payouts(campaign);
+
+CREATE TABLE IF NOT EXISTS refunds (
+    signature TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    log_index INTEGER NOT NULL,
+    campaign TEXT NOT NULL,
+    -- The refunded DepositRecord or DonationRecord.
+    record TEXT NOT NULL,
+    refund_receiver TEXT NOT NULL,
+    refund_amount INTEGER NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS refunds_campaign ON refunds(campaign);
+
+CREATE TABLE IF NOT EXISTS deposits (
+    signature TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    log_index INTEGER NOT NULL,
+    campaign TEXT NOT NULL,
+    deposit_record TEXT NOT NULL,
+    depositor TEXT NOT NULL,
+    processed_deposit_amount INTEGER NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS deposits_campaign ON deposits(campaign);
+
+CREATE TABLE IF NOT EXISTS votes (
+    signature TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    log_index INTEGER NOT NULL,
+    campaign TEXT NOT NULL,
+    payout_phase_index INTEGER NOT NULL,
+    -- The DepositRecord or DonationRecord the votes are weighted by.
+    record TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    veto_votes INTEGER NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS votes_campaign ON votes(campaign);
+
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- Payout phases which have not been paid out yet and can still be, i.e. are
+-- not vetoed, not deactivated by their stretch goal and not blocked by an
+-- earlier vetoed phase. Until the stretch goals are settled, this includes
+-- the payout phases for every stretch goal outcome.
+CREATE VIEW IF NOT EXISTS pending_payouts AS
+SELECT payout_phases.*
+FROM payout_phases
+JOIN campaigns ON campaigns.address = payout_phases.campaign
+WHERE campaigns.is_closed = 0
+    AND payout_phases.is_paid_out = 0
+    AND payout_phases.is_deactivated = 0
+    AND NOT EXISTS (
+        SELECT 1 FROM payout_phases AS vetoed
+        WHERE vetoed.campaign = payout_phases.campaign
+            AND vetoed.payout_phase_index <= payout_phases.payout_phase_index
+            AND vetoed.is_vetoed_by_authority = 1
+            AND vetoed.is_deactivated = 0
+    );
+";
+
+const LAST_SIGNATURE_KEY: &str = "last_signature";
+
+fn get_surplus_policy_name(surplus_policy: &SurplusPolicy) -> &'static str {
+    match surplus_policy {
+        SurplusPolicy::Creator => "creator",
+        SurplusPolicy::Supporters => "supporters",
+        SurplusPolicy::Authority => "authority",
+    }
+}
+
+// The crank_reward_amount and crank_reward_basis_points columns.
+fn get_crank_reward_columns(crank_reward: &CrankReward) -> (Option<u64>, Option<u16>) {
+    match crank_reward {
+        CrankReward::None => (None, None),
+        CrankReward::Fixed { amount } => (Some(*amount), None),
+        CrankReward::BasisPoints { basis_points } => (None, Some(*basis_points)),
+    }
+}
+
+pub struct Database {
+    connection: Connection,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Result<Self> {
+        Database::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Database::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Database { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    pub fn upsert_campaign_escrow(
+        &mut self,
+        address: &Pubkey,
+        campaign_escrow: &CampaignEscrow,
+    ) -> Result<()> {
+        let address = address.to_string();
+        let (crank_reward_amount, crank_reward_basis_points) =
+            get_crank_reward_columns(&campaign_escrow.crank_reward);
+        let transaction = self.connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO campaigns (
+                address, campaign_uuid, authority, creator, payout_wallet, treasury_mint,
+                treasury_escrow, total_funds, total_paid_out, total_refunded, total_surplus,
+                supporter_surplus, total_crank_rewards, campaign_end_time, deposit_count,
+                processed_deposit_count, closed_deposit_record_count, payouts_ready,
+                surplus_policy, crank_reward_amount, crank_reward_basis_points,
+                collection_mint, pending_estimated_funds, total_donations,
+                non_voting_donations, fundraising_start_time, deposit_grace_period, hard_cap,
+                auto_end_at_hard_cap, settled_raise, total_matched, has_matching_pool,
+                hard_cap_reached_at, non_refundable_donations, is_closed
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32,
+                ?33, ?34, 0
+            )
+            ON CONFLICT (address) DO UPDATE SET
+                campaign_uuid = excluded.campaign_uuid,
+                authority = excluded.authority,
+                creator = excluded.creator,
+                payout_wallet = excluded.payout_wallet,
+                treasury_mint = excluded.treasury_mint,
+                treasury_escrow = excluded.treasury_escrow,
+                total_funds = excluded.total_funds,
+                total_paid_out = excluded.total_paid_out,
+                total_refunded = excluded.total_refunded,
+                total_surplus = excluded.total_surplus,
+                supporter_surplus = excluded.supporter_surplus,
+                total_crank_rewards = excluded.total_crank_rewards,
+                campaign_end_time = excluded.campaign_end_time,
+                deposit_count = excluded.deposit_count,
+                processed_deposit_count = excluded.processed_deposit_count,
+                closed_deposit_record_count = excluded.closed_deposit_record_count,
+                payouts_ready = excluded.payouts_ready,
+                surplus_policy = excluded.surplus_policy,
+                crank_reward_amount = excluded.crank_reward_amount,
+                crank_reward_basis_points = excluded.crank_reward_basis_points,
+                collection_mint = excluded.collection_mint,
+                pending_estimated_funds = excluded.pending_estimated_funds,
+                total_donations = excluded.total_donations,
+                non_voting_donations = excluded.non_voting_donations,
+                fundraising_start_time = excluded.fundraising_start_time,
+                deposit_grace_period = excluded.deposit_grace_period,
+                hard_cap = excluded.hard_cap,
+                auto_end_at_hard_cap = excluded.auto_end_at_hard_cap,
+                settled_raise = excluded.settled_raise,
+                total_matched = excluded.total_matched,
+                has_matching_pool = excluded.has_matching_pool,
+                hard_cap_reached_at = excluded.hard_cap_reached_at,
+                non_refundable_donations = excluded.non_refundable_donations,
+                is_closed = 0",
+            params![
+                address,
+                campaign_escrow.campaign_uuid,
+                campaign_escrow.authority.to_string(),
+                campaign_escrow.creator.to_string(),
+                campaign_escrow.payout_wallet.to_string(),
+                campaign_escrow.treasury.treasury_mint.to_string(),
+                campaign_escrow.treasury.treasury_escrow.to_string(),
+                campaign_escrow.treasury.total_funds,
+                campaign_escrow.treasury.total_paid_out,
+                campaign_escrow.treasury.total_refunded,
+                campaign_escrow.treasury.total_surplus,
+                campaign_escrow.treasury.supporter_surplus,
+                campaign_escrow.treasury.total_crank_rewards,
+                campaign_escrow.campaign_end_time,
+                campaign_escrow.deposit_count,
+                campaign_escrow.processed_deposit_count,
+                campaign_escrow.closed_deposit_record_count,
+                campaign_escrow.payouts_ready,
+                get_surplus_policy_name(&campaign_escrow.surplus_policy),
+                crank_reward_amount,
+                crank_reward_basis_points,
+                campaign_escrow.collection_mint.to_string(),
+                campaign_escrow.treasury.pending_estimated_funds,
+                campaign_escrow.treasury.total_donations,
+                campaign_escrow.treasury.non_voting_donations,
+                campaign_escrow.fundraising_start_time,
+                campaign_escrow.deposit_grace_period,
+                campaign_escrow.hard_cap,
+                campaign_escrow.auto_end_at_hard_cap,
+                campaign_escrow.settled_raise,
+                campaign_escrow.treasury.total_matched,
+                campaign_escrow.has_matching_pool,
+                campaign_escrow.hard_cap_reached_at,
+                campaign_escrow.treasury.non_refundable_donations,
+            ],
+        )?;
+
+        // Payout phases can be replaced by UpdateEscrow, so they are rewritten.
+        transaction.execute(
+            "DELETE FROM payout_phases WHERE campaign = ?1",
+            params![address],
+        )?;
+        for (payout_phase_index, payout_phase) in campaign_escrow.payout_phases.iter().enumerate() {
+            let stretch_goal = payout_phase.stretch_goal.as_ref();
+            let stream = payout_phase.stream.as_ref();
+            let voting_config = payout_phase.voting_config.as_ref();
+            transaction.execute(
+                "INSERT INTO payout_phases (
+                    campaign, payout_phase_index, payout_basis_points, payout_time,
+                    refund_deadline, description, is_paid_out, paid_amount,
+                    is_vetoed_by_authority, is_deactivated, stretch_goal_raise_threshold,
+                    stretch_goal_is_active_when_reached, stream_end_time, stream_veto_time,
+                    voting_start_time, veto_votes, vote_basis_points_veto_threshold
+                ) VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17
+                )",
+                params![
+                    address,
+                    payout_phase_index,
+                    payout_phase.payout_basis_points,
+                    payout_phase.payout_time,
+                    payout_phase.refund_deadline,
+                    payout_phase.description,
+                    payout_phase.is_paid_out,
+                    payout_phase.paid_amount,
+                    payout_phase.is_vetoed_by_authority,
+                    payout_phase.is_deactivated,
+                    stretch_goal.map(|stretch_goal| stretch_goal.raise_threshold),
+                    stretch_goal.map(|stretch_goal| stretch_goal.is_active_when_reached),
+                    stream.map(|stream| stream.stream_end_time),
+                    stream.and_then(|stream| stream.veto_time),
+                    voting_config.map(|voting_config| voting_config.voting_start_time),
+                    voting_config.map(|voting_config| voting_config.veto_votes),
+                    voting_config
+                        .map(|voting_config| voting_config.vote_basis_points_veto_threshold),
+                ],
+            )?;
+        }
+
+        // Reward tiers can be replaced by UpdateEscrow before any deposits.
+        transaction.execute(
+            "DELETE FROM reward_tiers WHERE campaign = ?1",
+            params![address],
+        )?;
+        for (reward_tier_index, reward_tier) in campaign_escrow.reward_tiers.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO reward_tiers (
+                    campaign, reward_tier_index, min_deposit_amount, max_supply, sold_count,
+                    metadata_uri
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    address,
+                    reward_tier_index,
+                    reward_tier.min_deposit_amount,
+                    reward_tier.max_supply,
+                    reward_tier.sold_count,
+                    reward_tier.metadata_uri,
+                ],
+            )?;
+        }
+
+        transaction.execute(
+            "DELETE FROM deposit_escrows WHERE campaign = ?1",
+            params![address],
+        )?;
+        for deposit_escrow_info in campaign_escrow.deposit_escrow_infos.iter() {
+            transaction.execute(
+                "INSERT INTO deposit_escrows (campaign, mint, closed, disabled, price_oracle)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    address,
+                    deposit_escrow_info.mint.to_string(),
+                    deposit_escrow_info.closed,
+                    deposit_escrow_info.disabled,
+                    Some(deposit_escrow_info.price_oracle)
+                        .filter(|price_oracle| *price_oracle != Pubkey::default())
+                        .map(|price_oracle| price_oracle.to_string()),
+                ],
+            )?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn upsert_deposit_record(
+        &mut self,
+        address: &Pubkey,
+        deposit_record: &DepositRecord,
+    ) -> Result<()> {
+        // DepositRecords created before the campaign_escrow was recorded are
+        // linked to their campaign from CreateDeposit instructions instead.
+        let campaign = Some(deposit_record.campaign_escrow)
+            .filter(|campaign_escrow| *campaign_escrow != Pubkey::default())
+            .map(|campaign_escrow| campaign_escrow.to_string());
+
+        self.connection.execute(
+            "INSERT INTO deposit_records (
+                address, campaign, mint, depositor, deposit_escrow_mint, initial_deposit_amount,
+                processed_deposit_amount, deposit_processed, estimated_deposit_amount,
+                reward_tier_index, is_closed
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0)
+            ON CONFLICT (address) DO UPDATE SET
+                campaign = COALESCE(excluded.campaign, deposit_records.campaign),
+                mint = excluded.mint,
+                depositor = excluded.depositor,
+                deposit_escrow_mint = excluded.deposit_escrow_mint,
+                initial_deposit_amount = excluded.initial_deposit_amount,
+                processed_deposit_amount = excluded.processed_deposit_amount,
+                deposit_processed = excluded.deposit_processed,
+                estimated_deposit_amount = excluded.estimated_deposit_amount,
+                reward_tier_index = excluded.reward_tier_index,
+                is_closed = 0",
+            params![
+                address.to_string(),
+                campaign,
+                deposit_record.mint.to_string(),
+                deposit_record.depositor.to_string(),
+                deposit_record.deposit_escrow_mint.to_string(),
+                deposit_record.initial_deposit_amount,
+                deposit_record.processed_deposit_amount,
+                deposit_record.deposit_processed,
+                deposit_record.estimated_deposit_amount,
+                deposit_record.reward_tier_index,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn upsert_donation_record(
+        &mut self,
+        address: &Pubkey,
+        donation_record: &DonationRecord,
+    ) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO donation_records (
+                address, campaign, donation_uuid, donor, donation_amount, refundable,
+                has_vote_weight, donation_time, is_closed
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 0)
+            ON CONFLICT (address) DO UPDATE SET
+                campaign = excluded.campaign,
+                donation_uuid = excluded.donation_uuid,
+                donor = excluded.donor,
+                donation_amount = excluded.donation_amount,
+                refundable = excluded.refundable,
+                has_vote_weight = excluded.has_vote_weight,
+                donation_time = excluded.donation_time,
+                is_closed = 0",
+            params![
+                address.to_string(),
+                donation_record.campaign_escrow.to_string(),
+                donation_record.donation_uuid,
+                donation_record.donor.to_string(),
+                donation_record.donation_amount,
+                donation_record.refundable,
+                donation_record.has_vote_weight,
+                donation_record.donation_time,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn upsert_matching_pool(
+        &mut self,
+        address: &Pubkey,
+        matching_pool: &MatchingPool,
+    ) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO matching_pools (
+                address, campaign, sponsor, match_basis_points, match_cap, total_matched,
+                is_closed
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)
+            ON CONFLICT (address) DO UPDATE SET
+                campaign = excluded.campaign,
+                sponsor = excluded.sponsor,
+                match_basis_points = excluded.match_basis_points,
+                match_cap = excluded.match_cap,
+                total_matched = excluded.total_matched,
+                is_closed = 0",
+            params![
+                address.to_string(),
+                matching_pool.campaign_escrow.to_string(),
+                matching_pool.sponsor.to_string(),
+                matching_pool.match_basis_points,
+                matching_pool.match_cap,
+                matching_pool.total_matched,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    // Marks indexed accounts which no longer exist on-chain as closed.
+    pub fn mark_closed_accounts(
+        &mut self,
+        campaign_escrows: &[Pubkey],
+        deposit_records: &[Pubkey],
+        donation_records: &[Pubkey],
+        matching_pools: &[Pubkey],
+    ) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        for (table, addresses) in [
+            ("campaigns", campaign_escrows),
+            ("deposit_records", deposit_records),
+            ("donation_records", donation_records),
+            ("matching_pools", matching_pools),
+        ] {
+            transaction.execute(&format!("UPDATE {} SET is_closed = 1", table), [])?;
+            for address in addresses {
+                transaction.execute(
+                    &format!("UPDATE {} SET is_closed = 0 WHERE address = ?1", table),
+                    params![address.to_string()],
+                )?;
+            }
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    // Returns false if the transaction has already been indexed.
+    pub fn insert_transaction(&mut self, decoded_transaction: &DecodedTransaction) -> Result<bool> {
+        let transaction = self.connection.transaction()?;
+
+        let inserted_count = transaction.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time, error)
+            VALUES (?1, ?2, ?3, ?4)",
+            params![
+                decoded_transaction.signature,
+                decoded_transaction.slot,
+                decoded_transaction.block_time,
+                decoded_transaction.error,
+            ],
+        )?;
+        if inserted_count == 0 {
+            return Ok(false);
+        }
+
+        for instruction in decoded_transaction.instructions.iter() {
+            let accounts: Vec<String> = instruction
+                .accounts
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            transaction.execute(
+                "INSERT INTO instructions (
+                    signature, instruction_index, inner_instruction_index, name, campaign,
+                    deposit_record, accounts
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    decoded_transaction.signature,
+                    instruction.instruction_index,
+                    instruction
+                        .inner_instruction_index
+                        .map_or(-1, |index| index as i64),
+                    instruction.name,
+                    instruction.get_campaign_escrow().map(ToString::to_string),
+                    instruction
+                        .get_created_deposit_record()
+                        .map(ToString::to_string),
+                    accounts.join(","),
+                ],
+            )?;
+
+            // Failed transactions did not create anything.
+            if decoded_transaction.error.is_some() {
+                continue;
+            }
+            if let (Some(campaign_escrow), Some(deposit_record)) = (
+                instruction.get_campaign_escrow(),
+                instruction.get_created_deposit_record(),
+            ) {
+                transaction.execute(
+                    "INSERT INTO deposit_records (address, campaign) VALUES (?1, ?2)
+                    ON CONFLICT (address) DO UPDATE SET campaign = excluded.campaign",
+                    params![deposit_record.to_string(), campaign_escrow.to_string()],
+                )?;
+            }
+        }
+
+        for (log_index, log) in decoded_transaction.logs.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO program_logs (signature, log_index, kind, message)
+                VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    decoded_transaction.signature,
+                    log_index,
+                    log.kind.get_name(),
+                    log.message,
+                ],
+            )?;
+        }
+
+        for DecodedEvent { log_index, event } in decoded_transaction.events.iter() {
+            let signature = &decoded_transaction.signature;
+            match event {
+                ProgramEvent::Payout {
+                    campaign_escrow,
+                    payout_phase_index,
+                    amount,
+                    crank_reward_amount,
+                    is_streamed,
+                } => transaction.execute(
+                    "INSERT INTO payouts (
+                        signature, log_index, campaign, payout_phase_index, amount,
+                        crank_reward_amount, is_streamed
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        signature,
+                        log_index,
+                        campaign_escrow.to_string(),
+                        payout_phase_index,
+                        amount,
+                        crank_reward_amount,
+                        is_streamed,
+                    ],
+                )?,
+                ProgramEvent::Refund {
+                    campaign_escrow,
+                    record,
+                    refund_receiver,
+                    refund_amount,
+                } => transaction.execute(
+                    "INSERT INTO refunds (
+                        signature, log_index, campaign, record, refund_receiver, refund_amount
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        signature,
+                        log_index,
+                        campaign_escrow.to_string(),
+                        record.to_string(),
+                        refund_receiver.to_string(),
+                        refund_amount,
+                    ],
+                )?,
+                ProgramEvent::Deposit {
+                    campaign_escrow,
+                    deposit_record,
+                    depositor,
+                    processed_deposit_amount,
+                } => transaction.execute(
+                    "INSERT INTO deposits (
+                        signature, log_index, campaign, deposit_record, depositor,
+                        processed_deposit_amount
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        signature,
+                        log_index,
+                        campaign_escrow.to_string(),
+                        deposit_record.to_string(),
+                        depositor.to_string(),
+                        processed_deposit_amount,
+                    ],
+                )?,
+                ProgramEvent::Vote {
+                    campaign_escrow,
+                    payout_phase_index,
+                    record,
+                    voter,
+                    veto_votes,
+                } => transaction.execute(
+                    "INSERT INTO votes (
+                        signature, log_index, campaign, payout_phase_index, record, voter,
+                        veto_votes
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        signature,
+                        log_index,
+                        campaign_escrow.to_string(),
+                        payout_phase_index,
+                        record.to_string(),
+                        voter.to_string(),
+                        veto_votes,
+                    ],
+                )?,
+            };
+        }
+
+        transaction.commit()?;
+        Ok(true)
+    }
+
+    // Newest transaction signature ingested from RPC.
+    pub fn get_last_signature(&self) -> Result<Option<String>> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                params![LAST_SIGNATURE_KEY],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_last_signature(&self, signature: &str) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+            ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![LAST_SIGNATURE_KEY, signature],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,553 @@
+// Decodes program accounts and transactions into the records which are
+// written to the database.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, Context, Result};
+use campaign_treasury_manager::{
+    instruction, CampaignEscrow, DepositProcessed, DepositRecord, DonationRecord, MatchingPool,
+    PayoutPhasePaidOut, RefundProcessed, StreamedPayoutClaimed, VetoVotesCast, ID,
+};
+use campaign_treasury_manager_client::{
+    decode_campaign_escrow, decode_deposit_record, decode_donation_record, decode_matching_pool,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, UiInstruction, UiMessage,
+};
+use std::str::FromStr;
+
+pub enum ProgramAccount {
+    CampaignEscrow(Box<CampaignEscrow>),
+    DepositRecord(DepositRecord),
+    DonationRecord(DonationRecord),
+    MatchingPool(MatchingPool),
+}
+
+// Returns None for accounts which are not owned by the program or are not a
+// CampaignEscrow, DepositRecord, DonationRecord or MatchingPool, e.g. legacy
+// CampaignEscrow accounts which have not been migrated yet.
+pub fn decode_program_account(address: &Pubkey, account: &Account) -> Option<ProgramAccount> {
+    let discriminator = account.data.get(..8)?;
+
+    if discriminator == CampaignEscrow::discriminator() {
+        decode_campaign_escrow(address, account)
+            .ok()
+            .map(|campaign_escrow| ProgramAccount::CampaignEscrow(Box::new(campaign_escrow)))
+    } else if discriminator == DepositRecord::discriminator() {
+        decode_deposit_record(address, account)
+            .ok()
+            .map(ProgramAccount::DepositRecord)
+    } else if discriminator == DonationRecord::discriminator() {
+        decode_donation_record(address, account)
+            .ok()
+            .map(ProgramAccount::DonationRecord)
+    } else if discriminator == MatchingPool::discriminator() {
+        decode_matching_pool(address, account)
+            .ok()
+            .map(ProgramAccount::MatchingPool)
+    } else {
+        None
+    }
+}
+
+fn get_instruction_name(data: &[u8]) -> Option<&'static str> {
+    let discriminator = data.get(..8)?;
+
+    let instruction_names: [([u8; 8], &str); 26] = [
+        (instruction::CreateEscrow::DISCRIMINATOR, "create_escrow"),
+        (
+            instruction::CreateDepositEscrow::DISCRIMINATOR,
+            "create_deposit_escrow",
+        ),
+        (
+            instruction::CloseDepositEscrow::DISCRIMINATOR,
+            "close_deposit_escrow",
+        ),
+        (
+            instruction::DisableDepositEscrow::DISCRIMINATOR,
+            "disable_deposit_escrow",
+        ),
+        (
+            instruction::SetDepositEscrowPriceOracle::DISCRIMINATOR,
+            "set_deposit_escrow_price_oracle",
+        ),
+        (instruction::UpdateEscrow::DISCRIMINATOR, "update_escrow"),
+        (
+            instruction::MigrateCampaignEscrow::DISCRIMINATOR,
+            "migrate_campaign_escrow",
+        ),
+        (
+            instruction::MigrateDepositRecords::DISCRIMINATOR,
+            "migrate_deposit_records",
+        ),
+        (instruction::CloseEscrow::DISCRIMINATOR, "close_escrow"),
+        (instruction::CreateDeposit::DISCRIMINATOR, "create_deposit"),
+        (instruction::Donate::DISCRIMINATOR, "donate"),
+        (
+            instruction::FundMatchingPool::DISCRIMINATOR,
+            "fund_matching_pool",
+        ),
+        (
+            instruction::CloseMatchingPool::DISCRIMINATOR,
+            "close_matching_pool",
+        ),
+        (
+            instruction::ProcessDeposit::DISCRIMINATOR,
+            "process_deposit",
+        ),
+        (
+            instruction::CloseDepositRecord::DISCRIMINATOR,
+            "close_deposit_record",
+        ),
+        (
+            instruction::CloseDepositRecords::DISCRIMINATOR,
+            "close_deposit_records",
+        ),
+        (instruction::PayOutFunds::DISCRIMINATOR, "pay_out_funds"),
+        (
+            instruction::PayOutAllDueFunds::DISCRIMINATOR,
+            "pay_out_all_due_funds",
+        ),
+        (
+            instruction::ClaimStreamedPayout::DISCRIMINATOR,
+            "claim_streamed_payout",
+        ),
+        (
+            instruction::ProcessFullRefund::DISCRIMINATOR,
+            "process_full_refund",
+        ),
+        (
+            instruction::ProcessPartialRefund::DISCRIMINATOR,
+            "process_partial_refund",
+        ),
+        (
+            instruction::ProcessDonationRefund::DISCRIMINATOR,
+            "process_donation_refund",
+        ),
+        (
+            instruction::VetoPayoutPhase::DISCRIMINATOR,
+            "veto_payout_phase",
+        ),
+        (
+            instruction::VoteToVetoPayoutPhase::DISCRIMINATOR,
+            "vote_to_veto_payout_phase",
+        ),
+        (
+            instruction::VoteToVetoPayoutPhaseWithDonation::DISCRIMINATOR,
+            "vote_to_veto_payout_phase_with_donation",
+        ),
+        (
+            instruction::ReconcileTreasury::DISCRIMINATOR,
+            "reconcile_treasury",
+        ),
+    ];
+
+    instruction_names
+        .iter()
+        .find(|(instruction_discriminator, _)| instruction_discriminator == discriminator)
+        .map(|(_, name)| *name)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedInstruction {
+    // Index of the top level instruction.
+    pub instruction_index: usize,
+    // Set if this instruction was invoked by CPI from the top level instruction.
+    pub inner_instruction_index: Option<usize>,
+    pub name: &'static str,
+    pub accounts: Vec<Pubkey>,
+}
+
+impl DecodedInstruction {
+    // The campaign_escrow is the first account of every instruction.
+    pub fn get_campaign_escrow(&self) -> Option<&Pubkey> {
+        self.accounts.first()
+    }
+
+    // Only CreateDeposit takes the DepositRecord, which is how deposit records
+    // are linked to their campaign.
+    pub fn get_created_deposit_record(&self) -> Option<&Pubkey> {
+        if self.name == "create_deposit" {
+            self.accounts.get(1)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramLogKind {
+    // Program log: lines, i.e. msg! output.
+    Log,
+    // Program data: lines, i.e. base64 encoded events.
+    Data,
+}
+
+impl ProgramLogKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ProgramLogKind::Log => "log",
+            ProgramLogKind::Data => "data",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramLog {
+    pub kind: ProgramLogKind,
+    pub message: String,
+}
+
+// Program events, decoded from Program data: lines. Each kind of event is
+// written to its own table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgramEvent {
+    // A payout phase payout or a streamed payout claim. The amount includes
+    // the crank_reward_amount, which is 0 for streamed payout claims.
+    Payout {
+        campaign_escrow: Pubkey,
+        payout_phase_index: u8,
+        amount: u64,
+        crank_reward_amount: u64,
+        is_streamed: bool,
+    },
+    // The record is the refunded DepositRecord or DonationRecord.
+    Refund {
+        campaign_escrow: Pubkey,
+        record: Pubkey,
+        refund_receiver: Pubkey,
+        refund_amount: u64,
+    },
+    Deposit {
+        campaign_escrow: Pubkey,
+        deposit_record: Pubkey,
+        depositor: Pubkey,
+        processed_deposit_amount: u64,
+    },
+    // The record is the DepositRecord or DonationRecord the votes are
+    // weighted by.
+    Vote {
+        campaign_escrow: Pubkey,
+        payout_phase_index: u8,
+        record: Pubkey,
+        voter: Pubkey,
+        veto_votes: u64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedEvent {
+    // Index of the Program data: line in the transaction's program logs.
+    pub log_index: usize,
+    pub event: ProgramEvent,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub error: Option<String>,
+    pub instructions: Vec<DecodedInstruction>,
+    pub logs: Vec<ProgramLog>,
+    // Events of failed transactions are not decoded, since their effects were
+    // rolled back.
+    pub events: Vec<DecodedEvent>,
+}
+
+// Decodes an Anchor event, i.e. the event discriminator followed by the
+// serialized event. Returns None for unknown events.
+pub fn decode_program_event(data: &[u8]) -> Option<ProgramEvent> {
+    let discriminator = data.get(..8)?;
+    let mut event_data = data.get(8..)?;
+
+    if discriminator == PayoutPhasePaidOut::discriminator() {
+        let event = PayoutPhasePaidOut::deserialize(&mut event_data).ok()?;
+        Some(ProgramEvent::Payout {
+            campaign_escrow: event.campaign_escrow,
+            payout_phase_index: event.payout_phase_index,
+            amount: event.paid_amount,
+            crank_reward_amount: event.crank_reward_amount,
+            is_streamed: false,
+        })
+    } else if discriminator == StreamedPayoutClaimed::discriminator() {
+        let event = StreamedPayoutClaimed::deserialize(&mut event_data).ok()?;
+        Some(ProgramEvent::Payout {
+            campaign_escrow: event.campaign_escrow,
+            payout_phase_index: event.payout_phase_index,
+            amount: event.claimed_amount,
+            crank_reward_amount: 0,
+            is_streamed: true,
+        })
+    } else if discriminator == RefundProcessed::discriminator() {
+        let event = RefundProcessed::deserialize(&mut event_data).ok()?;
+        Some(ProgramEvent::Refund {
+            campaign_escrow: event.campaign_escrow,
+            record: event.record,
+            refund_receiver: event.refund_receiver,
+            refund_amount: event.refund_amount,
+        })
+    } else if discriminator == DepositProcessed::discriminator() {
+        let event = DepositProcessed::deserialize(&mut event_data).ok()?;
+        Some(ProgramEvent::Deposit {
+            campaign_escrow: event.campaign_escrow,
+            deposit_record: event.deposit_record,
+            depositor: event.depositor,
+            processed_deposit_amount: event.processed_deposit_amount,
+        })
+    } else if discriminator == VetoVotesCast::discriminator() {
+        let event = VetoVotesCast::deserialize(&mut event_data).ok()?;
+        Some(ProgramEvent::Vote {
+            campaign_escrow: event.campaign_escrow,
+            payout_phase_index: event.payout_phase_index,
+            record: event.record,
+            voter: event.voter,
+            veto_votes: event.veto_votes,
+        })
+    } else {
+        None
+    }
+}
+
+fn decode_program_events(logs: &[ProgramLog]) -> Vec<DecodedEvent> {
+    logs.iter()
+        .enumerate()
+        .filter(|(_, log)| log.kind == ProgramLogKind::Data)
+        .filter_map(|(log_index, log)| {
+            let data = base64::decode(&log.message).ok()?;
+            decode_program_event(&data).map(|event| DecodedEvent { log_index, event })
+        })
+        .collect()
+}
+
+struct CompiledInstruction {
+    program_id_index: u8,
+    accounts: Vec<u8>,
+    data: Vec<u8>,
+}
+
+fn decode_instruction(
+    account_keys: &[Pubkey],
+    compiled_instruction: &CompiledInstruction,
+    instruction_index: usize,
+    inner_instruction_index: Option<usize>,
+) -> Result<Option<DecodedInstruction>> {
+    let get_account_key = |index: u8| {
+        account_keys
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("Account index {} is out of range", index))
+    };
+
+    if get_account_key(compiled_instruction.program_id_index)? != ID {
+        return Ok(None);
+    }
+
+    let name = match get_instruction_name(&compiled_instruction.data) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    Ok(Some(DecodedInstruction {
+        instruction_index,
+        inner_instruction_index,
+        name,
+        accounts: compiled_instruction
+            .accounts
+            .iter()
+            .map(|index| get_account_key(*index))
+            .collect::<Result<_>>()?,
+    }))
+}
+
+// Collects the log lines emitted by the program itself, skipping the logs of
+// any programs it invokes.
+fn decode_program_logs(log_messages: &[String]) -> Vec<ProgramLog> {
+    let program_id = ID.to_string();
+    let mut invocation_stack: Vec<&str> = vec![];
+    let mut logs = vec![];
+
+    for log_message in log_messages {
+        let mut words = log_message.split(' ');
+        if let (Some("Program"), Some(invoked_program_id), Some("invoke")) =
+            (words.next(), words.next(), words.next())
+        {
+            invocation_stack.push(invoked_program_id);
+            continue;
+        }
+
+        let is_invocation_end = log_message.starts_with("Program ")
+            && (log_message.ends_with(" success") || log_message.contains(" failed: "));
+        if is_invocation_end
+            && matches!(
+                invocation_stack.last(),
+                Some(invoked) if log_message.starts_with(&format!("Program {} ", invoked))
+            )
+        {
+            invocation_stack.pop();
+            continue;
+        }
+
+        if invocation_stack.last() != Some(&program_id.as_str()) {
+            continue;
+        }
+
+        if let Some(message) = log_message.strip_prefix("Program log: ") {
+            logs.push(ProgramLog {
+                kind: ProgramLogKind::Log,
+                message: message.to_string(),
+            });
+        } else if let Some(message) = log_message.strip_prefix("Program data: ") {
+            logs.push(ProgramLog {
+                kind: ProgramLogKind::Data,
+                message: message.to_string(),
+            });
+        }
+    }
+
+    logs
+}
+
+// Decodes a transaction in the getTransaction RPC response format, with either
+// the json or a binary encoding.
+pub fn decode_transaction(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<DecodedTransaction> {
+    let (signature, mut account_keys, instructions) = match &transaction.transaction.transaction {
+        EncodedTransaction::Json(ui_transaction) => {
+            let message = match &ui_transaction.message {
+                UiMessage::Raw(message) => message,
+                UiMessage::Parsed(_) => {
+                    return Err(anyhow!("jsonParsed transactions are not supported"))
+                }
+            };
+            let account_keys = message
+                .account_keys
+                .iter()
+                .map(|account_key| Pubkey::from_str(account_key))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let instructions = message
+                .instructions
+                .iter()
+                .map(|instruction| {
+                    Ok(CompiledInstruction {
+                        program_id_index: instruction.program_id_index,
+                        accounts: instruction.accounts.clone(),
+                        data: bs58::decode(&instruction.data).into_vec()?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let signature = ui_transaction
+                .signatures
+                .first()
+                .cloned()
+                .ok_or_else(|| anyhow!("Transaction has no signatures"))?;
+
+            (signature, account_keys, instructions)
+        }
+        encoded_transaction => {
+            let versioned_transaction = encoded_transaction
+                .decode()
+                .ok_or_else(|| anyhow!("Failed to decode transaction"))?;
+            let instructions = versioned_transaction
+                .message
+                .instructions()
+                .iter()
+                .map(|instruction| CompiledInstruction {
+                    program_id_index: instruction.program_id_index,
+                    accounts: instruction.accounts.clone(),
+                    data: instruction.data.clone(),
+                })
+                .collect();
+            let signature = versioned_transaction
+                .signatures
+                .first()
+                .ok_or_else(|| anyhow!("Transaction has no signatures"))?
+                .to_string();
+
+            (
+                signature,
+                versioned_transaction.message.static_account_keys().to_vec(),
+                instructions,
+            )
+        }
+    };
+
+    let meta = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .with_context(|| format!("Transaction {} has no status meta", signature))?;
+
+    // Accounts loaded from address lookup tables come after the static keys.
+    if let OptionSerializer::Some(loaded_addresses) = &meta.loaded_addresses {
+        for address in loaded_addresses
+            .writable
+            .iter()
+            .chain(loaded_addresses.readonly.iter())
+        {
+            account_keys.push(Pubkey::from_str(address)?);
+        }
+    }
+
+    let mut decoded_instructions = vec![];
+    for (instruction_index, instruction) in instructions.iter().enumerate() {
+        decoded_instructions.extend(decode_instruction(
+            &account_keys,
+            instruction,
+            instruction_index,
+            None,
+        )?);
+    }
+
+    if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
+        for inner_instructions in inner_instructions {
+            for (inner_instruction_index, inner_instruction) in
+                inner_instructions.instructions.iter().enumerate()
+            {
+                let inner_instruction = match inner_instruction {
+                    UiInstruction::Compiled(inner_instruction) => inner_instruction,
+                    UiInstruction::Parsed(_) => continue,
+                };
+                let compiled_instruction = CompiledInstruction {
+                    program_id_index: inner_instruction.program_id_index,
+                    accounts: inner_instruction.accounts.clone(),
+                    data: bs58::decode(&inner_instruction.data).into_vec()?,
+                };
+                decoded_instructions.extend(decode_instruction(
+                    &account_keys,
+                    &compiled_instruction,
+                    inner_instructions.index as usize,
+                    Some(inner_instruction_index),
+                )?);
+            }
+        }
+    }
+    decoded_instructions.sort_by_key(|instruction| {
+        (
+            instruction.instruction_index,
+            instruction.inner_instruction_index,
+        )
+    });
+
+    let logs = match &meta.log_messages {
+        OptionSerializer::Some(log_messages) => decode_program_logs(log_messages),
+        _ => vec![],
+    };
+    let events = if meta.err.is_none() {
+        decode_program_events(&logs)
+    } else {
+        vec![]
+    };
+
+    Ok(DecodedTransaction {
+        signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        error: meta.err.as_ref().map(ToString::to_string),
+        instructions: decoded_instructions,
+        logs,
+        events,
+    })
+}
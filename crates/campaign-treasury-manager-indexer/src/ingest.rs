@@ -0,0 +1,1054 @@
+// Ingestion from a directory of dumps (backfill) or from an RPC endpoint
+// (incremental sync).
+
+use std::{fs, path::Path, str::FromStr};
+
+use anyhow::{anyhow, Context, Result};
+use campaign_treasury_manager::ID;
+use campaign_treasury_manager_client::{
+    fetch_all_campaign_escrows, fetch_all_deposit_records, fetch_all_donation_records,
+    fetch_all_matching_pools,
+};
+use serde::Deserialize;
+use solana_account_decoder::UiAccount;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+
+use crate::{decode_program_account, decode_transaction, Database, ProgramAccount};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct IngestSummary {
+    pub campaign_escrows: usize,
+    pub deposit_records: usize,
+    pub donation_records: usize,
+    pub matching_pools: usize,
+    // Accounts which are not a CampaignEscrow, DepositRecord, DonationRecord
+    // or MatchingPool of the program.
+    pub skipped_accounts: usize,
+    pub transactions: usize,
+}
+
+// The format written by `solana account <ADDRESS> --output json`.
+#[derive(Deserialize)]
+struct AccountDump {
+    pubkey: String,
+    account: UiAccount,
+}
+
+enum Dump {
+    Account(Pubkey, Account),
+    Transaction(Box<EncodedConfirmedTransactionWithStatusMeta>),
+}
+
+// Dumps are either accounts or getTransaction RPC responses, which are told
+// apart by their fields.
+fn read_dump(path: &Path) -> Result<Dump> {
+    let contents = fs::read_to_string(path)?;
+    let value: serde_json::Value = serde_json::from_str(&contents)?;
+
+    if value.get("account").is_some() {
+        let account_dump: AccountDump = serde_json::from_value(value)?;
+        let account = account_dump
+            .account
+            .decode()
+            .ok_or_else(|| anyhow!("Failed to decode account data"))?;
+        Ok(Dump::Account(
+            Pubkey::from_str(&account_dump.pubkey)?,
+            account,
+        ))
+    } else if value.get("transaction").is_some() {
+        Ok(Dump::Transaction(Box::new(serde_json::from_value(value)?)))
+    } else {
+        Err(anyhow!("Expected an account or transaction dump"))
+    }
+}
+
+fn index_account(
+    database: &mut Database,
+    summary: &mut IngestSummary,
+    address: &Pubkey,
+    account: &Account,
+) -> Result<()> {
+    match decode_program_account(address, account) {
+        Some(ProgramAccount::CampaignEscrow(campaign_escrow)) => {
+            database.upsert_campaign_escrow(address, &campaign_escrow)?;
+            summary.campaign_escrows += 1;
+        }
+        Some(ProgramAccount::DepositRecord(deposit_record)) => {
+            database.upsert_deposit_record(address, &deposit_record)?;
+            summary.deposit_records += 1;
+        }
+        Some(ProgramAccount::DonationRecord(donation_record)) => {
+            database.upsert_donation_record(address, &donation_record)?;
+            summary.donation_records += 1;
+        }
+        Some(ProgramAccount::MatchingPool(matching_pool)) => {
+            database.upsert_matching_pool(address, &matching_pool)?;
+            summary.matching_pools += 1;
+        }
+        None => summary.skipped_accounts += 1,
+    }
+
+    Ok(())
+}
+
+fn index_transaction(
+    database: &mut Database,
+    summary: &mut IngestSummary,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<()> {
+    let decoded_transaction = decode_transaction(transaction)?;
+    if database.insert_transaction(&decoded_transaction)? {
+        summary.transactions += 1;
+    }
+
+    Ok(())
+}
+
+// Indexes every .json file in the directory, recursively. Transactions are
+// indexed in slot order so that later deposits into a reused DepositRecord
+// address win.
+pub fn backfill_from_directory(database: &mut Database, directory: &Path) -> Result<IngestSummary> {
+    let mut paths = vec![];
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension() == Some("json".as_ref()) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    let mut summary = IngestSummary::default();
+    let mut transactions = vec![];
+    for path in paths {
+        match read_dump(&path).with_context(|| format!("Failed to read {}", path.display()))? {
+            Dump::Account(address, account) => {
+                index_account(database, &mut summary, &address, &account)?
+            }
+            Dump::Transaction(transaction) => transactions.push(transaction),
+        }
+    }
+
+    transactions.sort_by_key(|transaction| transaction.slot);
+    for transaction in transactions {
+        index_transaction(database, &mut summary, &transaction)?;
+    }
+
+    Ok(summary)
+}
+
+// Ingests program transactions since the last synced signature, then
+// refreshes all program accounts.
+pub fn sync_from_rpc(database: &mut Database, rpc_client: &RpcClient) -> Result<IngestSummary> {
+    let until = database
+        .get_last_signature()?
+        .map(|signature| Signature::from_str(&signature))
+        .transpose()?;
+
+    // Signatures are returned newest first, one page at a time.
+    let mut signatures = vec![];
+    let mut before = None;
+    loop {
+        let page = rpc_client.get_signatures_for_address_with_config(
+            &ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: None,
+                commitment: Some(rpc_client.commitment()),
+            },
+        )?;
+        let last_signature = match page.last() {
+            Some(status) => Signature::from_str(&status.signature)?,
+            None => break,
+        };
+        signatures.extend(page.into_iter().map(|status| status.signature));
+        before = Some(last_signature);
+    }
+
+    let mut summary = IngestSummary::default();
+    for signature in signatures.iter().rev() {
+        let transaction = rpc_client.get_transaction_with_config(
+            &Signature::from_str(signature)?,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(rpc_client.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        index_transaction(database, &mut summary, &transaction)?;
+        database.set_last_signature(signature)?;
+    }
+
+    let campaign_escrows = fetch_all_campaign_escrows(rpc_client)?;
+    for (address, campaign_escrow) in campaign_escrows.iter() {
+        database.upsert_campaign_escrow(address, campaign_escrow)?;
+        summary.campaign_escrows += 1;
+    }
+    let deposit_records = fetch_all_deposit_records(rpc_client)?;
+    for (address, deposit_record) in deposit_records.iter() {
+        database.upsert_deposit_record(address, deposit_record)?;
+        summary.deposit_records += 1;
+    }
+    let donation_records = fetch_all_donation_records(rpc_client)?;
+    for (address, donation_record) in donation_records.iter() {
+        database.upsert_donation_record(address, donation_record)?;
+        summary.donation_records += 1;
+    }
+    let matching_pools = fetch_all_matching_pools(rpc_client)?;
+    for (address, matching_pool) in matching_pools.iter() {
+        database.upsert_matching_pool(address, matching_pool)?;
+        summary.matching_pools += 1;
+    }
+    database.mark_closed_accounts(
+        &campaign_escrows
+            .iter()
+            .map(|(address, _)| *address)
+            .collect::<Vec<_>>(),
+        &deposit_records
+            .iter()
+            .map(|(address, _)| *address)
+            .collect::<Vec<_>>(),
+        &donation_records
+            .iter()
+            .map(|(address, _)| *address)
+            .collect::<Vec<_>>(),
+        &matching_pools
+            .iter()
+            .map(|(address, _)| *address)
+            .collect::<Vec<_>>(),
+    )?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use anchor_lang::{AccountSerialize, Event, InstructionData};
+    use async_trait::async_trait;
+    use campaign_treasury_manager::{
+        instruction, CampaignEscrow, CrankReward, DepositProcessed, DepositRecord, DonationRecord,
+        MatchingPool, PayoutPhaseInput, PayoutPhasePaidOut, PayoutPhases, RefundProcessed,
+        RewardTiers, StretchGoal, SurplusPolicy, Treasury, VetoVotesCast,
+    };
+    use rusqlite::params;
+    use serde_json::json;
+    use solana_account_decoder::UiAccountEncoding;
+    use solana_client::{
+        client_error::{ClientErrorKind, Result as ClientResult},
+        rpc_client::RpcClientConfig,
+        rpc_request::RpcRequest,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    };
+    use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+
+    use super::*;
+
+    const CAMPAIGN_END_TIME: i64 = 1_700_000_000;
+    const DAY_IN_SECONDS: i64 = 24 * 60 * 60;
+
+    fn get_campaign_escrow() -> CampaignEscrow {
+        let payout_phases = PayoutPhases::new(
+            vec![
+                PayoutPhaseInput {
+                    payout_basis_points: 5_000,
+                    payout_time: CAMPAIGN_END_TIME + 7 * DAY_IN_SECONDS,
+                    refund_deadline: CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+                    description: String::from("Initial payout"),
+                    stretch_goal: None,
+                    stream: None,
+                    voting_config: None,
+                },
+                PayoutPhaseInput {
+                    payout_basis_points: 5_000,
+                    payout_time: CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+                    refund_deadline: CAMPAIGN_END_TIME + 67 * DAY_IN_SECONDS,
+                    description: String::from("Final payout"),
+                    stretch_goal: None,
+                    stream: None,
+                    voting_config: None,
+                },
+            ],
+            CAMPAIGN_END_TIME,
+        )
+        .unwrap();
+
+        CampaignEscrow {
+            version: CampaignEscrow::VERSION,
+            bump: 255,
+            campaign_uuid: String::from("2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a"),
+            authority: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            payout_wallet: Pubkey::new_unique(),
+            treasury: Treasury {
+                bump: 255,
+                treasury_mint: Pubkey::new_unique(),
+                treasury_escrow: Pubkey::new_unique(),
+                total_funds: 1_000_000,
+                total_paid_out: 0,
+                total_refunded: 0,
+                total_surplus: 0,
+                supporter_surplus: 0,
+                supporter_surplus_paid_out: 0,
+                total_crank_rewards: 0,
+                pending_estimated_funds: 0,
+                total_donations: 0,
+                non_voting_donations: 0,
+                non_refundable_donations: 0,
+                total_matched: 0,
+                closed_deposit_amount: 0,
+            },
+            deposit_escrow_infos: vec![],
+            campaign_end_time: CAMPAIGN_END_TIME,
+            deposit_count: 1,
+            processed_deposit_count: 1,
+            closed_deposit_record_count: 0,
+            payouts_ready: true,
+            surplus_policy: SurplusPolicy::Creator,
+            crank_reward: CrankReward::Fixed { amount: 1_000 },
+            collection_mint: Pubkey::new_unique(),
+            deposit_escrow_infos_capacity: CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            fundraising_start_time: None,
+            deposit_grace_period: 0,
+            reward_tiers: RewardTiers::default(),
+            hard_cap: None,
+            auto_end_at_hard_cap: false,
+            hard_cap_reached_at: None,
+            settled_raise: None,
+            has_matching_pool: false,
+            payout_phases,
+        }
+    }
+
+    fn write_account_dump<T: AccountSerialize>(
+        directory: &Path,
+        address: &Pubkey,
+        account_data: &T,
+    ) {
+        let account = get_program_account(account_data);
+        let account_dump = json!({
+            "pubkey": address.to_string(),
+            "account": UiAccount::encode(address, &account, UiAccountEncoding::Base64, None, None),
+        });
+
+        fs::write(
+            directory.join(format!("{}.json", address)),
+            account_dump.to_string(),
+        )
+        .unwrap();
+    }
+
+    // A getTransaction response in which an outer program invokes the program
+    // by CPI, as CreateDeposit requires. The events are logged as Program data:
+    // lines.
+    fn get_transaction_response(
+        slot: u64,
+        instruction_name: &str,
+        instruction_data: Vec<u8>,
+        accounts: &[Pubkey],
+        events: &[Vec<u8>],
+        is_failed: bool,
+    ) -> (String, serde_json::Value) {
+        let signature = Signature::new_unique().to_string();
+        let outer_program = Pubkey::new_unique();
+        let mut account_keys = vec![Pubkey::new_unique().to_string()];
+        account_keys.extend(accounts.iter().map(ToString::to_string));
+        account_keys.push(outer_program.to_string());
+        account_keys.push(ID.to_string());
+        let program_index = accounts.len() + 2;
+
+        let mut log_messages = vec![
+            format!("Program {} invoke [1]", outer_program),
+            String::from("Program log: Outer program log"),
+            format!("Program {} invoke [2]", ID),
+            format!("Program log: Instruction: {}", instruction_name),
+        ];
+        log_messages.extend(
+            events
+                .iter()
+                .map(|event| format!("Program data: {}", base64::encode(event))),
+        );
+        log_messages.push(format!("Program {} success", ID));
+        log_messages.push(format!("Program {} success", outer_program));
+
+        let transaction_response = json!({
+            "slot": slot,
+            "blockTime": CAMPAIGN_END_TIME,
+            "transaction": {
+                "signatures": [signature],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 2,
+                    },
+                    "accountKeys": account_keys,
+                    "recentBlockhash": Pubkey::new_unique().to_string(),
+                    "instructions": [{
+                        "programIdIndex": program_index - 1,
+                        "accounts": (0..=accounts.len()).chain([program_index]).collect::<Vec<_>>(),
+                        "data": "",
+                    }],
+                },
+            },
+            "meta": {
+                "err": if is_failed { json!({"InstructionError": [0, {"Custom": 9000}]}) } else { json!(null) },
+                "status": if is_failed { json!({"Err": {"InstructionError": [0, {"Custom": 9000}]}}) } else { json!({"Ok": null}) },
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [{
+                        "programIdIndex": program_index,
+                        "accounts": (1..=accounts.len()).collect::<Vec<_>>(),
+                        "data": bs58::encode(instruction_data).into_string(),
+                    }],
+                }],
+                "logMessages": log_messages,
+            },
+        });
+
+        (signature, transaction_response)
+    }
+
+    fn get_create_deposit_transaction_response(
+        slot: u64,
+        campaign_escrow: &Pubkey,
+        deposit_record: &Pubkey,
+        is_failed: bool,
+    ) -> (String, serde_json::Value) {
+        let deposit_processed = DepositProcessed {
+            campaign_escrow: *campaign_escrow,
+            deposit_record: *deposit_record,
+            depositor: Pubkey::new_unique(),
+            processed_deposit_amount: 1_000_000,
+        };
+
+        get_transaction_response(
+            slot,
+            "CreateDeposit",
+            instruction::CreateDeposit {
+                deposit_amount: 1_000_000,
+                reward_tier_index: None,
+            }
+            .data(),
+            &[*campaign_escrow, *deposit_record],
+            &[deposit_processed.data()],
+            is_failed,
+        )
+    }
+
+    fn write_create_deposit_transaction_dump(
+        directory: &Path,
+        slot: u64,
+        campaign_escrow: &Pubkey,
+        deposit_record: &Pubkey,
+        is_failed: bool,
+    ) -> String {
+        let (signature, transaction_response) = get_create_deposit_transaction_response(
+            slot,
+            campaign_escrow,
+            deposit_record,
+            is_failed,
+        );
+
+        fs::write(
+            directory.join(format!("transaction-{}.json", signature)),
+            transaction_response.to_string(),
+        )
+        .unwrap();
+
+        signature
+    }
+
+    // Serves the requests made by sync_from_rpc from fixed transactions and
+    // program accounts.
+    struct MockRpcSender {
+        // Signatures and getTransaction responses, newest first.
+        transactions: Vec<(String, serde_json::Value)>,
+        accounts: Vec<(Pubkey, Account)>,
+    }
+
+    #[async_trait]
+    impl RpcSender for MockRpcSender {
+        async fn send(
+            &self,
+            request: RpcRequest,
+            params: serde_json::Value,
+        ) -> ClientResult<serde_json::Value> {
+            match request {
+                RpcRequest::GetVersion => Ok(json!({"solana-core": "1.14.16"})),
+                RpcRequest::GetSignaturesForAddress => {
+                    let config = &params[1];
+                    let start = match config["before"].as_str() {
+                        Some(before) => self
+                            .transactions
+                            .iter()
+                            .position(|(signature, _)| signature == before)
+                            .map_or(self.transactions.len(), |index| index + 1),
+                        None => 0,
+                    };
+                    let page: Vec<_> = self.transactions[start..]
+                        .iter()
+                        .take_while(|(signature, _)| {
+                            config["until"].as_str() != Some(signature.as_str())
+                        })
+                        .map(|(signature, transaction_response)| {
+                            json!({
+                                "signature": signature,
+                                "slot": transaction_response["slot"],
+                                "err": transaction_response["meta"]["err"],
+                            })
+                        })
+                        .collect();
+                    Ok(json!(page))
+                }
+                RpcRequest::GetTransaction => Ok(self
+                    .transactions
+                    .iter()
+                    .find(|(signature, _)| params[0].as_str() == Some(signature.as_str()))
+                    .map_or(json!(null), |(_, transaction_response)| {
+                        transaction_response.clone()
+                    })),
+                // Accounts of other types fail to decode, so the discriminator
+                // filter is not applied.
+                RpcRequest::GetProgramAccounts => Ok(json!(self
+                    .accounts
+                    .iter()
+                    .map(|(address, account)| json!({
+                        "pubkey": address.to_string(),
+                        "account": UiAccount::encode(
+                            address,
+                            account,
+                            UiAccountEncoding::Base64,
+                            None,
+                            None,
+                        ),
+                    }))
+                    .collect::<Vec<_>>())),
+                _ => Err(ClientErrorKind::Custom(format!("Unexpected request {}", request)).into()),
+            }
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            String::from("MockRpcSender")
+        }
+    }
+
+    fn get_program_account<T: AccountSerialize>(account_data: &T) -> Account {
+        let mut data = vec![];
+        account_data.try_serialize(&mut data).unwrap();
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn get_mock_rpc_client(
+        transactions: Vec<(String, serde_json::Value)>,
+        accounts: Vec<(Pubkey, Account)>,
+    ) -> RpcClient {
+        RpcClient::new_sender(
+            MockRpcSender {
+                transactions,
+                accounts,
+            },
+            RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+        )
+    }
+
+    fn query_strings(database: &Database, sql: &str, param: &str) -> Vec<String> {
+        let mut statement = database.connection().prepare(sql).unwrap();
+        let rows = statement
+            .query_map(params![param], |row| row.get(0))
+            .unwrap();
+        rows.collect::<std::result::Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn test_backfill_from_directory() {
+        let directory = env::temp_dir().join(format!("indexer-backfill-{}", process::id()));
+        fs::create_dir_all(directory.join("transactions")).unwrap();
+
+        let campaign_escrow_address = Pubkey::new_unique();
+        let mut campaign_escrow = get_campaign_escrow();
+        campaign_escrow.record_payout(500_000).unwrap();
+        write_account_dump(&directory, &campaign_escrow_address, &campaign_escrow);
+
+        let deposit_record_address = Pubkey::new_unique();
+        let deposit_record = DepositRecord {
+            depositor: Pubkey::new_unique(),
+            initial_deposit_amount: 1_000_000,
+            processed_deposit_amount: 1_000_000,
+            deposit_processed: true,
+            ..DepositRecord::default()
+        };
+        write_account_dump(&directory, &deposit_record_address, &deposit_record);
+
+        let donation_record_address = Pubkey::new_unique();
+        let donation_record = DonationRecord {
+            campaign_escrow: campaign_escrow_address,
+            donation_uuid: String::from("donation"),
+            donor: Pubkey::new_unique(),
+            donation_amount: 100_000,
+            has_vote_weight: true,
+            ..DonationRecord::default()
+        };
+        write_account_dump(&directory, &donation_record_address, &donation_record);
+
+        let matching_pool_address = Pubkey::new_unique();
+        let matching_pool = MatchingPool {
+            campaign_escrow: campaign_escrow_address,
+            sponsor: Pubkey::new_unique(),
+            match_basis_points: 10_000,
+            match_cap: 500_000,
+            total_matched: 100_000,
+            ..MatchingPool::default()
+        };
+        write_account_dump(&directory, &matching_pool_address, &matching_pool);
+
+        let signature = write_create_deposit_transaction_dump(
+            &directory.join("transactions"),
+            10,
+            &campaign_escrow_address,
+            &deposit_record_address,
+            false,
+        );
+        // A failed CreateDeposit does not link its deposit record.
+        let failed_deposit_record_address = Pubkey::new_unique();
+        write_create_deposit_transaction_dump(
+            &directory.join("transactions"),
+            11,
+            &campaign_escrow_address,
+            &failed_deposit_record_address,
+            true,
+        );
+
+        let mut database = Database::open_in_memory().unwrap();
+        let summary = backfill_from_directory(&mut database, &directory).unwrap();
+        assert_eq!(
+            summary,
+            IngestSummary {
+                campaign_escrows: 1,
+                deposit_records: 1,
+                donation_records: 1,
+                matching_pools: 1,
+                skipped_accounts: 0,
+                transactions: 2,
+            }
+        );
+
+        // Backfilling the same dumps again is idempotent.
+        let summary = backfill_from_directory(&mut database, &directory).unwrap();
+        assert_eq!(summary.transactions, 0);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT address FROM deposit_records WHERE campaign = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![deposit_record_address.to_string()]
+        );
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT address FROM donation_records WHERE campaign = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![donation_record_address.to_string()]
+        );
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT address FROM matching_pools WHERE campaign = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![matching_pool_address.to_string()]
+        );
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT DISTINCT campaign FROM pending_payouts WHERE campaign = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![campaign_escrow_address.to_string()]
+        );
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT name FROM instructions WHERE signature = ?1",
+                &signature,
+            ),
+            vec![String::from("create_deposit")]
+        );
+        // Only logs emitted by the program itself are indexed.
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT message FROM program_logs WHERE signature = ?1 AND kind = 'log'",
+                &signature,
+            ),
+            vec![String::from("Instruction: CreateDeposit")]
+        );
+        // The DepositProcessed event of the failed CreateDeposit is not
+        // indexed.
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT deposit_record FROM deposits WHERE campaign = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![deposit_record_address.to_string()]
+        );
+        let crank_reward_columns: (Option<i64>, Option<i64>) = database
+            .connection()
+            .query_row(
+                "SELECT crank_reward_amount, crank_reward_basis_points FROM campaigns
+                WHERE address = ?1",
+                params![campaign_escrow_address.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(crank_reward_columns, (Some(1_000), None));
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT collection_mint FROM campaigns WHERE address = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![campaign_escrow.collection_mint.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sync_from_rpc() {
+        let campaign_escrow_address = Pubkey::new_unique();
+        let campaign_escrow = get_campaign_escrow();
+        let deposit_record_address = Pubkey::new_unique();
+        let deposit_record = DepositRecord {
+            campaign_escrow: campaign_escrow_address,
+            depositor: Pubkey::new_unique(),
+            initial_deposit_amount: 1_000_000,
+            processed_deposit_amount: 1_000_000,
+            deposit_processed: true,
+            ..DepositRecord::default()
+        };
+        let accounts = vec![
+            (
+                campaign_escrow_address,
+                get_program_account(&campaign_escrow),
+            ),
+            (deposit_record_address, get_program_account(&deposit_record)),
+            // Not a program account type, e.g. a legacy account.
+            (
+                Pubkey::new_unique(),
+                Account {
+                    lamports: 1_000_000,
+                    data: vec![0; 16],
+                    owner: ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+        ];
+
+        let create_deposit = get_create_deposit_transaction_response(
+            10,
+            &campaign_escrow_address,
+            &deposit_record_address,
+            false,
+        );
+        let failed_create_deposit = get_create_deposit_transaction_response(
+            11,
+            &campaign_escrow_address,
+            &Pubkey::new_unique(),
+            true,
+        );
+        let mut transactions = vec![failed_create_deposit.clone(), create_deposit];
+
+        let mut database = Database::open_in_memory().unwrap();
+        let rpc_client = get_mock_rpc_client(transactions.clone(), accounts.clone());
+        let summary = sync_from_rpc(&mut database, &rpc_client).unwrap();
+        assert_eq!(
+            summary,
+            IngestSummary {
+                campaign_escrows: 1,
+                deposit_records: 1,
+                donation_records: 0,
+                matching_pools: 0,
+                skipped_accounts: 0,
+                transactions: 2,
+            }
+        );
+        assert_eq!(
+            database.get_last_signature().unwrap(),
+            Some(failed_create_deposit.0)
+        );
+        assert_eq!(
+            query_strings(
+                &database,
+                "SELECT deposit_record FROM deposits WHERE campaign = ?1",
+                &campaign_escrow_address.to_string(),
+            ),
+            vec![deposit_record_address.to_string()]
+        );
+
+        // The next sync only ingests the transactions since the last synced
+        // signature.
+        let pay_out_funds = get_transaction_response(
+            12,
+            "PayOutFunds",
+            instruction::PayOutFunds {}.data(),
+            &[campaign_escrow_address],
+            &[PayoutPhasePaidOut {
+                campaign_escrow: campaign_escrow_address,
+                payout_phase_index: 0,
+                paid_amount: 500_000,
+                crank_reward_amount: 1_000,
+            }
+            .data()],
+            false,
+        );
+        let voter = Pubkey::new_unique();
+        let vote_to_veto_payout_phase = get_transaction_response(
+            13,
+            "VoteToVetoPayoutPhase",
+            instruction::VoteToVetoPayoutPhase {
+                payout_phase_index: 1,
+            }
+            .data(),
+            &[campaign_escrow_address, deposit_record_address],
+            &[VetoVotesCast {
+                campaign_escrow: campaign_escrow_address,
+                payout_phase_index: 1,
+                record: deposit_record_address,
+                voter,
+                veto_votes: 1_000_000,
+            }
+            .data()],
+            false,
+        );
+        let refund_receiver = Pubkey::new_unique();
+        let process_full_refund = get_transaction_response(
+            14,
+            "ProcessFullRefund",
+            instruction::ProcessFullRefund {}.data(),
+            &[campaign_escrow_address, deposit_record_address],
+            &[RefundProcessed {
+                campaign_escrow: campaign_escrow_address,
+                record: deposit_record_address,
+                refund_receiver,
+                refund_amount: 500_000,
+            }
+            .data()],
+            false,
+        );
+        transactions.splice(
+            0..0,
+            [
+                process_full_refund.clone(),
+                vote_to_veto_payout_phase,
+                pay_out_funds.clone(),
+            ],
+        );
+
+        let rpc_client = get_mock_rpc_client(transactions.clone(), accounts.clone());
+        let summary = sync_from_rpc(&mut database, &rpc_client).unwrap();
+        assert_eq!(summary.transactions, 3);
+        assert_eq!(
+            database.get_last_signature().unwrap(),
+            Some(process_full_refund.0.clone())
+        );
+
+        let payout_row: (String, u8, u64, u64, bool) = database
+            .connection()
+            .query_row(
+                "SELECT signature, payout_phase_index, amount, crank_reward_amount, is_streamed
+                FROM payouts WHERE campaign = ?1",
+                params![campaign_escrow_address.to_string()],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(payout_row, (pay_out_funds.0, 0, 500_000, 1_000, false));
+        let vote_row: (u8, String, String, u64) = database
+            .connection()
+            .query_row(
+                "SELECT payout_phase_index, record, voter, veto_votes FROM votes
+                WHERE campaign = ?1",
+                params![campaign_escrow_address.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            vote_row,
+            (
+                1,
+                deposit_record_address.to_string(),
+                voter.to_string(),
+                1_000_000
+            )
+        );
+        let refund_row: (String, String, u64) = database
+            .connection()
+            .query_row(
+                "SELECT record, refund_receiver, refund_amount FROM refunds WHERE campaign = ?1",
+                params![campaign_escrow_address.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            refund_row,
+            (
+                deposit_record_address.to_string(),
+                refund_receiver.to_string(),
+                500_000
+            )
+        );
+
+        // Syncing again without new transactions is idempotent.
+        let rpc_client = get_mock_rpc_client(transactions, accounts);
+        let summary = sync_from_rpc(&mut database, &rpc_client).unwrap();
+        assert_eq!(summary.transactions, 0);
+        assert_eq!(
+            database.get_last_signature().unwrap(),
+            Some(process_full_refund.0)
+        );
+    }
+
+    #[test]
+    fn test_pending_payouts_exclude_vetoed_phases() {
+        let mut database = Database::open_in_memory().unwrap();
+        let campaign_escrow_address = Pubkey::new_unique();
+        let mut campaign_escrow = get_campaign_escrow();
+        database
+            .upsert_campaign_escrow(&campaign_escrow_address, &campaign_escrow)
+            .unwrap();
+
+        let pending_payouts_sql =
+            "SELECT payout_phase_index FROM pending_payouts WHERE campaign = ?1";
+        let get_pending_payout_indexes = |database: &Database| -> Vec<i64> {
+            let mut statement = database.connection().prepare(pending_payouts_sql).unwrap();
+            let rows = statement
+                .query_map(params![campaign_escrow_address.to_string()], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            rows.collect::<std::result::Result<_, _>>().unwrap()
+        };
+        assert_eq!(get_pending_payout_indexes(&database), vec![0, 1]);
+
+        // A vetoed phase blocks every later phase.
+        campaign_escrow
+            .payout_phases
+            .veto_payout_phase_by_authority(0, CAMPAIGN_END_TIME);
+        database
+            .upsert_campaign_escrow(&campaign_escrow_address, &campaign_escrow)
+            .unwrap();
+        assert!(get_pending_payout_indexes(&database).is_empty());
+
+        database.mark_closed_accounts(&[], &[], &[], &[]).unwrap();
+        let is_closed: bool = database
+            .connection()
+            .query_row(
+                "SELECT is_closed FROM campaigns WHERE address = ?1",
+                params![campaign_escrow_address.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(is_closed);
+    }
+
+    #[test]
+    fn test_pending_payouts_exclude_deactivated_phases() {
+        let mut database = Database::open_in_memory().unwrap();
+        let campaign_escrow_address = Pubkey::new_unique();
+        let mut campaign_escrow = get_campaign_escrow();
+        let get_stretch_goal_payout_phase_input = |days, is_active_when_reached| PayoutPhaseInput {
+            payout_basis_points: 5_000,
+            payout_time: CAMPAIGN_END_TIME + days * DAY_IN_SECONDS,
+            refund_deadline: CAMPAIGN_END_TIME + (days + 30) * DAY_IN_SECONDS,
+            description: String::from("Stretch goal payout"),
+            stretch_goal: Some(StretchGoal {
+                raise_threshold: 2_000_000,
+                is_active_when_reached,
+            }),
+            stream: None,
+            voting_config: None,
+        };
+        let mut payout_phases_input = vec![PayoutPhaseInput {
+            payout_basis_points: 5_000,
+            payout_time: CAMPAIGN_END_TIME + 7 * DAY_IN_SECONDS,
+            refund_deadline: CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+            description: String::from("Initial payout"),
+            stretch_goal: None,
+            stream: None,
+            voting_config: None,
+        }];
+        payout_phases_input.push(get_stretch_goal_payout_phase_input(37, false));
+        payout_phases_input.push(get_stretch_goal_payout_phase_input(67, true));
+        campaign_escrow.payout_phases =
+            PayoutPhases::new(payout_phases_input, CAMPAIGN_END_TIME).unwrap();
+        database
+            .upsert_campaign_escrow(&campaign_escrow_address, &campaign_escrow)
+            .unwrap();
+
+        let pending_payouts_sql =
+            "SELECT payout_phase_index FROM pending_payouts WHERE campaign = ?1";
+        let get_pending_payout_indexes = |database: &Database| -> Vec<i64> {
+            let mut statement = database.connection().prepare(pending_payouts_sql).unwrap();
+            let rows = statement
+                .query_map(params![campaign_escrow_address.to_string()], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            rows.collect::<std::result::Result<_, _>>().unwrap()
+        };
+        // Every stretch goal outcome is pending until the stretch goals are
+        // settled.
+        assert_eq!(get_pending_payout_indexes(&database), vec![0, 1, 2]);
+
+        campaign_escrow.settle_stretch_goals_if_needed();
+        database
+            .upsert_campaign_escrow(&campaign_escrow_address, &campaign_escrow)
+            .unwrap();
+        assert_eq!(get_pending_payout_indexes(&database), vec![0, 1]);
+        let settled_raise: Option<u64> = database
+            .connection()
+            .query_row(
+                "SELECT settled_raise FROM campaigns WHERE address = ?1",
+                params![campaign_escrow_address.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(settled_raise, Some(1_000_000));
+    }
+}
@@ -0,0 +1,11 @@
+// Indexes campaign treasury manager accounts and transactions into SQLite, so
+// that campaigns and deposits can be queried, e.g. all deposits for a campaign
+// or campaigns with pending payouts.
+
+pub mod db;
+pub mod decode;
+pub mod ingest;
+
+pub use db::*;
+pub use decode::*;
+pub use ingest::*;
@@ -0,0 +1,68 @@
+// Indexer binary. Backfills the database from a directory of account and
+// transaction dumps, or incrementally syncs it from an RPC endpoint.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use campaign_treasury_manager_indexer::{
+    backfill_from_directory, sync_from_rpc, Database, IngestSummary,
+};
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+#[derive(Parser)]
+#[clap(
+    version,
+    about = "Formfunction campaign treasury manager SQLite indexer"
+)]
+struct Cli {
+    /// SQLite database file, created if it does not exist.
+    #[clap(long, global = true, default_value = "campaign-treasury-manager.db")]
+    database: PathBuf,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index account dumps (`solana account --output json`) and transaction
+    /// dumps (getTransaction responses) from a directory.
+    Backfill {
+        #[clap(long)]
+        dir: PathBuf,
+    },
+    /// Index new program transactions and refresh all program accounts.
+    Sync {
+        #[clap(long, default_value = "http://localhost:8899")]
+        rpc_url: String,
+    },
+}
+
+fn print_summary(summary: &IngestSummary) {
+    println!(
+        "Indexed {} campaign escrows, {} deposit records, {} donation records, {} matching pools and {} transactions ({} accounts skipped).",
+        summary.campaign_escrows,
+        summary.deposit_records,
+        summary.donation_records,
+        summary.matching_pools,
+        summary.transactions,
+        summary.skipped_accounts,
+    );
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut database = Database::open(&cli.database)?;
+
+    let summary = match cli.command {
+        Command::Backfill { dir } => backfill_from_directory(&mut database, &dir)?,
+        Command::Sync { rpc_url } => {
+            let rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+            sync_from_rpc(&mut database, &rpc_client)?
+        }
+    };
+    print_summary(&summary);
+
+    Ok(())
+}
@@ -0,0 +1,352 @@
+// Keeper for the permissionless PayOutAllDueFunds ix. Scans all CampaignEscrow
+// accounts and submits a payout for every campaign which has payouts due,
+// which claims the campaign crank reward, if any, for the keeper.
+//
+// Payout eligibility comes from the program crate, so the keeper follows the
+// same rules as the on-chain instruction.
+
+use anyhow::{anyhow, Result};
+use campaign_treasury_manager::{is_native_mint, CampaignEscrow};
+use campaign_treasury_manager_client::{
+    fetch_all_campaign_escrows, get_payment_account, pay_out_all_due_funds_ix, PayOutFundsAccounts,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::from_account,
+    clock::Clock,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    sysvar,
+    transaction::Transaction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuePayout {
+    pub campaign_escrow: Pubkey,
+    // In its PDA seed form, as stored on the CampaignEscrow.
+    pub campaign_uuid: String,
+    // All payout phases which are due, paid out together in one transaction.
+    pub payout_phase_indexes: Vec<usize>,
+    pub payout_wallet: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub payout_amount: u64,
+    pub crank_reward_amount: u64,
+}
+
+#[derive(Debug)]
+pub struct CrankResult {
+    pub due_payout: DuePayout,
+    pub result: Result<Signature>,
+}
+
+// Returns the campaigns with payouts which can be paid out at the given time.
+pub fn find_due_payouts(campaign_escrows: &[(Pubkey, CampaignEscrow)], now: i64) -> Vec<DuePayout> {
+    campaign_escrows
+        .iter()
+        .filter_map(|(address, campaign_escrow)| {
+            // The instruction settles the stretch goals before selecting the
+            // payout phases, which deactivates some of them.
+            let mut campaign_escrow = campaign_escrow.clone();
+            campaign_escrow.settle_stretch_goals_if_needed();
+
+            let payout_phase_indexes = campaign_escrow
+                .payout_phases
+                .get_due_payout_phase_indexes(now, campaign_escrow.get_total_votes())
+                .ok()?;
+
+            // The crank reward is calculated per payout phase, matching the
+            // on-chain instruction.
+            let mut payout_amount = 0;
+            let mut crank_reward_amount = 0;
+            for &payout_phase_index in payout_phase_indexes.iter() {
+                let payout_phase = campaign_escrow.payout_phases.get(payout_phase_index)?;
+                let phase_payout_amount = campaign_escrow
+                    .calculate_payout_amount_from_payout_basis_points(
+                        payout_phase.payout_basis_points,
+                    );
+                payout_amount += phase_payout_amount;
+                crank_reward_amount +=
+                    campaign_escrow.calculate_crank_reward_amount(phase_payout_amount);
+            }
+
+            Some(DuePayout {
+                campaign_escrow: *address,
+                campaign_uuid: campaign_escrow.campaign_uuid.clone(),
+                payout_phase_indexes,
+                payout_wallet: campaign_escrow.payout_wallet,
+                treasury_mint: campaign_escrow.treasury.treasury_mint,
+                payout_amount,
+                crank_reward_amount,
+            })
+        })
+        .collect()
+}
+
+// The crank reward is received by the keeper itself for native treasuries, and
+// by the keeper's ATA for token treasuries, which is created if needed.
+pub fn get_crank_payout_ixs(due_payout: &DuePayout, keeper: &Pubkey) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    if due_payout.crank_reward_amount > 0 && !is_native_mint(&due_payout.treasury_mint) {
+        instructions.push(create_associated_token_account_idempotent(
+            keeper,
+            keeper,
+            &due_payout.treasury_mint,
+            &spl_token::id(),
+        ));
+    }
+
+    instructions.push(pay_out_all_due_funds_ix(
+        PayOutFundsAccounts {
+            payer: *keeper,
+            payout_wallet: due_payout.payout_wallet,
+            crank_reward_receiver: Some(get_payment_account(keeper, &due_payout.treasury_mint)),
+        },
+        &due_payout.campaign_uuid,
+    ));
+
+    instructions
+}
+
+// The payout eligibility checks use the on-chain clock, which may differ from
+// the local time.
+pub fn get_cluster_time(rpc_client: &RpcClient) -> Result<i64> {
+    let account = rpc_client.get_account(&sysvar::clock::id())?;
+    let clock: Clock =
+        from_account(&account).ok_or_else(|| anyhow!("Failed to decode the Clock sysvar"))?;
+    Ok(clock.unix_timestamp)
+}
+
+// Submits one transaction per due payout. A failed payout, e.g. one which was
+// already paid out by another keeper, does not stop the remaining payouts.
+pub fn crank_due_payouts(rpc_client: &RpcClient, keeper: &Keypair) -> Result<Vec<CrankResult>> {
+    let campaign_escrows = fetch_all_campaign_escrows(rpc_client)?;
+    let now = get_cluster_time(rpc_client)?;
+
+    let results = find_due_payouts(&campaign_escrows, now)
+        .into_iter()
+        .map(|due_payout| {
+            let result = submit_crank_payout(rpc_client, keeper, &due_payout);
+            CrankResult { due_payout, result }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+fn submit_crank_payout(
+    rpc_client: &RpcClient,
+    keeper: &Keypair,
+    due_payout: &DuePayout,
+) -> Result<Signature> {
+    let recent_blockhash = rpc_client.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        &get_crank_payout_ixs(due_payout, &keeper.pubkey()),
+        Some(&keeper.pubkey()),
+        &[keeper],
+        recent_blockhash,
+    );
+
+    Ok(rpc_client.send_and_confirm_transaction(&transaction)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use campaign_treasury_manager::{
+        CrankReward, PayoutPhaseInput, PayoutPhases, RewardTiers, StretchGoal, SurplusPolicy,
+        Treasury,
+    };
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+    const DAY_IN_SECONDS: i64 = 24 * 60 * 60;
+    const CAMPAIGN_END_TIME: i64 = 1_700_000_000;
+    const FIRST_PAYOUT_TIME: i64 = CAMPAIGN_END_TIME + 7 * DAY_IN_SECONDS;
+    const SECOND_PAYOUT_TIME: i64 = CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS;
+
+    fn get_campaign_escrow(treasury_mint: Pubkey, crank_reward: CrankReward) -> CampaignEscrow {
+        let payout_phases = PayoutPhases::new(
+            vec![
+                PayoutPhaseInput {
+                    payout_basis_points: 5_000,
+                    payout_time: FIRST_PAYOUT_TIME,
+                    refund_deadline: CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+                    description: String::from("Initial payout"),
+                    stretch_goal: None,
+                    stream: None,
+                    voting_config: None,
+                },
+                PayoutPhaseInput {
+                    payout_basis_points: 5_000,
+                    payout_time: SECOND_PAYOUT_TIME,
+                    refund_deadline: CAMPAIGN_END_TIME + 67 * DAY_IN_SECONDS,
+                    description: String::from("Final payout"),
+                    stretch_goal: None,
+                    stream: None,
+                    voting_config: None,
+                },
+            ],
+            CAMPAIGN_END_TIME,
+        )
+        .unwrap();
+
+        CampaignEscrow {
+            version: CampaignEscrow::VERSION,
+            bump: 255,
+            campaign_uuid: String::from("2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a"),
+            authority: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            payout_wallet: Pubkey::new_unique(),
+            treasury: Treasury {
+                bump: 255,
+                treasury_mint,
+                treasury_escrow: Pubkey::new_unique(),
+                total_funds: 2 * LAMPORTS_PER_SOL,
+                total_paid_out: 0,
+                total_refunded: 0,
+                total_surplus: 0,
+                supporter_surplus: 0,
+                supporter_surplus_paid_out: 0,
+                total_crank_rewards: 0,
+                pending_estimated_funds: 0,
+                total_donations: 0,
+                non_voting_donations: 0,
+                non_refundable_donations: 0,
+                total_matched: 0,
+                closed_deposit_amount: 0,
+            },
+            deposit_escrow_infos: vec![],
+            campaign_end_time: CAMPAIGN_END_TIME,
+            deposit_count: 1,
+            processed_deposit_count: 1,
+            closed_deposit_record_count: 0,
+            payouts_ready: true,
+            surplus_policy: SurplusPolicy::Creator,
+            crank_reward,
+            collection_mint: Pubkey::new_unique(),
+            deposit_escrow_infos_capacity: CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            fundraising_start_time: None,
+            deposit_grace_period: 0,
+            reward_tiers: RewardTiers::default(),
+            hard_cap: None,
+            auto_end_at_hard_cap: false,
+            hard_cap_reached_at: None,
+            settled_raise: None,
+            has_matching_pool: false,
+            payout_phases,
+        }
+    }
+
+    #[test]
+    fn test_find_due_payouts() {
+        let native_campaign_escrow = (
+            Pubkey::new_unique(),
+            get_campaign_escrow(
+                spl_token::native_mint::id(),
+                CrankReward::BasisPoints { basis_points: 100 },
+            ),
+        );
+        let mut vetoed_campaign_escrow = (
+            Pubkey::new_unique(),
+            get_campaign_escrow(Pubkey::new_unique(), CrankReward::None),
+        );
+        vetoed_campaign_escrow
+            .1
+            .payout_phases
+            .veto_payout_phase_by_authority(0, CAMPAIGN_END_TIME);
+        let campaign_escrows = vec![native_campaign_escrow.clone(), vetoed_campaign_escrow];
+
+        assert!(find_due_payouts(&campaign_escrows, FIRST_PAYOUT_TIME - 1).is_empty());
+
+        let due_payouts = find_due_payouts(&campaign_escrows, FIRST_PAYOUT_TIME);
+        assert_eq!(
+            due_payouts,
+            vec![DuePayout {
+                campaign_escrow: native_campaign_escrow.0,
+                campaign_uuid: native_campaign_escrow.1.campaign_uuid.clone(),
+                payout_phase_indexes: vec![0],
+                payout_wallet: native_campaign_escrow.1.payout_wallet,
+                treasury_mint: spl_token::native_mint::id(),
+                payout_amount: LAMPORTS_PER_SOL,
+                crank_reward_amount: LAMPORTS_PER_SOL / 100,
+            }]
+        );
+
+        // The keeper receives the reward directly for native treasuries.
+        let keeper = Pubkey::new_unique();
+        let instructions = get_crank_payout_ixs(&due_payouts[0], &keeper);
+        assert_eq!(instructions.len(), 1);
+        assert!(instructions[0]
+            .accounts
+            .iter()
+            .any(|account| account.pubkey == keeper && account.is_writable));
+    }
+
+    #[test]
+    fn test_get_crank_payout_ixs_creates_reward_ata() {
+        let treasury_mint = Pubkey::new_unique();
+        let campaign_escrows = vec![(
+            Pubkey::new_unique(),
+            get_campaign_escrow(treasury_mint, CrankReward::Fixed { amount: 1_000 }),
+        )];
+        // Both payout phases are due, and the fixed reward applies to each.
+        let due_payouts = find_due_payouts(&campaign_escrows, SECOND_PAYOUT_TIME);
+        assert_eq!(due_payouts[0].payout_phase_indexes, vec![0, 1]);
+        assert_eq!(due_payouts[0].payout_amount, 2 * LAMPORTS_PER_SOL);
+        assert_eq!(due_payouts[0].crank_reward_amount, 2_000);
+
+        let keeper = Pubkey::new_unique();
+        let instructions = get_crank_payout_ixs(&due_payouts[0], &keeper);
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0].program_id,
+            spl_associated_token_account::id()
+        );
+    }
+    #[test]
+    fn test_find_due_payouts_settles_stretch_goals() {
+        let mut campaign_escrow = get_campaign_escrow(Pubkey::new_unique(), CrankReward::None);
+        let get_stretch_goal_payout_phase_input = |days, is_active_when_reached| PayoutPhaseInput {
+            payout_basis_points: 5_000,
+            payout_time: CAMPAIGN_END_TIME + days * DAY_IN_SECONDS,
+            refund_deadline: CAMPAIGN_END_TIME + (days + 30) * DAY_IN_SECONDS,
+            description: String::from("Stretch goal payout"),
+            stretch_goal: Some(StretchGoal {
+                raise_threshold: 3 * LAMPORTS_PER_SOL,
+                is_active_when_reached,
+            }),
+            stream: None,
+            voting_config: None,
+        };
+        campaign_escrow.payout_phases = PayoutPhases::new(
+            vec![
+                PayoutPhaseInput {
+                    payout_basis_points: 5_000,
+                    payout_time: FIRST_PAYOUT_TIME,
+                    refund_deadline: CAMPAIGN_END_TIME + 37 * DAY_IN_SECONDS,
+                    description: String::from("Initial payout"),
+                    stretch_goal: None,
+                    stream: None,
+                    voting_config: None,
+                },
+                get_stretch_goal_payout_phase_input(37, true),
+                get_stretch_goal_payout_phase_input(67, false),
+            ],
+            CAMPAIGN_END_TIME,
+        )
+        .unwrap();
+        let campaign_escrows = vec![(Pubkey::new_unique(), campaign_escrow)];
+
+        // The raise is below the stretch goal, so the payout phase which is
+        // only active when it is reached is skipped.
+        let due_payouts = find_due_payouts(&campaign_escrows, SECOND_PAYOUT_TIME);
+        assert_eq!(due_payouts[0].payout_phase_indexes, vec![0]);
+
+        let due_payouts =
+            find_due_payouts(&campaign_escrows, CAMPAIGN_END_TIME + 67 * DAY_IN_SECONDS);
+        assert_eq!(due_payouts[0].payout_phase_indexes, vec![0, 2]);
+        assert_eq!(due_payouts[0].payout_amount, 2 * LAMPORTS_PER_SOL);
+    }
+}
@@ -0,0 +1,85 @@
+// Keeper binary. Submits all due payouts once, or repeatedly at an interval.
+
+use std::{path::PathBuf, thread, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use campaign_treasury_manager_keeper::{crank_due_payouts, CrankResult};
+use clap::Parser;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair},
+};
+
+#[derive(Parser)]
+#[clap(
+    version,
+    about = "Formfunction campaign treasury manager payout keeper"
+)]
+struct Cli {
+    /// Keypair file of the keeper, which pays the transaction fees and
+    /// receives the crank rewards.
+    #[clap(long, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[clap(long, default_value = "http://localhost:8899")]
+    rpc_url: String,
+    /// Keep running and scan for due payouts at this interval. Without it,
+    /// the keeper scans once and exits.
+    #[clap(long)]
+    interval_seconds: Option<u64>,
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(relative_path) => {
+            let home = std::env::var("HOME").context("HOME is not set")?;
+            PathBuf::from(home).join(relative_path)
+        }
+        None => PathBuf::from(path),
+    };
+
+    read_keypair_file(&path)
+        .map_err(|e| anyhow!("Failed to read keypair file {}: {}", path.display(), e))
+}
+
+fn print_results(results: &[CrankResult]) {
+    for CrankResult { due_payout, result } in results {
+        match result {
+            Ok(signature) => println!(
+                "Paid out phases {:?} of campaign {} with crank reward {}: {}",
+                due_payout.payout_phase_indexes,
+                due_payout.campaign_uuid,
+                due_payout.crank_reward_amount,
+                signature
+            ),
+            Err(e) => eprintln!(
+                "Failed to pay out phases {:?} of campaign {}: {:#}",
+                due_payout.payout_phase_indexes, due_payout.campaign_uuid, e
+            ),
+        }
+    }
+    println!("Submitted {} due payouts.", results.len());
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keeper = read_keypair(&cli.keypair)?;
+    let rpc_client = RpcClient::new_with_commitment(cli.rpc_url, CommitmentConfig::confirmed());
+
+    let interval_seconds = match cli.interval_seconds {
+        Some(interval_seconds) => interval_seconds,
+        None => {
+            print_results(&crank_due_payouts(&rpc_client, &keeper)?);
+            return Ok(());
+        }
+    };
+
+    loop {
+        // RPC errors are retried on the next scan.
+        match crank_due_payouts(&rpc_client, &keeper) {
+            Ok(results) => print_results(&results),
+            Err(e) => eprintln!("Failed to scan for due payouts: {:#}", e),
+        }
+        thread::sleep(Duration::from_secs(interval_seconds));
+    }
+}
@@ -0,0 +1,179 @@
+// Minimal JSON value and serializer. Object keys are written in insertion
+// order, so the output is deterministic for a given value.
+
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    // Wide enough to hold every i64 and u64 value.
+    Number(i128),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    pub fn to_string_pretty(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output, 0);
+        output
+    }
+
+    fn write(&self, output: &mut String, indent: usize) {
+        match self {
+            Json::Null => output.push_str("null"),
+            Json::Bool(value) => write!(output, "{}", value).unwrap(),
+            Json::Number(value) => write!(output, "{}", value).unwrap(),
+            Json::String(value) => write_string(output, value),
+            Json::Array(values) => {
+                if values.is_empty() {
+                    output.push_str("[]");
+                    return;
+                }
+
+                output.push('[');
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    write_newline(output, indent + 1);
+                    value.write(output, indent + 1);
+                }
+                write_newline(output, indent);
+                output.push(']');
+            }
+            Json::Object(entries) => {
+                if entries.is_empty() {
+                    output.push_str("{}");
+                    return;
+                }
+
+                output.push('{');
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        output.push(',');
+                    }
+                    write_newline(output, indent + 1);
+                    write_string(output, key);
+                    output.push_str(": ");
+                    value.write(output, indent + 1);
+                }
+                write_newline(output, indent);
+                output.push('}');
+            }
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<u8> for Json {
+    fn from(value: u8) -> Self {
+        Json::Number(value.into())
+    }
+}
+
+impl From<u16> for Json {
+    fn from(value: u16) -> Self {
+        Json::Number(value.into())
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::Number(value.into())
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Json::Number(value.into())
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value as i128)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+fn write_newline(output: &mut String, indent: usize) {
+    output.push('\n');
+    for _ in 0..indent {
+        output.push_str("  ");
+    }
+}
+
+fn write_string(output: &mut String, value: &str) {
+    output.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                write!(output, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => output.push(character),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string_pretty() {
+        let value = Json::Object(vec![
+            ("name", "a \"quoted\"\nvalue".into()),
+            ("amount", u64::MAX.into()),
+            ("time", i64::MIN.into()),
+            ("missing", Option::<u64>::None.into()),
+            ("empty", Json::Array(vec![])),
+            ("values", Json::Array(vec![true.into(), 1u8.into()])),
+        ]);
+
+        assert_eq!(
+            value.to_string_pretty(),
+            r#"{
+  "name": "a \"quoted\"\nvalue",
+  "amount": 18446744073709551615,
+  "time": -9223372036854775808,
+  "missing": null,
+  "empty": [],
+  "values": [
+    true,
+    1
+  ]
+}"#
+        );
+    }
+}
@@ -0,0 +1,16 @@
+// Off-chain simulator for campaign schedules. Given a CreateCampaignEscrowInput,
+// a funding amount and a list of hypothetical events, it replays the campaign
+// using the program's own validation and payout logic and produces a
+// deterministic timeline, which can be rendered as JSON.
+//
+// The simulator only uses std on top of the program crate. Note that the
+// program logs validation failures with msg!, which prints to stdout when not
+// running on-chain.
+
+pub mod json;
+pub mod simulation;
+pub mod timeline;
+
+pub use json::*;
+pub use simulation::*;
+pub use timeline::*;
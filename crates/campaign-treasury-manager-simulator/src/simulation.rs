@@ -0,0 +1,965 @@
+// Replays a campaign schedule with hypothetical events. Validation, payout
+// eligibility and payout amounts all come from the program crate, so the
+// simulation follows the same rules as the on-chain instructions.
+
+use std::fmt;
+
+use campaign_treasury_manager::{
+    assert_campaign_end_time_is_valid,
+    buffer_times::{MinBufferTimes, MinBufferTimesTrait},
+    calculate_payout_amount, CampaignEscrow, CreateCampaignEscrowInput, PayoutPhases, RewardTiers,
+    Treasury,
+};
+
+use crate::{PayoutPhaseSummary, Timeline, TimelineEntry, TimelineEntryKind, VotingWindow};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationEvent {
+    // The authority vetoes a payout phase, as in the VetoPayoutPhase ix.
+    VetoPayoutPhase { time: i64, payout_phase_index: u8 },
+    // A supporter claims a refund for a processed deposit of the given amount,
+    // as in the ProcessRefund and ProcessPartialRefund ixs.
+    Refund { time: i64, deposit_amount: u64 },
+}
+
+impl SimulationEvent {
+    fn get_time(&self) -> i64 {
+        match self {
+            SimulationEvent::VetoPayoutPhase { time, .. }
+            | SimulationEvent::Refund { time, .. } => *time,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationInput {
+    // Time the CampaignEscrow is created, used to validate the campaign_end_time.
+    pub created_at: i64,
+    pub campaign_escrow_input: CreateCampaignEscrowInput,
+    // Total funds raised by the end of the campaign.
+    pub total_funds: u64,
+    pub events: Vec<SimulationEvent>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    InvalidCampaignEndTime(String),
+    InvalidPayoutPhases(String),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimulationError::InvalidCampaignEndTime(error) => {
+                write!(f, "Invalid campaign_end_time: {}", error)
+            }
+            SimulationError::InvalidPayoutPhases(error) => {
+                write!(f, "Invalid payout phases: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+// Everything which happens during a simulation. Payouts are attempted at each
+// payout time, as if a keeper cranks PayOutFunds as soon as possible.
+// Streaming payouts are claimed at their payout time and stream end time.
+enum Action<'a> {
+    CampaignEnd,
+    VotingStart { payout_phase_index: usize },
+    Event(&'a SimulationEvent),
+    PayOutFunds,
+    RefundDeadline { payout_phase_index: usize },
+    EscrowClosable,
+}
+
+impl Action<'_> {
+    // Order of actions which happen at the same time. Vetoes and refunds are
+    // applied before a payout at the same time.
+    fn get_sort_order(&self) -> u8 {
+        match self {
+            Action::CampaignEnd => 0,
+            Action::VotingStart { .. } => 1,
+            Action::Event(SimulationEvent::VetoPayoutPhase { .. }) => 2,
+            Action::Event(SimulationEvent::Refund { .. }) => 3,
+            Action::PayOutFunds => 4,
+            Action::RefundDeadline { .. } => 5,
+            Action::EscrowClosable => 6,
+        }
+    }
+}
+
+// The simulated CampaignEscrow tracks the payout phases and the treasury
+// accounting, so payouts and refunds are recorded as the instructions do.
+struct Simulation {
+    campaign_escrow: CampaignEscrow,
+    is_escrow_closable: bool,
+    entries: Vec<TimelineEntry>,
+}
+
+impl Simulation {
+    fn get_remaining_funds(&self) -> u64 {
+        self.campaign_escrow.get_accounted_treasury_funds()
+    }
+
+    fn push_entry(&mut self, time: i64, kind: TimelineEntryKind) {
+        self.entries.push(TimelineEntry { time, kind });
+    }
+
+    fn veto_payout_phase(&mut self, time: i64, payout_phase_index: u8) {
+        self.campaign_escrow
+            .payout_phases
+            .veto_payout_phase_by_authority(payout_phase_index, time);
+        self.push_entry(
+            time,
+            TimelineEntryKind::PayoutPhaseVetoed { payout_phase_index },
+        );
+    }
+
+    // Deposits are refunded in full until funds are paid out, and their share
+    // of the remaining funds after that.
+    fn refund(&mut self, time: i64, deposit_amount: u64) {
+        let campaign_escrow = &self.campaign_escrow;
+        let rejected_reason = if let Err(error) = campaign_escrow.assert_refund_is_available(time) {
+            Some(error.to_string())
+        } else if deposit_amount > campaign_escrow.get_open_deposit_amount() {
+            Some(format!(
+                "Deposit exceeds the {} deposited by supporters who were not refunded yet.",
+                campaign_escrow.get_open_deposit_amount()
+            ))
+        } else {
+            None
+        };
+
+        if let Some(reason) = rejected_reason {
+            self.push_entry(
+                time,
+                TimelineEntryKind::RefundRejected {
+                    deposit_amount,
+                    reason,
+                },
+            );
+            return;
+        }
+
+        let amount =
+            if campaign_escrow.get_refundable_funds(time) == campaign_escrow.treasury.total_funds {
+                deposit_amount
+            } else {
+                campaign_escrow.calculate_refund_amount(deposit_amount, time)
+            };
+        self.campaign_escrow
+            .record_refund(amount, deposit_amount)
+            .unwrap();
+        self.push_entry(
+            time,
+            TimelineEntryKind::Refund {
+                deposit_amount,
+                amount,
+            },
+        );
+    }
+
+    // Pays out every phase which is due, in order, until a payout fails. A
+    // failure is only recorded for a phase whose payout time has passed.
+    fn pay_out_funds(&mut self, time: i64) {
+        loop {
+            let is_streaming = self
+                .campaign_escrow
+                .payout_phases
+                .get_current_active_payout_phase_for_payout()
+                .is_some_and(|(_, payout_phase)| payout_phase.is_streaming());
+            if is_streaming {
+                if !self.claim_streamed_payout(time) {
+                    return;
+                }
+                continue;
+            }
+
+            let (payout_phase_index, payout_basis_points) = match self
+                .campaign_escrow
+                .payout_phases
+                .get_payout_phase_for_payout(time, self.campaign_escrow.get_total_votes())
+            {
+                Ok((index, payout_phase)) => (index, payout_phase.payout_basis_points),
+                Err(_) => {
+                    self.record_blocked_payout(time);
+                    return;
+                }
+            };
+
+            let amount = self
+                .campaign_escrow
+                .calculate_payout_amount_from_payout_basis_points(payout_basis_points);
+            if amount > self.get_remaining_funds() {
+                let reason = format!(
+                    "Insufficient treasury funds for a payout of {}, {} remaining.",
+                    amount,
+                    self.get_remaining_funds()
+                );
+                self.push_entry(
+                    time,
+                    TimelineEntryKind::PayoutFailed {
+                        payout_phase_index,
+                        reason,
+                    },
+                );
+                return;
+            }
+
+            // The crank reward is deducted from the payout, as in the
+            // PayOutFunds ix.
+            let crank_reward = self.campaign_escrow.calculate_crank_reward_amount(amount);
+            self.campaign_escrow.record_payout(amount).unwrap();
+            self.campaign_escrow.record_crank_reward(crank_reward);
+            self.push_entry(
+                time,
+                TimelineEntryKind::Payout {
+                    payout_phase_index,
+                    amount,
+                    crank_reward,
+                },
+            );
+        }
+    }
+
+    // Claims the vested amount of the current streaming payout phase, as the
+    // ClaimStreamedPayout ix does. Returns whether the stream is fully paid
+    // out, in which case the next payout phase can be paid out.
+    fn claim_streamed_payout(&mut self, time: i64) -> bool {
+        let (payout_phase_index, payout_phase) = match self
+            .campaign_escrow
+            .payout_phases
+            .get_streamed_payout_phase_for_claim(time)
+        {
+            Ok(next_payout) => next_payout,
+            Err(_) => return false,
+        };
+
+        let payout_amount = self
+            .campaign_escrow
+            .calculate_payout_amount_from_payout_basis_points(payout_phase.payout_basis_points);
+        let amount = payout_phase.calculate_claimable_stream_amount(
+            payout_amount,
+            time,
+            self.campaign_escrow.get_total_votes(),
+        );
+        if amount == 0 {
+            self.record_blocked_payout(time);
+            return false;
+        }
+
+        if amount > self.get_remaining_funds() {
+            let reason = format!(
+                "Insufficient treasury funds for a streamed payout of {}, {} remaining.",
+                amount,
+                self.get_remaining_funds()
+            );
+            self.push_entry(
+                time,
+                TimelineEntryKind::PayoutFailed {
+                    payout_phase_index,
+                    reason,
+                },
+            );
+            return false;
+        }
+
+        // No crank reward is paid for streamed payout claims.
+        self.campaign_escrow
+            .record_streamed_payout(payout_phase_index, amount, payout_amount)
+            .unwrap();
+        self.push_entry(
+            time,
+            TimelineEntryKind::Payout {
+                payout_phase_index,
+                amount,
+                crank_reward: 0,
+            },
+        );
+
+        // The unvested remainder of a vetoed stream is frozen, which blocks
+        // every later phase.
+        let is_paid_out = self
+            .campaign_escrow
+            .payout_phases
+            .get(payout_phase_index)
+            .unwrap()
+            .is_paid_out;
+        if !is_paid_out {
+            self.record_blocked_payout(time);
+        }
+        is_paid_out
+    }
+
+    // The escrow can be closed once every payout is complete, as the
+    // CloseEscrow ix checks.
+    fn record_escrow_closable(&mut self, time: i64) {
+        if self.is_escrow_closable
+            || self
+                .campaign_escrow
+                .assert_all_payouts_are_complete_at(time)
+                .is_err()
+        {
+            return;
+        }
+
+        self.is_escrow_closable = true;
+        self.push_entry(time, TimelineEntryKind::EscrowClosable);
+    }
+
+    fn record_blocked_payout(&mut self, time: i64) {
+        let (payout_phase_index, payout_phase) = match self
+            .campaign_escrow
+            .payout_phases
+            .get_current_active_payout_phase_for_payout()
+        {
+            Some(next_payout) => next_payout,
+            None => return,
+        };
+
+        if payout_phase.payout_time > time || !payout_phase.is_vetoed_by_authority {
+            return;
+        }
+
+        // Payout times are increasing, so this is the latest phase which is due.
+        let due_payout_phase_index = self
+            .campaign_escrow
+            .payout_phases
+            .iter()
+            .enumerate()
+            .filter(|(_, payout_phase)| {
+                !payout_phase.is_deactivated && payout_phase.payout_time <= time
+            })
+            .map(|(index, _)| index)
+            .last()
+            .unwrap();
+        let reason = if due_payout_phase_index == payout_phase_index {
+            String::from("Payout phase was vetoed by the authority.")
+        } else {
+            format!(
+                "Blocked by payout phase {}, which was vetoed by the authority.",
+                payout_phase_index
+            )
+        };
+
+        self.push_entry(
+            time,
+            TimelineEntryKind::PayoutFailed {
+                payout_phase_index: due_payout_phase_index,
+                reason,
+            },
+        );
+    }
+}
+
+// The CampaignEscrow as the CreateEscrow ix creates it, with the total_funds
+// raised by every deposit. Accounts and reward tiers do not affect the
+// schedule, so they are left as defaults.
+fn create_campaign_escrow(input: &SimulationInput, payout_phases: PayoutPhases) -> CampaignEscrow {
+    let campaign_escrow_input = &input.campaign_escrow_input;
+    CampaignEscrow {
+        version: CampaignEscrow::VERSION,
+        bump: 0,
+        campaign_uuid: String::new(),
+        authority: Default::default(),
+        creator: Default::default(),
+        payout_wallet: Default::default(),
+        treasury: Treasury {
+            bump: 0,
+            treasury_mint: Default::default(),
+            treasury_escrow: Default::default(),
+            total_funds: input.total_funds,
+            total_paid_out: 0,
+            total_refunded: 0,
+            total_surplus: 0,
+            supporter_surplus: 0,
+            supporter_surplus_paid_out: 0,
+            total_crank_rewards: 0,
+            pending_estimated_funds: 0,
+            total_donations: 0,
+            non_voting_donations: 0,
+            non_refundable_donations: 0,
+            total_matched: 0,
+            closed_deposit_amount: 0,
+        },
+        deposit_escrow_infos: vec![],
+        campaign_end_time: campaign_escrow_input.campaign_end_time,
+        deposit_count: 0,
+        processed_deposit_count: 0,
+        closed_deposit_record_count: 0,
+        payouts_ready: true,
+        surplus_policy: campaign_escrow_input.surplus_policy,
+        crank_reward: campaign_escrow_input.crank_reward,
+        collection_mint: campaign_escrow_input.collection_mint,
+        deposit_escrow_infos_capacity: CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+        fundraising_start_time: campaign_escrow_input.fundraising_start_time,
+        deposit_grace_period: campaign_escrow_input.deposit_grace_period,
+        reward_tiers: RewardTiers::default(),
+        hard_cap: campaign_escrow_input.hard_cap,
+        auto_end_at_hard_cap: campaign_escrow_input.auto_end_at_hard_cap,
+        hard_cap_reached_at: None,
+        settled_raise: None,
+        has_matching_pool: false,
+        payout_phases,
+    }
+}
+
+pub fn simulate(input: &SimulationInput) -> Result<Timeline, SimulationError> {
+    let campaign_end_time = input.campaign_escrow_input.campaign_end_time;
+
+    assert_campaign_end_time_is_valid(input.created_at, campaign_end_time)
+        .map_err(|e| SimulationError::InvalidCampaignEndTime(e.to_string()))?;
+
+    let payout_phases = PayoutPhases::new(
+        input.campaign_escrow_input.payout_phases.clone(),
+        campaign_end_time,
+    )
+    .map_err(|e| SimulationError::InvalidPayoutPhases(e.to_string()))?;
+
+    let mut campaign_escrow = create_campaign_escrow(input, payout_phases);
+    // The total_funds are final by the first payout, which is when the
+    // instruction settles the stretch goals.
+    campaign_escrow.settle_stretch_goals_if_needed();
+    let payout_phases = &campaign_escrow.payout_phases;
+
+    // Payout phases deactivated by their stretch goal are left out of the
+    // timeline.
+    let payout_phase_summaries: Vec<PayoutPhaseSummary> = payout_phases
+        .iter()
+        .enumerate()
+        .filter(|(_, payout_phase)| !payout_phase.is_deactivated)
+        .map(|(index, payout_phase)| PayoutPhaseSummary {
+            index,
+            description: payout_phase.description.clone(),
+            payout_basis_points: payout_phase.payout_basis_points,
+            payout_amount: calculate_payout_amount(
+                input.total_funds,
+                payout_phase.payout_basis_points,
+            ),
+            payout_time: payout_phase.payout_time,
+            refund_deadline: payout_phase.refund_deadline,
+            stream_end_time: payout_phase.stream.map(|stream| stream.stream_end_time),
+            voting_window: payout_phase
+                .voting_config
+                .as_ref()
+                .map(|voting_config| VotingWindow {
+                    voting_start_time: voting_config.voting_start_time,
+                    voting_end_time: payout_phase.payout_time,
+                    vote_basis_points_veto_threshold: voting_config
+                        .vote_basis_points_veto_threshold,
+                }),
+        })
+        .collect();
+
+    let mut actions: Vec<(i64, Action)> = vec![(campaign_end_time, Action::CampaignEnd)];
+    for summary in payout_phase_summaries.iter() {
+        if let Some(voting_window) = &summary.voting_window {
+            actions.push((
+                voting_window.voting_start_time,
+                Action::VotingStart {
+                    payout_phase_index: summary.index,
+                },
+            ));
+        }
+        actions.push((summary.payout_time, Action::PayOutFunds));
+        if let Some(stream_end_time) = summary.stream_end_time {
+            actions.push((stream_end_time, Action::PayOutFunds));
+        }
+        actions.push((
+            summary.refund_deadline,
+            Action::RefundDeadline {
+                payout_phase_index: summary.index,
+            },
+        ));
+    }
+    for event in input.events.iter() {
+        actions.push((event.get_time(), Action::Event(event)));
+    }
+    // Payouts stop at a vetoed payout phase, so the escrow may become
+    // closable after the refund_deadline of any payout phase.
+    for payout_phase in payout_phases.iter() {
+        actions.push((
+            payout_phase.refund_deadline
+                + MinBufferTimes::LAST_REFUND_DEADLINE_TO_CLOSE_ESCROW_IN_SECONDS,
+            Action::EscrowClosable,
+        ));
+    }
+    // The sort is stable, so events at the same time keep their input order.
+    actions.sort_by_key(|(time, action)| (*time, action.get_sort_order()));
+    // A stream may end at the next payout time, which only needs one payout.
+    actions.dedup_by(|(time, action), (previous_time, previous_action)| {
+        time == previous_time
+            && matches!(action, Action::PayOutFunds)
+            && matches!(previous_action, Action::PayOutFunds)
+    });
+
+    let mut simulation = Simulation {
+        campaign_escrow,
+        is_escrow_closable: false,
+        entries: vec![],
+    };
+
+    for (time, action) in actions {
+        match action {
+            Action::CampaignEnd => simulation.push_entry(time, TimelineEntryKind::CampaignEnd),
+            Action::VotingStart { payout_phase_index } => {
+                simulation.push_entry(time, TimelineEntryKind::VotingStart { payout_phase_index })
+            }
+            Action::Event(SimulationEvent::VetoPayoutPhase {
+                payout_phase_index, ..
+            }) => simulation.veto_payout_phase(time, *payout_phase_index),
+            Action::Event(SimulationEvent::Refund { deposit_amount, .. }) => {
+                simulation.refund(time, *deposit_amount)
+            }
+            Action::PayOutFunds => simulation.pay_out_funds(time),
+            Action::RefundDeadline { payout_phase_index } => simulation.push_entry(
+                time,
+                TimelineEntryKind::RefundDeadline { payout_phase_index },
+            ),
+            Action::EscrowClosable => simulation.record_escrow_closable(time),
+        }
+    }
+
+    Ok(Timeline {
+        campaign_end_time,
+        total_funds: input.total_funds,
+        payout_phases: payout_phase_summaries,
+        total_paid_out: simulation.campaign_escrow.treasury.total_paid_out,
+        total_crank_rewards: simulation.campaign_escrow.treasury.total_crank_rewards,
+        total_refunded: simulation.campaign_escrow.treasury.total_refunded,
+        remaining_funds: simulation.get_remaining_funds(),
+        entries: simulation.entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use campaign_treasury_manager::{
+        CrankReward, PayoutPhaseInput, PayoutStreamInput, StretchGoal, SurplusPolicy,
+        VotingConfigInput,
+    };
+
+    use super::*;
+
+    const DAY_IN_SECONDS: i64 = 24 * 60 * 60;
+    const CREATED_AT: i64 = 1_700_000_000;
+    const CAMPAIGN_END_TIME: i64 = CREATED_AT + 20 * DAY_IN_SECONDS;
+
+    fn days_after_campaign_end(days: i64) -> i64 {
+        CAMPAIGN_END_TIME + days * DAY_IN_SECONDS
+    }
+
+    fn get_simulation_input(total_funds: u64, events: Vec<SimulationEvent>) -> SimulationInput {
+        SimulationInput {
+            created_at: CREATED_AT,
+            campaign_escrow_input: CreateCampaignEscrowInput {
+                campaign_end_time: CAMPAIGN_END_TIME,
+                payout_phases: vec![
+                    PayoutPhaseInput {
+                        payout_basis_points: 3_000,
+                        payout_time: days_after_campaign_end(7),
+                        refund_deadline: days_after_campaign_end(37),
+                        description: String::from("Initial payout"),
+                        stretch_goal: None,
+                        stream: None,
+                        voting_config: None,
+                    },
+                    PayoutPhaseInput {
+                        payout_basis_points: 7_000,
+                        payout_time: days_after_campaign_end(37),
+                        refund_deadline: days_after_campaign_end(67),
+                        description: String::from("Final payout"),
+                        stretch_goal: None,
+                        stream: None,
+                        voting_config: Some(VotingConfigInput {
+                            voting_start_time: days_after_campaign_end(23),
+                            vote_basis_points_veto_threshold: 8_000,
+                        }),
+                    },
+                ],
+                surplus_policy: SurplusPolicy::Creator,
+                crank_reward: CrankReward::None,
+                collection_mint: Default::default(),
+                fundraising_start_time: None,
+                deposit_grace_period: 0,
+                reward_tiers: vec![],
+                hard_cap: None,
+                auto_end_at_hard_cap: false,
+            },
+            total_funds,
+            events,
+        }
+    }
+
+    fn get_event_names(timeline: &Timeline) -> Vec<&'static str> {
+        timeline
+            .entries
+            .iter()
+            .map(|entry| match entry.kind {
+                TimelineEntryKind::CampaignEnd => "campaign_end",
+                TimelineEntryKind::VotingStart { .. } => "voting_start",
+                TimelineEntryKind::PayoutPhaseVetoed { .. } => "payout_phase_vetoed",
+                TimelineEntryKind::Refund { .. } => "refund",
+                TimelineEntryKind::RefundRejected { .. } => "refund_rejected",
+                TimelineEntryKind::Payout { .. } => "payout",
+                TimelineEntryKind::PayoutFailed { .. } => "payout_failed",
+                TimelineEntryKind::RefundDeadline { .. } => "refund_deadline",
+                TimelineEntryKind::EscrowClosable => "escrow_closable",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_without_events() {
+        let timeline = simulate(&get_simulation_input(1_000_001, vec![])).unwrap();
+
+        assert_eq!(
+            get_event_names(&timeline),
+            vec![
+                "campaign_end",
+                "payout",
+                "voting_start",
+                "payout",
+                "refund_deadline",
+                "refund_deadline",
+                "escrow_closable",
+            ]
+        );
+        assert_eq!(timeline.payout_phases[0].payout_amount, 300_000);
+        assert_eq!(timeline.payout_phases[1].payout_amount, 700_000);
+        assert_eq!(timeline.total_paid_out, 1_000_000);
+        assert_eq!(timeline.remaining_funds, 1);
+        assert_eq!(
+            timeline.entries.last().unwrap().time,
+            days_after_campaign_end(70)
+        );
+
+        // The JSON output is deterministic.
+        let json = timeline.to_json_string();
+        assert_eq!(
+            json,
+            simulate(&get_simulation_input(1_000_001, vec![]))
+                .unwrap()
+                .to_json_string()
+        );
+        assert!(json.contains(
+            r#"{
+      "time": 1702332800,
+      "event": "payout",
+      "payout_phase_index": 0,
+      "amount": 300000,
+      "crank_reward": 0
+    }"#
+        ));
+    }
+
+    #[test]
+    fn test_simulate_veto_blocks_payouts() {
+        let timeline = simulate(&get_simulation_input(
+            1_000_000,
+            vec![SimulationEvent::VetoPayoutPhase {
+                time: days_after_campaign_end(1),
+                payout_phase_index: 0,
+            }],
+        ))
+        .unwrap();
+
+        // A vetoed phase can never be paid out, which blocks every later
+        // phase, so the escrow becomes closable once its refunds close.
+        assert_eq!(
+            get_event_names(&timeline),
+            vec![
+                "campaign_end",
+                "payout_phase_vetoed",
+                "payout_failed",
+                "voting_start",
+                "payout_failed",
+                "refund_deadline",
+                "escrow_closable",
+                "refund_deadline",
+            ]
+        );
+        assert_eq!(
+            timeline.entries[4].kind,
+            TimelineEntryKind::PayoutFailed {
+                payout_phase_index: 1,
+                reason: String::from(
+                    "Blocked by payout phase 0, which was vetoed by the authority."
+                ),
+            }
+        );
+        assert_eq!(timeline.entries[6].time, days_after_campaign_end(40));
+        assert_eq!(timeline.total_paid_out, 0);
+        assert_eq!(timeline.remaining_funds, 1_000_000);
+    }
+
+    #[test]
+    fn test_simulate_streaming_payout() {
+        let get_streaming_input = |events| {
+            let mut input = get_simulation_input(1_000_000, events);
+            input.campaign_escrow_input.payout_phases[0].stream = Some(PayoutStreamInput {
+                stream_end_time: days_after_campaign_end(37),
+            });
+            input
+        };
+
+        // Nothing has vested at the payout_time, so the stream is claimed in
+        // full when it ends, followed by the next payout phase.
+        let timeline = simulate(&get_streaming_input(vec![])).unwrap();
+        assert_eq!(
+            get_event_names(&timeline),
+            vec![
+                "campaign_end",
+                "voting_start",
+                "payout",
+                "payout",
+                "refund_deadline",
+                "refund_deadline",
+                "escrow_closable",
+            ]
+        );
+        assert_eq!(
+            timeline.payout_phases[0].stream_end_time,
+            Some(days_after_campaign_end(37))
+        );
+        assert_eq!(timeline.total_paid_out, 1_000_000);
+
+        // A veto halfway through the stream freezes the unvested half.
+        let timeline = simulate(&get_streaming_input(vec![
+            SimulationEvent::VetoPayoutPhase {
+                time: days_after_campaign_end(22),
+                payout_phase_index: 0,
+            },
+        ]))
+        .unwrap();
+        assert_eq!(
+            get_event_names(&timeline),
+            vec![
+                "campaign_end",
+                "payout_phase_vetoed",
+                "voting_start",
+                "payout",
+                "payout_failed",
+                "refund_deadline",
+                "escrow_closable",
+                "refund_deadline",
+            ]
+        );
+        assert_eq!(
+            timeline.entries[3].kind,
+            TimelineEntryKind::Payout {
+                payout_phase_index: 0,
+                amount: 150_000,
+                crank_reward: 0,
+            }
+        );
+        assert_eq!(timeline.total_paid_out, 150_000);
+        assert_eq!(timeline.remaining_funds, 850_000);
+    }
+
+    #[test]
+    fn test_simulate_refunds() {
+        // Deposits are refunded in full while nothing is paid out.
+        let timeline = simulate(&get_simulation_input(
+            1_000_000,
+            vec![
+                SimulationEvent::VetoPayoutPhase {
+                    time: days_after_campaign_end(1),
+                    payout_phase_index: 0,
+                },
+                SimulationEvent::Refund {
+                    time: days_after_campaign_end(8),
+                    deposit_amount: 400_000,
+                },
+            ],
+        ))
+        .unwrap();
+        assert!(timeline.entries.contains(&TimelineEntry {
+            time: days_after_campaign_end(8),
+            kind: TimelineEntryKind::Refund {
+                deposit_amount: 400_000,
+                amount: 400_000,
+            },
+        }));
+        assert_eq!(timeline.total_refunded, 400_000);
+        assert_eq!(timeline.remaining_funds, 600_000);
+
+        let timeline = simulate(&get_simulation_input(
+            1_000_000,
+            vec![
+                // The payout phase is not vetoed.
+                SimulationEvent::Refund {
+                    time: days_after_campaign_end(8),
+                    deposit_amount: 100,
+                },
+                SimulationEvent::VetoPayoutPhase {
+                    time: days_after_campaign_end(10),
+                    payout_phase_index: 1,
+                },
+                // Before the refund window of the vetoed payout phase opens.
+                SimulationEvent::Refund {
+                    time: days_after_campaign_end(36),
+                    deposit_amount: 100,
+                },
+                SimulationEvent::Refund {
+                    time: days_after_campaign_end(38),
+                    deposit_amount: 500_000,
+                },
+                // Only 500_000 of deposits remain to be refunded.
+                SimulationEvent::Refund {
+                    time: days_after_campaign_end(39),
+                    deposit_amount: 600_000,
+                },
+            ],
+        ))
+        .unwrap();
+
+        let refunds: Vec<&TimelineEntryKind> = timeline
+            .entries
+            .iter()
+            .map(|entry| &entry.kind)
+            .filter(|kind| {
+                matches!(
+                    kind,
+                    TimelineEntryKind::Refund { .. } | TimelineEntryKind::RefundRejected { .. }
+                )
+            })
+            .collect();
+        assert!(matches!(
+            refunds[0],
+            TimelineEntryKind::RefundRejected {
+                deposit_amount: 100,
+                ..
+            }
+        ));
+        assert!(matches!(
+            refunds[1],
+            TimelineEntryKind::RefundRejected {
+                deposit_amount: 100,
+                ..
+            }
+        ));
+        // The initial payout was paid out, so the deposit is refunded its
+        // share of the remaining funds.
+        assert_eq!(
+            refunds[2],
+            &TimelineEntryKind::Refund {
+                deposit_amount: 500_000,
+                amount: 350_000,
+            }
+        );
+        assert_eq!(
+            refunds[3],
+            &TimelineEntryKind::RefundRejected {
+                deposit_amount: 600_000,
+                reason: String::from(
+                    "Deposit exceeds the 500000 deposited by supporters who were not refunded yet."
+                ),
+            }
+        );
+        assert_eq!(timeline.total_paid_out, 300_000);
+        assert_eq!(timeline.total_refunded, 350_000);
+        assert_eq!(timeline.remaining_funds, 350_000);
+        assert_eq!(get_event_names(&timeline).last(), Some(&"escrow_closable"));
+    }
+
+    #[test]
+    fn test_simulate_crank_rewards() {
+        let mut input = get_simulation_input(1_000_000, vec![]);
+        input.campaign_escrow_input.crank_reward = CrankReward::BasisPoints { basis_points: 100 };
+        let timeline = simulate(&input).unwrap();
+
+        assert_eq!(
+            timeline.entries[1].kind,
+            TimelineEntryKind::Payout {
+                payout_phase_index: 0,
+                amount: 300_000,
+                crank_reward: 3_000,
+            }
+        );
+        assert_eq!(timeline.total_paid_out, 1_000_000);
+        assert_eq!(timeline.total_crank_rewards, 10_000);
+        assert_eq!(timeline.remaining_funds, 0);
+    }
+
+    #[test]
+    fn test_simulate_stretch_goals() {
+        let get_stretch_goal_input = |total_funds| {
+            let mut input = get_simulation_input(total_funds, vec![]);
+            let payout_phases = &mut input.campaign_escrow_input.payout_phases;
+            payout_phases[1].stretch_goal = Some(StretchGoal {
+                raise_threshold: 2_000_000,
+                is_active_when_reached: false,
+            });
+            payout_phases.push(PayoutPhaseInput {
+                payout_basis_points: 7_000,
+                payout_time: days_after_campaign_end(67),
+                refund_deadline: days_after_campaign_end(97),
+                description: String::from("Stretch goal payout"),
+                stretch_goal: Some(StretchGoal {
+                    raise_threshold: 2_000_000,
+                    is_active_when_reached: true,
+                }),
+                stream: None,
+                voting_config: None,
+            });
+            input
+        };
+
+        let timeline = simulate(&get_stretch_goal_input(1_000_000)).unwrap();
+        let payout_phase_indexes: Vec<usize> = timeline
+            .payout_phases
+            .iter()
+            .map(|payout_phase| payout_phase.index)
+            .collect();
+        assert_eq!(payout_phase_indexes, vec![0, 1]);
+        assert_eq!(timeline.total_paid_out, 1_000_000);
+
+        let timeline = simulate(&get_stretch_goal_input(2_000_000)).unwrap();
+        let payout_phase_indexes: Vec<usize> = timeline
+            .payout_phases
+            .iter()
+            .map(|payout_phase| payout_phase.index)
+            .collect();
+        assert_eq!(payout_phase_indexes, vec![0, 2]);
+        assert_eq!(
+            get_event_names(&timeline),
+            vec![
+                "campaign_end",
+                "payout",
+                "refund_deadline",
+                "payout",
+                "refund_deadline",
+                "escrow_closable",
+            ]
+        );
+        assert_eq!(
+            timeline.entries[3].kind,
+            TimelineEntryKind::Payout {
+                payout_phase_index: 2,
+                amount: 1_400_000,
+                crank_reward: 0,
+            }
+        );
+        assert_eq!(timeline.total_paid_out, 2_000_000);
+    }
+
+    #[test]
+    fn test_simulate_invalid_input() {
+        let mut input = get_simulation_input(1_000_000, vec![]);
+        input.campaign_escrow_input.payout_phases[1].payout_basis_points = 6_000;
+        assert!(matches!(
+            simulate(&input),
+            Err(SimulationError::InvalidPayoutPhases(_))
+        ));
+
+        let mut input = get_simulation_input(1_000_000, vec![]);
+        input.created_at = CAMPAIGN_END_TIME - DAY_IN_SECONDS;
+        assert!(matches!(
+            simulate(&input),
+            Err(SimulationError::InvalidCampaignEndTime(_))
+        ));
+    }
+}
@@ -0,0 +1,223 @@
+// Simulation output: a summary of each payout phase plus the ordered list of
+// timeline entries, with a deterministic JSON representation.
+
+use crate::Json;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VotingWindow {
+    pub voting_start_time: i64,
+    // Voting closes at the payout time.
+    pub voting_end_time: i64,
+    pub vote_basis_points_veto_threshold: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayoutPhaseSummary {
+    pub index: usize,
+    pub description: String,
+    pub payout_basis_points: u16,
+    // Amount this phase pays out at the simulated funding amount.
+    pub payout_amount: u64,
+    pub payout_time: i64,
+    // Refunds for this phase can be claimed from the payout time until the
+    // refund deadline.
+    pub refund_deadline: i64,
+    // Set if the payout vests linearly from the payout time until this time.
+    pub stream_end_time: Option<i64>,
+    pub voting_window: Option<VotingWindow>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimelineEntryKind {
+    CampaignEnd,
+    VotingStart {
+        payout_phase_index: usize,
+    },
+    PayoutPhaseVetoed {
+        payout_phase_index: u8,
+    },
+    Refund {
+        deposit_amount: u64,
+        amount: u64,
+    },
+    RefundRejected {
+        deposit_amount: u64,
+        reason: String,
+    },
+    // The crank reward is deducted from the amount paid out.
+    Payout {
+        payout_phase_index: usize,
+        amount: u64,
+        crank_reward: u64,
+    },
+    PayoutFailed {
+        payout_phase_index: usize,
+        reason: String,
+    },
+    RefundDeadline {
+        payout_phase_index: usize,
+    },
+    EscrowClosable,
+}
+
+impl TimelineEntryKind {
+    fn get_name(&self) -> &'static str {
+        match self {
+            TimelineEntryKind::CampaignEnd => "campaign_end",
+            TimelineEntryKind::VotingStart { .. } => "voting_start",
+            TimelineEntryKind::PayoutPhaseVetoed { .. } => "payout_phase_vetoed",
+            TimelineEntryKind::Refund { .. } => "refund",
+            TimelineEntryKind::RefundRejected { .. } => "refund_rejected",
+            TimelineEntryKind::Payout { .. } => "payout",
+            TimelineEntryKind::PayoutFailed { .. } => "payout_failed",
+            TimelineEntryKind::RefundDeadline { .. } => "refund_deadline",
+            TimelineEntryKind::EscrowClosable => "escrow_closable",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimelineEntry {
+    pub time: i64,
+    pub kind: TimelineEntryKind,
+}
+
+impl TimelineEntry {
+    pub fn to_json(&self) -> Json {
+        let mut fields = vec![
+            ("time", self.time.into()),
+            ("event", self.kind.get_name().into()),
+        ];
+
+        match &self.kind {
+            TimelineEntryKind::CampaignEnd | TimelineEntryKind::EscrowClosable => {}
+            TimelineEntryKind::VotingStart { payout_phase_index }
+            | TimelineEntryKind::RefundDeadline { payout_phase_index } => {
+                fields.push(("payout_phase_index", (*payout_phase_index).into()));
+            }
+            TimelineEntryKind::PayoutPhaseVetoed { payout_phase_index } => {
+                fields.push(("payout_phase_index", (*payout_phase_index).into()));
+            }
+            TimelineEntryKind::Refund {
+                deposit_amount,
+                amount,
+            } => {
+                fields.push(("deposit_amount", (*deposit_amount).into()));
+                fields.push(("amount", (*amount).into()));
+            }
+            TimelineEntryKind::RefundRejected {
+                deposit_amount,
+                reason,
+            } => {
+                fields.push(("deposit_amount", (*deposit_amount).into()));
+                fields.push(("reason", reason.as_str().into()));
+            }
+            TimelineEntryKind::Payout {
+                payout_phase_index,
+                amount,
+                crank_reward,
+            } => {
+                fields.push(("payout_phase_index", (*payout_phase_index).into()));
+                fields.push(("amount", (*amount).into()));
+                fields.push(("crank_reward", (*crank_reward).into()));
+            }
+            TimelineEntryKind::PayoutFailed {
+                payout_phase_index,
+                reason,
+            } => {
+                fields.push(("payout_phase_index", (*payout_phase_index).into()));
+                fields.push(("reason", reason.as_str().into()));
+            }
+        }
+
+        Json::Object(fields)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timeline {
+    pub campaign_end_time: i64,
+    pub total_funds: u64,
+    pub payout_phases: Vec<PayoutPhaseSummary>,
+    pub entries: Vec<TimelineEntry>,
+    // Crank rewards are included in the total_paid_out.
+    pub total_paid_out: u64,
+    pub total_crank_rewards: u64,
+    pub total_refunded: u64,
+    // Funds left in the treasury escrow at the end of the simulation.
+    pub remaining_funds: u64,
+}
+
+impl Timeline {
+    pub fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("campaign_end_time", self.campaign_end_time.into()),
+            ("total_funds", self.total_funds.into()),
+            (
+                "payout_phases",
+                Json::Array(
+                    self.payout_phases
+                        .iter()
+                        .map(|payout_phase| {
+                            Json::Object(vec![
+                                ("index", payout_phase.index.into()),
+                                ("description", payout_phase.description.as_str().into()),
+                                (
+                                    "payout_basis_points",
+                                    payout_phase.payout_basis_points.into(),
+                                ),
+                                ("payout_amount", payout_phase.payout_amount.into()),
+                                ("payout_time", payout_phase.payout_time.into()),
+                                ("refund_deadline", payout_phase.refund_deadline.into()),
+                                (
+                                    "stream_end_time",
+                                    payout_phase
+                                        .stream_end_time
+                                        .map_or(Json::Null, |stream_end_time| {
+                                            stream_end_time.into()
+                                        }),
+                                ),
+                                (
+                                    "voting_window",
+                                    payout_phase.voting_window.as_ref().map_or(
+                                        Json::Null,
+                                        |voting_window| {
+                                            Json::Object(vec![
+                                                (
+                                                    "voting_start_time",
+                                                    voting_window.voting_start_time.into(),
+                                                ),
+                                                (
+                                                    "voting_end_time",
+                                                    voting_window.voting_end_time.into(),
+                                                ),
+                                                (
+                                                    "vote_basis_points_veto_threshold",
+                                                    voting_window
+                                                        .vote_basis_points_veto_threshold
+                                                        .into(),
+                                                ),
+                                            ])
+                                        },
+                                    ),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "timeline",
+                Json::Array(self.entries.iter().map(TimelineEntry::to_json).collect()),
+            ),
+            ("total_paid_out", self.total_paid_out.into()),
+            ("total_crank_rewards", self.total_crank_rewards.into()),
+            ("total_refunded", self.total_refunded.into()),
+            ("remaining_funds", self.remaining_funds.into()),
+        ])
+    }
+
+    pub fn to_json_string(&self) -> String {
+        self.to_json().to_string_pretty()
+    }
+}
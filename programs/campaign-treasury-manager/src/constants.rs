@@ -26,3 +26,8 @@ pub const FORMFN_CANDY_MACHINE_PROGRAM_ID: Pubkey =
 pub const PROGRAM_PREFIX: &str = "campaign_treasury_manager";
 
 pub const ONE_HUNDRED_PERCENT_BASIS_POINTS: u16 = 10_000;
+
+// Maximum number of payout phases a CampaignEscrow may hold. CreateEscrow and
+// UpdateEscrow size the account to the actual number of phases, so this only
+// bounds the account size.
+pub const MAX_PAYOUT_PHASES: usize = 24;
@@ -22,12 +22,8 @@ pub enum CampaignTreasuryManagerError {
     InvalidCloseEscrow,
     #[msg("Invalid treasury escrow account provided")]
     InvalidTreasuryEscrow,
-    #[msg("UpdateEscrow input must include both the treasury_escrow_owner and treasury_mint if updating the escrow treasury")]
-    InvalidTreasuryEscrowUpdate,
     #[msg("Invalid campaign_end_time provided")]
     InvalidCampaignEndTime,
-    #[msg("Deposit escrow mint cannot be the native mint")]
-    InvalidDepositEscrowMint,
     #[msg("Invalid deposit escrow account provided")]
     InvalidDepositEscrowAccount,
     #[msg("Invalid CPI invocation")]
@@ -38,4 +34,52 @@ pub enum CampaignTreasuryManagerError {
     InvalidPayoutRequest,
     #[msg("Deposit escrow account must be empty before it can be closed")]
     DepositEscrowAccountNotEmpty,
+    #[msg("Too many payout phases provided")]
+    TooManyPayoutPhases,
+    #[msg("Treasury paid out and refunded totals exceed the total funds")]
+    InvalidTreasuryAccounting,
+    #[msg("Surplus receiver does not match the CampaignEscrow surplus policy")]
+    InvalidSurplusReceiver,
+    #[msg("Invalid DepositRecord account provided")]
+    InvalidDepositRecord,
+    #[msg("Invalid crank reward provided")]
+    InvalidCrankReward,
+    #[msg("NFT is not a verified member of the campaign collection")]
+    InvalidNftCollection,
+    #[msg("Signer does not hold the DepositRecord NFT")]
+    InvalidNftHolder,
+    #[msg("Invalid vote")]
+    InvalidVote,
+    #[msg("Too many DepositEscrow currencies for the CampaignEscrow")]
+    TooManyDepositEscrows,
+    #[msg("Deposits in this currency have been disabled")]
+    DepositEscrowDisabled,
+    #[msg("Invalid price oracle account provided")]
+    InvalidPriceOracle,
+    #[msg("Invalid donation")]
+    InvalidDonation,
+    #[msg("Invalid fundraising window settings")]
+    InvalidFundraisingWindow,
+    #[msg("Deposits and donations are only accepted during the fundraising window")]
+    OutsideFundraisingWindow,
+    #[msg("Invalid reward tiers")]
+    InvalidRewardTiers,
+    #[msg("Deposit does not qualify for the claimed reward tier")]
+    InvalidRewardTier,
+    #[msg("Reward tier is sold out")]
+    RewardTierSoldOut,
+    #[msg("Invalid hard cap")]
+    InvalidHardCap,
+    #[msg("Campaign hard cap exceeded")]
+    HardCapExceeded,
+    #[msg("Invalid matching pool")]
+    InvalidMatchingPool,
+    #[msg("Cannot close the matching pool yet")]
+    InvalidCloseMatchingPool,
+    #[msg("Invalid streamed payout claim")]
+    InvalidStreamedPayoutClaim,
+    #[msg("Invalid refund request")]
+    InvalidRefundRequest,
+    #[msg("Cannot process the deposit")]
+    InvalidProcessDeposit,
 }
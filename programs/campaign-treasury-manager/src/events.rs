@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+// Emitted by the PayOutFunds and PayOutAllDueFunds ixs for every payout phase
+// which is paid out. The paid_amount includes the crank_reward_amount.
+#[event]
+pub struct PayoutPhasePaidOut {
+    pub campaign_escrow: Pubkey,
+    pub payout_phase_index: u8,
+    pub paid_amount: u64,
+    pub crank_reward_amount: u64,
+}
+
+// Emitted by the ClaimStreamedPayout ix. The paid_amount is the total claimed
+// from the payout phase so far.
+#[event]
+pub struct StreamedPayoutClaimed {
+    pub campaign_escrow: Pubkey,
+    pub payout_phase_index: u8,
+    pub claimed_amount: u64,
+    pub paid_amount: u64,
+}
+
+// Emitted by the CreateDeposit ix for deposits in the treasury currency, and
+// by the ProcessDeposit ix for deposits in other currencies, once the deposit
+// is added to the treasury funds.
+#[event]
+pub struct DepositProcessed {
+    pub campaign_escrow: Pubkey,
+    pub deposit_record: Pubkey,
+    pub depositor: Pubkey,
+    pub processed_deposit_amount: u64,
+}
+
+// Emitted by the ProcessFullRefund, ProcessPartialRefund and
+// ProcessDonationRefund ixs. The record is the refunded DepositRecord or
+// DonationRecord.
+#[event]
+pub struct RefundProcessed {
+    pub campaign_escrow: Pubkey,
+    pub record: Pubkey,
+    pub refund_receiver: Pubkey,
+    pub refund_amount: u64,
+}
+
+// Emitted by the VoteToVetoPayoutPhase and VoteToVetoPayoutPhaseWithDonation
+// ixs. The record is the DepositRecord or DonationRecord the votes are
+// weighted by.
+#[event]
+pub struct VetoVotesCast {
+    pub campaign_escrow: Pubkey,
+    pub payout_phase_index: u8,
+    pub record: Pubkey,
+    pub voter: Pubkey,
+    pub veto_votes: u64,
+}
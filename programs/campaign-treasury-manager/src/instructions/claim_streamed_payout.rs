@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::constants::PROGRAM_PREFIX;
+use crate::{
+    transfer_from_treasury_escrow, CampaignEscrow, CampaignTreasuryManagerError,
+    StreamedPayoutClaimed, TreasuryEscrow,
+};
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct ClaimStreamedPayout<'info> {
+    #[account(
+        mut,
+        has_one = payout_wallet,
+        constraint = campaign_escrow.treasury.treasury_escrow.key() == treasury_escrow.key(),
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    /// CHECK: Validated in instruction.
+    #[account(
+        mut,
+        seeds = [
+            PROGRAM_PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            TreasuryEscrow::PREFIX.as_bytes()
+        ],
+        bump = campaign_escrow.treasury.bump,
+    )]
+    treasury_escrow: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    /// CHECK: Validated by the has_one constraint.
+    #[account(mut)]
+    payout_wallet: UncheckedAccount<'info>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+// Claims are always transferred to the payout_wallet, which may be a token
+// account, so anyone can submit them. No crank reward is paid for claims.
+pub fn handle_claim_streamed_payout(ctx: Context<ClaimStreamedPayout>) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let payout_wallet = &ctx.accounts.payout_wallet;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    // Streams begin after the fundraising window, so the raise is final.
+    campaign_escrow.settle_stretch_goals_if_needed();
+
+    let clock = Clock::get()?;
+    let total_votes = campaign_escrow.get_total_votes();
+    let (payout_index, payout_phase) = campaign_escrow
+        .payout_phases
+        .get_streamed_payout_phase_for_claim(clock.unix_timestamp)?;
+
+    let payout_amount = campaign_escrow
+        .calculate_payout_amount_from_payout_basis_points(payout_phase.payout_basis_points);
+    let claim_amount = payout_phase.calculate_claimable_stream_amount(
+        payout_amount,
+        clock.unix_timestamp,
+        total_votes,
+    );
+    if claim_amount == 0 {
+        msg!(
+            "Nothing has vested for payout phase with index {} since the last claim.",
+            payout_index
+        );
+        return Err(CampaignTreasuryManagerError::InvalidStreamedPayoutClaim.into());
+    }
+
+    transfer_from_treasury_escrow(
+        campaign_escrow,
+        treasury_escrow.to_account_info(),
+        payout_wallet.to_account_info(),
+        token_program,
+        system_program,
+        claim_amount,
+    )?;
+
+    campaign_escrow.record_streamed_payout(payout_index, claim_amount, payout_amount)?;
+
+    let paid_amount = campaign_escrow
+        .payout_phases
+        .get(payout_index)
+        .unwrap()
+        .paid_amount;
+    emit!(StreamedPayoutClaimed {
+        campaign_escrow: campaign_escrow.key(),
+        payout_phase_index: payout_index as u8,
+        claimed_amount: claim_amount,
+        paid_amount,
+    });
+    msg!(
+        "Claimed {} of {} tokens streamed by payout phase with index {} to payout_wallet {}.",
+        claim_amount,
+        payout_amount,
+        payout_index,
+        payout_wallet.key(),
+    );
+
+    Ok(())
+}
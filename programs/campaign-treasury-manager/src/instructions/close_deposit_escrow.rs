@@ -10,6 +10,7 @@ use crate::{
 #[instruction()]
 pub struct CloseDepositEscrow<'info> {
     #[account(
+        mut,
         has_one = authority,
         seeds = [
             CampaignEscrow::PREFIX.as_bytes(),
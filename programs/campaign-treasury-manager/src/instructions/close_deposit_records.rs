@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    assert_keys_equal, constants::PROGRAM_PREFIX, transfer_from_treasury_escrow, CampaignEscrow,
+    CampaignTreasuryManagerError, DepositRecord, TreasuryEscrow,
+};
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct CloseDepositRecords<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    /// CHECK: Validated in instruction.
+    #[account(
+        mut,
+        seeds = [
+            PROGRAM_PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            TreasuryEscrow::PREFIX.as_bytes()
+        ],
+        bump = campaign_escrow.treasury.bump,
+    )]
+    treasury_escrow: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    // The remaining_accounts are groups of a DepositRecord, its depositor,
+    // the token account holding the DepositRecord NFT and the NFT holder's
+    // payment account, see get_refund_receiver. The DepositRecord rent is
+    // transferred to the depositor, and its share of the supporter surplus
+    // to the NFT holder, like refunds. All but the token account are
+    // writable. The NFT accounts are only checked if there is surplus to pay.
+}
+
+pub fn handle_close_deposit_records<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseDepositRecords<'info>>,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let remaining_accounts = ctx.remaining_accounts;
+
+    // DepositRecords mark eligible refunds, so they can only be closed once
+    // refunds are no longer possible.
+    campaign_escrow.assert_all_payouts_are_complete()?;
+
+    let account_chunks = remaining_accounts.chunks_exact(4);
+    if !account_chunks.remainder().is_empty() {
+        msg!(
+            "Expected groups of 4 DepositRecord, depositor and NFT holder accounts, received {} accounts.",
+            remaining_accounts.len()
+        );
+        return Err(CampaignTreasuryManagerError::InvalidDepositRecord.into());
+    }
+
+    let mut closed_count: u64 = 0;
+    let mut total_supporter_surplus_amount: u64 = 0;
+    for accounts in account_chunks {
+        let deposit_record_info = &accounts[0];
+        let depositor = &accounts[1];
+        let nft_token_account_info = &accounts[2];
+        let supporter_surplus_receiver = &accounts[3];
+
+        // Already closed accounts are skipped, so that the same batch can be
+        // retried or overlap with another one.
+        if deposit_record_info.owner == &System::id() && deposit_record_info.data_is_empty() {
+            msg!(
+                "DepositRecord {} is already closed, skipping.",
+                deposit_record_info.key()
+            );
+            continue;
+        }
+
+        let deposit_record: Account<DepositRecord> = Account::try_from(deposit_record_info)?;
+
+        deposit_record.assert_pda(&deposit_record_info.key())?;
+        assert_keys_equal(
+            &deposit_record.depositor,
+            &depositor.key(),
+            Some(CampaignTreasuryManagerError::InvalidDepositRecord),
+        )?;
+        deposit_record.assert_belongs_to(&campaign_escrow.key())?;
+
+        let supporter_surplus_amount = campaign_escrow
+            .calculate_supporter_surplus_share(deposit_record.processed_deposit_amount);
+        if supporter_surplus_amount > 0 {
+            let nft_token_account: Account<TokenAccount> =
+                Account::try_from(nft_token_account_info)?;
+            deposit_record.assert_is_held_by(&nft_token_account.owner, &nft_token_account)?;
+            assert_keys_equal(
+                &campaign_escrow.get_refund_receiver(&nft_token_account.owner),
+                &supporter_surplus_receiver.key(),
+                Some(CampaignTreasuryManagerError::InvalidDepositRecord),
+            )?;
+            transfer_from_treasury_escrow(
+                campaign_escrow,
+                treasury_escrow.to_account_info(),
+                supporter_surplus_receiver.to_account_info(),
+                token_program,
+                system_program,
+                supporter_surplus_amount,
+            )?;
+        }
+
+        campaign_escrow.record_closed_deposit_record(
+            deposit_record.processed_deposit_amount,
+            supporter_surplus_amount,
+        );
+        deposit_record.close(depositor.to_account_info())?;
+        closed_count += 1;
+        total_supporter_surplus_amount += supporter_surplus_amount;
+    }
+
+    msg!(
+        "Closed {} DepositRecord accounts for CampaignEscrow with uuid {} and distributed {} supporter surplus.",
+        closed_count,
+        campaign_escrow.campaign_uuid,
+        total_supporter_surplus_amount
+    );
+
+    Ok(())
+}
@@ -2,8 +2,9 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
 use crate::{
-    assert_valid_escrow_management_signers, close_system_account, close_token_account,
-    constants::PROGRAM_PREFIX, is_native_mint, CampaignEscrow, TreasuryEscrow,
+    assert_keys_equal, assert_valid_escrow_management_signers, close_system_account,
+    close_token_account, constants::PROGRAM_PREFIX, is_native_mint, transfer_from_treasury_escrow,
+    CampaignEscrow, CampaignTreasuryManagerError, TreasuryEscrow,
 };
 
 #[derive(Accounts)]
@@ -32,6 +33,10 @@ pub struct CloseEscrow<'info> {
         bump = campaign_escrow.treasury.bump,
     )]
     treasury_escrow: UncheckedAccount<'info>,
+    /// CHECK: Validated in instruction against CampaignEscrow::get_remainder_receiver.
+    /// This account is not used if the treasury holds no remaining funds.
+    #[account(mut)]
+    remainder_receiver: UncheckedAccount<'info>,
     #[account(mut)]
     receiver: SystemAccount<'info>,
     #[account(mut)]
@@ -59,6 +64,34 @@ pub fn handle_close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
     let treasury_mint = campaign_escrow.treasury.treasury_mint;
     let treasury_escrow = &ctx.accounts.treasury_escrow;
 
+    // Rounding dust from payouts and refunds which were not claimed before the
+    // refund_deadline are still accounted for, and are sent to the remainder
+    // receiver before the treasury escrow is closed.
+    let remainder_amount = campaign_escrow.get_accounted_treasury_funds();
+    if remainder_amount > 0 {
+        let remainder_receiver = &ctx.accounts.remainder_receiver;
+        assert_keys_equal(
+            &remainder_receiver.key(),
+            &campaign_escrow.get_remainder_receiver(),
+            Some(CampaignTreasuryManagerError::InvalidSurplusReceiver),
+        )?;
+
+        transfer_from_treasury_escrow(
+            campaign_escrow,
+            treasury_escrow.to_account_info(),
+            remainder_receiver.to_account_info(),
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            remainder_amount,
+        )?;
+
+        msg!(
+            "Transferred the remaining {} treasury funds to {}.",
+            remainder_amount,
+            remainder_receiver.key()
+        );
+    }
+
     if is_native_mint(&treasury_mint) {
         let escrow_signer_seeds = TreasuryEscrow::get_seeds(
             &campaign_escrow.campaign_uuid,
@@ -75,10 +108,13 @@ pub fn handle_close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
         let ref_data = treasury_escrow.try_borrow_data()?;
         let mut account_data: &[u8] = &ref_data;
         let token_account = TokenAccount::try_deserialize(&mut account_data)?;
-        assert_eq!(
-            token_account.amount, 0,
-            "TreasuryEscrow must have zero tokens if it is a token account."
-        );
+        if token_account.amount != 0 {
+            msg!(
+                "TreasuryEscrow token account still holds {} tokens, reconcile the treasury before closing it.",
+                token_account.amount
+            );
+            return Err(CampaignTreasuryManagerError::InvalidCloseEscrow.into());
+        }
         // Need to drop the mutable account borrow before trying to close the account below.
         drop(ref_data);
 
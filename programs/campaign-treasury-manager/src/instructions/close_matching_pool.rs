@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    assert_keys_equal, close_system_account, close_token_account, is_native_mint,
+    transfer_from_matching_pool_escrow, CampaignEscrow, CampaignTreasuryManagerError, MatchingPool,
+    MatchingPoolEscrow,
+};
+
+#[derive(Accounts)]
+pub struct CloseMatchingPool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account(
+        mut,
+        has_one = sponsor,
+        has_one = campaign_escrow,
+        seeds = [
+            MatchingPool::PREFIX.as_bytes(),
+            campaign_escrow.key().as_ref(),
+        ],
+        bump = matching_pool.bump,
+        close = sponsor,
+    )]
+    matching_pool: Account<'info, MatchingPool>,
+    /// CHECK: Validated by the seeds constraint.
+    /// This account is closed manually in the ix handler.
+    #[account(
+        mut,
+        seeds = [
+            MatchingPoolEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = matching_pool.escrow_bump,
+    )]
+    matching_pool_escrow: UncheckedAccount<'info>,
+    #[account(mut)]
+    sponsor: Signer<'info>,
+    /// CHECK: Validated in instruction as the sponsor payment account.
+    #[account(mut)]
+    sponsor_payment_account: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    token_program: Program<'info, Token>,
+}
+
+// Deposits are no longer matched once the deposit window closes, so the unused
+// match is returned to the sponsor rather than being left for supporters.
+pub fn handle_close_matching_pool(ctx: Context<CloseMatchingPool>) -> Result<()> {
+    let campaign_escrow = &ctx.accounts.campaign_escrow;
+    let matching_pool = &ctx.accounts.matching_pool;
+    let matching_pool_escrow = &ctx.accounts.matching_pool_escrow;
+    let sponsor = &ctx.accounts.sponsor;
+    let sponsor_payment_account = &ctx.accounts.sponsor_payment_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    let clock = Clock::get()?;
+    let deposit_window_end_time = campaign_escrow
+        .campaign_end_time
+        .checked_add(campaign_escrow.deposit_grace_period)
+        .unwrap();
+    if clock.unix_timestamp < deposit_window_end_time {
+        msg!(
+            "MatchingPool can only be closed after deposits close at {}, current time = {}.",
+            deposit_window_end_time,
+            clock.unix_timestamp
+        );
+        return Err(CampaignTreasuryManagerError::InvalidCloseMatchingPool.into());
+    }
+
+    let unused_amount = matching_pool.get_unused_amount();
+
+    if is_native_mint(&campaign_escrow.treasury.treasury_mint) {
+        // The escrow holds the unused funds and its rent, which the sponsor
+        // funded, so all of it is returned.
+        let escrow_signer_seeds = MatchingPoolEscrow::get_seeds(
+            &campaign_escrow.campaign_uuid,
+            &matching_pool.escrow_bump,
+        );
+
+        close_system_account(
+            matching_pool_escrow.to_account_info(),
+            sponsor.to_account_info(),
+            system_program.to_account_info(),
+            &escrow_signer_seeds,
+        )?;
+    } else {
+        assert_keys_equal(
+            &sponsor_payment_account.key(),
+            &campaign_escrow.get_refund_receiver(&sponsor.key()),
+            Some(CampaignTreasuryManagerError::InvalidMatchingPool),
+        )?;
+
+        // Tokens sent to the escrow directly are returned along with the
+        // unused funds, since the token account must be empty to close it.
+        let ref_data = matching_pool_escrow.try_borrow_data()?;
+        let mut account_data: &[u8] = &ref_data;
+        let token_account = TokenAccount::try_deserialize(&mut account_data)?;
+        let tokens = token_account.amount;
+        // Need to drop the account borrow before transferring from the account below.
+        drop(ref_data);
+
+        if tokens > 0 {
+            transfer_from_matching_pool_escrow(
+                campaign_escrow,
+                matching_pool_escrow.to_account_info(),
+                matching_pool.escrow_bump,
+                sponsor_payment_account.to_account_info(),
+                token_program,
+                system_program,
+                tokens,
+            )?;
+        }
+
+        let campaign_escrow_seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            &[campaign_escrow.bump],
+        ];
+
+        close_token_account(
+            matching_pool_escrow.to_account_info(),
+            sponsor.to_account_info(),
+            campaign_escrow.to_account_info(),
+            token_program.to_account_info(),
+            &campaign_escrow_seeds,
+        )?;
+    }
+
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    campaign_escrow.has_matching_pool = false;
+
+    msg!(
+        "Closed MatchingPool for CampaignEscrow with campaign_uuid {} and returned the unused match of {} to the sponsor {}.",
+        campaign_escrow.campaign_uuid,
+        unused_amount,
+        sponsor.key()
+    );
+
+    Ok(())
+}
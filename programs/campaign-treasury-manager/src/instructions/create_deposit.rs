@@ -3,8 +3,10 @@ use anchor_spl::token::{Mint, Token};
 use solana_program::sysvar;
 
 use crate::{
-    assert_keys_equal, cmp_pubkeys, transfer_funds, validate_cpi_invocation, CampaignEscrow,
-    DepositEscrow, DepositRecord, ID,
+    assert_keys_equal, assert_nft_is_in_collection, cmp_pubkeys, estimate_deposit_value,
+    transfer_from_matching_pool_escrow, transfer_funds, validate_cpi_invocation, CampaignEscrow,
+    CampaignTreasuryManagerError, DepositEscrow, DepositProcessed, DepositRecord, MatchingPool,
+    MatchingPoolEscrow, ID,
 };
 
 #[derive(Accounts)]
@@ -44,20 +46,35 @@ pub struct CreateDeposit<'info> {
     #[account(mut)]
     depositor_payment_account: UncheckedAccount<'info>,
     mint: Account<'info, Mint>,
+    /// CHECK: Validated in instruction against the mint and the CampaignEscrow
+    /// collection_mint.
+    metadata: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     token_program: Program<'info, Token>,
     /// CHECK: Validated by the address constraint.
     #[account(address = sysvar::instructions::id())]
     instruction_sysvar_account: UncheckedAccount<'info>,
+    // Remaining accounts: if the CampaignEscrow has a MatchingPool, the
+    // MatchingPool, its matching_pool_escrow and the treasury_escrow. Then,
+    // for deposits in a DepositEscrow currency with a price oracle, the price
+    // oracle followed by the treasury mint.
 }
 
+// The number of remaining accounts used for matching deposits.
+const MATCHING_POOL_ACCOUNTS_LEN: usize = 3;
+
 // TODO[@bonham000]: Add CPI validation. This instruction should only be callable by CPI
 // from our other programs.
-pub fn handle_create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -> Result<()> {
+pub fn handle_create_deposit<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateDeposit<'info>>,
+    deposit_amount: u64,
+    reward_tier_index: Option<u8>,
+) -> Result<()> {
     let campaign_escrow = &mut ctx.accounts.campaign_escrow;
     let token_program = &ctx.accounts.token_program;
     let system_program = &ctx.accounts.system_program;
     let mint = &ctx.accounts.mint;
+    let metadata = &ctx.accounts.metadata;
     let depositor = &ctx.accounts.depositor;
     let deposit_escrow = &ctx.accounts.deposit_escrow;
     let deposit_record = &mut ctx.accounts.deposit_record;
@@ -68,11 +85,31 @@ pub fn handle_create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -
     // This instruction may only be called via CPI from other formfn programs.
     validate_cpi_invocation(instruction_sysvar_account)?;
 
+    // Deposits from auctions which settle shortly after the campaign_end_time
+    // are accepted during the deposit_grace_period.
+    let clock = Clock::get()?;
+    campaign_escrow.assert_is_in_fundraising_window(clock.unix_timestamp, true)?;
+
+    // Refund and vote rights attach to the DepositRecord, so it may only be
+    // created for genuine campaign NFTs.
+    assert_nft_is_in_collection(metadata, &mint.key(), &campaign_escrow.collection_mint)?;
+
     let deposit_matches_treasury_mint = cmp_pubkeys(
         &campaign_escrow.treasury.treasury_mint,
         &deposit_escrow_mint.key(),
     );
 
+    let (matching_pool_accounts, price_oracle_accounts) = if campaign_escrow.has_matching_pool {
+        if ctx.remaining_accounts.len() < MATCHING_POOL_ACCOUNTS_LEN {
+            msg!("The MatchingPool accounts must be provided for campaigns with a MatchingPool.");
+            return Err(CampaignTreasuryManagerError::InvalidMatchingPool.into());
+        }
+        ctx.remaining_accounts.split_at(MATCHING_POOL_ACCOUNTS_LEN)
+    } else {
+        ctx.remaining_accounts.split_at(0)
+    };
+
+    let mut estimated_deposit_amount = 0;
     if deposit_matches_treasury_mint {
         assert_keys_equal(
             &deposit_escrow.key(),
@@ -90,9 +127,25 @@ pub fn handle_create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -
             deposit_amount,
         )?;
     } else {
-        let deposit_escrow_currency = campaign_escrow
-            .get_deposit_escrow_info(&deposit_escrow_mint.key())
-            .unwrap();
+        let deposit_escrow_currency =
+            match campaign_escrow.get_deposit_escrow_info(&deposit_escrow_mint.key()) {
+                Some(deposit_escrow_currency) => deposit_escrow_currency,
+                None => {
+                    msg!(
+                        "No DepositEscrow exists for deposit_escrow_mint {}.",
+                        deposit_escrow_mint.key()
+                    );
+                    return Err(CampaignTreasuryManagerError::InvalidDepositEscrowAccount.into());
+                }
+            };
+
+        if deposit_escrow_currency.disabled {
+            msg!(
+                "Deposits in deposit_escrow_mint {} have been disabled.",
+                deposit_escrow_mint.key()
+            );
+            return Err(CampaignTreasuryManagerError::DepositEscrowDisabled.into());
+        }
 
         let expected_deposit_escrow_pda = Pubkey::create_program_address(
             &[
@@ -105,7 +158,11 @@ pub fn handle_create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -
         )
         .unwrap();
 
-        assert_keys_equal(&expected_deposit_escrow_pda, &deposit_escrow.key(), None)?;
+        assert_keys_equal(
+            &expected_deposit_escrow_pda,
+            &deposit_escrow.key(),
+            Some(CampaignTreasuryManagerError::InvalidDepositEscrowAccount),
+        )?;
 
         transfer_funds(
             depositor.to_account_info(),
@@ -116,24 +173,74 @@ pub fn handle_create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -
             system_program,
             deposit_amount,
         )?;
+
+        // Deposits in this currency only count toward total_funds once they are
+        // processed, so their value is estimated for goal progress until then.
+        let price_oracle = campaign_escrow
+            .get_deposit_escrow_info(&deposit_escrow_mint.key())
+            .unwrap()
+            .price_oracle;
+        if !cmp_pubkeys(&price_oracle, &Pubkey::default()) {
+            estimated_deposit_amount = estimate_deposit_value(
+                price_oracle_accounts,
+                &price_oracle,
+                &campaign_escrow.treasury.treasury_mint,
+                deposit_escrow_mint.decimals,
+                deposit_amount,
+            )?;
+            campaign_escrow.increment_pending_estimated_funds(estimated_deposit_amount);
+        }
     }
 
+    // Reward tier minimums and matches are in the treasury currency, so
+    // deposits in other currencies are valued by their estimated value, which
+    // requires a price oracle.
+    let deposit_value = if deposit_matches_treasury_mint {
+        deposit_amount
+    } else {
+        estimated_deposit_amount
+    };
+    campaign_escrow.record_reward_tier_sale(reward_tier_index, deposit_value)?;
+
     deposit_record.bump = *ctx.bumps.get(DepositRecord::PREFIX).unwrap();
     deposit_record.mint = mint.key();
     deposit_record.depositor = depositor.key();
     deposit_record.deposit_escrow_mint = deposit_escrow_mint.key();
     deposit_record.initial_deposit_amount = deposit_amount;
+    deposit_record.campaign_escrow = campaign_escrow.key();
+    deposit_record.estimated_deposit_amount = estimated_deposit_amount;
+    deposit_record.reward_tier_index = reward_tier_index;
 
     campaign_escrow.increment_deposit_count();
 
     // If deposit matched treasury escrow then it is now fully processed.
     if deposit_matches_treasury_mint {
         campaign_escrow.increment_processed_deposit_count();
-        campaign_escrow.increment_total_funds(deposit_amount);
+        campaign_escrow.increment_total_funds(deposit_amount)?;
         deposit_record.deposit_processed = true;
         deposit_record.processed_deposit_amount = deposit_amount;
+
+        emit!(DepositProcessed {
+            campaign_escrow: campaign_escrow.key(),
+            deposit_record: deposit_record.key(),
+            depositor: depositor.key(),
+            processed_deposit_amount: deposit_amount,
+        });
+    }
+
+    if campaign_escrow.has_matching_pool {
+        match_deposit(
+            campaign_escrow,
+            matching_pool_accounts,
+            token_program,
+            system_program,
+            deposit_value,
+        )?;
     }
 
+    campaign_escrow.assert_is_within_hard_cap()?;
+    campaign_escrow.end_campaign_if_hard_cap_reached(clock.unix_timestamp);
+
     msg!(
         "Deposit of {} in currency mint {} created by {} for NFT mint {}.",
         deposit_amount,
@@ -143,3 +250,69 @@ pub fn handle_create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -
     );
     Ok(())
 }
+
+// Transfers the match for a deposit of the given value from the MatchingPool
+// to the treasury_escrow. Matches stop at the hard_cap rather than rejecting
+// the deposit.
+fn match_deposit<'info>(
+    campaign_escrow: &mut Account<'info, CampaignEscrow>,
+    matching_pool_accounts: &[AccountInfo<'info>],
+    token_program: &Program<'info, Token>,
+    system_program: &Program<'info, System>,
+    deposit_value: u64,
+) -> Result<()> {
+    let matching_pool_escrow = &matching_pool_accounts[1];
+    let treasury_escrow = &matching_pool_accounts[2];
+
+    let mut matching_pool = Account::<MatchingPool>::try_from(&matching_pool_accounts[0])?;
+    assert_keys_equal(
+        &matching_pool.campaign_escrow,
+        &campaign_escrow.key(),
+        Some(CampaignTreasuryManagerError::InvalidMatchingPool),
+    )?;
+
+    let expected_matching_pool_escrow_pda = Pubkey::create_program_address(
+        &MatchingPoolEscrow::get_seeds(&campaign_escrow.campaign_uuid, &matching_pool.escrow_bump),
+        &ID,
+    )
+    .unwrap();
+    assert_keys_equal(
+        &expected_matching_pool_escrow_pda,
+        &matching_pool_escrow.key(),
+        Some(CampaignTreasuryManagerError::InvalidMatchingPool),
+    )?;
+    assert_keys_equal(
+        &campaign_escrow.treasury.treasury_escrow,
+        &treasury_escrow.key(),
+        Some(CampaignTreasuryManagerError::InvalidMatchingPool),
+    )?;
+
+    let match_amount = matching_pool
+        .calculate_match_amount(deposit_value, campaign_escrow.get_remaining_hard_cap());
+    if match_amount == 0 {
+        return Ok(());
+    }
+
+    transfer_from_matching_pool_escrow(
+        campaign_escrow,
+        matching_pool_escrow.clone(),
+        matching_pool.escrow_bump,
+        treasury_escrow.clone(),
+        token_program,
+        system_program,
+        match_amount,
+    )?;
+
+    matching_pool.record_match(match_amount);
+    matching_pool.exit(&ID)?;
+    campaign_escrow.record_match(match_amount)?;
+
+    msg!(
+        "Matched deposit value {} with {} from the MatchingPool, total_matched = {}.",
+        deposit_value,
+        match_amount,
+        matching_pool.total_matched
+    );
+
+    Ok(())
+}
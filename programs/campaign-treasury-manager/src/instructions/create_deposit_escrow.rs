@@ -3,7 +3,7 @@ use anchor_spl::token::{Mint, Token};
 
 use crate::{
     cmp_pubkeys, create_deposit_escrow_account, is_native_mint, CampaignEscrow,
-    CampaignTreasuryManagerError, DepositEscrow,
+    CampaignTreasuryManagerError, CreateDepositEscrowAccounts, DepositEscrow,
 };
 
 #[derive(Accounts)]
@@ -79,20 +79,22 @@ pub fn handle_create_deposit_escrow(
 
     if !is_native_mint(&deposit_escrow_mint.key()) {
         create_deposit_escrow_account(
-            campaign_escrow.to_account_info(),
-            deposit_escrow.to_account_info(),
-            deposit_escrow_mint.to_account_info(),
-            authority.to_account_info(),
-            token_program,
-            system_program,
-            rent,
+            CreateDepositEscrowAccounts {
+                campaign_escrow: campaign_escrow.to_account_info(),
+                deposit_escrow_account: deposit_escrow.to_account_info(),
+                deposit_escrow_mint: deposit_escrow_mint.to_account_info(),
+                payer: authority.to_account_info(),
+                token_program,
+                system_program,
+                rent,
+            },
             &[],
             deposit_escrow_bump,
             campaign_escrow.campaign_uuid.clone(),
         )?;
     }
 
-    campaign_escrow.add_deposit_escrow_info(deposit_escrow_mint.key(), deposit_escrow_bump);
+    campaign_escrow.add_deposit_escrow_info(deposit_escrow_mint.key(), deposit_escrow_bump)?;
 
     msg!(
         "Created DepositEscrow account for token mint {} for CampaignEscrow with campaign_uuid {}.",
@@ -7,12 +7,16 @@ use anchor_spl::{
 use crate::{
     assert_campaign_end_time_is_valid, assert_valid_escrow_management_signers,
     constants::PROGRAM_PREFIX, create_treasury_escrow_and_assert_is_valid, is_native_mint,
-    CampaignEscrow, NonVotingPayoutPhaseInput, PayoutPhases, Treasury, TreasuryEscrow,
-    VotingPayoutPhaseInput,
+    CampaignEscrow, CrankReward, CreateTreasuryEscrowAccounts, PayoutPhaseInput, PayoutPhases,
+    RewardTierInput, RewardTiers, SurplusPolicy, Treasury, TreasuryEscrow,
 };
 
 #[derive(Accounts)]
-#[instruction(campaign_uuid: String, treasury_bump: u8)]
+#[instruction(
+    campaign_uuid: String,
+    treasury_bump: u8,
+    campaign_escrow_input: CreateCampaignEscrowInput,
+)]
 pub struct CreateEscrow<'info> {
     #[account(
         init,
@@ -22,7 +26,11 @@ pub struct CreateEscrow<'info> {
         ],
         bump,
         payer = payer,
-        space = CampaignEscrow::SPACE,
+        space = CampaignEscrow::get_space(
+            campaign_escrow_input.payout_phases.len(),
+            CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            campaign_escrow_input.reward_tiers.len(),
+        )?,
     )]
     campaign_escrow: Account<'info, CampaignEscrow>,
     #[account(mut)]
@@ -58,11 +66,15 @@ pub struct CreateEscrow<'info> {
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct CreateCampaignEscrowInput {
     pub campaign_end_time: i64,
-    pub non_voting_payout_phases: Vec<NonVotingPayoutPhaseInput>,
-    // TODO[@bonham000]: Should we go ahead and include the voting payout_phases
-    // data in the argument now for backwards compatibility reasons?
-    // context: https://www.notion.so/formfunction/Solana-Program-Backwards-Compatibility-18a68a79f6374c43be9b44f063998366#4d54d0b2012842d88b5d7214713ae5c4
-    pub voting_payout_phases: Vec<VotingPayoutPhaseInput>,
+    pub payout_phases: Vec<PayoutPhaseInput>,
+    pub surplus_policy: SurplusPolicy,
+    pub crank_reward: CrankReward,
+    pub collection_mint: Pubkey,
+    pub fundraising_start_time: Option<i64>,
+    pub deposit_grace_period: i64,
+    pub reward_tiers: Vec<RewardTierInput>,
+    pub hard_cap: Option<u64>,
+    pub auto_end_at_hard_cap: bool,
 }
 
 pub fn handle_create_escrow(
@@ -87,16 +99,18 @@ pub fn handle_create_escrow(
     assert_valid_escrow_management_signers(creator, authority, None)?;
 
     create_treasury_escrow_and_assert_is_valid(
-        campaign_escrow.to_account_info(),
-        treasury_escrow.to_account_info(),
-        treasury_mint.to_account_info(),
-        payout_wallet.to_account_info(),
-        payout_wallet_owner.to_account_info(),
-        payer.to_account_info(),
-        ata_program,
-        token_program,
-        system_program,
-        rent,
+        CreateTreasuryEscrowAccounts {
+            campaign_escrow: campaign_escrow.to_account_info(),
+            treasury_escrow: treasury_escrow.to_account_info(),
+            treasury_mint: treasury_mint.to_account_info(),
+            payout_wallet: payout_wallet.to_account_info(),
+            payout_wallet_owner: payout_wallet_owner.to_account_info(),
+            payer: payer.to_account_info(),
+            ata_program,
+            token_program,
+            system_program,
+            rent,
+        },
         &[],
         treasury_bump,
         campaign_uuid.clone(),
@@ -110,6 +124,7 @@ pub fn handle_create_escrow(
 
     let bump = *ctx.bumps.get(CampaignEscrow::PREFIX).unwrap();
 
+    campaign_escrow.version = CampaignEscrow::VERSION;
     campaign_escrow.bump = bump;
     campaign_escrow.campaign_uuid = campaign_uuid.clone();
     campaign_escrow.authority = authority.key();
@@ -120,19 +135,46 @@ pub fn handle_create_escrow(
         treasury_mint: treasury_mint.key(),
         treasury_escrow: treasury_escrow.key(),
         total_funds: 0,
+        total_paid_out: 0,
+        total_refunded: 0,
+        total_surplus: 0,
+        supporter_surplus: 0,
+        supporter_surplus_paid_out: 0,
+        total_crank_rewards: 0,
+        pending_estimated_funds: 0,
+        total_donations: 0,
+        non_voting_donations: 0,
+        non_refundable_donations: 0,
+        total_matched: 0,
+        closed_deposit_amount: 0,
     };
     campaign_escrow.campaign_end_time = create_campaign_escrow_input.campaign_end_time;
     campaign_escrow.payouts_ready = false;
+    campaign_escrow.surplus_policy = create_campaign_escrow_input.surplus_policy;
+    create_campaign_escrow_input
+        .crank_reward
+        .assert_is_valid()?;
+    campaign_escrow.crank_reward = create_campaign_escrow_input.crank_reward;
+    campaign_escrow.collection_mint = create_campaign_escrow_input.collection_mint;
+    campaign_escrow.deposit_escrow_infos_capacity =
+        CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY;
+    campaign_escrow.fundraising_start_time = create_campaign_escrow_input.fundraising_start_time;
+    campaign_escrow.deposit_grace_period = create_campaign_escrow_input.deposit_grace_period;
+    campaign_escrow.reward_tiers = RewardTiers::new(create_campaign_escrow_input.reward_tiers)?;
+    campaign_escrow.hard_cap = create_campaign_escrow_input.hard_cap;
+    campaign_escrow.auto_end_at_hard_cap = create_campaign_escrow_input.auto_end_at_hard_cap;
+    campaign_escrow.hard_cap_reached_at = None;
+    campaign_escrow.assert_hard_cap_is_valid()?;
     campaign_escrow.deposit_count = 0;
     campaign_escrow.processed_deposit_count = 0;
 
     let payout_phases = PayoutPhases::new(
-        create_campaign_escrow_input.non_voting_payout_phases,
-        create_campaign_escrow_input.voting_payout_phases,
+        create_campaign_escrow_input.payout_phases,
         create_campaign_escrow_input.campaign_end_time,
     )?;
 
     campaign_escrow.payout_phases = payout_phases;
+    campaign_escrow.assert_fundraising_window_is_valid()?;
 
     let is_treasury_native = is_native_mint(&treasury_mint.key());
     let treasury_description = if is_treasury_native {
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{CampaignEscrow, CampaignTreasuryManagerError};
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct DisableDepositEscrow<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    deposit_escrow_mint: Account<'info, Mint>,
+    #[account()]
+    authority: Signer<'info>,
+}
+
+pub fn handle_disable_deposit_escrow(ctx: Context<DisableDepositEscrow>) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let deposit_escrow_mint = &ctx.accounts.deposit_escrow_mint;
+
+    let deposit_escrow_info =
+        match campaign_escrow.get_deposit_escrow_info(&deposit_escrow_mint.key()) {
+            Some(deposit_escrow_info) => deposit_escrow_info,
+            None => {
+                msg!(
+                    "No DepositEscrow exists for deposit_escrow_mint {}.",
+                    deposit_escrow_mint.key()
+                );
+                return Err(CampaignTreasuryManagerError::InvalidDepositEscrowAccount.into());
+            }
+        };
+
+    if deposit_escrow_info.disabled {
+        msg!(
+            "DepositEscrow for deposit_escrow_mint {} is already disabled. This is a no-op.",
+            deposit_escrow_mint.key()
+        );
+        return Ok(());
+    }
+
+    campaign_escrow.mark_deposit_escrow_as_disabled(&deposit_escrow_mint.key());
+
+    msg!(
+        "Disabled deposits in currency mint {} for CampaignEscrow with campaign_uuid {}.",
+        deposit_escrow_mint.key(),
+        campaign_escrow.campaign_uuid
+    );
+
+    Ok(())
+}
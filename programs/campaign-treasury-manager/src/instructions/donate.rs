@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::{transfer_funds, CampaignEscrow, CampaignTreasuryManagerError, DonationRecord};
+
+#[derive(Accounts)]
+#[instruction(donation_uuid: String)]
+pub struct Donate<'info> {
+    #[account(
+        mut,
+        constraint = campaign_escrow.treasury.treasury_escrow.key() == treasury_escrow.key(),
+        constraint = campaign_escrow.treasury.treasury_mint.key() == treasury_mint.key(),
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account(
+        init,
+        seeds = [
+            DonationRecord::PREFIX.as_bytes(),
+            campaign_escrow.key().as_ref(),
+            donation_uuid.as_bytes(),
+        ],
+        bump,
+        payer = donor,
+        space = DonationRecord::SPACE,
+    )]
+    donation_record: Account<'info, DonationRecord>,
+    /// CHECK: Validated by the campaign_escrow constraint.
+    #[account(mut)]
+    treasury_escrow: UncheckedAccount<'info>,
+    treasury_mint: Account<'info, Mint>,
+    #[account(mut)]
+    donor: Signer<'info>,
+    /// CHECK: Validated in transfer_funds.
+    #[account(mut)]
+    donor_payment_account: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    token_program: Program<'info, Token>,
+}
+
+// Unlike CreateDeposit, this is not restricted to CPIs from the sale programs,
+// so anyone can donate to a campaign directly in the treasury currency.
+pub fn handle_donate(
+    ctx: Context<Donate>,
+    donation_uuid: String,
+    donation_amount: u64,
+    refundable: bool,
+    has_vote_weight: bool,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let donation_record = &mut ctx.accounts.donation_record;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let donor = &ctx.accounts.donor;
+    let donor_payment_account = &ctx.accounts.donor_payment_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    if donation_amount == 0 {
+        msg!("Donation amount must be greater than zero.");
+        return Err(CampaignTreasuryManagerError::InvalidDonation.into());
+    }
+
+    let clock = Clock::get()?;
+    campaign_escrow.assert_is_in_fundraising_window(clock.unix_timestamp, false)?;
+
+    transfer_funds(
+        donor.to_account_info(),
+        treasury_escrow.to_account_info(),
+        donor_payment_account.to_account_info(),
+        treasury_mint.to_account_info(),
+        token_program,
+        system_program,
+        donation_amount,
+    )?;
+
+    campaign_escrow.record_donation(donation_amount, refundable, has_vote_weight)?;
+    campaign_escrow.assert_is_within_hard_cap()?;
+    campaign_escrow.end_campaign_if_hard_cap_reached(clock.unix_timestamp);
+
+    donation_record.bump = *ctx.bumps.get(DonationRecord::PREFIX).unwrap();
+    donation_record.campaign_escrow = campaign_escrow.key();
+    donation_record.donation_uuid = donation_uuid;
+    donation_record.donor = donor.key();
+    donation_record.donation_amount = donation_amount;
+    donation_record.refundable = refundable;
+    donation_record.has_vote_weight = has_vote_weight;
+    donation_record.donation_time = clock.unix_timestamp;
+
+    msg!(
+        "Donation of {} created by {} for CampaignEscrow with campaign_uuid {}, refundable = {}, has_vote_weight = {}.",
+        donation_amount,
+        donor.key(),
+        campaign_escrow.campaign_uuid,
+        refundable,
+        has_vote_weight
+    );
+
+    Ok(())
+}
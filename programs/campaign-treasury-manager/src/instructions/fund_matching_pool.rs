@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::{
+    create_matching_pool_escrow_account, transfer_funds, CampaignEscrow,
+    CampaignTreasuryManagerError, CreateMatchingPoolEscrowAccounts, MatchingPool,
+    MatchingPoolEscrow,
+};
+
+#[derive(Accounts)]
+pub struct FundMatchingPool<'info> {
+    #[account(
+        mut,
+        constraint = campaign_escrow.treasury.treasury_mint.key() == treasury_mint.key(),
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account(
+        init,
+        seeds = [
+            MatchingPool::PREFIX.as_bytes(),
+            campaign_escrow.key().as_ref(),
+        ],
+        bump,
+        payer = sponsor,
+        space = MatchingPool::SPACE,
+    )]
+    matching_pool: Account<'info, MatchingPool>,
+    /// CHECK: Validated by the seeds constraint, and created in the instruction
+    /// for token treasuries.
+    #[account(
+        mut,
+        seeds = [
+            MatchingPoolEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump,
+    )]
+    matching_pool_escrow: UncheckedAccount<'info>,
+    treasury_mint: Account<'info, Mint>,
+    #[account(mut)]
+    sponsor: Signer<'info>,
+    /// CHECK: Validated in transfer_funds.
+    #[account(mut)]
+    sponsor_payment_account: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    token_program: Program<'info, Token>,
+    rent: Sysvar<'info, Rent>,
+}
+
+// The sponsor funds the whole match_cap up front, so every match is backed by
+// escrowed funds. A CampaignEscrow can only have one MatchingPool.
+pub fn handle_fund_matching_pool(
+    ctx: Context<FundMatchingPool>,
+    match_basis_points: u16,
+    match_cap: u64,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let matching_pool = &mut ctx.accounts.matching_pool;
+    let matching_pool_escrow = &ctx.accounts.matching_pool_escrow;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let sponsor = &ctx.accounts.sponsor;
+    let sponsor_payment_account = &ctx.accounts.sponsor_payment_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    if match_basis_points == 0 || match_cap == 0 {
+        msg!(
+            "MatchingPool match_basis_points {} and match_cap {} must be greater than 0.",
+            match_basis_points,
+            match_cap
+        );
+        return Err(CampaignTreasuryManagerError::InvalidMatchingPool.into());
+    }
+
+    // Only deposits made after this are matched, so the pool must be funded
+    // while the campaign is still raising funds.
+    let clock = Clock::get()?;
+    campaign_escrow.assert_is_in_fundraising_window(clock.unix_timestamp, false)?;
+
+    let matching_pool_escrow_bump = *ctx.bumps.get("matching_pool_escrow").unwrap();
+
+    create_matching_pool_escrow_account(
+        CreateMatchingPoolEscrowAccounts {
+            campaign_escrow: campaign_escrow.to_account_info(),
+            matching_pool_escrow: matching_pool_escrow.to_account_info(),
+            treasury_mint: treasury_mint.to_account_info(),
+            sponsor: sponsor.to_account_info(),
+            token_program,
+            system_program,
+            rent,
+        },
+        matching_pool_escrow_bump,
+        campaign_escrow.campaign_uuid.clone(),
+    )?;
+
+    transfer_funds(
+        sponsor.to_account_info(),
+        matching_pool_escrow.to_account_info(),
+        sponsor_payment_account.to_account_info(),
+        treasury_mint.to_account_info(),
+        token_program,
+        system_program,
+        match_cap,
+    )?;
+
+    matching_pool.bump = *ctx.bumps.get(MatchingPool::PREFIX).unwrap();
+    matching_pool.escrow_bump = matching_pool_escrow_bump;
+    matching_pool.campaign_escrow = campaign_escrow.key();
+    matching_pool.sponsor = sponsor.key();
+    matching_pool.match_basis_points = match_basis_points;
+    matching_pool.match_cap = match_cap;
+    matching_pool.total_matched = 0;
+
+    campaign_escrow.has_matching_pool = true;
+
+    msg!(
+        "MatchingPool funded with {} by {} for CampaignEscrow with campaign_uuid {}, match_basis_points = {}.",
+        match_cap,
+        sponsor.key(),
+        campaign_escrow.campaign_uuid,
+        match_basis_points
+    );
+
+    Ok(())
+}
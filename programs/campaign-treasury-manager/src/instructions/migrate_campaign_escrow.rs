@@ -0,0 +1,92 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    assert_keys_equal, realloc_account_if_needed, CampaignEscrow, CampaignTreasuryManagerError,
+    LegacyCampaignEscrow, ID,
+};
+
+#[derive(Accounts)]
+#[instruction(campaign_uuid: String)]
+pub struct MigrateCampaignEscrow<'info> {
+    /// CHECK: Validated in instruction.
+    /// Legacy accounts cannot be deserialized as a CampaignEscrow, so this
+    /// account is deserialized manually in the instruction handler.
+    #[account(
+        mut,
+        owner = ID,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_uuid.as_bytes(),
+        ],
+        bump,
+    )]
+    campaign_escrow: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account()]
+    authority: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_migrate_campaign_escrow(
+    ctx: Context<MigrateCampaignEscrow>,
+    campaign_uuid: String,
+) -> Result<()> {
+    let campaign_escrow = &ctx.accounts.campaign_escrow;
+    let payer = &ctx.accounts.payer;
+    let authority = &ctx.accounts.authority;
+    let system_program = &ctx.accounts.system_program;
+
+    let account_data = campaign_escrow.try_borrow_data()?;
+    if account_data.len() < 9 || account_data[..8] != CampaignEscrow::discriminator() {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+
+    // Legacy accounts start with the PDA bump where migrated accounts start
+    // with the layout version.
+    if account_data[8] == CampaignEscrow::VERSION {
+        msg!(
+            "CampaignEscrow with campaign_uuid {} has already been migrated. This is a no-op.",
+            campaign_uuid
+        );
+        return Ok(());
+    }
+
+    let migrated_campaign_escrow: CampaignEscrow =
+        LegacyCampaignEscrow::deserialize(&mut &account_data[8..])
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?
+            .into();
+    // Need to drop the account borrow before reallocating the account below.
+    drop(account_data);
+
+    assert_keys_equal(
+        &authority.key(),
+        &migrated_campaign_escrow.authority,
+        Some(CampaignTreasuryManagerError::AuthorityMismatch),
+    )?;
+
+    realloc_account_if_needed(
+        campaign_escrow.to_account_info(),
+        payer.to_account_info(),
+        system_program.to_account_info(),
+        CampaignEscrow::get_space(
+            migrated_campaign_escrow.payout_phases.len(),
+            migrated_campaign_escrow.deposit_escrow_infos_capacity,
+            migrated_campaign_escrow.reward_tiers.len(),
+        )?,
+    )?;
+
+    let mut account_data = campaign_escrow.try_borrow_mut_data()?;
+    // Zero out the existing data first, since the legacy layout may be longer
+    // than the migrated account.
+    account_data.fill(0);
+    let mut writer: &mut [u8] = &mut account_data;
+    migrated_campaign_escrow.try_serialize(&mut writer)?;
+
+    msg!(
+        "Migrated CampaignEscrow with campaign_uuid {} to the current layout.",
+        campaign_uuid
+    );
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_nft_is_in_collection, cmp_pubkeys, CampaignEscrow, CampaignTreasuryManagerError,
+    DepositRecord, ID,
+};
+
+#[derive(Accounts)]
+#[instruction(collection_mint: Option<Pubkey>)]
+pub struct MigrateDepositRecords<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account()]
+    authority: Signer<'info>,
+    // The remaining_accounts are pairs of a writable legacy DepositRecord of
+    // this campaign and the Metaplex metadata account of its NFT mint.
+}
+
+pub fn handle_migrate_deposit_records<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateDepositRecords<'info>>,
+    collection_mint: Option<Pubkey>,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let remaining_accounts = ctx.remaining_accounts;
+
+    let account_chunks = remaining_accounts.chunks_exact(2);
+    if !account_chunks.remainder().is_empty() {
+        msg!(
+            "Expected pairs of DepositRecord and NFT metadata accounts, received {} accounts.",
+            remaining_accounts.len()
+        );
+        return Err(CampaignTreasuryManagerError::InvalidDepositRecord.into());
+    }
+
+    // Legacy DepositRecords do not record their campaign, or anything else
+    // derived from it, so they are matched to the campaign by their NFT.
+    // Migrated legacy CampaignEscrows have no collection_mint, and can no
+    // longer be updated once they ended, so it is set here once.
+    let has_collection_mint = !cmp_pubkeys(&campaign_escrow.collection_mint, &Pubkey::default());
+    match collection_mint {
+        Some(collection_mint) if !has_collection_mint => {
+            msg!("Setting collection_mint to {}.", collection_mint);
+            campaign_escrow.collection_mint = collection_mint;
+        }
+        Some(_) => {
+            msg!(
+                "CampaignEscrow collection_mint is already set to {}.",
+                campaign_escrow.collection_mint
+            );
+            return Err(CampaignTreasuryManagerError::InvalidNftCollection.into());
+        }
+        None if !has_collection_mint => {
+            msg!("A collection_mint must be provided for a CampaignEscrow without one.");
+            return Err(CampaignTreasuryManagerError::InvalidNftCollection.into());
+        }
+        None => {}
+    }
+
+    let mut migrated_count: u64 = 0;
+    for accounts in account_chunks {
+        let deposit_record_info = &accounts[0];
+        let metadata = &accounts[1];
+        let mut deposit_record: Account<DepositRecord> = Account::try_from(deposit_record_info)?;
+        deposit_record.assert_pda(&deposit_record_info.key())?;
+
+        if !deposit_record.is_legacy() {
+            deposit_record.assert_belongs_to(&campaign_escrow.key())?;
+            msg!(
+                "DepositRecord {} has already been migrated, skipping.",
+                deposit_record_info.key()
+            );
+            continue;
+        }
+
+        assert_nft_is_in_collection(
+            metadata,
+            &deposit_record.mint,
+            &campaign_escrow.collection_mint,
+        )?;
+
+        // The deposit currency must also be supported by this campaign.
+        // Deposits in the treasury currency do not use a DepositEscrow.
+        let deposit_escrow_mint = &deposit_record.deposit_escrow_mint;
+        if !cmp_pubkeys(deposit_escrow_mint, &campaign_escrow.treasury.treasury_mint)
+            && campaign_escrow
+                .get_deposit_escrow_info(deposit_escrow_mint)
+                .is_none()
+        {
+            msg!(
+                "DepositRecord {} deposit_escrow_mint {} is not supported by this CampaignEscrow.",
+                deposit_record_info.key(),
+                deposit_record.deposit_escrow_mint
+            );
+            return Err(CampaignTreasuryManagerError::InvalidDepositRecord.into());
+        }
+
+        deposit_record.campaign_escrow = campaign_escrow.key();
+        deposit_record.exit(&ID)?;
+        migrated_count += 1;
+    }
+
+    msg!(
+        "Migrated {} legacy DepositRecord accounts for CampaignEscrow with uuid {}.",
+        migrated_count,
+        campaign_escrow.campaign_uuid
+    );
+
+    Ok(())
+}
@@ -1,25 +1,51 @@
+pub mod claim_streamed_payout;
 pub mod close_deposit_escrow;
 pub mod close_deposit_record;
+pub mod close_deposit_records;
 pub mod close_escrow;
+pub mod close_matching_pool;
 pub mod create_deposit;
 pub mod create_deposit_escrow;
 pub mod create_escrow;
+pub mod disable_deposit_escrow;
+pub mod donate;
+pub mod fund_matching_pool;
+pub mod migrate_campaign_escrow;
+pub mod migrate_deposit_records;
 pub mod pay_out_funds;
 pub mod process_deposit;
+pub mod process_donation_refund;
 pub mod process_partial_refund;
 pub mod process_refund;
+pub mod reconcile_treasury;
+pub mod set_deposit_escrow_price_oracle;
 pub mod update_escrow;
 pub mod veto_payout_phase;
+pub mod vote_to_veto_payout_phase;
+pub mod vote_to_veto_payout_phase_with_donation;
 
+pub use claim_streamed_payout::*;
 pub use close_deposit_escrow::*;
 pub use close_deposit_record::*;
+pub use close_deposit_records::*;
 pub use close_escrow::*;
+pub use close_matching_pool::*;
 pub use create_deposit::*;
 pub use create_deposit_escrow::*;
 pub use create_escrow::*;
+pub use disable_deposit_escrow::*;
+pub use donate::*;
+pub use fund_matching_pool::*;
+pub use migrate_campaign_escrow::*;
+pub use migrate_deposit_records::*;
 pub use pay_out_funds::*;
 pub use process_deposit::*;
+pub use process_donation_refund::*;
 pub use process_partial_refund::*;
 pub use process_refund::*;
+pub use reconcile_treasury::*;
+pub use set_deposit_escrow_price_oracle::*;
 pub use update_escrow::*;
 pub use veto_payout_phase::*;
+pub use vote_to_veto_payout_phase::*;
+pub use vote_to_veto_payout_phase_with_donation::*;
@@ -1,10 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
-use solana_program::program::invoke_signed;
-use solana_program::system_instruction;
 
 use crate::constants::PROGRAM_PREFIX;
-use crate::{is_native_mint, CampaignEscrow, CampaignTreasuryManagerError, TreasuryEscrow};
+use crate::{
+    transfer_from_treasury_escrow, CampaignEscrow, CampaignTreasuryManagerError,
+    PayoutPhasePaidOut, TreasuryEscrow,
+};
 
 #[derive(Accounts)]
 #[instruction()]
@@ -38,100 +39,140 @@ pub struct PayOutFunds<'info> {
     payout_wallet: UncheckedAccount<'info>,
     token_program: Program<'info, Token>,
     system_program: Program<'info, System>,
+    // The first remaining_account is the writable crank_reward_receiver, which
+    // is only required if a crank reward is paid. It is chosen by the payer
+    // and must be able to receive the treasury_mint currency.
 }
 
-pub fn handle_pay_out_funds(ctx: Context<PayOutFunds>) -> Result<()> {
+// Determines which payout phases are paid out by a PayOutFunds ix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayoutMode {
+    // Only the current active payout phase.
+    CurrentPhase,
+    // Every payout phase which is due, up to the first vetoed phase.
+    AllDuePhases,
+}
+
+pub fn handle_pay_out_funds<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayOutFunds<'info>>,
+    payout_mode: PayoutMode,
+) -> Result<()> {
     let campaign_escrow = &mut ctx.accounts.campaign_escrow;
     let treasury_escrow = &ctx.accounts.treasury_escrow;
     let payout_wallet = &ctx.accounts.payout_wallet;
     let token_program = &ctx.accounts.token_program;
     let system_program = &ctx.accounts.system_program;
 
-    let next_payout = campaign_escrow
-        .payout_phases
-        .get_current_active_payout_phase_for_payout();
-
-    if next_payout.is_none() {
-        msg!("There is no next payout available.");
-        return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
-    }
-
-    let next_payout = next_payout.unwrap();
+    // Payouts begin after the fundraising window, so the raise is final.
+    campaign_escrow.settle_stretch_goals_if_needed();
 
-    let payout_time = next_payout.get_payout_time();
     let clock = Clock::get()?;
-    let now = clock.unix_timestamp;
-    if now < payout_time {
-        msg!("Payout time has not been reached yet.");
-        return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
-    }
+    let total_votes = campaign_escrow.get_total_votes();
+    let payout_indexes = match payout_mode {
+        PayoutMode::CurrentPhase => {
+            let (payout_index, _) = campaign_escrow
+                .payout_phases
+                .get_payout_phase_for_payout(clock.unix_timestamp, total_votes)?;
+            vec![payout_index]
+        }
+        PayoutMode::AllDuePhases => campaign_escrow
+            .payout_phases
+            .get_due_payout_phase_indexes(clock.unix_timestamp, total_votes)?,
+    };
+
+    // The payout and crank reward amounts of each payout phase.
+    let payouts: Vec<(usize, u64, u64)> = payout_indexes
+        .into_iter()
+        .map(|payout_index| {
+            let payout_basis_points = campaign_escrow
+                .payout_phases
+                .get(payout_index)
+                .unwrap()
+                .payout_basis_points;
+            let payout_amount = campaign_escrow
+                .calculate_payout_amount_from_payout_basis_points(payout_basis_points);
+            let crank_reward_amount = campaign_escrow.calculate_crank_reward_amount(payout_amount);
+            (payout_index, payout_amount, crank_reward_amount)
+        })
+        .collect();
+
+    let total_payout_amount: u64 = payouts
+        .iter()
+        .map(|(_, payout_amount, _)| payout_amount)
+        .sum();
+    let total_crank_reward_amount: u64 = payouts
+        .iter()
+        .map(|(_, _, crank_reward_amount)| crank_reward_amount)
+        .sum();
+    let payout_wallet_amount = total_payout_amount
+        .checked_sub(total_crank_reward_amount)
+        .unwrap();
+
+    transfer_from_treasury_escrow(
+        campaign_escrow,
+        treasury_escrow.to_account_info(),
+        payout_wallet.to_account_info(),
+        token_program,
+        system_program,
+        payout_wallet_amount,
+    )?;
+
+    if total_crank_reward_amount > 0 {
+        let crank_reward_receiver = match ctx.remaining_accounts.first() {
+            Some(crank_reward_receiver) if crank_reward_receiver.is_writable => {
+                crank_reward_receiver
+            }
+            Some(crank_reward_receiver) => {
+                msg!(
+                    "crank_reward_receiver {} must be writable.",
+                    crank_reward_receiver.key()
+                );
+                return Err(CampaignTreasuryManagerError::InvalidCrankReward.into());
+            }
+            None => {
+                msg!("A crank_reward_receiver is required to pay the crank reward.");
+                return Err(CampaignTreasuryManagerError::InvalidCrankReward.into());
+            }
+        };
+
+        transfer_from_treasury_escrow(
+            campaign_escrow,
+            treasury_escrow.to_account_info(),
+            crank_reward_receiver.to_account_info(),
+            token_program,
+            system_program,
+            total_crank_reward_amount,
+        )?;
+        campaign_escrow.record_crank_reward(total_crank_reward_amount);
 
-    let is_vetoed_by_authority = next_payout.get_is_vetoed_by_authority();
-    if is_vetoed_by_authority {
-        msg!("Payout has been vetoed by authority.");
-        return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
+        msg!(
+            "Paid crank reward of {} tokens to {}.",
+            total_crank_reward_amount,
+            crank_reward_receiver.key(),
+        );
     }
 
-    let payout_index = next_payout.get_index();
-
-    let next_payout_basis_points = next_payout.get_payout_basis_points();
-    let payout_amount =
-        campaign_escrow.calculate_payout_amount_from_payout_basis_points(next_payout_basis_points);
-
-    if is_native_mint(&campaign_escrow.treasury.treasury_mint) {
-        let treasury_escrow_seeds = TreasuryEscrow::get_seeds(
-            &campaign_escrow.campaign_uuid,
-            &campaign_escrow.treasury.bump,
+    // Payout phases are recorded individually, in order.
+    for (payout_index, payout_amount, crank_reward_amount) in payouts {
+        campaign_escrow.record_payout(payout_amount)?;
+
+        emit!(PayoutPhasePaidOut {
+            campaign_escrow: campaign_escrow.key(),
+            payout_phase_index: payout_index as u8,
+            paid_amount: payout_amount,
+            crank_reward_amount,
+        });
+        msg!(
+            "Paid out {} tokens for payout with index {}.",
+            payout_amount,
+            payout_index,
         );
-
-        invoke_signed(
-            &system_instruction::transfer(
-                &treasury_escrow.key(),
-                &payout_wallet.key(),
-                payout_amount,
-            ),
-            &[
-                treasury_escrow.to_account_info(),
-                payout_wallet.to_account_info(),
-                system_program.to_account_info(),
-            ],
-            &[&treasury_escrow_seeds],
-        )?;
-    } else {
-        let campaign_escrow_seeds = [
-            CampaignEscrow::PREFIX.as_bytes(),
-            campaign_escrow.campaign_uuid.as_bytes(),
-            &[campaign_escrow.bump],
-        ];
-
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &treasury_escrow.key(),
-                &payout_wallet.key(),
-                &campaign_escrow.key(),
-                &[],
-                payout_amount,
-            )?,
-            &[
-                treasury_escrow.to_account_info(),
-                payout_wallet.to_account_info(),
-                token_program.to_account_info(),
-                campaign_escrow.to_account_info(),
-            ],
-            &[&campaign_escrow_seeds],
-        )?;
     }
 
-    campaign_escrow
-        .payout_phases
-        .mark_current_active_payout_phase_as_disbursed();
-
     msg!(
-        "Paid out {} tokens to payout_wallet {} for payout with index {}.",
-        payout_amount,
+        "Paid out {} tokens to payout_wallet {}.",
+        payout_wallet_amount,
         payout_wallet.key(),
-        payout_index,
     );
 
     Ok(())
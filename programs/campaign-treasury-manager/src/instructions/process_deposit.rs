@@ -1,13 +1,19 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
 
 use crate::constants::PROGRAM_PREFIX;
-use crate::{CampaignEscrow, TreasuryEscrow};
+use crate::{
+    assert_is_ata, assert_keys_equal, is_native_mint, transfer_from_deposit_escrow, transfer_funds,
+    CampaignEscrow, CampaignTreasuryManagerError, DepositEscrow, DepositProcessed, DepositRecord,
+    TreasuryEscrow, ID,
+};
 
 #[derive(Accounts)]
-#[instruction()]
+#[instruction(processed_deposit_amount: u64)]
 pub struct ProcessDeposit<'info> {
     #[account(
         mut,
+        has_one = authority,
         constraint = campaign_escrow.treasury.treasury_escrow.key() == treasury_escrow.key(),
         seeds = [
             CampaignEscrow::PREFIX.as_bytes(),
@@ -16,8 +22,24 @@ pub struct ProcessDeposit<'info> {
         bump = campaign_escrow.bump,
     )]
     campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account(
+        mut,
+        seeds = [
+            DepositRecord::PREFIX.as_bytes(),
+            deposit_record.depositor.as_ref(),
+            deposit_record.mint.as_ref(),
+        ],
+        bump = deposit_record.bump,
+    )]
+    deposit_record: Account<'info, DepositRecord>,
+    /// CHECK: Validated in instruction.
+    #[account(mut)]
+    deposit_escrow: UncheckedAccount<'info>,
+    #[account(address = deposit_record.deposit_escrow_mint)]
+    deposit_escrow_mint: Account<'info, Mint>,
     /// CHECK: Validated in instruction.
     #[account(
+        mut,
         seeds = [
             PROGRAM_PREFIX.as_bytes(),
             campaign_escrow.campaign_uuid.as_bytes(),
@@ -26,35 +48,151 @@ pub struct ProcessDeposit<'info> {
         bump = campaign_escrow.treasury.bump,
     )]
     treasury_escrow: UncheckedAccount<'info>,
+    #[account(address = campaign_escrow.treasury.treasury_mint)]
+    treasury_mint: Account<'info, Mint>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    /// CHECK: Validated in instruction. Receives the deposit from the
+    /// DepositEscrow, this is the authority for native deposit_escrow_mints
+    /// and the authority's ATA otherwise.
     #[account(mut)]
-    payer: Signer<'info>,
+    authority_deposit_escrow_account: UncheckedAccount<'info>,
+    /// CHECK: Validated in instruction.
+    #[account(mut)]
+    authority_payment_account: UncheckedAccount<'info>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
 }
 
 /**
- * This instruction will process an existing DepositRecord. It will swap and
- * transfer the deposited funds to the CampaignEscrow treasury escrow account.
- *
- * To do this it needs to CPI to a swap program, probably Jupiter Aggregator.
- * This will generally probably entail:
- * - Determine best swap route/instruction data off-chain using Jupiter SDK
- *   and then pass these parameters to this instruction.
- * - Use those parameters to construct the swap instruction and then CPI to
- *   the Jupiter program.
- * - Once the swap is complete, update the DepositRecord and CampaignEscrow
- *   accounts, marking this deposit as processed.
+ * This instruction processes an existing DepositRecord whose deposit is held
+ * in a DepositEscrow, by exchanging it for the treasury currency.
  *
- * Note: It may be hard to test this locally or on devnet, because in general
- * the various swapping protocols are not maintained very well on devnet
- * (evidence of this can be seen throughout Jupiter Discord). Consequently,
- * we may be able to test this locally by constructing a handle_swap_token
- * helper function which has an alternate implementation behind a feature flag.
- * The alternate implementation can CPI into a custom local program we maintain
- * for local testing, which will arbitrarily swap the tokens and allow us to
- * still test the overall flow.
+ * The authority swaps the deposit off-chain, e.g. with Jupiter Aggregator,
+ * and settles the swap in this instruction: the deposit is transferred from
+ * the DepositEscrow to the authority, and the processed_deposit_amount is
+ * transferred from the authority to the treasury_escrow. The DepositRecord is
+ * then marked as processed and the processed_deposit_amount counts toward
+ * total_funds, replacing the estimated_deposit_amount in the treasury
+ * pending_estimated_funds.
  *
- * The real Jupiter CPI will then be tested in prod.
+ * Payouts and refunds are calculated from total_funds, so deposits must be
+ * processed before the first payout.
  */
-pub fn handle_process_deposit(_ctx: Context<ProcessDeposit>) -> Result<()> {
-    msg!("handle_process_deposit ix: not implemented yet.");
+pub fn handle_process_deposit(
+    ctx: Context<ProcessDeposit>,
+    processed_deposit_amount: u64,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let deposit_record = &mut ctx.accounts.deposit_record;
+    let deposit_escrow = &ctx.accounts.deposit_escrow;
+    let deposit_escrow_mint = &ctx.accounts.deposit_escrow_mint;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let authority = &ctx.accounts.authority;
+    let authority_deposit_escrow_account = &ctx.accounts.authority_deposit_escrow_account;
+    let authority_payment_account = &ctx.accounts.authority_payment_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    deposit_record.assert_belongs_to(&campaign_escrow.key())?;
+
+    if deposit_record.deposit_processed {
+        msg!(
+            "DepositRecord for NFT mint {} is already processed.",
+            deposit_record.mint
+        );
+        return Err(CampaignTreasuryManagerError::InvalidProcessDeposit.into());
+    }
+
+    if processed_deposit_amount == 0 {
+        msg!("processed_deposit_amount must be greater than 0.");
+        return Err(CampaignTreasuryManagerError::InvalidProcessDeposit.into());
+    }
+
+    campaign_escrow.assert_deposits_can_be_processed()?;
+
+    let deposit_escrow_bump =
+        match campaign_escrow.get_deposit_escrow_info(&deposit_escrow_mint.key()) {
+            Some(deposit_escrow_info) => deposit_escrow_info.deposit_escrow_bump,
+            None => {
+                msg!(
+                    "No DepositEscrow exists for deposit_escrow_mint {}.",
+                    deposit_escrow_mint.key()
+                );
+                return Err(CampaignTreasuryManagerError::InvalidDepositEscrowAccount.into());
+            }
+        };
+
+    let deposit_escrow_mint_pubkey = deposit_escrow_mint.key();
+    let expected_deposit_escrow_pda = Pubkey::create_program_address(
+        &DepositEscrow::get_seeds(
+            &campaign_escrow.campaign_uuid,
+            &deposit_escrow_mint_pubkey,
+            &deposit_escrow_bump,
+        ),
+        &ID,
+    )
+    .unwrap();
+    assert_keys_equal(
+        &expected_deposit_escrow_pda,
+        &deposit_escrow.key(),
+        Some(CampaignTreasuryManagerError::InvalidDepositEscrowAccount),
+    )?;
+
+    if is_native_mint(&deposit_escrow_mint_pubkey) {
+        assert_keys_equal(
+            &authority_deposit_escrow_account.key(),
+            &authority.key(),
+            None,
+        )?;
+    } else {
+        assert_is_ata(
+            authority_deposit_escrow_account,
+            &authority.key(),
+            &deposit_escrow_mint_pubkey,
+        )?;
+    }
+
+    transfer_from_deposit_escrow(
+        campaign_escrow,
+        deposit_escrow.to_account_info(),
+        &deposit_escrow_mint_pubkey,
+        authority_deposit_escrow_account.to_account_info(),
+        token_program,
+        system_program,
+        deposit_record.initial_deposit_amount,
+    )?;
+
+    transfer_funds(
+        authority.to_account_info(),
+        treasury_escrow.to_account_info(),
+        authority_payment_account.to_account_info(),
+        treasury_mint.to_account_info(),
+        token_program,
+        system_program,
+        processed_deposit_amount,
+    )?;
+
+    deposit_record.deposit_processed = true;
+    deposit_record.processed_deposit_amount = processed_deposit_amount;
+    campaign_escrow.record_processed_deposit(
+        processed_deposit_amount,
+        deposit_record.estimated_deposit_amount,
+    )?;
+
+    emit!(DepositProcessed {
+        campaign_escrow: campaign_escrow.key(),
+        deposit_record: deposit_record.key(),
+        depositor: deposit_record.depositor,
+        processed_deposit_amount,
+    });
+    msg!(
+        "Processed deposit of {} in currency mint {} for NFT mint {} as {} in the treasury currency.",
+        deposit_record.initial_deposit_amount,
+        deposit_escrow_mint_pubkey,
+        deposit_record.mint,
+        processed_deposit_amount,
+    );
     Ok(())
 }
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::constants::PROGRAM_PREFIX;
+use crate::{
+    assert_keys_equal, transfer_from_treasury_escrow, CampaignEscrow, CampaignTreasuryManagerError,
+    DonationRecord, RefundProcessed, TreasuryEscrow,
+};
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct ProcessDonationRefund<'info> {
+    #[account(
+        mut,
+        constraint = campaign_escrow.treasury.treasury_escrow.key() == treasury_escrow.key(),
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    /// CHECK: Validated in instruction.
+    #[account(
+        mut,
+        seeds = [
+            PROGRAM_PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            TreasuryEscrow::PREFIX.as_bytes()
+        ],
+        bump = campaign_escrow.treasury.bump,
+    )]
+    treasury_escrow: UncheckedAccount<'info>,
+    // The DonationRecord marks an eligible refund, so it is closed once the
+    // donation is refunded.
+    #[account(
+        mut,
+        has_one = donor,
+        has_one = campaign_escrow,
+        seeds = [
+            DonationRecord::PREFIX.as_bytes(),
+            campaign_escrow.key().as_ref(),
+            donation_record.donation_uuid.as_bytes(),
+        ],
+        bump = donation_record.bump,
+        close = donor,
+    )]
+    donation_record: Account<'info, DonationRecord>,
+    // Donations have no NFT, so refunds are always claimed by the donor.
+    #[account(mut)]
+    donor: Signer<'info>,
+    /// CHECK: Validated in instruction as the donor payment account.
+    #[account(mut)]
+    refund_receiver: UncheckedAccount<'info>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_process_donation_refund(ctx: Context<ProcessDonationRefund>) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let donation_record = &ctx.accounts.donation_record;
+    let refund_receiver = &ctx.accounts.refund_receiver;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    assert_keys_equal(
+        &campaign_escrow.get_refund_receiver(&donation_record.donor),
+        &refund_receiver.key(),
+        None,
+    )?;
+
+    if !donation_record.refundable {
+        msg!(
+            "DonationRecord {} is not refundable.",
+            donation_record.key()
+        );
+        return Err(CampaignTreasuryManagerError::InvalidRefundRequest.into());
+    }
+
+    // Refunds begin after the fundraising window, so the raise is final.
+    campaign_escrow.settle_stretch_goals_if_needed();
+
+    let clock = Clock::get()?;
+    campaign_escrow.assert_refund_is_available(clock.unix_timestamp)?;
+
+    // Like deposits, donations are refunded in full until funds are paid out,
+    // and their share of the remaining funds after that.
+    let refund_amount = if campaign_escrow.get_refundable_funds(clock.unix_timestamp)
+        == campaign_escrow.treasury.total_funds
+    {
+        donation_record.donation_amount
+    } else {
+        campaign_escrow
+            .calculate_refund_amount(donation_record.donation_amount, clock.unix_timestamp)
+    };
+
+    transfer_from_treasury_escrow(
+        campaign_escrow,
+        treasury_escrow.to_account_info(),
+        refund_receiver.to_account_info(),
+        token_program,
+        system_program,
+        refund_amount,
+    )?;
+    campaign_escrow.record_donation_refund(refund_amount)?;
+
+    emit!(RefundProcessed {
+        campaign_escrow: campaign_escrow.key(),
+        record: donation_record.key(),
+        refund_receiver: refund_receiver.key(),
+        refund_amount,
+    });
+
+    msg!(
+        "Refunded {} of the {} tokens donated to {}.",
+        refund_amount,
+        donation_record.donation_amount,
+        refund_receiver.key()
+    );
+
+    Ok(())
+}
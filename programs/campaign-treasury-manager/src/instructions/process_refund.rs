@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 
 use crate::constants::PROGRAM_PREFIX;
-use crate::{CampaignEscrow, TreasuryEscrow};
+use crate::{
+    assert_refund_is_authorized, transfer_from_treasury_escrow, CampaignEscrow,
+    CampaignTreasuryManagerError, DepositRecord, RefundProcessed, TreasuryEscrow,
+};
 
 #[derive(Accounts)]
 #[instruction()]
@@ -18,6 +22,7 @@ pub struct ProcessFullRefund<'info> {
     campaign_escrow: Account<'info, CampaignEscrow>,
     /// CHECK: Validated in instruction.
     #[account(
+        mut,
         seeds = [
             PROGRAM_PREFIX.as_bytes(),
             campaign_escrow.campaign_uuid.as_bytes(),
@@ -26,11 +31,112 @@ pub struct ProcessFullRefund<'info> {
         bump = campaign_escrow.treasury.bump,
     )]
     treasury_escrow: UncheckedAccount<'info>,
+    // The DepositRecord marks an eligible refund, so it is closed once the
+    // deposit is refunded.
+    #[account(
+        mut,
+        has_one = depositor,
+        seeds = [
+            DepositRecord::PREFIX.as_bytes(),
+            deposit_record.depositor.as_ref(),
+            deposit_record.mint.as_ref(),
+        ],
+        bump = deposit_record.bump,
+        close = depositor,
+    )]
+    deposit_record: Account<'info, DepositRecord>,
+    // Receives the DepositRecord rent.
+    #[account(mut)]
+    depositor: SystemAccount<'info>,
+    // The current holder of the DepositRecord NFT, who may not be the
+    // original depositor.
+    #[account()]
+    nft_holder: Signer<'info>,
+    #[account(mut, address = deposit_record.mint)]
+    nft_mint: Account<'info, Mint>,
+    #[account(mut)]
+    nft_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Validated in instruction as the nft_holder payment account.
+    #[account(mut)]
+    refund_receiver: UncheckedAccount<'info>,
     #[account(mut)]
     payer: Signer<'info>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
 }
 
-pub fn handle_process_full_refund(_ctx: Context<ProcessFullRefund>) -> Result<()> {
-    msg!("handle_process_full_refund");
+pub fn handle_process_full_refund(ctx: Context<ProcessFullRefund>) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let deposit_record = &ctx.accounts.deposit_record;
+    let nft_holder = &ctx.accounts.nft_holder;
+    let nft_mint = &ctx.accounts.nft_mint;
+    let nft_token_account = &ctx.accounts.nft_token_account;
+    let refund_receiver = &ctx.accounts.refund_receiver;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    assert_refund_is_authorized(
+        campaign_escrow,
+        deposit_record,
+        &nft_holder.key(),
+        nft_token_account,
+        &refund_receiver.key(),
+    )?;
+    deposit_record.assert_is_processed()?;
+
+    // Refunds begin after the fundraising window, so the raise is final.
+    campaign_escrow.settle_stretch_goals_if_needed();
+
+    let clock = Clock::get()?;
+    campaign_escrow.assert_refund_is_available(clock.unix_timestamp)?;
+
+    // Once funds were paid out, supporters are refunded their share of the
+    // remaining funds with the ProcessPartialRefund ix instead.
+    if campaign_escrow.get_refundable_funds(clock.unix_timestamp)
+        != campaign_escrow.treasury.total_funds
+    {
+        msg!("Full refunds are only available if no funds have been paid out.");
+        return Err(CampaignTreasuryManagerError::InvalidRefundRequest.into());
+    }
+
+    // The NFT is burned in exchange for the full deposit amount.
+    token::burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: nft_mint.to_account_info(),
+                from: nft_token_account.to_account_info(),
+                authority: nft_holder.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    let refund_amount = deposit_record.processed_deposit_amount;
+    transfer_from_treasury_escrow(
+        campaign_escrow,
+        treasury_escrow.to_account_info(),
+        refund_receiver.to_account_info(),
+        token_program,
+        system_program,
+        refund_amount,
+    )?;
+    campaign_escrow.record_refund(refund_amount, deposit_record.processed_deposit_amount)?;
+
+    emit!(RefundProcessed {
+        campaign_escrow: campaign_escrow.key(),
+        record: deposit_record.key(),
+        refund_receiver: refund_receiver.key(),
+        refund_amount,
+    });
+
+    msg!(
+        "Refunded {} tokens to {} and burned NFT mint {}.",
+        refund_amount,
+        refund_receiver.key(),
+        nft_mint.key()
+    );
+
     Ok(())
 }
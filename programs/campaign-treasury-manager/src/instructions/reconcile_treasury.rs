@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::constants::PROGRAM_PREFIX;
+use crate::{
+    assert_keys_equal, is_native_mint, transfer_from_treasury_escrow, CampaignEscrow,
+    CampaignTreasuryManagerError, TreasuryEscrow,
+};
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct ReconcileTreasury<'info> {
+    #[account(
+        mut,
+        constraint = campaign_escrow.treasury.treasury_escrow.key() == treasury_escrow.key(),
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    /// CHECK: Validated in instruction.
+    #[account(
+        mut,
+        seeds = [
+            PROGRAM_PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            TreasuryEscrow::PREFIX.as_bytes()
+        ],
+        bump = campaign_escrow.treasury.bump,
+    )]
+    treasury_escrow: UncheckedAccount<'info>,
+    /// CHECK: Validated in instruction against the CampaignEscrow surplus_policy.
+    /// This account is not used if the surplus is held for supporters.
+    #[account(mut)]
+    surplus_receiver: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_reconcile_treasury(ctx: Context<ReconcileTreasury>) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let treasury_escrow = &ctx.accounts.treasury_escrow;
+    let surplus_receiver = &ctx.accounts.surplus_receiver;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    // Native treasury escrows are system accounts, which must keep the rent
+    // exempt minimum balance while they hold any funds.
+    let treasury_balance = if is_native_mint(&campaign_escrow.treasury.treasury_mint) {
+        let rent = Rent::get()?;
+        treasury_escrow
+            .lamports()
+            .saturating_sub(rent.minimum_balance(0))
+    } else {
+        let ref_data = treasury_escrow.try_borrow_data()?;
+        let mut account_data: &[u8] = &ref_data;
+        TokenAccount::try_deserialize(&mut account_data)?.amount
+    };
+
+    let accounted_funds = campaign_escrow.get_accounted_treasury_funds();
+    let surplus_amount = treasury_balance.saturating_sub(accounted_funds);
+    if surplus_amount == 0 {
+        msg!(
+            "TreasuryEscrow balance {} does not exceed the accounted funds {}, there is no surplus to reconcile.",
+            treasury_balance,
+            accounted_funds
+        );
+        return Ok(());
+    }
+
+    campaign_escrow.record_surplus(surplus_amount);
+
+    let expected_surplus_receiver = match campaign_escrow.get_surplus_receiver() {
+        Some(expected_surplus_receiver) => expected_surplus_receiver,
+        None => {
+            msg!(
+                "Recorded surplus of {} which is held in the TreasuryEscrow for supporters.",
+                surplus_amount
+            );
+            return Ok(());
+        }
+    };
+
+    assert_keys_equal(
+        &surplus_receiver.key(),
+        &expected_surplus_receiver,
+        Some(CampaignTreasuryManagerError::InvalidSurplusReceiver),
+    )?;
+
+    transfer_from_treasury_escrow(
+        campaign_escrow,
+        treasury_escrow.to_account_info(),
+        surplus_receiver.to_account_info(),
+        token_program,
+        system_program,
+        surplus_amount,
+    )?;
+
+    msg!(
+        "Recorded surplus of {} and transferred it to {} according to the {:?} surplus policy.",
+        surplus_amount,
+        surplus_receiver.key(),
+        campaign_escrow.surplus_policy
+    );
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{CampaignEscrow, CampaignTreasuryManagerError, PriceOracle};
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct SetDepositEscrowPriceOracle<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    deposit_escrow_mint: Account<'info, Mint>,
+    /// CHECK: Validated in instruction.
+    #[account()]
+    price_oracle: UncheckedAccount<'info>,
+    #[account()]
+    authority: Signer<'info>,
+}
+
+pub fn handle_set_deposit_escrow_price_oracle(
+    ctx: Context<SetDepositEscrowPriceOracle>,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let deposit_escrow_mint = &ctx.accounts.deposit_escrow_mint;
+    let price_oracle = &ctx.accounts.price_oracle;
+
+    if campaign_escrow
+        .get_deposit_escrow_info(&deposit_escrow_mint.key())
+        .is_none()
+    {
+        msg!(
+            "No DepositEscrow exists for deposit_escrow_mint {}.",
+            deposit_escrow_mint.key()
+        );
+        return Err(CampaignTreasuryManagerError::InvalidDepositEscrowAccount.into());
+    }
+
+    // The price itself is checked when it is used, this only checks that the
+    // account is a Pyth price account.
+    PriceOracle::try_from_account_info(&price_oracle.to_account_info())?;
+
+    campaign_escrow
+        .set_deposit_escrow_price_oracle(&deposit_escrow_mint.key(), &price_oracle.key());
+
+    msg!(
+        "Set price oracle {} for currency mint {} for CampaignEscrow with campaign_uuid {}.",
+        price_oracle.key(),
+        deposit_escrow_mint.key(),
+        campaign_escrow.campaign_uuid
+    );
+
+    Ok(())
+}
@@ -6,9 +6,10 @@ use anchor_spl::{
 
 use crate::{
     assert_campaign_end_time_is_valid, assert_valid_escrow_management_signers, cmp_pubkeys,
-    constants::PROGRAM_PREFIX, create_treasury_escrow_and_assert_is_valid, CampaignEscrow,
-    CampaignTreasuryManagerError, NonVotingPayoutPhaseInput, PayoutPhases, Treasury,
-    TreasuryEscrow, VotingPayoutPhaseInput,
+    constants::PROGRAM_PREFIX, create_treasury_escrow_and_assert_is_valid,
+    realloc_account_if_needed, CampaignEscrow, CampaignTreasuryManagerError, CrankReward,
+    CreateTreasuryEscrowAccounts, PayoutPhaseInput, PayoutPhases, RewardTierInput, RewardTiers,
+    SurplusPolicy, Treasury, TreasuryEscrow,
 };
 
 #[derive(Accounts)]
@@ -60,8 +61,20 @@ pub struct UpdateCampaignEscrowInput {
     pub authority: Option<Pubkey>,
     pub creator: Option<Pubkey>,
     pub campaign_end_time: Option<i64>,
-    pub non_voting_payout_phases: Option<Vec<NonVotingPayoutPhaseInput>>,
-    pub voting_payout_phases: Option<Vec<VotingPayoutPhaseInput>>,
+    pub payout_phases: Option<Vec<PayoutPhaseInput>>,
+    pub surplus_policy: Option<SurplusPolicy>,
+    pub crank_reward: Option<CrankReward>,
+    pub collection_mint: Option<Pubkey>,
+    pub deposit_escrow_infos_capacity: Option<u8>,
+    // Some(None) clears the fundraising_start_time, which is only allowed
+    // before the first deposit.
+    pub fundraising_start_time: Option<Option<i64>>,
+    pub deposit_grace_period: Option<i64>,
+    pub reward_tiers: Option<Vec<RewardTierInput>>,
+    // Some(None) clears the hard_cap, which is only allowed before the first
+    // deposit.
+    pub hard_cap: Option<Option<u64>>,
+    pub auto_end_at_hard_cap: Option<bool>,
 }
 
 pub fn handle_update_escrow(
@@ -97,21 +110,31 @@ pub fn handle_update_escrow(
         authority,
         creator,
         campaign_end_time,
-        non_voting_payout_phases,
-        voting_payout_phases,
+        payout_phases,
+        surplus_policy,
+        crank_reward,
+        collection_mint,
+        deposit_escrow_infos_capacity,
+        fundraising_start_time,
+        deposit_grace_period,
+        reward_tiers,
+        hard_cap,
+        auto_end_at_hard_cap,
     } = update_campaign_escrow_input;
 
     create_treasury_escrow_and_assert_is_valid(
-        campaign_escrow.to_account_info(),
-        treasury_escrow.to_account_info(),
-        treasury_mint.to_account_info(),
-        payout_wallet.to_account_info(),
-        payout_wallet_owner.to_account_info(),
-        payer.to_account_info(),
-        ata_program,
-        token_program,
-        system_program,
-        rent,
+        CreateTreasuryEscrowAccounts {
+            campaign_escrow: campaign_escrow.to_account_info(),
+            treasury_escrow: treasury_escrow.to_account_info(),
+            treasury_mint: treasury_mint.to_account_info(),
+            payout_wallet: payout_wallet.to_account_info(),
+            payout_wallet_owner: payout_wallet_owner.to_account_info(),
+            payer: payer.to_account_info(),
+            ata_program,
+            token_program,
+            system_program,
+            rent,
+        },
         &[],
         treasury_bump,
         campaign_uuid,
@@ -131,6 +154,12 @@ pub fn handle_update_escrow(
             return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
         }
 
+        // The MatchingPool funds are held in the treasury currency.
+        if campaign_escrow.has_matching_pool {
+            msg!("Cannot update CampaignEscrow treasury_mint after a MatchingPool was funded.");
+            return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
+        }
+
         msg!(
             "Updating treasury_mint from {} to {}.",
             campaign_escrow.treasury.treasury_mint,
@@ -141,6 +170,18 @@ pub fn handle_update_escrow(
             treasury_mint: treasury_mint.key(),
             treasury_escrow: treasury_escrow.key(),
             total_funds: 0,
+            total_paid_out: 0,
+            total_refunded: 0,
+            total_surplus: 0,
+            supporter_surplus: 0,
+            supporter_surplus_paid_out: 0,
+            total_crank_rewards: 0,
+            pending_estimated_funds: 0,
+            total_donations: 0,
+            non_voting_donations: 0,
+            non_refundable_donations: 0,
+            total_matched: 0,
+            closed_deposit_amount: 0,
         };
     }
 
@@ -164,6 +205,13 @@ pub fn handle_update_escrow(
 
     if let Some(campaign_end_time) = campaign_end_time {
         assert_campaign_end_time_is_valid(clock.unix_timestamp, campaign_end_time)?;
+        // New payout_phases are validated against the new campaign_end_time
+        // below, otherwise the existing ones must still be valid for it.
+        if payout_phases.is_none() {
+            campaign_escrow
+                .payout_phases
+                .assert_is_valid(campaign_end_time)?;
+        }
         msg!(
             "Updating campaign_end_time from {} to {}.",
             campaign_escrow.campaign_end_time,
@@ -172,17 +220,172 @@ pub fn handle_update_escrow(
         campaign_escrow.campaign_end_time = campaign_end_time;
     }
 
-    if let Some(non_voting_payout_phases) = non_voting_payout_phases {
+    if let Some(deposit_escrow_infos_capacity) = deposit_escrow_infos_capacity {
+        campaign_escrow
+            .assert_deposit_escrow_infos_capacity_is_valid(deposit_escrow_infos_capacity)?;
+        msg!(
+            "Updating deposit_escrow_infos_capacity from {} to {}.",
+            campaign_escrow.deposit_escrow_infos_capacity,
+            deposit_escrow_infos_capacity
+        );
+        let space = CampaignEscrow::get_space(
+            campaign_escrow.payout_phases.len(),
+            deposit_escrow_infos_capacity,
+            campaign_escrow.reward_tiers.len(),
+        )?;
+
+        realloc_account_if_needed(
+            campaign_escrow.to_account_info(),
+            payer.to_account_info(),
+            system_program.to_account_info(),
+            space,
+        )?;
+
+        campaign_escrow.deposit_escrow_infos_capacity = deposit_escrow_infos_capacity;
+    }
+
+    if let Some(payout_phases) = payout_phases {
         msg!("Updating payout_phases.");
-        let payout_phases = PayoutPhases::new(
-            non_voting_payout_phases,
-            voting_payout_phases.unwrap_or_default(),
-            campaign_escrow.campaign_end_time,
+        let space = CampaignEscrow::get_space(
+            payout_phases.len(),
+            campaign_escrow.deposit_escrow_infos_capacity,
+            campaign_escrow.reward_tiers.len(),
+        )?;
+        let payout_phases = PayoutPhases::new(payout_phases, campaign_escrow.campaign_end_time)?;
+
+        realloc_account_if_needed(
+            campaign_escrow.to_account_info(),
+            payer.to_account_info(),
+            system_program.to_account_info(),
+            space,
         )?;
 
         campaign_escrow.payout_phases = payout_phases;
     }
 
+    if let Some(surplus_policy) = surplus_policy {
+        msg!(
+            "Updating surplus_policy from {:?} to {:?}.",
+            campaign_escrow.surplus_policy,
+            surplus_policy
+        );
+        campaign_escrow.surplus_policy = surplus_policy;
+    }
+
+    if let Some(crank_reward) = crank_reward {
+        crank_reward.assert_is_valid()?;
+        msg!(
+            "Updating crank_reward from {:?} to {:?}.",
+            campaign_escrow.crank_reward,
+            crank_reward
+        );
+        campaign_escrow.crank_reward = crank_reward;
+    }
+
+    if let Some(collection_mint) = collection_mint {
+        // Existing DepositRecords were verified against the current collection.
+        if campaign_escrow.deposit_count > 0 {
+            msg!(
+                "Cannot update CampaignEscrow collection_mint after deposits have been made, current deposit count = {}.",
+                campaign_escrow.deposit_count
+            );
+            return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
+        }
+
+        msg!(
+            "Updating collection_mint from {} to {}.",
+            campaign_escrow.collection_mint,
+            collection_mint
+        );
+        campaign_escrow.collection_mint = collection_mint;
+    }
+
+    if let Some(reward_tiers) = reward_tiers {
+        // Existing DepositRecords claimed tiers by index and the sold counts
+        // would be reset.
+        if campaign_escrow.deposit_count > 0 {
+            msg!(
+                "Cannot update CampaignEscrow reward_tiers after deposits have been made, current deposit count = {}.",
+                campaign_escrow.deposit_count
+            );
+            return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
+        }
+
+        msg!("Updating reward_tiers.");
+        let space = CampaignEscrow::get_space(
+            campaign_escrow.payout_phases.len(),
+            campaign_escrow.deposit_escrow_infos_capacity,
+            reward_tiers.len(),
+        )?;
+        let reward_tiers = RewardTiers::new(reward_tiers)?;
+
+        realloc_account_if_needed(
+            campaign_escrow.to_account_info(),
+            payer.to_account_info(),
+            system_program.to_account_info(),
+            space,
+        )?;
+
+        campaign_escrow.reward_tiers = reward_tiers;
+    }
+
+    if let Some(fundraising_start_time) = fundraising_start_time {
+        if fundraising_start_time.is_none() && campaign_escrow.deposit_count > 0 {
+            msg!(
+                "Cannot clear the CampaignEscrow fundraising_start_time after deposits have been made, current deposit count = {}.",
+                campaign_escrow.deposit_count
+            );
+            return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
+        }
+
+        msg!(
+            "Updating fundraising_start_time from {:?} to {:?}.",
+            campaign_escrow.fundraising_start_time,
+            fundraising_start_time
+        );
+        campaign_escrow.fundraising_start_time = fundraising_start_time;
+    }
+
+    if let Some(deposit_grace_period) = deposit_grace_period {
+        msg!(
+            "Updating deposit_grace_period from {} to {}.",
+            campaign_escrow.deposit_grace_period,
+            deposit_grace_period
+        );
+        campaign_escrow.deposit_grace_period = deposit_grace_period;
+    }
+
+    if let Some(hard_cap) = hard_cap {
+        if hard_cap.is_none() && campaign_escrow.deposit_count > 0 {
+            msg!(
+                "Cannot clear the CampaignEscrow hard_cap after deposits have been made, current deposit count = {}.",
+                campaign_escrow.deposit_count
+            );
+            return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
+        }
+
+        msg!(
+            "Updating hard_cap from {:?} to {:?}.",
+            campaign_escrow.hard_cap,
+            hard_cap
+        );
+        campaign_escrow.hard_cap = hard_cap;
+        campaign_escrow.assert_hard_cap_is_valid()?;
+    }
+
+    if let Some(auto_end_at_hard_cap) = auto_end_at_hard_cap {
+        msg!(
+            "Updating auto_end_at_hard_cap from {} to {}.",
+            campaign_escrow.auto_end_at_hard_cap,
+            auto_end_at_hard_cap
+        );
+        campaign_escrow.auto_end_at_hard_cap = auto_end_at_hard_cap;
+    }
+
+    // The campaign_end_time and payout_phases bound the fundraising window, so
+    // this is checked after every update.
+    campaign_escrow.assert_fundraising_window_is_valid()?;
+
     msg!(
         "CampaignEscrow account with uuid {} updated by authority {}.",
         campaign_escrow.campaign_uuid,
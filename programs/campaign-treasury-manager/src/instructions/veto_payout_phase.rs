@@ -30,9 +30,10 @@ pub fn handle_veto_payout_phase(
     // TODO[@bonham000]: We probably want to verify that payouts have not
     // already begun for this payout phase?
 
+    let clock = Clock::get()?;
     campaign_escrow
         .payout_phases
-        .veto_payout_phase_by_authority(payout_phase_index);
+        .veto_payout_phase_by_authority(payout_phase_index, clock.unix_timestamp);
 
     msg!(
         "Authority {} vetoed payout phase with index {}.",
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    CampaignEscrow, CampaignTreasuryManagerError, DepositRecord, VetoVotesCast, VoteRecord,
+};
+
+#[derive(Accounts)]
+#[instruction(payout_phase_index: u8)]
+pub struct VoteToVetoPayoutPhase<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account(
+        seeds = [
+            DepositRecord::PREFIX.as_bytes(),
+            deposit_record.depositor.as_ref(),
+            deposit_record.mint.as_ref(),
+        ],
+        bump = deposit_record.bump,
+    )]
+    deposit_record: Account<'info, DepositRecord>,
+    #[account(
+        init,
+        seeds = [
+            VoteRecord::PREFIX.as_bytes(),
+            deposit_record.key().as_ref(),
+            &[payout_phase_index],
+        ],
+        bump,
+        payer = nft_holder,
+        space = VoteRecord::SPACE,
+    )]
+    vote_record: Account<'info, VoteRecord>,
+    // The current holder of the DepositRecord NFT, who may not be the
+    // original depositor.
+    #[account(mut)]
+    nft_holder: Signer<'info>,
+    #[account()]
+    nft_token_account: Account<'info, TokenAccount>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_vote_to_veto_payout_phase(
+    ctx: Context<VoteToVetoPayoutPhase>,
+    payout_phase_index: u8,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let deposit_record = &ctx.accounts.deposit_record;
+    let vote_record = &mut ctx.accounts.vote_record;
+    let nft_holder = &ctx.accounts.nft_holder;
+    let nft_token_account = &ctx.accounts.nft_token_account;
+
+    deposit_record.assert_belongs_to(&campaign_escrow.key())?;
+    deposit_record.assert_is_held_by(&nft_holder.key(), nft_token_account)?;
+
+    // Votes are weighted by the deposit amount in the treasury currency, which
+    // is only known once the deposit is processed.
+    if !deposit_record.deposit_processed {
+        msg!(
+            "DepositRecord {} has not been processed.",
+            deposit_record.key()
+        );
+        return Err(CampaignTreasuryManagerError::InvalidVote.into());
+    }
+
+    let veto_votes = deposit_record.processed_deposit_amount;
+    let clock = Clock::get()?;
+    campaign_escrow.record_veto_votes(payout_phase_index, veto_votes, clock.unix_timestamp)?;
+
+    vote_record.bump = *ctx.bumps.get(VoteRecord::PREFIX).unwrap();
+    vote_record.deposit_record = deposit_record.key();
+    vote_record.payout_phase_index = payout_phase_index;
+    vote_record.voter = nft_holder.key();
+    vote_record.veto_votes = veto_votes;
+
+    emit!(VetoVotesCast {
+        campaign_escrow: campaign_escrow.key(),
+        payout_phase_index,
+        record: deposit_record.key(),
+        voter: nft_holder.key(),
+        veto_votes,
+    });
+    msg!(
+        "{} cast {} veto votes on payout phase with index {} for NFT mint {}.",
+        nft_holder.key(),
+        veto_votes,
+        payout_phase_index,
+        deposit_record.mint
+    );
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    CampaignEscrow, CampaignTreasuryManagerError, DonationRecord, VetoVotesCast, VoteRecord,
+};
+
+#[derive(Accounts)]
+#[instruction(payout_phase_index: u8)]
+pub struct VoteToVetoPayoutPhaseWithDonation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+        ],
+        bump = campaign_escrow.bump,
+    )]
+    campaign_escrow: Account<'info, CampaignEscrow>,
+    #[account(
+        has_one = donor,
+        seeds = [
+            DonationRecord::PREFIX.as_bytes(),
+            campaign_escrow.key().as_ref(),
+            donation_record.donation_uuid.as_bytes(),
+        ],
+        bump = donation_record.bump,
+    )]
+    donation_record: Account<'info, DonationRecord>,
+    #[account(
+        init,
+        seeds = [
+            VoteRecord::PREFIX.as_bytes(),
+            donation_record.key().as_ref(),
+            &[payout_phase_index],
+        ],
+        bump,
+        payer = donor,
+        space = VoteRecord::SPACE,
+    )]
+    vote_record: Account<'info, VoteRecord>,
+    #[account(mut)]
+    donor: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_vote_to_veto_payout_phase_with_donation(
+    ctx: Context<VoteToVetoPayoutPhaseWithDonation>,
+    payout_phase_index: u8,
+) -> Result<()> {
+    let campaign_escrow = &mut ctx.accounts.campaign_escrow;
+    let donation_record = &ctx.accounts.donation_record;
+    let vote_record = &mut ctx.accounts.vote_record;
+    let donor = &ctx.accounts.donor;
+
+    if !donation_record.has_vote_weight {
+        msg!(
+            "DonationRecord {} does not carry vote weight.",
+            donation_record.key()
+        );
+        return Err(CampaignTreasuryManagerError::InvalidVote.into());
+    }
+
+    let veto_votes = donation_record.donation_amount;
+    let clock = Clock::get()?;
+    campaign_escrow.record_veto_votes(payout_phase_index, veto_votes, clock.unix_timestamp)?;
+
+    vote_record.bump = *ctx.bumps.get(VoteRecord::PREFIX).unwrap();
+    vote_record.deposit_record = donation_record.key();
+    vote_record.payout_phase_index = payout_phase_index;
+    vote_record.voter = donor.key();
+    vote_record.veto_votes = veto_votes;
+
+    emit!(VetoVotesCast {
+        campaign_escrow: campaign_escrow.key(),
+        payout_phase_index,
+        record: donation_record.key(),
+        voter: donor.key(),
+        veto_votes,
+    });
+    msg!(
+        "{} cast {} veto votes on payout phase with index {} for DonationRecord {}.",
+        donor.key(),
+        veto_votes,
+        payout_phase_index,
+        donation_record.key()
+    );
+
+    Ok(())
+}
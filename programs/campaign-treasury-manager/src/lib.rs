@@ -5,11 +5,13 @@ use solana_security_txt::security_txt;
 
 pub mod constants;
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod utils;
 
 pub use errors::CampaignTreasuryManagerError;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 pub use utils::*;
@@ -58,6 +60,21 @@ pub mod campaign_treasury_manager {
         handle_close_deposit_escrow(ctx)
     }
 
+    // Stop new deposits in a DepositEscrow currency. Existing balances can
+    // still be processed and the DepositEscrow closed. Only the authority can
+    // do this.
+    pub fn disable_deposit_escrow(ctx: Context<DisableDepositEscrow>) -> Result<()> {
+        handle_disable_deposit_escrow(ctx)
+    }
+
+    // Set the Pyth price account used to estimate the treasury currency value
+    // of deposits in a DepositEscrow currency. Only the authority can do this.
+    pub fn set_deposit_escrow_price_oracle(
+        ctx: Context<SetDepositEscrowPriceOracle>,
+    ) -> Result<()> {
+        handle_set_deposit_escrow_price_oracle(ctx)
+    }
+
     // Update a CampaignEscrow account. The authority or creator can do this.
     pub fn update_escrow(
         ctx: Context<UpdateEscrow>,
@@ -68,6 +85,27 @@ pub mod campaign_treasury_manager {
         handle_update_escrow(ctx, campaign_uuid, treasury_bump, campaign_escrow_input)
     }
 
+    // Migrate a CampaignEscrow account created with the legacy payout phases
+    // layout to the current layout. Only the authority can do this.
+    pub fn migrate_campaign_escrow(
+        ctx: Context<MigrateCampaignEscrow>,
+        campaign_uuid: String,
+    ) -> Result<()> {
+        handle_migrate_campaign_escrow(ctx, campaign_uuid)
+    }
+
+    // Set the campaign_escrow of legacy DepositRecord accounts, passed in the
+    // remaining_accounts, which were created before it was recorded. Their NFT
+    // must be a verified member of the campaign collection, the collection_mint
+    // is set by the first migration of a legacy CampaignEscrow. Only the
+    // authority can do this.
+    pub fn migrate_deposit_records<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateDepositRecords<'info>>,
+        collection_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        handle_migrate_deposit_records(ctx, collection_mint)
+    }
+
     // Close a CampaignEscrow account. Only the authority can do this. This ix
     // closes the CampaignEscrow and associated TreasuryEscrow accounts.
     pub fn close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
@@ -75,21 +113,66 @@ pub mod campaign_treasury_manager {
     }
 
     // Create a deposit. This is step 1 of the 2 step deposit flow.
-    pub fn create_deposit(ctx: Context<CreateDeposit>, deposit_amount: u64) -> Result<()> {
+    pub fn create_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateDeposit<'info>>,
+        deposit_amount: u64,
+        reward_tier_index: Option<u8>,
+    ) -> Result<()> {
         // Depositing will create a DepositRecord account which records
         // the deposit for the campaign supporter. This account will also
         // store the mint of the associated NFT (which needs to be known for
-        // processing refunds later).
-        handle_create_deposit(ctx, deposit_amount)
+        // processing refunds later), and the reward tier it claimed, if the
+        // campaign has reward tiers.
+        handle_create_deposit(ctx, deposit_amount, reward_tier_index)
+    }
+
+    // Donate to a campaign directly in the treasury currency, without an NFT
+    // purchase. The donation counts toward the treasury total_funds and the
+    // donor chooses if it is refundable and if it carries vote weight.
+    // Anyone can do this during the fundraising window.
+    pub fn donate(
+        ctx: Context<Donate>,
+        donation_uuid: String,
+        donation_amount: u64,
+        refundable: bool,
+        has_vote_weight: bool,
+    ) -> Result<()> {
+        handle_donate(
+            ctx,
+            donation_uuid,
+            donation_amount,
+            refundable,
+            has_vote_weight,
+        )
+    }
+
+    // Fund a MatchingPool for a campaign, which matches deposits at the
+    // match_basis_points ratio up to the match_cap. Anyone can sponsor a
+    // campaign during the fundraising window.
+    pub fn fund_matching_pool(
+        ctx: Context<FundMatchingPool>,
+        match_basis_points: u16,
+        match_cap: u64,
+    ) -> Result<()> {
+        handle_fund_matching_pool(ctx, match_basis_points, match_cap)
+    }
+
+    // Close a MatchingPool after the deposit window and return the unused
+    // match to the sponsor. Only the sponsor can do this.
+    pub fn close_matching_pool(ctx: Context<CloseMatchingPool>) -> Result<()> {
+        handle_close_matching_pool(ctx)
     }
 
     // Process a DepositRecord account and transfer funds to the escrow account.
     // This is step 2 of the deposit flow.
-    pub fn process_deposit(ctx: Context<ProcessDeposit>) -> Result<()> {
-        // This processes a created deposit and transfers the deposited funds
-        // (swapping if necessary to the treasury currency) to the CampaignEscrow
-        // treasury_escrow account.
-        handle_process_deposit(ctx)
+    pub fn process_deposit(
+        ctx: Context<ProcessDeposit>,
+        processed_deposit_amount: u64,
+    ) -> Result<()> {
+        // This processes a deposit which is held in a DepositEscrow. The
+        // authority swaps it to the treasury currency and transfers the
+        // processed_deposit_amount to the CampaignEscrow treasury_escrow account.
+        handle_process_deposit(ctx, processed_deposit_amount)
     }
 
     // Handles closing a DepositRecord account.
@@ -97,14 +180,41 @@ pub mod campaign_treasury_manager {
         handle_close_deposit_record(ctx)
     }
 
+    // Close a batch of DepositRecord accounts, passed with their depositors
+    // and NFT holders in the remaining_accounts, and refund the rent to each
+    // depositor and its share of the supporter surplus to each NFT holder.
+    // Anyone can do this once all payouts are complete.
+    pub fn close_deposit_records<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseDepositRecords<'info>>,
+    ) -> Result<()> {
+        handle_close_deposit_records(ctx)
+    }
+
     // Pay out funds from a CampaignEscrow account for a given payout phase.
-    pub fn pay_out_funds(ctx: Context<PayOutFunds>) -> Result<()> {
-        handle_pay_out_funds(ctx)
+    // This is permissionless, and the payer can claim the CampaignEscrow
+    // crank_reward for triggering a due payout.
+    pub fn pay_out_funds<'info>(ctx: Context<'_, '_, '_, 'info, PayOutFunds<'info>>) -> Result<()> {
+        handle_pay_out_funds(ctx, PayoutMode::CurrentPhase)
+    }
+
+    // Pay out every payout phase which is due in a single transfer, for
+    // catching up on missed payouts. Takes the same accounts as PayOutFunds.
+    pub fn pay_out_all_due_funds<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayOutFunds<'info>>,
+    ) -> Result<()> {
+        handle_pay_out_funds(ctx, PayoutMode::AllDuePhases)
+    }
+
+    // Claim the amount vested by the current streaming payout phase since the
+    // last claim. Streaming payout phases are not paid out by PayOutFunds.
+    pub fn claim_streamed_payout(ctx: Context<ClaimStreamedPayout>) -> Result<()> {
+        handle_claim_streamed_payout(ctx)
     }
 
     // Process a full refund from a campaign supporter.
-    // Requires supporter burning campaign NFT and for the campaign goal
-    // to not be met.
+    // Refunds are claimed by the current holder of the DepositRecord NFT.
+    // Requires supporter burning campaign NFT and for the current payout
+    // phase to be vetoed before any funds were paid out.
     pub fn process_full_refund(ctx: Context<ProcessFullRefund>) -> Result<()> {
         // Refund the full deposit amount. Refund submitter must burn the
         // NFT purchased for the campaign (on their DepositRecord account).
@@ -112,15 +222,52 @@ pub mod campaign_treasury_manager {
     }
 
     // Process a partial refund from a campaign supporter.
-    // Happens during a payout phase and does not require burning the campaign NFT.
+    // Refunds are claimed by the current holder of the DepositRecord NFT.
+    // Happens when a payout phase is vetoed after funds were paid out and
+    // does not require burning the campaign NFT.
     pub fn process_partial_refund(ctx: Context<ProcessPartialRefund>) -> Result<()> {
         // Calculate and refund the amount proportional to the remaining
         // funds in the escrow and the original deposit amount.
         handle_process_partial_refund(ctx)
     }
 
+    // Process a refund for a refundable donation. Refunds are claimed by the
+    // donor, and are full or partial like deposit refunds depending on
+    // whether funds were paid out before the veto.
+    pub fn process_donation_refund(ctx: Context<ProcessDonationRefund>) -> Result<()> {
+        handle_process_donation_refund(ctx)
+    }
+
     // Allow the authority account to veto a payout phase.
     pub fn veto_payout_phase(ctx: Context<VetoPayoutPhase>, payout_phase_index: u8) -> Result<()> {
         handle_veto_payout_phase(ctx, payout_phase_index)
     }
+
+    // Cast veto votes on a voting payout phase, weighted by the deposit
+    // amount. Votes are cast by the current holder of the DepositRecord NFT,
+    // and each NFT can vote once per payout phase.
+    pub fn vote_to_veto_payout_phase(
+        ctx: Context<VoteToVetoPayoutPhase>,
+        payout_phase_index: u8,
+    ) -> Result<()> {
+        handle_vote_to_veto_payout_phase(ctx, payout_phase_index)
+    }
+
+    // Cast veto votes on a voting payout phase with a DonationRecord which
+    // carries vote weight, weighted by the donation amount. Votes are cast by
+    // the donor, once per payout phase.
+    pub fn vote_to_veto_payout_phase_with_donation(
+        ctx: Context<VoteToVetoPayoutPhaseWithDonation>,
+        payout_phase_index: u8,
+    ) -> Result<()> {
+        handle_vote_to_veto_payout_phase_with_donation(ctx, payout_phase_index)
+    }
+
+    // Reconcile the treasury_escrow balance against the treasury accounting.
+    // Funds which were sent to the treasury_escrow directly are recorded as
+    // surplus and routed according to the CampaignEscrow surplus_policy.
+    // Anyone can do this.
+    pub fn reconcile_treasury(ctx: Context<ReconcileTreasury>) -> Result<()> {
+        handle_reconcile_treasury(ctx)
+    }
 }
@@ -1,16 +1,23 @@
+use std::convert::TryInto;
+
 use anchor_lang::prelude::*;
 
+use spl_associated_token_account::get_associated_token_address;
+
 use crate::{
     assert_min_time_buffer,
     buffer_times::{MinBufferTimes, MinBufferTimesTrait},
-    cmp_pubkeys,
-    constants::ONE_HUNDRED_PERCENT_BASIS_POINTS,
-    CampaignTreasuryManagerError, PayoutPhaseEnum, PayoutPhases,
+    calculate_payout_amount, cmp_pubkeys, is_native_mint, CampaignTreasuryManagerError,
+    PayoutPhase, PayoutPhases, RewardTiers,
 };
 
 // This account is a PDA of the Formfunction campaign UUID.
 #[account]
 pub struct CampaignEscrow {
+    // Account layout version. This is the first field so that the
+    // MigrateCampaignEscrow ix can tell migrated accounts apart from legacy
+    // accounts, which start with the PDA bump instead.
+    pub version: u8,
     // PDA bump.
     pub bump: u8,
     // Campaign uuid, this is needed to more easily verify the PDA after the
@@ -39,14 +46,65 @@ pub struct CampaignEscrow {
     // Marks if payouts can begin or not. This field is updated by the
     // authority account.
     pub payouts_ready: bool,
+    // Determines where unsolicited funds found by the ReconcileTreasury ix
+    // are routed.
+    pub surplus_policy: SurplusPolicy,
+    // Reward paid out of each payout to whoever triggers the PayOutFunds ix.
+    pub crank_reward: CrankReward,
+    // Mint of the verified Metaplex collection which the campaign NFTs belong
+    // to. Deposits can only be made for NFTs in this collection.
+    pub collection_mint: Pubkey,
+    // Number of DepositEscrowInfo entries the account is sized for. This can
+    // be raised with the UpdateEscrow ix, which reallocates the account.
+    pub deposit_escrow_infos_capacity: u8,
+    // Deposits and donations are accepted from this time, if set, until the
+    // campaign_end_time.
+    pub fundraising_start_time: Option<i64>,
+    // Seconds after the campaign_end_time during which deposits are still
+    // accepted, so auctions which are settled late still create a deposit.
+    // This does not apply to donations.
+    pub deposit_grace_period: i64,
+    // Reward tiers which deposits claim. Campaigns without reward tiers accept
+    // any deposit amount.
+    pub reward_tiers: RewardTiers,
+    // Maximum funds to raise in the treasury currency, if set. Deposits and
+    // donations which would raise more are rejected.
+    pub hard_cap: Option<u64>,
+    // Marks if the campaign ends as soon as the hard_cap is reached.
+    pub auto_end_at_hard_cap: bool,
+    // Time the campaign ended because the hard_cap was reached, if it auto
+    // ends. Fundraising closes at this time, while the campaign_end_time which
+    // payouts and votes are scheduled from is unchanged.
+    pub hard_cap_reached_at: Option<i64>,
+    // The raise which the payout phase stretch goals were settled with. This
+    // is set by the first payout, when deposits are no longer accepted.
+    pub settled_raise: Option<u64>,
+    // Marks if a sponsor funded a MatchingPool, in which case deposits are
+    // matched from it.
+    pub has_matching_pool: bool,
     // Payout phases.
     pub payout_phases: PayoutPhases,
 }
 
 impl CampaignEscrow {
-    pub const DEPOSIT_ESCROW_INFOS_MAX_LEN: usize = 4;
+    pub const VERSION: u8 = 1;
+    pub const DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY: u8 = 4;
+    // Maximum deposit_escrow_infos_capacity. This only bounds the account size,
+    // similar to MAX_PAYOUT_PHASES.
+    pub const DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT: u8 = 16;
+
+    // Space required for a CampaignEscrow account with the given number of
+    // payout phases, deposit_escrow_infos_capacity and number of reward tiers.
+    pub fn get_space(
+        payout_phases_len: usize,
+        deposit_escrow_infos_capacity: u8,
+        reward_tiers_len: usize,
+    ) -> Result<usize> {
+        let deposit_escrow_infos_capacity =
+            deposit_escrow_infos_capacity.min(Self::DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT) as usize;
 
-    pub const SPACE: usize = 8 + // account discriminator
+        Ok(8 + // account discriminator
+        1 + // version
         1 + // bump
         32 + // campaign_uuid
         32 + // authority
@@ -54,14 +112,31 @@ impl CampaignEscrow {
         32 + // payout_wallet
         Treasury::SPACE + // treasury
         4 + // 4 bytes of overhead for deposit_escrow_infos vec
-        CampaignEscrow::DEPOSIT_ESCROW_INFOS_MAX_LEN * DepositEscrowInfo::SPACE + // deposit_escrow_infos
+        deposit_escrow_infos_capacity * DepositEscrowInfo::SPACE + // deposit_escrow_infos
         8 + // campaign_end_time
         8 + // deposit_count
         8 + // processed_deposit_count
         8 + // closed_deposit_record_count
         1 + // payouts_ready
-        PayoutPhases::SPACE + // payout_phases space
-        128; // extra padding
+        1 + // surplus_policy
+        CrankReward::SPACE + // crank_reward
+        32 + // collection_mint
+        1 + // deposit_escrow_infos_capacity
+        1 + // Option memory overhead
+        8 + // fundraising_start_time
+        8 + // deposit_grace_period
+        RewardTiers::get_space(reward_tiers_len) + // reward_tiers
+        1 + // Option memory overhead
+        8 + // hard_cap
+        1 + // auto_end_at_hard_cap
+        1 + // Option memory overhead
+        8 + // hard_cap_reached_at
+        1 + // Option memory overhead
+        8 + // settled_raise
+        1 + // has_matching_pool
+        PayoutPhases::get_space(payout_phases_len)? + // payout_phases space
+        128) // extra padding
+    }
 
     pub const PREFIX: &'static str = "campaign_escrow";
 
@@ -73,16 +148,533 @@ impl CampaignEscrow {
         self.processed_deposit_count = self.processed_deposit_count.checked_add(1).unwrap();
     }
 
-    pub fn increment_total_funds(&mut self, funds_to_add: u64) {
+    pub fn increment_total_funds(&mut self, funds_to_add: u64) -> Result<()> {
         self.treasury.total_funds = self.treasury.total_funds.checked_add(funds_to_add).unwrap();
+        self.treasury.assert_accounting_is_valid()
+    }
+
+    // Payouts and refunds are calculated from total_funds, so deposits can
+    // only be processed before any funds leave the treasury.
+    pub fn assert_deposits_can_be_processed(&self) -> Result<()> {
+        if self.treasury.total_paid_out > 0 || self.treasury.total_refunded > 0 {
+            msg!(
+                "Deposits must be processed before the first payout or refund, total_paid_out = {}, total_refunded = {}.",
+                self.treasury.total_paid_out,
+                self.treasury.total_refunded
+            );
+            return Err(CampaignTreasuryManagerError::InvalidProcessDeposit.into());
+        }
+
+        Ok(())
+    }
+
+    // Donations are transferred to the treasury_escrow directly, so they count
+    // toward total_funds immediately.
+    pub fn record_donation(
+        &mut self,
+        donation_amount: u64,
+        refundable: bool,
+        has_vote_weight: bool,
+    ) -> Result<()> {
+        self.treasury.total_donations = self
+            .treasury
+            .total_donations
+            .checked_add(donation_amount)
+            .unwrap();
+        if !refundable {
+            self.treasury.non_refundable_donations = self
+                .treasury
+                .non_refundable_donations
+                .checked_add(donation_amount)
+                .unwrap();
+        }
+        if !has_vote_weight {
+            self.treasury.non_voting_donations = self
+                .treasury
+                .non_voting_donations
+                .checked_add(donation_amount)
+                .unwrap();
+        }
+        self.increment_total_funds(donation_amount)
+    }
+
+    // Matched funds are transferred to the treasury_escrow with the deposit
+    // they match, so they count toward total_funds immediately.
+    pub fn record_match(&mut self, match_amount: u64) -> Result<()> {
+        self.treasury.total_matched = self
+            .treasury
+            .total_matched
+            .checked_add(match_amount)
+            .unwrap();
+        self.increment_total_funds(match_amount)
+    }
+
+    // Veto votes are weighted by the funds each supporter contributed, except
+    // for donations without vote weight. Sponsors do not vote with matched
+    // funds.
+    pub fn get_total_votes(&self) -> u64 {
+        self.treasury
+            .total_funds
+            .checked_sub(self.treasury.non_voting_donations)
+            .unwrap()
+            .checked_sub(self.treasury.total_matched)
+            .unwrap()
+    }
+
+    // Validates a deposit against the reward tier it claims, if the campaign
+    // has reward tiers. The tier_deposit_amount is the deposit value in the
+    // treasury currency.
+    pub fn record_reward_tier_sale(
+        &mut self,
+        reward_tier_index: Option<u8>,
+        tier_deposit_amount: u64,
+    ) -> Result<()> {
+        match reward_tier_index {
+            Some(reward_tier_index) => self
+                .reward_tiers
+                .record_sale(reward_tier_index, tier_deposit_amount),
+            None if self.reward_tiers.is_empty() => Ok(()),
+            None => {
+                msg!("Deposits must claim one of the campaign reward tiers.");
+                Err(CampaignTreasuryManagerError::InvalidRewardTier.into())
+            }
+        }
+    }
+
+    // Records the estimated treasury currency value of a deposit which is
+    // still held in a DepositEscrow.
+    pub fn increment_pending_estimated_funds(&mut self, estimated_funds_to_add: u64) {
+        self.treasury.pending_estimated_funds = self
+            .treasury
+            .pending_estimated_funds
+            .checked_add(estimated_funds_to_add)
+            .unwrap();
+    }
+
+    // A processed deposit counts toward total_funds with the amount it was
+    // swapped for, which replaces its estimated value.
+    pub fn record_processed_deposit(
+        &mut self,
+        processed_deposit_amount: u64,
+        estimated_deposit_amount: u64,
+    ) -> Result<()> {
+        self.increment_processed_deposit_count();
+        self.treasury.pending_estimated_funds = self
+            .treasury
+            .pending_estimated_funds
+            .checked_sub(estimated_deposit_amount)
+            .unwrap();
+        self.increment_total_funds(processed_deposit_amount)
+    }
+
+    // The funds raised so far, including the estimated value of deposits
+    // which have not been swapped into the treasury currency yet. This is
+    // used for campaign goal progress, while payouts use total_funds only.
+    pub fn get_estimated_total_funds(&self) -> u64 {
+        self.treasury
+            .total_funds
+            .checked_add(self.treasury.pending_estimated_funds)
+            .unwrap()
+    }
+
+    // Settles the payout phase stretch goals with the funds raised, including
+    // the estimated value of unprocessed deposits. This only happens once, so
+    // later changes to the funds do not change the payout schedule.
+    pub fn settle_stretch_goals_if_needed(&mut self) {
+        if self.settled_raise.is_some() {
+            return;
+        }
+
+        let raise = self.get_estimated_total_funds();
+        self.payout_phases.settle_stretch_goals(raise);
+        self.settled_raise = Some(raise);
     }
 
-    pub fn add_deposit_escrow_info(&mut self, mint: Pubkey, deposit_escrow_bump: u8) {
+    // Adds veto votes to a voting payout phase. Votes on a stretch goal payout
+    // phase only count once its stretch goal is settled, so the stretch goals
+    // are settled by the first vote after fundraising ends, and votes on
+    // stretch goal payout phases are rejected before then.
+    pub fn record_veto_votes(
+        &mut self,
+        payout_phase_index: u8,
+        veto_votes: u64,
+        now: i64,
+    ) -> Result<()> {
+        if now >= self.get_fundraising_end_time(true) {
+            self.settle_stretch_goals_if_needed();
+        }
+
+        let has_stretch_goal = self
+            .payout_phases
+            .get(payout_phase_index as usize)
+            .is_some_and(|payout_phase| payout_phase.stretch_goal.is_some());
+        if has_stretch_goal && self.settled_raise.is_none() {
+            msg!(
+                "Payout phase with index {} has a stretch goal, which is settled once fundraising ends.",
+                payout_phase_index
+            );
+            return Err(CampaignTreasuryManagerError::InvalidVote.into());
+        }
+
+        self.payout_phases
+            .record_veto_votes(payout_phase_index, veto_votes, now)
+    }
+
+    // Marks the current active payout phase as paid out with the amount which
+    // was transferred to the payout_wallet.
+    pub fn record_payout(&mut self, payout_amount: u64) -> Result<()> {
+        self.payout_phases
+            .mark_current_active_payout_phase_as_disbursed(payout_amount);
+        self.treasury.total_paid_out = self
+            .treasury
+            .total_paid_out
+            .checked_add(payout_amount)
+            .unwrap();
+        self.treasury.assert_accounting_is_valid()
+    }
+
+    // Records a claim from a streaming payout phase, whose full payout is the
+    // payout_amount.
+    pub fn record_streamed_payout(
+        &mut self,
+        payout_phase_index: usize,
+        claim_amount: u64,
+        payout_amount: u64,
+    ) -> Result<()> {
+        self.payout_phases
+            .record_streamed_payout(payout_phase_index, claim_amount, payout_amount);
+        self.treasury.total_paid_out = self
+            .treasury
+            .total_paid_out
+            .checked_add(claim_amount)
+            .unwrap();
+        self.treasury.assert_accounting_is_valid()
+    }
+
+    // Crank rewards are deducted from the payout they are paid for, so they are
+    // already included in total_paid_out.
+    pub fn record_crank_reward(&mut self, crank_reward_amount: u64) {
+        self.treasury.total_crank_rewards = self
+            .treasury
+            .total_crank_rewards
+            .checked_add(crank_reward_amount)
+            .unwrap();
+    }
+
+    // Donation refunds close the refunded DonationRecord. DonationRecords are
+    // not counted as closed DepositRecords.
+    pub fn record_donation_refund(&mut self, refund_amount: u64) -> Result<()> {
+        self.treasury.total_refunded = self
+            .treasury
+            .total_refunded
+            .checked_add(refund_amount)
+            .unwrap();
+        self.treasury.assert_accounting_is_valid()
+    }
+
+    // Refunds close the refunded DepositRecord, so they are also counted as
+    // closed DepositRecords.
+    pub fn record_refund(
+        &mut self,
+        refund_amount: u64,
+        processed_deposit_amount: u64,
+    ) -> Result<()> {
+        self.treasury.total_refunded = self
+            .treasury
+            .total_refunded
+            .checked_add(refund_amount)
+            .unwrap();
+        self.record_closed_deposit_record(processed_deposit_amount, 0);
+        self.treasury.assert_accounting_is_valid()
+    }
+
+    pub fn record_closed_deposit_record(
+        &mut self,
+        processed_deposit_amount: u64,
+        supporter_surplus_amount: u64,
+    ) {
+        self.closed_deposit_record_count = self.closed_deposit_record_count.checked_add(1).unwrap();
+        self.treasury.closed_deposit_amount = self
+            .treasury
+            .closed_deposit_amount
+            .checked_add(processed_deposit_amount)
+            .unwrap();
+        self.treasury.supporter_surplus_paid_out = self
+            .treasury
+            .supporter_surplus_paid_out
+            .checked_add(supporter_surplus_amount)
+            .unwrap();
+    }
+
+    // Processed deposits of DepositRecords which are still open. Donations
+    // and matched funds do not have DepositRecords.
+    pub fn get_open_deposit_amount(&self) -> u64 {
+        self.treasury
+            .total_funds
+            .checked_sub(self.treasury.total_donations)
+            .unwrap()
+            .checked_sub(self.treasury.total_matched)
+            .unwrap()
+            .saturating_sub(self.treasury.closed_deposit_amount)
+    }
+
+    pub fn get_undistributed_supporter_surplus(&self) -> u64 {
+        self.treasury
+            .supporter_surplus
+            .checked_sub(self.treasury.supporter_surplus_paid_out)
+            .unwrap()
+    }
+
+    // The share of the undistributed supporter surplus for a DepositRecord
+    // which is being closed, pro rata to the deposits which are still open.
+    // The last open DepositRecord receives the remainder.
+    pub fn calculate_supporter_surplus_share(&self, processed_deposit_amount: u64) -> u64 {
+        let open_deposit_amount = self.get_open_deposit_amount();
+        if open_deposit_amount == 0 {
+            return 0;
+        }
+
+        let share = (self.get_undistributed_supporter_surplus() as u128)
+            .checked_mul(processed_deposit_amount.min(open_deposit_amount) as u128)
+            .unwrap()
+            .checked_div(open_deposit_amount as u128)
+            .unwrap();
+        share as u64
+    }
+
+    // Refunds are available while the current payout phase is vetoed, from
+    // its payout_time until its refund_deadline. Payouts stop at a vetoed
+    // payout phase, so the funds which were not paid out are refunded.
+    pub fn assert_refund_is_available(&self, now: i64) -> Result<()> {
+        let (payout_index, payout_phase) = match self
+            .payout_phases
+            .get_current_active_payout_phase_for_payout()
+        {
+            Some(current_payout) => current_payout,
+            None => {
+                msg!("Refunds are not available once every payout phase is paid out.");
+                return Err(CampaignTreasuryManagerError::InvalidRefundRequest.into());
+            }
+        };
+
+        if !self.is_payout_phase_vetoed(payout_phase) {
+            msg!(
+                "Refunds are only available if the payout phase with index {} is vetoed.",
+                payout_index
+            );
+            return Err(CampaignTreasuryManagerError::InvalidRefundRequest.into());
+        }
+
+        if now < payout_phase.payout_time || now > payout_phase.refund_deadline {
+            msg!(
+                "Refunds for payout phase with index {} are available from {} until {}.",
+                payout_index,
+                payout_phase.payout_time,
+                payout_phase.refund_deadline
+            );
+            return Err(CampaignTreasuryManagerError::InvalidRefundRequest.into());
+        }
+
+        Ok(())
+    }
+
+    // The treasury funds which were not paid out, which supporters are
+    // refunded pro rata to their deposits. A streaming payout phase keeps what
+    // vested before its veto, since the payout_wallet can still claim it.
+    pub fn get_refundable_funds(&self, now: i64) -> u64 {
+        let claimable_stream_amount = match self
+            .payout_phases
+            .get_current_active_payout_phase_for_payout()
+        {
+            Some((_, payout_phase)) => payout_phase.calculate_claimable_stream_amount(
+                self.calculate_payout_amount_from_payout_basis_points(
+                    payout_phase.payout_basis_points,
+                ),
+                now,
+                self.get_total_votes(),
+            ),
+            None => 0,
+        };
+
+        self.treasury
+            .total_funds
+            .checked_sub(self.treasury.total_paid_out)
+            .unwrap()
+            .checked_sub(claimable_stream_amount)
+            .unwrap()
+    }
+
+    // The share of the refundable funds for a deposit or refundable donation
+    // of the given amount. Donations which are not refundable are left out,
+    // so the refundable funds are split among the refundable contributions.
+    pub fn calculate_refund_amount(&self, processed_deposit_amount: u64, now: i64) -> u64 {
+        let refundable_contributions = self
+            .treasury
+            .total_funds
+            .checked_sub(self.treasury.non_refundable_donations)
+            .unwrap();
+        if refundable_contributions == 0 {
+            return 0;
+        }
+
+        // Multiply before dividing in u128, as in calculate_payout_amount.
+        let refund_amount = (processed_deposit_amount as u128)
+            .checked_mul(self.get_refundable_funds(now) as u128)
+            .unwrap()
+            .checked_div(refundable_contributions as u128)
+            .unwrap();
+        refund_amount.try_into().unwrap()
+    }
+
+    // Sets the treasury accounting of an account migrated from the legacy
+    // layout, which did not have it. Refunds were never implemented in that
+    // layout, so nothing has been refunded.
+    pub(crate) fn recalculate_legacy_treasury_accounting(&mut self) {
+        self.payout_phases
+            .recalculate_legacy_paid_amounts(self.treasury.total_funds);
+        self.treasury.total_paid_out = self.payout_phases.get_total_paid_amount() as u64;
+        self.treasury.total_refunded = 0;
+    }
+
+    // The funds which the treasury_escrow should hold according to the
+    // treasury accounting. Anything above this was sent to the treasury_escrow
+    // directly and is surplus.
+    pub fn get_accounted_treasury_funds(&self) -> u64 {
+        self.treasury
+            .total_funds
+            .checked_sub(self.treasury.total_paid_out)
+            .unwrap()
+            .checked_sub(self.treasury.total_refunded)
+            .unwrap()
+            .checked_add(self.get_undistributed_supporter_surplus())
+            .unwrap()
+    }
+
+    // Records surplus funds found by the ReconcileTreasury ix. Surplus for
+    // supporters stays in the treasury_escrow, so it is accounted for from
+    // now on.
+    pub fn record_surplus(&mut self, surplus_amount: u64) {
+        self.treasury.total_surplus = self
+            .treasury
+            .total_surplus
+            .checked_add(surplus_amount)
+            .unwrap();
+
+        if self.holds_surplus_for_supporters() {
+            self.treasury.supporter_surplus = self
+                .treasury
+                .supporter_surplus
+                .checked_add(surplus_amount)
+                .unwrap();
+        }
+    }
+
+    // Surplus for supporters is distributed when their DepositRecords are
+    // closed. Once every DepositRecord is closed, it goes to the creator
+    // instead.
+    pub fn holds_surplus_for_supporters(&self) -> bool {
+        self.surplus_policy == SurplusPolicy::Supporters
+            && self.closed_deposit_record_count < self.deposit_count
+    }
+
+    // The account which surplus funds are transferred to, or None if the
+    // surplus is held in the treasury_escrow for supporters.
+    pub fn get_surplus_receiver(&self) -> Option<Pubkey> {
+        if self.holds_surplus_for_supporters() {
+            return None;
+        }
+
+        Some(self.get_remainder_receiver())
+    }
+
+    // Funds which are still accounted for when the escrow is closed, i.e.
+    // rounding dust from payouts and refunds which were not claimed before the
+    // refund_deadline, are routed like surplus. Supporters cannot claim them
+    // anymore, so the Supporters policy routes them to the payout_wallet.
+    pub fn get_remainder_receiver(&self) -> Pubkey {
+        match self.surplus_policy {
+            SurplusPolicy::Creator | SurplusPolicy::Supporters => self.payout_wallet,
+            SurplusPolicy::Authority => {
+                if is_native_mint(&self.treasury.treasury_mint) {
+                    self.authority
+                } else {
+                    get_associated_token_address(&self.authority, &self.treasury.treasury_mint)
+                }
+            }
+        }
+    }
+
+    // Refunds and supporter surplus are paid to the current NFT holder, or
+    // their ATA for token treasuries.
+    pub fn get_refund_receiver(&self, nft_holder: &Pubkey) -> Pubkey {
+        if is_native_mint(&self.treasury.treasury_mint) {
+            *nft_holder
+        } else {
+            get_associated_token_address(nft_holder, &self.treasury.treasury_mint)
+        }
+    }
+
+    pub fn add_deposit_escrow_info(&mut self, mint: Pubkey, deposit_escrow_bump: u8) -> Result<()> {
+        if self.deposit_escrow_infos.len() >= self.deposit_escrow_infos_capacity as usize {
+            msg!(
+                "CampaignEscrow already has {} DepositEscrow currencies, which is its capacity. The capacity can be raised with the UpdateEscrow ix.",
+                self.deposit_escrow_infos.len()
+            );
+            return Err(CampaignTreasuryManagerError::TooManyDepositEscrows.into());
+        }
+
         self.deposit_escrow_infos.push(DepositEscrowInfo {
             deposit_escrow_bump,
             mint,
             closed: false,
+            disabled: false,
+            price_oracle: Pubkey::default(),
         });
+        Ok(())
+    }
+
+    // Validates a new deposit_escrow_infos_capacity. The capacity can never be
+    // lower than the number of existing DepositEscrowInfo entries, since
+    // entries are kept after their DepositEscrow is closed.
+    pub fn assert_deposit_escrow_infos_capacity_is_valid(
+        &self,
+        deposit_escrow_infos_capacity: u8,
+    ) -> Result<()> {
+        if deposit_escrow_infos_capacity > Self::DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT {
+            msg!(
+                "Received deposit_escrow_infos_capacity {} but at most {} is allowed.",
+                deposit_escrow_infos_capacity,
+                Self::DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT
+            );
+            return Err(CampaignTreasuryManagerError::TooManyDepositEscrows.into());
+        }
+
+        if (deposit_escrow_infos_capacity as usize) < self.deposit_escrow_infos.len() {
+            msg!(
+                "deposit_escrow_infos_capacity {} is lower than the {} existing DepositEscrow currencies.",
+                deposit_escrow_infos_capacity,
+                self.deposit_escrow_infos.len()
+            );
+            return Err(CampaignTreasuryManagerError::InvalidUpdateEscrow.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn set_deposit_escrow_price_oracle(&mut self, mint: &Pubkey, price_oracle: &Pubkey) {
+        for val in self.deposit_escrow_infos.iter_mut() {
+            if cmp_pubkeys(&val.mint, mint) {
+                val.price_oracle = *price_oracle;
+            }
+        }
+    }
+
+    pub fn mark_deposit_escrow_as_disabled(&mut self, mint: &Pubkey) {
+        for val in self.deposit_escrow_infos.iter_mut() {
+            if cmp_pubkeys(&val.mint, mint) {
+                val.disabled = true;
+            }
+        }
     }
 
     pub fn mark_deposit_escrow_as_closed(&mut self, mint: &Pubkey) {
@@ -99,16 +691,169 @@ impl CampaignEscrow {
             .find(|val| cmp_pubkeys(&val.mint, mint))
     }
 
+    // Validates the fundraising window. Deposits in the grace period must land
+    // before the first payout, so that they are included in every payout and
+    // vote.
+    pub fn assert_fundraising_window_is_valid(&self) -> Result<()> {
+        if let Some(fundraising_start_time) = self.fundraising_start_time {
+            if fundraising_start_time >= self.campaign_end_time {
+                msg!(
+                    "fundraising_start_time {} must be before the campaign_end_time {}.",
+                    fundraising_start_time,
+                    self.campaign_end_time
+                );
+                return Err(CampaignTreasuryManagerError::InvalidFundraisingWindow.into());
+            }
+        }
+
+        if self.deposit_grace_period < 0 {
+            msg!(
+                "deposit_grace_period {} cannot be negative.",
+                self.deposit_grace_period
+            );
+            return Err(CampaignTreasuryManagerError::InvalidFundraisingWindow.into());
+        }
+
+        if let Some(first_payout_phase) = self.payout_phases.get(0) {
+            let deposit_end_time = self
+                .campaign_end_time
+                .checked_add(self.deposit_grace_period)
+                .unwrap();
+            if deposit_end_time >= first_payout_phase.payout_time {
+                msg!(
+                    "deposit_grace_period {} must end before the first payout_time {}.",
+                    self.deposit_grace_period,
+                    first_payout_phase.payout_time
+                );
+                return Err(CampaignTreasuryManagerError::InvalidFundraisingWindow.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Deposits are accepted until the deposit_grace_period after the
+    // campaign_end_time, and donations until the campaign_end_time. A campaign
+    // which auto ended at the hard_cap closes when it was reached instead.
+    pub fn get_fundraising_end_time(&self, include_deposit_grace_period: bool) -> i64 {
+        let campaign_end_time = self.hard_cap_reached_at.unwrap_or(self.campaign_end_time);
+        if include_deposit_grace_period {
+            campaign_end_time
+                .checked_add(self.deposit_grace_period)
+                .unwrap()
+        } else {
+            campaign_end_time
+        }
+    }
+
+    pub fn assert_is_in_fundraising_window(
+        &self,
+        now: i64,
+        include_deposit_grace_period: bool,
+    ) -> Result<()> {
+        let fundraising_end_time = self.get_fundraising_end_time(include_deposit_grace_period);
+
+        let is_before_start = self
+            .fundraising_start_time
+            .is_some_and(|fundraising_start_time| now < fundraising_start_time);
+        if is_before_start || now >= fundraising_end_time {
+            msg!(
+                "Fundraising is only open from {:?} until {}, current time = {}.",
+                self.fundraising_start_time,
+                fundraising_end_time,
+                now
+            );
+            return Err(CampaignTreasuryManagerError::OutsideFundraisingWindow.into());
+        }
+
+        Ok(())
+    }
+
+    // The hard_cap must leave room for raising funds and cannot be lowered
+    // below the funds which were already raised.
+    pub fn assert_hard_cap_is_valid(&self) -> Result<()> {
+        if let Some(hard_cap) = self.hard_cap {
+            let estimated_total_funds = self.get_estimated_total_funds();
+            if hard_cap == 0 || hard_cap < estimated_total_funds {
+                msg!(
+                    "hard_cap {} must be greater than 0 and at least the funds raised so far {}.",
+                    hard_cap,
+                    estimated_total_funds
+                );
+                return Err(CampaignTreasuryManagerError::InvalidHardCap.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Checks the hard_cap after a deposit or donation was recorded. Deposits
+    // in a DepositEscrow currency count with their estimated value, so they
+    // are only capped if the DepositEscrow has a price oracle.
+    pub fn assert_is_within_hard_cap(&self) -> Result<()> {
+        if let Some(hard_cap) = self.hard_cap {
+            let estimated_total_funds = self.get_estimated_total_funds();
+            if estimated_total_funds > hard_cap {
+                msg!(
+                    "Funds raised {} would exceed the hard_cap {}.",
+                    estimated_total_funds,
+                    hard_cap
+                );
+                return Err(CampaignTreasuryManagerError::HardCapExceeded.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // The funds which can still be raised before the hard_cap is reached, or
+    // u64::MAX if there is no hard_cap.
+    pub fn get_remaining_hard_cap(&self) -> u64 {
+        match self.hard_cap {
+            Some(hard_cap) => hard_cap.saturating_sub(self.get_estimated_total_funds()),
+            None => u64::MAX,
+        }
+    }
+
+    // Ends fundraising at the given time if the campaign is set to auto end
+    // and the hard_cap was reached. The campaign_end_time is left unchanged,
+    // so payout phases, voting windows and the MatchingPool close time are
+    // not affected.
+    pub fn end_campaign_if_hard_cap_reached(&mut self, now: i64) {
+        let hard_cap_reached = self
+            .hard_cap
+            .is_some_and(|hard_cap| self.get_estimated_total_funds() >= hard_cap);
+        if self.auto_end_at_hard_cap
+            && hard_cap_reached
+            && self.hard_cap_reached_at.is_none()
+            && now < self.campaign_end_time
+        {
+            msg!(
+                "hard_cap reached, ending fundraising at {} before the campaign_end_time {}.",
+                now,
+                self.campaign_end_time
+            );
+            self.hard_cap_reached_at = Some(now);
+        }
+    }
+
     pub fn calculate_payout_amount_from_payout_basis_points(
         &self,
         payout_basis_points: u16,
     ) -> u64 {
-        let total_funds = self.treasury.total_funds;
-        let basis_points = payout_basis_points as u64;
-        let payout_as_percentage: u64 = basis_points
-            .checked_div(ONE_HUNDRED_PERCENT_BASIS_POINTS as u64)
-            .unwrap();
-        total_funds.checked_mul(payout_as_percentage).unwrap()
+        calculate_payout_amount(self.treasury.total_funds, payout_basis_points)
+    }
+
+    // The crank reward for a payout, which is never more than the payout
+    // amount itself.
+    pub fn calculate_crank_reward_amount(&self, payout_amount: u64) -> u64 {
+        match self.crank_reward {
+            CrankReward::None => 0,
+            CrankReward::Fixed { amount } => amount.min(payout_amount),
+            CrankReward::BasisPoints { basis_points } => {
+                calculate_payout_amount(payout_amount, basis_points)
+            }
+        }
     }
 
     pub fn assert_can_close_campaign_escrow(&self) -> Result<()> {
@@ -122,6 +867,18 @@ impl CampaignEscrow {
         //     return Err(CampaignTreasuryManagerError::InvalidCloseEscrow.into());
         // }
 
+        // Supporter surplus is distributed by the CloseDepositRecords ix.
+        let undistributed_supporter_surplus = self.get_undistributed_supporter_surplus();
+        if undistributed_supporter_surplus > 0
+            && self.closed_deposit_record_count < self.deposit_count
+        {
+            msg!(
+                "Cannot close escrow yet as {} supporter surplus has not been distributed, close the remaining DepositRecord accounts first.",
+                undistributed_supporter_surplus
+            );
+            return Err(CampaignTreasuryManagerError::InvalidCloseEscrow.into());
+        }
+
         for val in self.deposit_escrow_infos.iter() {
             if !val.closed {
                 msg!(
@@ -135,21 +892,40 @@ impl CampaignEscrow {
         Ok(())
     }
 
+    pub fn is_payout_phase_vetoed(&self, payout_phase: &PayoutPhase) -> bool {
+        payout_phase.is_vetoed_by_authority
+            || payout_phase.is_vetoed_by_votes(self.get_total_votes())
+    }
+
     pub fn assert_all_payouts_are_complete(&self) -> Result<()> {
-        let next_payout = self
+        let clock = Clock::get()?;
+        self.assert_all_payouts_are_complete_at(clock.unix_timestamp)
+    }
+
+    // Payouts are complete once every payout phase is paid out, or once the
+    // refunds for a vetoed payout phase closed, since payouts stop there.
+    pub fn assert_all_payouts_are_complete_at(&self, now: i64) -> Result<()> {
+        let refund_deadline = match self
             .payout_phases
-            .get_current_active_payout_phase_for_payout();
-        if next_payout.is_some() {
-            msg!("Cannot close escrow yet as a payout still exists.");
-            return Err(CampaignTreasuryManagerError::InvalidCloseEscrow.into());
-        }
+            .get_current_active_payout_phase_for_payout()
+        {
+            Some((_, next_payout)) => {
+                if !self.is_payout_phase_vetoed(next_payout) {
+                    msg!("Cannot close escrow yet as a payout still exists.");
+                    return Err(CampaignTreasuryManagerError::InvalidCloseEscrow.into());
+                }
+                Some(next_payout.refund_deadline)
+            }
+            None => self
+                .payout_phases
+                .last()
+                .map(|last_payout| last_payout.refund_deadline),
+        };
 
-        if let Some(last_payout) = self.payout_phases.to_ordered_list().last() {
-            let refund_deadline = PayoutPhaseEnum::get_refund_deadline(last_payout);
-            let clock = Clock::get()?;
+        if let Some(refund_deadline) = refund_deadline {
             assert_min_time_buffer(
                 refund_deadline,
-                clock.unix_timestamp,
+                now,
                 MinBufferTimes::LAST_REFUND_DEADLINE_TO_CLOSE_ESCROW_IN_SECONDS,
                 CampaignTreasuryManagerError::InvalidCloseEscrow,
                 String::from("Min time buffer from last payout refund deadline violated, cannot close escrow yet.")
@@ -170,13 +946,21 @@ pub struct DepositEscrowInfo {
     // is used to ensure all of these accounts are closed before the main escrow
     // account is closed.
     pub closed: bool,
+    // Marks if new deposits in this currency are rejected. Existing balances
+    // can still be processed and the DepositEscrow can still be closed.
+    pub disabled: bool,
+    // Pyth price account used to estimate the treasury currency value of
+    // deposits in this currency. Pubkey::default() if there is none.
+    pub price_oracle: Pubkey,
 }
 
 impl DepositEscrowInfo {
     pub const SPACE: usize = 1 + // Option memory overhead
     1 + // deposit_escrow_bump
     32 + // mint
-    1; // closed
+    1 + // closed
+    1 + // disabled
+    32; // price_oracle
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -190,11 +974,498 @@ pub struct Treasury {
     // Total funds deposited into the escrow. Only incremented when processed
     // deposits occur. This represents the maximum amount deposited.
     pub total_funds: u64,
+    // Total funds transferred to the payout_wallet by payouts.
+    pub total_paid_out: u64,
+    // Total funds returned to campaign supporters by refunds.
+    pub total_refunded: u64,
+    // Total unsolicited funds found in the treasury_escrow by the
+    // ReconcileTreasury ix. These are never part of total_funds.
+    pub total_surplus: u64,
+    // Surplus funds which are held in the treasury_escrow to be distributed
+    // to supporters pro rata.
+    pub supporter_surplus: u64,
+    // Supporter surplus transferred to depositors when their DepositRecords
+    // are closed. This is part of supporter_surplus.
+    pub supporter_surplus_paid_out: u64,
+    // Total crank rewards paid out of payouts. This is part of total_paid_out.
+    pub total_crank_rewards: u64,
+    // Estimated treasury currency value of deposits which are still held in
+    // DepositEscrow accounts. These are not part of total_funds until the
+    // deposits are processed.
+    pub pending_estimated_funds: u64,
+    // Total funds from direct donations. This is part of total_funds.
+    pub total_donations: u64,
+    // Donated funds which do not carry vote weight. These are part of
+    // total_funds, but not of the total votes.
+    pub non_voting_donations: u64,
+    // Donated funds which are not refundable. These are part of total_funds,
+    // but refunds are split among the refundable contributions only.
+    pub non_refundable_donations: u64,
+    // Total funds matched from the MatchingPool. This is part of total_funds,
+    // but not of the total votes, and is not refundable to supporters.
+    pub total_matched: u64,
+    // Total processed_deposit_amount of closed DepositRecords. The supporter
+    // surplus is distributed pro rata to the DepositRecords which are still
+    // open.
+    pub closed_deposit_amount: u64,
 }
 
 impl Treasury {
     pub const SPACE: usize = 1 + // bump
         32 + // treasury_mint
         32 + // treasury_escrow
-        8; // total_funds
+        8 + // total_funds
+        8 + // total_paid_out
+        8 + // total_refunded
+        8 + // total_surplus
+        8 + // supporter_surplus
+        8 + // supporter_surplus_paid_out
+        8 + // total_crank_rewards
+        8 + // pending_estimated_funds
+        8 + // total_donations
+        8 + // non_voting_donations
+        8 + // non_refundable_donations
+        8 + // total_matched
+        8; // closed_deposit_amount
+
+    // Funds can only leave the treasury once, either as a payout or a refund.
+    pub fn assert_accounting_is_valid(&self) -> Result<()> {
+        let total_paid_out_and_refunded = self.total_paid_out as u128 + self.total_refunded as u128;
+
+        if total_paid_out_and_refunded > self.total_funds as u128 {
+            msg!(
+                "Treasury total_paid_out {} plus total_refunded {} exceeds total_funds {}.",
+                self.total_paid_out,
+                self.total_refunded,
+                self.total_funds
+            );
+            return Err(CampaignTreasuryManagerError::InvalidTreasuryAccounting.into());
+        }
+
+        Ok(())
+    }
+}
+
+// Determines where funds which were sent to the treasury_escrow directly,
+// bypassing deposits, are routed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurplusPolicy {
+    // Transferred to the payout_wallet.
+    Creator,
+    // Held in the treasury_escrow and distributed to supporters pro rata to
+    // their deposits when their DepositRecords are closed.
+    Supporters,
+    // Transferred to the authority, which returns the funds to the sender.
+    Authority,
+}
+
+// Optional reward for whoever triggers a due payout with the PayOutFunds ix,
+// which is deducted from the payout. Amounts are in the treasury_mint currency,
+// i.e. lamports for native treasuries.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrankReward {
+    None,
+    // A fixed amount per payout, capped at the payout amount.
+    Fixed { amount: u64 },
+    // A share of each payout.
+    BasisPoints { basis_points: u16 },
+}
+
+impl CrankReward {
+    pub const SPACE: usize = 1 + // enum variant
+        8; // largest variant field
+
+    // Rewards are meant to cover transaction costs, so a basis points reward is
+    // limited to a small share of the payout.
+    pub const MAX_BASIS_POINTS: u16 = 100;
+
+    pub fn assert_is_valid(&self) -> Result<()> {
+        if let CrankReward::BasisPoints { basis_points } = self {
+            if *basis_points > CrankReward::MAX_BASIS_POINTS {
+                msg!(
+                    "Crank reward basis_points {} exceeds the maximum of {}.",
+                    basis_points,
+                    CrankReward::MAX_BASIS_POINTS
+                );
+                return Err(CampaignTreasuryManagerError::InvalidCrankReward.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PayoutPhase, PayoutPhases, StretchGoal, VotingConfig};
+
+    fn get_campaign_escrow_for_test(total_funds: u64) -> CampaignEscrow {
+        let payout_phase = PayoutPhase {
+            payout_basis_points: 5_000,
+            payout_time: 0,
+            refund_deadline: 1,
+            description: String::from("Payout phase."),
+            is_paid_out: false,
+            paid_amount: 0,
+            is_vetoed_by_authority: false,
+            is_deactivated: false,
+            stretch_goal: None,
+            stream: None,
+            voting_config: None,
+        };
+
+        CampaignEscrow {
+            version: CampaignEscrow::VERSION,
+            bump: 255,
+            campaign_uuid: String::from("2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a"),
+            authority: Pubkey::default(),
+            creator: Pubkey::default(),
+            payout_wallet: Pubkey::default(),
+            treasury: Treasury {
+                bump: 255,
+                treasury_mint: Pubkey::default(),
+                treasury_escrow: Pubkey::default(),
+                total_funds,
+                total_paid_out: 0,
+                total_refunded: 0,
+                total_surplus: 0,
+                supporter_surplus: 0,
+                supporter_surplus_paid_out: 0,
+                total_crank_rewards: 0,
+                pending_estimated_funds: 0,
+                total_donations: 0,
+                non_voting_donations: 0,
+                non_refundable_donations: 0,
+                total_matched: 0,
+                closed_deposit_amount: 0,
+            },
+            deposit_escrow_infos: vec![],
+            campaign_end_time: 0,
+            deposit_count: 0,
+            processed_deposit_count: 0,
+            closed_deposit_record_count: 0,
+            payouts_ready: false,
+            surplus_policy: SurplusPolicy::Creator,
+            crank_reward: CrankReward::None,
+            collection_mint: Pubkey::default(),
+            deposit_escrow_infos_capacity: CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            fundraising_start_time: None,
+            deposit_grace_period: 0,
+            reward_tiers: RewardTiers::default(),
+            hard_cap: None,
+            auto_end_at_hard_cap: false,
+            hard_cap_reached_at: None,
+            settled_raise: None,
+            has_matching_pool: false,
+            payout_phases: PayoutPhases::from_ordered_list(vec![
+                payout_phase.clone(),
+                payout_phase,
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_treasury_accounting() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+
+        campaign_escrow.record_payout(500).unwrap();
+        campaign_escrow.record_refund(300, 300).unwrap();
+        assert_eq!(campaign_escrow.treasury.total_paid_out, 500);
+        assert_eq!(campaign_escrow.treasury.total_refunded, 300);
+        assert_eq!(
+            campaign_escrow.payout_phases.get(0).unwrap().paid_amount,
+            500
+        );
+
+        // Paying out the second phase in full would exceed the total funds.
+        assert!(campaign_escrow.record_payout(500).is_err());
+
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        assert!(campaign_escrow.record_refund(1_001, 1_000).is_err());
+
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.increment_total_funds(1).unwrap();
+        assert!(campaign_escrow.record_refund(1_001, 1_001).is_ok());
+    }
+
+    #[test]
+    fn test_record_surplus() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.record_payout(500).unwrap();
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 500);
+
+        campaign_escrow.record_surplus(100);
+        assert_eq!(campaign_escrow.treasury.total_surplus, 100);
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 500);
+        assert_eq!(
+            campaign_escrow.get_surplus_receiver(),
+            Some(campaign_escrow.payout_wallet)
+        );
+
+        campaign_escrow.surplus_policy = SurplusPolicy::Supporters;
+        campaign_escrow.deposit_count = 2;
+        campaign_escrow.record_surplus(50);
+        assert_eq!(campaign_escrow.treasury.total_surplus, 150);
+        assert_eq!(campaign_escrow.treasury.supporter_surplus, 50);
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 550);
+        assert_eq!(campaign_escrow.get_surplus_receiver(), None);
+    }
+
+    #[test]
+    fn test_supporter_surplus_distribution() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.surplus_policy = SurplusPolicy::Supporters;
+        campaign_escrow.deposit_count = 3;
+        campaign_escrow.record_surplus(101);
+
+        // A refunded deposit does not receive a share, so the surplus is
+        // distributed to the remaining deposits.
+        campaign_escrow.record_refund(200, 200).unwrap();
+        assert_eq!(campaign_escrow.get_open_deposit_amount(), 800);
+        assert_eq!(campaign_escrow.calculate_supporter_surplus_share(200), 25);
+        campaign_escrow.record_closed_deposit_record(200, 25);
+
+        // The last DepositRecord receives the remainder.
+        assert_eq!(campaign_escrow.calculate_supporter_surplus_share(600), 76);
+        campaign_escrow.record_closed_deposit_record(600, 76);
+        assert_eq!(campaign_escrow.get_undistributed_supporter_surplus(), 0);
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 800);
+
+        // Without open DepositRecords, surplus goes to the creator.
+        assert_eq!(
+            campaign_escrow.get_surplus_receiver(),
+            Some(campaign_escrow.payout_wallet)
+        );
+        campaign_escrow.record_surplus(10);
+        assert_eq!(campaign_escrow.treasury.supporter_surplus, 101);
+    }
+
+    #[test]
+    fn test_record_donation() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        assert_eq!(campaign_escrow.get_total_votes(), 1_000);
+
+        campaign_escrow.record_donation(300, false, true).unwrap();
+        campaign_escrow.record_donation(200, true, false).unwrap();
+        assert_eq!(campaign_escrow.treasury.total_funds, 1_500);
+        assert_eq!(campaign_escrow.treasury.total_donations, 500);
+        assert_eq!(campaign_escrow.treasury.non_voting_donations, 200);
+        assert_eq!(campaign_escrow.treasury.non_refundable_donations, 300);
+        assert_eq!(campaign_escrow.get_total_votes(), 1_300);
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 1_500);
+
+        // Refunds are split among the 1_200 of refundable contributions.
+        campaign_escrow.treasury.total_paid_out = 300;
+        assert_eq!(campaign_escrow.calculate_refund_amount(600, 0), 600);
+        assert_eq!(campaign_escrow.calculate_refund_amount(200, 0), 200);
+    }
+
+    #[test]
+    fn test_is_in_fundraising_window() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.campaign_end_time = 100;
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(0, true)
+            .is_ok());
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(100, true)
+            .is_err());
+
+        campaign_escrow.fundraising_start_time = Some(10);
+        campaign_escrow.deposit_grace_period = 20;
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(9, true)
+            .is_err());
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(10, false)
+            .is_ok());
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(100, false)
+            .is_err());
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(119, true)
+            .is_ok());
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(120, true)
+            .is_err());
+    }
+
+    #[test]
+    fn test_hard_cap() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.campaign_end_time = 100;
+        campaign_escrow.hard_cap = Some(1_500);
+        assert!(campaign_escrow.assert_hard_cap_is_valid().is_ok());
+        campaign_escrow.increment_pending_estimated_funds(500);
+        assert!(campaign_escrow.assert_is_within_hard_cap().is_ok());
+
+        // The campaign only ends early if it is set to auto end, and keeps
+        // its campaign_end_time.
+        campaign_escrow.end_campaign_if_hard_cap_reached(50);
+        assert_eq!(campaign_escrow.hard_cap_reached_at, None);
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(60, false)
+            .is_ok());
+        campaign_escrow.auto_end_at_hard_cap = true;
+        campaign_escrow.end_campaign_if_hard_cap_reached(50);
+        campaign_escrow.end_campaign_if_hard_cap_reached(60);
+        assert_eq!(campaign_escrow.hard_cap_reached_at, Some(50));
+        assert_eq!(campaign_escrow.campaign_end_time, 100);
+        assert!(campaign_escrow
+            .assert_is_in_fundraising_window(60, false)
+            .is_err());
+
+        campaign_escrow.increment_total_funds(1).unwrap();
+        assert!(campaign_escrow.assert_is_within_hard_cap().is_err());
+        assert!(campaign_escrow.assert_hard_cap_is_valid().is_err());
+        campaign_escrow.hard_cap = Some(0);
+        assert!(campaign_escrow.assert_hard_cap_is_valid().is_err());
+    }
+
+    #[test]
+    fn test_record_processed_deposit() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.increment_pending_estimated_funds(500);
+        campaign_escrow.increment_pending_estimated_funds(300);
+        assert_eq!(campaign_escrow.get_estimated_total_funds(), 1_800);
+
+        campaign_escrow.record_processed_deposit(450, 500).unwrap();
+        assert_eq!(campaign_escrow.processed_deposit_count, 1);
+        assert_eq!(campaign_escrow.treasury.total_funds, 1_450);
+        assert_eq!(campaign_escrow.treasury.pending_estimated_funds, 300);
+        assert_eq!(campaign_escrow.get_estimated_total_funds(), 1_750);
+    }
+
+    #[test]
+    fn test_settle_stretch_goals_if_needed() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.increment_pending_estimated_funds(500);
+        campaign_escrow.settle_stretch_goals_if_needed();
+        assert_eq!(campaign_escrow.settled_raise, Some(1_500));
+
+        // The stretch goals are only settled once.
+        campaign_escrow.increment_total_funds(500).unwrap();
+        campaign_escrow.settle_stretch_goals_if_needed();
+        assert_eq!(campaign_escrow.settled_raise, Some(1_500));
+    }
+
+    #[test]
+    fn test_record_veto_votes_settles_stretch_goals() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.campaign_end_time = 100;
+        campaign_escrow.deposit_grace_period = 10;
+        let voting_payout_phase = PayoutPhase {
+            payout_time: 200,
+            refund_deadline: 300,
+            voting_config: Some(VotingConfig {
+                voting_start_time: 50,
+                veto_votes: 0,
+                vote_basis_points_veto_threshold: 5_000,
+            }),
+            ..campaign_escrow.payout_phases.get(0).unwrap().clone()
+        };
+        let get_stretch_goal_payout_phase = |is_active_when_reached| PayoutPhase {
+            stretch_goal: Some(StretchGoal {
+                raise_threshold: 2_000,
+                is_active_when_reached,
+            }),
+            ..voting_payout_phase.clone()
+        };
+        campaign_escrow.payout_phases = PayoutPhases::from_ordered_list(vec![
+            voting_payout_phase.clone(),
+            get_stretch_goal_payout_phase(false),
+            get_stretch_goal_payout_phase(true),
+        ]);
+
+        // During fundraising, payout phases without a stretch goal can be
+        // voted on, but stretch goal payout phases cannot.
+        campaign_escrow.record_veto_votes(0, 10, 60).unwrap();
+        assert!(campaign_escrow.record_veto_votes(1, 10, 60).is_err());
+        // Deposits are still accepted during the deposit_grace_period.
+        assert!(campaign_escrow.record_veto_votes(2, 10, 105).is_err());
+        assert_eq!(campaign_escrow.settled_raise, None);
+
+        // The first vote after fundraising settles the stretch goals, so votes
+        // cannot land on a payout phase which is deactivated afterwards.
+        assert!(campaign_escrow.record_veto_votes(2, 10, 110).is_err());
+        assert_eq!(campaign_escrow.settled_raise, Some(1_000));
+        assert!(campaign_escrow.payout_phases.get(2).unwrap().is_deactivated);
+        campaign_escrow.record_veto_votes(1, 10, 110).unwrap();
+        let voting_config = campaign_escrow
+            .payout_phases
+            .get(1)
+            .unwrap()
+            .voting_config
+            .as_ref()
+            .unwrap();
+        assert_eq!(voting_config.veto_votes, 10);
+    }
+
+    #[test]
+    fn test_calculate_crank_reward_amount() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        assert_eq!(campaign_escrow.calculate_crank_reward_amount(500), 0);
+
+        campaign_escrow.crank_reward = CrankReward::Fixed { amount: 10 };
+        assert_eq!(campaign_escrow.calculate_crank_reward_amount(500), 10);
+        assert_eq!(campaign_escrow.calculate_crank_reward_amount(5), 5);
+
+        campaign_escrow.crank_reward = CrankReward::BasisPoints { basis_points: 100 };
+        assert_eq!(campaign_escrow.calculate_crank_reward_amount(500), 5);
+        assert!(campaign_escrow.crank_reward.assert_is_valid().is_ok());
+
+        let crank_reward = CrankReward::BasisPoints { basis_points: 101 };
+        assert!(crank_reward.assert_is_valid().is_err());
+    }
+
+    #[test]
+    fn test_deposit_escrow_infos_capacity() {
+        let mut campaign_escrow = get_campaign_escrow_for_test(1_000);
+        campaign_escrow.deposit_escrow_infos_capacity = 2;
+        campaign_escrow
+            .add_deposit_escrow_info(Pubkey::new_unique(), 255)
+            .unwrap();
+        campaign_escrow
+            .add_deposit_escrow_info(Pubkey::new_unique(), 255)
+            .unwrap();
+        assert!(campaign_escrow
+            .add_deposit_escrow_info(Pubkey::new_unique(), 255)
+            .is_err());
+        assert_eq!(campaign_escrow.deposit_escrow_infos.len(), 2);
+
+        assert!(campaign_escrow
+            .assert_deposit_escrow_infos_capacity_is_valid(1)
+            .is_err());
+        assert!(campaign_escrow
+            .assert_deposit_escrow_infos_capacity_is_valid(2)
+            .is_ok());
+        assert!(campaign_escrow
+            .assert_deposit_escrow_infos_capacity_is_valid(
+                CampaignEscrow::DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT + 1
+            )
+            .is_err());
+
+        // Space grows with the capacity, up to the capacity limit.
+        assert_eq!(
+            CampaignEscrow::get_space(2, 3, 0).unwrap()
+                - CampaignEscrow::get_space(2, 2, 0).unwrap(),
+            DepositEscrowInfo::SPACE
+        );
+        assert_eq!(
+            CampaignEscrow::get_space(2, u8::MAX, 0).unwrap(),
+            CampaignEscrow::get_space(2, CampaignEscrow::DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT, 0)
+                .unwrap()
+        );
+
+        let mint = campaign_escrow.deposit_escrow_infos[1].mint;
+        campaign_escrow.mark_deposit_escrow_as_disabled(&mint);
+        assert!(!campaign_escrow.deposit_escrow_infos[0].disabled);
+        assert!(
+            campaign_escrow
+                .get_deposit_escrow_info(&mint)
+                .unwrap()
+                .disabled
+        );
+    }
 }
@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{assert_keys_equal, cmp_pubkeys, CampaignTreasuryManagerError, ID};
 
 // This is a PDA of the depositor + NFT mint.
-// If this account exists, there is an eligible refund for that buyer + mint.
+// If this account exists, there is an eligible refund for the current holder
+// of the NFT mint. The depositor only reclaims the account rent.
 #[account]
 #[derive(Default, Debug)]
 pub struct DepositRecord {
@@ -25,6 +29,17 @@ pub struct DepositRecord {
     // to this deposit escrow account, but not swapped and transferred into
     // the treasury escrow yet.
     pub deposit_processed: bool,
+    // The CampaignEscrow this deposit was made to. DepositRecord PDAs are not
+    // derived from the campaign, so this is needed to validate that a
+    // DepositRecord belongs to a campaign.
+    pub campaign_escrow: Pubkey,
+    // Value of the initial_deposit_amount in the treasury currency, estimated
+    // with the DepositEscrow price oracle when the deposit was created. This is
+    // zero for deposits in the treasury currency, or without a price oracle.
+    pub estimated_deposit_amount: u64,
+    // Index of the CampaignEscrow reward tier this deposit claimed, if the
+    // campaign has reward tiers.
+    pub reward_tier_index: Option<u8>,
 }
 
 impl DepositRecord {
@@ -36,7 +51,99 @@ impl DepositRecord {
         8 + // initial_deposit_amount
         8 + // processed_deposit_amount
         1 + // deposit_processed
-        64; // extra padding
+        32 + // campaign_escrow
+        8 + // estimated_deposit_amount
+        1 + // Option memory overhead
+        1 + // reward_tier_index
+        32; // extra padding
 
     pub const PREFIX: &'static str = "deposit_record";
+
+    // Refund and vote rights follow the NFT, so they are authorized by the
+    // current holder rather than the original depositor.
+    pub fn assert_is_held_by(
+        &self,
+        nft_holder: &Pubkey,
+        nft_token_account: &TokenAccount,
+    ) -> Result<()> {
+        if !cmp_pubkeys(&nft_token_account.owner, nft_holder)
+            || !cmp_pubkeys(&nft_token_account.mint, &self.mint)
+            || nft_token_account.amount != 1
+        {
+            msg!(
+                "{} does not hold NFT mint {}, token account owner = {}, mint = {}, amount = {}.",
+                nft_holder,
+                self.mint,
+                nft_token_account.owner,
+                nft_token_account.mint,
+                nft_token_account.amount
+            );
+            return Err(CampaignTreasuryManagerError::InvalidNftHolder.into());
+        }
+
+        Ok(())
+    }
+
+    // Refunds are paid from the treasury_escrow, so only deposits which were
+    // transferred there can be refunded.
+    pub fn assert_is_processed(&self) -> Result<()> {
+        if !self.deposit_processed {
+            msg!(
+                "DepositRecord for NFT mint {} must be processed before it can be refunded.",
+                self.mint
+            );
+            return Err(CampaignTreasuryManagerError::InvalidRefundRequest.into());
+        }
+
+        Ok(())
+    }
+
+    // DepositRecords created before the campaign_escrow field was added have
+    // a default campaign_escrow. These must be migrated with the
+    // MigrateDepositRecords ix before they can be used.
+    pub fn is_legacy(&self) -> bool {
+        cmp_pubkeys(&self.campaign_escrow, &Pubkey::default())
+    }
+
+    pub fn assert_belongs_to(&self, campaign_escrow: &Pubkey) -> Result<()> {
+        if self.is_legacy() {
+            msg!(
+                "DepositRecord for NFT mint {} is a legacy account, it must be migrated with the MigrateDepositRecords ix first.",
+                self.mint
+            );
+            return Err(CampaignTreasuryManagerError::InvalidDepositRecord.into());
+        }
+
+        if !cmp_pubkeys(&self.campaign_escrow, campaign_escrow) {
+            msg!(
+                "DepositRecord for NFT mint {} belongs to CampaignEscrow {}, not {}.",
+                self.mint,
+                self.campaign_escrow,
+                campaign_escrow
+            );
+            return Err(CampaignTreasuryManagerError::InvalidDepositRecord.into());
+        }
+
+        Ok(())
+    }
+
+    // DepositRecords passed in the remaining_accounts are not validated by
+    // Anchor, so the PDA is checked manually.
+    pub fn assert_pda(&self, deposit_record: &Pubkey) -> Result<()> {
+        let expected_deposit_record_pda = Pubkey::create_program_address(
+            &[
+                DepositRecord::PREFIX.as_bytes(),
+                self.depositor.as_ref(),
+                self.mint.as_ref(),
+                &[self.bump],
+            ],
+            &ID,
+        )
+        .map_err(|_| CampaignTreasuryManagerError::InvalidDepositRecord)?;
+        assert_keys_equal(
+            &expected_deposit_record_pda,
+            deposit_record,
+            Some(CampaignTreasuryManagerError::InvalidDepositRecord),
+        )
+    }
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+// This is a PDA of the CampaignEscrow + donation uuid.
+// Donations are made directly in the treasury currency without an NFT
+// purchase, so refund and vote rights stay with the donor.
+#[account]
+#[derive(Default, Debug)]
+pub struct DonationRecord {
+    // PDA bump.
+    pub bump: u8,
+    // The CampaignEscrow this donation was made to.
+    pub campaign_escrow: Pubkey,
+    // Donation uuid, this is needed to more easily verify the PDA after the
+    // account is created.
+    pub donation_uuid: String,
+    // The pubkey of the campaign supporter this donation is coming from.
+    pub donor: Pubkey,
+    // The donation amount in the treasury currency. This is part of the
+    // treasury total_funds.
+    pub donation_amount: u64,
+    // Marks if the donor can be refunded like an NFT depositor, with the
+    // ProcessDonationRefund ix. Donations which are not refundable are left
+    // out of the refund pro rata.
+    pub refundable: bool,
+    // Marks if the donation carries veto votes weighted by the donation
+    // amount. Donations without vote weight are not part of the total votes.
+    pub has_vote_weight: bool,
+    // Donation time.
+    pub donation_time: i64,
+}
+
+impl DonationRecord {
+    // Maximum donation_uuid length in bytes, which is the PDA seed length
+    // limit.
+    pub const MAX_DONATION_UUID_LENGTH: usize = 32;
+
+    pub const SPACE: usize = 8 + // account discriminator
+        1 + // bump
+        32 + // campaign_escrow
+        4 + // string size allocation
+        DonationRecord::MAX_DONATION_UUID_LENGTH + // donation_uuid
+        32 + // donor
+        8 + // donation_amount
+        1 + // refundable
+        1 + // has_vote_weight
+        8 + // donation_time
+        32; // extra padding
+
+    pub const PREFIX: &'static str = "donation_record";
+}
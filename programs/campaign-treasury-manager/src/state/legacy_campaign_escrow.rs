@@ -0,0 +1,360 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::ONE_HUNDRED_PERCENT_BASIS_POINTS, CampaignEscrow, CrankReward, DepositEscrowInfo,
+    PayoutPhase, PayoutPhases, RewardTiers, SurplusPolicy, Treasury, VotingConfig,
+};
+
+// CampaignEscrow account layout from before payout phases were unified into a
+// single ordered list. Accounts in this layout cannot be deserialized as a
+// CampaignEscrow and must be converted with the MigrateCampaignEscrow ix.
+//
+// These types are frozen and should not be changed when the CampaignEscrow
+// layout changes. Instead, update the conversion below.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyCampaignEscrow {
+    pub bump: u8,
+    pub campaign_uuid: String,
+    pub authority: Pubkey,
+    pub creator: Pubkey,
+    pub payout_wallet: Pubkey,
+    pub treasury: LegacyTreasury,
+    pub deposit_escrow_infos: Vec<LegacyDepositEscrowInfo>,
+    pub campaign_end_time: i64,
+    pub deposit_count: u64,
+    pub processed_deposit_count: u64,
+    pub closed_deposit_record_count: u64,
+    pub payouts_ready: bool,
+    pub payout_phases: LegacyPayoutPhases,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyTreasury {
+    pub bump: u8,
+    pub treasury_mint: Pubkey,
+    pub treasury_escrow: Pubkey,
+    pub total_funds: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyDepositEscrowInfo {
+    pub deposit_escrow_bump: u8,
+    pub mint: Pubkey,
+    pub closed: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyPayoutPhases {
+    pub non_voting_payout_phases: Vec<LegacyNonVotingPayoutPhase>,
+    pub voting_payout_phases: Vec<LegacyVotingPayoutPhase>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacySharedPayoutPhaseFields {
+    pub index: u8,
+    pub payout_basis_points: u16,
+    pub payout_time: i64,
+    pub refund_deadline: i64,
+    pub description: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyNonVotingPayoutPhase {
+    pub shared_fields: LegacySharedPayoutPhaseFields,
+    pub is_paid_out: bool,
+    pub is_vetoed_by_authority: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyVotingPayoutPhase {
+    pub shared_fields: LegacySharedPayoutPhaseFields,
+    pub is_paid_out: bool,
+    pub is_vetoed_by_authority: bool,
+    pub voting_start_time: i64,
+    pub veto_votes: u64,
+    pub vote_basis_points_veto_threshold: u64,
+}
+
+// Payout amount calculation of the legacy program, which divided the basis
+// points before multiplying. Payout phases below 100% paid out nothing and a
+// single 100% payout phase paid out the total_funds.
+pub(crate) fn calculate_legacy_payout_amount(total_funds: u64, payout_basis_points: u16) -> u64 {
+    let payout_as_percentage = (payout_basis_points as u64)
+        .checked_div(ONE_HUNDRED_PERCENT_BASIS_POINTS as u64)
+        .unwrap();
+    total_funds.checked_mul(payout_as_percentage).unwrap()
+}
+
+impl From<LegacyPayoutPhases> for PayoutPhases {
+    fn from(legacy_payout_phases: LegacyPayoutPhases) -> Self {
+        let non_voting_payout_phases = legacy_payout_phases
+            .non_voting_payout_phases
+            .into_iter()
+            .map(|val| {
+                (
+                    val.shared_fields.index,
+                    PayoutPhase {
+                        payout_basis_points: val.shared_fields.payout_basis_points,
+                        payout_time: val.shared_fields.payout_time,
+                        refund_deadline: val.shared_fields.refund_deadline,
+                        description: val.shared_fields.description,
+                        is_paid_out: val.is_paid_out,
+                        paid_amount: 0,
+                        is_vetoed_by_authority: val.is_vetoed_by_authority,
+                        is_deactivated: false,
+                        stretch_goal: None,
+                        stream: None,
+                        voting_config: None,
+                    },
+                )
+            });
+
+        let voting_payout_phases =
+            legacy_payout_phases
+                .voting_payout_phases
+                .into_iter()
+                .map(|val| {
+                    (
+                        val.shared_fields.index,
+                        PayoutPhase {
+                            payout_basis_points: val.shared_fields.payout_basis_points,
+                            payout_time: val.shared_fields.payout_time,
+                            refund_deadline: val.shared_fields.refund_deadline,
+                            description: val.shared_fields.description,
+                            is_paid_out: val.is_paid_out,
+                            paid_amount: 0,
+                            is_vetoed_by_authority: val.is_vetoed_by_authority,
+                            is_deactivated: false,
+                            stretch_goal: None,
+                            stream: None,
+                            voting_config: Some(VotingConfig {
+                                voting_start_time: val.voting_start_time,
+                                veto_votes: val.veto_votes,
+                                vote_basis_points_veto_threshold: val
+                                    .vote_basis_points_veto_threshold,
+                            }),
+                        },
+                    )
+                });
+
+        // The legacy layout recorded the payout order with the index field.
+        let mut indexed_payout_phases: Vec<(u8, PayoutPhase)> = non_voting_payout_phases
+            .chain(voting_payout_phases)
+            .collect();
+        indexed_payout_phases.sort_by_key(|(index, _)| *index);
+
+        PayoutPhases::from_ordered_list(
+            indexed_payout_phases
+                .into_iter()
+                .map(|(_, payout_phase)| payout_phase)
+                .collect(),
+        )
+    }
+}
+
+impl From<LegacyCampaignEscrow> for CampaignEscrow {
+    fn from(legacy_campaign_escrow: LegacyCampaignEscrow) -> Self {
+        let mut campaign_escrow = CampaignEscrow {
+            version: CampaignEscrow::VERSION,
+            bump: legacy_campaign_escrow.bump,
+            campaign_uuid: legacy_campaign_escrow.campaign_uuid,
+            authority: legacy_campaign_escrow.authority,
+            creator: legacy_campaign_escrow.creator,
+            payout_wallet: legacy_campaign_escrow.payout_wallet,
+            treasury: Treasury {
+                bump: legacy_campaign_escrow.treasury.bump,
+                treasury_mint: legacy_campaign_escrow.treasury.treasury_mint,
+                treasury_escrow: legacy_campaign_escrow.treasury.treasury_escrow,
+                total_funds: legacy_campaign_escrow.treasury.total_funds,
+                total_paid_out: 0,
+                total_refunded: 0,
+                total_surplus: 0,
+                supporter_surplus: 0,
+                supporter_surplus_paid_out: 0,
+                total_crank_rewards: 0,
+                pending_estimated_funds: 0,
+                total_donations: 0,
+                non_voting_donations: 0,
+                non_refundable_donations: 0,
+                total_matched: 0,
+                closed_deposit_amount: 0,
+            },
+            deposit_escrow_infos: legacy_campaign_escrow
+                .deposit_escrow_infos
+                .into_iter()
+                .map(|val| DepositEscrowInfo {
+                    deposit_escrow_bump: val.deposit_escrow_bump,
+                    mint: val.mint,
+                    closed: val.closed,
+                    disabled: false,
+                    price_oracle: Pubkey::default(),
+                })
+                .collect(),
+            campaign_end_time: legacy_campaign_escrow.campaign_end_time,
+            deposit_count: legacy_campaign_escrow.deposit_count,
+            processed_deposit_count: legacy_campaign_escrow.processed_deposit_count,
+            closed_deposit_record_count: legacy_campaign_escrow.closed_deposit_record_count,
+            payouts_ready: legacy_campaign_escrow.payouts_ready,
+            // Unsolicited funds are returned via the authority unless the policy
+            // is changed with the UpdateEscrow ix.
+            surplus_policy: SurplusPolicy::Authority,
+            crank_reward: CrankReward::None,
+            // Deposits are rejected until the collection_mint is set with the
+            // UpdateEscrow ix.
+            collection_mint: Pubkey::default(),
+            deposit_escrow_infos_capacity: CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            fundraising_start_time: None,
+            deposit_grace_period: 0,
+            reward_tiers: RewardTiers::default(),
+            hard_cap: None,
+            auto_end_at_hard_cap: false,
+            hard_cap_reached_at: None,
+            settled_raise: None,
+            has_matching_pool: false,
+            payout_phases: legacy_campaign_escrow.payout_phases.into(),
+        };
+        campaign_escrow.recalculate_legacy_treasury_accounting();
+
+        campaign_escrow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_legacy_shared_fields(
+        index: u8,
+        payout_basis_points: u16,
+    ) -> LegacySharedPayoutPhaseFields {
+        LegacySharedPayoutPhaseFields {
+            index,
+            payout_basis_points,
+            payout_time: index as i64,
+            refund_deadline: index as i64 + 1,
+            description: format!("Payout phase {}.", index),
+        }
+    }
+
+    fn get_legacy_campaign_escrow(
+        total_funds: u64,
+        payout_phases: Vec<(u16, bool)>,
+    ) -> LegacyCampaignEscrow {
+        LegacyCampaignEscrow {
+            bump: 255,
+            campaign_uuid: String::from("campaign"),
+            authority: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            payout_wallet: Pubkey::new_unique(),
+            treasury: LegacyTreasury {
+                bump: 255,
+                treasury_mint: Pubkey::new_unique(),
+                treasury_escrow: Pubkey::new_unique(),
+                total_funds,
+            },
+            deposit_escrow_infos: vec![],
+            campaign_end_time: 0,
+            deposit_count: 0,
+            processed_deposit_count: 0,
+            closed_deposit_record_count: 0,
+            payouts_ready: true,
+            payout_phases: LegacyPayoutPhases {
+                non_voting_payout_phases: payout_phases
+                    .into_iter()
+                    .enumerate()
+                    .map(
+                        |(index, (payout_basis_points, is_paid_out))| LegacyNonVotingPayoutPhase {
+                            shared_fields: get_legacy_shared_fields(
+                                index as u8,
+                                payout_basis_points,
+                            ),
+                            is_paid_out,
+                            is_vetoed_by_authority: false,
+                        },
+                    )
+                    .collect(),
+                voting_payout_phases: vec![],
+            },
+        }
+    }
+
+    // The legacy program calculated payout amounts as
+    // total_funds * (payout_basis_points / 10_000) in integer arithmetic.
+    #[test]
+    fn test_legacy_paid_amounts_use_the_legacy_payout_calculation() {
+        let total_funds = 3_000_000;
+
+        // Payout phases below 100% transferred nothing.
+        let campaign_escrow: CampaignEscrow =
+            get_legacy_campaign_escrow(total_funds, vec![(5_000, true), (5_000, false)]).into();
+        assert_eq!(campaign_escrow.payout_phases.get(0).unwrap().paid_amount, 0);
+        assert_eq!(campaign_escrow.payout_phases.get(1).unwrap().paid_amount, 0);
+        assert_eq!(campaign_escrow.treasury.total_paid_out, 0);
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), total_funds);
+
+        // A single 100% payout phase transferred the total_funds.
+        let campaign_escrow: CampaignEscrow =
+            get_legacy_campaign_escrow(total_funds, vec![(10_000, true)]).into();
+        assert_eq!(
+            campaign_escrow.payout_phases.get(0).unwrap().paid_amount,
+            total_funds
+        );
+        assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+        assert_eq!(campaign_escrow.treasury.total_refunded, 0);
+        assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 0);
+    }
+
+    #[test]
+    fn test_legacy_payout_phases_conversion_preserves_order() {
+        let legacy_payout_phases = LegacyPayoutPhases {
+            non_voting_payout_phases: vec![LegacyNonVotingPayoutPhase {
+                shared_fields: get_legacy_shared_fields(1, 5_000),
+                is_paid_out: false,
+                is_vetoed_by_authority: true,
+            }],
+            voting_payout_phases: vec![
+                LegacyVotingPayoutPhase {
+                    shared_fields: get_legacy_shared_fields(2, 2_000),
+                    is_paid_out: false,
+                    is_vetoed_by_authority: false,
+                    voting_start_time: 10,
+                    veto_votes: 3,
+                    vote_basis_points_veto_threshold: 80,
+                },
+                LegacyVotingPayoutPhase {
+                    shared_fields: get_legacy_shared_fields(0, 3_000),
+                    is_paid_out: true,
+                    is_vetoed_by_authority: false,
+                    voting_start_time: 20,
+                    veto_votes: 0,
+                    vote_basis_points_veto_threshold: 50,
+                },
+            ],
+        };
+
+        let payout_phases: PayoutPhases = legacy_payout_phases.into();
+        let payout_phases: Vec<&PayoutPhase> = payout_phases.iter().collect();
+
+        assert_eq!(payout_phases.len(), 3);
+        for (index, payout_phase) in payout_phases.iter().enumerate() {
+            assert_eq!(payout_phase.payout_time, index as i64);
+            assert_eq!(payout_phase.description, format!("Payout phase {}.", index));
+        }
+
+        assert!(payout_phases[0].is_paid_out);
+        assert_eq!(
+            payout_phases[0]
+                .voting_config
+                .as_ref()
+                .unwrap()
+                .vote_basis_points_veto_threshold,
+            50
+        );
+        assert!(payout_phases[1].voting_config.is_none());
+        assert!(payout_phases[1].is_vetoed_by_authority);
+        assert_eq!(
+            payout_phases[2].voting_config.as_ref().unwrap().veto_votes,
+            3
+        );
+    }
+}
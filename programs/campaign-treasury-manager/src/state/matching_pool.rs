@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+// This is a PDA of the CampaignEscrow.
+// A sponsor funds the pool to match deposits at the match_basis_points ratio,
+// up to the match_cap. The funds are held in the MatchingPoolEscrow, and any
+// unused match is returned to the sponsor after the fundraising window.
+#[account]
+#[derive(Default, Debug)]
+pub struct MatchingPool {
+    // PDA bump.
+    pub bump: u8,
+    // Bump for the associated MatchingPoolEscrow PDA.
+    pub escrow_bump: u8,
+    // The CampaignEscrow whose deposits are matched.
+    pub campaign_escrow: Pubkey,
+    // The pubkey of the sponsor who funded the pool. Unused funds are
+    // returned to the sponsor.
+    pub sponsor: Pubkey,
+    // Match per deposit in basis points of the deposit value, e.g. 10_000
+    // matches every deposit 1:1.
+    pub match_basis_points: u16,
+    // Maximum total match in the treasury currency, which is the amount the
+    // sponsor funded.
+    pub match_cap: u64,
+    // Total funds matched into the treasury_escrow so far.
+    pub total_matched: u64,
+}
+
+impl MatchingPool {
+    pub const SPACE: usize = 8 + // account discriminator
+        1 + // bump
+        1 + // escrow_bump
+        32 + // campaign_escrow
+        32 + // sponsor
+        2 + // match_basis_points
+        8 + // match_cap
+        8 + // total_matched
+        32; // extra padding
+
+    pub const PREFIX: &'static str = "matching_pool";
+
+    // The sponsor funds which have not been matched.
+    pub fn get_unused_amount(&self) -> u64 {
+        self.match_cap.checked_sub(self.total_matched).unwrap()
+    }
+
+    // The match for a deposit of the given value in the treasury currency,
+    // which is limited by the unused funds and the max_match_amount, e.g. the
+    // room left below the CampaignEscrow hard_cap.
+    pub fn calculate_match_amount(&self, deposit_value: u64, max_match_amount: u64) -> u64 {
+        let match_amount = (deposit_value as u128 * self.match_basis_points as u128 / 10_000)
+            .min(u64::MAX as u128) as u64;
+
+        match_amount
+            .min(self.get_unused_amount())
+            .min(max_match_amount)
+    }
+
+    pub fn record_match(&mut self, match_amount: u64) {
+        self.total_matched = self.total_matched.checked_add(match_amount).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_matching_pool(match_basis_points: u16, match_cap: u64) -> MatchingPool {
+        MatchingPool {
+            match_basis_points,
+            match_cap,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_calculate_match_amount() {
+        let matching_pool = get_matching_pool(10_000, 1_000);
+        assert_eq!(matching_pool.calculate_match_amount(400, u64::MAX), 400);
+        assert_eq!(matching_pool.calculate_match_amount(1_500, u64::MAX), 1_000);
+        assert_eq!(matching_pool.calculate_match_amount(400, 250), 250);
+
+        let matching_pool = get_matching_pool(5_000, 1_000);
+        assert_eq!(matching_pool.calculate_match_amount(401, u64::MAX), 200);
+
+        let matching_pool = get_matching_pool(20_000, 1_000);
+        assert_eq!(matching_pool.calculate_match_amount(400, u64::MAX), 800);
+        assert_eq!(
+            matching_pool.calculate_match_amount(u64::MAX, u64::MAX),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_record_match() {
+        let mut matching_pool = get_matching_pool(10_000, 1_000);
+        matching_pool.record_match(400);
+        assert_eq!(matching_pool.get_unused_amount(), 600);
+        assert_eq!(matching_pool.calculate_match_amount(1_000, u64::MAX), 600);
+
+        matching_pool.record_match(600);
+        assert_eq!(matching_pool.get_unused_amount(), 0);
+        assert_eq!(matching_pool.calculate_match_amount(1_000, u64::MAX), 0);
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use bytemuck::bytes_of;
+
+// This account escrows the sponsor funds of a MatchingPool until they are
+// matched into the treasury_escrow or returned to the sponsor. Like the
+// TreasuryEscrow, it is a system account for native treasuries and a token
+// account owned by the CampaignEscrow otherwise.
+#[account]
+pub struct MatchingPoolEscrow {}
+
+impl MatchingPoolEscrow {
+    pub const PREFIX: &'static str = "matching_pool_escrow";
+
+    pub fn get_seeds<'a>(campaign_uuid: &'a String, bump: &'a u8) -> [&'a [u8]; 3] {
+        let seeds = [
+            MatchingPoolEscrow::PREFIX.as_bytes(),
+            campaign_uuid.as_ref(),
+            bytes_of(bump),
+        ];
+
+        seeds
+    }
+}
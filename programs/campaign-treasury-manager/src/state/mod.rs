@@ -1,11 +1,23 @@
 pub mod campaign_escrow;
 pub mod deposit_escrow;
 pub mod deposit_record;
+pub mod donation_record;
+pub mod legacy_campaign_escrow;
+pub mod matching_pool;
+pub mod matching_pool_escrow;
 pub mod payout_phases;
+pub mod reward_tiers;
 pub mod treasury_escrow;
+pub mod vote_record;
 
 pub use campaign_escrow::*;
 pub use deposit_escrow::*;
 pub use deposit_record::*;
+pub use donation_record::*;
+pub use legacy_campaign_escrow::*;
+pub use matching_pool::*;
+pub use matching_pool_escrow::*;
 pub use payout_phases::*;
+pub use reward_tiers::*;
 pub use treasury_escrow::*;
+pub use vote_record::*;
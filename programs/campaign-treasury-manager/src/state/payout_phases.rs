@@ -1,102 +1,127 @@
+use std::convert::TryInto;
+
 use anchor_lang::prelude::*;
 
 use crate::{
-    assert_payout_phase_is_valid, constants::ONE_HUNDRED_PERCENT_BASIS_POINTS,
+    assert_payout_phase_is_valid, calculate_legacy_payout_amount,
+    constants::{MAX_PAYOUT_PHASES, ONE_HUNDRED_PERCENT_BASIS_POINTS},
     CampaignTreasuryManagerError,
 };
 
+// Payout phases, stored as a single list in payout order.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct PayoutPhases {
-    non_voting_payout_phases: Vec<NonVotingPayoutPhase>,
-    voting_payout_phases: Vec<VotingPayoutPhase>,
+    payout_phases: Vec<PayoutPhase>,
 }
 
 impl PayoutPhases {
-    pub const NON_VOTING_PAYOUT_PHASE_LEN_LIMIT: usize = 1;
-    pub const VOTING_PAYOUT_PHASE_LEN_LIMIT: usize = 3;
+    pub fn get_space(payout_phases_len: usize) -> Result<usize> {
+        PayoutPhases::assert_payout_phases_len_is_valid(payout_phases_len)?;
 
-    pub const SPACE: usize = 4 + // 4 bytes of overhead for vec storage
-        PayoutPhases::NON_VOTING_PAYOUT_PHASE_LEN_LIMIT * NonVotingPayoutPhase::SPACE + // non_voting_payout_phases
-        4 + // 4 bytes of overhead for vec storage
-        PayoutPhases::VOTING_PAYOUT_PHASE_LEN_LIMIT * VotingPayoutPhase::SPACE; // voting_payout_phases
-}
+        Ok(4 + // 4 bytes of overhead for vec storage
+        payout_phases_len * PayoutPhase::SPACE) // payout_phases
+    }
 
-impl PayoutPhases {
-    fn default() -> Self {
-        PayoutPhases {
-            non_voting_payout_phases: vec![],
-            voting_payout_phases: vec![],
+    pub fn assert_payout_phases_len_is_valid(payout_phases_len: usize) -> Result<()> {
+        if payout_phases_len > MAX_PAYOUT_PHASES {
+            msg!(
+                "Received {} payout phases but at most {} are allowed.",
+                payout_phases_len,
+                MAX_PAYOUT_PHASES
+            );
+            return Err(CampaignTreasuryManagerError::TooManyPayoutPhases.into());
         }
-    }
 
-    pub fn new(
-        non_voting_payout_phases_input: Vec<NonVotingPayoutPhaseInput>,
-        voting_payout_phases_input: Vec<VotingPayoutPhaseInput>,
-        campaign_end_time: i64,
-    ) -> Result<Self> {
-        let mut payout_phases = PayoutPhases::default();
+        Ok(())
+    }
+}
 
-        for payout_phase_input in non_voting_payout_phases_input.iter() {
-            payout_phases
-                .non_voting_payout_phases
-                .push(NonVotingPayoutPhase {
-                    shared_fields: payout_phase_input.shared_fields.clone(),
+impl PayoutPhases {
+    pub fn new(payout_phases_input: Vec<PayoutPhaseInput>, campaign_end_time: i64) -> Result<Self> {
+        PayoutPhases::assert_payout_phases_len_is_valid(payout_phases_input.len())?;
+
+        let payout_phases = PayoutPhases::from_ordered_list(
+            payout_phases_input
+                .into_iter()
+                .map(|payout_phase_input| PayoutPhase {
+                    payout_basis_points: payout_phase_input.payout_basis_points,
+                    payout_time: payout_phase_input.payout_time,
+                    refund_deadline: payout_phase_input.refund_deadline,
+                    description: payout_phase_input.description,
                     is_paid_out: false,
+                    paid_amount: 0,
                     is_vetoed_by_authority: false,
-                });
-        }
-
-        for payout_phase_input in voting_payout_phases_input.iter() {
-            payout_phases.voting_payout_phases.push(VotingPayoutPhase {
-                shared_fields: payout_phase_input.shared_fields.clone(),
-                is_paid_out: false,
-                is_vetoed_by_authority: false,
-                voting_start_time: payout_phase_input.voting_start_time,
-                vote_basis_points_veto_threshold: payout_phase_input
-                    .vote_basis_points_veto_threshold,
-                veto_votes: payout_phase_input.veto_votes,
-            });
-        }
-
+                    is_deactivated: false,
+                    stretch_goal: payout_phase_input.stretch_goal,
+                    stream: payout_phase_input.stream.map(|stream_input| PayoutStream {
+                        stream_end_time: stream_input.stream_end_time,
+                        veto_time: None,
+                    }),
+                    voting_config: payout_phase_input.voting_config.map(|voting_config_input| {
+                        VotingConfig {
+                            voting_start_time: voting_config_input.voting_start_time,
+                            veto_votes: 0,
+                            vote_basis_points_veto_threshold: voting_config_input
+                                .vote_basis_points_veto_threshold,
+                        }
+                    }),
+                })
+                .collect(),
+        );
+
+        payout_phases.assert_descriptions_are_valid()?;
         payout_phases.assert_is_valid(campaign_end_time)?;
 
         Ok(payout_phases)
     }
 
+    pub(crate) fn from_ordered_list(payout_phases: Vec<PayoutPhase>) -> Self {
+        PayoutPhases { payout_phases }
+    }
+
     pub fn assert_is_valid(&self, campaign_end_time: i64) -> Result<()> {
         if self.is_empty() {
             return Ok(());
         }
 
-        let ordered_list = self.to_ordered_list();
-        let length = ordered_list.len();
-        let expected_length = self.len();
-        if length != expected_length {
-            msg!(
-                "Payout phases ordered list length {} did not match the expected length {}.",
-                length,
-                expected_length
-            );
-            return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+        for (index, payout_phase) in self.payout_phases.iter().enumerate() {
+            if let Some(stretch_goal) = payout_phase.stretch_goal {
+                if stretch_goal.raise_threshold == 0 {
+                    msg!(
+                        "Index {} PayoutPhase stretch goal raise_threshold must be greater than 0.",
+                        index
+                    );
+                    return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+                }
+            }
         }
 
-        let total_basis_points: u16 = ordered_list.iter().fold(0, |total, item| {
-            total + PayoutPhaseEnum::get_payout_basis_points(item)
-        });
-
-        if total_basis_points != ONE_HUNDRED_PERCENT_BASIS_POINTS {
-            msg!(
-                "Total payout phase basis points of {} must equal 100.",
-                total_basis_points
-            );
-            return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+        // Every raise between two consecutive stretch goal thresholds activates
+        // the same payout phases, so checking each threshold (and a raise of
+        // zero) covers every possible activation outcome.
+        for raise in self.get_stretch_goal_outcome_raises() {
+            let total_basis_points: u32 = self
+                .payout_phases
+                .iter()
+                .filter(|payout_phase| payout_phase.is_active_for_raise(raise))
+                .map(|payout_phase| payout_phase.payout_basis_points as u32)
+                .sum();
+
+            if total_basis_points != ONE_HUNDRED_PERCENT_BASIS_POINTS as u32 {
+                msg!(
+                    "Total payout phase basis points of {} must equal 100 for a raise of {}.",
+                    total_basis_points,
+                    raise
+                );
+                return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+            }
         }
 
-        let mut previous_payout_phase: Option<PayoutPhaseEnum> = None;
-        for (index, payout_phase) in ordered_list.into_iter().enumerate() {
+        let mut previous_payout_phase: Option<&PayoutPhase> = None;
+        for (index, payout_phase) in self.payout_phases.iter().enumerate() {
             assert_payout_phase_is_valid(
                 index,
-                payout_phase.clone(),
+                payout_phase,
                 previous_payout_phase,
                 campaign_end_time,
             )?;
@@ -106,174 +131,279 @@ impl PayoutPhases {
         Ok(())
     }
 
-    pub fn len(&self) -> usize {
-        self.non_voting_payout_phases.len() + self.voting_payout_phases.len()
+    // Descriptions are bounded because the account space is calculated using
+    // the maximum description length.
+    fn assert_descriptions_are_valid(&self) -> Result<()> {
+        for (index, payout_phase) in self.payout_phases.iter().enumerate() {
+            let description_length = payout_phase.description.len();
+            if description_length > PayoutPhase::MAX_DESCRIPTION_LENGTH {
+                msg!(
+                    "Index {} PayoutPhase description length {} exceeds the maximum length of {}.",
+                    index,
+                    description_length,
+                    PayoutPhase::MAX_DESCRIPTION_LENGTH
+                );
+                return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+            }
+        }
+
+        Ok(())
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    // Returns zero followed by each distinct stretch goal raise_threshold in
+    // increasing order.
+    fn get_stretch_goal_outcome_raises(&self) -> Vec<u64> {
+        let mut raises: Vec<u64> = self
+            .payout_phases
+            .iter()
+            .filter_map(|payout_phase| payout_phase.stretch_goal)
+            .map(|stretch_goal| stretch_goal.raise_threshold)
+            .collect();
+        raises.push(0);
+        raises.sort_unstable();
+        raises.dedup();
+        raises
     }
 
-    pub fn get_current_active_payout_phase_for_payout(&self) -> Option<PayoutPhaseEnum> {
-        for payout_phases in self.to_ordered_list() {
-            match payout_phases {
-                PayoutPhaseEnum::Voting(val) => {
-                    if !val.is_paid_out {
-                        let result = PayoutPhaseEnum::Voting(val);
-                        return Some(result);
-                    }
-                }
-                PayoutPhaseEnum::NonVoting(val) => {
-                    if !val.is_paid_out {
-                        return Some(PayoutPhaseEnum::NonVoting(val));
-                    }
-                }
-            }
+    // Deactivates the payout phases whose stretch goal does not match the
+    // final raise. Deactivated phases are skipped by payouts and votes.
+    pub fn settle_stretch_goals(&mut self, raise: u64) {
+        for payout_phase in self.payout_phases.iter_mut() {
+            payout_phase.is_deactivated = !payout_phase.is_active_for_raise(raise);
         }
+    }
 
-        None
+    pub fn len(&self) -> usize {
+        self.payout_phases.len()
     }
 
-    pub fn mark_current_active_payout_phase_as_disbursed(&mut self) {
-        let next_payout_phase = self.get_current_active_payout_phase_for_payout();
-        if let Some(val) = next_payout_phase {
-            let payout_phase_index = PayoutPhaseEnum::get_index(&val);
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-            for val in self.non_voting_payout_phases.iter_mut() {
-                if val.shared_fields.index == payout_phase_index {
-                    val.is_paid_out = true;
-                    return;
-                }
-            }
+    pub fn get(&self, index: usize) -> Option<&PayoutPhase> {
+        self.payout_phases.get(index)
+    }
 
-            for val in self.voting_payout_phases.iter_mut() {
-                if val.shared_fields.index == payout_phase_index {
-                    val.is_paid_out = true;
-                    return;
-                }
-            }
-        }
+    pub fn last(&self) -> Option<&PayoutPhase> {
+        self.payout_phases.last()
     }
 
-    pub fn veto_payout_phase_by_authority(&mut self, payout_phase_index: u8) {
-        let payout_phases = self.to_ordered_list();
-        let payout_phase = payout_phases.get(payout_phase_index as usize);
-        if let Some(val) = payout_phase {
-            let payout_phase_index = PayoutPhaseEnum::get_index(val);
+    pub fn iter(&self) -> impl Iterator<Item = &PayoutPhase> {
+        self.payout_phases.iter()
+    }
 
-            for val in self.non_voting_payout_phases.iter_mut() {
-                if val.shared_fields.index == payout_phase_index {
-                    val.is_vetoed_by_authority = true;
-                    return;
-                }
-            }
+    // Returns the first payout phase which has not been paid out yet, along
+    // with its index. Deactivated payout phases are skipped.
+    pub fn get_current_active_payout_phase_for_payout(&self) -> Option<(usize, &PayoutPhase)> {
+        self.payout_phases
+            .iter()
+            .enumerate()
+            .find(|(_, payout_phase)| !payout_phase.is_paid_out && !payout_phase.is_deactivated)
+    }
 
-            for val in self.voting_payout_phases.iter_mut() {
-                if val.shared_fields.index == payout_phase_index {
-                    val.is_vetoed_by_authority = true;
-                    return;
-                }
+    // Returns the payout phase which can be paid out at the given time, along
+    // with its index, or an error if no payout is available. Veto votes are
+    // weighted by deposit amount, so total_votes is the treasury total_funds.
+    pub fn get_payout_phase_for_payout(
+        &self,
+        now: i64,
+        total_votes: u64,
+    ) -> Result<(usize, &PayoutPhase)> {
+        let (payout_index, next_payout) = match self.get_current_active_payout_phase_for_payout() {
+            Some(next_payout) => next_payout,
+            None => {
+                msg!("There is no next payout available.");
+                return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
             }
+        };
+
+        if next_payout.is_streaming() {
+            msg!("Streaming payout phases are paid out by the ClaimStreamedPayout ix.");
+            return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
         }
-    }
 
-    pub fn to_ordered_list(&self) -> Vec<PayoutPhaseEnum> {
-        let mut current_index: usize = 0;
+        if now < next_payout.payout_time {
+            msg!("Payout time has not been reached yet.");
+            return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
+        }
 
-        let mut ordered_list: Vec<PayoutPhaseEnum> = Vec::with_capacity(self.len());
-        while current_index < self.len() {
-            let next_item = self.get_next_in_order(current_index);
-            match next_item {
-                Some(item) => ordered_list.push(item),
-                None => break,
-            }
+        if next_payout.is_vetoed_by_authority {
+            msg!("Payout has been vetoed by authority.");
+            return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
+        }
 
-            current_index += 1;
+        if next_payout.is_vetoed_by_votes(total_votes) {
+            msg!("Payout has been vetoed by supporter votes.");
+            return Err(CampaignTreasuryManagerError::InvalidPayoutRequest.into());
         }
 
-        ordered_list
+        Ok((payout_index, next_payout))
     }
 
-    fn get_next_in_order(&self, current_index: usize) -> Option<PayoutPhaseEnum> {
-        for i in 0..self.voting_payout_phases.len() {
-            let payout_phase = &self.voting_payout_phases[i];
-            if current_index == payout_phase.shared_fields.index as usize {
-                return Some(PayoutPhaseEnum::Voting(payout_phase));
+    // Returns the indexes of every payout phase which can be paid out at the
+    // given time, for catching up on missed payouts. Payout phases are paid
+    // out in order, so this stops at the first phase which is not due yet, is
+    // vetoed or is streaming.
+    pub fn get_due_payout_phase_indexes(&self, now: i64, total_votes: u64) -> Result<Vec<usize>> {
+        let (first_payout_index, _) = self.get_payout_phase_for_payout(now, total_votes)?;
+
+        Ok(self
+            .payout_phases
+            .iter()
+            .enumerate()
+            .skip(first_payout_index)
+            .filter(|(_, payout_phase)| !payout_phase.is_deactivated)
+            .take_while(|(_, payout_phase)| {
+                !payout_phase.is_paid_out
+                    && now >= payout_phase.payout_time
+                    && !payout_phase.is_vetoed_by_authority
+                    && !payout_phase.is_vetoed_by_votes(total_votes)
+                    && !payout_phase.is_streaming()
+            })
+            .map(|(index, _)| index)
+            .collect())
+    }
+
+    // Returns the streaming payout phase which can be claimed from at the
+    // given time, along with its index. Streams are claimed in payout order
+    // like lump sum payouts, so this is always the current active phase.
+    pub fn get_streamed_payout_phase_for_claim(&self, now: i64) -> Result<(usize, &PayoutPhase)> {
+        let (payout_index, next_payout) = match self.get_current_active_payout_phase_for_payout() {
+            Some(next_payout) => next_payout,
+            None => {
+                msg!("There is no next payout available.");
+                return Err(CampaignTreasuryManagerError::InvalidStreamedPayoutClaim.into());
             }
+        };
+
+        if !next_payout.is_streaming() {
+            msg!(
+                "Payout phase with index {} is not streaming, use the PayOutFunds ix.",
+                payout_index
+            );
+            return Err(CampaignTreasuryManagerError::InvalidStreamedPayoutClaim.into());
         }
 
-        for i in 0..self.non_voting_payout_phases.len() {
-            let payout_phase = &self.non_voting_payout_phases[i];
-            if current_index == payout_phase.shared_fields.index as usize {
-                return Some(PayoutPhaseEnum::NonVoting(payout_phase));
-            }
+        if now < next_payout.payout_time {
+            msg!("Payout time has not been reached yet.");
+            return Err(CampaignTreasuryManagerError::InvalidStreamedPayoutClaim.into());
         }
 
-        None
+        Ok((payout_index, next_payout))
     }
-}
-
-#[derive(Clone, Debug)]
-pub enum PayoutPhaseEnum<'a> {
-    Voting(&'a VotingPayoutPhase),
-    NonVoting(&'a NonVotingPayoutPhase),
-}
 
-impl PayoutPhaseEnum<'_> {
-    pub fn get_index(&self) -> u8 {
-        match self {
-            PayoutPhaseEnum::Voting(val) => val.shared_fields.index,
-            PayoutPhaseEnum::NonVoting(val) => val.shared_fields.index,
+    // Adds a claim to a streaming payout phase, which is paid out once the
+    // whole payout_amount has been claimed.
+    pub fn record_streamed_payout(
+        &mut self,
+        payout_phase_index: usize,
+        claim_amount: u64,
+        payout_amount: u64,
+    ) {
+        if let Some(payout_phase) = self.payout_phases.get_mut(payout_phase_index) {
+            payout_phase.paid_amount = payout_phase.paid_amount.checked_add(claim_amount).unwrap();
+            payout_phase.is_paid_out = payout_phase.paid_amount >= payout_amount;
         }
     }
 
-    pub fn get_payout_basis_points(&self) -> u16 {
-        match self {
-            PayoutPhaseEnum::Voting(val) => val.shared_fields.payout_basis_points,
-            PayoutPhaseEnum::NonVoting(val) => val.shared_fields.payout_basis_points,
+    pub fn mark_current_active_payout_phase_as_disbursed(&mut self, paid_amount: u64) {
+        if let Some(payout_phase) = self
+            .payout_phases
+            .iter_mut()
+            .find(|payout_phase| !payout_phase.is_paid_out && !payout_phase.is_deactivated)
+        {
+            payout_phase.is_paid_out = true;
+            payout_phase.paid_amount = paid_amount;
         }
     }
 
-    pub fn get_payout_time(&self) -> i64 {
-        match self {
-            PayoutPhaseEnum::Voting(val) => val.shared_fields.payout_time,
-            PayoutPhaseEnum::NonVoting(val) => val.shared_fields.payout_time,
-        }
+    // Sum of the amounts paid out by each payout phase.
+    pub fn get_total_paid_amount(&self) -> u128 {
+        self.payout_phases
+            .iter()
+            .map(|payout_phase| payout_phase.paid_amount as u128)
+            .sum()
     }
 
-    pub fn get_refund_deadline(&self) -> i64 {
-        match self {
-            PayoutPhaseEnum::Voting(val) => val.shared_fields.refund_deadline,
-            PayoutPhaseEnum::NonVoting(val) => val.shared_fields.refund_deadline,
+    // The legacy layout only recorded which payout phases were paid out. Its
+    // payouts always transferred the amount calculated from total_funds with
+    // calculate_legacy_payout_amount, so the paid amounts can be recalculated
+    // when migrating.
+    pub(crate) fn recalculate_legacy_paid_amounts(&mut self, total_funds: u64) {
+        for payout_phase in self
+            .payout_phases
+            .iter_mut()
+            .filter(|payout_phase| payout_phase.is_paid_out)
+        {
+            payout_phase.paid_amount =
+                calculate_legacy_payout_amount(total_funds, payout_phase.payout_basis_points);
         }
     }
 
-    pub fn get_description(&self) -> &String {
-        match self {
-            PayoutPhaseEnum::Voting(val) => &val.shared_fields.description,
-            PayoutPhaseEnum::NonVoting(val) => &val.shared_fields.description,
+    // Streaming payout phases stop vesting when they are first vetoed.
+    pub fn veto_payout_phase_by_authority(&mut self, payout_phase_index: u8, now: i64) {
+        if let Some(payout_phase) = self.payout_phases.get_mut(payout_phase_index as usize) {
+            payout_phase.is_vetoed_by_authority = true;
+            if let Some(stream) = payout_phase.stream.as_mut() {
+                stream.veto_time.get_or_insert(now);
+            }
         }
     }
 
-    pub fn get_is_paid_out(&self) -> bool {
-        match self {
-            PayoutPhaseEnum::Voting(val) => val.is_paid_out,
-            PayoutPhaseEnum::NonVoting(val) => val.is_paid_out,
+    // Adds veto votes to a voting payout phase. Votes can be cast from the
+    // voting_start_time until the payout_time.
+    pub fn record_veto_votes(
+        &mut self,
+        payout_phase_index: u8,
+        veto_votes: u64,
+        now: i64,
+    ) -> Result<()> {
+        let payout_phase = match self.payout_phases.get_mut(payout_phase_index as usize) {
+            Some(payout_phase) => payout_phase,
+            None => {
+                msg!(
+                    "Payout phase with index {} does not exist.",
+                    payout_phase_index
+                );
+                return Err(CampaignTreasuryManagerError::InvalidVote.into());
+            }
+        };
+
+        if payout_phase.is_paid_out {
+            msg!("Payout phase has already been paid out.");
+            return Err(CampaignTreasuryManagerError::InvalidVote.into());
         }
-    }
 
-    pub fn get_is_vetoed_by_authority(&self) -> bool {
-        match self {
-            PayoutPhaseEnum::Voting(val) => val.is_vetoed_by_authority,
-            PayoutPhaseEnum::NonVoting(val) => val.is_vetoed_by_authority,
+        if payout_phase.is_deactivated {
+            msg!("Payout phase was deactivated by its stretch goal.");
+            return Err(CampaignTreasuryManagerError::InvalidVote.into());
         }
+
+        let voting_config = match payout_phase.voting_config.as_mut() {
+            Some(voting_config) => voting_config,
+            None => {
+                msg!("Payout phase does not allow voting.");
+                return Err(CampaignTreasuryManagerError::InvalidVote.into());
+            }
+        };
+
+        if now < voting_config.voting_start_time || now >= payout_phase.payout_time {
+            msg!(
+                "Voting is only open from {} until {}.",
+                voting_config.voting_start_time,
+                payout_phase.payout_time
+            );
+            return Err(CampaignTreasuryManagerError::InvalidVote.into());
+        }
+
+        voting_config.veto_votes = voting_config.veto_votes.checked_add(veto_votes).unwrap();
+        Ok(())
     }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct SharedPayoutPhaseFields {
-    // Marks the order of this payout phase in the list of payout phases.
-    pub index: u8,
+pub struct PayoutPhase {
     // Basis points to pay out to payout_wallet.
     pub payout_basis_points: u16,
     // Time when payout can be disbursed. This is also when refunds can begin.
@@ -283,80 +413,208 @@ pub struct SharedPayoutPhaseFields {
     pub refund_deadline: i64,
     // Description. May be an off-chain uri.
     pub description: String,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct NonVotingPayoutPhase {
-    pub shared_fields: SharedPayoutPhaseFields,
-    // Marks if initial payout has occurred or not.
+    // Marks if the payout has occurred or not.
     pub is_paid_out: bool,
-    // Marks if the authority vetoed the initial payout.
+    // Amount transferred to the payout_wallet for this payout phase, set when
+    // the payout occurs.
+    pub paid_amount: u64,
+    // Marks if the authority vetoed the payout.
     pub is_vetoed_by_authority: bool,
+    // Marks if the payout phase is skipped because its stretch goal does not
+    // match the final raise. This is set when stretch goals are settled.
+    pub is_deactivated: bool,
+    // Present if this payout phase depends on a stretch goal.
+    pub stretch_goal: Option<StretchGoal>,
+    // Present if this payout phase streams its payout instead of paying it
+    // out at the payout_time.
+    pub stream: Option<PayoutStream>,
+    // Present if campaign supporters can vote to veto this payout phase.
+    pub voting_config: Option<VotingConfig>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct NonVotingPayoutPhaseInput {
-    pub shared_fields: SharedPayoutPhaseFields,
-}
-
-impl NonVotingPayoutPhase {
+impl PayoutPhase {
     // Arbitrary limit in bytes for description length.
-    const MAX_DESCRIPTION_LENGTH: usize = 200;
+    pub const MAX_DESCRIPTION_LENGTH: usize = 200;
 
-    #[allow(dead_code)]
-    const SPACE: usize = 1 + // index
-        1 + // is_paid_out
-        1 + // is_vetoed_by_authority
-        2 + // payout_basis_points
+    pub const SPACE: usize = 2 + // payout_basis_points
         8 + // payout_time
         8 + // refund_deadline
         4 + // string size allocation
-        NonVotingPayoutPhase::MAX_DESCRIPTION_LENGTH + // max description length
-        96; // extra padding
+        PayoutPhase::MAX_DESCRIPTION_LENGTH + // max description length
+        1 + // is_paid_out
+        8 + // paid_amount
+        1 + // is_vetoed_by_authority
+        1 + // is_deactivated
+        1 + // Option memory overhead
+        StretchGoal::SPACE + // stretch_goal
+        1 + // Option memory overhead
+        PayoutStream::SPACE + // stream
+        1 + // Option memory overhead
+        VotingConfig::SPACE + // voting_config
+        67; // extra padding
+
+    pub fn is_voting(&self) -> bool {
+        self.voting_config.is_some()
+    }
+
+    // Payout phases without a stretch goal are active for every raise.
+    pub fn is_active_for_raise(&self, raise: u64) -> bool {
+        match &self.stretch_goal {
+            Some(stretch_goal) => stretch_goal.is_active_for_raise(raise),
+            None => true,
+        }
+    }
+
+    pub fn is_vetoed_by_votes(&self, total_votes: u64) -> bool {
+        match &self.voting_config {
+            Some(voting_config) => voting_config.is_veto_threshold_reached(total_votes),
+            None => false,
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    // The part of the payout_amount which a streaming payout phase has vested
+    // by now. Vetoes freeze the unvested remainder: voting closes at the
+    // payout_time, so a veto by votes freezes the whole stream, while a veto
+    // by the authority freezes it at the veto_time.
+    pub fn calculate_vested_stream_amount(
+        &self,
+        payout_amount: u64,
+        now: i64,
+        total_votes: u64,
+    ) -> u64 {
+        let stream = match &self.stream {
+            Some(stream) => stream,
+            None => return 0,
+        };
+
+        if self.is_vetoed_by_votes(total_votes) {
+            return 0;
+        }
+
+        let vesting_end_time = match stream.veto_time {
+            Some(veto_time) => now.min(veto_time),
+            None => now,
+        }
+        .min(stream.stream_end_time);
+        if vesting_end_time <= self.payout_time {
+            return 0;
+        }
+
+        // Multiply before dividing in u128, as in calculate_payout_amount.
+        let vested_amount = (payout_amount as u128)
+            .checked_mul((vesting_end_time - self.payout_time) as u128)
+            .unwrap()
+            .checked_div((stream.stream_end_time - self.payout_time) as u128)
+            .unwrap();
+        vested_amount.try_into().unwrap()
+    }
+
+    // The vested amount which has not been claimed yet.
+    pub fn calculate_claimable_stream_amount(
+        &self,
+        payout_amount: u64,
+        now: i64,
+        total_votes: u64,
+    ) -> u64 {
+        self.calculate_vested_stream_amount(payout_amount, now, total_votes)
+            .saturating_sub(self.paid_amount)
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct VotingPayoutPhase {
-    // These fields are the same as the NonVotingPayoutPhase:
-    pub shared_fields: SharedPayoutPhaseFields,
-    pub is_paid_out: bool,
-    pub is_vetoed_by_authority: bool,
+// Makes a payout phase depend on whether the campaign raise reached the
+// raise_threshold. Phases which are only active below the threshold let
+// stretch goal phases replace part of the regular schedule.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StretchGoal {
+    // Raise in the treasury currency.
+    pub raise_threshold: u64,
+    // If true, the payout phase is only active if the raise reached the
+    // raise_threshold, otherwise only if it did not.
+    pub is_active_when_reached: bool,
+}
 
-    // Time when veto votes can be submitted for this payout phase.
+impl StretchGoal {
+    pub const SPACE: usize = 8 + // raise_threshold
+        1; // is_active_when_reached
+
+    pub fn is_active_for_raise(&self, raise: u64) -> bool {
+        (raise >= self.raise_threshold) == self.is_active_when_reached
+    }
+}
+
+// Makes a payout phase vest its payout linearly from the payout_time until
+// the stream_end_time. The vested amount can be claimed at any time with the
+// ClaimStreamedPayout ix.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayoutStream {
+    pub stream_end_time: i64,
+    // Set when the authority vetoes the payout phase. Nothing vests after
+    // this time.
+    pub veto_time: Option<i64>,
+}
+
+impl PayoutStream {
+    pub const SPACE: usize = 8 + // stream_end_time
+        1 + // Option memory overhead
+        8; // veto_time
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VotingConfig {
+    // Time when veto votes can be submitted for this payout phase. Voting
+    // closes at the payout_time.
     pub voting_start_time: i64,
     pub veto_votes: u64,
     // Percentage of veto votes required to veto this payout phase.
     // Total votes can be determined by the total deposit amount, assuming
     // votes are distributed based on deposit contribution.
     pub vote_basis_points_veto_threshold: u64,
-    // Deadline for initial refunds if campaign does not meet its goal.
-    // After this time, any remaining funds can be withdrawn by the creator.
+}
+
+impl VotingConfig {
+    pub const SPACE: usize = 8 + // voting_start_time
+        8 + // veto_votes
+        8; // vote_basis_points_veto_threshold
+
+    // A campaign without deposits has no votes, so it can never be vetoed.
+    pub fn is_veto_threshold_reached(&self, total_votes: u64) -> bool {
+        if total_votes == 0 {
+            return false;
+        }
+
+        let veto_votes_basis_points = self.veto_votes as u128
+            * ONE_HUNDRED_PERCENT_BASIS_POINTS as u128
+            / total_votes as u128;
+        veto_votes_basis_points >= self.vote_basis_points_veto_threshold as u128
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct VotingPayoutPhaseInput {
-    pub shared_fields: SharedPayoutPhaseFields,
-    pub voting_start_time: i64,
-    pub veto_votes: u64,
-    pub vote_basis_points_veto_threshold: u64,
+pub struct PayoutPhaseInput {
+    pub payout_basis_points: u16,
+    pub payout_time: i64,
+    pub refund_deadline: i64,
+    pub description: String,
+    pub stretch_goal: Option<StretchGoal>,
+    pub stream: Option<PayoutStreamInput>,
+    pub voting_config: Option<VotingConfigInput>,
 }
 
-impl VotingPayoutPhase {
-    // Arbitrary limit in bytes for description length.
-    const MAX_DESCRIPTION_LENGTH: usize = 200;
+// The veto_time is not part of the input, it is only set by a veto.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayoutStreamInput {
+    pub stream_end_time: i64,
+}
 
-    const SPACE: usize = 1 + // index
-        1 + // is_paid_out
-        1 + // is_vetoed_by_authority
-        2 + // payout_basis_points
-        8 + // payout_time
-        8 + // refund_deadline
-        4 + // string size allocation
-        VotingPayoutPhase::MAX_DESCRIPTION_LENGTH + // max description length
-        96 + // extra padding
-        8 + // voting_start_time
-        8 + // veto_votes
-        8; // vote_basis_points_veto_threshold
+// Veto votes are not part of the input, they always start at zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VotingConfigInput {
+    pub voting_start_time: i64,
+    pub vote_basis_points_veto_threshold: u64,
 }
 
 #[cfg(test)]
@@ -371,73 +629,178 @@ mod tests {
         percent.checked_mul(100).unwrap()
     }
 
-    fn get_default_non_voting_payout_phase(
-        shared_fields: SharedPayoutPhaseFields,
-    ) -> NonVotingPayoutPhase {
-        NonVotingPayoutPhase {
-            shared_fields,
-            is_paid_out: false,
-            is_vetoed_by_authority: false,
+    fn get_default_voting_config(payout_time: i64) -> VotingConfig {
+        VotingConfig {
+            veto_votes: 0,
+            vote_basis_points_veto_threshold: 80,
+            voting_start_time: payout_time - get_days_ahead_unix_time(14),
         }
     }
 
-    fn get_default_voting_payout_phase(
-        shared_fields: SharedPayoutPhaseFields,
-    ) -> VotingPayoutPhase {
-        VotingPayoutPhase {
-            shared_fields,
+    fn get_default_payout_phase(
+        payout_basis_points: u16,
+        payout_time: i64,
+        refund_deadline: i64,
+        is_voting: bool,
+    ) -> PayoutPhase {
+        let (description, voting_config) = if is_voting {
+            (
+                "Voting payout phase.",
+                Some(get_default_voting_config(payout_time)),
+            )
+        } else {
+            ("Non-voting payout phase.", None)
+        };
+
+        PayoutPhase {
+            payout_basis_points,
+            payout_time,
+            refund_deadline,
+            description: String::from(description),
             is_paid_out: false,
+            paid_amount: 0,
             is_vetoed_by_authority: false,
-            veto_votes: 0,
-            vote_basis_points_veto_threshold: 80,
-            voting_start_time: 0,
+            is_deactivated: false,
+            stretch_goal: None,
+            stream: None,
+            voting_config,
         }
     }
 
+    fn get_payout_phases_with_voting_config_for_test(voting_config: VotingConfig) -> PayoutPhases {
+        PayoutPhases::from_ordered_list(vec![
+            get_default_payout_phase(
+                percent_to_basis_points(50),
+                get_days_ahead_unix_time(10),
+                get_days_ahead_unix_time(41),
+                false,
+            ),
+            PayoutPhase {
+                voting_config: Some(voting_config),
+                ..get_default_payout_phase(
+                    percent_to_basis_points(50),
+                    get_days_ahead_unix_time(100),
+                    get_days_ahead_unix_time(131),
+                    true,
+                )
+            },
+        ])
+    }
+
     fn get_valid_payout_phases_for_test() -> PayoutPhases {
-        let payout_phases = PayoutPhases {
-            non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                SharedPayoutPhaseFields {
-                    index: 0,
-                    payout_basis_points: percent_to_basis_points(50),
-                    payout_time: get_days_ahead_unix_time(10),
-                    refund_deadline: get_days_ahead_unix_time(41),
-                    description: String::from("Non-voting payout phase."),
-                },
-            )],
-            voting_payout_phases: vec![
-                get_default_voting_payout_phase(SharedPayoutPhaseFields {
-                    index: 1,
-                    payout_basis_points: percent_to_basis_points(20),
-                    payout_time: get_days_ahead_unix_time(100),
-                    refund_deadline: get_days_ahead_unix_time(131),
-                    description: String::from("Voting payout phase."),
-                }),
-                get_default_voting_payout_phase(SharedPayoutPhaseFields {
-                    index: 2,
-                    payout_basis_points: percent_to_basis_points(20),
-                    payout_time: get_days_ahead_unix_time(200),
-                    refund_deadline: get_days_ahead_unix_time(231),
-                    description: String::from("Voting payout phase."),
-                }),
-                get_default_voting_payout_phase(SharedPayoutPhaseFields {
-                    index: 3,
-                    payout_basis_points: percent_to_basis_points(5),
-                    payout_time: get_days_ahead_unix_time(300),
-                    refund_deadline: get_days_ahead_unix_time(331),
-                    description: String::from("Voting payout phase."),
-                }),
-                get_default_voting_payout_phase(SharedPayoutPhaseFields {
-                    index: 4,
-                    payout_basis_points: percent_to_basis_points(5),
-                    payout_time: get_days_ahead_unix_time(400),
-                    refund_deadline: get_days_ahead_unix_time(431),
-                    description: String::from("Voting payout phase."),
-                }),
-            ],
-        };
+        PayoutPhases::from_ordered_list(vec![
+            get_default_payout_phase(
+                percent_to_basis_points(50),
+                get_days_ahead_unix_time(10),
+                get_days_ahead_unix_time(41),
+                false,
+            ),
+            get_default_payout_phase(
+                percent_to_basis_points(20),
+                get_days_ahead_unix_time(100),
+                get_days_ahead_unix_time(131),
+                true,
+            ),
+            get_default_payout_phase(
+                percent_to_basis_points(20),
+                get_days_ahead_unix_time(200),
+                get_days_ahead_unix_time(231),
+                true,
+            ),
+            get_default_payout_phase(
+                percent_to_basis_points(5),
+                get_days_ahead_unix_time(300),
+                get_days_ahead_unix_time(331),
+                true,
+            ),
+            get_default_payout_phase(
+                percent_to_basis_points(5),
+                get_days_ahead_unix_time(400),
+                get_days_ahead_unix_time(431),
+                true,
+            ),
+        ])
+    }
 
-        payout_phases
+    fn get_stretch_goal_payout_phase(
+        payout_basis_points: u16,
+        payout_time: i64,
+        raise_threshold: u64,
+        is_active_when_reached: bool,
+    ) -> PayoutPhase {
+        PayoutPhase {
+            stretch_goal: Some(StretchGoal {
+                raise_threshold,
+                is_active_when_reached,
+            }),
+            ..get_default_payout_phase(
+                payout_basis_points,
+                payout_time,
+                payout_time + get_days_ahead_unix_time(31),
+                false,
+            )
+        }
+    }
+
+    // Pays out the second half in one payout phase below a raise of 1000, and
+    // in two payout phases otherwise.
+    fn get_stretch_goal_payout_phases_for_test(final_payout_percent: u16) -> PayoutPhases {
+        PayoutPhases::from_ordered_list(vec![
+            get_default_payout_phase(
+                percent_to_basis_points(50),
+                get_days_ahead_unix_time(10),
+                get_days_ahead_unix_time(41),
+                false,
+            ),
+            get_stretch_goal_payout_phase(
+                percent_to_basis_points(50),
+                get_days_ahead_unix_time(100),
+                1_000,
+                false,
+            ),
+            get_stretch_goal_payout_phase(
+                percent_to_basis_points(30),
+                get_days_ahead_unix_time(200),
+                1_000,
+                true,
+            ),
+            get_stretch_goal_payout_phase(
+                percent_to_basis_points(final_payout_percent),
+                get_days_ahead_unix_time(300),
+                1_000,
+                true,
+            ),
+        ])
+    }
+
+    // Streams the second payout phase from day 100 until day 130.
+    fn get_streaming_payout_phases_for_test(stream_end_time: i64) -> PayoutPhases {
+        PayoutPhases::from_ordered_list(vec![
+            get_default_payout_phase(
+                percent_to_basis_points(50),
+                get_days_ahead_unix_time(10),
+                get_days_ahead_unix_time(41),
+                false,
+            ),
+            PayoutPhase {
+                stream: Some(PayoutStream {
+                    stream_end_time,
+                    veto_time: None,
+                }),
+                ..get_default_payout_phase(
+                    percent_to_basis_points(30),
+                    get_days_ahead_unix_time(100),
+                    get_days_ahead_unix_time(131),
+                    false,
+                )
+            },
+            get_default_payout_phase(
+                percent_to_basis_points(20),
+                get_days_ahead_unix_time(200),
+                get_days_ahead_unix_time(231),
+                false,
+            ),
+        ])
     }
 
     struct InvalidPayoutPhaseTestCase {
@@ -447,174 +810,188 @@ mod tests {
 
     fn get_invalid_payout_phases_for_test() -> Vec<InvalidPayoutPhaseTestCase> {
         vec![
-            InvalidPayoutPhaseTestCase {
-                label: "Invalid payout phase indexes.",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(0),
-                            refund_deadline: get_days_ahead_unix_time(10),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![get_default_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(12),
-                            refund_deadline: get_days_ahead_unix_time(50),
-                            description: String::from("Voting payout phase."),
-                        },
-                    )],
-                },
-            },
             InvalidPayoutPhaseTestCase {
                 label: "Invalid total basis points.",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(0),
-                            refund_deadline: get_days_ahead_unix_time(10),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![get_default_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 1,
-                            payout_basis_points: percent_to_basis_points(30),
-                            payout_time: get_days_ahead_unix_time(10),
-                            refund_deadline: get_days_ahead_unix_time(50),
-                            description: String::from("Voting payout phase."),
-                        },
-                    )],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(0),
+                        get_days_ahead_unix_time(10),
+                        false,
+                    ),
+                    get_default_payout_phase(
+                        percent_to_basis_points(30),
+                        get_days_ahead_unix_time(10),
+                        get_days_ahead_unix_time(50),
+                        true,
+                    ),
+                ]),
             },
             InvalidPayoutPhaseTestCase {
                 label: "Invalid payout_time for index 1 payout phase.",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(10),
-                            refund_deadline: get_days_ahead_unix_time(20),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![get_default_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 1,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(5),
-                            refund_deadline: get_days_ahead_unix_time(50),
-                            description: String::from("Voting payout phase."),
-                        },
-                    )],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(10),
+                        get_days_ahead_unix_time(20),
+                        false,
+                    ),
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(5),
+                        get_days_ahead_unix_time(50),
+                        true,
+                    ),
+                ]),
             },
             InvalidPayoutPhaseTestCase {
                 label: "Invalid refund_deadline for index 1 payout phase.",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(10),
-                            refund_deadline: get_days_ahead_unix_time(20),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![get_default_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 1,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(25),
-                            refund_deadline: get_days_ahead_unix_time(20),
-                            description: String::from("Voting payout phase."),
-                        },
-                    )],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(10),
+                        get_days_ahead_unix_time(20),
+                        false,
+                    ),
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(25),
+                        get_days_ahead_unix_time(20),
+                        true,
+                    ),
+                ]),
             },
             InvalidPayoutPhaseTestCase {
                 label: "Invalid refund_deadline (exceeds maximum buffer time).",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(100),
-                            payout_time: get_days_ahead_unix_time(20),
-                            refund_deadline: get_days_ahead_unix_time(210),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![get_default_payout_phase(
+                    percent_to_basis_points(100),
+                    get_days_ahead_unix_time(20),
+                    get_days_ahead_unix_time(210),
+                    false,
+                )]),
             },
             InvalidPayoutPhaseTestCase {
                 label:
                     "Invalid payout time for index 1 payout phase (exceeds maximum buffer time).",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(20),
-                            refund_deadline: get_days_ahead_unix_time(20),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![get_default_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 1,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(225),
-                            refund_deadline: get_days_ahead_unix_time(100),
-                            description: String::from("Voting payout phase."),
-                        },
-                    )],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(20),
+                        get_days_ahead_unix_time(20),
+                        false,
+                    ),
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(225),
+                        get_days_ahead_unix_time(100),
+                        true,
+                    ),
+                ]),
             },
             InvalidPayoutPhaseTestCase {
                 label: "Invalid refund_deadline for index 0 payout phase (exceeds maximum buffer time).",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(20),
-                            refund_deadline: get_days_ahead_unix_time(22),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![get_default_payout_phase(
+                    percent_to_basis_points(50),
+                    get_days_ahead_unix_time(20),
+                    get_days_ahead_unix_time(22),
+                    false,
+                )]),
             },
             InvalidPayoutPhaseTestCase {
                 label: "Invalid refund_deadline for index 1 payout phase (exceeds maximum buffer time).",
-                payout_phases: PayoutPhases {
-                    non_voting_payout_phases: vec![get_default_non_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 0,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(20),
-                            refund_deadline: get_days_ahead_unix_time(40),
-                            description: String::from("Non-voting payout phase."),
-                        },
-                    )],
-                    voting_payout_phases: vec![get_default_voting_payout_phase(
-                        SharedPayoutPhaseFields {
-                            index: 1,
-                            payout_basis_points: percent_to_basis_points(50),
-                            payout_time: get_days_ahead_unix_time(50),
-                            refund_deadline: get_days_ahead_unix_time(55),
-                            description: String::from("Voting payout phase."),
-                        },
-                    )],
-                },
+                payout_phases: PayoutPhases::from_ordered_list(vec![
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(20),
+                        get_days_ahead_unix_time(40),
+                        false,
+                    ),
+                    get_default_payout_phase(
+                        percent_to_basis_points(50),
+                        get_days_ahead_unix_time(50),
+                        get_days_ahead_unix_time(55),
+                        true,
+                    ),
+                ]),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid vote_basis_points_veto_threshold (exceeds 100%).",
+                payout_phases: get_payout_phases_with_voting_config_for_test(VotingConfig {
+                    vote_basis_points_veto_threshold: ONE_HUNDRED_PERCENT_BASIS_POINTS as u64 + 1,
+                    ..get_default_voting_config(get_days_ahead_unix_time(100))
+                }),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid vote_basis_points_veto_threshold (zero).",
+                payout_phases: get_payout_phases_with_voting_config_for_test(VotingConfig {
+                    vote_basis_points_veto_threshold: 0,
+                    ..get_default_voting_config(get_days_ahead_unix_time(100))
+                }),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid voting_start_time (after payout_time).",
+                payout_phases: get_payout_phases_with_voting_config_for_test(VotingConfig {
+                    voting_start_time: get_days_ahead_unix_time(101),
+                    ..get_default_voting_config(get_days_ahead_unix_time(100))
+                }),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid voting_start_time (before campaign_end_time).",
+                payout_phases: PayoutPhases::from_ordered_list(vec![PayoutPhase {
+                    voting_config: Some(VotingConfig {
+                        voting_start_time: get_days_ahead_unix_time(-1),
+                        ..get_default_voting_config(get_days_ahead_unix_time(10))
+                    }),
+                    ..get_default_payout_phase(
+                        percent_to_basis_points(100),
+                        get_days_ahead_unix_time(10),
+                        get_days_ahead_unix_time(41),
+                        true,
+                    )
+                }]),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid voting window (below minimum buffer time).",
+                payout_phases: get_payout_phases_with_voting_config_for_test(VotingConfig {
+                    voting_start_time: get_days_ahead_unix_time(100) - 60,
+                    ..get_default_voting_config(get_days_ahead_unix_time(100))
+                }),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid total basis points for a stretch goal outcome.",
+                payout_phases: get_stretch_goal_payout_phases_for_test(10),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid stretch goal raise_threshold.",
+                payout_phases: PayoutPhases::from_ordered_list(vec![
+                    get_default_payout_phase(
+                        percent_to_basis_points(100),
+                        get_days_ahead_unix_time(10),
+                        get_days_ahead_unix_time(41),
+                        false,
+                    ),
+                    get_stretch_goal_payout_phase(
+                        percent_to_basis_points(0),
+                        get_days_ahead_unix_time(100),
+                        0,
+                        false,
+                    ),
+                ]),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid stream_end_time for index 1 payout phase.",
+                payout_phases: get_streaming_payout_phases_for_test(get_days_ahead_unix_time(100)),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid payout_time for index 2 payout phase (before the stream ends).",
+                payout_phases: get_streaming_payout_phases_for_test(get_days_ahead_unix_time(201)),
+            },
+            InvalidPayoutPhaseTestCase {
+                label: "Invalid voting window (exceeds maximum buffer time).",
+                payout_phases: get_payout_phases_with_voting_config_for_test(VotingConfig {
+                    voting_start_time: get_days_ahead_unix_time(50),
+                    ..get_default_voting_config(get_days_ahead_unix_time(100))
+                }),
             },
         ]
     }
@@ -625,12 +1002,19 @@ mod tests {
 
         assert!(payout_phases.assert_is_valid(0).is_ok());
 
-        for (index, payout_phase_enum) in payout_phases.to_ordered_list().into_iter().enumerate() {
-            assert_eq!(
-                PayoutPhaseEnum::get_index(&payout_phase_enum) as usize,
-                index
-            );
-        }
+        // The invalid voting cases below rely on this being valid.
+        let payout_phases = get_payout_phases_with_voting_config_for_test(
+            get_default_voting_config(get_days_ahead_unix_time(100)),
+        );
+        assert!(payout_phases.assert_is_valid(0).is_ok());
+
+        // The invalid stretch goal case below relies on this being valid.
+        let payout_phases = get_stretch_goal_payout_phases_for_test(20);
+        assert!(payout_phases.assert_is_valid(0).is_ok());
+
+        // A stream may end at the next payout_time.
+        let payout_phases = get_streaming_payout_phases_for_test(get_days_ahead_unix_time(200));
+        assert!(payout_phases.assert_is_valid(0).is_ok());
     }
 
     #[test]
@@ -644,56 +1028,671 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_payout_phases_len_limit() {
+        let get_payout_phases_input = |len: usize| -> Vec<PayoutPhaseInput> {
+            (0..len)
+                .map(|_| PayoutPhaseInput {
+                    payout_basis_points: 0,
+                    payout_time: get_days_ahead_unix_time(10),
+                    refund_deadline: get_days_ahead_unix_time(41),
+                    description: String::from("Voting payout phase."),
+                    stretch_goal: None,
+                    stream: None,
+                    voting_config: Some(VotingConfigInput {
+                        voting_start_time: get_days_ahead_unix_time(1),
+                        vote_basis_points_veto_threshold: 80,
+                    }),
+                })
+                .collect()
+        };
+
+        let result = PayoutPhases::new(get_payout_phases_input(MAX_PAYOUT_PHASES + 1), 0);
+        assert_eq!(
+            result.unwrap_err(),
+            CampaignTreasuryManagerError::TooManyPayoutPhases.into()
+        );
+
+        let result = PayoutPhases::new(get_payout_phases_input(MAX_PAYOUT_PHASES), 0);
+        assert_eq!(
+            result.unwrap_err(),
+            CampaignTreasuryManagerError::InvalidPayoutPhases.into()
+        );
+
+        assert_eq!(
+            PayoutPhases::get_space(MAX_PAYOUT_PHASES + 1).unwrap_err(),
+            CampaignTreasuryManagerError::TooManyPayoutPhases.into()
+        );
+        assert_eq!(
+            PayoutPhases::get_space(MAX_PAYOUT_PHASES).unwrap(),
+            4 + MAX_PAYOUT_PHASES * PayoutPhase::SPACE
+        );
+    }
+
     #[test]
     fn test_get_next_payout_phase_for_payout() {
         let mut payout_phases = get_valid_payout_phases_for_test();
 
-        let payout_ready = payout_phases.get_current_active_payout_phase_for_payout();
+        let (index, payout_phase) = payout_phases
+            .get_current_active_payout_phase_for_payout()
+            .expect("Value should be a Some variant.");
+        assert_eq!(index, 0);
+        assert!(!payout_phase.is_voting());
+        assert_eq!(
+            payout_phase.payout_basis_points,
+            percent_to_basis_points(50)
+        );
+        assert_eq!(payout_phase.payout_time, get_days_ahead_unix_time(10));
+        assert_eq!(payout_phase.refund_deadline, get_days_ahead_unix_time(41));
+
+        payout_phases.mark_current_active_payout_phase_as_disbursed(500);
+        assert_eq!(payout_phases.get(0).unwrap().paid_amount, 500);
+        assert_eq!(payout_phases.get_total_paid_amount(), 500);
+
+        let (index, payout_phase) = payout_phases
+            .get_current_active_payout_phase_for_payout()
+            .expect("Value should be a Some variant.");
+        assert_eq!(index, 1);
+        assert!(payout_phase.is_voting());
+        assert_eq!(
+            payout_phase.payout_basis_points,
+            percent_to_basis_points(20)
+        );
+        assert_eq!(payout_phase.payout_time, get_days_ahead_unix_time(100));
+        assert_eq!(payout_phase.refund_deadline, get_days_ahead_unix_time(131));
+    }
 
-        match payout_ready {
-            Some(payout_phase) => match payout_phase {
-                PayoutPhaseEnum::NonVoting(val) => {
-                    assert_eq!(val.shared_fields.index, 0);
-                    assert_eq!(
-                        val.shared_fields.payout_basis_points,
-                        percent_to_basis_points(50)
-                    );
-                    assert_eq!(val.shared_fields.payout_time, get_days_ahead_unix_time(10));
-                    assert_eq!(
-                        val.shared_fields.refund_deadline,
-                        get_days_ahead_unix_time(41)
-                    );
-                }
-                PayoutPhaseEnum::Voting(_) => {
-                    panic!("Value should be a PayoutPhaseEnum::NonVoting.")
-                }
+    #[test]
+    fn test_veto_payout_phase_by_authority() {
+        let mut payout_phases = get_valid_payout_phases_for_test();
+
+        payout_phases.veto_payout_phase_by_authority(2, get_days_ahead_unix_time(0));
+
+        for (index, payout_phase) in payout_phases.iter().enumerate() {
+            assert_eq!(payout_phase.is_vetoed_by_authority, index == 2);
+        }
+    }
+
+    #[test]
+    fn test_record_veto_votes() {
+        let mut payout_phases = get_valid_payout_phases_for_test();
+        let voting_start_time = get_days_ahead_unix_time(86);
+
+        // Payout phase 0 does not allow voting, and phase 5 does not exist.
+        assert!(payout_phases
+            .record_veto_votes(0, 10, get_days_ahead_unix_time(5))
+            .is_err());
+        assert!(payout_phases
+            .record_veto_votes(5, 10, voting_start_time)
+            .is_err());
+
+        // Voting is open from the voting_start_time until the payout_time.
+        assert!(payout_phases
+            .record_veto_votes(1, 10, voting_start_time - 1)
+            .is_err());
+        assert!(payout_phases
+            .record_veto_votes(1, 10, get_days_ahead_unix_time(100))
+            .is_err());
+        payout_phases
+            .record_veto_votes(1, 10, voting_start_time)
+            .unwrap();
+        payout_phases
+            .record_veto_votes(1, 15, get_days_ahead_unix_time(100) - 1)
+            .unwrap();
+        assert_eq!(
+            payout_phases
+                .get(1)
+                .unwrap()
+                .voting_config
+                .as_ref()
+                .unwrap()
+                .veto_votes,
+            25
+        );
+
+        payout_phases.mark_current_active_payout_phase_as_disbursed(500);
+        payout_phases.mark_current_active_payout_phase_as_disbursed(200);
+        assert!(payout_phases
+            .record_veto_votes(1, 10, voting_start_time)
+            .is_err());
+    }
+
+    #[test]
+    fn test_get_due_payout_phase_indexes() {
+        let mut payout_phases = get_valid_payout_phases_for_test();
+        let total_votes = 10_000;
+
+        assert!(payout_phases
+            .get_due_payout_phase_indexes(get_days_ahead_unix_time(9), total_votes)
+            .is_err());
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(250), total_votes)
+                .unwrap(),
+            vec![0, 1, 2]
+        );
+
+        // Catching up stops at the first vetoed payout phase.
+        payout_phases.veto_payout_phase_by_authority(3, get_days_ahead_unix_time(250));
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(500), total_votes)
+                .unwrap(),
+            vec![0, 1, 2]
+        );
+
+        // The default voting config vetoes with 0.8% of the votes.
+        payout_phases.payout_phases[2]
+            .voting_config
+            .as_mut()
+            .unwrap()
+            .veto_votes = 80;
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(500), total_votes)
+                .unwrap(),
+            vec![0, 1]
+        );
+        assert!(!payout_phases.get(2).unwrap().is_vetoed_by_votes(0));
+
+        payout_phases.mark_current_active_payout_phase_as_disbursed(500);
+        payout_phases.mark_current_active_payout_phase_as_disbursed(200);
+        assert!(payout_phases
+            .get_due_payout_phase_indexes(get_days_ahead_unix_time(500), total_votes)
+            .is_err());
+    }
+
+    #[test]
+    fn test_streaming_payout_phase() {
+        let mut payout_phases = get_streaming_payout_phases_for_test(get_days_ahead_unix_time(130));
+        let total_votes = 10_000;
+
+        // Streams are not paid out by PayOutFunds, so catching up stops there.
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(250), total_votes)
+                .unwrap(),
+            vec![0]
+        );
+        assert!(payout_phases
+            .get_streamed_payout_phase_for_claim(get_days_ahead_unix_time(250))
+            .is_err());
+
+        payout_phases.mark_current_active_payout_phase_as_disbursed(500);
+        assert!(payout_phases
+            .get_payout_phase_for_payout(get_days_ahead_unix_time(250), total_votes)
+            .is_err());
+        assert!(payout_phases
+            .get_streamed_payout_phase_for_claim(get_days_ahead_unix_time(99))
+            .is_err());
+
+        let (index, payout_phase) = payout_phases
+            .get_streamed_payout_phase_for_claim(get_days_ahead_unix_time(100))
+            .unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(
+            payout_phase.calculate_vested_stream_amount(
+                3_000,
+                get_days_ahead_unix_time(100),
+                total_votes
+            ),
+            0
+        );
+        assert_eq!(
+            payout_phase.calculate_vested_stream_amount(
+                3_000,
+                get_days_ahead_unix_time(115),
+                total_votes
+            ),
+            1_500
+        );
+        assert_eq!(
+            payout_phase.calculate_vested_stream_amount(
+                3_000,
+                get_days_ahead_unix_time(250),
+                total_votes
+            ),
+            3_000
+        );
+
+        payout_phases.record_streamed_payout(1, 1_500, 3_000);
+        let payout_phase = payout_phases.get(1).unwrap();
+        assert!(!payout_phase.is_paid_out);
+        assert_eq!(
+            payout_phase.calculate_claimable_stream_amount(
+                3_000,
+                get_days_ahead_unix_time(115),
+                total_votes
+            ),
+            0
+        );
+        assert_eq!(
+            payout_phase.calculate_claimable_stream_amount(
+                3_000,
+                get_days_ahead_unix_time(130),
+                total_votes
+            ),
+            1_500
+        );
+
+        payout_phases.record_streamed_payout(1, 1_500, 3_000);
+        assert!(payout_phases.get(1).unwrap().is_paid_out);
+        assert_eq!(payout_phases.get_total_paid_amount(), 3_500);
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(250), total_votes)
+                .unwrap(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_vetoes_freeze_streaming_payout_phase() {
+        let mut payout_phases = get_streaming_payout_phases_for_test(get_days_ahead_unix_time(130));
+        let total_votes = 10_000;
+
+        // Only the first veto sets the veto_time.
+        payout_phases.veto_payout_phase_by_authority(1, get_days_ahead_unix_time(110));
+        payout_phases.veto_payout_phase_by_authority(1, get_days_ahead_unix_time(120));
+        let payout_phase = payout_phases.get(1).unwrap();
+        assert_eq!(
+            payout_phase.stream.unwrap().veto_time,
+            Some(get_days_ahead_unix_time(110))
+        );
+        assert_eq!(
+            payout_phase.calculate_vested_stream_amount(
+                3_000,
+                get_days_ahead_unix_time(250),
+                total_votes
+            ),
+            1_000
+        );
+
+        // A veto before the payout_time freezes the whole stream.
+        let mut payout_phases = get_streaming_payout_phases_for_test(get_days_ahead_unix_time(130));
+        payout_phases.veto_payout_phase_by_authority(1, get_days_ahead_unix_time(50));
+        assert_eq!(
+            payout_phases
+                .get(1)
+                .unwrap()
+                .calculate_vested_stream_amount(3_000, get_days_ahead_unix_time(250), total_votes),
+            0
+        );
+
+        // As does a veto by votes, since voting closes at the payout_time.
+        let mut payout_phases = get_streaming_payout_phases_for_test(get_days_ahead_unix_time(130));
+        payout_phases.payout_phases[1].voting_config = Some(VotingConfig {
+            veto_votes: 80,
+            ..get_default_voting_config(get_days_ahead_unix_time(100))
+        });
+        assert_eq!(
+            payout_phases
+                .get(1)
+                .unwrap()
+                .calculate_vested_stream_amount(3_000, get_days_ahead_unix_time(250), total_votes),
+            0
+        );
+    }
+
+    #[test]
+    fn test_settle_stretch_goals() {
+        let mut payout_phases = get_stretch_goal_payout_phases_for_test(20);
+        payout_phases.settle_stretch_goals(999);
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(500), 0)
+                .unwrap(),
+            vec![0, 1]
+        );
+        assert!(payout_phases.record_veto_votes(2, 10, 0).is_err());
+
+        let mut payout_phases = get_stretch_goal_payout_phases_for_test(20);
+        payout_phases.settle_stretch_goals(1_000);
+        assert!(payout_phases.get(1).unwrap().is_deactivated);
+        assert_eq!(
+            payout_phases
+                .get_due_payout_phase_indexes(get_days_ahead_unix_time(500), 0)
+                .unwrap(),
+            vec![0, 2, 3]
+        );
+
+        // Deactivated payout phases are skipped when paying out.
+        payout_phases.mark_current_active_payout_phase_as_disbursed(500);
+        payout_phases.mark_current_active_payout_phase_as_disbursed(300);
+        assert!(!payout_phases.get(1).unwrap().is_paid_out);
+        assert_eq!(
+            payout_phases
+                .get_current_active_payout_phase_for_payout()
+                .unwrap()
+                .0,
+            3
+        );
+        payout_phases.mark_current_active_payout_phase_as_disbursed(200);
+        assert!(payout_phases
+            .get_current_active_payout_phase_for_payout()
+            .is_none());
+    }
+}
+
+// Property tests for payout phase validation and payout math. Failing cases
+// are persisted to proptest-regressions/state/payout_phases.txt and replayed
+// before any new cases are generated, so keep that file checked in.
+#[cfg(test)]
+mod proptests {
+    use proptest::{collection::vec, option, prelude::*};
+
+    use super::*;
+    use crate::{
+        buffer_times::{MaxBufferTimes, MaxBufferTimesTrait, MinBufferTimes, MinBufferTimesTrait},
+        CampaignEscrow, CrankReward, RewardTiers, SurplusPolicy, Treasury,
+    };
+
+    const CAMPAIGN_END_TIME: i64 = 1_700_000_000;
+
+    // Mostly offsets within the [min, max] buffer range, with some offsets
+    // anywhere from -max to 2 * max so that every buffer check gets exercised.
+    fn arb_time_offset(min: i64, max: i64) -> impl Strategy<Value = i64> {
+        prop_oneof![
+            19 => min..=max,
+            1 => -max..=2 * max,
+        ]
+    }
+
+    // Splits 100% at random cut points, so the basis points always sum to 100%.
+    fn arb_exact_basis_points(len: usize) -> impl Strategy<Value = Vec<u16>> {
+        vec(0..=ONE_HUNDRED_PERCENT_BASIS_POINTS, len - 1).prop_map(|mut cut_points| {
+            cut_points.sort_unstable();
+            cut_points.push(ONE_HUNDRED_PERCENT_BASIS_POINTS);
+
+            let mut previous_cut_point = 0;
+            cut_points
+                .into_iter()
+                .map(|cut_point| {
+                    let basis_points = cut_point - previous_cut_point;
+                    previous_cut_point = cut_point;
+                    basis_points
+                })
+                .collect()
+        })
+    }
+
+    fn arb_basis_points(len: usize) -> impl Strategy<Value = Vec<u16>> {
+        prop_oneof![
+            19 => arb_exact_basis_points(len),
+            1 => vec(any::<u16>(), len),
+        ]
+    }
+
+    fn arb_veto_threshold() -> impl Strategy<Value = u64> {
+        prop_oneof![
+            19 => 1..=ONE_HUNDRED_PERCENT_BASIS_POINTS as u64,
+            1 => any::<u64>(),
+        ]
+    }
+
+    fn arb_description() -> impl Strategy<Value = String> {
+        prop_oneof![
+            19 => "[a-z ]{0,200}",
+            1 => "[a-z ]{201,210}",
+        ]
+    }
+
+    fn arb_voting_config() -> impl Strategy<Value = Option<(i64, u64)>> {
+        option::weighted(
+            0.3,
+            (
+                arb_time_offset(
+                    MinBufferTimes::VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS,
+                    MaxBufferTimes::VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS,
+                ),
+                arb_veto_threshold(),
+            ),
+        )
+    }
+
+    // Arbitrary payout phase input built from time offsets, where each payout
+    // time is an offset from the previous payout (or the campaign end time),
+    // and each refund deadline and voting start time is an offset from the
+    // payout time.
+    fn arb_payout_phases_input() -> impl Strategy<Value = Vec<PayoutPhaseInput>> {
+        (1..=MAX_PAYOUT_PHASES)
+            .prop_flat_map(|len| {
+                (
+                    arb_time_offset(
+                        MinBufferTimes::CAMPAIGN_END_TIME_TO_INITIAL_PAYOUT_IN_SECONDS,
+                        MaxBufferTimes::CAMPAIGN_END_TIME_TO_INITIAL_PAYOUT_IN_SECONDS,
+                    ),
+                    vec(
+                        arb_time_offset(
+                            MinBufferTimes::PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS,
+                            MaxBufferTimes::PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS,
+                        ),
+                        len - 1,
+                    ),
+                    vec(
+                        arb_time_offset(
+                            MinBufferTimes::PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS,
+                            MaxBufferTimes::PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS,
+                        ),
+                        len,
+                    ),
+                    vec(arb_voting_config(), len),
+                    vec(arb_description(), len),
+                    arb_basis_points(len),
+                )
+            })
+            .prop_map(
+                |(
+                    initial_payout_offset,
+                    payout_offsets,
+                    refund_deadline_offsets,
+                    voting_configs,
+                    descriptions,
+                    basis_points,
+                )| {
+                    let mut payout_time = CAMPAIGN_END_TIME;
+                    std::iter::once(initial_payout_offset)
+                        .chain(payout_offsets)
+                        .zip(refund_deadline_offsets)
+                        .zip(voting_configs)
+                        .zip(descriptions)
+                        .zip(basis_points)
+                        .map(
+                            |(
+                                (
+                                    ((payout_offset, refund_deadline_offset), voting_config),
+                                    description,
+                                ),
+                                payout_basis_points,
+                            )| {
+                                payout_time += payout_offset;
+                                PayoutPhaseInput {
+                                    payout_basis_points,
+                                    payout_time,
+                                    refund_deadline: payout_time + refund_deadline_offset,
+                                    description,
+                                    stretch_goal: None,
+                                    stream: None,
+                                    voting_config: voting_config.map(
+                                        |(voting_window, vote_basis_points_veto_threshold)| {
+                                            VotingConfigInput {
+                                                voting_start_time: payout_time - voting_window,
+                                                vote_basis_points_veto_threshold,
+                                            }
+                                        },
+                                    ),
+                                }
+                            },
+                        )
+                        .collect()
+                },
+            )
+    }
+
+    // Any timestamp, biased towards the ends of the i64 range.
+    fn arb_extreme_time() -> impl Strategy<Value = i64> {
+        prop_oneof![
+            any::<i64>(),
+            i64::MAX - MaxBufferTimes::PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS..=i64::MAX,
+            i64::MIN..=i64::MIN + MaxBufferTimes::PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS,
+        ]
+    }
+
+    fn get_campaign_escrow_for_test(
+        payout_phases: PayoutPhases,
+        total_funds: u64,
+    ) -> CampaignEscrow {
+        CampaignEscrow {
+            version: CampaignEscrow::VERSION,
+            bump: 255,
+            campaign_uuid: String::from("2c6e5e8f4a9f4b4c9d627d7a2e1c5b3a"),
+            authority: Pubkey::default(),
+            creator: Pubkey::default(),
+            payout_wallet: Pubkey::default(),
+            treasury: Treasury {
+                bump: 255,
+                treasury_mint: Pubkey::default(),
+                treasury_escrow: Pubkey::default(),
+                total_funds,
+                total_paid_out: 0,
+                total_refunded: 0,
+                total_surplus: 0,
+                supporter_surplus: 0,
+                supporter_surplus_paid_out: 0,
+                total_crank_rewards: 0,
+                pending_estimated_funds: 0,
+                total_donations: 0,
+                non_voting_donations: 0,
+                non_refundable_donations: 0,
+                total_matched: 0,
+                closed_deposit_amount: 0,
             },
-            None => panic!("Value should be a Some variant."),
+            deposit_escrow_infos: vec![],
+            campaign_end_time: CAMPAIGN_END_TIME,
+            deposit_count: 0,
+            processed_deposit_count: 0,
+            closed_deposit_record_count: 0,
+            payouts_ready: false,
+            surplus_policy: SurplusPolicy::Creator,
+            crank_reward: CrankReward::None,
+            collection_mint: Pubkey::default(),
+            deposit_escrow_infos_capacity: CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            fundraising_start_time: None,
+            deposit_grace_period: 0,
+            reward_tiers: RewardTiers::default(),
+            hard_cap: None,
+            auto_end_at_hard_cap: false,
+            hard_cap_reached_at: None,
+            settled_raise: None,
+            has_matching_pool: false,
+            payout_phases,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_accepted_payout_phases_are_well_formed(
+            payout_phases_input in arb_payout_phases_input()
+        ) {
+            let payout_phases = match PayoutPhases::new(payout_phases_input, CAMPAIGN_END_TIME) {
+                Ok(payout_phases) => payout_phases,
+                Err(_) => return Ok(()),
+            };
+
+            let total_basis_points: u32 = payout_phases
+                .iter()
+                .map(|payout_phase| payout_phase.payout_basis_points as u32)
+                .sum();
+            prop_assert_eq!(total_basis_points, ONE_HUNDRED_PERCENT_BASIS_POINTS as u32);
+
+            let mut previous_payout_time = CAMPAIGN_END_TIME;
+            for payout_phase in payout_phases.iter() {
+                prop_assert!(payout_phase.payout_time > previous_payout_time);
+                prop_assert!(payout_phase.refund_deadline > payout_phase.payout_time);
+                if let Some(voting_config) = &payout_phase.voting_config {
+                    prop_assert!(voting_config.voting_start_time > CAMPAIGN_END_TIME);
+                    prop_assert!(voting_config.voting_start_time < payout_phase.payout_time);
+                }
+                previous_payout_time = payout_phase.payout_time;
+            }
         }
 
-        payout_phases.mark_current_active_payout_phase_as_disbursed();
+        #[test]
+        fn test_payout_phases_validation_never_panics(
+            campaign_end_time in arb_extreme_time(),
+            payout_phases_input in (1..=MAX_PAYOUT_PHASES).prop_flat_map(|len| {
+                (
+                    arb_exact_basis_points(len),
+                    vec(
+                        (
+                            arb_extreme_time(),
+                            arb_extreme_time(),
+                            option::of((arb_extreme_time(), arb_veto_threshold())),
+                        ),
+                        len,
+                    ),
+                )
+            }),
+        ) {
+            let (basis_points, times) = payout_phases_input;
+            let payout_phases_input = basis_points
+                .into_iter()
+                .zip(times)
+                .map(|(payout_basis_points, (payout_time, refund_deadline, voting_config))| {
+                    PayoutPhaseInput {
+                        payout_basis_points,
+                        payout_time,
+                        refund_deadline,
+                        description: String::new(),
+                        stretch_goal: None,
+                        stream: None,
+                        voting_config: voting_config.map(
+                            |(voting_start_time, vote_basis_points_veto_threshold)| {
+                                VotingConfigInput {
+                                    voting_start_time,
+                                    vote_basis_points_veto_threshold,
+                                }
+                            },
+                        ),
+                    }
+                })
+                .collect();
 
-        let payout_ready = payout_phases.get_current_active_payout_phase_for_payout();
+            // Invalid input must be rejected with an error rather than a panic.
+            let _ = PayoutPhases::new(payout_phases_input, campaign_end_time);
+        }
 
-        match payout_ready {
-            Some(payout_phase) => match payout_phase {
-                PayoutPhaseEnum::Voting(val) => {
-                    assert_eq!(val.shared_fields.index, 1);
-                    assert_eq!(
-                        val.shared_fields.payout_basis_points,
-                        percent_to_basis_points(20)
-                    );
-                    assert_eq!(val.shared_fields.payout_time, get_days_ahead_unix_time(100));
-                    assert_eq!(
-                        val.shared_fields.refund_deadline,
-                        get_days_ahead_unix_time(131)
+        #[test]
+        fn test_payouts_never_exceed_total_funds(
+            payout_phases_input in arb_payout_phases_input(),
+            total_funds in prop_oneof![any::<u64>(), 0..=1_000_000u64],
+        ) {
+            let payout_phases = match PayoutPhases::new(payout_phases_input, CAMPAIGN_END_TIME) {
+                Ok(payout_phases) => payout_phases,
+                Err(_) => return Ok(()),
+            };
+            let payout_phases_len = payout_phases.len() as u128;
+            let mut campaign_escrow = get_campaign_escrow_for_test(payout_phases, total_funds);
+
+            let mut total_paid_out: u128 = 0;
+            while let Some((_, payout_phase)) = campaign_escrow
+                .payout_phases
+                .get_current_active_payout_phase_for_payout()
+            {
+                let payout_amount = campaign_escrow
+                    .calculate_payout_amount_from_payout_basis_points(
+                        payout_phase.payout_basis_points,
                     );
-                }
-                PayoutPhaseEnum::NonVoting(_) => {
-                    panic!("Value should be a PayoutPhaseEnum::Voting.")
-                }
-            },
-            None => panic!("Value should be a Some variant."),
+                prop_assert!(payout_amount <= total_funds);
+                total_paid_out += payout_amount as u128;
+                prop_assert!(campaign_escrow.record_payout(payout_amount).is_ok());
+            }
+            prop_assert_eq!(campaign_escrow.treasury.total_paid_out as u128, total_paid_out);
+            prop_assert_eq!(campaign_escrow.payout_phases.get_total_paid_amount(), total_paid_out);
+
+            // Each payout rounds down by less than one unit.
+            prop_assert!(total_paid_out <= total_funds as u128);
+            prop_assert!(total_paid_out + payout_phases_len > total_funds as u128);
         }
     }
 }
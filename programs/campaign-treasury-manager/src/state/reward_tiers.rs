@@ -0,0 +1,218 @@
+use anchor_lang::prelude::*;
+
+use crate::CampaignTreasuryManagerError;
+
+// Reward tiers the campaign NFTs are sold at, stored as a single list. A
+// deposit claims a tier by its index in this list.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct RewardTiers {
+    reward_tiers: Vec<RewardTier>,
+}
+
+impl RewardTiers {
+    // Maximum number of reward tiers. Like PayoutPhases, the CampaignEscrow
+    // account is sized for the actual number of tiers.
+    pub const REWARD_TIERS_LEN_LIMIT: usize = 16;
+
+    pub fn get_space(reward_tiers_len: usize) -> usize {
+        // Clamp the length to the limit so that oversized input fails with a clear
+        // error in RewardTiers::new rather than during account allocation.
+        let reward_tiers_len = reward_tiers_len.min(Self::REWARD_TIERS_LEN_LIMIT);
+
+        4 + // 4 bytes of overhead for vec storage
+        reward_tiers_len * RewardTier::SPACE // reward_tiers
+    }
+}
+
+impl RewardTiers {
+    pub fn new(reward_tiers_input: Vec<RewardTierInput>) -> Result<Self> {
+        let reward_tiers_len = reward_tiers_input.len();
+        if reward_tiers_len > RewardTiers::REWARD_TIERS_LEN_LIMIT {
+            msg!(
+                "Received {} reward tiers but at most {} are allowed.",
+                reward_tiers_len,
+                RewardTiers::REWARD_TIERS_LEN_LIMIT
+            );
+            return Err(CampaignTreasuryManagerError::InvalidRewardTiers.into());
+        }
+
+        let reward_tiers = RewardTiers {
+            reward_tiers: reward_tiers_input
+                .into_iter()
+                .map(|reward_tier_input| RewardTier {
+                    min_deposit_amount: reward_tier_input.min_deposit_amount,
+                    max_supply: reward_tier_input.max_supply,
+                    sold_count: 0,
+                    metadata_uri: reward_tier_input.metadata_uri,
+                })
+                .collect(),
+        };
+
+        reward_tiers.assert_is_valid()?;
+
+        Ok(reward_tiers)
+    }
+
+    fn assert_is_valid(&self) -> Result<()> {
+        for (index, reward_tier) in self.reward_tiers.iter().enumerate() {
+            if reward_tier.max_supply == 0 {
+                msg!(
+                    "Index {} RewardTier max_supply must be greater than 0.",
+                    index
+                );
+                return Err(CampaignTreasuryManagerError::InvalidRewardTiers.into());
+            }
+
+            // The metadata_uri is bounded because the account space is
+            // calculated using the maximum length.
+            let metadata_uri_length = reward_tier.metadata_uri.len();
+            if metadata_uri_length > RewardTier::MAX_METADATA_URI_LENGTH {
+                msg!(
+                    "Index {} RewardTier metadata_uri length {} exceeds the maximum length of {}.",
+                    index,
+                    metadata_uri_length,
+                    RewardTier::MAX_METADATA_URI_LENGTH
+                );
+                return Err(CampaignTreasuryManagerError::InvalidRewardTiers.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.reward_tiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&RewardTier> {
+        self.reward_tiers.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RewardTier> {
+        self.reward_tiers.iter()
+    }
+
+    // Validates a deposit against the reward tier it claims and counts it
+    // toward the tier supply. The tier_deposit_amount is the deposit value in
+    // the treasury currency.
+    pub fn record_sale(&mut self, reward_tier_index: u8, tier_deposit_amount: u64) -> Result<()> {
+        let reward_tier = match self.reward_tiers.get_mut(reward_tier_index as usize) {
+            Some(reward_tier) => reward_tier,
+            None => {
+                msg!("No RewardTier exists at index {}.", reward_tier_index);
+                return Err(CampaignTreasuryManagerError::InvalidRewardTier.into());
+            }
+        };
+
+        if tier_deposit_amount < reward_tier.min_deposit_amount {
+            msg!(
+                "Deposit amount {} is below the RewardTier {} min_deposit_amount {}.",
+                tier_deposit_amount,
+                reward_tier_index,
+                reward_tier.min_deposit_amount
+            );
+            return Err(CampaignTreasuryManagerError::InvalidRewardTier.into());
+        }
+
+        if reward_tier.sold_count >= reward_tier.max_supply {
+            msg!(
+                "RewardTier {} is sold out, max_supply = {}.",
+                reward_tier_index,
+                reward_tier.max_supply
+            );
+            return Err(CampaignTreasuryManagerError::RewardTierSoldOut.into());
+        }
+
+        reward_tier.sold_count = reward_tier.sold_count.checked_add(1).unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RewardTierInput {
+    pub min_deposit_amount: u64,
+    pub max_supply: u32,
+    pub metadata_uri: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RewardTier {
+    // Minimum deposit amount in the treasury currency.
+    pub min_deposit_amount: u64,
+    // Maximum number of deposits which can claim this tier.
+    pub max_supply: u32,
+    // Number of deposits which have claimed this tier.
+    pub sold_count: u32,
+    // URI of the off-chain tier metadata, e.g. the reward description.
+    pub metadata_uri: String,
+}
+
+impl RewardTier {
+    // Maximum metadata_uri length in bytes, which matches the Metaplex
+    // metadata URI length limit.
+    pub const MAX_METADATA_URI_LENGTH: usize = 200;
+
+    pub const SPACE: usize = 8 + // min_deposit_amount
+        4 + // max_supply
+        4 + // sold_count
+        4 + // string size allocation
+        RewardTier::MAX_METADATA_URI_LENGTH; // metadata_uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_reward_tier_input(min_deposit_amount: u64, max_supply: u32) -> RewardTierInput {
+        RewardTierInput {
+            min_deposit_amount,
+            max_supply,
+            metadata_uri: String::from("https://arweave.net/reward-tier"),
+        }
+    }
+
+    #[test]
+    fn test_new_reward_tiers() {
+        let reward_tiers = RewardTiers::new(vec![
+            get_reward_tier_input(100, 10),
+            get_reward_tier_input(500, 1),
+        ])
+        .unwrap();
+        assert_eq!(reward_tiers.len(), 2);
+        assert_eq!(reward_tiers.get(1).unwrap().sold_count, 0);
+
+        assert!(RewardTiers::new(vec![get_reward_tier_input(100, 0)]).is_err());
+
+        let mut reward_tier_input = get_reward_tier_input(100, 10);
+        reward_tier_input.metadata_uri = "a".repeat(RewardTier::MAX_METADATA_URI_LENGTH + 1);
+        assert!(RewardTiers::new(vec![reward_tier_input]).is_err());
+
+        let reward_tiers_input =
+            vec![get_reward_tier_input(100, 10); RewardTiers::REWARD_TIERS_LEN_LIMIT + 1];
+        assert!(RewardTiers::new(reward_tiers_input).is_err());
+    }
+
+    #[test]
+    fn test_record_sale() {
+        let mut reward_tiers = RewardTiers::new(vec![
+            get_reward_tier_input(100, 10),
+            get_reward_tier_input(500, 1),
+        ])
+        .unwrap();
+
+        assert!(reward_tiers.record_sale(2, 500).is_err());
+        assert!(reward_tiers.record_sale(1, 499).is_err());
+        reward_tiers.record_sale(1, 500).unwrap();
+        assert_eq!(reward_tiers.get(1).unwrap().sold_count, 1);
+        assert!(reward_tiers.record_sale(1, 500).is_err());
+
+        // A deposit can claim a lower tier than its amount allows.
+        reward_tiers.record_sale(0, 500).unwrap();
+        assert_eq!(reward_tiers.get(0).unwrap().sold_count, 1);
+    }
+}
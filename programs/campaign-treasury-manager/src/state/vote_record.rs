@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+// This is a PDA of the DepositRecord + payout phase index, or of the
+// DonationRecord + payout phase index for donation votes.
+// If this account exists, the DepositRecord NFT has voted on the payout phase,
+// so it cannot vote again after it changes hands.
+#[account]
+#[derive(Default, Debug)]
+pub struct VoteRecord {
+    // PDA bump.
+    pub bump: u8,
+    // The DepositRecord whose NFT cast the vote, or the DonationRecord which
+    // cast the vote.
+    pub deposit_record: Pubkey,
+    // The payout phase which was voted on.
+    pub payout_phase_index: u8,
+    // The NFT holder or donor which cast the vote.
+    pub voter: Pubkey,
+    // Veto votes cast, which are weighted by the processed deposit amount.
+    pub veto_votes: u64,
+}
+
+impl VoteRecord {
+    pub const SPACE: usize = 8 + // account discriminator
+        1 + // bump
+        32 + // deposit_record
+        1 + // payout_phase_index
+        32 + // voter
+        8 + // veto_votes
+        32; // extra padding
+
+    pub const PREFIX: &'static str = "vote_record";
+}
@@ -26,6 +26,8 @@ pub trait MinBufferTimesTrait {
     const PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS: i64;
     // Minimum time between last payout and when the escrow can be closed.
     const LAST_REFUND_DEADLINE_TO_CLOSE_ESCROW_IN_SECONDS: i64;
+    // Minimum voting window for a voting payout phase (voting closes at the payout time).
+    const VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS: i64;
 }
 
 // Enabled for testing.
@@ -36,6 +38,7 @@ impl MinBufferTimesTrait for MinBufferTimes {
     const PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS: i64 = 2;
     const PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS: i64 = 2;
     const LAST_REFUND_DEADLINE_TO_CLOSE_ESCROW_IN_SECONDS: i64 = 2;
+    const VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS: i64 = 2;
 }
 
 // Enabled for non-test builds.
@@ -46,6 +49,7 @@ impl MinBufferTimesTrait for MinBufferTimes {
     const PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS: i64 = days(30);
     const PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS: i64 = days(30);
     const LAST_REFUND_DEADLINE_TO_CLOSE_ESCROW_IN_SECONDS: i64 = days(3);
+    const VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS: i64 = days(3);
 }
 
 pub struct MaxBufferTimes {}
@@ -59,6 +63,8 @@ pub trait MaxBufferTimesTrait {
     const PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS: i64;
     // Maximum time to claim refunds for a payout phase.
     const PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS: i64;
+    // Maximum voting window for a voting payout phase (voting closes at the payout time).
+    const VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS: i64;
 }
 
 // Enabled for testing.
@@ -68,6 +74,7 @@ impl MaxBufferTimesTrait for MaxBufferTimes {
     const CAMPAIGN_END_TIME_TO_INITIAL_PAYOUT_IN_SECONDS: i64 = 10;
     const PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS: i64 = 10;
     const PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS: i64 = 10;
+    const VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS: i64 = 10;
 }
 
 // Enabled for non-test builds.
@@ -77,4 +84,5 @@ impl MaxBufferTimesTrait for MaxBufferTimes {
     const CAMPAIGN_END_TIME_TO_INITIAL_PAYOUT_IN_SECONDS: i64 = days(14);
     const PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS: i64 = months(6);
     const PAYOUT_TIME_TO_REFUND_DEADLINE_IN_SECONDS: i64 = months(3);
+    const VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS: i64 = days(30);
 }
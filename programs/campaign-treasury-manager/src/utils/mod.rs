@@ -1,8 +1,10 @@
 pub mod buffer_times;
+pub mod price_oracle;
 #[allow(clippy::module_inception)]
 pub mod utils;
 pub mod validate_cpi_invocation;
 
 pub use buffer_times::*;
+pub use price_oracle::*;
 pub use utils::*;
 pub use validate_cpi_invocation::*;
@@ -0,0 +1,300 @@
+use std::convert::TryInto;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{assert_keys_equal, cmp_pubkeys, CampaignTreasuryManagerError};
+
+pub const PYTH_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+pub const PYTH_DEVNET_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+
+// The price fields of a Pyth price account. Only the fields needed to value
+// deposits are read, at their offsets in the Pyth V2 price account layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriceOracle {
+    // Price exponent, the price is price * 10^expo.
+    pub expo: i32,
+    // Time the aggregate price was last updated.
+    pub timestamp: i64,
+    // Aggregate price.
+    pub price: i64,
+    // Aggregate price confidence interval.
+    pub conf: u64,
+    // Aggregate price status, see PriceOracle::STATUS_TRADING.
+    pub status: u32,
+}
+
+impl PriceOracle {
+    pub const MAGIC: u32 = 0xa1b2c3d4;
+    pub const VERSION: u32 = 2;
+    pub const ACCOUNT_TYPE_PRICE: u32 = 3;
+    pub const STATUS_TRADING: u32 = 1;
+
+    const EXPO_OFFSET: usize = 20;
+    const TIMESTAMP_OFFSET: usize = 96;
+    const AGGREGATE_PRICE_OFFSET: usize = 208;
+    const AGGREGATE_CONF_OFFSET: usize = 216;
+    const AGGREGATE_STATUS_OFFSET: usize = 224;
+
+    // Size of the price account up to and including the aggregate price. The
+    // per publisher price components which follow are not read.
+    pub const LEN: usize = 240;
+
+    // Prices older than this are rejected.
+    pub const MAX_PRICE_AGE_IN_SECONDS: i64 = 60;
+
+    pub fn is_valid_owner(owner: &Pubkey) -> bool {
+        cmp_pubkeys(owner, &PYTH_PROGRAM_ID) || cmp_pubkeys(owner, &PYTH_DEVNET_PROGRAM_ID)
+    }
+
+    pub fn try_from_account_info(price_oracle: &AccountInfo) -> Result<Self> {
+        if !PriceOracle::is_valid_owner(price_oracle.owner) {
+            msg!(
+                "Price oracle {} is owned by {}, which is not the Pyth program.",
+                price_oracle.key(),
+                price_oracle.owner
+            );
+            return Err(CampaignTreasuryManagerError::InvalidPriceOracle.into());
+        }
+
+        let data = price_oracle.try_borrow_data()?;
+        PriceOracle::try_from_slice(&data)
+    }
+
+    pub fn try_from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() < PriceOracle::LEN
+            || read_u32(data, 0) != PriceOracle::MAGIC
+            || read_u32(data, 4) != PriceOracle::VERSION
+            || read_u32(data, 8) != PriceOracle::ACCOUNT_TYPE_PRICE
+        {
+            msg!("Price oracle account is not a Pyth price account.");
+            return Err(CampaignTreasuryManagerError::InvalidPriceOracle.into());
+        }
+
+        Ok(PriceOracle {
+            expo: read_u32(data, PriceOracle::EXPO_OFFSET) as i32,
+            timestamp: read_u64(data, PriceOracle::TIMESTAMP_OFFSET) as i64,
+            price: read_u64(data, PriceOracle::AGGREGATE_PRICE_OFFSET) as i64,
+            conf: read_u64(data, PriceOracle::AGGREGATE_CONF_OFFSET),
+            status: read_u32(data, PriceOracle::AGGREGATE_STATUS_OFFSET),
+        })
+    }
+
+    // Serializes the price fields in the Pyth price account layout. This is
+    // used to create mock price accounts for local testing.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        let mut data = vec![0; PriceOracle::LEN];
+        write_bytes(&mut data, 0, &PriceOracle::MAGIC.to_le_bytes());
+        write_bytes(&mut data, 4, &PriceOracle::VERSION.to_le_bytes());
+        write_bytes(&mut data, 8, &PriceOracle::ACCOUNT_TYPE_PRICE.to_le_bytes());
+        write_bytes(&mut data, 12, &(PriceOracle::LEN as u32).to_le_bytes());
+        write_bytes(
+            &mut data,
+            PriceOracle::EXPO_OFFSET,
+            &self.expo.to_le_bytes(),
+        );
+        write_bytes(
+            &mut data,
+            PriceOracle::TIMESTAMP_OFFSET,
+            &self.timestamp.to_le_bytes(),
+        );
+        write_bytes(
+            &mut data,
+            PriceOracle::AGGREGATE_PRICE_OFFSET,
+            &self.price.to_le_bytes(),
+        );
+        write_bytes(
+            &mut data,
+            PriceOracle::AGGREGATE_CONF_OFFSET,
+            &self.conf.to_le_bytes(),
+        );
+        write_bytes(
+            &mut data,
+            PriceOracle::AGGREGATE_STATUS_OFFSET,
+            &self.status.to_le_bytes(),
+        );
+        data
+    }
+
+    // Returns the current price and exponent, if the price is trading, positive
+    // and recent.
+    pub fn get_current_price(&self, now: i64) -> Result<(u64, i32)> {
+        if self.status != PriceOracle::STATUS_TRADING || self.price <= 0 {
+            msg!(
+                "Price oracle price {} with status {} is not usable.",
+                self.price,
+                self.status
+            );
+            return Err(CampaignTreasuryManagerError::InvalidPriceOracle.into());
+        }
+
+        if now.saturating_sub(self.timestamp) > PriceOracle::MAX_PRICE_AGE_IN_SECONDS {
+            msg!(
+                "Price oracle price was last updated at {}, which is more than {} seconds ago.",
+                self.timestamp,
+                PriceOracle::MAX_PRICE_AGE_IN_SECONDS
+            );
+            return Err(CampaignTreasuryManagerError::InvalidPriceOracle.into());
+        }
+
+        Ok((self.price as u64, self.expo))
+    }
+
+    // Values an amount of the deposit mint in the treasury mint. The price is
+    // the price of one deposit mint token in treasury mint tokens, so the mint
+    // decimals are used to convert between base units.
+    pub fn estimate_value(
+        &self,
+        amount: u64,
+        deposit_mint_decimals: u8,
+        treasury_mint_decimals: u8,
+        now: i64,
+    ) -> Result<u64> {
+        let (price, expo) = self.get_current_price(now)?;
+        let exponent = expo + treasury_mint_decimals as i32 - deposit_mint_decimals as i32;
+
+        let value = (amount as u128).checked_mul(price as u128);
+        let value = if exponent >= 0 {
+            10u128
+                .checked_pow(exponent as u32)
+                .and_then(|scale| value?.checked_mul(scale))
+        } else {
+            10u128
+                .checked_pow(exponent.unsigned_abs())
+                .map_or(Some(0), |scale| value.map(|value| value / scale))
+        };
+
+        match value.and_then(|value| value.try_into().ok()) {
+            Some(value) => Ok(value),
+            None => {
+                msg!(
+                    "Estimated value of {} at price {} * 10^{} overflows.",
+                    amount,
+                    price,
+                    expo
+                );
+                Err(CampaignTreasuryManagerError::InvalidPriceOracle.into())
+            }
+        }
+    }
+}
+
+// Estimates the treasury currency value of a deposit with the DepositEscrow
+// price oracle. The price oracle and the treasury mint, which is needed for its
+// decimals, must be passed as the first two remaining accounts.
+pub fn estimate_deposit_value(
+    remaining_accounts: &[AccountInfo],
+    price_oracle: &Pubkey,
+    treasury_mint: &Pubkey,
+    deposit_mint_decimals: u8,
+    deposit_amount: u64,
+) -> Result<u64> {
+    let (price_oracle_account, treasury_mint_account) = match remaining_accounts {
+        [price_oracle_account, treasury_mint_account, ..] => {
+            (price_oracle_account, treasury_mint_account)
+        }
+        _ => {
+            msg!(
+                "The price oracle {} and treasury mint {} must be passed as remaining accounts.",
+                price_oracle,
+                treasury_mint
+            );
+            return Err(CampaignTreasuryManagerError::InvalidPriceOracle.into());
+        }
+    };
+
+    assert_keys_equal(
+        price_oracle,
+        price_oracle_account.key,
+        Some(CampaignTreasuryManagerError::InvalidPriceOracle),
+    )?;
+    assert_keys_equal(
+        treasury_mint,
+        treasury_mint_account.key,
+        Some(CampaignTreasuryManagerError::InvalidPriceOracle),
+    )?;
+
+    let treasury_mint_decimals = {
+        let data = treasury_mint_account.try_borrow_data()?;
+        Mint::try_deserialize(&mut data.as_ref())?.decimals
+    };
+
+    let clock = Clock::get()?;
+    PriceOracle::try_from_account_info(price_oracle_account)?.estimate_value(
+        deposit_amount,
+        deposit_mint_decimals,
+        treasury_mint_decimals,
+        clock.unix_timestamp,
+    )
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn write_bytes(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_price_oracle_for_test(price: i64, expo: i32) -> PriceOracle {
+        PriceOracle {
+            expo,
+            timestamp: 1_000,
+            price,
+            conf: 0,
+            status: PriceOracle::STATUS_TRADING,
+        }
+    }
+
+    #[test]
+    fn test_price_oracle_account_data_round_trip() {
+        let price_oracle = get_price_oracle_for_test(2_050_000, -8);
+        let data = price_oracle.to_account_data();
+        assert_eq!(PriceOracle::try_from_slice(&data).unwrap(), price_oracle);
+
+        assert!(PriceOracle::try_from_slice(&data[..PriceOracle::LEN - 1]).is_err());
+        let mut data = data;
+        data[8] = 1;
+        assert!(PriceOracle::try_from_slice(&data).is_err());
+    }
+
+    #[test]
+    fn test_estimate_value() {
+        // 1 token with 6 decimals is worth 0.5 of a 9 decimal token.
+        let price_oracle = get_price_oracle_for_test(50, -2);
+        assert_eq!(
+            price_oracle.estimate_value(2_000_000, 6, 9, 1_000).unwrap(),
+            1_000_000_000
+        );
+        // 1 token with 9 decimals is worth 20.5 of a 6 decimal token.
+        let price_oracle = get_price_oracle_for_test(2_050_000_000, -8);
+        assert_eq!(
+            price_oracle
+                .estimate_value(1_000_000_000, 9, 6, 1_000)
+                .unwrap(),
+            20_500_000
+        );
+
+        assert!(price_oracle.estimate_value(u64::MAX, 0, 18, 1_000).is_err());
+        assert!(price_oracle
+            .estimate_value(1, 9, 6, 1_000 + PriceOracle::MAX_PRICE_AGE_IN_SECONDS + 1)
+            .is_err());
+
+        let mut price_oracle = price_oracle;
+        price_oracle.status = 0;
+        assert!(price_oracle.estimate_value(1, 9, 6, 1_000).is_err());
+        let price_oracle = get_price_oracle_for_test(-1, -8);
+        assert!(price_oracle.estimate_value(1, 9, 6, 1_000).is_err());
+    }
+}
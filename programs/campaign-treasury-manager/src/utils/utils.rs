@@ -3,7 +3,11 @@ use std::convert::TryInto;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Token},
+    token::{self, Token, TokenAccount},
+};
+use mpl_token_metadata::{
+    pda::find_metadata_account,
+    state::{Metadata, TokenMetadataAccount},
 };
 use solana_program::{
     program::{invoke, invoke_signed},
@@ -19,7 +23,9 @@ use spl_token::instruction::initialize_account2;
 
 use crate::{
     buffer_times::{MaxBufferTimes, MaxBufferTimesTrait, MinBufferTimes, MinBufferTimesTrait},
-    CampaignEscrow, CampaignTreasuryManagerError, DepositEscrow, PayoutPhaseEnum, TreasuryEscrow,
+    constants::ONE_HUNDRED_PERCENT_BASIS_POINTS,
+    CampaignEscrow, CampaignTreasuryManagerError, DepositEscrow, DepositRecord, MatchingPoolEscrow,
+    PayoutPhase, TreasuryEscrow,
 };
 
 pub fn cmp_pubkeys(a: &Pubkey, b: &Pubkey) -> bool {
@@ -30,6 +36,18 @@ pub fn is_native_mint(mint: &Pubkey) -> bool {
     mint.key() == spl_token::native_mint::id()
 }
 
+// Payout amount for a payout phase, as a share of the total funds.
+pub fn calculate_payout_amount(total_funds: u64, payout_basis_points: u16) -> u64 {
+    // Multiply before dividing, otherwise any payout below 100% rounds
+    // down to zero. This is done in u128 to avoid overflow.
+    let payout_amount = (total_funds as u128)
+        .checked_mul(payout_basis_points as u128)
+        .unwrap()
+        .checked_div(ONE_HUNDRED_PERCENT_BASIS_POINTS as u128)
+        .unwrap();
+    payout_amount.try_into().unwrap()
+}
+
 pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<()> {
     if !cmp_pubkeys(account.owner, owner) {
         Err(CampaignTreasuryManagerError::IncorrectOwner.into())
@@ -74,6 +92,63 @@ pub fn assert_keys_equal(
     }
 }
 
+// Asserts that the metadata account is the Metaplex metadata PDA of the NFT
+// mint and that the NFT is a verified member of the given collection.
+pub fn assert_nft_is_in_collection(
+    metadata: &AccountInfo,
+    mint: &Pubkey,
+    collection_mint: &Pubkey,
+) -> Result<()> {
+    let (expected_metadata, _) = find_metadata_account(mint);
+    assert_keys_equal(
+        &expected_metadata,
+        metadata.key,
+        Some(CampaignTreasuryManagerError::InvalidNftCollection),
+    )?;
+
+    let metadata = match Metadata::from_account_info(metadata) {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            msg!("Failed to deserialize Metadata account {}.", metadata.key());
+            return Err(CampaignTreasuryManagerError::InvalidNftCollection.into());
+        }
+    };
+
+    match metadata.collection {
+        Some(collection)
+            if collection.verified && cmp_pubkeys(&collection.key, collection_mint) =>
+        {
+            Ok(())
+        }
+        _ => {
+            msg!(
+                "NFT mint {} is not a verified member of collection {}.",
+                mint,
+                collection_mint
+            );
+            Err(CampaignTreasuryManagerError::InvalidNftCollection.into())
+        }
+    }
+}
+
+// Asserts that the DepositRecord belongs to the campaign, and that its refund
+// is claimed by the current NFT holder and paid to their payment account.
+pub fn assert_refund_is_authorized(
+    campaign_escrow: &Account<CampaignEscrow>,
+    deposit_record: &DepositRecord,
+    nft_holder: &Pubkey,
+    nft_token_account: &TokenAccount,
+    refund_receiver: &Pubkey,
+) -> Result<()> {
+    deposit_record.assert_belongs_to(&campaign_escrow.key())?;
+    deposit_record.assert_is_held_by(nft_holder, nft_token_account)?;
+    assert_keys_equal(
+        &campaign_escrow.get_refund_receiver(nft_holder),
+        refund_receiver,
+        None,
+    )
+}
+
 pub fn assert_valid_escrow_management_signers<'a>(
     creator: &AccountInfo<'a>,
     authority: &AccountInfo<'a>,
@@ -133,12 +208,108 @@ pub fn assert_payout_time_and_refund_deadline_are_valid(
     Ok(())
 }
 
+pub fn assert_voting_config_is_valid(
+    index: usize,
+    payout_phase: &PayoutPhase,
+    campaign_end_time: i64,
+) -> Result<()> {
+    let voting_config = match &payout_phase.voting_config {
+        Some(voting_config) => voting_config,
+        None => return Ok(()),
+    };
+
+    let vote_basis_points_veto_threshold = voting_config.vote_basis_points_veto_threshold;
+    if vote_basis_points_veto_threshold == 0
+        || vote_basis_points_veto_threshold > ONE_HUNDRED_PERCENT_BASIS_POINTS as u64
+    {
+        msg!(
+            "Index {} PayoutPhase vote_basis_points_veto_threshold {} must be between 1 and {}.",
+            index,
+            vote_basis_points_veto_threshold,
+            ONE_HUNDRED_PERCENT_BASIS_POINTS
+        );
+        return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+    }
+
+    let voting_start_time = voting_config.voting_start_time;
+    if voting_start_time <= campaign_end_time {
+        msg!(
+            "Index {} PayoutPhase voting_start_time must be after the campaign_end_time.",
+            index
+        );
+        return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+    }
+
+    // Voting closes at the payout_time.
+    let payout_time = payout_phase.payout_time;
+    if payout_time <= voting_start_time {
+        msg!(
+            "Index {} PayoutPhase voting_start_time must be before the payout_time.",
+            index
+        );
+        return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+    }
+
+    assert_min_time_buffer(
+        voting_start_time,
+        payout_time,
+        MinBufferTimes::VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS,
+        CampaignTreasuryManagerError::InvalidPayoutPhases,
+        format!(
+            "PayoutPhase index {} voting_start_time {} failed min buffer time check against payout_time.",
+            index, voting_start_time
+        ),
+    )?;
+
+    assert_max_time_buffer(
+        voting_start_time,
+        payout_time,
+        MaxBufferTimes::VOTING_START_TIME_TO_PAYOUT_TIME_IN_SECONDS,
+        CampaignTreasuryManagerError::InvalidPayoutPhases,
+        format!(
+            "PayoutPhase index {} voting_start_time {} failed max buffer time check against payout_time.",
+            index, voting_start_time
+        ),
+    )?;
+
+    Ok(())
+}
+
+pub fn assert_payout_stream_is_valid(index: usize, payout_phase: &PayoutPhase) -> Result<()> {
+    let stream = match &payout_phase.stream {
+        Some(stream) => stream,
+        None => return Ok(()),
+    };
+
+    if stream.stream_end_time <= payout_phase.payout_time {
+        msg!(
+            "Index {} PayoutPhase stream_end_time must be after the payout_time.",
+            index
+        );
+        return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+    }
+
+    // A stream lasts at most as long as the time between two payout phases.
+    assert_max_time_buffer(
+        payout_phase.payout_time,
+        stream.stream_end_time,
+        MaxBufferTimes::PAYOUT_TIME_TO_NEXT_PAYOUT_TIME_IN_SECONDS,
+        CampaignTreasuryManagerError::InvalidPayoutPhases,
+        format!(
+            "PayoutPhase index {} stream_end_time failed max buffer time check against payout_time.",
+            index
+        ),
+    )?;
+
+    Ok(())
+}
+
 pub fn assert_initial_payout_phase_is_valid(
-    initial_payout_phase: PayoutPhaseEnum,
+    initial_payout_phase: &PayoutPhase,
     campaign_end_time: i64,
 ) -> Result<()> {
-    let payout_time = PayoutPhaseEnum::get_payout_time(&initial_payout_phase);
-    let refund_deadline = PayoutPhaseEnum::get_refund_deadline(&initial_payout_phase);
+    let payout_time = initial_payout_phase.payout_time;
+    let refund_deadline = initial_payout_phase.refund_deadline;
 
     if payout_time <= campaign_end_time {
         msg!("Initial PayoutPhase payout_time must be after the campaign_end_time payout_time.",);
@@ -174,17 +345,32 @@ pub fn assert_initial_payout_phase_is_valid(
 
 pub fn assert_payout_phase_is_valid(
     index: usize,
-    payout_phase: PayoutPhaseEnum,
-    previous_payout_phase: Option<PayoutPhaseEnum>,
+    payout_phase: &PayoutPhase,
+    previous_payout_phase: Option<&PayoutPhase>,
     campaign_end_time: i64,
 ) -> Result<()> {
+    assert_voting_config_is_valid(index, payout_phase, campaign_end_time)?;
+    assert_payout_stream_is_valid(index, payout_phase)?;
+
     if index == 0 {
-        return assert_initial_payout_phase_is_valid(payout_phase.clone(), campaign_end_time);
+        return assert_initial_payout_phase_is_valid(payout_phase, campaign_end_time);
     }
 
-    let previous_payout_time = PayoutPhaseEnum::get_payout_time(&previous_payout_phase.unwrap());
-    let payout_time = PayoutPhaseEnum::get_payout_time(&payout_phase);
-    let refund_deadline = PayoutPhaseEnum::get_refund_deadline(&payout_phase);
+    let previous_payout_time = previous_payout_phase.unwrap().payout_time;
+    let payout_time = payout_phase.payout_time;
+    let refund_deadline = payout_phase.refund_deadline;
+
+    // A stream must end before the next payout phase begins, since payout
+    // phases are paid out in order.
+    if let Some(previous_stream) = &previous_payout_phase.unwrap().stream {
+        if payout_time < previous_stream.stream_end_time {
+            msg!(
+                "Index {} PayoutPhase payout_time must not be before the previous payout phase stream_end_time.",
+                index
+            );
+            return Err(CampaignTreasuryManagerError::InvalidPayoutPhases.into());
+        }
+    }
 
     if payout_time <= previous_payout_time {
         msg!(
@@ -248,10 +434,14 @@ pub fn assert_min_time_buffer(
     error_code: CampaignTreasuryManagerError,
     error_description: String,
 ) -> Result<()> {
-    let min_future_time = time_start.checked_add(min_time_buffer).unwrap();
+    // If the addition overflows then no time_end can satisfy the buffer.
+    let is_below_min_buffer = match time_start.checked_add(min_time_buffer) {
+        Some(min_future_time) => time_end < min_future_time,
+        None => true,
+    };
 
-    if time_end < min_future_time {
-        let diff = min_future_time.checked_sub(time_end).unwrap();
+    if is_below_min_buffer {
+        let diff = time_end.saturating_sub(time_start);
         msg!("{}", error_description);
         msg!(
             "Expected difference between time_start of {} and time_end of {} to be at least {} but got {}.",
@@ -273,10 +463,14 @@ pub fn assert_max_time_buffer(
     error_code: CampaignTreasuryManagerError,
     error_description: String,
 ) -> Result<()> {
-    let max_future_time = time_start.checked_add(max_time_buffer).unwrap();
+    // If the addition overflows then every time_end is within the buffer.
+    let max_future_time = match time_start.checked_add(max_time_buffer) {
+        Some(max_future_time) => max_future_time,
+        None => return Ok(()),
+    };
 
     if time_end > max_future_time {
-        let diff = time_end.checked_sub(max_future_time).unwrap();
+        let diff = time_end.saturating_sub(time_start);
         msg!("{}", error_description);
         msg!(
             "Expected difference between time_start of {} and time_end of {} to be no more than {} but got {}.",
@@ -291,21 +485,38 @@ pub fn assert_max_time_buffer(
     Ok(())
 }
 
-pub fn create_treasury_escrow_and_assert_is_valid<'a>(
-    campaign_escrow: AccountInfo<'a>,
-    treasury_escrow: AccountInfo<'a>,
-    treasury_mint: AccountInfo<'a>,
-    payout_wallet: AccountInfo<'a>,
-    payout_wallet_owner: AccountInfo<'a>,
-    payer: AccountInfo<'a>,
-    ata_program: &Program<'a, AssociatedToken>,
-    token_program: &Program<'a, Token>,
-    system_program: &Program<'a, System>,
-    rent: &Sysvar<'a, Rent>,
+pub struct CreateTreasuryEscrowAccounts<'a, 'b> {
+    pub campaign_escrow: AccountInfo<'a>,
+    pub treasury_escrow: AccountInfo<'a>,
+    pub treasury_mint: AccountInfo<'a>,
+    pub payout_wallet: AccountInfo<'a>,
+    pub payout_wallet_owner: AccountInfo<'a>,
+    pub payer: AccountInfo<'a>,
+    pub ata_program: &'b Program<'a, AssociatedToken>,
+    pub token_program: &'b Program<'a, Token>,
+    pub system_program: &'b Program<'a, System>,
+    pub rent: &'b Sysvar<'a, Rent>,
+}
+
+pub fn create_treasury_escrow_and_assert_is_valid(
+    accounts: CreateTreasuryEscrowAccounts,
     fee_payer_seeds: &[&[u8]],
     treasury_bump: u8,
     campaign_uuid: String,
 ) -> Result<()> {
+    let CreateTreasuryEscrowAccounts {
+        campaign_escrow,
+        treasury_escrow,
+        treasury_mint,
+        payout_wallet,
+        payout_wallet_owner,
+        payer,
+        ata_program,
+        token_program,
+        system_program,
+        rent,
+    } = accounts;
+
     if is_native_mint(&treasury_mint.key()) {
         assert_keys_equal(
             &payout_wallet.key(),
@@ -318,27 +529,31 @@ pub fn create_treasury_escrow_and_assert_is_valid<'a>(
     let treasury_seeds = TreasuryEscrow::get_seeds(&campaign_uuid, &treasury_bump);
 
     create_program_token_account_if_not_present(
-        treasury_escrow,
-        system_program,
-        &payer,
-        token_program,
-        &treasury_mint.to_account_info(),
-        &campaign_escrow.to_account_info(),
-        rent,
+        CreateProgramTokenAccountAccounts {
+            new_account: treasury_escrow,
+            system_program,
+            fee_payer: &payer,
+            token_program,
+            token_mint: &treasury_mint,
+            owner: &campaign_escrow,
+            rent,
+        },
         &treasury_seeds,
         fee_payer_seeds,
     )?;
 
     if payout_wallet.data_is_empty() {
         make_ata(
-            payout_wallet.to_account_info(),
-            payout_wallet_owner.to_account_info(),
-            treasury_mint.to_account_info(),
-            payer.to_account_info(),
-            ata_program.to_account_info(),
-            token_program.to_account_info(),
-            system_program.to_account_info(),
-            rent.to_account_info(),
+            MakeAtaAccounts {
+                ata: payout_wallet.to_account_info(),
+                wallet: payout_wallet_owner.to_account_info(),
+                mint: treasury_mint.to_account_info(),
+                fee_payer: payer.to_account_info(),
+                ata_program: ata_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+                system_program: system_program.to_account_info(),
+                rent: rent.to_account_info(),
+            },
             fee_payer_seeds,
         )?;
     }
@@ -355,18 +570,32 @@ pub fn create_treasury_escrow_and_assert_is_valid<'a>(
     Ok(())
 }
 
-pub fn create_deposit_escrow_account<'a>(
-    campaign_escrow: AccountInfo<'a>,
-    deposit_escrow_account: AccountInfo<'a>,
-    deposit_escrow_mint: AccountInfo<'a>,
-    payer: AccountInfo<'a>,
-    token_program: &Program<'a, Token>,
-    system_program: &Program<'a, System>,
-    rent: &Sysvar<'a, Rent>,
+pub struct CreateDepositEscrowAccounts<'a, 'b> {
+    pub campaign_escrow: AccountInfo<'a>,
+    pub deposit_escrow_account: AccountInfo<'a>,
+    pub deposit_escrow_mint: AccountInfo<'a>,
+    pub payer: AccountInfo<'a>,
+    pub token_program: &'b Program<'a, Token>,
+    pub system_program: &'b Program<'a, System>,
+    pub rent: &'b Sysvar<'a, Rent>,
+}
+
+pub fn create_deposit_escrow_account(
+    accounts: CreateDepositEscrowAccounts,
     fee_payer_seeds: &[&[u8]],
     deposit_escrow_bump: u8,
     campaign_uuid: String,
 ) -> Result<()> {
+    let CreateDepositEscrowAccounts {
+        campaign_escrow,
+        deposit_escrow_account,
+        deposit_escrow_mint,
+        payer,
+        token_program,
+        system_program,
+        rent,
+    } = accounts;
+
     if is_native_mint(&deposit_escrow_mint.key()) {
         return Ok(());
     }
@@ -379,13 +608,15 @@ pub fn create_deposit_escrow_account<'a>(
     );
 
     create_program_token_account_if_not_present(
-        deposit_escrow_account,
-        system_program,
-        &payer,
-        token_program,
-        &deposit_escrow_mint.to_account_info(),
-        &campaign_escrow.to_account_info(),
-        rent,
+        CreateProgramTokenAccountAccounts {
+            new_account: deposit_escrow_account,
+            system_program,
+            fee_payer: &payer,
+            token_program,
+            token_mint: &deposit_escrow_mint,
+            owner: &campaign_escrow,
+            rent,
+        },
         &deposit_escrow_seeds,
         fee_payer_seeds,
     )?;
@@ -393,17 +624,101 @@ pub fn create_deposit_escrow_account<'a>(
     Ok(())
 }
 
-pub fn create_program_token_account_if_not_present<'a>(
-    new_account: AccountInfo<'a>,
-    system_program: &Program<'a, System>,
-    fee_payer: &AccountInfo<'a>,
-    token_program: &Program<'a, Token>,
-    token_mint: &AccountInfo<'a>,
-    owner: &AccountInfo<'a>,
-    rent: &Sysvar<'a, Rent>,
+// Native matching pool escrows are system accounts, so the sponsor funds
+// their rent exemption up front. This keeps the escrow rent exempt when all
+// of the pool is matched, and the rent is returned when it is closed.
+pub struct CreateMatchingPoolEscrowAccounts<'a, 'b> {
+    pub campaign_escrow: AccountInfo<'a>,
+    pub matching_pool_escrow: AccountInfo<'a>,
+    pub treasury_mint: AccountInfo<'a>,
+    pub sponsor: AccountInfo<'a>,
+    pub token_program: &'b Program<'a, Token>,
+    pub system_program: &'b Program<'a, System>,
+    pub rent: &'b Sysvar<'a, Rent>,
+}
+
+pub fn create_matching_pool_escrow_account(
+    accounts: CreateMatchingPoolEscrowAccounts,
+    matching_pool_escrow_bump: u8,
+    campaign_uuid: String,
+) -> Result<()> {
+    let CreateMatchingPoolEscrowAccounts {
+        campaign_escrow,
+        matching_pool_escrow,
+        treasury_mint,
+        sponsor,
+        token_program,
+        system_program,
+        rent,
+    } = accounts;
+
+    if is_native_mint(&treasury_mint.key()) {
+        let required_lamports = rent
+            .minimum_balance(0)
+            .saturating_sub(matching_pool_escrow.lamports());
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    &sponsor.key(),
+                    &matching_pool_escrow.key(),
+                    required_lamports,
+                ),
+                &[
+                    sponsor,
+                    matching_pool_escrow,
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+
+        return Ok(());
+    }
+
+    let matching_pool_escrow_seeds =
+        MatchingPoolEscrow::get_seeds(&campaign_uuid, &matching_pool_escrow_bump);
+
+    create_program_token_account_if_not_present(
+        CreateProgramTokenAccountAccounts {
+            new_account: matching_pool_escrow,
+            system_program,
+            fee_payer: &sponsor,
+            token_program,
+            token_mint: &treasury_mint,
+            owner: &campaign_escrow,
+            rent,
+        },
+        &matching_pool_escrow_seeds,
+        &[],
+    )?;
+
+    Ok(())
+}
+
+pub struct CreateProgramTokenAccountAccounts<'a, 'b> {
+    pub new_account: AccountInfo<'a>,
+    pub system_program: &'b Program<'a, System>,
+    pub fee_payer: &'b AccountInfo<'a>,
+    pub token_program: &'b Program<'a, Token>,
+    pub token_mint: &'b AccountInfo<'a>,
+    pub owner: &'b AccountInfo<'a>,
+    pub rent: &'b Sysvar<'a, Rent>,
+}
+
+pub fn create_program_token_account_if_not_present(
+    accounts: CreateProgramTokenAccountAccounts,
     signer_seeds: &[&[u8]],
     fee_seeds: &[&[u8]],
 ) -> Result<()> {
+    let CreateProgramTokenAccountAccounts {
+        new_account,
+        system_program,
+        fee_payer,
+        token_program,
+        token_mint,
+        owner,
+        rent,
+    } = accounts;
+
     if is_native_mint(&token_mint.key()) {
         return Ok(());
     }
@@ -411,10 +726,12 @@ pub fn create_program_token_account_if_not_present<'a>(
     if new_account.data_is_empty() {
         create_or_allocate_account_raw(
             *token_program.key,
-            &new_account.to_account_info(),
-            &rent.to_account_info(),
-            system_program,
-            fee_payer,
+            CreateOrAllocateAccountRawAccounts {
+                new_account_info: &new_account.to_account_info(),
+                rent_sysvar_info: &rent.to_account_info(),
+                system_program_info: system_program,
+                payer_info: fee_payer,
+            },
             spl_token::state::Account::LEN,
             fee_seeds,
             signer_seeds,
@@ -443,17 +760,27 @@ pub fn create_program_token_account_if_not_present<'a>(
 
 /// Create account almost from scratch, lifted from
 /// https://github.com/solana-labs/solana-program-library/blob/7d4873c61721aca25464d42cc5ef651a7923ca79/associated-token-account/program/src/processor.rs#L51-L98
+pub struct CreateOrAllocateAccountRawAccounts<'a, 'b> {
+    pub new_account_info: &'b AccountInfo<'a>,
+    pub rent_sysvar_info: &'b AccountInfo<'a>,
+    pub system_program_info: &'b AccountInfo<'a>,
+    pub payer_info: &'b AccountInfo<'a>,
+}
+
 #[inline(always)]
-pub fn create_or_allocate_account_raw<'a>(
+pub fn create_or_allocate_account_raw(
     program_id: Pubkey,
-    new_account_info: &AccountInfo<'a>,
-    rent_sysvar_info: &AccountInfo<'a>,
-    system_program_info: &AccountInfo<'a>,
-    payer_info: &AccountInfo<'a>,
+    accounts: CreateOrAllocateAccountRawAccounts,
     size: usize,
     signer_seeds: &[&[u8]],
     new_acct_seeds: &[&[u8]],
 ) -> Result<()> {
+    let CreateOrAllocateAccountRawAccounts {
+        new_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        payer_info,
+    } = accounts;
     let rent = &Rent::from_account_info(rent_sysvar_info)?;
     let required_lamports = rent
         .minimum_balance(size)
@@ -500,6 +827,42 @@ pub fn create_or_allocate_account_raw<'a>(
     Ok(())
 }
 
+// Grows an account to new_size, funding any additional rent from the payer.
+// Accounts are never shrunk.
+pub fn realloc_account_if_needed<'a>(
+    account: AccountInfo<'a>,
+    payer: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    new_size: usize,
+) -> Result<()> {
+    let current_size = account.data_len();
+    if new_size <= current_size {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let required_lamports = rent
+        .minimum_balance(new_size)
+        .saturating_sub(account.lamports());
+
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer, account.clone(), system_program],
+        )?;
+    }
+
+    msg!(
+        "Reallocating account {} from {} to {} bytes.",
+        account.key,
+        current_size,
+        new_size
+    );
+    account.realloc(new_size, false)?;
+
+    Ok(())
+}
+
 pub fn transfer_funds<'a>(
     source_account: AccountInfo<'a>,
     destination_account: AccountInfo<'a>,
@@ -542,17 +905,28 @@ pub fn transfer_funds<'a>(
     Ok(())
 }
 
-pub fn make_ata<'a>(
-    ata: AccountInfo<'a>,
-    wallet: AccountInfo<'a>,
-    mint: AccountInfo<'a>,
-    fee_payer: AccountInfo<'a>,
-    ata_program: AccountInfo<'a>,
-    token_program: AccountInfo<'a>,
-    system_program: AccountInfo<'a>,
-    rent: AccountInfo<'a>,
-    fee_payer_seeds: &[&[u8]],
-) -> Result<()> {
+pub struct MakeAtaAccounts<'a> {
+    pub ata: AccountInfo<'a>,
+    pub wallet: AccountInfo<'a>,
+    pub mint: AccountInfo<'a>,
+    pub fee_payer: AccountInfo<'a>,
+    pub ata_program: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub rent: AccountInfo<'a>,
+}
+
+pub fn make_ata(accounts: MakeAtaAccounts, fee_payer_seeds: &[&[u8]]) -> Result<()> {
+    let MakeAtaAccounts {
+        ata,
+        wallet,
+        mint,
+        fee_payer,
+        ata_program,
+        token_program,
+        system_program,
+        rent,
+    } = accounts;
     let as_arr = [fee_payer_seeds];
     let seeds: &[&[&[u8]]] = if fee_payer_seeds.is_empty() {
         &[]
@@ -618,3 +992,173 @@ pub fn close_token_account<'a>(
 
     Ok(())
 }
+
+// Transfers funds out of the treasury_escrow. Native treasury escrows are
+// system accounts which sign with their own seeds, token treasury escrows are
+// owned by the CampaignEscrow.
+pub fn transfer_from_treasury_escrow<'a>(
+    campaign_escrow: &Account<'a, CampaignEscrow>,
+    treasury_escrow: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    token_program: &Program<'a, Token>,
+    system_program: &Program<'a, System>,
+    amount: u64,
+) -> Result<()> {
+    if is_native_mint(&campaign_escrow.treasury.treasury_mint) {
+        let treasury_escrow_seeds = TreasuryEscrow::get_seeds(
+            &campaign_escrow.campaign_uuid,
+            &campaign_escrow.treasury.bump,
+        );
+
+        invoke_signed(
+            &system_instruction::transfer(&treasury_escrow.key(), &destination.key(), amount),
+            &[
+                treasury_escrow,
+                destination,
+                system_program.to_account_info(),
+            ],
+            &[&treasury_escrow_seeds],
+        )?;
+    } else {
+        let campaign_escrow_seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            &[campaign_escrow.bump],
+        ];
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                &treasury_escrow.key(),
+                &destination.key(),
+                &campaign_escrow.key(),
+                &[],
+                amount,
+            )?,
+            &[
+                treasury_escrow,
+                destination,
+                token_program.to_account_info(),
+                campaign_escrow.to_account_info(),
+            ],
+            &[&campaign_escrow_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Transfers funds out of a matching_pool_escrow, which signs like the
+// treasury_escrow in transfer_from_treasury_escrow.
+pub fn transfer_from_matching_pool_escrow<'a>(
+    campaign_escrow: &Account<'a, CampaignEscrow>,
+    matching_pool_escrow: AccountInfo<'a>,
+    matching_pool_escrow_bump: u8,
+    destination: AccountInfo<'a>,
+    token_program: &Program<'a, Token>,
+    system_program: &Program<'a, System>,
+    amount: u64,
+) -> Result<()> {
+    if is_native_mint(&campaign_escrow.treasury.treasury_mint) {
+        let matching_pool_escrow_seeds = MatchingPoolEscrow::get_seeds(
+            &campaign_escrow.campaign_uuid,
+            &matching_pool_escrow_bump,
+        );
+
+        invoke_signed(
+            &system_instruction::transfer(&matching_pool_escrow.key(), &destination.key(), amount),
+            &[
+                matching_pool_escrow,
+                destination,
+                system_program.to_account_info(),
+            ],
+            &[&matching_pool_escrow_seeds],
+        )?;
+    } else {
+        let campaign_escrow_seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            &[campaign_escrow.bump],
+        ];
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                &matching_pool_escrow.key(),
+                &destination.key(),
+                &campaign_escrow.key(),
+                &[],
+                amount,
+            )?,
+            &[
+                matching_pool_escrow,
+                destination,
+                token_program.to_account_info(),
+                campaign_escrow.to_account_info(),
+            ],
+            &[&campaign_escrow_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Transfers funds out of a DepositEscrow, which signs like the treasury_escrow
+// in transfer_from_treasury_escrow, in the deposit_escrow_mint currency.
+pub fn transfer_from_deposit_escrow<'a>(
+    campaign_escrow: &Account<'a, CampaignEscrow>,
+    deposit_escrow: AccountInfo<'a>,
+    deposit_escrow_mint: &Pubkey,
+    destination: AccountInfo<'a>,
+    token_program: &Program<'a, Token>,
+    system_program: &Program<'a, System>,
+    amount: u64,
+) -> Result<()> {
+    if is_native_mint(deposit_escrow_mint) {
+        let deposit_escrow_bump = campaign_escrow
+            .get_deposit_escrow_info(deposit_escrow_mint)
+            .unwrap()
+            .deposit_escrow_bump;
+        let deposit_escrow_seeds = DepositEscrow::get_seeds(
+            &campaign_escrow.campaign_uuid,
+            deposit_escrow_mint,
+            &deposit_escrow_bump,
+        );
+
+        invoke_signed(
+            &system_instruction::transfer(&deposit_escrow.key(), &destination.key(), amount),
+            &[
+                deposit_escrow,
+                destination,
+                system_program.to_account_info(),
+            ],
+            &[&deposit_escrow_seeds],
+        )?;
+    } else {
+        let campaign_escrow_seeds = [
+            CampaignEscrow::PREFIX.as_bytes(),
+            campaign_escrow.campaign_uuid.as_bytes(),
+            &[campaign_escrow.bump],
+        ];
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                &deposit_escrow.key(),
+                &destination.key(),
+                &campaign_escrow.key(),
+                &[],
+                amount,
+            )?,
+            &[
+                deposit_escrow,
+                destination,
+                token_program.to_account_info(),
+                campaign_escrow.to_account_info(),
+            ],
+            &[&campaign_escrow_seeds],
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1548 @@
+// Negative tests, with at least one test for every CampaignTreasuryManagerError
+// variant which the program can return.
+
+mod utils;
+
+use campaign_treasury_manager::{
+    constants::MAX_PAYOUT_PHASES, CampaignEscrow, CampaignTreasuryManagerError, CrankReward,
+    CreateCampaignEscrowInput, PriceOracle, RewardTier, StretchGoal, UpdateCampaignEscrowInput,
+};
+use campaign_treasury_manager_client::{
+    close_deposit_escrow_ix, close_deposit_records_ix, close_escrow_ix, create_escrow_ix,
+    find_deposit_escrow_pda, find_deposit_record_pda, find_metadata_pda, get_payment_account,
+    migrate_campaign_escrow_ix, pay_out_funds_ix, update_escrow_ix, vote_to_veto_payout_phase_ix,
+    CloseDepositEscrowAccounts, CloseDepositRecordsAccounts, CloseEscrowAccounts,
+    MigrateCampaignEscrowAccounts, PayOutFundsAccounts, UpdateEscrowAccounts,
+    VoteToVetoPayoutPhaseAccounts,
+};
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::{
+    account::Account, instruction::AccountMeta, program_pack::Pack, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, system_instruction,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::native_mint;
+use utils::*;
+
+// Each variant maps to the test which covers it. The match is exhaustive so
+// that adding a variant fails to compile until it has test coverage.
+#[allow(dead_code)]
+fn get_covering_test(error: CampaignTreasuryManagerError) -> &'static str {
+    use CampaignTreasuryManagerError::*;
+    match error {
+        PublicKeyMismatch => "test_public_key_mismatch",
+        IncorrectOwner => "test_incorrect_owner",
+        UninitializedAccount => "test_uninitialized_account",
+        MissingSigner => "test_missing_signer",
+        CreatorMismatch => "test_creator_mismatch",
+        AuthorityMismatch => "test_authority_mismatch",
+        InvalidPayoutPhases => "test_invalid_payout_phases",
+        InvalidUpdateEscrow => "test_invalid_update_escrow",
+        InvalidCloseEscrow => "test_invalid_close_escrow",
+        InvalidTreasuryEscrow => "test_invalid_treasury_escrow",
+        InvalidCampaignEndTime => "test_invalid_campaign_end_time",
+        InvalidDepositEscrowAccount => "test_invalid_deposit_escrow_account",
+        InvalidCpiInvocation => "test_invalid_cpi_invocation",
+        InvalidCreateDepositEscrowIx => "test_invalid_create_deposit_escrow_ix",
+        InvalidPayoutRequest => "test_invalid_payout_request",
+        DepositEscrowAccountNotEmpty => "test_deposit_escrow_account_not_empty",
+        TooManyPayoutPhases => "test_too_many_payout_phases",
+        InvalidTreasuryAccounting => "test_invalid_treasury_accounting",
+        InvalidSurplusReceiver => "test_invalid_surplus_receiver",
+        InvalidDepositRecord => "test_invalid_deposit_record",
+        InvalidCrankReward => "test_invalid_crank_reward",
+        InvalidNftCollection => "test_invalid_nft_collection",
+        InvalidNftHolder => "test_invalid_nft_holder",
+        InvalidVote => "test_invalid_vote",
+        TooManyDepositEscrows => "test_too_many_deposit_escrows",
+        DepositEscrowDisabled => "test_deposit_escrow_disabled",
+        InvalidPriceOracle => "test_invalid_price_oracle",
+        InvalidDonation => "test_invalid_donation",
+        InvalidFundraisingWindow => "test_invalid_fundraising_window",
+        OutsideFundraisingWindow => "test_outside_fundraising_window",
+        InvalidRewardTiers => "test_invalid_reward_tiers",
+        InvalidRewardTier => "test_invalid_reward_tier",
+        RewardTierSoldOut => "test_reward_tier_sold_out",
+        InvalidHardCap => "test_invalid_hard_cap",
+        HardCapExceeded => "test_hard_cap_exceeded",
+        InvalidMatchingPool => "test_invalid_matching_pool",
+        InvalidCloseMatchingPool => "test_invalid_close_matching_pool",
+        InvalidStreamedPayoutClaim => "test_invalid_streamed_payout_claim",
+        InvalidRefundRequest => "test_invalid_refund_request",
+        InvalidProcessDeposit => "test_invalid_process_deposit",
+    }
+}
+
+async fn create_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    input: CreateCampaignEscrowInput,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[create_escrow_ix(
+            setup.create_escrow_accounts(&payer.pubkey()),
+            &setup.campaign_uuid,
+            input,
+        )],
+        &[],
+    )
+    .await
+}
+
+async fn update_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    input: UpdateCampaignEscrowInput,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[update_escrow_ix(
+            UpdateEscrowAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+                creator: setup.creator.pubkey(),
+                payout_wallet_owner: setup.creator.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            input,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+async fn pay_out_funds(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[pay_out_funds_ix(
+            PayOutFundsAccounts {
+                payer: payer.pubkey(),
+                payout_wallet: setup.payout_wallet,
+                crank_reward_receiver: None,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[],
+    )
+    .await
+}
+
+async fn close_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    creator: Pubkey,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[close_escrow_ix(
+            CloseEscrowAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+                creator,
+                remainder_receiver: setup.payout_wallet,
+                receiver: payer.pubkey(),
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+// Deposits with a depositor which has SOL but no payment token account.
+async fn create_deposit_without_payment_account(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: &Pubkey,
+) -> (Keypair, Result<(), BanksClientError>) {
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    let result = ctx
+        .process_instructions(
+            &get_create_deposit_ixs(
+                setup,
+                &depositor.pubkey(),
+                &nft_mint,
+                deposit_escrow_mint,
+                1_000,
+            ),
+            &[&depositor],
+        )
+        .await;
+    (depositor, result)
+}
+
+#[tokio::test]
+async fn test_public_key_mismatch() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+
+    // A treasury mint deposit must go to the treasury escrow.
+    let mut ixs = get_create_deposit_ixs(
+        &setup,
+        &depositor.pubkey(),
+        &nft_mint,
+        &native_mint::id(),
+        1_000,
+    );
+    for account in ixs[1].accounts.iter_mut() {
+        if account.pubkey == setup.treasury_escrow {
+            account.pubkey = Pubkey::new_unique();
+        }
+    }
+
+    let result = ctx.process_instructions(&ixs, &[&depositor]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::PublicKeyMismatch);
+}
+
+#[tokio::test]
+async fn test_incorrect_owner() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+
+    // The depositor's ATA does not exist, so it is owned by the system program.
+    let (_, result) =
+        create_deposit_without_payment_account(&mut ctx, &setup, &treasury_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::IncorrectOwner);
+}
+
+#[tokio::test]
+async fn test_uninitialized_account() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+
+    // A token program account at the ATA address which was never initialized.
+    let payment_account = get_payment_account(&depositor.pubkey(), &treasury_mint);
+    ctx.context.set_account(
+        &payment_account,
+        &Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![0; spl_token::state::Account::LEN],
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into(),
+    );
+
+    let result = ctx
+        .process_instructions(
+            &get_create_deposit_ixs(
+                &setup,
+                &depositor.pubkey(),
+                &nft_mint,
+                &treasury_mint,
+                1_000,
+            ),
+            &[&depositor],
+        )
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::UninitializedAccount);
+}
+
+#[tokio::test]
+async fn test_missing_signer() {
+    let mut ctx = TestContext::new().await;
+    let mut setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+    // Neither the authority nor the creator signs, only the payer.
+    setup.authority = Keypair::new();
+
+    let input = setup.create_escrow_input();
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::MissingSigner);
+}
+
+#[tokio::test]
+async fn test_creator_mismatch() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    let result = close_escrow(&mut ctx, &setup, Pubkey::new_unique()).await;
+    assert_program_error(result, CampaignTreasuryManagerError::CreatorMismatch);
+}
+
+#[tokio::test]
+async fn test_authority_mismatch() {
+    let mut ctx = TestContext::new().await;
+    let campaign_uuid = get_campaign_uuid();
+    let legacy_campaign_escrow =
+        get_legacy_campaign_escrow(&campaign_uuid, &Pubkey::new_unique(), ctx.now + days(20));
+    set_legacy_campaign_escrow_account(&mut ctx, &campaign_uuid, &legacy_campaign_escrow);
+
+    let payer = ctx.payer();
+    let result = ctx
+        .process_instructions(
+            &[migrate_campaign_escrow_ix(
+                MigrateCampaignEscrowAccounts {
+                    payer: payer.pubkey(),
+                    authority: payer.pubkey(),
+                },
+                &campaign_uuid,
+            )],
+            &[],
+        )
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::AuthorityMismatch);
+}
+
+#[tokio::test]
+async fn test_invalid_payout_phases() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+
+    // The payout basis points must sum to 10_000.
+    let mut input = setup.create_escrow_input();
+    input.payout_phases[1].payout_basis_points = 4_000;
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPayoutPhases);
+
+    // The first payout must be at least the min buffer after the campaign end.
+    let mut input = setup.create_escrow_input();
+    input.payout_phases[0].payout_time = setup.campaign_end_time + days(1);
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPayoutPhases);
+
+    // The payout basis points must sum to 10_000 for every stretch goal
+    // outcome, here only if the stretch goal is reached.
+    let mut input = setup.create_escrow_input();
+    input.payout_phases[1].stretch_goal = Some(StretchGoal {
+        raise_threshold: LAMPORTS_PER_SOL,
+        is_active_when_reached: true,
+    });
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPayoutPhases);
+
+    // Moving only the campaign_end_time must keep the existing payout phases
+    // valid, here leaving less than the min buffer before the first payout.
+    create_escrow(&mut ctx, &setup, setup.create_escrow_input())
+        .await
+        .unwrap();
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: Some(setup.campaign_end_time + days(5)),
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPayoutPhases);
+}
+
+#[tokio::test]
+async fn test_invalid_update_escrow() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let input = UpdateCampaignEscrowInput {
+        authority: None,
+        creator: None,
+        campaign_end_time: None,
+        payout_phases: None,
+        surplus_policy: None,
+        crank_reward: None,
+        collection_mint: None,
+        deposit_escrow_infos_capacity: None,
+        fundraising_start_time: None,
+        deposit_grace_period: None,
+        reward_tiers: None,
+        hard_cap: None,
+        auto_end_at_hard_cap: None,
+    };
+
+    // The collection_mint cannot change once deposits were verified against it.
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            collection_mint: Some(Pubkey::new_unique()),
+            ..input.clone()
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidUpdateEscrow);
+
+    // The reward tiers cannot change once deposits have claimed them.
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            reward_tiers: Some(get_default_reward_tiers()),
+            ..input.clone()
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidUpdateEscrow);
+
+    // The fundraising_start_time cannot be cleared once deposits were made.
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            fundraising_start_time: Some(None),
+            ..input.clone()
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidUpdateEscrow);
+
+    // Neither can the hard_cap.
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            hard_cap: Some(None),
+            ..input.clone()
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidUpdateEscrow);
+
+    // The capacity cannot be lower than the number of DepositEscrow currencies.
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            deposit_escrow_infos_capacity: Some(0),
+            ..input.clone()
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidUpdateEscrow);
+
+    ctx.warp_to_timestamp(setup.campaign_end_time + 1).await;
+    let result = update_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidUpdateEscrow);
+}
+
+#[tokio::test]
+async fn test_invalid_close_escrow() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    // Payouts are not complete.
+    let result = close_escrow(&mut ctx, &setup, setup.creator.pubkey()).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCloseEscrow);
+
+    // Payouts are complete, but the last refund deadline buffer has not passed.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await.unwrap();
+    }
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline)
+        .await;
+    let result = close_escrow(&mut ctx, &setup, setup.creator.pubkey()).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCloseEscrow);
+
+    // A DepositEscrow has not been closed.
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    ctx.warp_forward(days(3)).await;
+    let result = close_escrow(&mut ctx, &setup, setup.creator.pubkey()).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCloseEscrow);
+}
+
+#[tokio::test]
+async fn test_invalid_treasury_escrow() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+    let payer = ctx.payer();
+
+    // For a native treasury the payout wallet must be the payout wallet owner.
+    let mut ix = create_escrow_ix(
+        setup.create_escrow_accounts(&payer.pubkey()),
+        &setup.campaign_uuid,
+        setup.create_escrow_input(),
+    );
+    let payout_wallet = ix
+        .accounts
+        .iter_mut()
+        .find(|account| account.pubkey == setup.payout_wallet && account.is_writable)
+        .unwrap();
+    payout_wallet.pubkey = Pubkey::new_unique();
+
+    let result = ctx.process_instructions(&[ix], &[]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidTreasuryEscrow);
+}
+
+#[tokio::test]
+async fn test_invalid_campaign_end_time() {
+    let mut ctx = TestContext::new().await;
+
+    for campaign_end_time in [ctx.now + days(1), ctx.now + days(365)] {
+        let mut setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+        setup.campaign_end_time = campaign_end_time;
+
+        let input = setup.create_escrow_input();
+        let result = create_escrow(&mut ctx, &setup, input).await;
+        assert_program_error(result, CampaignTreasuryManagerError::InvalidCampaignEndTime);
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_deposit_escrow_account() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    // No DepositEscrow exists for this mint.
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    let (_, result) =
+        create_deposit_without_payment_account(&mut ctx, &setup, &deposit_escrow_mint).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidDepositEscrowAccount,
+    );
+
+    // The deposit_escrow is not the DepositEscrow PDA for the mint.
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    let mut ixs = get_create_deposit_ixs(
+        &setup,
+        &depositor.pubkey(),
+        &nft_mint,
+        &deposit_escrow_mint,
+        1_000,
+    );
+    let (deposit_escrow, _) = find_deposit_escrow_pda(&setup.campaign_uuid, &deposit_escrow_mint);
+    for account in ixs[1].accounts.iter_mut() {
+        if account.pubkey == deposit_escrow {
+            account.pubkey = setup.treasury_escrow;
+        }
+    }
+    let result = ctx.process_instructions(&ixs, &[&depositor]).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidDepositEscrowAccount,
+    );
+
+    // Only existing DepositEscrow currencies can be disabled.
+    let other_deposit_escrow_mint = ctx.create_mint(6).await;
+    let result = disable_deposit_escrow(&mut ctx, &setup, other_deposit_escrow_mint).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidDepositEscrowAccount,
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_cpi_invocation() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+
+    // Without the preceding auction house instruction.
+    let mut ixs = get_create_deposit_ixs(
+        &setup,
+        &depositor.pubkey(),
+        &nft_mint,
+        &native_mint::id(),
+        1_000,
+    );
+    ixs.remove(0);
+
+    let result = ctx.process_instructions(&ixs, &[&depositor]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCpiInvocation);
+}
+
+#[tokio::test]
+async fn test_invalid_create_deposit_escrow_ix() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    let result = create_deposit_escrow(&mut ctx, &setup, native_mint::id()).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidCreateDepositEscrowIx,
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_payout_request() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+
+    // Before the payout time.
+    ctx.warp_to_timestamp(payout_phases[0].payout_time - 1)
+        .await;
+    let result = pay_out_funds(&mut ctx, &setup).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPayoutRequest);
+
+    // After all payouts are complete.
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await.unwrap();
+    }
+    ctx.warp_forward(days(1)).await;
+    let result = pay_out_funds(&mut ctx, &setup).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPayoutRequest);
+}
+
+#[tokio::test]
+async fn test_deposit_escrow_account_not_empty() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    create_deposit(&mut ctx, &setup, &deposit_escrow_mint, 1_000).await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await.unwrap();
+    }
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+
+    let payer = ctx.payer();
+    let result = ctx
+        .process_instructions(
+            &[close_deposit_escrow_ix(
+                CloseDepositEscrowAccounts {
+                    authority: setup.authority.pubkey(),
+                    deposit_escrow_mint,
+                    receiver: payer.pubkey(),
+                },
+                &setup.campaign_uuid,
+            )],
+            &[&setup.authority],
+        )
+        .await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::DepositEscrowAccountNotEmpty,
+    );
+}
+
+#[tokio::test]
+async fn test_too_many_payout_phases() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+
+    let mut input = setup.create_escrow_input();
+    let payout_phase = input.payout_phases[0].clone();
+    input.payout_phases = vec![payout_phase; MAX_PAYOUT_PHASES + 1];
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::TooManyPayoutPhases);
+}
+
+#[tokio::test]
+async fn test_invalid_treasury_accounting() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    // The accounting check guards against funds leaving the treasury twice,
+    // which no instruction allows, so the refunded total is set directly.
+    let mut campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    campaign_escrow.treasury.total_refunded = campaign_escrow.treasury.total_funds;
+    ctx.set_campaign_escrow(&setup.campaign_uuid, &campaign_escrow)
+        .await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    let result = pay_out_funds(&mut ctx, &setup).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidTreasuryAccounting,
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_surplus_receiver() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &setup.treasury_escrow,
+            LAMPORTS_PER_SOL,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // The campaign surplus policy routes surplus to the payout_wallet.
+    let result = reconcile_treasury(&mut ctx, &setup, payer.pubkey()).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidSurplusReceiver);
+}
+
+#[tokio::test]
+async fn test_invalid_deposit_record() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let other_setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let (other_depositor, other_nft_mint) =
+        create_deposit(&mut ctx, &other_setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await.unwrap();
+    }
+
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+
+    // A DepositRecord of another campaign.
+    let result = close_deposit_records(
+        &mut ctx,
+        &setup,
+        &[(other_depositor.pubkey(), other_nft_mint)],
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidDepositRecord);
+
+    // A depositor which does not match the DepositRecord.
+    let (deposit_record, _) = find_deposit_record_pda(&depositor.pubkey(), &nft_mint);
+    let mut instruction = close_deposit_records_ix(
+        CloseDepositRecordsAccounts {
+            payer: ctx.payer().pubkey(),
+            treasury_mint: setup.treasury_mint,
+        },
+        &setup.campaign_uuid,
+        &[],
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(deposit_record, false),
+        AccountMeta::new(other_depositor.pubkey(), false),
+        AccountMeta::new_readonly(
+            get_associated_token_address(&depositor.pubkey(), &nft_mint),
+            false,
+        ),
+        AccountMeta::new(depositor.pubkey(), false),
+    ]);
+    let result = ctx.process_instructions(&[instruction.clone()], &[]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidDepositRecord);
+
+    // An incomplete group of remaining accounts.
+    instruction.accounts.pop();
+    let result = ctx.process_instructions(&[instruction], &[]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidDepositRecord);
+}
+
+#[tokio::test]
+async fn test_invalid_crank_reward() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+
+    let mut input = setup.create_escrow_input();
+    input.crank_reward = CrankReward::BasisPoints {
+        basis_points: CrankReward::MAX_BASIS_POINTS + 1,
+    };
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCrankReward);
+
+    let mut input = setup.create_escrow_input();
+    input.crank_reward = CrankReward::Fixed { amount: 1_000 };
+    create_escrow(&mut ctx, &setup, input).await.unwrap();
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+
+    // The crank_reward_receiver is required when a crank reward is paid.
+    let result = pay_out_funds(&mut ctx, &setup).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCrankReward);
+
+    // And it must be writable.
+    let payer = ctx.payer();
+    let mut instruction = pay_out_funds_ix(
+        PayOutFundsAccounts {
+            payer: payer.pubkey(),
+            payout_wallet: setup.payout_wallet,
+            crank_reward_receiver: None,
+        },
+        &setup.campaign_uuid,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+    let result = ctx.process_instructions(&[instruction], &[]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCrankReward);
+}
+
+#[tokio::test]
+async fn test_invalid_nft_collection() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+
+    let other_collection_nft_mint = ctx.create_nft_mint(&Pubkey::new_unique(), true).await;
+    let unverified_nft_mint = ctx.create_nft_mint(&setup.collection_mint, false).await;
+    // An NFT mint without a metadata account.
+    let nft_mint_without_metadata = ctx.create_mint(0).await;
+
+    for nft_mint in [
+        other_collection_nft_mint,
+        unverified_nft_mint,
+        nft_mint_without_metadata,
+    ] {
+        let result = ctx
+            .process_instructions(
+                &get_create_deposit_ixs(
+                    &setup,
+                    &depositor.pubkey(),
+                    &nft_mint,
+                    &native_mint::id(),
+                    1_000,
+                ),
+                &[&depositor],
+            )
+            .await;
+        assert_program_error(result, CampaignTreasuryManagerError::InvalidNftCollection);
+    }
+
+    // The metadata account must be the metadata PDA of the NFT mint.
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    let mut ixs = get_create_deposit_ixs(
+        &setup,
+        &depositor.pubkey(),
+        &nft_mint,
+        &native_mint::id(),
+        1_000,
+    );
+    let (metadata, _) = find_metadata_pda(&nft_mint);
+    let (other_metadata, _) = find_metadata_pda(&other_collection_nft_mint);
+    for account in ixs[1].accounts.iter_mut() {
+        if account.pubkey == metadata {
+            account.pubkey = other_metadata;
+        }
+    }
+    let result = ctx.process_instructions(&ixs, &[&depositor]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftCollection);
+}
+
+#[tokio::test]
+async fn test_invalid_nft_holder() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let holder = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let holder_nft_token_account = ctx.mint_tokens_to_ata(&holder.pubkey(), &nft_mint, 1).await;
+
+    // The depositor's token account for the NFT is empty.
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 0)
+        .await;
+    let result =
+        process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftHolder);
+
+    // A token account which holds the NFT but is owned by someone else.
+    let mut instruction = vote_to_veto_payout_phase_ix(
+        VoteToVetoPayoutPhaseAccounts {
+            nft_holder: depositor.pubkey(),
+            depositor: depositor.pubkey(),
+            mint: nft_mint,
+        },
+        &setup.campaign_uuid,
+        1,
+    );
+    let depositor_nft_token_account = get_associated_token_address(&depositor.pubkey(), &nft_mint);
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == depositor_nft_token_account {
+            account.pubkey = holder_nft_token_account;
+        }
+    }
+    let result = ctx
+        .process_instructions(&[instruction], &[&depositor])
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftHolder);
+
+    // A token account of another NFT mint.
+    let other_nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    let other_nft_token_account = ctx
+        .mint_tokens_to_ata(&holder.pubkey(), &other_nft_mint, 1)
+        .await;
+    let mut instruction = vote_to_veto_payout_phase_ix(
+        VoteToVetoPayoutPhaseAccounts {
+            nft_holder: holder.pubkey(),
+            depositor: depositor.pubkey(),
+            mint: nft_mint,
+        },
+        &setup.campaign_uuid,
+        1,
+    );
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == holder_nft_token_account {
+            account.pubkey = other_nft_token_account;
+        }
+    }
+    let result = ctx.process_instructions(&[instruction], &[&holder]).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftHolder);
+}
+
+#[tokio::test]
+async fn test_invalid_vote() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+    let donor = ctx.create_funded_keypair(2 * LAMPORTS_PER_SOL).await;
+    donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "donation",
+        LAMPORTS_PER_SOL,
+        true,
+        false,
+    )
+    .await
+    .unwrap();
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    let voting_config = payout_phases[1].voting_config.as_ref().unwrap();
+
+    // Voting has not started yet.
+    ctx.warp_to_timestamp(voting_config.voting_start_time - 1)
+        .await;
+    let result = vote_to_veto_payout_phase(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &depositor.pubkey(),
+        &nft_mint,
+        1,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidVote);
+
+    // A payout phase without voting, and a payout phase which does not exist.
+    ctx.warp_to_timestamp(voting_config.voting_start_time).await;
+    for payout_phase_index in [0, 2] {
+        let result = vote_to_veto_payout_phase(
+            &mut ctx,
+            &setup,
+            &depositor,
+            &depositor.pubkey(),
+            &nft_mint,
+            payout_phase_index,
+        )
+        .await;
+        assert_program_error(result, CampaignTreasuryManagerError::InvalidVote);
+    }
+
+    // A donation without vote weight.
+    ctx.warp_to_timestamp(voting_config.voting_start_time).await;
+    let result =
+        vote_to_veto_payout_phase_with_donation(&mut ctx, &setup, &donor, "donation", 1).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidVote);
+
+    // Voting has ended.
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let result = vote_to_veto_payout_phase(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &depositor.pubkey(),
+        &nft_mint,
+        1,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidVote);
+}
+
+#[tokio::test]
+async fn test_too_many_deposit_escrows() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    for _ in 0..CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY {
+        let deposit_escrow_mint = ctx.create_mint(6).await;
+        create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+            .await
+            .unwrap();
+    }
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    let result = create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::TooManyDepositEscrows);
+
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: Some(
+                CampaignEscrow::DEPOSIT_ESCROW_INFOS_CAPACITY_LIMIT + 1,
+            ),
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::TooManyDepositEscrows);
+}
+
+#[tokio::test]
+async fn test_deposit_escrow_disabled() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    disable_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+
+    let (_, result) =
+        create_deposit_without_payment_account(&mut ctx, &setup, &deposit_escrow_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::DepositEscrowDisabled);
+}
+
+#[tokio::test]
+async fn test_invalid_price_oracle() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+
+    // The price oracle must be owned by the Pyth program.
+    let not_price_oracle = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let result = set_deposit_escrow_price_oracle(
+        &mut ctx,
+        &setup,
+        deposit_escrow_mint,
+        not_price_oracle.pubkey(),
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPriceOracle);
+
+    let price_oracle = ctx.create_price_oracle(50, -2).await;
+    set_deposit_escrow_price_oracle(&mut ctx, &setup, deposit_escrow_mint, price_oracle)
+        .await
+        .unwrap();
+
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &deposit_escrow_mint, 1_000_000)
+        .await;
+
+    // The price oracle must be passed when depositing.
+    for price_oracle in [None, Some(not_price_oracle.pubkey())] {
+        let result = ctx
+            .process_instructions(
+                &get_create_deposit_ixs_with_price_oracle(
+                    &setup,
+                    &depositor.pubkey(),
+                    &nft_mint,
+                    &deposit_escrow_mint,
+                    1_000_000,
+                    price_oracle,
+                ),
+                &[&depositor],
+            )
+            .await;
+        assert_program_error(result, CampaignTreasuryManagerError::InvalidPriceOracle);
+    }
+
+    // The price must be recent.
+    ctx.warp_forward(PriceOracle::MAX_PRICE_AGE_IN_SECONDS + 1)
+        .await;
+    let result = ctx
+        .process_instructions(
+            &get_create_deposit_ixs_with_price_oracle(
+                &setup,
+                &depositor.pubkey(),
+                &nft_mint,
+                &deposit_escrow_mint,
+                1_000_000,
+                Some(price_oracle),
+            ),
+            &[&depositor],
+        )
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidPriceOracle);
+}
+
+#[tokio::test]
+async fn test_invalid_donation() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let donor = ctx.create_funded_keypair(2 * LAMPORTS_PER_SOL).await;
+
+    let result = donate(&mut ctx, &setup, &donor, "empty-donation", 0, true, true).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidDonation);
+}
+
+#[tokio::test]
+async fn test_invalid_fundraising_window() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+
+    // The fundraising window must start before the campaign_end_time.
+    let mut input = setup.create_escrow_input();
+    input.fundraising_start_time = Some(setup.campaign_end_time);
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidFundraisingWindow,
+    );
+
+    let mut input = setup.create_escrow_input();
+    input.deposit_grace_period = -1;
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidFundraisingWindow,
+    );
+
+    // Deposits in the grace period must be made before the first payout.
+    let mut input = setup.create_escrow_input();
+    input.deposit_grace_period = input.payout_phases[0].payout_time - setup.campaign_end_time;
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidFundraisingWindow,
+    );
+
+    create_escrow(&mut ctx, &setup, setup.create_escrow_input())
+        .await
+        .unwrap();
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: Some(days(8)),
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidFundraisingWindow,
+    );
+}
+
+#[tokio::test]
+async fn test_outside_fundraising_window() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+    let mut input = setup.create_escrow_input();
+    input.fundraising_start_time = Some(ctx.now + days(1));
+    input.deposit_grace_period = days(1);
+    create_escrow(&mut ctx, &setup, input).await.unwrap();
+    let donor = ctx.create_funded_keypair(2 * LAMPORTS_PER_SOL).await;
+
+    let (_, result) =
+        create_deposit_without_payment_account(&mut ctx, &setup, &native_mint::id()).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::OutsideFundraisingWindow,
+    );
+    let result = donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "early-donation",
+        LAMPORTS_PER_SOL,
+        true,
+        true,
+    )
+    .await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::OutsideFundraisingWindow,
+    );
+
+    // Donations close at the campaign_end_time, the grace period only applies
+    // to deposits.
+    ctx.warp_to_timestamp(setup.campaign_end_time).await;
+    let result = donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "late-donation",
+        LAMPORTS_PER_SOL,
+        true,
+        true,
+    )
+    .await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::OutsideFundraisingWindow,
+    );
+
+    ctx.warp_to_timestamp(setup.campaign_end_time + days(1))
+        .await;
+    let (_, result) =
+        create_deposit_without_payment_account(&mut ctx, &setup, &native_mint::id()).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::OutsideFundraisingWindow,
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_reward_tiers() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+
+    let mut input = setup.create_escrow_input();
+    input.reward_tiers = get_default_reward_tiers();
+    input.reward_tiers[0].max_supply = 0;
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRewardTiers);
+
+    let mut input = setup.create_escrow_input();
+    input.reward_tiers = get_default_reward_tiers();
+    input.reward_tiers[1].metadata_uri = "a".repeat(RewardTier::MAX_METADATA_URI_LENGTH + 1);
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRewardTiers);
+}
+
+#[tokio::test]
+async fn test_invalid_reward_tier() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_reward_tiers(&mut ctx).await;
+    let reward_tiers = get_default_reward_tiers();
+
+    // Deposits must claim a tier if the campaign has reward tiers.
+    let (_, _, result) = create_native_deposit_for_reward_tier(
+        &mut ctx,
+        &setup,
+        reward_tiers[0].min_deposit_amount,
+        None,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRewardTier);
+
+    let (_, _, result) = create_native_deposit_for_reward_tier(
+        &mut ctx,
+        &setup,
+        reward_tiers[0].min_deposit_amount,
+        Some(reward_tiers.len() as u8),
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRewardTier);
+
+    let (_, _, result) = create_native_deposit_for_reward_tier(
+        &mut ctx,
+        &setup,
+        reward_tiers[1].min_deposit_amount - 1,
+        Some(1),
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRewardTier);
+}
+
+#[tokio::test]
+async fn test_reward_tier_sold_out() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_reward_tiers(&mut ctx).await;
+    let reward_tier = get_default_reward_tiers().remove(1);
+
+    for _ in 0..reward_tier.max_supply {
+        let (_, _, result) = create_native_deposit_for_reward_tier(
+            &mut ctx,
+            &setup,
+            reward_tier.min_deposit_amount,
+            Some(1),
+        )
+        .await;
+        result.unwrap();
+    }
+
+    let (_, _, result) = create_native_deposit_for_reward_tier(
+        &mut ctx,
+        &setup,
+        reward_tier.min_deposit_amount,
+        Some(1),
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::RewardTierSoldOut);
+}
+
+#[tokio::test]
+async fn test_invalid_hard_cap() {
+    let mut ctx = TestContext::new().await;
+    let setup = get_campaign_setup(&mut ctx, native_mint::id()).await;
+
+    let mut input = setup.create_escrow_input();
+    input.hard_cap = Some(0);
+    let result = create_escrow(&mut ctx, &setup, input).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidHardCap);
+
+    // The hard_cap cannot be lowered below the funds raised so far.
+    create_escrow(&mut ctx, &setup, setup.create_escrow_input())
+        .await
+        .unwrap();
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let result = update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: Some(Some(LAMPORTS_PER_SOL - 1)),
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidHardCap);
+}
+
+#[tokio::test]
+async fn test_hard_cap_exceeded() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_input(&mut ctx, native_mint::id(), |input| {
+        input.hard_cap = Some(2 * LAMPORTS_PER_SOL);
+    })
+    .await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    let (_, _, result) =
+        create_native_deposit_for_reward_tier(&mut ctx, &setup, LAMPORTS_PER_SOL + 1, None).await;
+    assert_program_error(result, CampaignTreasuryManagerError::HardCapExceeded);
+
+    let donor = ctx.create_funded_keypair(3 * LAMPORTS_PER_SOL).await;
+    let result = donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "over-cap-donation",
+        LAMPORTS_PER_SOL + 1,
+        true,
+        true,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::HardCapExceeded);
+}
+
+#[tokio::test]
+async fn test_invalid_matching_pool() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let sponsor = ctx.create_funded_keypair(3 * LAMPORTS_PER_SOL).await;
+
+    let result = fund_matching_pool(&mut ctx, &setup, &sponsor, 0, LAMPORTS_PER_SOL).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidMatchingPool);
+
+    let result = fund_matching_pool(&mut ctx, &setup, &sponsor, 10_000, 0).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidMatchingPool);
+
+    // Deposits must pass the MatchingPool accounts once the pool is funded.
+    fund_matching_pool(&mut ctx, &setup, &sponsor, 10_000, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let (_, _, result) =
+        create_native_deposit_for_reward_tier(&mut ctx, &setup, LAMPORTS_PER_SOL, None).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidMatchingPool);
+}
+
+#[tokio::test]
+async fn test_invalid_close_matching_pool() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_input(&mut ctx, native_mint::id(), |input| {
+        input.deposit_grace_period = days(1);
+    })
+    .await;
+    let sponsor = ctx.create_funded_keypair(2 * LAMPORTS_PER_SOL).await;
+    fund_matching_pool(&mut ctx, &setup, &sponsor, 10_000, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Deposits are still matched during the deposit_grace_period.
+    ctx.warp_to_timestamp(setup.campaign_end_time).await;
+    let result = close_matching_pool(&mut ctx, &setup, &sponsor).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidCloseMatchingPool,
+    );
+
+    ctx.warp_to_timestamp(setup.campaign_end_time + days(1))
+        .await;
+    close_matching_pool(&mut ctx, &setup, &sponsor)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_streamed_payout_claim() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_streaming_payout(&mut ctx, native_mint::id()).await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+
+    // Before the stream begins.
+    ctx.warp_to_timestamp(payout_phases[0].payout_time - 1)
+        .await;
+    let result = claim_streamed_payout(&mut ctx, &setup).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidStreamedPayoutClaim,
+    );
+
+    // Nothing has vested at the payout_time.
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    let result = claim_streamed_payout(&mut ctx, &setup).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidStreamedPayoutClaim,
+    );
+
+    // Once the stream is claimed, the next payout phase is not streaming.
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    claim_streamed_payout(&mut ctx, &setup).await.unwrap();
+    ctx.warp_forward(1).await;
+    let result = claim_streamed_payout(&mut ctx, &setup).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidStreamedPayoutClaim,
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_refund_request() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+
+    // Refunds are only available if the current payout phase is vetoed.
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    let result =
+        process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRefundRequest);
+
+    // Once funds were paid out, only partial refunds are available.
+    pay_out_funds(&mut ctx, &setup).await.unwrap();
+    veto_payout_phase(&mut ctx, &setup, 1).await.unwrap();
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let result =
+        process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRefundRequest);
+}
+
+#[tokio::test]
+async fn test_invalid_process_deposit() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    let mut deposits = vec![];
+    for _ in 0..2 {
+        let (depositor, nft_mint) =
+            create_deposit(&mut ctx, &setup, &deposit_escrow_mint, 1_000_000).await;
+        deposits.push((depositor.pubkey(), nft_mint));
+    }
+    ctx.mint_tokens_to_ata(&setup.authority.pubkey(), &deposit_escrow_mint, 0)
+        .await;
+
+    // A deposit can only be processed once.
+    let (depositor, nft_mint) = deposits[0];
+    process_deposit(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &nft_mint,
+        &deposit_escrow_mint,
+        LAMPORTS_PER_SOL,
+    )
+    .await
+    .unwrap();
+    ctx.warp_forward(1).await;
+    let result = process_deposit(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &nft_mint,
+        &deposit_escrow_mint,
+        LAMPORTS_PER_SOL,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidProcessDeposit);
+
+    // Deposits cannot be processed once funds were paid out.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    pay_out_funds(&mut ctx, &setup).await.unwrap();
+    let (depositor, nft_mint) = deposits[1];
+    let result = process_deposit(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &nft_mint,
+        &deposit_escrow_mint,
+        LAMPORTS_PER_SOL,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidProcessDeposit);
+}
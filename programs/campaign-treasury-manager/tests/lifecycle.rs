@@ -0,0 +1,2386 @@
+// End-to-end campaign lifecycle tests: create escrow, deposit escrows,
+// deposits, payouts, vetoes and close, for native and SPL treasuries.
+
+mod utils;
+
+use campaign_treasury_manager::{
+    CampaignEscrow, CampaignTreasuryManagerError, CrankReward, MatchingPool, PayoutPhaseInput,
+    StretchGoal, SurplusPolicy, UpdateCampaignEscrowInput,
+};
+use campaign_treasury_manager_client::*;
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::{pubkey::Pubkey, rent::Rent, signer::Signer, system_instruction};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::native_mint;
+use utils::*;
+
+async fn pay_out_funds(ctx: &mut TestContext, setup: &CampaignSetup) {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[pay_out_funds_ix(
+            PayOutFundsAccounts {
+                payer: payer.pubkey(),
+                payout_wallet: setup.payout_wallet,
+                crank_reward_receiver: None,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+async fn close_deposit_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: Pubkey,
+) {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[close_deposit_escrow_ix(
+            CloseDepositEscrowAccounts {
+                authority: setup.authority.pubkey(),
+                deposit_escrow_mint,
+                receiver: payer.pubkey(),
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+}
+
+async fn close_escrow(ctx: &mut TestContext, setup: &CampaignSetup) {
+    let payer = ctx.payer();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    ctx.process_instructions(
+        &[close_escrow_ix(
+            CloseEscrowAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+                creator: setup.creator.pubkey(),
+                remainder_receiver: campaign_escrow.get_remainder_receiver(),
+                receiver: payer.pubkey(),
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+}
+
+async fn update_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    update_campaign_escrow_input: UpdateCampaignEscrowInput,
+) {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[update_escrow_ix(
+            UpdateEscrowAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+                creator: setup.creator.pubkey(),
+                payout_wallet_owner: setup.creator.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            update_campaign_escrow_input,
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+}
+
+async fn set_surplus_policy(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    surplus_policy: SurplusPolicy,
+) {
+    update_escrow(
+        ctx,
+        setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: Some(surplus_policy),
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+}
+
+async fn set_crank_reward(ctx: &mut TestContext, setup: &CampaignSetup, crank_reward: CrankReward) {
+    update_escrow(
+        ctx,
+        setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: Some(crank_reward),
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+}
+
+// The DepositRecord closing instruction is not implemented yet, but should
+// accept valid accounts.
+async fn process_unimplemented_instructions(ctx: &mut TestContext, setup: &CampaignSetup) {
+    let payer = ctx.payer().pubkey();
+    let campaign_uuid = &setup.campaign_uuid;
+    ctx.process_instructions(
+        &[close_deposit_record_ix(
+            CloseDepositRecordAccounts { payer },
+            campaign_uuid,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_native_treasury_lifecycle() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.campaign_uuid, setup.campaign_uuid);
+    assert_eq!(campaign_escrow.authority, setup.authority.pubkey());
+    assert_eq!(campaign_escrow.creator, setup.creator.pubkey());
+    assert_eq!(campaign_escrow.payout_wallet, setup.creator.pubkey());
+    assert_eq!(campaign_escrow.treasury.treasury_mint, native_mint::id());
+    assert_eq!(
+        campaign_escrow.treasury.treasury_escrow,
+        setup.treasury_escrow
+    );
+    assert_eq!(campaign_escrow.campaign_end_time, setup.campaign_end_time);
+    assert_eq!(campaign_escrow.payout_phases.len(), 2);
+
+    // An SPL deposit escrow, which is never deposited into.
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    let (deposit_escrow, _) = find_deposit_escrow_pda(&setup.campaign_uuid, &deposit_escrow_mint);
+    assert_eq!(ctx.get_token_balance(&deposit_escrow).await, 0);
+
+    let deposit_amounts = [2 * LAMPORTS_PER_SOL, 3 * LAMPORTS_PER_SOL];
+    for deposit_amount in deposit_amounts {
+        let (depositor, nft_mint) =
+            create_deposit(&mut ctx, &setup, &native_mint::id(), deposit_amount).await;
+        let deposit_record = ctx.get_deposit_record(&depositor.pubkey(), &nft_mint).await;
+        assert!(deposit_record.deposit_processed);
+        assert_eq!(deposit_record.initial_deposit_amount, deposit_amount);
+        assert_eq!(deposit_record.processed_deposit_amount, deposit_amount);
+    }
+
+    let total_funds: u64 = deposit_amounts.iter().sum();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.deposit_count, 2);
+    assert_eq!(campaign_escrow.processed_deposit_count, 2);
+    assert_eq!(campaign_escrow.treasury.total_funds, total_funds);
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, total_funds);
+
+    process_unimplemented_instructions(&mut ctx, &setup).await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+
+        pay_out_funds(&mut ctx, &setup).await;
+
+        let expected_payout = total_funds * payout_phase.payout_basis_points as u64 / 10_000;
+        assert_eq!(
+            ctx.get_balance(&setup.payout_wallet).await,
+            payout_wallet_balance + expected_payout
+        );
+    }
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow
+        .payout_phases
+        .iter()
+        .all(|payout_phase| payout_phase.is_paid_out));
+    for (payout_phase, expected_payout_phase) in campaign_escrow
+        .payout_phases
+        .iter()
+        .zip(payout_phases.iter())
+    {
+        assert_eq!(
+            payout_phase.paid_amount,
+            total_funds * expected_payout_phase.payout_basis_points as u64 / 10_000
+        );
+    }
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+    assert_eq!(campaign_escrow.treasury.total_refunded, 0);
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+
+    close_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint).await;
+    assert!(ctx.get_account(&deposit_escrow).await.is_none());
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.deposit_escrow_infos[0].closed);
+
+    close_escrow(&mut ctx, &setup).await;
+    let (campaign_escrow, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    assert!(ctx.get_account(&campaign_escrow).await.is_none());
+}
+
+#[tokio::test]
+async fn test_spl_treasury_lifecycle() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+
+    // The treasury escrow is a token account owned by the CampaignEscrow, and
+    // the payout wallet is the creator's ATA.
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.payout_wallet, setup.payout_wallet);
+    assert_eq!(ctx.get_token_balance(&setup.treasury_escrow).await, 0);
+    assert_eq!(ctx.get_token_balance(&setup.payout_wallet).await, 0);
+
+    // A native deposit escrow, which is a system account PDA.
+    create_deposit_escrow(&mut ctx, &setup, native_mint::id())
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.deposit_escrow_infos.len(), 1);
+
+    // Creating the same deposit escrow again is a no-op.
+    create_deposit_escrow(&mut ctx, &setup, native_mint::id())
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.deposit_escrow_infos.len(), 1);
+
+    let deposit_amounts = [1_000_000, 2_500_000];
+    for deposit_amount in deposit_amounts {
+        create_deposit(&mut ctx, &setup, &treasury_mint, deposit_amount).await;
+    }
+    let total_funds: u64 = deposit_amounts.iter().sum();
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        total_funds
+    );
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    let mut total_paid_out = 0;
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+
+        total_paid_out += total_funds * payout_phase.payout_basis_points as u64 / 10_000;
+        assert_eq!(
+            ctx.get_token_balance(&setup.payout_wallet).await,
+            total_paid_out
+        );
+    }
+
+    assert_eq!(ctx.get_token_balance(&setup.treasury_escrow).await, 0);
+
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+    close_deposit_escrow(&mut ctx, &setup, native_mint::id()).await;
+    close_escrow(&mut ctx, &setup).await;
+    assert!(ctx.get_account(&setup.treasury_escrow).await.is_none());
+}
+
+#[tokio::test]
+async fn test_spl_treasury_rounding_dust_is_sent_to_payout_wallet_on_close() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+
+    // Each payout phase pays half of the total funds rounded down, which
+    // leaves one token in the treasury escrow.
+    let deposit_amounts = [1_000_001, 2_000_000];
+    for deposit_amount in deposit_amounts {
+        create_deposit(&mut ctx, &setup, &treasury_mint, deposit_amount).await;
+    }
+    let total_funds: u64 = deposit_amounts.iter().sum();
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+    }
+    let total_paid_out = total_funds / 2 * 2;
+    assert_eq!(
+        ctx.get_token_balance(&setup.payout_wallet).await,
+        total_paid_out
+    );
+    assert_eq!(ctx.get_token_balance(&setup.treasury_escrow).await, 1);
+
+    // The dust is accounted for, so reconciling finds no surplus.
+    let payer = ctx.payer();
+    reconcile_treasury(&mut ctx, &setup, payer.pubkey())
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_surplus, 0);
+    assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 1);
+
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+    close_escrow(&mut ctx, &setup).await;
+    assert_eq!(
+        ctx.get_token_balance(&setup.payout_wallet).await,
+        total_funds
+    );
+    assert!(ctx.get_account(&setup.treasury_escrow).await.is_none());
+}
+
+#[tokio::test]
+async fn test_spl_treasury_unclaimed_refunds_are_sent_to_payout_wallet_on_close() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+    let deposit_amount = 1_000_000;
+    let unclaimed_deposit_amount = 2_000_000;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &treasury_mint, deposit_amount).await;
+    create_deposit(&mut ctx, &setup, &treasury_mint, unclaimed_deposit_amount).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+
+    veto_payout_phase(&mut ctx, &setup, 0).await.unwrap();
+
+    // Only one of the depositors claims their refund.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint)
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        unclaimed_deposit_amount
+    );
+
+    // Payouts stop at the vetoed payout phase, so the escrow can be closed
+    // once its refunds end, instead of after the last payout phase.
+    ctx.warp_to_timestamp(payout_phases[0].refund_deadline)
+        .await;
+    let result = ctx
+        .process_instructions(
+            &[close_escrow_ix(
+                CloseEscrowAccounts {
+                    payer: ctx.payer().pubkey(),
+                    authority: setup.authority.pubkey(),
+                    creator: setup.creator.pubkey(),
+                    remainder_receiver: setup.payout_wallet,
+                    receiver: ctx.payer().pubkey(),
+                },
+                &setup.campaign_uuid,
+            )],
+            &[&setup.authority],
+        )
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCloseEscrow);
+
+    ctx.warp_to_timestamp(payout_phases[0].refund_deadline + days(3))
+        .await;
+    close_escrow(&mut ctx, &setup).await;
+    assert_eq!(
+        ctx.get_token_balance(&setup.payout_wallet).await,
+        unclaimed_deposit_amount
+    );
+    assert!(ctx.get_account(&setup.treasury_escrow).await.is_none());
+}
+
+#[tokio::test]
+async fn test_vetoed_payout_phase_is_not_paid_out() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[veto_payout_phase_ix(
+            VetoPayoutPhaseAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+            },
+            &setup.campaign_uuid,
+            1,
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(
+        !campaign_escrow
+            .payout_phases
+            .get(0)
+            .unwrap()
+            .is_vetoed_by_authority
+    );
+    assert!(
+        campaign_escrow
+            .payout_phases
+            .get(1)
+            .unwrap()
+            .is_vetoed_by_authority
+    );
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    pay_out_funds(&mut ctx, &setup).await;
+
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let result = ctx
+        .process_instructions(
+            &[pay_out_funds_ix(
+                PayOutFundsAccounts {
+                    payer: payer.pubkey(),
+                    payout_wallet: setup.payout_wallet,
+                    crank_reward_receiver: None,
+                },
+                &setup.campaign_uuid,
+            )],
+            &[],
+        )
+        .await;
+    assert_program_error(
+        result,
+        campaign_treasury_manager::CampaignTreasuryManagerError::InvalidPayoutRequest,
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.payout_phases.get(0).unwrap().is_paid_out);
+    assert!(!campaign_escrow.payout_phases.get(1).unwrap().is_paid_out);
+}
+
+#[tokio::test]
+async fn test_update_escrow_reallocates_for_more_payout_phases() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (campaign_escrow, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let initial_space = ctx.get_account(&campaign_escrow).await.unwrap().data.len();
+    assert_eq!(
+        initial_space,
+        CampaignEscrow::get_space(2, CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY, 0)
+            .unwrap()
+    );
+
+    let campaign_end_time = setup.campaign_end_time + days(1);
+    let mut payout_phases = get_default_payout_phases(campaign_end_time);
+    let mut final_payout_phase = payout_phases.last().unwrap().clone();
+    payout_phases[1].payout_basis_points = 2_500;
+    final_payout_phase.payout_basis_points = 2_500;
+    final_payout_phase.payout_time += days(30);
+    final_payout_phase.refund_deadline += days(30);
+    final_payout_phase.voting_config = None;
+    payout_phases.push(final_payout_phase);
+
+    let new_creator = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[update_escrow_ix(
+            UpdateEscrowAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+                creator: setup.creator.pubkey(),
+                payout_wallet_owner: setup.creator.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            UpdateCampaignEscrowInput {
+                authority: None,
+                creator: Some(new_creator.pubkey()),
+                campaign_end_time: Some(campaign_end_time),
+                payout_phases: Some(payout_phases),
+                surplus_policy: Some(SurplusPolicy::Supporters),
+                crank_reward: None,
+                collection_mint: None,
+                deposit_escrow_infos_capacity: None,
+                fundraising_start_time: None,
+                deposit_grace_period: None,
+                reward_tiers: None,
+                hard_cap: None,
+                auto_end_at_hard_cap: None,
+            },
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+
+    let account = ctx.get_account(&campaign_escrow).await.unwrap();
+    assert_eq!(
+        account.data.len(),
+        CampaignEscrow::get_space(3, CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY, 0)
+            .unwrap()
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.creator, new_creator.pubkey());
+    assert_eq!(campaign_escrow.campaign_end_time, campaign_end_time);
+    assert_eq!(campaign_escrow.payout_phases.len(), 3);
+    assert_eq!(campaign_escrow.surplus_policy, SurplusPolicy::Supporters);
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_campaign_escrow() {
+    let mut ctx = TestContext::new().await;
+    let campaign_uuid = get_campaign_uuid();
+    let authority = ctx.payer();
+    let legacy_campaign_escrow =
+        get_legacy_campaign_escrow(&campaign_uuid, &authority.pubkey(), ctx.now + days(20));
+    set_legacy_campaign_escrow_account(&mut ctx, &campaign_uuid, &legacy_campaign_escrow);
+
+    let migrate_ix = migrate_campaign_escrow_ix(
+        MigrateCampaignEscrowAccounts {
+            payer: authority.pubkey(),
+            authority: authority.pubkey(),
+        },
+        &campaign_uuid,
+    );
+    ctx.process_instructions(&[migrate_ix.clone()], &[])
+        .await
+        .unwrap();
+
+    let campaign_escrow = ctx.get_campaign_escrow(&campaign_uuid).await;
+    assert_eq!(campaign_escrow.version, CampaignEscrow::VERSION);
+    assert_eq!(campaign_escrow.authority, authority.pubkey());
+    assert_eq!(campaign_escrow.payout_phases.len(), 2);
+    assert!(!campaign_escrow.payout_phases.get(0).unwrap().is_voting());
+    assert!(campaign_escrow.payout_phases.get(1).unwrap().is_voting());
+
+    // Migrating an account which is already migrated is a no-op.
+    ctx.process_instructions(&[migrate_ix], &[]).await.unwrap();
+
+    // Legacy CampaignEscrows have no collection_mint, so their DepositRecords
+    // cannot be migrated until it is set by the first migration.
+    assert_eq!(campaign_escrow.collection_mint, Pubkey::default());
+    let get_migrate_deposit_records_ix = |collection_mint| {
+        migrate_deposit_records_ix(
+            MigrateDepositRecordsAccounts {
+                authority: authority.pubkey(),
+            },
+            &campaign_uuid,
+            collection_mint,
+            &[],
+        )
+    };
+    let result = ctx
+        .process_instructions(&[get_migrate_deposit_records_ix(None)], &[])
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftCollection);
+
+    let collection_mint = Pubkey::new_unique();
+    ctx.process_instructions(
+        &[get_migrate_deposit_records_ix(Some(collection_mint))],
+        &[],
+    )
+    .await
+    .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&campaign_uuid).await;
+    assert_eq!(campaign_escrow.collection_mint, collection_mint);
+
+    // The collection_mint cannot be replaced.
+    ctx.warp_forward(1).await;
+    let result = ctx
+        .process_instructions(
+            &[get_migrate_deposit_records_ix(Some(Pubkey::new_unique()))],
+            &[],
+        )
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftCollection);
+}
+
+#[tokio::test]
+async fn test_reconcile_native_treasury_surplus() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let total_funds = 2 * LAMPORTS_PER_SOL;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), total_funds).await;
+
+    // Without any unsolicited funds this is a no-op.
+    reconcile_treasury(&mut ctx, &setup, setup.payout_wallet)
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_surplus, 0);
+
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &setup.treasury_escrow,
+            LAMPORTS_PER_SOL,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // The surplus goes to the payout_wallet, except for the rent exempt
+    // minimum which stays in the treasury_escrow.
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+    let expected_surplus = LAMPORTS_PER_SOL - rent_exempt_minimum;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    reconcile_treasury(&mut ctx, &setup, setup.payout_wallet)
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + expected_surplus
+    );
+    assert_eq!(
+        ctx.get_balance(&setup.treasury_escrow).await,
+        total_funds + rent_exempt_minimum
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_funds, total_funds);
+    assert_eq!(campaign_escrow.treasury.total_surplus, expected_surplus);
+    assert_eq!(campaign_escrow.treasury.supporter_surplus, 0);
+
+    // Payouts are unaffected and the escrow can still be closed.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+    }
+    assert_eq!(
+        ctx.get_balance(&setup.treasury_escrow).await,
+        rent_exempt_minimum
+    );
+
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+    close_escrow(&mut ctx, &setup).await;
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_reconcile_spl_treasury_surplus() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+    let total_funds = 1_000_000;
+    create_deposit(&mut ctx, &setup, &treasury_mint, total_funds).await;
+
+    let payer = ctx.payer();
+    let mint_to_treasury_escrow = |amount: u64| {
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &treasury_mint,
+            &setup.treasury_escrow,
+            &payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap()
+    };
+
+    // Surplus held for supporters stays in the treasury_escrow and is not
+    // counted again by later reconciliations.
+    set_surplus_policy(&mut ctx, &setup, SurplusPolicy::Supporters).await;
+    ctx.process_instructions(&[mint_to_treasury_escrow(300)], &[])
+        .await
+        .unwrap();
+    reconcile_treasury(&mut ctx, &setup, payer.pubkey())
+        .await
+        .unwrap();
+    reconcile_treasury(&mut ctx, &setup, payer.pubkey())
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_surplus, 300);
+    assert_eq!(campaign_escrow.treasury.supporter_surplus, 300);
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        total_funds + 300
+    );
+
+    // Surplus for the authority goes to the authority's ATA.
+    set_surplus_policy(&mut ctx, &setup, SurplusPolicy::Authority).await;
+    let authority_ata = ctx
+        .mint_tokens_to_ata(&setup.authority.pubkey(), &treasury_mint, 0)
+        .await;
+    ctx.process_instructions(&[mint_to_treasury_escrow(200)], &[])
+        .await
+        .unwrap();
+    reconcile_treasury(&mut ctx, &setup, authority_ata)
+        .await
+        .unwrap();
+    assert_eq!(ctx.get_token_balance(&authority_ata).await, 200);
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        total_funds + 300
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_surplus, 500);
+    assert_eq!(campaign_escrow.treasury.supporter_surplus, 300);
+    assert_eq!(campaign_escrow.treasury.total_funds, total_funds);
+}
+
+#[tokio::test]
+async fn test_close_deposit_records_in_batches() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    let mut deposits = vec![];
+    for _ in 0..3 {
+        let (depositor, nft_mint) =
+            create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+        deposits.push((depositor.pubkey(), nft_mint));
+    }
+    let (campaign_escrow, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    for (depositor, nft_mint) in deposits.iter() {
+        let deposit_record = ctx.get_deposit_record(depositor, nft_mint).await;
+        assert_eq!(deposit_record.campaign_escrow, campaign_escrow);
+    }
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+    }
+
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+
+    let deposit_record_rent =
+        Rent::default().minimum_balance(campaign_treasury_manager::DepositRecord::SPACE);
+    let depositor_balance = ctx.get_balance(&deposits[0].0).await;
+
+    // Batches may overlap, already closed DepositRecords are skipped.
+    close_deposit_records(&mut ctx, &setup, &deposits[..2])
+        .await
+        .unwrap();
+    close_deposit_records(&mut ctx, &setup, &deposits[1..])
+        .await
+        .unwrap();
+
+    for (depositor, nft_mint) in deposits.iter() {
+        let (deposit_record, _) = find_deposit_record_pda(depositor, nft_mint);
+        assert!(ctx.get_account(&deposit_record).await.is_none());
+    }
+    assert_eq!(
+        ctx.get_balance(&deposits[0].0).await,
+        depositor_balance + deposit_record_rent
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.closed_deposit_record_count, 3);
+}
+
+#[tokio::test]
+async fn test_supporter_surplus_is_distributed_to_nft_holders() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (first_depositor, first_nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let (second_depositor, second_nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), 3 * LAMPORTS_PER_SOL).await;
+    // The second depositor sold their NFT.
+    let nft_holder = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    ctx.mint_tokens_to_ata(&first_depositor.pubkey(), &first_nft_mint, 1)
+        .await;
+    ctx.mint_tokens_to_ata(&nft_holder.pubkey(), &second_nft_mint, 1)
+        .await;
+
+    set_surplus_policy(&mut ctx, &setup, SurplusPolicy::Supporters).await;
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+    let supporter_surplus = 1_000_003;
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &setup.treasury_escrow,
+            supporter_surplus + rent_exempt_minimum,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    reconcile_treasury(&mut ctx, &setup, payer.pubkey())
+        .await
+        .unwrap();
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+    }
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+
+    // The supporter surplus must be distributed before the escrow is closed.
+    let close_escrow_instruction = close_escrow_ix(
+        CloseEscrowAccounts {
+            payer: payer.pubkey(),
+            authority: setup.authority.pubkey(),
+            creator: setup.creator.pubkey(),
+            remainder_receiver: setup.payout_wallet,
+            receiver: payer.pubkey(),
+        },
+        &setup.campaign_uuid,
+    );
+    let result = ctx
+        .process_instructions(&[close_escrow_instruction], &[&setup.authority])
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidCloseEscrow);
+
+    // Each NFT holder receives their share pro rata to the deposit, and the
+    // last one also receives the rounding remainder. The DepositRecord rent
+    // goes to the depositor.
+    let deposit_record_rent =
+        Rent::default().minimum_balance(campaign_treasury_manager::DepositRecord::SPACE);
+    let first_depositor_balance = ctx.get_balance(&first_depositor.pubkey()).await;
+    let second_depositor_balance = ctx.get_balance(&second_depositor.pubkey()).await;
+    let nft_holder_balance = ctx.get_balance(&nft_holder.pubkey()).await;
+    close_deposit_records(
+        &mut ctx,
+        &setup,
+        &[(first_depositor.pubkey(), first_nft_mint)],
+    )
+    .await
+    .unwrap();
+
+    let instruction = close_deposit_records_ix(
+        CloseDepositRecordsAccounts {
+            payer: payer.pubkey(),
+            treasury_mint: setup.treasury_mint,
+        },
+        &setup.campaign_uuid,
+        &[DepositRecordToClose {
+            depositor: second_depositor.pubkey(),
+            mint: second_nft_mint,
+            nft_holder: nft_holder.pubkey(),
+        }],
+    );
+    // The supporter surplus cannot be sent to the depositor.
+    let mut depositor_instruction = instruction.clone();
+    depositor_instruction.accounts.last_mut().unwrap().pubkey = second_depositor.pubkey();
+    let result = ctx
+        .process_instructions(&[depositor_instruction], &[])
+        .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidDepositRecord);
+
+    ctx.process_instructions(&[instruction], &[]).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&first_depositor.pubkey()).await,
+        first_depositor_balance + deposit_record_rent + 250_000
+    );
+    assert_eq!(
+        ctx.get_balance(&second_depositor.pubkey()).await,
+        second_depositor_balance + deposit_record_rent
+    );
+    assert_eq!(
+        ctx.get_balance(&nft_holder.pubkey()).await,
+        nft_holder_balance + 750_003
+    );
+    assert_eq!(
+        ctx.get_balance(&setup.treasury_escrow).await,
+        rent_exempt_minimum
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.treasury.supporter_surplus_paid_out,
+        supporter_surplus
+    );
+    assert_eq!(
+        campaign_escrow.treasury.closed_deposit_amount,
+        4 * LAMPORTS_PER_SOL
+    );
+
+    close_escrow(&mut ctx, &setup).await;
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_deposit_records() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+    ctx.set_legacy_deposit_record(&depositor.pubkey(), &nft_mint)
+        .await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    let voting_config = payout_phases[1].voting_config.as_ref().unwrap();
+    ctx.warp_to_timestamp(voting_config.voting_start_time).await;
+
+    // Legacy DepositRecords cannot be used until they are migrated.
+    let result = vote_to_veto_payout_phase(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &depositor.pubkey(),
+        &nft_mint,
+        1,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidDepositRecord);
+
+    // A legacy DepositRecord of another campaign in the same currency cannot
+    // be migrated, since its NFT is not in this campaign's collection.
+    let other_setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let (other_depositor, other_nft_mint) =
+        create_deposit(&mut ctx, &other_setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    ctx.set_legacy_deposit_record(&other_depositor.pubkey(), &other_nft_mint)
+        .await;
+    let result = migrate_deposit_records(
+        &mut ctx,
+        &setup,
+        &[(other_depositor.pubkey(), other_nft_mint)],
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidNftCollection);
+
+    // Migrating is idempotent.
+    let deposits = [(depositor.pubkey(), nft_mint)];
+    migrate_deposit_records(&mut ctx, &setup, &deposits)
+        .await
+        .unwrap();
+    ctx.warp_forward(1).await;
+    migrate_deposit_records(&mut ctx, &setup, &deposits)
+        .await
+        .unwrap();
+    let (campaign_escrow, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let deposit_record = ctx.get_deposit_record(&depositor.pubkey(), &nft_mint).await;
+    assert_eq!(deposit_record.campaign_escrow, campaign_escrow);
+
+    vote_to_veto_payout_phase(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &depositor.pubkey(),
+        &nft_mint,
+        1,
+    )
+    .await
+    .unwrap();
+}
+
+// Pays out the current payout phase with a separate keeper account receiving
+// the crank reward.
+async fn crank_payout(ctx: &mut TestContext, setup: &CampaignSetup, crank_reward_receiver: Pubkey) {
+    let keeper = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    ctx.process_instructions(
+        &[pay_out_funds_ix(
+            PayOutFundsAccounts {
+                payer: keeper.pubkey(),
+                payout_wallet: setup.payout_wallet,
+                crank_reward_receiver: Some(crank_reward_receiver),
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&keeper],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_native_payout_crank_reward() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    set_crank_reward(
+        &mut ctx,
+        &setup,
+        CrankReward::BasisPoints { basis_points: 100 },
+    )
+    .await;
+    let total_funds = 2 * LAMPORTS_PER_SOL;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), total_funds).await;
+
+    let crank_reward_receiver = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await.pubkey();
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    let mut total_crank_rewards = 0;
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+        let crank_reward_receiver_balance = ctx.get_balance(&crank_reward_receiver).await;
+
+        crank_payout(&mut ctx, &setup, crank_reward_receiver).await;
+
+        let payout_amount = total_funds * payout_phase.payout_basis_points as u64 / 10_000;
+        let crank_reward_amount = payout_amount / 100;
+        total_crank_rewards += crank_reward_amount;
+        assert_eq!(
+            ctx.get_balance(&crank_reward_receiver).await,
+            crank_reward_receiver_balance + crank_reward_amount
+        );
+        assert_eq!(
+            ctx.get_balance(&setup.payout_wallet).await,
+            payout_wallet_balance + payout_amount - crank_reward_amount
+        );
+    }
+
+    // Crank rewards are part of the paid out funds.
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+    assert_eq!(
+        campaign_escrow.treasury.total_crank_rewards,
+        total_crank_rewards
+    );
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_spl_payout_crank_reward() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+    set_crank_reward(&mut ctx, &setup, CrankReward::Fixed { amount: 1_000 }).await;
+    let total_funds = 1_000_000;
+    create_deposit(&mut ctx, &setup, &treasury_mint, total_funds).await;
+
+    let keeper = Pubkey::new_unique();
+    let crank_reward_receiver = ctx.mint_tokens_to_ata(&keeper, &treasury_mint, 0).await;
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        crank_payout(&mut ctx, &setup, crank_reward_receiver).await;
+    }
+
+    assert_eq!(ctx.get_token_balance(&crank_reward_receiver).await, 2_000);
+    assert_eq!(
+        ctx.get_token_balance(&setup.payout_wallet).await,
+        total_funds - 2_000
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_crank_rewards, 2_000);
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+}
+
+async fn pay_out_all_due_funds(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+) -> std::result::Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[pay_out_all_due_funds_ix(
+            PayOutFundsAccounts {
+                payer: payer.pubkey(),
+                payout_wallet: setup.payout_wallet,
+                crank_reward_receiver: None,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_pay_out_all_due_funds() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let total_funds = 2 * LAMPORTS_PER_SOL;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), total_funds).await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time - 1)
+        .await;
+    assert_program_error(
+        pay_out_all_due_funds(&mut ctx, &setup).await,
+        campaign_treasury_manager::CampaignTreasuryManagerError::InvalidPayoutRequest,
+    );
+
+    // Both payout phases were missed, and are paid out together.
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    pay_out_all_due_funds(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    for (payout_phase, expected_payout_phase) in campaign_escrow
+        .payout_phases
+        .iter()
+        .zip(payout_phases.iter())
+    {
+        assert!(payout_phase.is_paid_out);
+        assert_eq!(
+            payout_phase.paid_amount,
+            total_funds * expected_payout_phase.payout_basis_points as u64 / 10_000
+        );
+    }
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_pay_out_all_due_funds_stops_at_vetoed_payout_phase() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let total_funds = 2 * LAMPORTS_PER_SOL;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), total_funds).await;
+
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[veto_payout_phase_ix(
+            VetoPayoutPhaseAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+            },
+            &setup.campaign_uuid,
+            1,
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    pay_out_all_due_funds(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds / 2
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.payout_phases.get(0).unwrap().is_paid_out);
+    assert!(!campaign_escrow.payout_phases.get(1).unwrap().is_paid_out);
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds / 2);
+}
+
+#[tokio::test]
+async fn test_refund_and_vote_rights_follow_nft_holder() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let total_funds = 5 * LAMPORTS_PER_SOL;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let veto_votes = total_funds - LAMPORTS_PER_SOL;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), veto_votes).await;
+
+    // The depositor sells the NFT to a buyer.
+    let buyer = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+    ctx.transfer_tokens_to_ata(&depositor, &nft_mint, &buyer.pubkey(), 1)
+        .await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    let voting_config = payout_phases[1].voting_config.as_ref().unwrap();
+    ctx.warp_to_timestamp(voting_config.voting_start_time).await;
+
+    // The original depositor no longer holds the NFT.
+    let result = vote_to_veto_payout_phase(
+        &mut ctx,
+        &setup,
+        &depositor,
+        &depositor.pubkey(),
+        &nft_mint,
+        1,
+    )
+    .await;
+    assert_program_error(
+        result,
+        campaign_treasury_manager::CampaignTreasuryManagerError::InvalidNftHolder,
+    );
+    let result =
+        process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint).await;
+    assert_program_error(
+        result,
+        campaign_treasury_manager::CampaignTreasuryManagerError::InvalidNftHolder,
+    );
+
+    vote_to_veto_payout_phase(&mut ctx, &setup, &buyer, &depositor.pubkey(), &nft_mint, 1)
+        .await
+        .unwrap();
+
+    let (deposit_record, _) = find_deposit_record_pda(&depositor.pubkey(), &nft_mint);
+    let vote_record = ctx.get_vote_record(&deposit_record, 1).await;
+    assert_eq!(vote_record.deposit_record, deposit_record);
+    assert_eq!(vote_record.payout_phase_index, 1);
+    assert_eq!(vote_record.voter, buyer.pubkey());
+    assert_eq!(vote_record.veto_votes, veto_votes);
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    let payout_phase = campaign_escrow.payout_phases.get(1).unwrap();
+    assert_eq!(
+        payout_phase.voting_config.as_ref().unwrap().veto_votes,
+        veto_votes
+    );
+    assert!(payout_phase.is_vetoed_by_votes(total_funds));
+
+    // Each NFT votes once per payout phase.
+    ctx.warp_forward(1).await;
+    let result =
+        vote_to_veto_payout_phase(&mut ctx, &setup, &buyer, &depositor.pubkey(), &nft_mint, 1)
+            .await;
+    assert!(result.is_err());
+
+    // The vetoed payout phase is not paid out.
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    pay_out_all_due_funds(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds / 2
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(!campaign_escrow.payout_phases.get(1).unwrap().is_paid_out);
+
+    // The buyer is refunded the deposit's share of the half which was not
+    // paid out.
+    let buyer_balance = ctx.get_balance(&buyer.pubkey()).await;
+    process_partial_refund(&mut ctx, &setup, &buyer, &depositor.pubkey(), &nft_mint)
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_balance(&buyer.pubkey()).await,
+        buyer_balance + veto_votes / 2
+    );
+    assert!(ctx.get_account(&deposit_record).await.is_none());
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_refunded, veto_votes / 2);
+    assert_eq!(campaign_escrow.closed_deposit_record_count, 1);
+    assert_eq!(
+        ctx.get_balance(&setup.treasury_escrow).await,
+        campaign_escrow.get_accounted_treasury_funds()
+    );
+}
+
+#[tokio::test]
+async fn test_full_refund_when_first_payout_phase_is_vetoed() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_amount = 2 * LAMPORTS_PER_SOL;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), deposit_amount).await;
+    let (other_depositor, other_nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+    ctx.mint_tokens_to_ata(&other_depositor.pubkey(), &other_nft_mint, 1)
+        .await;
+
+    veto_payout_phase(&mut ctx, &setup, 0).await.unwrap();
+
+    // Refunds begin at the payout_time of the vetoed payout phase.
+    let result =
+        process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRefundRequest);
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+
+    // Nothing was paid out, so only full refunds are available.
+    let result =
+        process_partial_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRefundRequest);
+
+    let (deposit_record, _) = find_deposit_record_pda(&depositor.pubkey(), &nft_mint);
+    let deposit_record_rent = ctx.get_balance(&deposit_record).await;
+    let depositor_balance = ctx.get_balance(&depositor.pubkey()).await;
+    process_full_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint)
+        .await
+        .unwrap();
+
+    // The depositor still holds the NFT, so they receive both the refund and
+    // the DepositRecord rent. The NFT is burned.
+    assert_eq!(
+        ctx.get_balance(&depositor.pubkey()).await,
+        depositor_balance + deposit_amount + deposit_record_rent
+    );
+    assert!(ctx.get_account(&deposit_record).await.is_none());
+    assert_eq!(
+        ctx.get_token_balance(&get_associated_token_address(
+            &depositor.pubkey(),
+            &nft_mint
+        ))
+        .await,
+        0
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_refunded, deposit_amount);
+    assert_eq!(campaign_escrow.closed_deposit_record_count, 1);
+    assert_eq!(
+        ctx.get_balance(&setup.treasury_escrow).await,
+        LAMPORTS_PER_SOL
+    );
+
+    // Refunds end at the refund_deadline.
+    ctx.warp_to_timestamp(payout_phases[0].refund_deadline + 1)
+        .await;
+    let result = process_full_refund(
+        &mut ctx,
+        &setup,
+        &other_depositor,
+        &other_depositor.pubkey(),
+        &other_nft_mint,
+    )
+    .await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRefundRequest);
+}
+
+#[tokio::test]
+async fn test_update_escrow_reallocates_for_more_deposit_escrows() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    for _ in 0..CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY {
+        let deposit_escrow_mint = ctx.create_mint(6).await;
+        create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+            .await
+            .unwrap();
+    }
+
+    let deposit_escrow_infos_capacity = CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY + 2;
+    update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: Some(deposit_escrow_infos_capacity),
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+
+    let (campaign_escrow, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let account = ctx.get_account(&campaign_escrow).await.unwrap();
+    assert_eq!(
+        account.data.len(),
+        CampaignEscrow::get_space(2, deposit_escrow_infos_capacity, 0).unwrap()
+    );
+
+    for _ in 0..2 {
+        let deposit_escrow_mint = ctx.create_mint(6).await;
+        create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+            .await
+            .unwrap();
+    }
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.deposit_escrow_infos_capacity,
+        deposit_escrow_infos_capacity
+    );
+    assert_eq!(
+        campaign_escrow.deposit_escrow_infos.len(),
+        deposit_escrow_infos_capacity as usize
+    );
+}
+
+#[tokio::test]
+async fn test_disabled_deposit_escrow_can_be_closed() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    disable_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.deposit_escrow_infos[0].disabled);
+    assert!(!campaign_escrow.deposit_escrow_infos[0].closed);
+
+    // Disabling the same DepositEscrow again is a no-op.
+    ctx.warp_forward(1).await;
+    disable_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+    }
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+
+    close_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint).await;
+    let (deposit_escrow, _) = find_deposit_escrow_pda(&setup.campaign_uuid, &deposit_escrow_mint);
+    assert!(ctx.get_account(&deposit_escrow).await.is_none());
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.deposit_escrow_infos[0].closed);
+}
+
+#[tokio::test]
+async fn test_deposit_escrow_deposits_are_valued_with_price_oracle() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    // 1 deposit token is worth 0.5 SOL.
+    let price_oracle = ctx.create_price_oracle(50, -2).await;
+    set_deposit_escrow_price_oracle(&mut ctx, &setup, deposit_escrow_mint, price_oracle)
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.deposit_escrow_infos[0].price_oracle,
+        price_oracle
+    );
+
+    let (depositor, nft_mint) = create_deposit_with_price_oracle(
+        &mut ctx,
+        &setup,
+        &deposit_escrow_mint,
+        2_000_000,
+        Some(price_oracle),
+    )
+    .await;
+    let deposit_record = ctx.get_deposit_record(&depositor.pubkey(), &nft_mint).await;
+    assert_eq!(deposit_record.initial_deposit_amount, 2_000_000);
+    assert_eq!(deposit_record.estimated_deposit_amount, LAMPORTS_PER_SOL);
+
+    // The estimated value is pending until the deposit is processed.
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_funds, LAMPORTS_PER_SOL);
+    assert_eq!(
+        campaign_escrow.treasury.pending_estimated_funds,
+        LAMPORTS_PER_SOL
+    );
+    assert_eq!(
+        campaign_escrow.get_estimated_total_funds(),
+        2 * LAMPORTS_PER_SOL
+    );
+
+    // Once processed, the deposit counts toward total_funds with the swapped
+    // amount instead of its estimated value.
+    let processed_deposit_amount = 9 * LAMPORTS_PER_SOL / 10;
+    ctx.mint_tokens_to_ata(&setup.authority.pubkey(), &deposit_escrow_mint, 0)
+        .await;
+    process_deposit(
+        &mut ctx,
+        &setup,
+        &depositor.pubkey(),
+        &nft_mint,
+        &deposit_escrow_mint,
+        processed_deposit_amount,
+    )
+    .await
+    .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.treasury.total_funds,
+        LAMPORTS_PER_SOL + processed_deposit_amount
+    );
+    assert_eq!(campaign_escrow.treasury.pending_estimated_funds, 0);
+    assert_eq!(
+        campaign_escrow.get_estimated_total_funds(),
+        LAMPORTS_PER_SOL + processed_deposit_amount
+    );
+}
+
+#[tokio::test]
+async fn test_native_treasury_processes_token_deposits() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_escrow_mint = ctx.create_mint(6).await;
+    create_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint)
+        .await
+        .unwrap();
+    let (deposit_escrow, _) = find_deposit_escrow_pda(&setup.campaign_uuid, &deposit_escrow_mint);
+
+    let deposit_amounts = [2_000_000, 3_000_000];
+    let mut deposits = vec![];
+    for deposit_amount in deposit_amounts {
+        let (depositor, nft_mint) =
+            create_deposit(&mut ctx, &setup, &deposit_escrow_mint, deposit_amount).await;
+        deposits.push((depositor.pubkey(), nft_mint));
+    }
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.deposit_count, 2);
+    assert_eq!(campaign_escrow.processed_deposit_count, 0);
+    assert_eq!(campaign_escrow.treasury.total_funds, 0);
+    assert_eq!(ctx.get_token_balance(&deposit_escrow).await, 5_000_000);
+
+    // The authority swaps the deposits to SOL, receiving the deposit tokens.
+    let authority = setup.authority.pubkey();
+    let authority_ata = ctx
+        .mint_tokens_to_ata(&authority, &deposit_escrow_mint, 0)
+        .await;
+    let processed_deposit_amounts = [LAMPORTS_PER_SOL, 3 * LAMPORTS_PER_SOL / 2];
+    for ((depositor, nft_mint), processed_deposit_amount) in
+        deposits.iter().zip(processed_deposit_amounts)
+    {
+        let treasury_escrow_balance = ctx.get_balance(&setup.treasury_escrow).await;
+        process_deposit(
+            &mut ctx,
+            &setup,
+            depositor,
+            nft_mint,
+            &deposit_escrow_mint,
+            processed_deposit_amount,
+        )
+        .await
+        .unwrap();
+
+        let deposit_record = ctx.get_deposit_record(depositor, nft_mint).await;
+        assert!(deposit_record.deposit_processed);
+        assert_eq!(
+            deposit_record.processed_deposit_amount,
+            processed_deposit_amount
+        );
+        assert_eq!(
+            ctx.get_balance(&setup.treasury_escrow).await,
+            treasury_escrow_balance + processed_deposit_amount
+        );
+    }
+
+    let total_funds: u64 = processed_deposit_amounts.iter().sum();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.processed_deposit_count, 2);
+    assert_eq!(campaign_escrow.treasury.total_funds, total_funds);
+    assert_eq!(ctx.get_token_balance(&deposit_escrow).await, 0);
+    assert_eq!(ctx.get_token_balance(&authority_ata).await, 5_000_000);
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    for payout_phase in payout_phases.iter() {
+        ctx.warp_to_timestamp(payout_phase.payout_time).await;
+        pay_out_funds(&mut ctx, &setup).await;
+    }
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+
+    // The DepositEscrow is empty once its deposits are processed.
+    ctx.warp_to_timestamp(payout_phases.last().unwrap().refund_deadline + days(3))
+        .await;
+    close_deposit_escrow(&mut ctx, &setup, deposit_escrow_mint).await;
+    assert!(ctx.get_account(&deposit_escrow).await.is_none());
+}
+
+#[tokio::test]
+async fn test_spl_treasury_processes_native_deposits() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign(&mut ctx, treasury_mint).await;
+    create_deposit_escrow(&mut ctx, &setup, native_mint::id())
+        .await
+        .unwrap();
+    let (deposit_escrow, _) = find_deposit_escrow_pda(&setup.campaign_uuid, &native_mint::id());
+
+    let deposit_amount = 2 * LAMPORTS_PER_SOL;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), deposit_amount).await;
+    assert_eq!(ctx.get_balance(&deposit_escrow).await, deposit_amount);
+
+    let processed_deposit_amount = 40_000_000;
+    let authority = setup.authority.pubkey();
+    ctx.mint_tokens_to_ata(&authority, &treasury_mint, processed_deposit_amount)
+        .await;
+    let authority_balance = ctx.get_balance(&authority).await;
+    process_deposit(
+        &mut ctx,
+        &setup,
+        &depositor.pubkey(),
+        &nft_mint,
+        &native_mint::id(),
+        processed_deposit_amount,
+    )
+    .await
+    .unwrap();
+
+    // The authority receives the deposit, less the transaction fee it pays.
+    assert!(ctx.get_balance(&authority).await > authority_balance);
+    assert_eq!(ctx.get_balance(&deposit_escrow).await, 0);
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        processed_deposit_amount
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.processed_deposit_count, 1);
+    assert_eq!(
+        campaign_escrow.treasury.total_funds,
+        processed_deposit_amount
+    );
+}
+
+#[tokio::test]
+async fn test_donations_count_toward_total_funds() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+
+    let donor = ctx.create_funded_keypair(10 * LAMPORTS_PER_SOL).await;
+    let voting_donation_amount = 4 * LAMPORTS_PER_SOL;
+    let non_voting_donation_amount = 5 * LAMPORTS_PER_SOL;
+    let treasury_escrow_balance = ctx.get_balance(&setup.treasury_escrow).await;
+    donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "voting-donation",
+        voting_donation_amount,
+        true,
+        true,
+    )
+    .await
+    .unwrap();
+    donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "non-voting-donation",
+        non_voting_donation_amount,
+        false,
+        false,
+    )
+    .await
+    .unwrap();
+
+    let total_funds = LAMPORTS_PER_SOL + voting_donation_amount + non_voting_donation_amount;
+    assert_eq!(
+        ctx.get_balance(&setup.treasury_escrow).await,
+        treasury_escrow_balance + voting_donation_amount + non_voting_donation_amount
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_funds, total_funds);
+    assert_eq!(
+        campaign_escrow.treasury.total_donations,
+        voting_donation_amount + non_voting_donation_amount
+    );
+    assert_eq!(
+        campaign_escrow.treasury.non_voting_donations,
+        non_voting_donation_amount
+    );
+    assert_eq!(
+        campaign_escrow.get_total_votes(),
+        LAMPORTS_PER_SOL + voting_donation_amount
+    );
+    // Donations are not deposits.
+    assert_eq!(campaign_escrow.deposit_count, 1);
+
+    let donation_record = ctx
+        .get_donation_record(&setup.campaign_uuid, "non-voting-donation")
+        .await;
+    assert_eq!(donation_record.donor, donor.pubkey());
+    assert_eq!(donation_record.donation_amount, non_voting_donation_amount);
+    assert!(!donation_record.refundable);
+    assert!(!donation_record.has_vote_weight);
+
+    // The voting donation alone reaches the veto threshold, since the
+    // non-voting donation is not part of the total votes.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    let voting_config = payout_phases[1].voting_config.as_ref().unwrap();
+    ctx.warp_to_timestamp(voting_config.voting_start_time).await;
+    vote_to_veto_payout_phase_with_donation(&mut ctx, &setup, &donor, "voting-donation", 1)
+        .await
+        .unwrap();
+
+    let (campaign_escrow_address, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let (donation_record, _) =
+        find_donation_record_pda(&campaign_escrow_address, "voting-donation");
+    let vote_record = ctx.get_vote_record(&donation_record, 1).await;
+    assert_eq!(vote_record.voter, donor.pubkey());
+    assert_eq!(vote_record.veto_votes, voting_donation_amount);
+
+    // Each donation votes once per payout phase.
+    ctx.warp_forward(1).await;
+    let result =
+        vote_to_veto_payout_phase_with_donation(&mut ctx, &setup, &donor, "voting-donation", 1)
+            .await;
+    assert!(result.is_err());
+
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    pay_out_all_due_funds(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds / 2
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(!campaign_escrow.payout_phases.get(1).unwrap().is_paid_out);
+}
+
+#[tokio::test]
+async fn test_only_refundable_donations_are_refunded() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let deposit_amount = LAMPORTS_PER_SOL;
+    let (depositor, nft_mint) =
+        create_deposit(&mut ctx, &setup, &native_mint::id(), deposit_amount).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &nft_mint, 1)
+        .await;
+
+    let donor = ctx.create_funded_keypair(10 * LAMPORTS_PER_SOL).await;
+    let refundable_donation_amount = LAMPORTS_PER_SOL;
+    let non_refundable_donation_amount = 2 * LAMPORTS_PER_SOL;
+    donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "refundable-donation",
+        refundable_donation_amount,
+        true,
+        true,
+    )
+    .await
+    .unwrap();
+    donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "non-refundable-donation",
+        non_refundable_donation_amount,
+        false,
+        true,
+    )
+    .await
+    .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.treasury.non_refundable_donations,
+        non_refundable_donation_amount
+    );
+
+    // Half of the total funds is paid out before the last payout phase is
+    // vetoed.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    pay_out_funds(&mut ctx, &setup).await;
+    veto_payout_phase(&mut ctx, &setup, 1).await.unwrap();
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+
+    let result = process_donation_refund(&mut ctx, &setup, &donor, "non-refundable-donation").await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidRefundRequest);
+
+    // The remaining funds are split among the deposit and the refundable
+    // donation only, so both are refunded in full.
+    let (campaign_escrow_address, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let (donation_record, _) =
+        find_donation_record_pda(&campaign_escrow_address, "refundable-donation");
+    let donation_record_rent = ctx.get_balance(&donation_record).await;
+    let donor_balance = ctx.get_balance(&donor.pubkey()).await;
+    process_donation_refund(&mut ctx, &setup, &donor, "refundable-donation")
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_balance(&donor.pubkey()).await,
+        donor_balance + refundable_donation_amount + donation_record_rent
+    );
+    assert!(ctx.get_account(&donation_record).await.is_none());
+
+    let (deposit_record, _) = find_deposit_record_pda(&depositor.pubkey(), &nft_mint);
+    let deposit_record_rent = ctx.get_balance(&deposit_record).await;
+    let depositor_balance = ctx.get_balance(&depositor.pubkey()).await;
+    process_partial_refund(&mut ctx, &setup, &depositor, &depositor.pubkey(), &nft_mint)
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_balance(&depositor.pubkey()).await,
+        depositor_balance + deposit_amount + deposit_record_rent
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.treasury.total_refunded,
+        refundable_donation_amount + deposit_amount
+    );
+    assert_eq!(campaign_escrow.get_accounted_treasury_funds(), 0);
+}
+
+#[tokio::test]
+async fn test_deposits_are_accepted_during_grace_period() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let fundraising_start_time = ctx.now;
+    update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: Some(Some(fundraising_start_time)),
+            deposit_grace_period: Some(days(1)),
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.fundraising_start_time,
+        Some(fundraising_start_time)
+    );
+    assert_eq!(campaign_escrow.deposit_grace_period, days(1));
+
+    // An auction settling after the campaign_end_time still deposits.
+    ctx.warp_to_timestamp(setup.campaign_end_time + days(1) - 1)
+        .await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.deposit_count, 1);
+    assert_eq!(campaign_escrow.treasury.total_funds, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn test_fundraising_start_time_can_be_cleared_before_deposits() {
+    let mut ctx = TestContext::new().await;
+    let fundraising_start_time = ctx.now + days(1);
+    let setup = create_campaign_with_input(&mut ctx, native_mint::id(), |input| {
+        input.fundraising_start_time = Some(fundraising_start_time);
+    })
+    .await;
+    let donor = ctx.create_funded_keypair(2 * LAMPORTS_PER_SOL).await;
+    let result = donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "before-start",
+        LAMPORTS_PER_SOL,
+        true,
+        true,
+    )
+    .await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::OutsideFundraisingWindow,
+    );
+
+    update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: Some(None),
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.fundraising_start_time, None);
+
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.deposit_count, 1);
+}
+
+#[tokio::test]
+async fn test_deposits_claim_reward_tiers() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+    let reward_tiers = get_default_reward_tiers();
+    update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: Some(reward_tiers.clone()),
+            hard_cap: None,
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    let (campaign_escrow_address, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    assert_eq!(
+        ctx.get_account(&campaign_escrow_address)
+            .await
+            .unwrap()
+            .data
+            .len(),
+        CampaignEscrow::get_space(
+            2,
+            CampaignEscrow::DEFAULT_DEPOSIT_ESCROW_INFOS_CAPACITY,
+            reward_tiers.len()
+        )
+        .unwrap()
+    );
+
+    // A deposit can claim any tier whose minimum it meets.
+    let deposit_amount = reward_tiers[1].min_deposit_amount;
+    let (depositor, nft_mint, result) =
+        create_native_deposit_for_reward_tier(&mut ctx, &setup, deposit_amount, Some(0)).await;
+    result.unwrap();
+    let deposit_record = ctx.get_deposit_record(&depositor.pubkey(), &nft_mint).await;
+    assert_eq!(deposit_record.reward_tier_index, Some(0));
+
+    let (depositor, nft_mint, result) =
+        create_native_deposit_for_reward_tier(&mut ctx, &setup, deposit_amount, Some(1)).await;
+    result.unwrap();
+    let deposit_record = ctx.get_deposit_record(&depositor.pubkey(), &nft_mint).await;
+    assert_eq!(deposit_record.reward_tier_index, Some(1));
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.reward_tiers.get(0).unwrap().sold_count, 1);
+    assert_eq!(campaign_escrow.reward_tiers.get(1).unwrap().sold_count, 1);
+    assert_eq!(
+        campaign_escrow.reward_tiers.get(1).unwrap().metadata_uri,
+        reward_tiers[1].metadata_uri
+    );
+    assert_eq!(campaign_escrow.treasury.total_funds, 2 * deposit_amount);
+}
+
+#[tokio::test]
+async fn test_campaign_auto_ends_at_hard_cap() {
+    let mut ctx = TestContext::new().await;
+    let hard_cap = 2 * LAMPORTS_PER_SOL;
+    let setup = create_campaign_with_input(&mut ctx, native_mint::id(), |input| {
+        input.hard_cap = Some(hard_cap);
+        input.auto_end_at_hard_cap = true;
+    })
+    .await;
+
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.campaign_end_time, setup.campaign_end_time);
+
+    // A deposit which exactly reaches the hard_cap is accepted and ends the
+    // campaign.
+    ctx.warp_forward(days(1)).await;
+    let now = ctx.now;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), LAMPORTS_PER_SOL).await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_funds, hard_cap);
+    assert_eq!(campaign_escrow.hard_cap_reached_at, Some(now));
+    assert_eq!(campaign_escrow.campaign_end_time, setup.campaign_end_time);
+
+    let donor = ctx.create_funded_keypair(2 * LAMPORTS_PER_SOL).await;
+    let result = donate(
+        &mut ctx,
+        &setup,
+        &donor,
+        "after-hard-cap",
+        LAMPORTS_PER_SOL,
+        true,
+        true,
+    )
+    .await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::OutsideFundraisingWindow,
+    );
+
+    // Payouts are still scheduled from the original campaign_end_time.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    pay_out_funds(&mut ctx, &setup).await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.payout_phases.get(0).unwrap().is_paid_out);
+}
+
+#[tokio::test]
+async fn test_hard_cap_can_be_cleared_before_deposits() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_input(&mut ctx, native_mint::id(), |input| {
+        input.hard_cap = Some(LAMPORTS_PER_SOL);
+    })
+    .await;
+
+    update_escrow(
+        &mut ctx,
+        &setup,
+        UpdateCampaignEscrowInput {
+            authority: None,
+            creator: None,
+            campaign_end_time: None,
+            payout_phases: None,
+            surplus_policy: None,
+            crank_reward: None,
+            collection_mint: None,
+            deposit_escrow_infos_capacity: None,
+            fundraising_start_time: None,
+            deposit_grace_period: None,
+            reward_tiers: None,
+            hard_cap: Some(None),
+            auto_end_at_hard_cap: None,
+        },
+    )
+    .await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.hard_cap, None);
+
+    create_deposit(&mut ctx, &setup, &native_mint::id(), 2 * LAMPORTS_PER_SOL).await;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_funds, 2 * LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn test_stretch_goal_payout_phases_follow_the_raise() {
+    let mut ctx = TestContext::new().await;
+    let total_funds = 2 * LAMPORTS_PER_SOL;
+    let setup = create_campaign_with_input(&mut ctx, native_mint::id(), |input| {
+        // The final payout is replaced by a later stretch goal payout if the
+        // raise reaches the total_funds.
+        let stretch_goal = StretchGoal {
+            raise_threshold: total_funds,
+            is_active_when_reached: false,
+        };
+        input.payout_phases[1].stretch_goal = Some(stretch_goal);
+        input.payout_phases.push(PayoutPhaseInput {
+            payout_basis_points: 5_000,
+            payout_time: input.campaign_end_time + days(67),
+            refund_deadline: input.campaign_end_time + days(97),
+            description: String::from("Stretch goal payout"),
+            stretch_goal: Some(StretchGoal {
+                is_active_when_reached: true,
+                ..stretch_goal
+            }),
+            stream: None,
+            voting_config: None,
+        });
+    })
+    .await;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), total_funds).await;
+
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    pay_out_all_due_funds(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds / 2
+    );
+
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.settled_raise, Some(total_funds));
+    assert!(campaign_escrow.payout_phases.get(1).unwrap().is_deactivated);
+    assert!(!campaign_escrow.payout_phases.get(2).unwrap().is_deactivated);
+
+    ctx.warp_forward(days(30)).await;
+    pay_out_all_due_funds(&mut ctx, &setup).await.unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(!campaign_escrow.payout_phases.get(1).unwrap().is_paid_out);
+    assert!(campaign_escrow.payout_phases.get(2).unwrap().is_paid_out);
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_native_matching_pool_matches_deposits() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign(&mut ctx, native_mint::id()).await;
+
+    // Deposits are matched at half of their value, up to 3 SOL.
+    let sponsor = ctx.create_funded_keypair(5 * LAMPORTS_PER_SOL).await;
+    let match_cap = 3 * LAMPORTS_PER_SOL;
+    fund_matching_pool(&mut ctx, &setup, &sponsor, 5_000, match_cap)
+        .await
+        .unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(campaign_escrow.has_matching_pool);
+    let (campaign_escrow_address, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let (matching_pool_escrow, _) = find_matching_pool_escrow_pda(&setup.campaign_uuid);
+    let matching_pool_escrow_rent = Rent::default().minimum_balance(0);
+    assert_eq!(
+        ctx.get_balance(&matching_pool_escrow).await,
+        match_cap + matching_pool_escrow_rent
+    );
+
+    // A campaign can only have one MatchingPool.
+    ctx.warp_forward(1).await;
+    let result = fund_matching_pool(&mut ctx, &setup, &sponsor, 5_000, LAMPORTS_PER_SOL).await;
+    assert!(result.is_err());
+
+    for _ in 0..2 {
+        let (_, _, result) =
+            create_matched_native_deposit(&mut ctx, &setup, 2 * LAMPORTS_PER_SOL).await;
+        result.unwrap();
+    }
+
+    let total_deposits = 4 * LAMPORTS_PER_SOL;
+    let total_matched = 2 * LAMPORTS_PER_SOL;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(
+        campaign_escrow.treasury.total_funds,
+        total_deposits + total_matched
+    );
+    assert_eq!(campaign_escrow.treasury.total_matched, total_matched);
+    // Sponsors do not vote with matched funds.
+    assert_eq!(campaign_escrow.get_total_votes(), total_deposits);
+    let matching_pool = ctx.get_matching_pool(&setup.campaign_uuid).await;
+    assert_eq!(matching_pool.sponsor, sponsor.pubkey());
+    assert_eq!(matching_pool.campaign_escrow, campaign_escrow_address);
+    assert_eq!(matching_pool.total_matched, total_matched);
+
+    // The last deposit is matched with the rest of the pool only.
+    let (_, _, result) =
+        create_matched_native_deposit(&mut ctx, &setup, 4 * LAMPORTS_PER_SOL).await;
+    result.unwrap();
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_matched, match_cap);
+    assert_eq!(
+        ctx.get_balance(&matching_pool_escrow).await,
+        matching_pool_escrow_rent
+    );
+
+    // Deposits to a campaign with a MatchingPool must include its accounts.
+    let (_, _, result) =
+        create_native_deposit_for_reward_tier(&mut ctx, &setup, LAMPORTS_PER_SOL, None).await;
+    assert_program_error(result, CampaignTreasuryManagerError::InvalidMatchingPool);
+
+    // Matched funds are paid out with the rest of the treasury.
+    let total_funds = 8 * LAMPORTS_PER_SOL + match_cap;
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    pay_out_funds(&mut ctx, &setup).await;
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds * payout_phases[0].payout_basis_points as u64 / 10_000
+    );
+
+    // Closing returns the escrow and MatchingPool rent to the sponsor.
+    let sponsor_balance = ctx.get_balance(&sponsor.pubkey()).await;
+    close_matching_pool(&mut ctx, &setup, &sponsor)
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_balance(&sponsor.pubkey()).await,
+        sponsor_balance
+            + matching_pool_escrow_rent
+            + Rent::default().minimum_balance(MatchingPool::SPACE)
+    );
+    assert!(ctx.get_account(&matching_pool_escrow).await.is_none());
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(!campaign_escrow.has_matching_pool);
+}
+
+#[tokio::test]
+async fn test_spl_matching_pool_returns_unused_match_to_sponsor() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let hard_cap = 5_000_000;
+    let setup = create_campaign_with_input(&mut ctx, treasury_mint, |input| {
+        input.hard_cap = Some(hard_cap);
+    })
+    .await;
+
+    // Deposits are matched 2:1, up to 4 tokens.
+    let sponsor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let match_cap = 4_000_000;
+    let sponsor_ata = ctx
+        .mint_tokens_to_ata(&sponsor.pubkey(), &treasury_mint, match_cap)
+        .await;
+    fund_matching_pool(&mut ctx, &setup, &sponsor, 20_000, match_cap)
+        .await
+        .unwrap();
+    let (matching_pool_escrow, _) = find_matching_pool_escrow_pda(&setup.campaign_uuid);
+    assert_eq!(
+        ctx.get_token_balance(&matching_pool_escrow).await,
+        match_cap
+    );
+    assert_eq!(ctx.get_token_balance(&sponsor_ata).await, 0);
+
+    // Matches stop at the hard_cap instead of rejecting the deposit.
+    let deposit_amount = 2_000_000;
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    ctx.mint_tokens_to_ata(&depositor.pubkey(), &treasury_mint, deposit_amount)
+        .await;
+    ctx.process_instructions(
+        &get_create_deposit_ixs_with_options(
+            &setup,
+            &depositor.pubkey(),
+            &nft_mint,
+            &treasury_mint,
+            deposit_amount,
+            None,
+            None,
+            true,
+        ),
+        &[&depositor],
+    )
+    .await
+    .unwrap();
+
+    let total_matched = hard_cap - deposit_amount;
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert_eq!(campaign_escrow.treasury.total_funds, hard_cap);
+    assert_eq!(campaign_escrow.treasury.total_matched, total_matched);
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        hard_cap
+    );
+
+    // The MatchingPool can only be closed once deposits are over.
+    let result = close_matching_pool(&mut ctx, &setup, &sponsor).await;
+    assert_program_error(
+        result,
+        CampaignTreasuryManagerError::InvalidCloseMatchingPool,
+    );
+
+    ctx.warp_to_timestamp(setup.campaign_end_time).await;
+    close_matching_pool(&mut ctx, &setup, &sponsor)
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.get_token_balance(&sponsor_ata).await,
+        match_cap - total_matched
+    );
+    assert!(ctx.get_account(&matching_pool_escrow).await.is_none());
+    let (campaign_escrow_address, _) = find_campaign_escrow_pda(&setup.campaign_uuid);
+    let (matching_pool, _) = find_matching_pool_pda(&campaign_escrow_address);
+    assert!(ctx.get_account(&matching_pool).await.is_none());
+}
+
+#[tokio::test]
+async fn test_native_streaming_payout_phase() {
+    let mut ctx = TestContext::new().await;
+    let setup = create_campaign_with_streaming_payout(&mut ctx, native_mint::id()).await;
+    let total_funds = 2 * LAMPORTS_PER_SOL;
+    create_deposit(&mut ctx, &setup, &native_mint::id(), total_funds).await;
+
+    // Streaming payout phases are not paid out by PayOutFunds.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time).await;
+    assert_program_error(
+        pay_out_all_due_funds(&mut ctx, &setup).await,
+        CampaignTreasuryManagerError::InvalidPayoutRequest,
+    );
+
+    // Half of the initial payout has vested halfway through the stream.
+    ctx.warp_forward(days(15)).await;
+    let payout_wallet_balance = ctx.get_balance(&setup.payout_wallet).await;
+    claim_streamed_payout(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds / 4
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    let payout_phase = campaign_escrow.payout_phases.get(0).unwrap();
+    assert!(!payout_phase.is_paid_out);
+    assert_eq!(payout_phase.paid_amount, total_funds / 4);
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds / 4);
+
+    // The stream ends at the final payout_time, which is then paid out.
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    claim_streamed_payout(&mut ctx, &setup).await.unwrap();
+    pay_out_funds(&mut ctx, &setup).await;
+    assert_eq!(
+        ctx.get_balance(&setup.payout_wallet).await,
+        payout_wallet_balance + total_funds
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    let payout_phase = campaign_escrow.payout_phases.get(0).unwrap();
+    assert!(payout_phase.is_paid_out);
+    assert_eq!(payout_phase.paid_amount, total_funds / 2);
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds);
+    assert_eq!(ctx.get_balance(&setup.treasury_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_spl_veto_freezes_streaming_payout_phase() {
+    let mut ctx = TestContext::new().await;
+    let treasury_mint = ctx.create_mint(6).await;
+    let setup = create_campaign_with_streaming_payout(&mut ctx, treasury_mint).await;
+    let total_funds = 1_000_000;
+    create_deposit(&mut ctx, &setup, &treasury_mint, total_funds).await;
+
+    // The authority vetoes the stream a third of the way through.
+    let payout_phases = get_default_payout_phases(setup.campaign_end_time);
+    ctx.warp_to_timestamp(payout_phases[0].payout_time + days(10))
+        .await;
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[veto_payout_phase_ix(
+            VetoPayoutPhaseAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+            },
+            &setup.campaign_uuid,
+            0,
+        )],
+        &[&setup.authority],
+    )
+    .await
+    .unwrap();
+
+    // Only the amount vested before the veto can be claimed.
+    ctx.warp_to_timestamp(payout_phases[1].payout_time).await;
+    claim_streamed_payout(&mut ctx, &setup).await.unwrap();
+    assert_eq!(
+        ctx.get_token_balance(&setup.payout_wallet).await,
+        total_funds / 6
+    );
+
+    // The frozen remainder blocks the later payout phases.
+    ctx.warp_forward(days(1)).await;
+    assert_program_error(
+        claim_streamed_payout(&mut ctx, &setup).await,
+        CampaignTreasuryManagerError::InvalidStreamedPayoutClaim,
+    );
+    assert_program_error(
+        pay_out_all_due_funds(&mut ctx, &setup).await,
+        CampaignTreasuryManagerError::InvalidPayoutRequest,
+    );
+    let campaign_escrow = ctx.get_campaign_escrow(&setup.campaign_uuid).await;
+    assert!(!campaign_escrow.payout_phases.get(0).unwrap().is_paid_out);
+    assert_eq!(campaign_escrow.treasury.total_paid_out, total_funds / 6);
+    assert_eq!(
+        ctx.get_token_balance(&setup.treasury_escrow).await,
+        total_funds - total_funds / 6
+    );
+}
@@ -0,0 +1,1331 @@
+// Shared harness for the solana-program-test integration tests.
+//
+// The program runs natively with the production buffer times. The clock is
+// set explicitly before every transaction, so tests can move across the
+// MinBufferTimes/MaxBufferTimes boundaries by warping to a unix timestamp.
+
+#![allow(dead_code)]
+
+use anchor_lang::{
+    error::ErrorCode, AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator,
+};
+use campaign_treasury_manager::{
+    constants::FORMFN_AUCTION_HOUSE_PROGRAM_ID, CampaignEscrow, CampaignTreasuryManagerError,
+    CrankReward, CreateCampaignEscrowInput, DepositRecord, DonationRecord, LegacyCampaignEscrow,
+    LegacyNonVotingPayoutPhase, LegacyPayoutPhases, LegacySharedPayoutPhaseFields, LegacyTreasury,
+    LegacyVotingPayoutPhase, MatchingPool, PayoutPhaseInput, PayoutStreamInput, PriceOracle,
+    RewardTierInput, SurplusPolicy, VoteRecord, VotingConfigInput, ID, PYTH_PROGRAM_ID,
+};
+use campaign_treasury_manager_client::{
+    claim_streamed_payout_ix, close_deposit_records_ix, close_matching_pool_ix,
+    create_deposit_escrow_ix, create_deposit_ix, create_escrow_ix, disable_deposit_escrow_ix,
+    donate_ix, find_campaign_escrow_pda, find_deposit_record_pda, find_donation_record_pda,
+    find_matching_pool_pda, find_metadata_pda, find_treasury_escrow_pda, find_vote_record_pda,
+    fund_matching_pool_ix, get_payout_wallet, migrate_deposit_records_ix, process_deposit_ix,
+    process_donation_refund_ix, process_full_refund_ix, process_partial_refund_ix,
+    reconcile_treasury_ix, set_deposit_escrow_price_oracle_ix, veto_payout_phase_ix,
+    vote_to_veto_payout_phase_ix, vote_to_veto_payout_phase_with_donation_ix,
+    ClaimStreamedPayoutAccounts, CloseDepositRecordsAccounts, CloseMatchingPoolAccounts,
+    CreateDepositAccounts, CreateDepositEscrowAccounts, CreateEscrowAccounts, DepositRecordToClose,
+    DisableDepositEscrowAccounts, DonateAccounts, FundMatchingPoolAccounts,
+    MigrateDepositRecordsAccounts, ProcessDepositAccounts, ProcessDonationRefundAccounts,
+    ProcessFullRefundAccounts, ProcessPartialRefundAccounts, ReconcileTreasuryAccounts,
+    SetDepositEscrowPriceOracleAccounts, VetoPayoutPhaseAccounts, VoteToVetoPayoutPhaseAccounts,
+    VoteToVetoPayoutPhaseWithDonationAccounts,
+};
+use mpl_token_metadata::state::{Collection, Data, Key, Metadata, TokenStandard, MAX_METADATA_LEN};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+use spl_token::native_mint;
+
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// Fixed start time, so that test timelines are deterministic.
+pub const START_TIME: i64 = 1_700_000_000;
+
+pub const fn days(num_days: i64) -> i64 {
+    SECONDS_PER_DAY * num_days
+}
+
+// Anchor's entry function requires the account infos to outlive their
+// borrowed data, which the program-test processor signature does not express.
+// Leaking the account infos is fine for tests.
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    campaign_treasury_manager::entry(program_id, accounts, instruction_data)
+}
+
+// Stands in for the auction house program, so that CreateDeposit passes CPI
+// validation when it is preceded by an instruction to this program.
+fn process_noop_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    Ok(())
+}
+
+pub fn auction_house_noop_ix() -> Instruction {
+    Instruction::new_with_bytes(FORMFN_AUCTION_HOUSE_PROGRAM_ID, &[], vec![])
+}
+
+fn get_native_mint_account() -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 0,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
+
+pub fn get_campaign_uuid() -> String {
+    // Campaign UUIDs are passed in their PDA seed form, which is 32 characters.
+    Pubkey::new_unique().to_bytes()[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub struct TestContext {
+    pub context: ProgramTestContext,
+    // The unix timestamp the clock is set to for the next transaction.
+    pub now: i64,
+    // The slot of the working bank, which the clock sysvar can lag behind.
+    slot: u64,
+}
+
+impl TestContext {
+    pub async fn new() -> Self {
+        let mut program_test = ProgramTest::new(
+            "campaign_treasury_manager",
+            ID,
+            processor!(process_instruction),
+        );
+        program_test.add_program(
+            "formfn_auction_house",
+            FORMFN_AUCTION_HOUSE_PROGRAM_ID,
+            processor!(process_noop_instruction),
+        );
+        // The bundled SPL program binaries fail to load in this program-test
+        // version, so run the token programs natively as well.
+        program_test.add_program(
+            "spl_token",
+            spl_token::id(),
+            processor!(spl_token::processor::Processor::process),
+        );
+        program_test.add_program(
+            "spl_associated_token_account",
+            spl_associated_token_account::id(),
+            processor!(spl_associated_token_account::processor::process_instruction),
+        );
+        program_test.add_account(native_mint::id(), get_native_mint_account());
+
+        let mut test_context = TestContext {
+            context: program_test.start_with_context().await,
+            now: START_TIME,
+            slot: 1,
+        };
+        test_context.advance_slot().await;
+        test_context
+    }
+
+    pub fn payer(&self) -> Keypair {
+        clone_keypair(&self.context.payer)
+    }
+
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        self.now = unix_timestamp;
+        self.advance_slot().await;
+    }
+
+    pub async fn warp_forward(&mut self, seconds: i64) {
+        self.warp_to_timestamp(self.now + seconds).await;
+    }
+
+    // Moves to the next slot, which gives each transaction a fresh blockhash,
+    // and then resets the clock to the current test time.
+    async fn advance_slot(&mut self) {
+        self.slot += 1;
+        self.context.warp_to_slot(self.slot).unwrap();
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        self.context.set_sysvar(&Clock {
+            unix_timestamp: self.now,
+            ..clock
+        });
+    }
+
+    pub async fn process_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        self.advance_slot().await;
+
+        let payer = self.payer();
+        let mut all_signers: Vec<&Keypair> = vec![&payer];
+        for signer in signers {
+            if !all_signers
+                .iter()
+                .any(|existing| existing.pubkey() == signer.pubkey())
+            {
+                all_signers.push(signer);
+            }
+        }
+
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    pub async fn get_account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_balance(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_token_balance(&mut self, address: &Pubkey) -> u64 {
+        let account = self.get_account(address).await.unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn get_campaign_escrow(&mut self, campaign_uuid: &str) -> CampaignEscrow {
+        let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+        let account = self.get_account(&campaign_escrow).await.unwrap();
+        CampaignEscrow::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn set_campaign_escrow(
+        &mut self,
+        campaign_uuid: &str,
+        campaign_escrow_data: &CampaignEscrow,
+    ) {
+        let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+        let mut account = self.get_account(&campaign_escrow).await.unwrap();
+        campaign_escrow_data
+            .try_serialize(&mut account.data.as_mut_slice())
+            .unwrap();
+        self.context.set_account(&campaign_escrow, &account.into());
+    }
+
+    pub async fn get_deposit_record(&mut self, depositor: &Pubkey, mint: &Pubkey) -> DepositRecord {
+        let (deposit_record, _) = find_deposit_record_pda(depositor, mint);
+        let account = self.get_account(&deposit_record).await.unwrap();
+        DepositRecord::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    // Rewrites a DepositRecord as a legacy account, created before its
+    // campaign_escrow was recorded.
+    pub async fn set_legacy_deposit_record(&mut self, depositor: &Pubkey, mint: &Pubkey) {
+        let (deposit_record_pda, _) = find_deposit_record_pda(depositor, mint);
+        let mut account = self.get_account(&deposit_record_pda).await.unwrap();
+        let mut deposit_record =
+            DepositRecord::try_deserialize(&mut account.data.as_slice()).unwrap();
+        deposit_record.campaign_escrow = Pubkey::default();
+        deposit_record
+            .try_serialize(&mut account.data.as_mut_slice())
+            .unwrap();
+        self.context
+            .set_account(&deposit_record_pda, &account.into());
+    }
+
+    pub async fn get_vote_record(
+        &mut self,
+        deposit_record: &Pubkey,
+        payout_phase_index: u8,
+    ) -> VoteRecord {
+        let (vote_record, _) = find_vote_record_pda(deposit_record, payout_phase_index);
+        let account = self.get_account(&vote_record).await.unwrap();
+        VoteRecord::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn get_donation_record(
+        &mut self,
+        campaign_uuid: &str,
+        donation_uuid: &str,
+    ) -> DonationRecord {
+        let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+        let (donation_record, _) = find_donation_record_pda(&campaign_escrow, donation_uuid);
+        let account = self.get_account(&donation_record).await.unwrap();
+        DonationRecord::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn get_matching_pool(&mut self, campaign_uuid: &str) -> MatchingPool {
+        let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+        let (matching_pool, _) = find_matching_pool_pda(&campaign_escrow);
+        let account = self.get_account(&matching_pool).await.unwrap();
+        MatchingPool::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn create_funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let payer = self.payer();
+        self.process_instructions(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &keypair.pubkey(),
+                lamports,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        keypair
+    }
+
+    // Creates a mint with the test payer as the mint authority.
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+
+        self.process_instructions(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+
+        mint.pubkey()
+    }
+
+    // Creates an NFT mint whose metadata lists the given collection. The
+    // metadata account is written directly, so the token metadata program does
+    // not need to be loaded.
+    pub async fn create_nft_mint(&mut self, collection_mint: &Pubkey, verified: bool) -> Pubkey {
+        let nft_mint = self.create_mint(0).await;
+        let metadata = Metadata {
+            key: Key::MetadataV1,
+            update_authority: self.payer().pubkey(),
+            mint: nft_mint,
+            data: Data {
+                name: String::from("Campaign NFT"),
+                symbol: String::from("CAMPAIGN"),
+                uri: String::from("https://formfunction.xyz"),
+                seller_fee_basis_points: 0,
+                creators: None,
+            },
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: None,
+            token_standard: Some(TokenStandard::NonFungible),
+            collection: Some(Collection {
+                verified,
+                key: *collection_mint,
+            }),
+            uses: None,
+            collection_details: None,
+            programmable_config: None,
+        };
+
+        let mut data = vec![0; MAX_METADATA_LEN];
+        metadata.save(&mut data).unwrap();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.context.set_account(
+            &find_metadata_pda(&nft_mint).0,
+            &Account {
+                lamports: rent.minimum_balance(MAX_METADATA_LEN),
+                data,
+                owner: mpl_token_metadata::id(),
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        );
+
+        nft_mint
+    }
+
+    // Creates a mock Pyth price account with a trading price updated at the
+    // current test time. The account is written directly, so the Pyth program
+    // does not need to be loaded.
+    pub async fn create_price_oracle(&mut self, price: i64, expo: i32) -> Pubkey {
+        let price_oracle = Pubkey::new_unique();
+        let data = PriceOracle {
+            expo,
+            timestamp: self.now,
+            price,
+            conf: 0,
+            status: PriceOracle::STATUS_TRADING,
+        }
+        .to_account_data();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.context.set_account(
+            &price_oracle,
+            &Account {
+                lamports: rent.minimum_balance(data.len()),
+                data,
+                owner: PYTH_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        );
+
+        price_oracle
+    }
+
+    // Creates the owner's ATA if needed and mints the given amount to it.
+    pub async fn mint_tokens_to_ata(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let ata = get_associated_token_address(owner, mint);
+        let payer = self.payer();
+
+        let mut instructions = vec![];
+        if self.get_account(&ata).await.is_none() {
+            instructions.push(create_associated_token_account(
+                &payer.pubkey(),
+                owner,
+                mint,
+                &spl_token::id(),
+            ));
+        }
+        instructions.push(
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                mint,
+                &ata,
+                &payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        );
+
+        self.process_instructions(&instructions, &[]).await.unwrap();
+
+        ata
+    }
+
+    // Transfers tokens from the owner's ATA to the receiver's ATA, creating
+    // the receiver's ATA if needed.
+    pub async fn transfer_tokens_to_ata(
+        &mut self,
+        owner: &Keypair,
+        mint: &Pubkey,
+        receiver: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let receiver_ata = get_associated_token_address(receiver, mint);
+        let payer = self.payer();
+
+        let mut instructions = vec![];
+        if self.get_account(&receiver_ata).await.is_none() {
+            instructions.push(create_associated_token_account(
+                &payer.pubkey(),
+                receiver,
+                mint,
+                &spl_token::id(),
+            ));
+        }
+        instructions.push(
+            spl_token::instruction::transfer(
+                &spl_token::id(),
+                &get_associated_token_address(&owner.pubkey(), mint),
+                &receiver_ata,
+                &owner.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        );
+
+        self.process_instructions(&instructions, &[owner])
+            .await
+            .unwrap();
+
+        receiver_ata
+    }
+}
+
+pub fn assert_custom_error(result: Result<(), BanksClientError>, expected_code: u32) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(
+            code, expected_code,
+            "Expected error code {} but got {}",
+            expected_code, code
+        ),
+        other => panic!(
+            "Expected custom error code {} but got {:?}",
+            expected_code, other
+        ),
+    }
+}
+
+pub fn assert_program_error(
+    result: Result<(), BanksClientError>,
+    expected_error: CampaignTreasuryManagerError,
+) {
+    assert_custom_error(result, expected_error.into());
+}
+
+pub fn assert_anchor_error(result: Result<(), BanksClientError>, expected_error: ErrorCode) {
+    assert_custom_error(result, expected_error.into());
+}
+
+// Payout phases for a campaign ending at campaign_end_time which satisfy the
+// production buffer times. The second phase is a voting phase.
+pub fn get_default_payout_phases(campaign_end_time: i64) -> Vec<PayoutPhaseInput> {
+    vec![
+        PayoutPhaseInput {
+            payout_basis_points: 5_000,
+            payout_time: campaign_end_time + days(7),
+            refund_deadline: campaign_end_time + days(37),
+            description: String::from("Initial payout"),
+            stretch_goal: None,
+            stream: None,
+            voting_config: None,
+        },
+        PayoutPhaseInput {
+            payout_basis_points: 5_000,
+            payout_time: campaign_end_time + days(37),
+            refund_deadline: campaign_end_time + days(67),
+            description: String::from("Final payout"),
+            stretch_goal: None,
+            stream: None,
+            voting_config: Some(VotingConfigInput {
+                voting_start_time: campaign_end_time + days(23),
+                vote_basis_points_veto_threshold: 8_000,
+            }),
+        },
+    ]
+}
+
+pub struct CampaignSetup {
+    pub campaign_uuid: String,
+    pub authority: Keypair,
+    pub creator: Keypair,
+    pub treasury_mint: Pubkey,
+    pub payout_wallet: Pubkey,
+    pub treasury_escrow: Pubkey,
+    pub campaign_end_time: i64,
+    pub collection_mint: Pubkey,
+}
+
+impl CampaignSetup {
+    pub fn create_escrow_accounts(&self, payer: &Pubkey) -> CreateEscrowAccounts {
+        CreateEscrowAccounts {
+            payer: *payer,
+            authority: self.authority.pubkey(),
+            creator: self.creator.pubkey(),
+            payout_wallet_owner: self.creator.pubkey(),
+            treasury_mint: self.treasury_mint,
+        }
+    }
+
+    pub fn create_escrow_input(&self) -> CreateCampaignEscrowInput {
+        CreateCampaignEscrowInput {
+            campaign_end_time: self.campaign_end_time,
+            payout_phases: get_default_payout_phases(self.campaign_end_time),
+            surplus_policy: SurplusPolicy::Creator,
+            crank_reward: CrankReward::None,
+            collection_mint: self.collection_mint,
+            fundraising_start_time: None,
+            deposit_grace_period: 0,
+            reward_tiers: vec![],
+            hard_cap: None,
+            auto_end_at_hard_cap: false,
+        }
+    }
+}
+
+// Returns the setup for a campaign whose authority is the test payer and
+// whose creator owns the payout wallet, without creating the escrow.
+pub async fn get_campaign_setup(ctx: &mut TestContext, treasury_mint: Pubkey) -> CampaignSetup {
+    let campaign_uuid = get_campaign_uuid();
+    let creator = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let (treasury_escrow, _) = find_treasury_escrow_pda(&campaign_uuid);
+
+    CampaignSetup {
+        payout_wallet: get_payout_wallet(&creator.pubkey(), &treasury_mint),
+        campaign_uuid,
+        authority: ctx.payer(),
+        creator,
+        treasury_mint,
+        treasury_escrow,
+        campaign_end_time: ctx.now + days(20),
+        collection_mint: Pubkey::new_unique(),
+    }
+}
+
+// Two tiers, the second being a limited tier with a higher minimum.
+pub fn get_default_reward_tiers() -> Vec<RewardTierInput> {
+    vec![
+        RewardTierInput {
+            min_deposit_amount: LAMPORTS_PER_SOL,
+            max_supply: 100,
+            metadata_uri: String::from("https://arweave.net/supporter-tier"),
+        },
+        RewardTierInput {
+            min_deposit_amount: 5 * LAMPORTS_PER_SOL,
+            max_supply: 2,
+            metadata_uri: String::from("https://arweave.net/patron-tier"),
+        },
+    ]
+}
+
+// Creates a native treasury campaign with the default reward tiers.
+pub async fn create_campaign_with_reward_tiers(ctx: &mut TestContext) -> CampaignSetup {
+    create_campaign_with_input(ctx, native_mint::id(), |input| {
+        input.reward_tiers = get_default_reward_tiers();
+    })
+    .await
+}
+
+// Like create_campaign, with the default CreateCampaignEscrowInput modified
+// by update_input.
+pub async fn create_campaign_with_input(
+    ctx: &mut TestContext,
+    treasury_mint: Pubkey,
+    update_input: impl FnOnce(&mut CreateCampaignEscrowInput),
+) -> CampaignSetup {
+    let setup = get_campaign_setup(ctx, treasury_mint).await;
+    let payer = ctx.payer();
+    let mut input = setup.create_escrow_input();
+    update_input(&mut input);
+
+    ctx.process_instructions(
+        &[create_escrow_ix(
+            setup.create_escrow_accounts(&payer.pubkey()),
+            &setup.campaign_uuid,
+            input,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    setup
+}
+
+pub async fn create_campaign(ctx: &mut TestContext, treasury_mint: Pubkey) -> CampaignSetup {
+    let setup = get_campaign_setup(ctx, treasury_mint).await;
+    let payer = ctx.payer();
+
+    ctx.process_instructions(
+        &[create_escrow_ix(
+            setup.create_escrow_accounts(&payer.pubkey()),
+            &setup.campaign_uuid,
+            setup.create_escrow_input(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    setup
+}
+
+// Streams the initial payout from its payout_time until the final payout_time.
+pub async fn create_campaign_with_streaming_payout(
+    ctx: &mut TestContext,
+    treasury_mint: Pubkey,
+) -> CampaignSetup {
+    create_campaign_with_input(ctx, treasury_mint, |input| {
+        input.payout_phases[0].stream = Some(PayoutStreamInput {
+            stream_end_time: input.payout_phases[1].payout_time,
+        });
+    })
+    .await
+}
+
+pub async fn create_deposit_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: Pubkey,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[create_deposit_escrow_ix(
+            CreateDepositEscrowAccounts {
+                authority: setup.authority.pubkey(),
+                deposit_escrow_mint,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+pub async fn disable_deposit_escrow(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: Pubkey,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[disable_deposit_escrow_ix(
+            DisableDepositEscrowAccounts {
+                authority: setup.authority.pubkey(),
+                deposit_escrow_mint,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+pub async fn set_deposit_escrow_price_oracle(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: Pubkey,
+    price_oracle: Pubkey,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[set_deposit_escrow_price_oracle_ix(
+            SetDepositEscrowPriceOracleAccounts {
+                authority: setup.authority.pubkey(),
+                deposit_escrow_mint,
+                price_oracle,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+// The authority exchanges the deposit for the processed_deposit_amount in the
+// treasury currency, so it must hold that amount, and have an ATA for token
+// deposit_escrow_mints.
+pub async fn process_deposit(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+    deposit_escrow_mint: &Pubkey,
+    processed_deposit_amount: u64,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[process_deposit_ix(
+            ProcessDepositAccounts {
+                authority: setup.authority.pubkey(),
+                depositor: *depositor,
+                mint: *nft_mint,
+                deposit_escrow_mint: *deposit_escrow_mint,
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            processed_deposit_amount,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+pub async fn reconcile_treasury(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    surplus_receiver: Pubkey,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[reconcile_treasury_ix(
+            ReconcileTreasuryAccounts {
+                payer: payer.pubkey(),
+                surplus_receiver,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[],
+    )
+    .await
+}
+
+// Each deposit is a depositor and NFT mint pair, as returned by create_deposit.
+// The depositors are expected to still hold their NFTs.
+pub async fn close_deposit_records(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposits: &[(Pubkey, Pubkey)],
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    let deposit_records: Vec<DepositRecordToClose> = deposits
+        .iter()
+        .map(|(depositor, mint)| DepositRecordToClose {
+            depositor: *depositor,
+            mint: *mint,
+            nft_holder: *depositor,
+        })
+        .collect();
+    ctx.process_instructions(
+        &[close_deposit_records_ix(
+            CloseDepositRecordsAccounts {
+                payer: payer.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            &deposit_records,
+        )],
+        &[],
+    )
+    .await
+}
+
+// The nft_holder must hold the NFT of the depositor's DepositRecord.
+pub async fn process_full_refund(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    nft_holder: &Keypair,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[process_full_refund_ix(
+            ProcessFullRefundAccounts {
+                payer: payer.pubkey(),
+                nft_holder: nft_holder.pubkey(),
+                depositor: *depositor,
+                mint: *nft_mint,
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[nft_holder],
+    )
+    .await
+}
+
+// The nft_holder must hold the NFT of the depositor's DepositRecord.
+pub async fn process_partial_refund(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    nft_holder: &Keypair,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[process_partial_refund_ix(
+            ProcessPartialRefundAccounts {
+                payer: payer.pubkey(),
+                nft_holder: nft_holder.pubkey(),
+                depositor: *depositor,
+                mint: *nft_mint,
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[nft_holder],
+    )
+    .await
+}
+
+pub async fn veto_payout_phase(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    payout_phase_index: u8,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[veto_payout_phase_ix(
+            VetoPayoutPhaseAccounts {
+                payer: payer.pubkey(),
+                authority: setup.authority.pubkey(),
+            },
+            &setup.campaign_uuid,
+            payout_phase_index,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+// The nft_holder must hold the NFT of the depositor's DepositRecord.
+pub async fn vote_to_veto_payout_phase(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    nft_holder: &Keypair,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+    payout_phase_index: u8,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[vote_to_veto_payout_phase_ix(
+            VoteToVetoPayoutPhaseAccounts {
+                nft_holder: nft_holder.pubkey(),
+                depositor: *depositor,
+                mint: *nft_mint,
+            },
+            &setup.campaign_uuid,
+            payout_phase_index,
+        )],
+        &[nft_holder],
+    )
+    .await
+}
+
+pub async fn migrate_deposit_records(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposits: &[(Pubkey, Pubkey)],
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[migrate_deposit_records_ix(
+            MigrateDepositRecordsAccounts {
+                authority: setup.authority.pubkey(),
+            },
+            &setup.campaign_uuid,
+            None,
+            deposits,
+        )],
+        &[&setup.authority],
+    )
+    .await
+}
+
+pub async fn vote_to_veto_payout_phase_with_donation(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    donor: &Keypair,
+    donation_uuid: &str,
+    payout_phase_index: u8,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[vote_to_veto_payout_phase_with_donation_ix(
+            VoteToVetoPayoutPhaseWithDonationAccounts {
+                donor: donor.pubkey(),
+            },
+            &setup.campaign_uuid,
+            donation_uuid,
+            payout_phase_index,
+        )],
+        &[donor],
+    )
+    .await
+}
+
+pub async fn donate(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    donor: &Keypair,
+    donation_uuid: &str,
+    donation_amount: u64,
+    refundable: bool,
+    has_vote_weight: bool,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[donate_ix(
+            DonateAccounts {
+                donor: donor.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            donation_uuid,
+            donation_amount,
+            refundable,
+            has_vote_weight,
+        )],
+        &[donor],
+    )
+    .await
+}
+
+pub async fn process_donation_refund(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    donor: &Keypair,
+    donation_uuid: &str,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[process_donation_refund_ix(
+            ProcessDonationRefundAccounts {
+                donor: donor.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            donation_uuid,
+        )],
+        &[donor],
+    )
+    .await
+}
+
+pub async fn fund_matching_pool(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    sponsor: &Keypair,
+    match_basis_points: u16,
+    match_cap: u64,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[fund_matching_pool_ix(
+            FundMatchingPoolAccounts {
+                sponsor: sponsor.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+            match_basis_points,
+            match_cap,
+        )],
+        &[sponsor],
+    )
+    .await
+}
+
+pub async fn close_matching_pool(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    sponsor: &Keypair,
+) -> Result<(), BanksClientError> {
+    ctx.process_instructions(
+        &[close_matching_pool_ix(
+            CloseMatchingPoolAccounts {
+                sponsor: sponsor.pubkey(),
+                treasury_mint: setup.treasury_mint,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[sponsor],
+    )
+    .await
+}
+
+pub async fn claim_streamed_payout(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+) -> Result<(), BanksClientError> {
+    let payer = ctx.payer();
+    ctx.process_instructions(
+        &[claim_streamed_payout_ix(
+            ClaimStreamedPayoutAccounts {
+                payer: payer.pubkey(),
+                payout_wallet: setup.payout_wallet,
+            },
+            &setup.campaign_uuid,
+        )],
+        &[],
+    )
+    .await
+}
+
+pub fn get_create_deposit_ixs(
+    setup: &CampaignSetup,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+    deposit_escrow_mint: &Pubkey,
+    deposit_amount: u64,
+) -> Vec<Instruction> {
+    get_create_deposit_ixs_with_price_oracle(
+        setup,
+        depositor,
+        nft_mint,
+        deposit_escrow_mint,
+        deposit_amount,
+        None,
+    )
+}
+
+pub fn get_create_deposit_ixs_with_price_oracle(
+    setup: &CampaignSetup,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+    deposit_escrow_mint: &Pubkey,
+    deposit_amount: u64,
+    price_oracle: Option<Pubkey>,
+) -> Vec<Instruction> {
+    get_create_deposit_ixs_with_options(
+        setup,
+        depositor,
+        nft_mint,
+        deposit_escrow_mint,
+        deposit_amount,
+        price_oracle,
+        None,
+        false,
+    )
+}
+
+pub fn get_create_deposit_ixs_with_options(
+    setup: &CampaignSetup,
+    depositor: &Pubkey,
+    nft_mint: &Pubkey,
+    deposit_escrow_mint: &Pubkey,
+    deposit_amount: u64,
+    price_oracle: Option<Pubkey>,
+    reward_tier_index: Option<u8>,
+    has_matching_pool: bool,
+) -> Vec<Instruction> {
+    vec![
+        auction_house_noop_ix(),
+        create_deposit_ix(
+            CreateDepositAccounts {
+                depositor: *depositor,
+                mint: *nft_mint,
+                deposit_escrow_mint: *deposit_escrow_mint,
+                treasury_mint: setup.treasury_mint,
+                price_oracle,
+                has_matching_pool,
+            },
+            &setup.campaign_uuid,
+            deposit_amount,
+            reward_tier_index,
+        ),
+    ]
+}
+
+// Creates a depositor and an NFT mint and deposits the given amount in the
+// native treasury currency, claiming the given reward tier. Returns the
+// depositor and NFT mint.
+pub async fn create_native_deposit_for_reward_tier(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_amount: u64,
+    reward_tier_index: Option<u8>,
+) -> (Keypair, Pubkey, Result<(), BanksClientError>) {
+    create_native_deposit_with_options(ctx, setup, deposit_amount, reward_tier_index, false).await
+}
+
+// Like create_native_deposit_for_reward_tier, for a campaign which has a
+// MatchingPool.
+pub async fn create_matched_native_deposit(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_amount: u64,
+) -> (Keypair, Pubkey, Result<(), BanksClientError>) {
+    create_native_deposit_with_options(ctx, setup, deposit_amount, None, true).await
+}
+
+async fn create_native_deposit_with_options(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_amount: u64,
+    reward_tier_index: Option<u8>,
+    has_matching_pool: bool,
+) -> (Keypair, Pubkey, Result<(), BanksClientError>) {
+    let depositor = ctx
+        .create_funded_keypair(LAMPORTS_PER_SOL + deposit_amount)
+        .await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+    let result = ctx
+        .process_instructions(
+            &get_create_deposit_ixs_with_options(
+                setup,
+                &depositor.pubkey(),
+                &nft_mint,
+                &native_mint::id(),
+                deposit_amount,
+                None,
+                reward_tier_index,
+                has_matching_pool,
+            ),
+            &[&depositor],
+        )
+        .await;
+
+    (depositor, nft_mint, result)
+}
+
+// Creates a depositor and an NFT mint and deposits the given amount in the
+// deposit_escrow_mint currency. Returns the depositor and NFT mint.
+pub async fn create_deposit(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: &Pubkey,
+    deposit_amount: u64,
+) -> (Keypair, Pubkey) {
+    create_deposit_with_price_oracle(ctx, setup, deposit_escrow_mint, deposit_amount, None).await
+}
+
+// Like create_deposit, for a deposit_escrow_mint with a price oracle.
+pub async fn create_deposit_with_price_oracle(
+    ctx: &mut TestContext,
+    setup: &CampaignSetup,
+    deposit_escrow_mint: &Pubkey,
+    deposit_amount: u64,
+    price_oracle: Option<Pubkey>,
+) -> (Keypair, Pubkey) {
+    let depositor = ctx.create_funded_keypair(LAMPORTS_PER_SOL).await;
+    let nft_mint = ctx.create_nft_mint(&setup.collection_mint, true).await;
+
+    if native_mint::check_id(deposit_escrow_mint) {
+        let payer = ctx.payer();
+        ctx.process_instructions(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &depositor.pubkey(),
+                deposit_amount,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    } else {
+        ctx.mint_tokens_to_ata(&depositor.pubkey(), deposit_escrow_mint, deposit_amount)
+            .await;
+    }
+
+    ctx.process_instructions(
+        &get_create_deposit_ixs_with_price_oracle(
+            setup,
+            &depositor.pubkey(),
+            &nft_mint,
+            deposit_escrow_mint,
+            deposit_amount,
+            price_oracle,
+        ),
+        &[&depositor],
+    )
+    .await
+    .unwrap();
+
+    (depositor, nft_mint)
+}
+
+pub fn get_legacy_campaign_escrow(
+    campaign_uuid: &str,
+    authority: &Pubkey,
+    campaign_end_time: i64,
+) -> LegacyCampaignEscrow {
+    let (_, bump) = find_campaign_escrow_pda(campaign_uuid);
+    let (treasury_escrow, treasury_bump) = find_treasury_escrow_pda(campaign_uuid);
+    let payout_phases = get_default_payout_phases(campaign_end_time);
+
+    let get_shared_fields = |index: usize| LegacySharedPayoutPhaseFields {
+        index: index as u8,
+        payout_basis_points: payout_phases[index].payout_basis_points,
+        payout_time: payout_phases[index].payout_time,
+        refund_deadline: payout_phases[index].refund_deadline,
+        description: payout_phases[index].description.clone(),
+    };
+    let voting_config = payout_phases[1].voting_config.as_ref().unwrap();
+
+    LegacyCampaignEscrow {
+        bump,
+        campaign_uuid: campaign_uuid.to_string(),
+        authority: *authority,
+        creator: Pubkey::new_unique(),
+        payout_wallet: Pubkey::new_unique(),
+        treasury: LegacyTreasury {
+            bump: treasury_bump,
+            treasury_mint: native_mint::id(),
+            treasury_escrow,
+            total_funds: 0,
+        },
+        deposit_escrow_infos: vec![],
+        campaign_end_time,
+        deposit_count: 0,
+        processed_deposit_count: 0,
+        closed_deposit_record_count: 0,
+        payouts_ready: false,
+        payout_phases: LegacyPayoutPhases {
+            non_voting_payout_phases: vec![LegacyNonVotingPayoutPhase {
+                shared_fields: get_shared_fields(0),
+                is_paid_out: false,
+                is_vetoed_by_authority: false,
+            }],
+            voting_payout_phases: vec![LegacyVotingPayoutPhase {
+                shared_fields: get_shared_fields(1),
+                is_paid_out: false,
+                is_vetoed_by_authority: false,
+                voting_start_time: voting_config.voting_start_time,
+                veto_votes: 0,
+                vote_basis_points_veto_threshold: voting_config.vote_basis_points_veto_threshold,
+            }],
+        },
+    }
+}
+
+// Writes a CampaignEscrow account in the legacy layout, as it would exist
+// on-chain before the migration.
+pub fn set_legacy_campaign_escrow_account(
+    ctx: &mut TestContext,
+    campaign_uuid: &str,
+    legacy_campaign_escrow: &LegacyCampaignEscrow,
+) {
+    let mut data = CampaignEscrow::discriminator().to_vec();
+    legacy_campaign_escrow.serialize(&mut data).unwrap();
+    let (campaign_escrow, _) = find_campaign_escrow_pda(campaign_uuid);
+
+    ctx.context.set_account(
+        &campaign_escrow,
+        &Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into(),
+    );
+}
@@ -2,18 +2,22 @@ import CampaignTreasuryManagerSdk from "sdk/CampaignTreasuryManagerSdk";
 import { CAMPAIGN_TREASURY_MANAGER_IDL } from "sdk/idl";
 import CampaignTreasuryManagerInstructionName from "sdk/types/CampaignTreasuryManagerInstructionName";
 import DecodedCampaignTreasuryManagerTransactionResult from "sdk/types/DecodedCampaignTreasuryManagerTransactionResult";
-import PayoutPhaseUnion from "sdk/types/PayoutPhaseUnion";
+import PayoutPhase from "sdk/types/PayoutPhase";
+import SurplusPolicy from "sdk/types/SurplusPolicy";
 import decodeCampaignTreasuryManagerTransaction from "sdk/utils/decodeCampaignTreasuryManagerTransaction";
 import getCurrentActivePayoutPhase from "sdk/utils/getCurrentActivePayoutPhase";
 import getErrorMessageFromCampaignTreasuryManagerIdl from "sdk/utils/getErrorMessageFromCampaignTreasuryManagerIdl";
 import getPayoutPhasesOrderedArray from "sdk/utils/getPayoutPhasesOrderedArray";
 import getProgramIdsFromEnvironment from "sdk/utils/getProgramIdsFromEnvironment";
+import getRemainderReceiver from "sdk/utils/getRemainderReceiver";
+import getSurplusReceiver from "sdk/utils/getSurplusReceiver";
 
 export {
   CAMPAIGN_TREASURY_MANAGER_IDL,
   CampaignTreasuryManagerInstructionName,
   DecodedCampaignTreasuryManagerTransactionResult,
-  PayoutPhaseUnion,
+  PayoutPhase,
+  SurplusPolicy,
 };
 
 export {
@@ -22,6 +26,8 @@ export {
   getErrorMessageFromCampaignTreasuryManagerIdl,
   getPayoutPhasesOrderedArray,
   getProgramIdsFromEnvironment,
+  getRemainderReceiver,
+  getSurplusReceiver,
 };
 
 export default CampaignTreasuryManagerSdk;
@@ -11,21 +11,36 @@ import {
   CampaignTreasuryManagerProgram,
 } from "sdk/idl";
 import { CampaignTreasuryManager } from "sdk/idl/CampaignTreasuryManager";
+import claimStreamedPayoutIx from "sdk/instructions/claimStreamedPayoutIx";
 import closeDepositEscrowIx from "sdk/instructions/closeDepositEscrowIx";
 import closeDepositRecordIx from "sdk/instructions/closeDepositRecordIx";
+import closeDepositRecordsIx from "sdk/instructions/closeDepositRecordsIx";
 import closeEscrowIx from "sdk/instructions/closeEscrowIx";
+import closeMatchingPoolIx from "sdk/instructions/closeMatchingPoolIx";
 import createDepositEscrowIx from "sdk/instructions/createDepositEscrowIx";
 import createDepositIx from "sdk/instructions/createDepositIx";
 import createEscrowIx from "sdk/instructions/createEscrowIx";
+import disableDepositEscrowIx from "sdk/instructions/disableDepositEscrowIx";
+import donateIx from "sdk/instructions/donateIx";
+import fundMatchingPoolIx from "sdk/instructions/fundMatchingPoolIx";
+import migrateCampaignEscrowIx from "sdk/instructions/migrateCampaignEscrowIx";
+import migrateDepositRecordsIx from "sdk/instructions/migrateDepositRecordsIx";
+import payOutAllDueFundsIx from "sdk/instructions/payOutAllDueFundsIx";
 import payOutFundsIx from "sdk/instructions/payOutFundsIx";
 import processDepositIx from "sdk/instructions/processDepositIx";
+import processDonationRefundIx from "sdk/instructions/processDonationRefundIx";
 import processFullRefundIx from "sdk/instructions/processFullRefundIx";
 import processPartialRefundIx from "sdk/instructions/processPartialRefundIx";
+import reconcileTreasuryIx from "sdk/instructions/reconcileTreasuryIx";
+import setDepositEscrowPriceOracleIx from "sdk/instructions/setDepositEscrowPriceOracleIx";
 import updateEscrowIx from "sdk/instructions/updateEscrowIx";
 import vetoPayoutPhaseIx from "sdk/instructions/vetoPayoutPhaseIx";
+import voteToVetoPayoutPhaseIx from "sdk/instructions/voteToVetoPayoutPhaseIx";
+import voteToVetoPayoutPhaseWithDonationIx from "sdk/instructions/voteToVetoPayoutPhaseWithDonationIx";
 import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
 import CampaignEscrowAccount from "sdk/types/CampaignEscrowAccount";
 import CreateCampaignEscrowInput from "sdk/types/CreateCampaignEscrowInput";
+import DepositRecordToClose from "sdk/types/DepositRecordToClose";
 import UpdateCampaignEscrowInput from "sdk/types/UpdateCampaignEscrowInput";
 import getProgramIdsFromEnvironment from "sdk/utils/getProgramIdsFromEnvironment";
 
@@ -180,6 +195,59 @@ export default class CampaignTreasuryManagerSdk {
     return ixToTx(ix);
   }
 
+  async migrateCampaignEscrowTx(
+    {
+      authority,
+      payer,
+    }: {
+      authority: PublicKey;
+      payer: PublicKey;
+    },
+    {
+      campaignUuid,
+    }: {
+      campaignUuid: string;
+    }
+  ): Promise<Transaction> {
+    const ix = await migrateCampaignEscrowIx(
+      {
+        authority,
+        payer,
+      },
+      {
+        campaignUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async migrateDepositRecordsTx(
+    { authority }: { authority: PublicKey },
+    {
+      campaignUuid,
+      collectionMint,
+      depositRecords,
+    }: {
+      campaignUuid: string;
+      collectionMint?: PublicKey;
+      depositRecords: Array<DepositRecordToClose>;
+    }
+  ): Promise<Transaction> {
+    const ix = await migrateDepositRecordsIx(
+      {
+        authority,
+      },
+      {
+        campaignUuid,
+        collectionMint,
+        depositRecords,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
   async createDepositEscrowTx(
     {
       depositEscrowMint,
@@ -204,6 +272,57 @@ export default class CampaignTreasuryManagerSdk {
     return ixToTx(ix);
   }
 
+  async disableDepositEscrowTx(
+    {
+      depositEscrowMint,
+    }: {
+      depositEscrowMint: PublicKey;
+    },
+    {
+      campaignUuid,
+    }: {
+      campaignUuid: string;
+    }
+  ): Promise<Transaction> {
+    const ix = await disableDepositEscrowIx(
+      {
+        depositEscrowMint,
+      },
+      {
+        campaignUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async setDepositEscrowPriceOracleTx(
+    {
+      depositEscrowMint,
+      priceOracle,
+    }: {
+      depositEscrowMint: PublicKey;
+      priceOracle: PublicKey;
+    },
+    {
+      campaignUuid,
+    }: {
+      campaignUuid: string;
+    }
+  ): Promise<Transaction> {
+    const ix = await setDepositEscrowPriceOracleIx(
+      {
+        depositEscrowMint,
+        priceOracle,
+      },
+      {
+        campaignUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
   async closeDepositEscrowTx(
     {
       authority,
@@ -297,6 +416,65 @@ export default class CampaignTreasuryManagerSdk {
     return ixToTx(ix);
   }
 
+  async voteToVetoPayoutPhaseTx(
+    {
+      depositor,
+      mint,
+      nftHolder,
+    }: {
+      depositor: PublicKey;
+      mint: PublicKey;
+      nftHolder: PublicKey;
+    },
+    {
+      campaignUuid,
+      payoutPhaseIndex,
+    }: {
+      campaignUuid: string;
+      payoutPhaseIndex: number;
+    }
+  ): Promise<Transaction> {
+    const ix = await voteToVetoPayoutPhaseIx(
+      {
+        depositor,
+        mint,
+        nftHolder,
+      },
+      {
+        campaignUuid,
+        payoutPhaseIndex,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async voteToVetoPayoutPhaseWithDonationTx(
+    { donor }: { donor: PublicKey },
+    {
+      campaignUuid,
+      donationUuid,
+      payoutPhaseIndex,
+    }: {
+      campaignUuid: string;
+      donationUuid: string;
+      payoutPhaseIndex: number;
+    }
+  ): Promise<Transaction> {
+    const ix = await voteToVetoPayoutPhaseWithDonationIx(
+      {
+        donor,
+      },
+      {
+        campaignUuid,
+        donationUuid,
+        payoutPhaseIndex,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
   async createDepositTx(
     {
       depositor,
@@ -306,9 +484,11 @@ export default class CampaignTreasuryManagerSdk {
     {
       campaignUuid,
       depositAmount,
+      rewardTierIndex,
     }: {
       campaignUuid: string;
       depositAmount: BN;
+      rewardTierIndex?: number;
     }
   ): Promise<Transaction> {
     const ix = await createDepositIx(
@@ -321,25 +501,113 @@ export default class CampaignTreasuryManagerSdk {
         campaignUuid,
         depositAmount,
         program: this.program,
+        rewardTierIndex,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async donateTx(
+    { donor }: { donor: PublicKey },
+    {
+      campaignUuid,
+      donationAmount,
+      donationUuid,
+      hasVoteWeight,
+      refundable,
+    }: {
+      campaignUuid: string;
+      donationAmount: BN;
+      donationUuid: string;
+      hasVoteWeight: boolean;
+      refundable: boolean;
+    }
+  ): Promise<Transaction> {
+    const ix = await donateIx(
+      {
+        donor,
+      },
+      {
+        campaignUuid,
+        donationAmount,
+        donationUuid,
+        hasVoteWeight,
+        program: this.program,
+        refundable,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async fundMatchingPoolTx(
+    { sponsor }: { sponsor: PublicKey },
+    {
+      campaignUuid,
+      matchBasisPoints,
+      matchCap,
+    }: {
+      campaignUuid: string;
+      matchBasisPoints: number;
+      matchCap: BN;
+    }
+  ): Promise<Transaction> {
+    const ix = await fundMatchingPoolIx(
+      {
+        sponsor,
+      },
+      {
+        campaignUuid,
+        matchBasisPoints,
+        matchCap,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async closeMatchingPoolTx(
+    { sponsor }: { sponsor: PublicKey },
+    { campaignUuid }: { campaignUuid: string }
+  ): Promise<Transaction> {
+    const ix = await closeMatchingPoolIx(
+      {
+        sponsor,
+      },
+      {
+        campaignUuid,
+        program: this.program,
       }
     );
     return ixToTx(ix);
   }
 
   async processDepositTx(
-    { payer }: { payer: PublicKey },
+    {
+      authority,
+      depositor,
+      mint,
+    }: {
+      authority: PublicKey;
+      depositor: PublicKey;
+      mint: PublicKey;
+    },
     {
       campaignUuid,
+      processedDepositAmount,
     }: {
       campaignUuid: string;
+      processedDepositAmount: BN;
     }
   ): Promise<Transaction> {
     const ix = await processDepositIx(
       {
-        payer,
+        authority,
+        depositor,
+        mint,
       },
       {
         campaignUuid,
+        processedDepositAmount,
         program: this.program,
       }
     );
@@ -347,7 +615,17 @@ export default class CampaignTreasuryManagerSdk {
   }
 
   async processPartialRefundTx(
-    { payer }: { payer: PublicKey },
+    {
+      depositor,
+      mint,
+      nftHolder,
+      payer,
+    }: {
+      depositor: PublicKey;
+      mint: PublicKey;
+      nftHolder: PublicKey;
+      payer: PublicKey;
+    },
     {
       campaignUuid,
     }: {
@@ -356,6 +634,9 @@ export default class CampaignTreasuryManagerSdk {
   ): Promise<Transaction> {
     const ix = await processPartialRefundIx(
       {
+        depositor,
+        mint,
+        nftHolder,
         payer,
       },
       {
@@ -367,7 +648,17 @@ export default class CampaignTreasuryManagerSdk {
   }
 
   async processRefundTx(
-    { payer }: { payer: PublicKey },
+    {
+      depositor,
+      mint,
+      nftHolder,
+      payer,
+    }: {
+      depositor: PublicKey;
+      mint: PublicKey;
+      nftHolder: PublicKey;
+      payer: PublicKey;
+    },
     {
       campaignUuid,
     }: {
@@ -376,6 +667,9 @@ export default class CampaignTreasuryManagerSdk {
   ): Promise<Transaction> {
     const ix = await processFullRefundIx(
       {
+        depositor,
+        mint,
+        nftHolder,
         payer,
       },
       {
@@ -386,8 +680,34 @@ export default class CampaignTreasuryManagerSdk {
     return ixToTx(ix);
   }
 
+  async processDonationRefundTx(
+    { donor }: { donor: PublicKey },
+    {
+      campaignUuid,
+      donationUuid,
+    }: {
+      campaignUuid: string;
+      donationUuid: string;
+    }
+  ): Promise<Transaction> {
+    const ix = await processDonationRefundIx(
+      {
+        donor,
+      },
+      {
+        campaignUuid,
+        donationUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
   async payOutFundsTx(
-    { payer }: { payer: PublicKey },
+    {
+      crankRewardReceiver,
+      payer,
+    }: { crankRewardReceiver?: PublicKey; payer: PublicKey },
     {
       campaignUuid,
     }: {
@@ -396,6 +716,67 @@ export default class CampaignTreasuryManagerSdk {
   ): Promise<Transaction> {
     const ix = await payOutFundsIx(
       {
+        crankRewardReceiver,
+        payer,
+      },
+      {
+        campaignUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async claimStreamedPayoutTx(
+    { payer }: { payer: PublicKey },
+    { campaignUuid }: { campaignUuid: string }
+  ): Promise<Transaction> {
+    const ix = await claimStreamedPayoutIx(
+      {
+        payer,
+      },
+      {
+        campaignUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async reconcileTreasuryTx(
+    { payer }: { payer: PublicKey },
+    {
+      campaignUuid,
+    }: {
+      campaignUuid: string;
+    }
+  ): Promise<Transaction> {
+    const ix = await reconcileTreasuryIx(
+      {
+        payer,
+      },
+      {
+        campaignUuid,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
+
+  async payOutAllDueFundsTx(
+    {
+      crankRewardReceiver,
+      payer,
+    }: { crankRewardReceiver?: PublicKey; payer: PublicKey },
+    {
+      campaignUuid,
+    }: {
+      campaignUuid: string;
+    }
+  ): Promise<Transaction> {
+    const ix = await payOutAllDueFundsIx(
+      {
+        crankRewardReceiver,
         payer,
       },
       {
@@ -425,4 +806,27 @@ export default class CampaignTreasuryManagerSdk {
     );
     return ixToTx(ix);
   }
+
+  async closeDepositRecordsTx(
+    { payer }: { payer: PublicKey },
+    {
+      campaignUuid,
+      depositRecords,
+    }: {
+      campaignUuid: string;
+      depositRecords: Array<DepositRecordToClose>;
+    }
+  ): Promise<Transaction> {
+    const ix = await closeDepositRecordsIx(
+      {
+        payer,
+      },
+      {
+        campaignUuid,
+        depositRecords,
+        program: this.program,
+      }
+    );
+    return ixToTx(ix);
+  }
 }
@@ -3,3 +3,7 @@ export const PROGRAM_PREFIX = "campaign_treasury_manager";
 export const TREASURY_PREFIX = "treasury_escrow";
 export const DEPOSIT_RECORD_PREFIX = "deposit_record";
 export const DEPOSIT_RECORD_ESCROW_PREFIX = "deposit_escrow";
+export const VOTE_RECORD_PREFIX = "vote_record";
+export const DONATION_RECORD_PREFIX = "donation_record";
+export const MATCHING_POOL_PREFIX = "matching_pool";
+export const MATCHING_POOL_ESCROW_PREFIX = "matching_pool_escrow";
@@ -4,6 +4,7 @@ export type CampaignTreasuryManager = {
       name: "campaignEscrow";
       type: {
         fields: [
+          { name: "version"; type: "u8" },
           { name: "bump"; type: "u8" },
           { name: "campaignUuid"; type: "string" },
           { name: "authority"; type: "publicKey" },
@@ -19,6 +20,18 @@ export type CampaignTreasuryManager = {
           { name: "processedDepositCount"; type: "u64" },
           { name: "closedDepositRecordCount"; type: "u64" },
           { name: "payoutsReady"; type: "bool" },
+          { name: "surplusPolicy"; type: { defined: "SurplusPolicy" } },
+          { name: "crankReward"; type: { defined: "CrankReward" } },
+          { name: "collectionMint"; type: "publicKey" },
+          { name: "depositEscrowInfosCapacity"; type: "u8" },
+          { name: "fundraisingStartTime"; type: { option: "i64" } },
+          { name: "depositGracePeriod"; type: "i64" },
+          { name: "rewardTiers"; type: { defined: "RewardTiers" } },
+          { name: "hardCap"; type: { option: "u64" } },
+          { name: "autoEndAtHardCap"; type: "bool" },
+          { name: "hardCapReachedAt"; type: { option: "i64" } },
+          { name: "settledRaise"; type: { option: "u64" } },
+          { name: "hasMatchingPool"; type: "bool" },
           { name: "payoutPhases"; type: { defined: "PayoutPhases" } }
         ];
         kind: "struct";
@@ -35,12 +48,60 @@ export type CampaignTreasuryManager = {
           { name: "depositEscrowMint"; type: "publicKey" },
           { name: "initialDepositAmount"; type: "u64" },
           { name: "processedDepositAmount"; type: "u64" },
-          { name: "depositProcessed"; type: "bool" }
+          { name: "depositProcessed"; type: "bool" },
+          { name: "campaignEscrow"; type: "publicKey" },
+          { name: "estimatedDepositAmount"; type: "u64" },
+          { name: "rewardTierIndex"; type: { option: "u8" } }
         ];
         kind: "struct";
       };
     },
-    { name: "treasuryEscrow"; type: { fields: []; kind: "struct" } }
+    {
+      name: "donationRecord";
+      type: {
+        fields: [
+          { name: "bump"; type: "u8" },
+          { name: "campaignEscrow"; type: "publicKey" },
+          { name: "donationUuid"; type: "string" },
+          { name: "donor"; type: "publicKey" },
+          { name: "donationAmount"; type: "u64" },
+          { name: "refundable"; type: "bool" },
+          { name: "hasVoteWeight"; type: "bool" },
+          { name: "donationTime"; type: "i64" }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "matchingPool";
+      type: {
+        fields: [
+          { name: "bump"; type: "u8" },
+          { name: "escrowBump"; type: "u8" },
+          { name: "campaignEscrow"; type: "publicKey" },
+          { name: "sponsor"; type: "publicKey" },
+          { name: "matchBasisPoints"; type: "u16" },
+          { name: "matchCap"; type: "u64" },
+          { name: "totalMatched"; type: "u64" }
+        ];
+        kind: "struct";
+      };
+    },
+    { name: "matchingPoolEscrow"; type: { fields: []; kind: "struct" } },
+    { name: "treasuryEscrow"; type: { fields: []; kind: "struct" } },
+    {
+      name: "voteRecord";
+      type: {
+        fields: [
+          { name: "bump"; type: "u8" },
+          { name: "depositRecord"; type: "publicKey" },
+          { name: "payoutPhaseIndex"; type: "u8" },
+          { name: "voter"; type: "publicKey" },
+          { name: "vetoVotes"; type: "u64" }
+        ];
+        kind: "struct";
+      };
+    }
   ];
   errors: [
     { code: 9000; msg: "PublicKey check failed"; name: "PublicKeyMismatch" },
@@ -91,41 +152,179 @@ export type CampaignTreasuryManager = {
     },
     {
       code: 9010;
-      msg: "UpdateEscrow input must include both the treasury_escrow_owner and treasury_mint if updating the escrow treasury";
-      name: "InvalidTreasuryEscrowUpdate";
-    },
-    {
-      code: 9011;
       msg: "Invalid campaign_end_time provided";
       name: "InvalidCampaignEndTime";
     },
     {
-      code: 9012;
-      msg: "Deposit escrow mint cannot be the native mint";
-      name: "InvalidDepositEscrowMint";
-    },
-    {
-      code: 9013;
+      code: 9011;
       msg: "Invalid deposit escrow account provided";
       name: "InvalidDepositEscrowAccount";
     },
-    { code: 9014; msg: "Invalid CPI invocation"; name: "InvalidCpiInvocation" },
+    { code: 9012; msg: "Invalid CPI invocation"; name: "InvalidCpiInvocation" },
     {
-      code: 9015;
+      code: 9013;
       msg: "Invalid CreateDepositEscrow instruction";
       name: "InvalidCreateDepositEscrowIx";
     },
-    { code: 9016; msg: "Invalid payout request"; name: "InvalidPayoutRequest" },
+    { code: 9014; msg: "Invalid payout request"; name: "InvalidPayoutRequest" },
     {
-      code: 9017;
+      code: 9015;
       msg: "Deposit escrow account must be empty before it can be closed";
       name: "DepositEscrowAccountNotEmpty";
+    },
+    {
+      code: 9016;
+      msg: "Too many payout phases provided";
+      name: "TooManyPayoutPhases";
+    },
+    {
+      code: 9017;
+      msg: "Treasury paid out and refunded totals exceed the total funds";
+      name: "InvalidTreasuryAccounting";
+    },
+    {
+      code: 9018;
+      msg: "Surplus receiver does not match the CampaignEscrow surplus policy";
+      name: "InvalidSurplusReceiver";
+    },
+    {
+      code: 9019;
+      msg: "Invalid DepositRecord account provided";
+      name: "InvalidDepositRecord";
+    },
+    {
+      code: 9020;
+      msg: "Invalid crank reward provided";
+      name: "InvalidCrankReward";
+    },
+    {
+      code: 9021;
+      msg: "NFT is not a verified member of the campaign collection";
+      name: "InvalidNftCollection";
+    },
+    {
+      code: 9022;
+      msg: "Signer does not hold the DepositRecord NFT";
+      name: "InvalidNftHolder";
+    },
+    { code: 9023; msg: "Invalid vote"; name: "InvalidVote" },
+    {
+      code: 9024;
+      msg: "Too many DepositEscrow currencies for the CampaignEscrow";
+      name: "TooManyDepositEscrows";
+    },
+    {
+      code: 9025;
+      msg: "Deposits in this currency have been disabled";
+      name: "DepositEscrowDisabled";
+    },
+    {
+      code: 9026;
+      msg: "Invalid price oracle account provided";
+      name: "InvalidPriceOracle";
+    },
+    { code: 9027; msg: "Invalid donation"; name: "InvalidDonation" },
+    {
+      code: 9028;
+      msg: "Invalid fundraising window settings";
+      name: "InvalidFundraisingWindow";
+    },
+    {
+      code: 9029;
+      msg: "Deposits and donations are only accepted during the fundraising window";
+      name: "OutsideFundraisingWindow";
+    },
+    { code: 9030; msg: "Invalid reward tiers"; name: "InvalidRewardTiers" },
+    {
+      code: 9031;
+      msg: "Deposit does not qualify for the claimed reward tier";
+      name: "InvalidRewardTier";
+    },
+    { code: 9032; msg: "Reward tier is sold out"; name: "RewardTierSoldOut" },
+    { code: 9033; msg: "Invalid hard cap"; name: "InvalidHardCap" },
+    { code: 9034; msg: "Campaign hard cap exceeded"; name: "HardCapExceeded" },
+    { code: 9035; msg: "Invalid matching pool"; name: "InvalidMatchingPool" },
+    {
+      code: 9036;
+      msg: "Cannot close the matching pool yet";
+      name: "InvalidCloseMatchingPool";
+    },
+    {
+      code: 9037;
+      msg: "Invalid streamed payout claim";
+      name: "InvalidStreamedPayoutClaim";
+    },
+    { code: 9038; msg: "Invalid refund request"; name: "InvalidRefundRequest" },
+    {
+      code: 9039;
+      msg: "Cannot process the deposit";
+      name: "InvalidProcessDeposit";
+    }
+  ];
+  events: [
+    {
+      fields: [
+        { index: false; name: "campaignEscrow"; type: "publicKey" },
+        { index: false; name: "payoutPhaseIndex"; type: "u8" },
+        { index: false; name: "paidAmount"; type: "u64" },
+        { index: false; name: "crankRewardAmount"; type: "u64" }
+      ];
+      name: "PayoutPhasePaidOut";
+    },
+    {
+      fields: [
+        { index: false; name: "campaignEscrow"; type: "publicKey" },
+        { index: false; name: "payoutPhaseIndex"; type: "u8" },
+        { index: false; name: "claimedAmount"; type: "u64" },
+        { index: false; name: "paidAmount"; type: "u64" }
+      ];
+      name: "StreamedPayoutClaimed";
+    },
+    {
+      fields: [
+        { index: false; name: "campaignEscrow"; type: "publicKey" },
+        { index: false; name: "depositRecord"; type: "publicKey" },
+        { index: false; name: "depositor"; type: "publicKey" },
+        { index: false; name: "processedDepositAmount"; type: "u64" }
+      ];
+      name: "DepositProcessed";
+    },
+    {
+      fields: [
+        { index: false; name: "campaignEscrow"; type: "publicKey" },
+        { index: false; name: "record"; type: "publicKey" },
+        { index: false; name: "refundReceiver"; type: "publicKey" },
+        { index: false; name: "refundAmount"; type: "u64" }
+      ];
+      name: "RefundProcessed";
+    },
+    {
+      fields: [
+        { index: false; name: "campaignEscrow"; type: "publicKey" },
+        { index: false; name: "payoutPhaseIndex"; type: "u8" },
+        { index: false; name: "record"; type: "publicKey" },
+        { index: false; name: "voter"; type: "publicKey" },
+        { index: false; name: "vetoVotes"; type: "u64" }
+      ];
+      name: "VetoVotesCast";
     }
   ];
   instructions: [
     {
       accounts: [
-        { isMut: false; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: true; isSigner: false; name: "payoutWallet" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [];
+      name: "claimStreamedPayout";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
         { isMut: true; isSigner: false; name: "depositEscrow" },
         { isMut: false; isSigner: false; name: "depositEscrowMint" },
         { isMut: true; isSigner: false; name: "receiver" },
@@ -145,6 +344,17 @@ export type CampaignTreasuryManager = {
       args: [];
       name: "closeDepositRecord";
     },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [];
+      name: "closeDepositRecords";
+    },
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
@@ -154,6 +364,14 @@ export type CampaignTreasuryManager = {
           isSigner: false;
           name: "treasuryEscrow";
         },
+        {
+          docs: [
+            "This account is not used if the treasury holds no remaining funds."
+          ];
+          isMut: true;
+          isSigner: false;
+          name: "remainderReceiver";
+        },
         { isMut: true; isSigner: false; name: "receiver" },
         { isMut: true; isSigner: true; name: "payer" },
         { isMut: false; isSigner: true; name: "authority" },
@@ -164,6 +382,24 @@ export type CampaignTreasuryManager = {
       args: [];
       name: "closeEscrow";
     },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "matchingPool" },
+        {
+          docs: ["This account is closed manually in the ix handler."];
+          isMut: true;
+          isSigner: false;
+          name: "matchingPoolEscrow";
+        },
+        { isMut: true; isSigner: true; name: "sponsor" },
+        { isMut: true; isSigner: false; name: "sponsorPaymentAccount" },
+        { isMut: false; isSigner: false; name: "systemProgram" },
+        { isMut: false; isSigner: false; name: "tokenProgram" }
+      ];
+      args: [];
+      name: "closeMatchingPool";
+    },
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
@@ -182,11 +418,20 @@ export type CampaignTreasuryManager = {
         { isMut: true; isSigner: true; name: "depositor" },
         { isMut: true; isSigner: false; name: "depositorPaymentAccount" },
         { isMut: false; isSigner: false; name: "mint" },
+        {
+          docs: ["collection_mint."];
+          isMut: false;
+          isSigner: false;
+          name: "metadata";
+        },
         { isMut: false; isSigner: false; name: "systemProgram" },
         { isMut: false; isSigner: false; name: "tokenProgram" },
         { isMut: false; isSigner: false; name: "instructionSysvarAccount" }
       ];
-      args: [{ name: "depositAmount"; type: "u64" }];
+      args: [
+        { name: "depositAmount"; type: "u64" },
+        { name: "rewardTierIndex"; type: { option: "u8" } }
+      ];
       name: "createDeposit";
     },
     {
@@ -235,6 +480,83 @@ export type CampaignTreasuryManager = {
       ];
       name: "createEscrow";
     },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: false; isSigner: false; name: "depositEscrowMint" },
+        { isMut: false; isSigner: true; name: "authority" }
+      ];
+      args: [];
+      name: "disableDepositEscrow";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "donationRecord" },
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: false; isSigner: false; name: "treasuryMint" },
+        { isMut: true; isSigner: true; name: "donor" },
+        { isMut: true; isSigner: false; name: "donorPaymentAccount" },
+        { isMut: false; isSigner: false; name: "systemProgram" },
+        { isMut: false; isSigner: false; name: "tokenProgram" }
+      ];
+      args: [
+        { name: "donationUuid"; type: "string" },
+        { name: "donationAmount"; type: "u64" },
+        { name: "refundable"; type: "bool" },
+        { name: "hasVoteWeight"; type: "bool" }
+      ];
+      name: "donate";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "matchingPool" },
+        {
+          docs: ["for token treasuries."];
+          isMut: true;
+          isSigner: false;
+          name: "matchingPoolEscrow";
+        },
+        { isMut: false; isSigner: false; name: "treasuryMint" },
+        { isMut: true; isSigner: true; name: "sponsor" },
+        { isMut: true; isSigner: false; name: "sponsorPaymentAccount" },
+        { isMut: false; isSigner: false; name: "systemProgram" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "rent" }
+      ];
+      args: [
+        { name: "matchBasisPoints"; type: "u16" },
+        { name: "matchCap"; type: "u64" }
+      ];
+      name: "fundMatchingPool";
+    },
+    {
+      accounts: [
+        {
+          docs: [
+            "Legacy accounts cannot be deserialized as a CampaignEscrow, so this",
+            "account is deserialized manually in the instruction handler."
+          ];
+          isMut: true;
+          isSigner: false;
+          name: "campaignEscrow";
+        },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: false; isSigner: true; name: "authority" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [{ name: "campaignUuid"; type: "string" }];
+      name: "migrateCampaignEscrow";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: false; isSigner: true; name: "authority" }
+      ];
+      args: [{ name: "collectionMint"; type: { option: "publicKey" } }];
+      name: "migrateDepositRecords";
+    },
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
@@ -245,22 +567,71 @@ export type CampaignTreasuryManager = {
         { isMut: false; isSigner: false; name: "systemProgram" }
       ];
       args: [];
-      name: "payOutFunds";
+      name: "payOutAllDueFunds";
     },
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
-        { isMut: false; isSigner: false; name: "treasuryEscrow" },
-        { isMut: true; isSigner: true; name: "payer" }
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: true; isSigner: false; name: "payoutWallet" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
       ];
       args: [];
+      name: "payOutFunds";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "depositRecord" },
+        { isMut: true; isSigner: false; name: "depositEscrow" },
+        { isMut: false; isSigner: false; name: "depositEscrowMint" },
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: false; isSigner: false; name: "treasuryMint" },
+        { isMut: true; isSigner: true; name: "authority" },
+        {
+          docs: [
+            "DepositEscrow, this is the authority for native deposit_escrow_mints",
+            "and the authority's ATA otherwise."
+          ];
+          isMut: true;
+          isSigner: false;
+          name: "authorityDepositEscrowAccount";
+        },
+        { isMut: true; isSigner: false; name: "authorityPaymentAccount" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [{ name: "processedDepositAmount"; type: "u64" }];
       name: "processDeposit";
     },
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
-        { isMut: false; isSigner: false; name: "treasuryEscrow" },
-        { isMut: true; isSigner: true; name: "payer" }
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: true; isSigner: false; name: "donationRecord" },
+        { isMut: true; isSigner: true; name: "donor" },
+        { isMut: true; isSigner: false; name: "refundReceiver" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [];
+      name: "processDonationRefund";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: true; isSigner: false; name: "depositRecord" },
+        { isMut: true; isSigner: false; name: "depositor" },
+        { isMut: false; isSigner: true; name: "nftHolder" },
+        { isMut: true; isSigner: false; name: "nftMint" },
+        { isMut: true; isSigner: false; name: "nftTokenAccount" },
+        { isMut: true; isSigner: false; name: "refundReceiver" },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
       ];
       args: [];
       name: "processFullRefund";
@@ -268,12 +639,48 @@ export type CampaignTreasuryManager = {
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
-        { isMut: false; isSigner: false; name: "treasuryEscrow" },
-        { isMut: true; isSigner: true; name: "payer" }
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        { isMut: true; isSigner: false; name: "depositRecord" },
+        { isMut: true; isSigner: false; name: "depositor" },
+        { isMut: false; isSigner: true; name: "nftHolder" },
+        { isMut: false; isSigner: false; name: "nftTokenAccount" },
+        { isMut: true; isSigner: false; name: "refundReceiver" },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
       ];
       args: [];
       name: "processPartialRefund";
     },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: true; isSigner: false; name: "treasuryEscrow" },
+        {
+          docs: [
+            "This account is not used if the surplus is held for supporters."
+          ];
+          isMut: true;
+          isSigner: false;
+          name: "surplusReceiver";
+        },
+        { isMut: true; isSigner: true; name: "payer" },
+        { isMut: false; isSigner: false; name: "tokenProgram" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [];
+      name: "reconcileTreasury";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: false; isSigner: false; name: "depositEscrowMint" },
+        { isMut: false; isSigner: false; name: "priceOracle" },
+        { isMut: false; isSigner: true; name: "authority" }
+      ];
+      args: [];
+      name: "setDepositEscrowPriceOracle";
+    },
     {
       accounts: [
         { isMut: true; isSigner: false; name: "campaignEscrow" },
@@ -307,9 +714,40 @@ export type CampaignTreasuryManager = {
       ];
       args: [{ name: "payoutPhaseIndex"; type: "u8" }];
       name: "vetoPayoutPhase";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: false; isSigner: false; name: "depositRecord" },
+        { isMut: true; isSigner: false; name: "voteRecord" },
+        { isMut: true; isSigner: true; name: "nftHolder" },
+        { isMut: false; isSigner: false; name: "nftTokenAccount" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [{ name: "payoutPhaseIndex"; type: "u8" }];
+      name: "voteToVetoPayoutPhase";
+    },
+    {
+      accounts: [
+        { isMut: true; isSigner: false; name: "campaignEscrow" },
+        { isMut: false; isSigner: false; name: "donationRecord" },
+        { isMut: true; isSigner: false; name: "voteRecord" },
+        { isMut: true; isSigner: true; name: "donor" },
+        { isMut: false; isSigner: false; name: "systemProgram" }
+      ];
+      args: [{ name: "payoutPhaseIndex"; type: "u8" }];
+      name: "voteToVetoPayoutPhaseWithDonation";
     }
   ];
   instructionsMap: {
+    claimStreamedPayout: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "payer",
+      "payoutWallet",
+      "tokenProgram",
+      "systemProgram"
+    ];
     closeDepositEscrow: [
       "campaignEscrow",
       "depositEscrow",
@@ -320,9 +758,17 @@ export type CampaignTreasuryManager = {
       "systemProgram"
     ];
     closeDepositRecord: ["campaignEscrow", "treasuryEscrow", "payer"];
+    closeDepositRecords: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "payer",
+      "tokenProgram",
+      "systemProgram"
+    ];
     closeEscrow: [
       "campaignEscrow",
       "treasuryEscrow",
+      "remainderReceiver",
       "receiver",
       "payer",
       "authority",
@@ -330,6 +776,15 @@ export type CampaignTreasuryManager = {
       "tokenProgram",
       "systemProgram"
     ];
+    closeMatchingPool: [
+      "campaignEscrow",
+      "matchingPool",
+      "matchingPoolEscrow",
+      "sponsor",
+      "sponsorPaymentAccount",
+      "systemProgram",
+      "tokenProgram"
+    ];
     createDeposit: [
       "campaignEscrow",
       "depositRecord",
@@ -338,6 +793,7 @@ export type CampaignTreasuryManager = {
       "depositor",
       "depositorPaymentAccount",
       "mint",
+      "metadata",
       "systemProgram",
       "tokenProgram",
       "instructionSysvarAccount"
@@ -365,6 +821,43 @@ export type CampaignTreasuryManager = {
       "systemProgram",
       "rent"
     ];
+    disableDepositEscrow: ["campaignEscrow", "depositEscrowMint", "authority"];
+    donate: [
+      "campaignEscrow",
+      "donationRecord",
+      "treasuryEscrow",
+      "treasuryMint",
+      "donor",
+      "donorPaymentAccount",
+      "systemProgram",
+      "tokenProgram"
+    ];
+    fundMatchingPool: [
+      "campaignEscrow",
+      "matchingPool",
+      "matchingPoolEscrow",
+      "treasuryMint",
+      "sponsor",
+      "sponsorPaymentAccount",
+      "systemProgram",
+      "tokenProgram",
+      "rent"
+    ];
+    migrateCampaignEscrow: [
+      "campaignEscrow",
+      "payer",
+      "authority",
+      "systemProgram"
+    ];
+    migrateDepositRecords: ["campaignEscrow", "authority"];
+    payOutAllDueFunds: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "payer",
+      "payoutWallet",
+      "tokenProgram",
+      "systemProgram"
+    ];
     payOutFunds: [
       "campaignEscrow",
       "treasuryEscrow",
@@ -373,9 +866,67 @@ export type CampaignTreasuryManager = {
       "tokenProgram",
       "systemProgram"
     ];
-    processDeposit: ["campaignEscrow", "treasuryEscrow", "payer"];
-    processFullRefund: ["campaignEscrow", "treasuryEscrow", "payer"];
-    processPartialRefund: ["campaignEscrow", "treasuryEscrow", "payer"];
+    processDeposit: [
+      "campaignEscrow",
+      "depositRecord",
+      "depositEscrow",
+      "depositEscrowMint",
+      "treasuryEscrow",
+      "treasuryMint",
+      "authority",
+      "authorityDepositEscrowAccount",
+      "authorityPaymentAccount",
+      "tokenProgram",
+      "systemProgram"
+    ];
+    processDonationRefund: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "donationRecord",
+      "donor",
+      "refundReceiver",
+      "tokenProgram",
+      "systemProgram"
+    ];
+    processFullRefund: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "depositRecord",
+      "depositor",
+      "nftHolder",
+      "nftMint",
+      "nftTokenAccount",
+      "refundReceiver",
+      "payer",
+      "tokenProgram",
+      "systemProgram"
+    ];
+    processPartialRefund: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "depositRecord",
+      "depositor",
+      "nftHolder",
+      "nftTokenAccount",
+      "refundReceiver",
+      "payer",
+      "tokenProgram",
+      "systemProgram"
+    ];
+    reconcileTreasury: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "surplusReceiver",
+      "payer",
+      "tokenProgram",
+      "systemProgram"
+    ];
+    setDepositEscrowPriceOracle: [
+      "campaignEscrow",
+      "depositEscrowMint",
+      "priceOracle",
+      "authority"
+    ];
     updateEscrow: [
       "campaignEscrow",
       "payer",
@@ -391,6 +942,21 @@ export type CampaignTreasuryManager = {
       "rent"
     ];
     vetoPayoutPhase: ["campaignEscrow", "payer", "authority"];
+    voteToVetoPayoutPhase: [
+      "campaignEscrow",
+      "depositRecord",
+      "voteRecord",
+      "nftHolder",
+      "nftTokenAccount",
+      "systemProgram"
+    ];
+    voteToVetoPayoutPhaseWithDonation: [
+      "campaignEscrow",
+      "donationRecord",
+      "voteRecord",
+      "donor",
+      "systemProgram"
+    ];
   };
   name: "campaign_treasury_manager";
   types: [
@@ -400,13 +966,20 @@ export type CampaignTreasuryManager = {
         fields: [
           { name: "campaignEndTime"; type: "i64" },
           {
-            name: "nonVotingPayoutPhases";
-            type: { vec: { defined: "NonVotingPayoutPhaseInput" } };
+            name: "payoutPhases";
+            type: { vec: { defined: "PayoutPhaseInput" } };
           },
+          { name: "surplusPolicy"; type: { defined: "SurplusPolicy" } },
+          { name: "crankReward"; type: { defined: "CrankReward" } },
+          { name: "collectionMint"; type: "publicKey" },
+          { name: "fundraisingStartTime"; type: { option: "i64" } },
+          { name: "depositGracePeriod"; type: "i64" },
           {
-            name: "votingPayoutPhases";
-            type: { vec: { defined: "VotingPayoutPhaseInput" } };
-          }
+            name: "rewardTiers";
+            type: { vec: { defined: "RewardTierInput" } };
+          },
+          { name: "hardCap"; type: { option: "u64" } },
+          { name: "autoEndAtHardCap"; type: "bool" }
         ];
         kind: "struct";
       };
@@ -419,13 +992,24 @@ export type CampaignTreasuryManager = {
           { name: "creator"; type: { option: "publicKey" } },
           { name: "campaignEndTime"; type: { option: "i64" } },
           {
-            name: "nonVotingPayoutPhases";
-            type: { option: { vec: { defined: "NonVotingPayoutPhaseInput" } } };
+            name: "payoutPhases";
+            type: { option: { vec: { defined: "PayoutPhaseInput" } } };
           },
           {
-            name: "votingPayoutPhases";
-            type: { option: { vec: { defined: "VotingPayoutPhaseInput" } } };
-          }
+            name: "surplusPolicy";
+            type: { option: { defined: "SurplusPolicy" } };
+          },
+          { name: "crankReward"; type: { option: { defined: "CrankReward" } } },
+          { name: "collectionMint"; type: { option: "publicKey" } },
+          { name: "depositEscrowInfosCapacity"; type: { option: "u8" } },
+          { name: "fundraisingStartTime"; type: { option: { option: "i64" } } },
+          { name: "depositGracePeriod"; type: { option: "i64" } },
+          {
+            name: "rewardTiers";
+            type: { option: { vec: { defined: "RewardTierInput" } } };
+          },
+          { name: "hardCap"; type: { option: { option: "u64" } } },
+          { name: "autoEndAtHardCap"; type: { option: "bool" } }
         ];
         kind: "struct";
       };
@@ -436,13 +1020,63 @@ export type CampaignTreasuryManager = {
         fields: [
           { name: "depositEscrowBump"; type: "u8" },
           { name: "mint"; type: "publicKey" },
-          { name: "closed"; type: "bool" }
+          { name: "closed"; type: "bool" },
+          { name: "disabled"; type: "bool" },
+          { name: "priceOracle"; type: "publicKey" }
         ];
         kind: "struct";
       };
     },
     {
       name: "Treasury";
+      type: {
+        fields: [
+          { name: "bump"; type: "u8" },
+          { name: "treasuryMint"; type: "publicKey" },
+          { name: "treasuryEscrow"; type: "publicKey" },
+          { name: "totalFunds"; type: "u64" },
+          { name: "totalPaidOut"; type: "u64" },
+          { name: "totalRefunded"; type: "u64" },
+          { name: "totalSurplus"; type: "u64" },
+          { name: "supporterSurplus"; type: "u64" },
+          { name: "supporterSurplusPaidOut"; type: "u64" },
+          { name: "totalCrankRewards"; type: "u64" },
+          { name: "pendingEstimatedFunds"; type: "u64" },
+          { name: "totalDonations"; type: "u64" },
+          { name: "nonVotingDonations"; type: "u64" },
+          { name: "nonRefundableDonations"; type: "u64" },
+          { name: "totalMatched"; type: "u64" },
+          { name: "closedDepositAmount"; type: "u64" }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "LegacyCampaignEscrow";
+      type: {
+        fields: [
+          { name: "bump"; type: "u8" },
+          { name: "campaignUuid"; type: "string" },
+          { name: "authority"; type: "publicKey" },
+          { name: "creator"; type: "publicKey" },
+          { name: "payoutWallet"; type: "publicKey" },
+          { name: "treasury"; type: { defined: "LegacyTreasury" } },
+          {
+            name: "depositEscrowInfos";
+            type: { vec: { defined: "LegacyDepositEscrowInfo" } };
+          },
+          { name: "campaignEndTime"; type: "i64" },
+          { name: "depositCount"; type: "u64" },
+          { name: "processedDepositCount"; type: "u64" },
+          { name: "closedDepositRecordCount"; type: "u64" },
+          { name: "payoutsReady"; type: "bool" },
+          { name: "payoutPhases"; type: { defined: "LegacyPayoutPhases" } }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "LegacyTreasury";
       type: {
         fields: [
           { name: "bump"; type: "u8" },
@@ -454,23 +1088,34 @@ export type CampaignTreasuryManager = {
       };
     },
     {
-      name: "PayoutPhases";
+      name: "LegacyDepositEscrowInfo";
+      type: {
+        fields: [
+          { name: "depositEscrowBump"; type: "u8" },
+          { name: "mint"; type: "publicKey" },
+          { name: "closed"; type: "bool" }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "LegacyPayoutPhases";
       type: {
         fields: [
           {
             name: "nonVotingPayoutPhases";
-            type: { vec: { defined: "NonVotingPayoutPhase" } };
+            type: { vec: { defined: "LegacyNonVotingPayoutPhase" } };
           },
           {
             name: "votingPayoutPhases";
-            type: { vec: { defined: "VotingPayoutPhase" } };
+            type: { vec: { defined: "LegacyVotingPayoutPhase" } };
           }
         ];
         kind: "struct";
       };
     },
     {
-      name: "SharedPayoutPhaseFields";
+      name: "LegacySharedPayoutPhaseFields";
       type: {
         fields: [
           { name: "index"; type: "u8" },
@@ -483,12 +1128,12 @@ export type CampaignTreasuryManager = {
       };
     },
     {
-      name: "NonVotingPayoutPhase";
+      name: "LegacyNonVotingPayoutPhase";
       type: {
         fields: [
           {
             name: "sharedFields";
-            type: { defined: "SharedPayoutPhaseFields" };
+            type: { defined: "LegacySharedPayoutPhaseFields" };
           },
           { name: "isPaidOut"; type: "bool" },
           { name: "isVetoedByAuthority"; type: "bool" }
@@ -497,24 +1142,77 @@ export type CampaignTreasuryManager = {
       };
     },
     {
-      name: "NonVotingPayoutPhaseInput";
+      name: "LegacyVotingPayoutPhase";
       type: {
         fields: [
-          { name: "sharedFields"; type: { defined: "SharedPayoutPhaseFields" } }
+          {
+            name: "sharedFields";
+            type: { defined: "LegacySharedPayoutPhaseFields" };
+          },
+          { name: "isPaidOut"; type: "bool" },
+          { name: "isVetoedByAuthority"; type: "bool" },
+          { name: "votingStartTime"; type: "i64" },
+          { name: "vetoVotes"; type: "u64" },
+          { name: "voteBasisPointsVetoThreshold"; type: "u64" }
         ];
         kind: "struct";
       };
     },
     {
-      name: "VotingPayoutPhase";
+      name: "PayoutPhases";
       type: {
         fields: [
-          {
-            name: "sharedFields";
-            type: { defined: "SharedPayoutPhaseFields" };
-          },
+          { name: "payoutPhases"; type: { vec: { defined: "PayoutPhase" } } }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "PayoutPhase";
+      type: {
+        fields: [
+          { name: "payoutBasisPoints"; type: "u16" },
+          { name: "payoutTime"; type: "i64" },
+          { name: "refundDeadline"; type: "i64" },
+          { name: "description"; type: "string" },
           { name: "isPaidOut"; type: "bool" },
+          { name: "paidAmount"; type: "u64" },
           { name: "isVetoedByAuthority"; type: "bool" },
+          { name: "isDeactivated"; type: "bool" },
+          { name: "stretchGoal"; type: { option: { defined: "StretchGoal" } } },
+          { name: "stream"; type: { option: { defined: "PayoutStream" } } },
+          {
+            name: "votingConfig";
+            type: { option: { defined: "VotingConfig" } };
+          }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "StretchGoal";
+      type: {
+        fields: [
+          { name: "raiseThreshold"; type: "u64" },
+          { name: "isActiveWhenReached"; type: "bool" }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "PayoutStream";
+      type: {
+        fields: [
+          { name: "streamEndTime"; type: "i64" },
+          { name: "vetoTime"; type: { option: "i64" } }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "VotingConfig";
+      type: {
+        fields: [
           { name: "votingStartTime"; type: "i64" },
           { name: "vetoVotes"; type: "u64" },
           { name: "voteBasisPointsVetoThreshold"; type: "u64" }
@@ -523,29 +1221,103 @@ export type CampaignTreasuryManager = {
       };
     },
     {
-      name: "VotingPayoutPhaseInput";
+      name: "PayoutPhaseInput";
       type: {
         fields: [
+          { name: "payoutBasisPoints"; type: "u16" },
+          { name: "payoutTime"; type: "i64" },
+          { name: "refundDeadline"; type: "i64" },
+          { name: "description"; type: "string" },
+          { name: "stretchGoal"; type: { option: { defined: "StretchGoal" } } },
           {
-            name: "sharedFields";
-            type: { defined: "SharedPayoutPhaseFields" };
+            name: "stream";
+            type: { option: { defined: "PayoutStreamInput" } };
           },
+          {
+            name: "votingConfig";
+            type: { option: { defined: "VotingConfigInput" } };
+          }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "PayoutStreamInput";
+      type: {
+        fields: [{ name: "streamEndTime"; type: "i64" }];
+        kind: "struct";
+      };
+    },
+    {
+      name: "VotingConfigInput";
+      type: {
+        fields: [
           { name: "votingStartTime"; type: "i64" },
-          { name: "vetoVotes"; type: "u64" },
           { name: "voteBasisPointsVetoThreshold"; type: "u64" }
         ];
-        kind: "struct";
+        kind: "struct";
+      };
+    },
+    {
+      name: "RewardTiers";
+      type: {
+        fields: [
+          { name: "rewardTiers"; type: { vec: { defined: "RewardTier" } } }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "RewardTierInput";
+      type: {
+        fields: [
+          { name: "minDepositAmount"; type: "u64" },
+          { name: "maxSupply"; type: "u32" },
+          { name: "metadataUri"; type: "string" }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "RewardTier";
+      type: {
+        fields: [
+          { name: "minDepositAmount"; type: "u64" },
+          { name: "maxSupply"; type: "u32" },
+          { name: "soldCount"; type: "u32" },
+          { name: "metadataUri"; type: "string" }
+        ];
+        kind: "struct";
+      };
+    },
+    {
+      name: "PayoutMode";
+      type: {
+        kind: "enum";
+        variants: [{ name: "CurrentPhase" }, { name: "AllDuePhases" }];
+      };
+    },
+    {
+      name: "SurplusPolicy";
+      type: {
+        kind: "enum";
+        variants: [
+          { name: "Creator" },
+          { name: "Supporters" },
+          { name: "Authority" }
+        ];
       };
     },
     {
-      name: "PayoutPhaseEnum";
+      name: "CrankReward";
       type: {
         kind: "enum";
         variants: [
-          { fields: [{ defined: "&'aVotingPayoutPhase" }]; name: "Voting" },
+          { name: "None" },
+          { fields: [{ name: "amount"; type: "u64" }]; name: "Fixed" },
           {
-            fields: [{ defined: "&'aNonVotingPayoutPhase" }];
-            name: "NonVoting";
+            fields: [{ name: "basis_points"; type: "u16" }];
+            name: "BasisPoints";
           }
         ];
       };
@@ -559,6 +1331,7 @@ export const IDL: CampaignTreasuryManager = {
       name: "campaignEscrow",
       type: {
         fields: [
+          { name: "version", type: "u8" },
           { name: "bump", type: "u8" },
           { name: "campaignUuid", type: "string" },
           { name: "authority", type: "publicKey" },
@@ -574,6 +1347,18 @@ export const IDL: CampaignTreasuryManager = {
           { name: "processedDepositCount", type: "u64" },
           { name: "closedDepositRecordCount", type: "u64" },
           { name: "payoutsReady", type: "bool" },
+          { name: "surplusPolicy", type: { defined: "SurplusPolicy" } },
+          { name: "crankReward", type: { defined: "CrankReward" } },
+          { name: "collectionMint", type: "publicKey" },
+          { name: "depositEscrowInfosCapacity", type: "u8" },
+          { name: "fundraisingStartTime", type: { option: "i64" } },
+          { name: "depositGracePeriod", type: "i64" },
+          { name: "rewardTiers", type: { defined: "RewardTiers" } },
+          { name: "hardCap", type: { option: "u64" } },
+          { name: "autoEndAtHardCap", type: "bool" },
+          { name: "hardCapReachedAt", type: { option: "i64" } },
+          { name: "settledRaise", type: { option: "u64" } },
+          { name: "hasMatchingPool", type: "bool" },
           { name: "payoutPhases", type: { defined: "PayoutPhases" } },
         ],
         kind: "struct",
@@ -591,11 +1376,59 @@ export const IDL: CampaignTreasuryManager = {
           { name: "initialDepositAmount", type: "u64" },
           { name: "processedDepositAmount", type: "u64" },
           { name: "depositProcessed", type: "bool" },
+          { name: "campaignEscrow", type: "publicKey" },
+          { name: "estimatedDepositAmount", type: "u64" },
+          { name: "rewardTierIndex", type: { option: "u8" } },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "donationRecord",
+      type: {
+        fields: [
+          { name: "bump", type: "u8" },
+          { name: "campaignEscrow", type: "publicKey" },
+          { name: "donationUuid", type: "string" },
+          { name: "donor", type: "publicKey" },
+          { name: "donationAmount", type: "u64" },
+          { name: "refundable", type: "bool" },
+          { name: "hasVoteWeight", type: "bool" },
+          { name: "donationTime", type: "i64" },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "matchingPool",
+      type: {
+        fields: [
+          { name: "bump", type: "u8" },
+          { name: "escrowBump", type: "u8" },
+          { name: "campaignEscrow", type: "publicKey" },
+          { name: "sponsor", type: "publicKey" },
+          { name: "matchBasisPoints", type: "u16" },
+          { name: "matchCap", type: "u64" },
+          { name: "totalMatched", type: "u64" },
         ],
         kind: "struct",
       },
     },
+    { name: "matchingPoolEscrow", type: { fields: [], kind: "struct" } },
     { name: "treasuryEscrow", type: { fields: [], kind: "struct" } },
+    {
+      name: "voteRecord",
+      type: {
+        fields: [
+          { name: "bump", type: "u8" },
+          { name: "depositRecord", type: "publicKey" },
+          { name: "payoutPhaseIndex", type: "u8" },
+          { name: "voter", type: "publicKey" },
+          { name: "vetoVotes", type: "u64" },
+        ],
+        kind: "struct",
+      },
+    },
   ],
   errors: [
     { code: 9000, msg: "PublicKey check failed", name: "PublicKeyMismatch" },
@@ -646,41 +1479,179 @@ export const IDL: CampaignTreasuryManager = {
     },
     {
       code: 9010,
-      msg: "UpdateEscrow input must include both the treasury_escrow_owner and treasury_mint if updating the escrow treasury",
-      name: "InvalidTreasuryEscrowUpdate",
-    },
-    {
-      code: 9011,
       msg: "Invalid campaign_end_time provided",
       name: "InvalidCampaignEndTime",
     },
     {
-      code: 9012,
-      msg: "Deposit escrow mint cannot be the native mint",
-      name: "InvalidDepositEscrowMint",
-    },
-    {
-      code: 9013,
+      code: 9011,
       msg: "Invalid deposit escrow account provided",
       name: "InvalidDepositEscrowAccount",
     },
-    { code: 9014, msg: "Invalid CPI invocation", name: "InvalidCpiInvocation" },
+    { code: 9012, msg: "Invalid CPI invocation", name: "InvalidCpiInvocation" },
     {
-      code: 9015,
+      code: 9013,
       msg: "Invalid CreateDepositEscrow instruction",
       name: "InvalidCreateDepositEscrowIx",
     },
-    { code: 9016, msg: "Invalid payout request", name: "InvalidPayoutRequest" },
+    { code: 9014, msg: "Invalid payout request", name: "InvalidPayoutRequest" },
     {
-      code: 9017,
+      code: 9015,
       msg: "Deposit escrow account must be empty before it can be closed",
       name: "DepositEscrowAccountNotEmpty",
     },
+    {
+      code: 9016,
+      msg: "Too many payout phases provided",
+      name: "TooManyPayoutPhases",
+    },
+    {
+      code: 9017,
+      msg: "Treasury paid out and refunded totals exceed the total funds",
+      name: "InvalidTreasuryAccounting",
+    },
+    {
+      code: 9018,
+      msg: "Surplus receiver does not match the CampaignEscrow surplus policy",
+      name: "InvalidSurplusReceiver",
+    },
+    {
+      code: 9019,
+      msg: "Invalid DepositRecord account provided",
+      name: "InvalidDepositRecord",
+    },
+    {
+      code: 9020,
+      msg: "Invalid crank reward provided",
+      name: "InvalidCrankReward",
+    },
+    {
+      code: 9021,
+      msg: "NFT is not a verified member of the campaign collection",
+      name: "InvalidNftCollection",
+    },
+    {
+      code: 9022,
+      msg: "Signer does not hold the DepositRecord NFT",
+      name: "InvalidNftHolder",
+    },
+    { code: 9023, msg: "Invalid vote", name: "InvalidVote" },
+    {
+      code: 9024,
+      msg: "Too many DepositEscrow currencies for the CampaignEscrow",
+      name: "TooManyDepositEscrows",
+    },
+    {
+      code: 9025,
+      msg: "Deposits in this currency have been disabled",
+      name: "DepositEscrowDisabled",
+    },
+    {
+      code: 9026,
+      msg: "Invalid price oracle account provided",
+      name: "InvalidPriceOracle",
+    },
+    { code: 9027, msg: "Invalid donation", name: "InvalidDonation" },
+    {
+      code: 9028,
+      msg: "Invalid fundraising window settings",
+      name: "InvalidFundraisingWindow",
+    },
+    {
+      code: 9029,
+      msg: "Deposits and donations are only accepted during the fundraising window",
+      name: "OutsideFundraisingWindow",
+    },
+    { code: 9030, msg: "Invalid reward tiers", name: "InvalidRewardTiers" },
+    {
+      code: 9031,
+      msg: "Deposit does not qualify for the claimed reward tier",
+      name: "InvalidRewardTier",
+    },
+    { code: 9032, msg: "Reward tier is sold out", name: "RewardTierSoldOut" },
+    { code: 9033, msg: "Invalid hard cap", name: "InvalidHardCap" },
+    { code: 9034, msg: "Campaign hard cap exceeded", name: "HardCapExceeded" },
+    { code: 9035, msg: "Invalid matching pool", name: "InvalidMatchingPool" },
+    {
+      code: 9036,
+      msg: "Cannot close the matching pool yet",
+      name: "InvalidCloseMatchingPool",
+    },
+    {
+      code: 9037,
+      msg: "Invalid streamed payout claim",
+      name: "InvalidStreamedPayoutClaim",
+    },
+    { code: 9038, msg: "Invalid refund request", name: "InvalidRefundRequest" },
+    {
+      code: 9039,
+      msg: "Cannot process the deposit",
+      name: "InvalidProcessDeposit",
+    },
+  ],
+  events: [
+    {
+      fields: [
+        { index: false, name: "campaignEscrow", type: "publicKey" },
+        { index: false, name: "payoutPhaseIndex", type: "u8" },
+        { index: false, name: "paidAmount", type: "u64" },
+        { index: false, name: "crankRewardAmount", type: "u64" },
+      ],
+      name: "PayoutPhasePaidOut",
+    },
+    {
+      fields: [
+        { index: false, name: "campaignEscrow", type: "publicKey" },
+        { index: false, name: "payoutPhaseIndex", type: "u8" },
+        { index: false, name: "claimedAmount", type: "u64" },
+        { index: false, name: "paidAmount", type: "u64" },
+      ],
+      name: "StreamedPayoutClaimed",
+    },
+    {
+      fields: [
+        { index: false, name: "campaignEscrow", type: "publicKey" },
+        { index: false, name: "depositRecord", type: "publicKey" },
+        { index: false, name: "depositor", type: "publicKey" },
+        { index: false, name: "processedDepositAmount", type: "u64" },
+      ],
+      name: "DepositProcessed",
+    },
+    {
+      fields: [
+        { index: false, name: "campaignEscrow", type: "publicKey" },
+        { index: false, name: "record", type: "publicKey" },
+        { index: false, name: "refundReceiver", type: "publicKey" },
+        { index: false, name: "refundAmount", type: "u64" },
+      ],
+      name: "RefundProcessed",
+    },
+    {
+      fields: [
+        { index: false, name: "campaignEscrow", type: "publicKey" },
+        { index: false, name: "payoutPhaseIndex", type: "u8" },
+        { index: false, name: "record", type: "publicKey" },
+        { index: false, name: "voter", type: "publicKey" },
+        { index: false, name: "vetoVotes", type: "u64" },
+      ],
+      name: "VetoVotesCast",
+    },
   ],
   instructions: [
     {
       accounts: [
-        { isMut: false, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: true, name: "payer" },
+        { isMut: true, isSigner: false, name: "payoutWallet" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [],
+      name: "claimStreamedPayout",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
         { isMut: true, isSigner: false, name: "depositEscrow" },
         { isMut: false, isSigner: false, name: "depositEscrowMint" },
         { isMut: true, isSigner: false, name: "receiver" },
@@ -700,6 +1671,17 @@ export const IDL: CampaignTreasuryManager = {
       args: [],
       name: "closeDepositRecord",
     },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: true, name: "payer" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [],
+      name: "closeDepositRecords",
+    },
     {
       accounts: [
         { isMut: true, isSigner: false, name: "campaignEscrow" },
@@ -709,6 +1691,14 @@ export const IDL: CampaignTreasuryManager = {
           isSigner: false,
           name: "treasuryEscrow",
         },
+        {
+          docs: [
+            "This account is not used if the treasury holds no remaining funds.",
+          ],
+          isMut: true,
+          isSigner: false,
+          name: "remainderReceiver",
+        },
         { isMut: true, isSigner: false, name: "receiver" },
         { isMut: true, isSigner: true, name: "payer" },
         { isMut: false, isSigner: true, name: "authority" },
@@ -719,6 +1709,24 @@ export const IDL: CampaignTreasuryManager = {
       args: [],
       name: "closeEscrow",
     },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "matchingPool" },
+        {
+          docs: ["This account is closed manually in the ix handler."],
+          isMut: true,
+          isSigner: false,
+          name: "matchingPoolEscrow",
+        },
+        { isMut: true, isSigner: true, name: "sponsor" },
+        { isMut: true, isSigner: false, name: "sponsorPaymentAccount" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+      ],
+      args: [],
+      name: "closeMatchingPool",
+    },
     {
       accounts: [
         { isMut: true, isSigner: false, name: "campaignEscrow" },
@@ -737,11 +1745,20 @@ export const IDL: CampaignTreasuryManager = {
         { isMut: true, isSigner: true, name: "depositor" },
         { isMut: true, isSigner: false, name: "depositorPaymentAccount" },
         { isMut: false, isSigner: false, name: "mint" },
+        {
+          docs: ["collection_mint."],
+          isMut: false,
+          isSigner: false,
+          name: "metadata",
+        },
         { isMut: false, isSigner: false, name: "systemProgram" },
         { isMut: false, isSigner: false, name: "tokenProgram" },
         { isMut: false, isSigner: false, name: "instructionSysvarAccount" },
       ],
-      args: [{ name: "depositAmount", type: "u64" }],
+      args: [
+        { name: "depositAmount", type: "u64" },
+        { name: "rewardTierIndex", type: { option: "u8" } },
+      ],
       name: "createDeposit",
     },
     {
@@ -790,6 +1807,83 @@ export const IDL: CampaignTreasuryManager = {
       ],
       name: "createEscrow",
     },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: false, isSigner: false, name: "depositEscrowMint" },
+        { isMut: false, isSigner: true, name: "authority" },
+      ],
+      args: [],
+      name: "disableDepositEscrow",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "donationRecord" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: false, isSigner: false, name: "treasuryMint" },
+        { isMut: true, isSigner: true, name: "donor" },
+        { isMut: true, isSigner: false, name: "donorPaymentAccount" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+      ],
+      args: [
+        { name: "donationUuid", type: "string" },
+        { name: "donationAmount", type: "u64" },
+        { name: "refundable", type: "bool" },
+        { name: "hasVoteWeight", type: "bool" },
+      ],
+      name: "donate",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "matchingPool" },
+        {
+          docs: ["for token treasuries."],
+          isMut: true,
+          isSigner: false,
+          name: "matchingPoolEscrow",
+        },
+        { isMut: false, isSigner: false, name: "treasuryMint" },
+        { isMut: true, isSigner: true, name: "sponsor" },
+        { isMut: true, isSigner: false, name: "sponsorPaymentAccount" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "rent" },
+      ],
+      args: [
+        { name: "matchBasisPoints", type: "u16" },
+        { name: "matchCap", type: "u64" },
+      ],
+      name: "fundMatchingPool",
+    },
+    {
+      accounts: [
+        {
+          docs: [
+            "Legacy accounts cannot be deserialized as a CampaignEscrow, so this",
+            "account is deserialized manually in the instruction handler.",
+          ],
+          isMut: true,
+          isSigner: false,
+          name: "campaignEscrow",
+        },
+        { isMut: true, isSigner: true, name: "payer" },
+        { isMut: false, isSigner: true, name: "authority" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [{ name: "campaignUuid", type: "string" }],
+      name: "migrateCampaignEscrow",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: false, isSigner: true, name: "authority" },
+      ],
+      args: [{ name: "collectionMint", type: { option: "publicKey" } }],
+      name: "migrateDepositRecords",
+    },
     {
       accounts: [
         { isMut: true, isSigner: false, name: "campaignEscrow" },
@@ -800,34 +1894,119 @@ export const IDL: CampaignTreasuryManager = {
         { isMut: false, isSigner: false, name: "systemProgram" },
       ],
       args: [],
-      name: "payOutFunds",
+      name: "payOutAllDueFunds",
     },
     {
       accounts: [
         { isMut: true, isSigner: false, name: "campaignEscrow" },
-        { isMut: false, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
         { isMut: true, isSigner: true, name: "payer" },
+        { isMut: true, isSigner: false, name: "payoutWallet" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
       ],
       args: [],
+      name: "payOutFunds",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "depositRecord" },
+        { isMut: true, isSigner: false, name: "depositEscrow" },
+        { isMut: false, isSigner: false, name: "depositEscrowMint" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: false, isSigner: false, name: "treasuryMint" },
+        { isMut: true, isSigner: true, name: "authority" },
+        {
+          docs: [
+            "DepositEscrow, this is the authority for native deposit_escrow_mints",
+            "and the authority's ATA otherwise.",
+          ],
+          isMut: true,
+          isSigner: false,
+          name: "authorityDepositEscrowAccount",
+        },
+        { isMut: true, isSigner: false, name: "authorityPaymentAccount" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [{ name: "processedDepositAmount", type: "u64" }],
       name: "processDeposit",
     },
     {
       accounts: [
         { isMut: true, isSigner: false, name: "campaignEscrow" },
-        { isMut: false, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: false, name: "donationRecord" },
+        { isMut: true, isSigner: true, name: "donor" },
+        { isMut: true, isSigner: false, name: "refundReceiver" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [],
+      name: "processDonationRefund",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: false, name: "depositRecord" },
+        { isMut: true, isSigner: false, name: "depositor" },
+        { isMut: false, isSigner: true, name: "nftHolder" },
+        { isMut: true, isSigner: false, name: "nftMint" },
+        { isMut: true, isSigner: false, name: "nftTokenAccount" },
+        { isMut: true, isSigner: false, name: "refundReceiver" },
+        { isMut: true, isSigner: true, name: "payer" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [],
+      name: "processFullRefund",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        { isMut: true, isSigner: false, name: "depositRecord" },
+        { isMut: true, isSigner: false, name: "depositor" },
+        { isMut: false, isSigner: true, name: "nftHolder" },
+        { isMut: false, isSigner: false, name: "nftTokenAccount" },
+        { isMut: true, isSigner: false, name: "refundReceiver" },
+        { isMut: true, isSigner: true, name: "payer" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [],
+      name: "processPartialRefund",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: true, isSigner: false, name: "treasuryEscrow" },
+        {
+          docs: [
+            "This account is not used if the surplus is held for supporters.",
+          ],
+          isMut: true,
+          isSigner: false,
+          name: "surplusReceiver",
+        },
         { isMut: true, isSigner: true, name: "payer" },
+        { isMut: false, isSigner: false, name: "tokenProgram" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
       ],
       args: [],
-      name: "processFullRefund",
+      name: "reconcileTreasury",
     },
     {
       accounts: [
         { isMut: true, isSigner: false, name: "campaignEscrow" },
-        { isMut: false, isSigner: false, name: "treasuryEscrow" },
-        { isMut: true, isSigner: true, name: "payer" },
+        { isMut: false, isSigner: false, name: "depositEscrowMint" },
+        { isMut: false, isSigner: false, name: "priceOracle" },
+        { isMut: false, isSigner: true, name: "authority" },
       ],
       args: [],
-      name: "processPartialRefund",
+      name: "setDepositEscrowPriceOracle",
     },
     {
       accounts: [
@@ -863,8 +2042,39 @@ export const IDL: CampaignTreasuryManager = {
       args: [{ name: "payoutPhaseIndex", type: "u8" }],
       name: "vetoPayoutPhase",
     },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: false, isSigner: false, name: "depositRecord" },
+        { isMut: true, isSigner: false, name: "voteRecord" },
+        { isMut: true, isSigner: true, name: "nftHolder" },
+        { isMut: false, isSigner: false, name: "nftTokenAccount" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [{ name: "payoutPhaseIndex", type: "u8" }],
+      name: "voteToVetoPayoutPhase",
+    },
+    {
+      accounts: [
+        { isMut: true, isSigner: false, name: "campaignEscrow" },
+        { isMut: false, isSigner: false, name: "donationRecord" },
+        { isMut: true, isSigner: false, name: "voteRecord" },
+        { isMut: true, isSigner: true, name: "donor" },
+        { isMut: false, isSigner: false, name: "systemProgram" },
+      ],
+      args: [{ name: "payoutPhaseIndex", type: "u8" }],
+      name: "voteToVetoPayoutPhaseWithDonation",
+    },
   ],
   instructionsMap: {
+    claimStreamedPayout: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "payer",
+      "payoutWallet",
+      "tokenProgram",
+      "systemProgram",
+    ],
     closeDepositEscrow: [
       "campaignEscrow",
       "depositEscrow",
@@ -875,9 +2085,17 @@ export const IDL: CampaignTreasuryManager = {
       "systemProgram",
     ],
     closeDepositRecord: ["campaignEscrow", "treasuryEscrow", "payer"],
+    closeDepositRecords: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "payer",
+      "tokenProgram",
+      "systemProgram",
+    ],
     closeEscrow: [
       "campaignEscrow",
       "treasuryEscrow",
+      "remainderReceiver",
       "receiver",
       "payer",
       "authority",
@@ -885,6 +2103,15 @@ export const IDL: CampaignTreasuryManager = {
       "tokenProgram",
       "systemProgram",
     ],
+    closeMatchingPool: [
+      "campaignEscrow",
+      "matchingPool",
+      "matchingPoolEscrow",
+      "sponsor",
+      "sponsorPaymentAccount",
+      "systemProgram",
+      "tokenProgram",
+    ],
     createDeposit: [
       "campaignEscrow",
       "depositRecord",
@@ -893,6 +2120,7 @@ export const IDL: CampaignTreasuryManager = {
       "depositor",
       "depositorPaymentAccount",
       "mint",
+      "metadata",
       "systemProgram",
       "tokenProgram",
       "instructionSysvarAccount",
@@ -920,6 +2148,43 @@ export const IDL: CampaignTreasuryManager = {
       "systemProgram",
       "rent",
     ],
+    disableDepositEscrow: ["campaignEscrow", "depositEscrowMint", "authority"],
+    donate: [
+      "campaignEscrow",
+      "donationRecord",
+      "treasuryEscrow",
+      "treasuryMint",
+      "donor",
+      "donorPaymentAccount",
+      "systemProgram",
+      "tokenProgram",
+    ],
+    fundMatchingPool: [
+      "campaignEscrow",
+      "matchingPool",
+      "matchingPoolEscrow",
+      "treasuryMint",
+      "sponsor",
+      "sponsorPaymentAccount",
+      "systemProgram",
+      "tokenProgram",
+      "rent",
+    ],
+    migrateCampaignEscrow: [
+      "campaignEscrow",
+      "payer",
+      "authority",
+      "systemProgram",
+    ],
+    migrateDepositRecords: ["campaignEscrow", "authority"],
+    payOutAllDueFunds: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "payer",
+      "payoutWallet",
+      "tokenProgram",
+      "systemProgram",
+    ],
     payOutFunds: [
       "campaignEscrow",
       "treasuryEscrow",
@@ -928,9 +2193,67 @@ export const IDL: CampaignTreasuryManager = {
       "tokenProgram",
       "systemProgram",
     ],
-    processDeposit: ["campaignEscrow", "treasuryEscrow", "payer"],
-    processFullRefund: ["campaignEscrow", "treasuryEscrow", "payer"],
-    processPartialRefund: ["campaignEscrow", "treasuryEscrow", "payer"],
+    processDeposit: [
+      "campaignEscrow",
+      "depositRecord",
+      "depositEscrow",
+      "depositEscrowMint",
+      "treasuryEscrow",
+      "treasuryMint",
+      "authority",
+      "authorityDepositEscrowAccount",
+      "authorityPaymentAccount",
+      "tokenProgram",
+      "systemProgram",
+    ],
+    processDonationRefund: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "donationRecord",
+      "donor",
+      "refundReceiver",
+      "tokenProgram",
+      "systemProgram",
+    ],
+    processFullRefund: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "depositRecord",
+      "depositor",
+      "nftHolder",
+      "nftMint",
+      "nftTokenAccount",
+      "refundReceiver",
+      "payer",
+      "tokenProgram",
+      "systemProgram",
+    ],
+    processPartialRefund: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "depositRecord",
+      "depositor",
+      "nftHolder",
+      "nftTokenAccount",
+      "refundReceiver",
+      "payer",
+      "tokenProgram",
+      "systemProgram",
+    ],
+    reconcileTreasury: [
+      "campaignEscrow",
+      "treasuryEscrow",
+      "surplusReceiver",
+      "payer",
+      "tokenProgram",
+      "systemProgram",
+    ],
+    setDepositEscrowPriceOracle: [
+      "campaignEscrow",
+      "depositEscrowMint",
+      "priceOracle",
+      "authority",
+    ],
     updateEscrow: [
       "campaignEscrow",
       "payer",
@@ -946,6 +2269,21 @@ export const IDL: CampaignTreasuryManager = {
       "rent",
     ],
     vetoPayoutPhase: ["campaignEscrow", "payer", "authority"],
+    voteToVetoPayoutPhase: [
+      "campaignEscrow",
+      "depositRecord",
+      "voteRecord",
+      "nftHolder",
+      "nftTokenAccount",
+      "systemProgram",
+    ],
+    voteToVetoPayoutPhaseWithDonation: [
+      "campaignEscrow",
+      "donationRecord",
+      "voteRecord",
+      "donor",
+      "systemProgram",
+    ],
   },
   name: "campaign_treasury_manager",
   types: [
@@ -955,13 +2293,20 @@ export const IDL: CampaignTreasuryManager = {
         fields: [
           { name: "campaignEndTime", type: "i64" },
           {
-            name: "nonVotingPayoutPhases",
-            type: { vec: { defined: "NonVotingPayoutPhaseInput" } },
+            name: "payoutPhases",
+            type: { vec: { defined: "PayoutPhaseInput" } },
           },
+          { name: "surplusPolicy", type: { defined: "SurplusPolicy" } },
+          { name: "crankReward", type: { defined: "CrankReward" } },
+          { name: "collectionMint", type: "publicKey" },
+          { name: "fundraisingStartTime", type: { option: "i64" } },
+          { name: "depositGracePeriod", type: "i64" },
           {
-            name: "votingPayoutPhases",
-            type: { vec: { defined: "VotingPayoutPhaseInput" } },
+            name: "rewardTiers",
+            type: { vec: { defined: "RewardTierInput" } },
           },
+          { name: "hardCap", type: { option: "u64" } },
+          { name: "autoEndAtHardCap", type: "bool" },
         ],
         kind: "struct",
       },
@@ -974,13 +2319,24 @@ export const IDL: CampaignTreasuryManager = {
           { name: "creator", type: { option: "publicKey" } },
           { name: "campaignEndTime", type: { option: "i64" } },
           {
-            name: "nonVotingPayoutPhases",
-            type: { option: { vec: { defined: "NonVotingPayoutPhaseInput" } } },
+            name: "payoutPhases",
+            type: { option: { vec: { defined: "PayoutPhaseInput" } } },
           },
           {
-            name: "votingPayoutPhases",
-            type: { option: { vec: { defined: "VotingPayoutPhaseInput" } } },
+            name: "surplusPolicy",
+            type: { option: { defined: "SurplusPolicy" } },
           },
+          { name: "crankReward", type: { option: { defined: "CrankReward" } } },
+          { name: "collectionMint", type: { option: "publicKey" } },
+          { name: "depositEscrowInfosCapacity", type: { option: "u8" } },
+          { name: "fundraisingStartTime", type: { option: { option: "i64" } } },
+          { name: "depositGracePeriod", type: { option: "i64" } },
+          {
+            name: "rewardTiers",
+            type: { option: { vec: { defined: "RewardTierInput" } } },
+          },
+          { name: "hardCap", type: { option: { option: "u64" } } },
+          { name: "autoEndAtHardCap", type: { option: "bool" } },
         ],
         kind: "struct",
       },
@@ -992,6 +2348,8 @@ export const IDL: CampaignTreasuryManager = {
           { name: "depositEscrowBump", type: "u8" },
           { name: "mint", type: "publicKey" },
           { name: "closed", type: "bool" },
+          { name: "disabled", type: "bool" },
+          { name: "priceOracle", type: "publicKey" },
         ],
         kind: "struct",
       },
@@ -1004,28 +2362,87 @@ export const IDL: CampaignTreasuryManager = {
           { name: "treasuryMint", type: "publicKey" },
           { name: "treasuryEscrow", type: "publicKey" },
           { name: "totalFunds", type: "u64" },
+          { name: "totalPaidOut", type: "u64" },
+          { name: "totalRefunded", type: "u64" },
+          { name: "totalSurplus", type: "u64" },
+          { name: "supporterSurplus", type: "u64" },
+          { name: "supporterSurplusPaidOut", type: "u64" },
+          { name: "totalCrankRewards", type: "u64" },
+          { name: "pendingEstimatedFunds", type: "u64" },
+          { name: "totalDonations", type: "u64" },
+          { name: "nonVotingDonations", type: "u64" },
+          { name: "nonRefundableDonations", type: "u64" },
+          { name: "totalMatched", type: "u64" },
+          { name: "closedDepositAmount", type: "u64" },
         ],
         kind: "struct",
       },
     },
     {
-      name: "PayoutPhases",
+      name: "LegacyCampaignEscrow",
+      type: {
+        fields: [
+          { name: "bump", type: "u8" },
+          { name: "campaignUuid", type: "string" },
+          { name: "authority", type: "publicKey" },
+          { name: "creator", type: "publicKey" },
+          { name: "payoutWallet", type: "publicKey" },
+          { name: "treasury", type: { defined: "LegacyTreasury" } },
+          {
+            name: "depositEscrowInfos",
+            type: { vec: { defined: "LegacyDepositEscrowInfo" } },
+          },
+          { name: "campaignEndTime", type: "i64" },
+          { name: "depositCount", type: "u64" },
+          { name: "processedDepositCount", type: "u64" },
+          { name: "closedDepositRecordCount", type: "u64" },
+          { name: "payoutsReady", type: "bool" },
+          { name: "payoutPhases", type: { defined: "LegacyPayoutPhases" } },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "LegacyTreasury",
+      type: {
+        fields: [
+          { name: "bump", type: "u8" },
+          { name: "treasuryMint", type: "publicKey" },
+          { name: "treasuryEscrow", type: "publicKey" },
+          { name: "totalFunds", type: "u64" },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "LegacyDepositEscrowInfo",
+      type: {
+        fields: [
+          { name: "depositEscrowBump", type: "u8" },
+          { name: "mint", type: "publicKey" },
+          { name: "closed", type: "bool" },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "LegacyPayoutPhases",
       type: {
         fields: [
           {
             name: "nonVotingPayoutPhases",
-            type: { vec: { defined: "NonVotingPayoutPhase" } },
+            type: { vec: { defined: "LegacyNonVotingPayoutPhase" } },
           },
           {
             name: "votingPayoutPhases",
-            type: { vec: { defined: "VotingPayoutPhase" } },
+            type: { vec: { defined: "LegacyVotingPayoutPhase" } },
           },
         ],
         kind: "struct",
       },
     },
     {
-      name: "SharedPayoutPhaseFields",
+      name: "LegacySharedPayoutPhaseFields",
       type: {
         fields: [
           { name: "index", type: "u8" },
@@ -1038,12 +2455,12 @@ export const IDL: CampaignTreasuryManager = {
       },
     },
     {
-      name: "NonVotingPayoutPhase",
+      name: "LegacyNonVotingPayoutPhase",
       type: {
         fields: [
           {
             name: "sharedFields",
-            type: { defined: "SharedPayoutPhaseFields" },
+            type: { defined: "LegacySharedPayoutPhaseFields" },
           },
           { name: "isPaidOut", type: "bool" },
           { name: "isVetoedByAuthority", type: "bool" },
@@ -1052,27 +2469,77 @@ export const IDL: CampaignTreasuryManager = {
       },
     },
     {
-      name: "NonVotingPayoutPhaseInput",
+      name: "LegacyVotingPayoutPhase",
       type: {
         fields: [
           {
             name: "sharedFields",
-            type: { defined: "SharedPayoutPhaseFields" },
+            type: { defined: "LegacySharedPayoutPhaseFields" },
           },
+          { name: "isPaidOut", type: "bool" },
+          { name: "isVetoedByAuthority", type: "bool" },
+          { name: "votingStartTime", type: "i64" },
+          { name: "vetoVotes", type: "u64" },
+          { name: "voteBasisPointsVetoThreshold", type: "u64" },
         ],
         kind: "struct",
       },
     },
     {
-      name: "VotingPayoutPhase",
+      name: "PayoutPhases",
       type: {
         fields: [
-          {
-            name: "sharedFields",
-            type: { defined: "SharedPayoutPhaseFields" },
-          },
+          { name: "payoutPhases", type: { vec: { defined: "PayoutPhase" } } },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "PayoutPhase",
+      type: {
+        fields: [
+          { name: "payoutBasisPoints", type: "u16" },
+          { name: "payoutTime", type: "i64" },
+          { name: "refundDeadline", type: "i64" },
+          { name: "description", type: "string" },
           { name: "isPaidOut", type: "bool" },
+          { name: "paidAmount", type: "u64" },
           { name: "isVetoedByAuthority", type: "bool" },
+          { name: "isDeactivated", type: "bool" },
+          { name: "stretchGoal", type: { option: { defined: "StretchGoal" } } },
+          { name: "stream", type: { option: { defined: "PayoutStream" } } },
+          {
+            name: "votingConfig",
+            type: { option: { defined: "VotingConfig" } },
+          },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "StretchGoal",
+      type: {
+        fields: [
+          { name: "raiseThreshold", type: "u64" },
+          { name: "isActiveWhenReached", type: "bool" },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "PayoutStream",
+      type: {
+        fields: [
+          { name: "streamEndTime", type: "i64" },
+          { name: "vetoTime", type: { option: "i64" } },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "VotingConfig",
+      type: {
+        fields: [
           { name: "votingStartTime", type: "i64" },
           { name: "vetoVotes", type: "u64" },
           { name: "voteBasisPointsVetoThreshold", type: "u64" },
@@ -1081,29 +2548,103 @@ export const IDL: CampaignTreasuryManager = {
       },
     },
     {
-      name: "VotingPayoutPhaseInput",
+      name: "PayoutPhaseInput",
       type: {
         fields: [
+          { name: "payoutBasisPoints", type: "u16" },
+          { name: "payoutTime", type: "i64" },
+          { name: "refundDeadline", type: "i64" },
+          { name: "description", type: "string" },
+          { name: "stretchGoal", type: { option: { defined: "StretchGoal" } } },
           {
-            name: "sharedFields",
-            type: { defined: "SharedPayoutPhaseFields" },
+            name: "stream",
+            type: { option: { defined: "PayoutStreamInput" } },
           },
+          {
+            name: "votingConfig",
+            type: { option: { defined: "VotingConfigInput" } },
+          },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "PayoutStreamInput",
+      type: {
+        fields: [{ name: "streamEndTime", type: "i64" }],
+        kind: "struct",
+      },
+    },
+    {
+      name: "VotingConfigInput",
+      type: {
+        fields: [
           { name: "votingStartTime", type: "i64" },
-          { name: "vetoVotes", type: "u64" },
           { name: "voteBasisPointsVetoThreshold", type: "u64" },
         ],
         kind: "struct",
       },
     },
     {
-      name: "PayoutPhaseEnum",
+      name: "RewardTiers",
+      type: {
+        fields: [
+          { name: "rewardTiers", type: { vec: { defined: "RewardTier" } } },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "RewardTierInput",
+      type: {
+        fields: [
+          { name: "minDepositAmount", type: "u64" },
+          { name: "maxSupply", type: "u32" },
+          { name: "metadataUri", type: "string" },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "RewardTier",
+      type: {
+        fields: [
+          { name: "minDepositAmount", type: "u64" },
+          { name: "maxSupply", type: "u32" },
+          { name: "soldCount", type: "u32" },
+          { name: "metadataUri", type: "string" },
+        ],
+        kind: "struct",
+      },
+    },
+    {
+      name: "PayoutMode",
+      type: {
+        kind: "enum",
+        variants: [{ name: "CurrentPhase" }, { name: "AllDuePhases" }],
+      },
+    },
+    {
+      name: "SurplusPolicy",
+      type: {
+        kind: "enum",
+        variants: [
+          { name: "Creator" },
+          { name: "Supporters" },
+          { name: "Authority" },
+        ],
+      },
+    },
+    {
+      name: "CrankReward",
       type: {
         kind: "enum",
         variants: [
-          { fields: [{ defined: "&'aVotingPayoutPhase" }], name: "Voting" },
+          { name: "None" },
+          { fields: [{ name: "amount", type: "u64" }], name: "Fixed" },
           {
-            fields: [{ defined: "&'aNonVotingPayoutPhase" }],
-            name: "NonVoting",
+            fields: [{ name: "basis_points", type: "u16" }],
+            name: "BasisPoints",
           },
         ],
       },
@@ -0,0 +1,50 @@
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
+
+type Accounts = {
+  payer: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function claimStreamedPayoutIx(
+  { payer }: Accounts,
+  { campaignUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [treasuryEscrow] = findTreasuryEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  // Claims are always transferred to the payout wallet, so anyone can pay for
+  // them.
+  const { payoutWallet } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+
+  return program.methods
+    .claimStreamedPayout()
+    .accounts({
+      campaignEscrow,
+      payer,
+      payoutWallet,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryEscrow,
+    })
+    .instruction();
+}
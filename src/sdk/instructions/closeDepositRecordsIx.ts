@@ -0,0 +1,74 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import DepositRecordToClose from "sdk/types/DepositRecordToClose";
+
+type Accounts = {
+  payer: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  depositRecords: Array<DepositRecordToClose>;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function closeDepositRecordsIx(
+  { payer }: Accounts,
+  { campaignUuid, depositRecords, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  const { treasury } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+  const { treasuryEscrow, treasuryMint } = treasury;
+
+  // Each DepositRecord is followed by its depositor, who receives the rent,
+  // and the NFT holder's NFT token account and payment account, which
+  // receives the DepositRecord's share of the supporter surplus.
+  const remainingAccounts = depositRecords.flatMap(
+    ({ depositor, mint, nftHolder = depositor }) => {
+      const [depositRecord] = findDepositRecordPda(
+        depositor,
+        mint,
+        program.programId
+      );
+      const [nftTokenAccount] = findAtaPda(nftHolder, mint);
+      const [surplusReceiver] = isMintNative(treasuryMint)
+        ? [nftHolder]
+        : findAtaPda(nftHolder, treasuryMint);
+      return [
+        { isSigner: false, isWritable: true, pubkey: depositRecord },
+        { isSigner: false, isWritable: true, pubkey: depositor },
+        { isSigner: false, isWritable: false, pubkey: nftTokenAccount },
+        { isSigner: false, isWritable: true, pubkey: surplusReceiver },
+      ];
+    }
+  );
+
+  return program.methods
+    .closeDepositRecords()
+    .accounts({
+      campaignEscrow,
+      payer,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryEscrow,
+    })
+    .remainingAccounts(remainingAccounts)
+    .instruction();
+}
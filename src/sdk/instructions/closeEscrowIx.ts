@@ -3,6 +3,8 @@ import { PublicKey, TransactionInstruction } from "@solana/web3.js";
 import { CampaignTreasuryManagerProgram } from "sdk/idl";
 import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
 import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
+import CampaignEscrowAccount from "sdk/types/CampaignEscrowAccount";
+import getRemainderReceiver from "sdk/utils/getRemainderReceiver";
 
 type Accounts = {
   authority: PublicKey;
@@ -29,6 +31,11 @@ export default async function closeEscrowIx(
     program.programId
   );
 
+  const campaignEscrowAccount = (await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  )) as any as CampaignEscrowAccount;
+  const remainderReceiver = getRemainderReceiver(campaignEscrowAccount);
+
   return program.methods
     .closeEscrow()
     .accounts({
@@ -37,6 +44,7 @@ export default async function closeEscrowIx(
       creator,
       payer,
       receiver,
+      remainderReceiver,
       tokenProgram: TOKEN_PROGRAM_ID,
       treasuryEscrow,
     })
@@ -0,0 +1,60 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findMatchingPoolEscrowPda from "sdk/pdas/findMatchingPoolEscrowPda";
+import findMatchingPoolPda from "sdk/pdas/findMatchingPoolPda";
+
+type Accounts = {
+  sponsor: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function closeMatchingPoolIx(
+  { sponsor }: Accounts,
+  { campaignUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [matchingPool] = findMatchingPoolPda(campaignEscrow, program.programId);
+  const [matchingPoolEscrow] = findMatchingPoolEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  const { treasury } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+  const { treasuryMint } = treasury;
+
+  const [sponsorPaymentAccount] = isMintNative(treasuryMint)
+    ? [sponsor]
+    : findAtaPda(sponsor, treasuryMint);
+
+  return program.methods
+    .closeMatchingPool()
+    .accounts({
+      campaignEscrow,
+      matchingPool,
+      matchingPoolEscrow,
+      sponsor,
+      sponsorPaymentAccount,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .instruction();
+}
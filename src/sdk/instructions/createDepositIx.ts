@@ -15,6 +15,9 @@ import { CampaignTreasuryManagerProgram } from "sdk/idl";
 import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
 import findDepositEscrowPda from "sdk/pdas/findDepositEscrowPda";
 import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import findMatchingPoolEscrowPda from "sdk/pdas/findMatchingPoolEscrowPda";
+import findMatchingPoolPda from "sdk/pdas/findMatchingPoolPda";
+import findTokenMetadataPda from "sdk/pdas/findTokenMetadataPda";
 
 type Accounts = {
   depositor: PublicKey;
@@ -26,11 +29,12 @@ type Args = {
   campaignUuid: string;
   depositAmount: BN;
   program: CampaignTreasuryManagerProgram;
+  rewardTierIndex?: number;
 };
 
 export default async function createDepositIx(
   { depositor, mint, saleCurrencyMint }: Accounts,
-  { campaignUuid, depositAmount, program }: Args
+  { campaignUuid, depositAmount, program, rewardTierIndex }: Args
 ): Promise<TransactionInstruction> {
   const [campaignEscrow] = findCampaignEscrowPda(
     campaignUuid,
@@ -47,6 +51,8 @@ export default async function createDepositIx(
     program.programId
   );
 
+  const [metadata] = findTokenMetadataPda(mint);
+
   const campaignEscrowAccount = await program.account.campaignEscrow.fetch(
     campaignEscrow
   );
@@ -64,8 +70,38 @@ export default async function createDepositIx(
     ? [depositor]
     : findAtaPda(depositor, saleCurrencyMint);
 
+  // Deposits in a currency with a price oracle are valued in the treasury
+  // currency, which requires the oracle and treasury mint accounts.
+  const { treasuryEscrow, treasuryMint } = campaignEscrowAccount.treasury;
+  const priceOracle = campaignEscrowAccount.depositEscrowInfos.find((info) =>
+    arePublicKeysEqual(info.mint, saleCurrencyMint)
+  )?.priceOracle;
+  const oracleAccounts =
+    priceOracle == null || arePublicKeysEqual(priceOracle, PublicKey.default)
+      ? []
+      : [
+          { isSigner: false, isWritable: false, pubkey: priceOracle },
+          { isSigner: false, isWritable: false, pubkey: treasuryMint },
+        ];
+
+  // Deposits are matched from the MatchingPool escrow into the treasury
+  // escrow, if the campaign has a MatchingPool. These accounts precede the
+  // price oracle accounts.
+  const [matchingPool] = findMatchingPoolPda(campaignEscrow, program.programId);
+  const [matchingPoolEscrow] = findMatchingPoolEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const matchingPoolAccounts = campaignEscrowAccount.hasMatchingPool
+    ? [
+        { isSigner: false, isWritable: true, pubkey: matchingPool },
+        { isSigner: false, isWritable: true, pubkey: matchingPoolEscrow },
+        { isSigner: false, isWritable: true, pubkey: treasuryEscrow },
+      ]
+    : [];
+
   return program.methods
-    .createDeposit(depositAmount)
+    .createDeposit(depositAmount, rewardTierIndex ?? null)
     .accounts({
       campaignEscrow,
       depositEscrow: depositEscrowAccount,
@@ -74,9 +110,11 @@ export default async function createDepositIx(
       depositor,
       depositorPaymentAccount,
       instructionSysvarAccount: SYSVAR_INSTRUCTIONS_PUBKEY,
+      metadata,
       mint,
       systemProgram: SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
     })
+    .remainingAccounts([...matchingPoolAccounts, ...oracleAccounts])
     .instruction();
 }
@@ -0,0 +1,35 @@
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+
+type Accounts = {
+  depositEscrowMint: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function disableDepositEscrowIx(
+  { depositEscrowMint }: Accounts,
+  { campaignUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  const { authority } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+
+  return program.methods
+    .disableDepositEscrow()
+    .accounts({
+      authority,
+      campaignEscrow,
+      depositEscrowMint,
+    })
+    .instruction();
+}
@@ -0,0 +1,78 @@
+import {
+  convertUuidToPdaSeed,
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import BN from "bn.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDonationRecordPda from "sdk/pdas/findDonationRecordPda";
+
+type Accounts = {
+  donor: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  donationAmount: BN;
+  donationUuid: string;
+  hasVoteWeight: boolean;
+  program: CampaignTreasuryManagerProgram;
+  refundable: boolean;
+};
+
+export default async function donateIx(
+  { donor }: Accounts,
+  {
+    campaignUuid,
+    donationAmount,
+    donationUuid,
+    hasVoteWeight,
+    program,
+    refundable,
+  }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [donationRecord] = findDonationRecordPda(
+    campaignEscrow,
+    donationUuid,
+    program.programId
+  );
+
+  const { treasury } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+  const { treasuryEscrow, treasuryMint } = treasury;
+
+  const [donorPaymentAccount] = isMintNative(treasuryMint)
+    ? [donor]
+    : findAtaPda(donor, treasuryMint);
+
+  return program.methods
+    .donate(
+      convertUuidToPdaSeed(donationUuid),
+      donationAmount,
+      refundable,
+      hasVoteWeight
+    )
+    .accounts({
+      campaignEscrow,
+      donationRecord,
+      donor,
+      donorPaymentAccount,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryEscrow,
+      treasuryMint,
+    })
+    .instruction();
+}
@@ -0,0 +1,67 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import BN from "bn.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findMatchingPoolEscrowPda from "sdk/pdas/findMatchingPoolEscrowPda";
+import findMatchingPoolPda from "sdk/pdas/findMatchingPoolPda";
+
+type Accounts = {
+  sponsor: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  matchBasisPoints: number;
+  matchCap: BN;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function fundMatchingPoolIx(
+  { sponsor }: Accounts,
+  { campaignUuid, matchBasisPoints, matchCap, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [matchingPool] = findMatchingPoolPda(campaignEscrow, program.programId);
+  const [matchingPoolEscrow] = findMatchingPoolEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  const { treasury } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+  const { treasuryMint } = treasury;
+
+  // The pool is funded in the treasury currency.
+  const [sponsorPaymentAccount] = isMintNative(treasuryMint)
+    ? [sponsor]
+    : findAtaPda(sponsor, treasuryMint);
+
+  return program.methods
+    .fundMatchingPool(matchBasisPoints, matchCap)
+    .accounts({
+      campaignEscrow,
+      matchingPool,
+      matchingPoolEscrow,
+      rent: SYSVAR_RENT_PUBKEY,
+      sponsor,
+      sponsorPaymentAccount,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryMint,
+    })
+    .instruction();
+}
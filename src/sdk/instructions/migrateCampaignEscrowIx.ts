@@ -0,0 +1,38 @@
+import { convertUuidToPdaSeed } from "@formfunction-hq/formfunction-program-shared";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+
+type Accounts = {
+  authority: PublicKey;
+  payer: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function migrateCampaignEscrowIx(
+  { authority, payer }: Accounts,
+  { campaignUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  return program.methods
+    .migrateCampaignEscrow(convertUuidToPdaSeed(campaignUuid))
+    .accounts({
+      authority,
+      campaignEscrow,
+      payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
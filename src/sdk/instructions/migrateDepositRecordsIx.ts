@@ -0,0 +1,52 @@
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import findTokenMetadataPda from "sdk/pdas/findTokenMetadataPda";
+import DepositRecordToClose from "sdk/types/DepositRecordToClose";
+
+type Accounts = {
+  authority: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  // Only for legacy CampaignEscrows which do not have a collectionMint yet.
+  collectionMint?: PublicKey;
+  depositRecords: Array<DepositRecordToClose>;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function migrateDepositRecordsIx(
+  { authority }: Accounts,
+  { campaignUuid, collectionMint, depositRecords, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  // Legacy DepositRecords are migrated in place, so each one is writable.
+  // The NFT metadata verifies that the NFT is in the campaign collection.
+  const remainingAccounts = depositRecords.flatMap(({ depositor, mint }) => {
+    const [depositRecord] = findDepositRecordPda(
+      depositor,
+      mint,
+      program.programId
+    );
+    const [metadata] = findTokenMetadataPda(mint);
+    return [
+      { isSigner: false, isWritable: true, pubkey: depositRecord },
+      { isSigner: false, isWritable: false, pubkey: metadata },
+    ];
+  });
+
+  return program.methods
+    .migrateDepositRecords(collectionMint ?? null)
+    .accounts({
+      authority,
+      campaignEscrow,
+    })
+    .remainingAccounts(remainingAccounts)
+    .instruction();
+}
@@ -0,0 +1,75 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
+
+type Accounts = {
+  // Receives the CampaignEscrow crankReward, if any. Defaults to the payer, or
+  // the payer's treasury mint ATA for non-native treasuries.
+  crankRewardReceiver?: PublicKey;
+  payer: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+/**
+ * Pays out every payout phase which is due, see payOutFundsIx.
+ */
+export default async function payOutAllDueFundsIx(
+  { crankRewardReceiver, payer }: Accounts,
+  { campaignUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [treasuryEscrow] = findTreasuryEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  const campaignEscrowAccount = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+
+  const { treasuryMint } = campaignEscrowAccount.treasury;
+  const [defaultCrankRewardReceiver] = isMintNative(treasuryMint)
+    ? [payer]
+    : findAtaPda(payer, treasuryMint);
+  // The crank reward receiver is only passed if a crank reward is paid.
+  const remainingAccounts =
+    "none" in campaignEscrowAccount.crankReward
+      ? []
+      : [
+          {
+            isSigner: false,
+            isWritable: true,
+            pubkey: crankRewardReceiver ?? defaultCrankRewardReceiver,
+          },
+        ];
+
+  return program.methods
+    .payOutAllDueFunds()
+    .accounts({
+      campaignEscrow,
+      payer,
+      payoutWallet: campaignEscrowAccount.payoutWallet,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryEscrow,
+    })
+    .remainingAccounts(remainingAccounts)
+    .instruction();
+}
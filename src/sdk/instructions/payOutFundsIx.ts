@@ -1,3 +1,7 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import {
   PublicKey,
@@ -9,6 +13,9 @@ import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
 import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
 
 type Accounts = {
+  // Receives the CampaignEscrow crankReward, if any. Defaults to the payer, or
+  // the payer's treasury mint ATA for non-native treasuries.
+  crankRewardReceiver?: PublicKey;
   payer: PublicKey;
 };
 
@@ -18,7 +25,7 @@ type Args = {
 };
 
 export default async function payOutFundsIx(
-  { payer }: Accounts,
+  { crankRewardReceiver, payer }: Accounts,
   { campaignUuid, program }: Args
 ): Promise<TransactionInstruction> {
   const [campaignEscrow] = findCampaignEscrowPda(
@@ -34,6 +41,22 @@ export default async function payOutFundsIx(
     campaignEscrow
   );
 
+  const { treasuryMint } = campaignEscrowAccount.treasury;
+  const [defaultCrankRewardReceiver] = isMintNative(treasuryMint)
+    ? [payer]
+    : findAtaPda(payer, treasuryMint);
+  // The crank reward receiver is only passed if a crank reward is paid.
+  const remainingAccounts =
+    "none" in campaignEscrowAccount.crankReward
+      ? []
+      : [
+          {
+            isSigner: false,
+            isWritable: true,
+            pubkey: crankRewardReceiver ?? defaultCrankRewardReceiver,
+          },
+        ];
+
   return program.methods
     .payOutFunds()
     .accounts({
@@ -44,5 +67,6 @@ export default async function payOutFundsIx(
       tokenProgram: TOKEN_PROGRAM_ID,
       treasuryEscrow,
     })
+    .remainingAccounts(remainingAccounts)
     .instruction();
 }
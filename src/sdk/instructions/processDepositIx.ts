@@ -1,20 +1,35 @@
-import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import BN from "bn.js";
 import { CampaignTreasuryManagerProgram } from "sdk/idl";
 import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDepositEscrowPda from "sdk/pdas/findDepositEscrowPda";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
 import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
 
 type Accounts = {
-  payer: PublicKey;
+  authority: PublicKey;
+  depositor: PublicKey;
+  mint: PublicKey;
 };
 
 type Args = {
   campaignUuid: string;
+  processedDepositAmount: BN;
   program: CampaignTreasuryManagerProgram;
 };
 
 export default async function processDepositIx(
-  { payer }: Accounts,
-  { campaignUuid, program }: Args
+  { authority, depositor, mint }: Accounts,
+  { campaignUuid, processedDepositAmount, program }: Args
 ): Promise<TransactionInstruction> {
   const [campaignEscrow] = findCampaignEscrowPda(
     campaignUuid,
@@ -24,13 +39,48 @@ export default async function processDepositIx(
     campaignUuid,
     program.programId
   );
+  const [depositRecord] = findDepositRecordPda(
+    depositor,
+    mint,
+    program.programId
+  );
+
+  const [campaignEscrowAccount, depositRecordAccount] = await Promise.all([
+    program.account.campaignEscrow.fetch(campaignEscrow),
+    program.account.depositRecord.fetch(depositRecord),
+  ]);
+  const { treasuryMint } = campaignEscrowAccount.treasury;
+  const { depositEscrowMint } = depositRecordAccount;
+
+  const [depositEscrow] = findDepositEscrowPda(
+    campaignUuid,
+    depositEscrowMint,
+    program.programId
+  );
+
+  // The authority swaps the deposit off-chain, so it receives the deposit and
+  // pays the processedDepositAmount in the treasury currency.
+  const [authorityDepositEscrowAccount] = isMintNative(depositEscrowMint)
+    ? [authority]
+    : findAtaPda(authority, depositEscrowMint);
+  const [authorityPaymentAccount] = isMintNative(treasuryMint)
+    ? [authority]
+    : findAtaPda(authority, treasuryMint);
 
   return program.methods
-    .processDeposit()
+    .processDeposit(processedDepositAmount)
     .accounts({
+      authority,
+      authorityDepositEscrowAccount,
+      authorityPaymentAccount,
       campaignEscrow,
-      payer,
+      depositEscrow,
+      depositEscrowMint,
+      depositRecord,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
       treasuryEscrow,
+      treasuryMint,
     })
     .instruction();
 }
@@ -0,0 +1,60 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDonationRecordPda from "sdk/pdas/findDonationRecordPda";
+
+type Accounts = {
+  donor: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  donationUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function processDonationRefundIx(
+  { donor }: Accounts,
+  { campaignUuid, donationUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [donationRecord] = findDonationRecordPda(
+    campaignEscrow,
+    donationUuid,
+    program.programId
+  );
+
+  const { treasury } = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+  const { treasuryEscrow, treasuryMint } = treasury;
+
+  const [refundReceiver] = isMintNative(treasuryMint)
+    ? [donor]
+    : findAtaPda(donor, treasuryMint);
+
+  return program.methods
+    .processDonationRefund()
+    .accounts({
+      campaignEscrow,
+      donationRecord,
+      donor,
+      refundReceiver,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryEscrow,
+    })
+    .instruction();
+}
@@ -1,9 +1,22 @@
-import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
 import { CampaignTreasuryManagerProgram } from "sdk/idl";
 import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
 import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
 
 type Accounts = {
+  depositor: PublicKey;
+  mint: PublicKey;
+  nftHolder: PublicKey;
   payer: PublicKey;
 };
 
@@ -13,7 +26,7 @@ type Args = {
 };
 
 export default async function processFullRefundIx(
-  { payer }: Accounts,
+  { depositor, mint, nftHolder, payer }: Accounts,
   { campaignUuid, program }: Args
 ): Promise<TransactionInstruction> {
   const [campaignEscrow] = findCampaignEscrowPda(
@@ -24,12 +37,36 @@ export default async function processFullRefundIx(
     campaignUuid,
     program.programId
   );
+  const [depositRecord] = findDepositRecordPda(
+    depositor,
+    mint,
+    program.programId
+  );
+  const [nftTokenAccount] = findAtaPda(nftHolder, mint);
+
+  const campaignEscrowAccount = await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  );
+  const { treasuryMint } = campaignEscrowAccount.treasury;
+
+  // The refund goes to the current NFT holder, not the original depositor.
+  const [refundReceiver] = isMintNative(treasuryMint)
+    ? [nftHolder]
+    : findAtaPda(nftHolder, treasuryMint);
 
   return program.methods
     .processFullRefund()
     .accounts({
       campaignEscrow,
+      depositRecord,
+      depositor,
+      nftHolder,
+      nftMint: mint,
+      nftTokenAccount,
       payer,
+      refundReceiver,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
       treasuryEscrow,
     })
     .instruction();
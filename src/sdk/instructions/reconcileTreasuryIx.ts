@@ -0,0 +1,56 @@
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
+import CampaignEscrowAccount from "sdk/types/CampaignEscrowAccount";
+import getSurplusReceiver from "sdk/utils/getSurplusReceiver";
+
+type Accounts = {
+  payer: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function reconcileTreasuryIx(
+  { payer }: Accounts,
+  { campaignUuid, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [treasuryEscrow] = findTreasuryEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+
+  const campaignEscrowAccount = (await program.account.campaignEscrow.fetch(
+    campaignEscrow
+  )) as any as CampaignEscrowAccount;
+
+  // The surplus receiver is not used if the surplus is held for supporters,
+  // so any account can be passed in that case.
+  const surplusReceiver =
+    getSurplusReceiver(campaignEscrowAccount) ??
+    campaignEscrowAccount.payoutWallet;
+
+  return program.methods
+    .reconcileTreasury()
+    .accounts({
+      campaignEscrow,
+      payer,
+      surplusReceiver,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      treasuryEscrow,
+    })
+    .instruction();
+}
@@ -0,0 +1,55 @@
+import { findAtaPda } from "@formfunction-hq/formfunction-program-shared";
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import findVoteRecordPda from "sdk/pdas/findVoteRecordPda";
+
+type Accounts = {
+  depositor: PublicKey;
+  mint: PublicKey;
+  nftHolder: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  payoutPhaseIndex: number;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function voteToVetoPayoutPhaseIx(
+  { depositor, mint, nftHolder }: Accounts,
+  { campaignUuid, payoutPhaseIndex, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [depositRecord] = findDepositRecordPda(
+    depositor,
+    mint,
+    program.programId
+  );
+  const [voteRecord] = findVoteRecordPda(
+    depositRecord,
+    payoutPhaseIndex,
+    program.programId
+  );
+  const [nftTokenAccount] = findAtaPda(nftHolder, mint);
+
+  return program.methods
+    .voteToVetoPayoutPhase(payoutPhaseIndex)
+    .accounts({
+      campaignEscrow,
+      depositRecord,
+      nftHolder,
+      nftTokenAccount,
+      systemProgram: SystemProgram.programId,
+      voteRecord,
+    })
+    .instruction();
+}
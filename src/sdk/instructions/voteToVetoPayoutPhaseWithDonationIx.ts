@@ -0,0 +1,52 @@
+import {
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import findCampaignEscrowPda from "sdk/pdas/findCampaignEscrowPda";
+import findDonationRecordPda from "sdk/pdas/findDonationRecordPda";
+import findVoteRecordPda from "sdk/pdas/findVoteRecordPda";
+
+type Accounts = {
+  donor: PublicKey;
+};
+
+type Args = {
+  campaignUuid: string;
+  donationUuid: string;
+  payoutPhaseIndex: number;
+  program: CampaignTreasuryManagerProgram;
+};
+
+export default async function voteToVetoPayoutPhaseWithDonationIx(
+  { donor }: Accounts,
+  { campaignUuid, donationUuid, payoutPhaseIndex, program }: Args
+): Promise<TransactionInstruction> {
+  const [campaignEscrow] = findCampaignEscrowPda(
+    campaignUuid,
+    program.programId
+  );
+  const [donationRecord] = findDonationRecordPda(
+    campaignEscrow,
+    donationUuid,
+    program.programId
+  );
+  // Donation votes use the DonationRecord in place of the DepositRecord.
+  const [voteRecord] = findVoteRecordPda(
+    donationRecord,
+    payoutPhaseIndex,
+    program.programId
+  );
+
+  return program.methods
+    .voteToVetoPayoutPhaseWithDonation(payoutPhaseIndex)
+    .accounts({
+      campaignEscrow,
+      donationRecord,
+      donor,
+      systemProgram: SystemProgram.programId,
+      voteRecord,
+    })
+    .instruction();
+}
@@ -0,0 +1,21 @@
+import {
+  convertUuidToPdaSeed,
+  PdaResult,
+} from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
+import { DONATION_RECORD_PREFIX } from "sdk/constants/AccountPrefixes";
+
+export default function findDonationRecordPda(
+  campaignEscrow: PublicKey,
+  donationUuid: string,
+  campaignTreasuryManagerProgramId: PublicKey
+): PdaResult {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(DONATION_RECORD_PREFIX),
+      campaignEscrow.toBuffer(),
+      Buffer.from(convertUuidToPdaSeed(donationUuid), "utf-8"),
+    ],
+    campaignTreasuryManagerProgramId
+  );
+}
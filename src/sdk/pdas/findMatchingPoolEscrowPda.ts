@@ -0,0 +1,19 @@
+import {
+  convertUuidToPdaSeed,
+  PdaResult,
+} from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
+import { MATCHING_POOL_ESCROW_PREFIX } from "sdk/constants/AccountPrefixes";
+
+export default function findMatchingPoolEscrowPda(
+  campaignUuid: string,
+  campaignTreasuryManagerProgramId: PublicKey
+): PdaResult {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(MATCHING_POOL_ESCROW_PREFIX),
+      Buffer.from(convertUuidToPdaSeed(campaignUuid), "utf-8"),
+    ],
+    campaignTreasuryManagerProgramId
+  );
+}
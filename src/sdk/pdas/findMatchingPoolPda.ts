@@ -0,0 +1,13 @@
+import { PdaResult } from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
+import { MATCHING_POOL_PREFIX } from "sdk/constants/AccountPrefixes";
+
+export default function findMatchingPoolPda(
+  campaignEscrow: PublicKey,
+  campaignTreasuryManagerProgramId: PublicKey
+): PdaResult {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(MATCHING_POOL_PREFIX), campaignEscrow.toBuffer()],
+    campaignTreasuryManagerProgramId
+  );
+}
@@ -0,0 +1,18 @@
+import { PdaResult } from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
+import { VOTE_RECORD_PREFIX } from "sdk/constants/AccountPrefixes";
+
+export default function findVoteRecordPda(
+  depositRecord: PublicKey,
+  payoutPhaseIndex: number,
+  campaignTreasuryManagerProgramId: PublicKey
+): PdaResult {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(VOTE_RECORD_PREFIX),
+      depositRecord.toBuffer(),
+      Buffer.from([payoutPhaseIndex]),
+    ],
+    campaignTreasuryManagerProgramId
+  );
+}
@@ -1,6 +1,8 @@
 import { CampaignTreasuryManagerProgram } from "sdk/idl";
+import CampaignEscrowTreasury from "sdk/types/CampaignEscrowTreasury";
 import DepositEscrowInfo from "sdk/types/DepositEscrowInfo";
 import PayoutPhases from "sdk/types/PayoutPhases";
+import RewardTiers from "sdk/types/RewardTiers";
 
 /**
  * Everything referenced in here needs to be kept in sync with the program.
@@ -15,12 +17,14 @@ type CampaignEscrowAnchorIdlType = Awaited<
 
 type CampaignEscrowAccountOmittedFields = Omit<
   CampaignEscrowAnchorIdlType,
-  "payoutPhases"
+  "payoutPhases" | "rewardTiers" | "treasury"
 >;
 
 type CampaignEscrowAccount = CampaignEscrowAccountOmittedFields & {
   depositEscrowInfos: Array<DepositEscrowInfo>;
   payoutPhases: PayoutPhases;
+  rewardTiers: RewardTiers;
+  treasury: CampaignEscrowTreasury;
 };
 
 export default CampaignEscrowAccount;
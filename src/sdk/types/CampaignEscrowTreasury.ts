@@ -1,9 +1,22 @@
 import { PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
 
+// Note: Keep in sync with program.
 type CampaignEscrowTreasury = {
-  totalDepositAmount: BN;
-  totalDepositCount: BN;
+  bump: number;
+  closedDepositAmount: BN;
+  nonRefundableDonations: BN;
+  nonVotingDonations: BN;
+  pendingEstimatedFunds: BN;
+  supporterSurplus: BN;
+  supporterSurplusPaidOut: BN;
+  totalCrankRewards: BN;
+  totalDonations: BN;
+  totalFunds: BN;
+  totalMatched: BN;
+  totalPaidOut: BN;
+  totalRefunded: BN;
+  totalSurplus: BN;
   treasuryEscrow: PublicKey;
   treasuryMint: PublicKey;
 };
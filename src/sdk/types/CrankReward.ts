@@ -0,0 +1,9 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program.
+type CrankReward =
+  | { none: Record<string, never> }
+  | { fixed: { amount: BN } }
+  | { basisPoints: { basis_points: number } };
+
+export default CrankReward;
@@ -1,11 +1,23 @@
+import { Maybe } from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
-import NonVotingPayoutPhaseInput from "sdk/types/NonVotingPayoutPhaseInput";
+import CrankReward from "sdk/types/CrankReward";
+import PayoutPhaseInput from "sdk/types/PayoutPhaseInput";
+import RewardTierInput from "sdk/types/RewardTierInput";
+import SurplusPolicy from "sdk/types/SurplusPolicy";
 
 // Note: Keep in sync with program.
 type CreateCampaignEscrowInput = {
+  autoEndAtHardCap: boolean;
   campaignEndTime: BN;
-  nonVotingPayoutPhases: Array<NonVotingPayoutPhaseInput>;
-  votingPayoutPhases: Array<never>;
+  collectionMint: PublicKey;
+  crankReward: CrankReward;
+  depositGracePeriod: BN;
+  fundraisingStartTime: Maybe<BN>;
+  hardCap: Maybe<BN>;
+  payoutPhases: Array<PayoutPhaseInput>;
+  rewardTiers: Array<RewardTierInput>;
+  surplusPolicy: SurplusPolicy;
 };
 
 export default CreateCampaignEscrowInput;
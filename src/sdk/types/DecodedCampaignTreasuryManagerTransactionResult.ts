@@ -12,6 +12,10 @@ const identity = <T>(val: T): T => val;
 
 const ixMap = CAMPAIGN_TREASURY_MANAGER_IDL.instructionsMap ?? {};
 
+const ClaimStreamedPayoutAccounts = (ixMap.claimStreamedPayout ?? []).map(
+  identity
+);
+
 const CloseDepositEscrowAccounts = (ixMap.closeDepositEscrow ?? []).map(
   identity
 );
@@ -20,8 +24,14 @@ const CloseDepositRecordAccounts = (ixMap.closeDepositRecord ?? []).map(
   identity
 );
 
+const CloseDepositRecordsAccounts = (ixMap.closeDepositRecords ?? []).map(
+  identity
+);
+
 const CloseEscrowAccounts = (ixMap.closeEscrow ?? []).map(identity);
 
+const CloseMatchingPoolAccounts = (ixMap.closeMatchingPool ?? []).map(identity);
+
 const CreateDepositAccounts = (ixMap.createDeposit ?? []).map(identity);
 
 const CreateDepositEscrowAccounts = (ixMap.createDepositEscrow ?? []).map(
@@ -30,21 +40,62 @@ const CreateDepositEscrowAccounts = (ixMap.createDepositEscrow ?? []).map(
 
 const CreateEscrowAccounts = (ixMap.createEscrow ?? []).map(identity);
 
+const DisableDepositEscrowAccounts = (ixMap.disableDepositEscrow ?? []).map(
+  identity
+);
+
+const DonateAccounts = (ixMap.donate ?? []).map(identity);
+
+const FundMatchingPoolAccounts = (ixMap.fundMatchingPool ?? []).map(identity);
+
+const MigrateCampaignEscrowAccounts = (ixMap.migrateCampaignEscrow ?? []).map(
+  identity
+);
+
+const MigrateDepositRecordsAccounts = (ixMap.migrateDepositRecords ?? []).map(
+  identity
+);
+
+const PayOutAllDueFundsAccounts = (ixMap.payOutAllDueFunds ?? []).map(identity);
+
 const PayOutFundsAccounts = (ixMap.payOutFunds ?? []).map(identity);
 
 const ProcessDepositAccounts = (ixMap.processDeposit ?? []).map(identity);
 
+const ProcessDonationRefundAccounts = (ixMap.processDonationRefund ?? []).map(
+  identity
+);
+
 const ProcessFullRefundAccounts = (ixMap.processFullRefund ?? []).map(identity);
 
 const ProcessPartialRefundAccounts = (ixMap.processPartialRefund ?? []).map(
   identity
 );
 
+const ReconcileTreasuryAccounts = (ixMap.reconcileTreasury ?? []).map(identity);
+
+const SetDepositEscrowPriceOracleAccounts = (ixMap.setDepositEscrowPriceOracle ?? []).map(
+  identity
+);
+
 const UpdateEscrowAccounts = (ixMap.updateEscrow ?? []).map(identity);
 
 const VetoPayoutPhaseAccounts = (ixMap.vetoPayoutPhase ?? []).map(identity);
 
+const VoteToVetoPayoutPhaseAccounts = (ixMap.voteToVetoPayoutPhase ?? []).map(
+  identity
+);
+
+const VoteToVetoPayoutPhaseWithDonationAccounts = (ixMap.voteToVetoPayoutPhaseWithDonation ?? []).map(
+  identity
+);
+
 type DecodedCampaignTreasuryManagerTransactionResult = {
+  claimStreamedPayout?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof ClaimStreamedPayoutAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
   closeDepositEscrow?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
     accountsMap: {
       [Key in typeof CloseDepositEscrowAccounts[0]]: DecodedInstructionAccount;
@@ -55,11 +106,21 @@ type DecodedCampaignTreasuryManagerTransactionResult = {
       [Key in typeof CloseDepositRecordAccounts[0]]: DecodedInstructionAccount;
     };
   };
+  closeDepositRecords?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof CloseDepositRecordsAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
   closeEscrow?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
     accountsMap: {
       [Key in typeof CloseEscrowAccounts[0]]: DecodedInstructionAccount;
     };
   };
+  closeMatchingPool?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof CloseMatchingPoolAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
   createDeposit?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
     accountsMap: {
       [Key in typeof CreateDepositAccounts[0]]: DecodedInstructionAccount;
@@ -75,6 +136,36 @@ type DecodedCampaignTreasuryManagerTransactionResult = {
       [Key in typeof CreateEscrowAccounts[0]]: DecodedInstructionAccount;
     };
   };
+  disableDepositEscrow?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof DisableDepositEscrowAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  donate?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof DonateAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  fundMatchingPool?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof FundMatchingPoolAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  migrateCampaignEscrow?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof MigrateCampaignEscrowAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  migrateDepositRecords?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof MigrateDepositRecordsAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  payOutAllDueFunds?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof PayOutAllDueFundsAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
   payOutFunds?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
     accountsMap: {
       [Key in typeof PayOutFundsAccounts[0]]: DecodedInstructionAccount;
@@ -85,6 +176,11 @@ type DecodedCampaignTreasuryManagerTransactionResult = {
       [Key in typeof ProcessDepositAccounts[0]]: DecodedInstructionAccount;
     };
   };
+  processDonationRefund?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof ProcessDonationRefundAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
   processFullRefund?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
     accountsMap: {
       [Key in typeof ProcessFullRefundAccounts[0]]: DecodedInstructionAccount;
@@ -95,6 +191,16 @@ type DecodedCampaignTreasuryManagerTransactionResult = {
       [Key in typeof ProcessPartialRefundAccounts[0]]: DecodedInstructionAccount;
     };
   };
+  reconcileTreasury?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof ReconcileTreasuryAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  setDepositEscrowPriceOracle?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof SetDepositEscrowPriceOracleAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
   updateEscrow?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
     accountsMap: {
       [Key in typeof UpdateEscrowAccounts[0]]: DecodedInstructionAccount;
@@ -105,6 +211,16 @@ type DecodedCampaignTreasuryManagerTransactionResult = {
       [Key in typeof VetoPayoutPhaseAccounts[0]]: DecodedInstructionAccount;
     };
   };
+  voteToVetoPayoutPhase?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof VoteToVetoPayoutPhaseAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
+  voteToVetoPayoutPhaseWithDonation?: GenericDecodedTransaction<CampaignTreasuryManagerInstructionName> & {
+    accountsMap: {
+      [Key in typeof VoteToVetoPayoutPhaseWithDonationAccounts[0]]: DecodedInstructionAccount;
+    };
+  };
 };
 
 export default DecodedCampaignTreasuryManagerTransactionResult;
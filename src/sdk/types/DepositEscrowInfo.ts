@@ -2,8 +2,11 @@ import { PublicKey } from "@solana/web3.js";
 
 // Note: Keep in sync with program.
 type DepositEscrowInfo = {
+  closed: boolean;
   deposit_escrow_bump: number;
+  disabled: boolean;
   mint: PublicKey;
+  priceOracle: PublicKey;
 };
 
 export default DepositEscrowInfo;
@@ -0,0 +1,12 @@
+import { PublicKey } from "@solana/web3.js";
+
+type DepositRecordToClose = {
+  depositor: PublicKey;
+  // The NFT mint the deposit was made for.
+  mint: PublicKey;
+  // Current holder of the NFT, who receives the supporter surplus. Defaults
+  // to the depositor.
+  nftHolder?: PublicKey;
+};
+
+export default DepositRecordToClose;
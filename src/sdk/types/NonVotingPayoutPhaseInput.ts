@@ -1,8 +0,0 @@
-import SharedPayoutPhaseFields from "sdk/types/SharedPayoutPhaseFields";
-
-// Note: Keep in sync with program.
-type NonVotingPayoutPhaseInput = {
-  sharedFields: SharedPayoutPhaseFields;
-};
-
-export default NonVotingPayoutPhaseInput;
@@ -0,0 +1,22 @@
+import { Maybe } from "@formfunction-hq/formfunction-program-shared";
+import BN from "bn.js";
+import PayoutStream from "sdk/types/PayoutStream";
+import StretchGoal from "sdk/types/StretchGoal";
+import VotingConfig from "sdk/types/VotingConfig";
+
+// Note: Keep in sync with program.
+type PayoutPhase = {
+  description: string;
+  isDeactivated: boolean;
+  isPaidOut: boolean;
+  isVetoedByAuthority: boolean;
+  paidAmount: BN;
+  payoutBasisPoints: number;
+  payoutTime: BN;
+  refundDeadline: BN;
+  stream: Maybe<PayoutStream>;
+  stretchGoal: Maybe<StretchGoal>;
+  votingConfig: Maybe<VotingConfig>;
+};
+
+export default PayoutPhase;
@@ -0,0 +1,18 @@
+import { Maybe } from "@formfunction-hq/formfunction-program-shared";
+import BN from "bn.js";
+import PayoutStreamInput from "sdk/types/PayoutStreamInput";
+import StretchGoal from "sdk/types/StretchGoal";
+import VotingConfigInput from "sdk/types/VotingConfigInput";
+
+// Note: Keep in sync with program.
+type PayoutPhaseInput = {
+  description: string;
+  payoutBasisPoints: number;
+  payoutTime: BN;
+  refundDeadline: BN;
+  stream: Maybe<PayoutStreamInput>;
+  stretchGoal: Maybe<StretchGoal>;
+  votingConfig: Maybe<VotingConfigInput>;
+};
+
+export default PayoutPhaseInput;
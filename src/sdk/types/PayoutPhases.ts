@@ -1,10 +1,8 @@
-import NonVotingPayoutPhase from "sdk/types/NonVotingPayoutPhase";
-import VotingPayoutPhase from "sdk/types/VotingPayoutPhase";
+import PayoutPhase from "sdk/types/PayoutPhase";
 
-// Note: Keep in sync with program.
+// Note: Keep in sync with program. Payout phases are stored in payout order.
 type PayoutPhases = {
-  nonVotingPayoutPhases: Array<NonVotingPayoutPhase>;
-  votingPayoutPhases: Array<VotingPayoutPhase>;
+  payoutPhases: Array<PayoutPhase>;
 };
 
 export default PayoutPhases;
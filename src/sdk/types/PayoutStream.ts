@@ -0,0 +1,11 @@
+import { Maybe } from "@formfunction-hq/formfunction-program-shared";
+import BN from "bn.js";
+
+// Note: Keep in sync with program. Nothing vests after the vetoTime, which is
+// set when the authority vetoes the payout phase.
+type PayoutStream = {
+  streamEndTime: BN;
+  vetoTime: Maybe<BN>;
+};
+
+export default PayoutStream;
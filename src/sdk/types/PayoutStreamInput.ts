@@ -0,0 +1,9 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program. The payout vests linearly from the payout
+// time until the streamEndTime.
+type PayoutStreamInput = {
+  streamEndTime: BN;
+};
+
+export default PayoutStreamInput;
@@ -0,0 +1,11 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program.
+type RewardTier = {
+  maxSupply: number;
+  metadataUri: string;
+  minDepositAmount: BN;
+  soldCount: number;
+};
+
+export default RewardTier;
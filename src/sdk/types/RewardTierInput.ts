@@ -0,0 +1,10 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program.
+type RewardTierInput = {
+  maxSupply: number;
+  metadataUri: string;
+  minDepositAmount: BN;
+};
+
+export default RewardTierInput;
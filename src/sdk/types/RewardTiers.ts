@@ -0,0 +1,9 @@
+import RewardTier from "sdk/types/RewardTier";
+
+// Note: Keep in sync with program. Reward tiers are stored in the order they
+// were configured, which is the order of the reward_tier_index.
+type RewardTiers = {
+  rewardTiers: Array<RewardTier>;
+};
+
+export default RewardTiers;
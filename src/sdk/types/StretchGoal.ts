@@ -0,0 +1,10 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program. The payout phase is active when the final
+// raise reaching the raiseThreshold matches isActiveWhenReached.
+type StretchGoal = {
+  isActiveWhenReached: boolean;
+  raiseThreshold: BN;
+};
+
+export default StretchGoal;
@@ -0,0 +1,7 @@
+// Note: Keep in sync with program.
+type SurplusPolicy =
+  | { creator: Record<string, never> }
+  | { supporters: Record<string, never> }
+  | { authority: Record<string, never> };
+
+export default SurplusPolicy;
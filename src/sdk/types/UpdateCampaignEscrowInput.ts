@@ -1,15 +1,30 @@
 import { Maybe } from "@formfunction-hq/formfunction-program-shared";
 import { PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
-import NonVotingPayoutPhaseInput from "sdk/types/NonVotingPayoutPhaseInput";
+import CrankReward from "sdk/types/CrankReward";
+import PayoutPhaseInput from "sdk/types/PayoutPhaseInput";
+import RewardTierInput from "sdk/types/RewardTierInput";
+import SurplusPolicy from "sdk/types/SurplusPolicy";
 
 // Note: Keep in sync with program.
 type UpdateCampaignEscrowInput = {
   authority: Maybe<PublicKey>;
+  autoEndAtHardCap: Maybe<boolean>;
   campaignEndTime: Maybe<BN>;
+  collectionMint: Maybe<PublicKey>;
+  crankReward: Maybe<CrankReward>;
   creator: Maybe<PublicKey>;
-  nonVotingPayoutPhases: Maybe<Array<NonVotingPayoutPhaseInput>>;
-  votingPayoutPhases: Maybe<Array<never>>;
+  depositGracePeriod: Maybe<BN>;
+  depositEscrowInfosCapacity: Maybe<number>;
+  // The program also accepts Some(None) to clear the fundraising start time
+  // before the first deposit, but Anchor encodes null as None, so the SDK can
+  // only leave it unchanged or set it.
+  fundraisingStartTime: Maybe<BN>;
+  // As with the fundraisingStartTime, the SDK cannot clear the hard cap.
+  hardCap: Maybe<BN>;
+  payoutPhases: Maybe<Array<PayoutPhaseInput>>;
+  rewardTiers: Maybe<Array<RewardTierInput>>;
+  surplusPolicy: Maybe<SurplusPolicy>;
 };
 
 export default UpdateCampaignEscrowInput;
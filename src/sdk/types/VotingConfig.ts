@@ -0,0 +1,10 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program.
+type VotingConfig = {
+  vetoVotes: BN;
+  voteBasisPointsVetoThreshold: BN;
+  votingStartTime: BN;
+};
+
+export default VotingConfig;
@@ -0,0 +1,9 @@
+import BN from "bn.js";
+
+// Note: Keep in sync with program. Veto votes always start at zero.
+type VotingConfigInput = {
+  voteBasisPointsVetoThreshold: BN;
+  votingStartTime: BN;
+};
+
+export default VotingConfigInput;
@@ -1,13 +1,14 @@
 import { Maybe } from "@formfunction-hq/formfunction-program-shared";
+import PayoutPhase from "sdk/types/PayoutPhase";
 import PayoutPhases from "sdk/types/PayoutPhases";
-import PayoutPhaseUnion from "sdk/types/PayoutPhaseUnion";
 import getPayoutPhasesOrderedArray from "sdk/utils/getPayoutPhasesOrderedArray";
 
 export default function getCurrentActivePayoutPhase(
   payoutPhases: PayoutPhases
-): Maybe<PayoutPhaseUnion> {
+): Maybe<PayoutPhase> {
   for (const payoutPhase of getPayoutPhasesOrderedArray(payoutPhases)) {
-    if (!payoutPhase.isPaidOut) {
+    // Payout phases deactivated by their stretch goal are skipped.
+    if (!payoutPhase.isPaidOut && !payoutPhase.isDeactivated) {
       return payoutPhase;
     }
   }
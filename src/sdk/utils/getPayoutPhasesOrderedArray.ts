@@ -1,13 +1,14 @@
+import PayoutPhase from "sdk/types/PayoutPhase";
 import PayoutPhases from "sdk/types/PayoutPhases";
-import PayoutPhaseUnion from "sdk/types/PayoutPhaseUnion";
 
-type PayoutPhasesOrderedArray = Array<PayoutPhaseUnion>;
+type PayoutPhasesOrderedArray = Array<PayoutPhase>;
 
+/**
+ * The program stores payout phases as a single list in payout order, so this
+ * just returns a copy of that list.
+ */
 export default function getPayoutPhasesOrderedArray(
   payoutPhases: PayoutPhases
 ): PayoutPhasesOrderedArray {
-  return [
-    ...payoutPhases.nonVotingPayoutPhases,
-    ...payoutPhases.votingPayoutPhases,
-  ].sort((a, b) => a.sharedFields.index - b.sharedFields.index);
+  return [...payoutPhases.payoutPhases];
 }
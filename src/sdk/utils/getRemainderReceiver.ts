@@ -0,0 +1,27 @@
+import {
+  findAtaPda,
+  isMintNative,
+} from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
+import CampaignEscrowAccount from "sdk/types/CampaignEscrowAccount";
+
+/**
+ * Returns the account which the remaining treasury funds, i.e. rounding dust
+ * and unclaimed refunds, are transferred to when the escrow is closed.
+ *
+ * Note: Keep in sync with CampaignEscrow::get_remainder_receiver.
+ */
+export default function getRemainderReceiver(
+  campaignEscrowAccount: CampaignEscrowAccount
+): PublicKey {
+  const { authority, payoutWallet, surplusPolicy, treasury } =
+    campaignEscrowAccount;
+
+  if ("authority" in surplusPolicy) {
+    return isMintNative(treasury.treasuryMint)
+      ? authority
+      : findAtaPda(authority, treasury.treasuryMint)[0];
+  }
+
+  return payoutWallet;
+}
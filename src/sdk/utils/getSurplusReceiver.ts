@@ -0,0 +1,20 @@
+import { Maybe } from "@formfunction-hq/formfunction-program-shared";
+import { PublicKey } from "@solana/web3.js";
+import CampaignEscrowAccount from "sdk/types/CampaignEscrowAccount";
+import getRemainderReceiver from "sdk/utils/getRemainderReceiver";
+
+/**
+ * Returns the account which surplus treasury funds are transferred to, or
+ * null if the surplus is held in the treasury escrow for supporters.
+ *
+ * Note: Keep in sync with CampaignEscrow::get_surplus_receiver in the program.
+ */
+export default function getSurplusReceiver(
+  campaignEscrowAccount: CampaignEscrowAccount
+): Maybe<PublicKey> {
+  if ("supporters" in campaignEscrowAccount.surplusPolicy) {
+    return null;
+  }
+
+  return getRemainderReceiver(campaignEscrowAccount);
+}
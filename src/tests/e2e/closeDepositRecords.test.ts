@@ -0,0 +1,124 @@
+import { expectNumbersEqual } from "@formfunction-hq/formfunction-program-shared";
+import dayjs from "dayjs";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import getPayoutPhasesOrderedArray from "sdk/utils/getPayoutPhasesOrderedArray";
+import closeCampaignEscrowForTest from "tests/utils/closeCampaignEscrowForTest";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositsForTest from "tests/utils/createDepositsForTest";
+import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import payoutFundsForTest from "tests/utils/payoutFundsForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+import waitMinDelayForTimeBuffer from "tests/utils/waitMinDelayForTimeBuffer";
+
+describe("CloseDepositRecords instruction.", () => {
+  test("DepositRecords cannot be closed before all payouts are complete.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+    const { campaignUuid } = setupResult;
+
+    const { deposits } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [1],
+      depositCurrency: setupResult.campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const tx = await sdk.closeDepositRecordsTx(
+      { payer: authority.publicKey },
+      { campaignUuid, depositRecords: deposits }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidCloseEscrow",
+      signers: [authority],
+      transaction: tx,
+    });
+  });
+
+  test("DepositRecords can be closed in a batch once all payouts are complete.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+    const { campaignUuid, creator } = setupResult;
+
+    const { deposits, totalDepositAmount } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [1, 2],
+      depositCurrency: setupResult.campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    await payoutFundsForTest({
+      authority,
+      connection,
+      sdk,
+      setupResult,
+    });
+
+    const payoutPhase = getPayoutPhasesOrderedArray(
+      setupResult.campaignEscrowAccount.payoutPhases
+    )[0];
+    const refundDeadlineTime = payoutPhase!.refundDeadline;
+    const minDelay = refundDeadlineTime.toNumber() - dayjs().unix();
+    await waitMinDelayForTimeBuffer(minDelay);
+
+    const tx = await sdk.closeDepositRecordsTx(
+      { payer: authority.publicKey },
+      { campaignUuid, depositRecords: deposits }
+    );
+    await sendTransactionForTest(connection, tx, [authority]);
+
+    await Promise.all(
+      deposits.map(async ({ depositor, mint }) => {
+        const [depositRecord] = findDepositRecordPda(
+          depositor,
+          mint,
+          sdk.programId
+        );
+        expect(await connection.getAccountInfo(depositRecord)).toBe(null);
+      })
+    );
+
+    const campaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      campaignEscrowAccount.closedDepositRecordCount,
+      deposits.length
+    );
+    expectNumbersEqual(
+      campaignEscrowAccount.treasury.closedDepositAmount,
+      totalDepositAmount
+    );
+
+    // Closing the same DepositRecords again is a no-op.
+    const retryTx = await sdk.closeDepositRecordsTx(
+      { payer: authority.publicKey },
+      { campaignUuid, depositRecords: deposits }
+    );
+    await sendTransactionForTest(connection, retryTx, [authority]);
+
+    await closeCampaignEscrowForTest({
+      authority,
+      campaignUuid,
+      connection,
+      creator,
+      sdk,
+      signer: authority,
+    });
+  });
+});
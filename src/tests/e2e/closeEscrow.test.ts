@@ -6,7 +6,7 @@ import createDepositsForTest from "tests/utils/createDepositsForTest";
 import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
 import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
 import getDefaultCampaignEndTimeForTest from "tests/utils/getDefaultCampaignEndTimeForTest";
-import getDefaultNonVotingSharedFields from "tests/utils/payout-phases/getDefaultNonVotingSharedFields";
+import getDefaultPayoutPhaseInput from "tests/utils/payout-phases/getDefaultPayoutPhaseInput";
 import getSecondsAfterCampaignEndTime from "tests/utils/payout-phases/getSecondsAfterCampaignEndTime";
 import payoutFundsForTest from "tests/utils/payoutFundsForTest";
 import sendTransactionForTest from "tests/utils/sendTransactionForTest";
@@ -22,18 +22,15 @@ describe("CloseEscrow instruction.", () => {
       sdk,
       setupOptions: {
         payoutPhases: {
-          nonVotingPayoutPhases: [
+          payoutPhases: [
             {
-              sharedFields: {
-                ...getDefaultNonVotingSharedFields(),
-                refundDeadline: getSecondsAfterCampaignEndTime(
-                  getDefaultCampaignEndTimeForTest(),
-                  15
-                ),
-              },
+              ...getDefaultPayoutPhaseInput(),
+              refundDeadline: getSecondsAfterCampaignEndTime(
+                getDefaultCampaignEndTimeForTest(),
+                15
+              ),
             },
           ],
-          votingPayoutPhases: [],
         },
       },
     });
@@ -122,8 +119,7 @@ describe("CloseEscrow instruction.", () => {
       setupResult.campaignEscrowAccount.payoutPhases
     )[0];
 
-    const refundDeadlineTime =
-      nextActivePayoutPhase!.sharedFields.refundDeadline;
+    const refundDeadlineTime = nextActivePayoutPhase!.refundDeadline;
     const minDelay = refundDeadlineTime.toNumber() - dayjs().unix();
     await waitMinDelayForTimeBuffer(minDelay);
 
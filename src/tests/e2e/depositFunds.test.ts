@@ -10,10 +10,12 @@ import {
   sumArray,
 } from "@formfunction-hq/formfunction-program-shared";
 import { NATIVE_MINT } from "@solana/spl-token";
+import BN from "bn.js";
 import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
 import createDepositForTest from "tests/utils/createDepositForTest";
 import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
 import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import mintCampaignNftForTest from "tests/utils/mintCampaignNftForTest";
 import sendTransactionForTest from "tests/utils/sendTransactionForTest";
 
 describe("CreateDeposit instruction.", () => {
@@ -35,10 +37,13 @@ describe("CreateDeposit instruction.", () => {
     });
 
     await forEachAsync(buyers, async (buyer, index) => {
-      const masterEditionMint = await mintMasterEditionForTest(
+      const masterEditionMint = await mintCampaignNftForTest({
+        authority,
         buyer,
-        connection
-      );
+        campaignUuid,
+        connection,
+        sdk,
+      });
       const depositAmount = depositAmounts[index];
       await createDepositForTest({
         buyer,
@@ -89,10 +94,13 @@ describe("CreateDeposit instruction.", () => {
     });
 
     await forEachAsync(buyers, async (buyerKeypair, index) => {
-      const masterEditionMint = await mintMasterEditionForTest(
-        buyerKeypair,
-        connection
-      );
+      const masterEditionMint = await mintCampaignNftForTest({
+        authority,
+        buyer: buyerKeypair,
+        campaignUuid,
+        connection,
+        sdk,
+      });
       const depositAmount = depositAmounts[index];
       await fundSplTokenAtas(
         connection,
@@ -148,10 +156,13 @@ describe("CreateDeposit instruction.", () => {
     });
 
     await forEachAsync(buyers, async (buyerKeypair, index) => {
-      const masterEditionMint = await mintMasterEditionForTest(
-        buyerKeypair,
-        connection
-      );
+      const masterEditionMint = await mintCampaignNftForTest({
+        authority,
+        buyer: buyerKeypair,
+        campaignUuid,
+        connection,
+        sdk,
+      });
       const depositAmount = solToLamports(depositAmounts[index]);
       await createDepositForTest({
         buyer: buyerKeypair,
@@ -186,6 +197,37 @@ describe("CreateDeposit instruction.", () => {
     expectNumbersEqual(campaignEscrowAccount.treasury.totalFunds, 0);
   });
 
+  test("Deposits cannot be created for NFTs which are not verified members of the campaign collection.", async () => {
+    const { connection, authority, otherKeypair: buyer, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignUuid } = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+
+    const mint = await mintMasterEditionForTest(buyer, connection);
+    const transaction = await sdk.createDepositTx(
+      {
+        depositor: buyer.publicKey,
+        mint,
+        saleCurrencyMint: NATIVE_MINT,
+      },
+      {
+        campaignUuid,
+        depositAmount: new BN(solToLamports(1)),
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidNftCollection",
+      signers: [buyer],
+      transaction,
+    });
+  });
+
   test("CreateDepositEscrow requires the CampaignEscrow authority as a signer.", async () => {
     const { connection, authority, otherKeypair, sdk } =
       await getConnectionAndSdkForTest();
@@ -317,4 +359,174 @@ describe("CreateDeposit instruction.", () => {
       )
     ).toBe(true);
   });
+
+  test("Deposits cannot be created in a disabled deposit escrow currency.", async () => {
+    const { connection, authority, otherKeypair: buyer, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignUuid, depositEscrowCurrencies } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: { enableDepositEscrowCurrencies: 1 },
+      });
+
+    const depositEscrowMint = depositEscrowCurrencies[0];
+
+    const disableTx = await sdk.disableDepositEscrowTx(
+      {
+        depositEscrowMint,
+      },
+      {
+        campaignUuid,
+      }
+    );
+
+    await sendTransactionForTest(connection, disableTx, [authority]);
+
+    const campaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expect(campaignEscrowAccount.depositEscrowInfos[0].disabled).toBe(true);
+
+    const mint = await mintCampaignNftForTest({
+      authority,
+      buyer,
+      campaignUuid,
+      connection,
+      sdk,
+    });
+    const depositAmount = 10;
+    await fundSplTokenAtas(
+      connection,
+      [buyer.publicKey],
+      depositEscrowMint,
+      authority,
+      depositAmount
+    );
+
+    const transaction = await sdk.createDepositTx(
+      {
+        depositor: buyer.publicKey,
+        mint,
+        saleCurrencyMint: depositEscrowMint,
+      },
+      {
+        campaignUuid,
+        depositAmount: new BN(depositAmount),
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "DepositEscrowDisabled",
+      signers: [buyer],
+      transaction,
+    });
+  });
+
+  test("A deposit escrow price oracle must be a Pyth price account.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const { campaignUuid, depositEscrowCurrencies } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: { enableDepositEscrowCurrencies: 1 },
+      });
+
+    const depositEscrowMint = depositEscrowCurrencies[0];
+
+    const transaction = await sdk.setDepositEscrowPriceOracleTx(
+      {
+        depositEscrowMint,
+        priceOracle: depositEscrowMint,
+      },
+      {
+        campaignUuid,
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidPriceOracle",
+      signers: [authority],
+      transaction,
+    });
+  });
+
+  test("Deposits can claim a reward tier until it sells out.", async () => {
+    const { connection, authority, otherKeypair: buyer, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const minDepositAmount = solToLamports(1);
+    const { campaignUuid } = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: {
+        rewardTiers: [
+          {
+            maxSupply: 1,
+            metadataUri: "https://example.com/reward-tier.json",
+            minDepositAmount: new BN(minDepositAmount),
+          },
+        ],
+      },
+    });
+
+    const masterEditionMint = await mintCampaignNftForTest({
+      authority,
+      buyer,
+      campaignUuid,
+      connection,
+      sdk,
+    });
+    await createDepositForTest({
+      buyer,
+      campaignUuid,
+      connection,
+      depositAmount: minDepositAmount,
+      depositCurrency: NATIVE_MINT,
+      masterEditionMint,
+      rewardTierIndex: 0,
+      sdk,
+    });
+
+    const campaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      campaignEscrowAccount.rewardTiers.rewardTiers[0].soldCount,
+      1
+    );
+
+    // A second claim on the tier exceeds its max supply.
+    const mint = await mintCampaignNftForTest({
+      authority,
+      buyer,
+      campaignUuid,
+      connection,
+      sdk,
+    });
+    const transaction = await sdk.createDepositTx(
+      {
+        depositor: buyer.publicKey,
+        mint,
+        saleCurrencyMint: NATIVE_MINT,
+      },
+      {
+        campaignUuid,
+        depositAmount: new BN(minDepositAmount),
+        rewardTierIndex: 0,
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "RewardTierSoldOut",
+      signers: [buyer],
+      transaction,
+    });
+  });
 });
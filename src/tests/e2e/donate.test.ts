@@ -0,0 +1,348 @@
+import {
+  convertUuidToPdaSeed,
+  expectNumbersEqual,
+  expectPublicKeysEqual,
+  sleep,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import BN from "bn.js";
+import dayjs from "dayjs";
+import findDonationRecordPda from "sdk/pdas/findDonationRecordPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import getDefaultPayoutPhaseInput from "tests/utils/payout-phases/getDefaultPayoutPhaseInput";
+import getPayoutPhasesForTest from "tests/utils/payout-phases/getPayoutPhasesForTest";
+import getSecondsAfterCampaignEndTime from "tests/utils/payout-phases/getSecondsAfterCampaignEndTime";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+import uuid from "tests/utils/uuid";
+import waitMinDelayForTimeBuffer from "tests/utils/waitMinDelayForTimeBuffer";
+
+describe("Test Donate instruction.", () => {
+  test("Donations are added to the treasury.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+      });
+    const { treasuryEscrow } = campaignEscrowAccount.treasury;
+
+    const donationAmount = solToLamports(1);
+    const donationUuid = uuid();
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+
+    const tx = await sdk.donateTx(
+      { donor: donor.publicKey },
+      {
+        campaignUuid,
+        donationAmount: new BN(donationAmount),
+        donationUuid,
+        hasVoteWeight: false,
+        refundable: true,
+      }
+    );
+    await sendTransactionForTest(connection, tx, [donor]);
+
+    expectNumbersEqual(
+      (await connection.getBalance(treasuryEscrow)) - startingTreasuryBalance,
+      donationAmount
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    const { treasury } = updatedCampaignEscrowAccount;
+    expectNumbersEqual(treasury.totalDonations, donationAmount);
+    expectNumbersEqual(treasury.nonVotingDonations, donationAmount);
+    expectNumbersEqual(treasury.totalFunds, donationAmount);
+
+    const [campaignEscrow] = await sdk.findCampaignEscrowPda(campaignUuid);
+    const [donationRecord] = findDonationRecordPda(
+      campaignEscrow,
+      donationUuid,
+      sdk.programId
+    );
+    const donationRecordAccount =
+      await sdk.program.account.donationRecord.fetch(donationRecord);
+    expectPublicKeysEqual(donationRecordAccount.campaignEscrow, campaignEscrow);
+    expect(donationRecordAccount.donationUuid).toBe(
+      convertUuidToPdaSeed(donationUuid)
+    );
+    expectPublicKeysEqual(donationRecordAccount.donor, donor.publicKey);
+    expectNumbersEqual(donationRecordAccount.donationAmount, donationAmount);
+    expect(donationRecordAccount.refundable).toBe(true);
+    expect(donationRecordAccount.hasVoteWeight).toBe(false);
+  });
+
+  test("Donations must be greater than zero.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignUuid } = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+
+    const transaction = await sdk.donateTx(
+      { donor: donor.publicKey },
+      {
+        campaignUuid,
+        donationAmount: new BN(0),
+        donationUuid: uuid(),
+        hasVoteWeight: false,
+        refundable: false,
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidDonation",
+      signers: [donor],
+      transaction,
+    });
+  });
+
+  test("Donations with vote weight can vote to veto a payout phase.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid voting payout phase."
+    )!;
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: { payoutPhases },
+      });
+
+    const donationAmount = solToLamports(2);
+    const donationUuid = uuid();
+    const donateTx = await sdk.donateTx(
+      { donor: donor.publicKey },
+      {
+        campaignUuid,
+        donationAmount: new BN(donationAmount),
+        donationUuid,
+        hasVoteWeight: true,
+        refundable: false,
+      }
+    );
+    await sendTransactionForTest(connection, donateTx, [donor]);
+
+    const payoutPhaseIndex = 1;
+    const { votingConfig } =
+      campaignEscrowAccount.payoutPhases.payoutPhases[payoutPhaseIndex];
+    // Votes are only accepted before the payout time, so this cannot use the
+    // longer waitMinDelayForTimeBuffer delay.
+    await sleep(votingConfig!.votingStartTime.toNumber() - dayjs().unix() + 1);
+
+    const voteTx = await sdk.voteToVetoPayoutPhaseWithDonationTx(
+      { donor: donor.publicKey },
+      { campaignUuid, donationUuid, payoutPhaseIndex }
+    );
+    await sendTransactionForTest(connection, voteTx, [donor]);
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.payoutPhases.payoutPhases[payoutPhaseIndex]
+        .votingConfig!.vetoVotes,
+      donationAmount
+    );
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.nonVotingDonations,
+      0
+    );
+  });
+
+  test("Donations are rejected after the campaign end time.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+      });
+
+    const campaignEndTime = campaignEscrowAccount.campaignEndTime.toNumber();
+    await waitMinDelayForTimeBuffer(campaignEndTime - dayjs().unix());
+
+    const transaction = await sdk.donateTx(
+      { donor: donor.publicKey },
+      {
+        campaignUuid,
+        donationAmount: new BN(solToLamports(1)),
+        donationUuid: uuid(),
+        hasVoteWeight: false,
+        refundable: false,
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "OutsideFundraisingWindow",
+      signers: [donor],
+      transaction,
+    });
+  });
+
+  test("Donations cannot exceed the campaign hard cap.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const hardCap = solToLamports(1);
+    const { campaignUuid } = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { hardCap: new BN(hardCap) },
+    });
+
+    const transaction = await sdk.donateTx(
+      { donor: donor.publicKey },
+      {
+        campaignUuid,
+        donationAmount: new BN(hardCap + 1),
+        donationUuid: uuid(),
+        hasVoteWeight: false,
+        refundable: false,
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "HardCapExceeded",
+      signers: [donor],
+      transaction,
+    });
+  });
+
+  test("Reaching the hard cap ends fundraising if the campaign auto ends.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const hardCap = solToLamports(1);
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: { autoEndAtHardCap: true, hardCap: new BN(hardCap) },
+      });
+
+    const tx = await sdk.donateTx(
+      { donor: donor.publicKey },
+      {
+        campaignUuid,
+        donationAmount: new BN(hardCap),
+        donationUuid: uuid(),
+        hasVoteWeight: false,
+        refundable: false,
+      }
+    );
+    await sendTransactionForTest(connection, tx, [donor]);
+
+    // The campaign end time is left unchanged.
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expect(updatedCampaignEscrowAccount.hardCapReachedAt).not.toBe(null);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.campaignEndTime,
+      campaignEscrowAccount.campaignEndTime
+    );
+  });
+
+  test("Only refundable donations are refunded once the payout phase is vetoed.", async () => {
+    const { connection, authority, otherKeypair: donor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignEndTime } = getPayoutPhasesForTest();
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: {
+          payoutPhases: {
+            payoutPhases: [
+              {
+                ...getDefaultPayoutPhaseInput(campaignEndTime),
+                refundDeadline: getSecondsAfterCampaignEndTime(
+                  campaignEndTime,
+                  14
+                ),
+              },
+            ],
+          },
+        },
+      });
+    const { treasuryEscrow } = campaignEscrowAccount.treasury;
+
+    const donationAmount = solToLamports(1);
+    const refundableDonationUuid = uuid();
+    const nonRefundableDonationUuid = uuid();
+    for (const [donationUuid, refundable] of [
+      [refundableDonationUuid, true],
+      [nonRefundableDonationUuid, false],
+    ] as const) {
+      const donateTx = await sdk.donateTx(
+        { donor: donor.publicKey },
+        {
+          campaignUuid,
+          donationAmount: new BN(donationAmount),
+          donationUuid,
+          hasVoteWeight: false,
+          refundable,
+        }
+      );
+      await sendTransactionForTest(connection, donateTx, [donor]);
+    }
+
+    const vetoTx = await sdk.vetoPayoutPhaseTx(
+      { authority: authority.publicKey, payer: authority.publicKey },
+      { campaignUuid, payoutPhaseIndex: 0 }
+    );
+    await sendTransactionForTest(connection, vetoTx, [authority]);
+
+    const [payoutPhase] = campaignEscrowAccount.payoutPhases.payoutPhases;
+    await sleep(payoutPhase.payoutTime.toNumber() - dayjs().unix() + 1);
+
+    const transaction = await sdk.processDonationRefundTx(
+      { donor: donor.publicKey },
+      { campaignUuid, donationUuid: nonRefundableDonationUuid }
+    );
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidRefundRequest",
+      signers: [donor],
+      transaction,
+    });
+
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+    const refundTx = await sdk.processDonationRefundTx(
+      { donor: donor.publicKey },
+      { campaignUuid, donationUuid: refundableDonationUuid }
+    );
+    await sendTransactionForTest(connection, refundTx, [donor]);
+
+    expectNumbersEqual(
+      startingTreasuryBalance - (await connection.getBalance(treasuryEscrow)),
+      donationAmount
+    );
+    const [campaignEscrow] = await sdk.findCampaignEscrowPda(campaignUuid);
+    const [donationRecord] = findDonationRecordPda(
+      campaignEscrow,
+      refundableDonationUuid,
+      sdk.programId
+    );
+    expect(await connection.getAccountInfo(donationRecord)).toBe(null);
+  });
+});
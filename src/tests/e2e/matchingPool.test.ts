@@ -0,0 +1,129 @@
+import {
+  expectNumbersEqual,
+  expectPublicKeysEqual,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import { NATIVE_MINT } from "@solana/spl-token";
+import BN from "bn.js";
+import findMatchingPoolEscrowPda from "sdk/pdas/findMatchingPoolEscrowPda";
+import findMatchingPoolPda from "sdk/pdas/findMatchingPoolPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositForTest from "tests/utils/createDepositForTest";
+import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import mintCampaignNftForTest from "tests/utils/mintCampaignNftForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
+describe("Test MatchingPool instructions.", () => {
+  test("Deposits are matched from the matching pool.", async () => {
+    const {
+      connection,
+      authority,
+      creatorKeypair: sponsor,
+      otherKeypair: buyer,
+      sdk,
+    } = await getConnectionAndSdkForTest();
+
+    const { campaignUuid } = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+
+    const matchCap = solToLamports(1);
+    const tx = await sdk.fundMatchingPoolTx(
+      { sponsor: sponsor.publicKey },
+      {
+        campaignUuid,
+        matchBasisPoints: 5_000,
+        matchCap: new BN(matchCap),
+      }
+    );
+    await sendTransactionForTest(connection, tx, [sponsor]);
+
+    const [campaignEscrow] = await sdk.findCampaignEscrowPda(campaignUuid);
+    const [matchingPool] = findMatchingPoolPda(campaignEscrow, sdk.programId);
+    const [matchingPoolEscrow] = findMatchingPoolEscrowPda(
+      campaignUuid,
+      sdk.programId
+    );
+    const startingMatchingPoolEscrowBalance = await connection.getBalance(
+      matchingPoolEscrow
+    );
+
+    const depositAmount = solToLamports(1);
+    const masterEditionMint = await mintCampaignNftForTest({
+      authority,
+      buyer,
+      campaignUuid,
+      connection,
+      sdk,
+    });
+    await createDepositForTest({
+      buyer,
+      campaignUuid,
+      connection,
+      depositAmount,
+      depositCurrency: NATIVE_MINT,
+      masterEditionMint,
+      sdk,
+    });
+
+    const matchAmount = depositAmount / 2;
+    expectNumbersEqual(
+      startingMatchingPoolEscrowBalance -
+        (await connection.getBalance(matchingPoolEscrow)),
+      matchAmount
+    );
+
+    const campaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expect(campaignEscrowAccount.hasMatchingPool).toBe(true);
+    expectNumbersEqual(
+      campaignEscrowAccount.treasury.totalMatched,
+      matchAmount
+    );
+
+    const matchingPoolAccount = await sdk.program.account.matchingPool.fetch(
+      matchingPool
+    );
+    expectPublicKeysEqual(matchingPoolAccount.campaignEscrow, campaignEscrow);
+    expectPublicKeysEqual(matchingPoolAccount.sponsor, sponsor.publicKey);
+    expectNumbersEqual(matchingPoolAccount.matchBasisPoints, 5_000);
+    expectNumbersEqual(matchingPoolAccount.matchCap, matchCap);
+    expectNumbersEqual(matchingPoolAccount.totalMatched, matchAmount);
+  });
+
+  test("The matching pool cannot be closed before deposits close.", async () => {
+    const { connection, authority, creatorKeypair: sponsor, sdk } =
+      await getConnectionAndSdkForTest();
+
+    const { campaignUuid } = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+
+    const tx = await sdk.fundMatchingPoolTx(
+      { sponsor: sponsor.publicKey },
+      {
+        campaignUuid,
+        matchBasisPoints: 10_000,
+        matchCap: new BN(solToLamports(1)),
+      }
+    );
+    await sendTransactionForTest(connection, tx, [sponsor]);
+
+    const transaction = await sdk.closeMatchingPoolTx(
+      { sponsor: sponsor.publicKey },
+      { campaignUuid }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidCloseMatchingPool",
+      signers: [sponsor],
+      transaction,
+    });
+  });
+});
@@ -0,0 +1,85 @@
+import { expectPublicKeysEqual } from "@formfunction-hq/formfunction-program-shared";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositsForTest from "tests/utils/createDepositsForTest";
+import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
+describe("Test MigrateDepositRecords instruction.", () => {
+  test("DepositRecords which are already migrated are skipped.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+      });
+
+    const { deposits } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [1],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const tx = await sdk.migrateDepositRecordsTx(
+      { authority: authority.publicKey },
+      { campaignUuid, depositRecords: deposits }
+    );
+    await sendTransactionForTest(connection, tx, [authority]);
+
+    const [campaignEscrow] = await sdk.findCampaignEscrowPda(campaignUuid);
+    const [{ depositor, mint }] = deposits;
+    const [depositRecord] = findDepositRecordPda(
+      depositor,
+      mint,
+      sdk.programId
+    );
+    const depositRecordAccount = await sdk.program.account.depositRecord.fetch(
+      depositRecord
+    );
+    expectPublicKeysEqual(depositRecordAccount.campaignEscrow, campaignEscrow);
+  });
+
+  test("DepositRecords of another campaign cannot be migrated.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+      });
+    const { campaignUuid: otherCampaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+      });
+
+    const { deposits } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [1],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const transaction = await sdk.migrateDepositRecordsTx(
+      { authority: authority.publicKey },
+      { campaignUuid: otherCampaignUuid, depositRecords: deposits }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidDepositRecord",
+      signers: [authority],
+      transaction,
+    });
+  });
+});
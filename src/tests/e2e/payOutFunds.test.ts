@@ -1,7 +1,21 @@
+import {
+  expectNumbersEqual,
+  sleep,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import { Keypair } from "@solana/web3.js";
+import BN from "bn.js";
+import dayjs from "dayjs";
+import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
+import getPayoutPhasesOrderedArray from "sdk/utils/getPayoutPhasesOrderedArray";
 import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
 import createDepositsForTest from "tests/utils/createDepositsForTest";
+import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
 import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import getPayoutPhasesForTest from "tests/utils/payout-phases/getPayoutPhasesForTest";
 import payoutFundsForTest from "tests/utils/payoutFundsForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+import waitMinDelayForTimeBuffer from "tests/utils/waitMinDelayForTimeBuffer";
 
 describe("Test PayOutFunds instruction.", () => {
   test("Funds can be paid out.", async () => {
@@ -32,5 +46,274 @@ describe("Test PayOutFunds instruction.", () => {
     });
   });
 
-  test.todo("Test payouts with multiple payout phases.");
+  test("Funds can be paid out over multiple payout phases.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid multiple payout phases."
+    )!;
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { payoutPhases },
+    });
+    const { campaignUuid } = setupResult;
+
+    const depositAmountSol = 4;
+    await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [depositAmountSol],
+      depositCurrency: setupResult.campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const { payoutAmount: firstPayoutAmount } = await payoutFundsForTest({
+      authority,
+      connection,
+      sdk,
+      setupResult,
+    });
+    const { payoutAmount: secondPayoutAmount } = await payoutFundsForTest({
+      authority,
+      connection,
+      sdk,
+      setupResult,
+    });
+
+    // Both payout phases are 50% of the treasury total funds.
+    expectNumbersEqual(firstPayoutAmount, secondPayoutAmount);
+
+    const campaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    campaignEscrowAccount.payoutPhases.payoutPhases.forEach((payoutPhase) =>
+      expect(payoutPhase.isPaidOut).toBe(true)
+    );
+  });
+
+  test("A fixed crank reward is paid to the crank reward receiver.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const crankRewardAmount = solToLamports(0.01);
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: {
+        crankReward: { fixed: { amount: new BN(crankRewardAmount) } },
+      },
+    });
+    const { campaignUuid } = setupResult;
+
+    await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [3],
+      depositCurrency: setupResult.campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const result = await payoutFundsForTest({
+      authority,
+      connection,
+      crankRewardReceiver: Keypair.generate().publicKey,
+      sdk,
+      setupResult,
+    });
+
+    expectNumbersEqual(result.crankRewardAmount, crankRewardAmount);
+  });
+
+  test("All due payout phases can be paid out at once.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid multiple payout phases."
+    )!;
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { payoutPhases },
+    });
+    const { campaignEscrowAccount, campaignUuid } = setupResult;
+
+    const { totalDepositAmount } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [4],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const lastPayoutPhase = getPayoutPhasesOrderedArray(
+      campaignEscrowAccount.payoutPhases
+    )[1];
+    const payoutTime = lastPayoutPhase!.payoutTime.toNumber();
+    await waitMinDelayForTimeBuffer(payoutTime - dayjs().unix());
+
+    const [treasuryEscrow] = findTreasuryEscrowPda(
+      campaignUuid,
+      sdk.program.programId
+    );
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+
+    const tx = await sdk.payOutAllDueFundsTx(
+      { payer: authority.publicKey },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, tx, [authority]);
+
+    expectNumbersEqual(
+      startingTreasuryBalance - (await connection.getBalance(treasuryEscrow)),
+      totalDepositAmount
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    updatedCampaignEscrowAccount.payoutPhases.payoutPhases.forEach(
+      (payoutPhase) => expect(payoutPhase.isPaidOut).toBe(true)
+    );
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalPaidOut,
+      totalDepositAmount
+    );
+  });
+
+  test("Stretch goals are settled with the raise by the first payout.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid stretch goal payout phases."
+    )!;
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { payoutPhases },
+    });
+    const { campaignEscrowAccount, campaignUuid } = setupResult;
+
+    // The raise stays below the stretch goal threshold.
+    const { totalDepositAmount } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [4],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    await payoutFundsForTest({
+      authority,
+      connection,
+      sdk,
+      setupResult,
+    });
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.settledRaise!,
+      totalDepositAmount
+    );
+    expect(
+      updatedCampaignEscrowAccount.payoutPhases.payoutPhases.map(
+        (payoutPhase) => payoutPhase.isDeactivated
+      )
+    ).toEqual([false, false, true]);
+  });
+
+  test("Streaming payout phases are claimed as they vest.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid streaming payout phase."
+    )!;
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: { payoutPhases },
+      });
+
+    const { totalDepositAmount } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [3],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    // Streaming payout phases are not paid out by the PayOutFunds ix.
+    const transaction = await sdk.payOutFundsTx(
+      { payer: authority.publicKey },
+      { campaignUuid }
+    );
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidPayoutRequest",
+      signers: [authority],
+      transaction,
+    });
+
+    const [treasuryEscrow] = findTreasuryEscrowPda(
+      campaignUuid,
+      sdk.program.programId
+    );
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+
+    const [payoutPhase] = getPayoutPhasesOrderedArray(
+      campaignEscrowAccount.payoutPhases
+    );
+    // Claim shortly after the stream starts, so only part of it has vested.
+    await sleep(payoutPhase.payoutTime.toNumber() - dayjs().unix() + 1);
+    const firstClaimTx = await sdk.claimStreamedPayoutTx(
+      { payer: authority.publicKey },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, firstClaimTx, [authority]);
+
+    const firstClaimAmount =
+      startingTreasuryBalance - (await connection.getBalance(treasuryEscrow));
+    expect(firstClaimAmount).toBeGreaterThan(0);
+    const claimedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      claimedCampaignEscrowAccount.payoutPhases.payoutPhases[0].paidAmount,
+      firstClaimAmount
+    );
+
+    // The rest is claimable once the stream ends.
+    const streamEndTime = payoutPhase.stream!.streamEndTime.toNumber();
+    await waitMinDelayForTimeBuffer(streamEndTime - dayjs().unix());
+
+    const secondClaimTx = await sdk.claimStreamedPayoutTx(
+      { payer: authority.publicKey },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, secondClaimTx, [authority]);
+
+    expectNumbersEqual(
+      startingTreasuryBalance - (await connection.getBalance(treasuryEscrow)),
+      totalDepositAmount
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    const [updatedPayoutPhase] =
+      updatedCampaignEscrowAccount.payoutPhases.payoutPhases;
+    expect(updatedPayoutPhase.isPaidOut).toBe(true);
+    expectNumbersEqual(updatedPayoutPhase.paidAmount, totalDepositAmount);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalPaidOut,
+      totalDepositAmount
+    );
+  });
 });
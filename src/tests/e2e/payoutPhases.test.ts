@@ -1,4 +1,6 @@
 import { NATIVE_MINT } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import BN from "bn.js";
 import CreateCampaignEscrowInput from "sdk/types/CreateCampaignEscrowInput";
 import UpdateCampaignEscrowInput from "sdk/types/UpdateCampaignEscrowInput";
 import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
@@ -27,7 +29,15 @@ describe("Test PayoutPhases create and update validation.", () => {
 
       const createCampaignEscrowInput: CreateCampaignEscrowInput = {
         ...payoutPhases,
+        autoEndAtHardCap: false,
         campaignEndTime: getSecondsAheadUnixTime(3),
+        collectionMint: Keypair.generate().publicKey,
+        crankReward: { none: {} },
+        depositGracePeriod: new BN(0),
+        fundraisingStartTime: null,
+        hardCap: null,
+        rewardTiers: [],
+        surplusPolicy: { creator: {} },
       };
 
       const { campaignUuid, creator } = options;
@@ -50,8 +60,17 @@ describe("Test PayoutPhases create and update validation.", () => {
       const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
         ...payoutPhases,
         authority: null,
+        autoEndAtHardCap: null,
         campaignEndTime: null,
+        collectionMint: null,
+        crankReward: null,
         creator: null,
+        depositEscrowInfosCapacity: null,
+        depositGracePeriod: null,
+        fundraisingStartTime: null,
+        hardCap: null,
+        rewardTiers: null,
+        surplusPolicy: null,
       };
 
       const updateTx = await sdk.updateEscrowTx(
@@ -87,7 +106,15 @@ describe("Test PayoutPhases create and update validation.", () => {
 
       const createCampaignEscrowInput: CreateCampaignEscrowInput = {
         ...payoutPhases,
+        autoEndAtHardCap: false,
         campaignEndTime: getSecondsAheadUnixTime(4),
+        collectionMint: Keypair.generate().publicKey,
+        crankReward: { none: {} },
+        depositGracePeriod: new BN(0),
+        fundraisingStartTime: null,
+        hardCap: null,
+        rewardTiers: [],
+        surplusPolicy: { creator: {} },
       };
 
       const { campaignUuid, creator } = options;
@@ -114,7 +141,15 @@ describe("Test PayoutPhases create and update validation.", () => {
 
       const validInput: CreateCampaignEscrowInput = {
         ...getPayoutPhasesForTest().valid[0],
+        autoEndAtHardCap: false,
         campaignEndTime: getSecondsAheadUnixTime(3),
+        collectionMint: Keypair.generate().publicKey,
+        crankReward: { none: {} },
+        depositGracePeriod: new BN(0),
+        fundraisingStartTime: null,
+        hardCap: null,
+        rewardTiers: [],
+        surplusPolicy: { creator: {} },
       };
 
       const validCreateTx = await sdk.createEscrowTx(
@@ -136,8 +171,17 @@ describe("Test PayoutPhases create and update validation.", () => {
       const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
         ...payoutPhases,
         authority: null,
+        autoEndAtHardCap: null,
         campaignEndTime: null,
+        collectionMint: null,
+        crankReward: null,
         creator: null,
+        depositEscrowInfosCapacity: null,
+        depositGracePeriod: null,
+        fundraisingStartTime: null,
+        hardCap: null,
+        rewardTiers: null,
+        surplusPolicy: null,
       };
 
       const invalidUpdateTx = await sdk.updateEscrowTx(
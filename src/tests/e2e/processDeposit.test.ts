@@ -0,0 +1,107 @@
+import {
+  expectNumbersEqual,
+  fundSplTokenAtas,
+  generateKeypairArray,
+  requestAirdrops,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import BN from "bn.js";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositForTest from "tests/utils/createDepositForTest";
+import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import mintCampaignNftForTest from "tests/utils/mintCampaignNftForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
+describe("Test ProcessDeposit instruction.", () => {
+  test("Deposits in a DepositEscrow currency are processed into the treasury.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const { campaignUuid, depositEscrowCurrencies } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: { enableDepositEscrowCurrencies: 1 },
+      });
+    const depositCurrency = depositEscrowCurrencies[0];
+
+    const [buyer] = generateKeypairArray(1);
+    await requestAirdrops({ connection, wallets: [buyer] });
+    const masterEditionMint = await mintCampaignNftForTest({
+      authority,
+      buyer,
+      campaignUuid,
+      connection,
+      sdk,
+    });
+    const depositAmount = 10;
+    await fundSplTokenAtas(
+      connection,
+      [buyer.publicKey, authority.publicKey],
+      depositCurrency,
+      authority,
+      depositAmount
+    );
+    await createDepositForTest({
+      buyer,
+      campaignUuid,
+      connection,
+      depositAmount,
+      depositCurrency,
+      masterEditionMint,
+      sdk,
+    });
+
+    // The authority swaps the deposit, so it pays the processed amount.
+    const processedDepositAmount = solToLamports(1);
+    const tx = await sdk.processDepositTx(
+      {
+        authority: authority.publicKey,
+        depositor: buyer.publicKey,
+        mint: masterEditionMint,
+      },
+      { campaignUuid, processedDepositAmount: new BN(processedDepositAmount) }
+    );
+    await sendTransactionForTest(connection, tx, [authority]);
+
+    const campaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(campaignEscrowAccount.processedDepositCount, 1);
+    expectNumbersEqual(
+      campaignEscrowAccount.treasury.totalFunds,
+      processedDepositAmount
+    );
+
+    const [depositRecord] = findDepositRecordPda(
+      buyer.publicKey,
+      masterEditionMint,
+      sdk.programId
+    );
+    const depositRecordAccount = await sdk.program.account.depositRecord.fetch(
+      depositRecord
+    );
+    expect(depositRecordAccount.depositProcessed).toBe(true);
+    expectNumbersEqual(
+      depositRecordAccount.processedDepositAmount,
+      processedDepositAmount
+    );
+
+    // A deposit can only be processed once.
+    const transaction = await sdk.processDepositTx(
+      {
+        authority: authority.publicKey,
+        depositor: buyer.publicKey,
+        mint: masterEditionMint,
+      },
+      { campaignUuid, processedDepositAmount: new BN(processedDepositAmount) }
+    );
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidProcessDeposit",
+      signers: [authority],
+      transaction,
+    });
+  });
+});
@@ -1,3 +1,104 @@
+import {
+  expectNumbersEqual,
+  findAtaPda,
+  getTokenBalance,
+  sleep,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import dayjs from "dayjs";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositsForTest from "tests/utils/createDepositsForTest";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import getDefaultPayoutPhaseInput from "tests/utils/payout-phases/getDefaultPayoutPhaseInput";
+import getPayoutPhasesForTest from "tests/utils/payout-phases/getPayoutPhasesForTest";
+import getSecondsAfterCampaignEndTime from "tests/utils/payout-phases/getSecondsAfterCampaignEndTime";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
 describe("Test ProcessFullRefund instruction.", () => {
-  test.todo("Test ProcessFullRefund instruction.");
+  test("Deposits are fully refunded once the payout phase is vetoed.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    // Refunds are available from the payout time until the refund deadline,
+    // so the refund window is longer than the default one.
+    const { campaignEndTime } = getPayoutPhasesForTest();
+    const { campaignEscrowAccount, campaignUuid } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+        setupOptions: {
+          payoutPhases: {
+            payoutPhases: [
+              {
+                ...getDefaultPayoutPhaseInput(campaignEndTime),
+                refundDeadline: getSecondsAfterCampaignEndTime(
+                  campaignEndTime,
+                  14
+                ),
+              },
+            ],
+          },
+        },
+      });
+    const { treasuryEscrow } = campaignEscrowAccount.treasury;
+
+    const depositAmountSol = 2;
+    const {
+      buyers: [nftHolder],
+      deposits: [{ depositor, mint }],
+    } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [depositAmountSol],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const vetoTx = await sdk.vetoPayoutPhaseTx(
+      { authority: authority.publicKey, payer: authority.publicKey },
+      { campaignUuid, payoutPhaseIndex: 0 }
+    );
+    await sendTransactionForTest(connection, vetoTx, [authority]);
+
+    const [payoutPhase] = campaignEscrowAccount.payoutPhases.payoutPhases;
+    await sleep(payoutPhase.payoutTime.toNumber() - dayjs().unix() + 1);
+
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+
+    const tx = await sdk.processRefundTx(
+      {
+        depositor,
+        mint,
+        nftHolder: nftHolder.publicKey,
+        payer: nftHolder.publicKey,
+      },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, tx, [nftHolder]);
+
+    const refundAmount = solToLamports(depositAmountSol);
+    expectNumbersEqual(
+      startingTreasuryBalance - (await connection.getBalance(treasuryEscrow)),
+      refundAmount
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalRefunded,
+      refundAmount
+    );
+
+    // The NFT is burned and the DepositRecord is closed.
+    const [nftTokenAccount] = findAtaPda(nftHolder.publicKey, mint);
+    expectNumbersEqual(await getTokenBalance(connection, nftTokenAccount), 0);
+    const [depositRecord] = findDepositRecordPda(
+      depositor,
+      mint,
+      sdk.programId
+    );
+    expect(await connection.getAccountInfo(depositRecord)).toBe(null);
+  });
 });
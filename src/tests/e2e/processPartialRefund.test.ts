@@ -1,3 +1,87 @@
+import {
+  expectNumbersEqual,
+  sleep,
+} from "@formfunction-hq/formfunction-program-shared";
+import dayjs from "dayjs";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositsForTest from "tests/utils/createDepositsForTest";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import getPayoutPhasesForTest from "tests/utils/payout-phases/getPayoutPhasesForTest";
+import payoutFundsForTest from "tests/utils/payoutFundsForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
 describe("Test ProcessPartialRefund instruction.", () => {
-  test.todo("Test ProcessPartialRefund instruction.");
+  test("The funds left after a payout are refunded once the next payout phase is vetoed.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid multiple payout phases."
+    )!;
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { payoutPhases },
+    });
+    const { campaignEscrowAccount, campaignUuid } = setupResult;
+    const { treasuryEscrow } = campaignEscrowAccount.treasury;
+
+    const {
+      buyers: [nftHolder],
+      deposits: [{ depositor, mint }],
+      totalDepositAmount,
+    } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [4],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const { payoutAmount } = await payoutFundsForTest({
+      authority,
+      connection,
+      sdk,
+      setupResult,
+    });
+
+    const payoutPhaseIndex = 1;
+    const vetoTx = await sdk.vetoPayoutPhaseTx(
+      { authority: authority.publicKey, payer: authority.publicKey },
+      { campaignUuid, payoutPhaseIndex }
+    );
+    await sendTransactionForTest(connection, vetoTx, [authority]);
+
+    const { payoutTime } =
+      campaignEscrowAccount.payoutPhases.payoutPhases[payoutPhaseIndex];
+    await sleep(payoutTime.toNumber() - dayjs().unix() + 1);
+
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+
+    const tx = await sdk.processPartialRefundTx(
+      {
+        depositor,
+        mint,
+        nftHolder: nftHolder.publicKey,
+        payer: nftHolder.publicKey,
+      },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, tx, [nftHolder]);
+
+    // The only supporter is refunded everything which was not paid out.
+    const refundAmount = totalDepositAmount - payoutAmount;
+    expectNumbersEqual(
+      startingTreasuryBalance - (await connection.getBalance(treasuryEscrow)),
+      refundAmount
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalRefunded,
+      refundAmount
+    );
+  });
 });
@@ -0,0 +1,142 @@
+import {
+  expectNumbersEqual,
+  ixToTx,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import { SystemProgram } from "@solana/web3.js";
+import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositsForTest from "tests/utils/createDepositsForTest";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
+describe("Test ReconcileTreasury instruction.", () => {
+  test("Unsolicited treasury funds are transferred to the payout wallet with the creator surplus policy.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+    });
+    const { campaignUuid, campaignEscrowAccount } = setupResult;
+
+    const { totalDepositAmount } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [2],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const [treasuryEscrow] = findTreasuryEscrowPda(
+      campaignUuid,
+      sdk.program.programId
+    );
+    const surplusAmount = solToLamports(1);
+    await sendTransactionForTest(
+      connection,
+      ixToTx(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          lamports: surplusAmount,
+          toPubkey: treasuryEscrow,
+        })
+      ),
+      [authority]
+    );
+
+    const { payoutWallet } = campaignEscrowAccount;
+    const startingPayoutWalletBalance = await connection.getBalance(
+      payoutWallet
+    );
+
+    const tx = await sdk.reconcileTreasuryTx(
+      { payer: authority.publicKey },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, tx, [authority]);
+
+    const endingPayoutWalletBalance = await connection.getBalance(
+      payoutWallet
+    );
+    expectNumbersEqual(
+      endingPayoutWalletBalance - startingPayoutWalletBalance,
+      surplusAmount
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalSurplus,
+      surplusAmount
+    );
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalFunds,
+      totalDepositAmount
+    );
+  });
+
+  test("Unsolicited treasury funds are held in the treasury with the supporters surplus policy.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { surplusPolicy: { supporters: {} } },
+    });
+    const { campaignUuid, campaignEscrowAccount } = setupResult;
+
+    const { totalDepositAmount } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [2],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const [treasuryEscrow] = findTreasuryEscrowPda(
+      campaignUuid,
+      sdk.program.programId
+    );
+    const surplusAmount = solToLamports(1);
+    await sendTransactionForTest(
+      connection,
+      ixToTx(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          lamports: surplusAmount,
+          toPubkey: treasuryEscrow,
+        })
+      ),
+      [authority]
+    );
+
+    const startingTreasuryBalance = await connection.getBalance(treasuryEscrow);
+
+    const tx = await sdk.reconcileTreasuryTx(
+      { payer: authority.publicKey },
+      { campaignUuid }
+    );
+    await sendTransactionForTest(connection, tx, [authority]);
+
+    expectNumbersEqual(
+      await connection.getBalance(treasuryEscrow),
+      startingTreasuryBalance
+    );
+
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.supporterSurplus,
+      surplusAmount
+    );
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.treasury.totalFunds,
+      totalDepositAmount
+    );
+  });
+});
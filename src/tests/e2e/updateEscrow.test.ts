@@ -1,8 +1,10 @@
 import {
   createSplToken,
   expectPublicKeysEqual,
+  solToLamports,
 } from "@formfunction-hq/formfunction-program-shared";
 import { NATIVE_MINT } from "@solana/spl-token";
+import BN from "bn.js";
 import UpdateCampaignEscrowInput from "sdk/types/UpdateCampaignEscrowInput";
 import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
 import expectTransactionToFailWithErrorCode from "tests/utils/expectTransactionToFailWithErrorCode";
@@ -23,10 +25,18 @@ describe("UpdateEscrow instruction.", () => {
 
     const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
       authority: null,
+      autoEndAtHardCap: null,
       campaignEndTime: getSecondsAheadUnixTime(6),
+      collectionMint: null,
+      crankReward: null,
       creator: creator.publicKey,
-      nonVotingPayoutPhases: null,
-      votingPayoutPhases: null,
+      depositEscrowInfosCapacity: 6,
+      depositGracePeriod: null,
+      fundraisingStartTime: getSecondsAheadUnixTime(3),
+      hardCap: new BN(solToLamports(10)),
+      payoutPhases: null,
+      rewardTiers: null,
+      surplusPolicy: null,
     };
 
     await updateCampaignEscrowForTest({
@@ -62,10 +72,18 @@ describe("UpdateEscrow instruction.", () => {
 
     const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
       authority: null,
+      autoEndAtHardCap: null,
       campaignEndTime: getSecondsAheadUnixTime(5),
+      collectionMint: null,
+      crankReward: null,
       creator: creator.publicKey,
-      nonVotingPayoutPhases: null,
-      votingPayoutPhases: null,
+      depositEscrowInfosCapacity: null,
+      depositGracePeriod: null,
+      fundraisingStartTime: null,
+      hardCap: null,
+      payoutPhases: null,
+      rewardTiers: null,
+      surplusPolicy: null,
     };
 
     const treasuryMint = await createSplToken(connection, authority);
@@ -119,10 +137,18 @@ describe("UpdateEscrow instruction.", () => {
 
     const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
       authority: null,
+      autoEndAtHardCap: null,
       campaignEndTime: getSecondsAheadUnixTime(8),
+      collectionMint: null,
+      crankReward: null,
       creator: creator.publicKey,
-      nonVotingPayoutPhases: null,
-      votingPayoutPhases: null,
+      depositEscrowInfosCapacity: null,
+      depositGracePeriod: null,
+      fundraisingStartTime: null,
+      hardCap: null,
+      payoutPhases: null,
+      rewardTiers: null,
+      surplusPolicy: null,
     };
 
     const treasuryMint = NATIVE_MINT;
@@ -167,10 +193,18 @@ describe("UpdateEscrow instruction.", () => {
 
     const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
       authority: null,
+      autoEndAtHardCap: null,
       campaignEndTime: getSecondsAheadUnixTime(4),
+      collectionMint: null,
+      crankReward: null,
       creator: creator.publicKey,
-      nonVotingPayoutPhases: null,
-      votingPayoutPhases: null,
+      depositEscrowInfosCapacity: null,
+      depositGracePeriod: null,
+      fundraisingStartTime: null,
+      hardCap: null,
+      payoutPhases: null,
+      rewardTiers: null,
+      surplusPolicy: null,
     };
 
     const tx = await sdk.updateEscrowTx(
@@ -209,10 +243,18 @@ describe("UpdateEscrow instruction.", () => {
 
     const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
       authority: null,
+      autoEndAtHardCap: null,
       campaignEndTime: getSecondsAheadUnixTime(5),
+      collectionMint: null,
+      crankReward: null,
       creator: creator.publicKey,
-      nonVotingPayoutPhases: null,
-      votingPayoutPhases: null,
+      depositEscrowInfosCapacity: null,
+      depositGracePeriod: null,
+      fundraisingStartTime: null,
+      hardCap: null,
+      payoutPhases: null,
+      rewardTiers: null,
+      surplusPolicy: null,
     };
 
     const tx = await sdk.updateEscrowTx(
@@ -236,4 +278,52 @@ describe("UpdateEscrow instruction.", () => {
       transaction: tx,
     });
   });
+
+  test("The fundraising start time must be before the campaign end time.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const { campaignEscrowAccount, creator } =
+      await createCampaignEscrowForTest({
+        authority,
+        connection,
+        sdk,
+      });
+
+    const updateCampaignEscrowInput: UpdateCampaignEscrowInput = {
+      authority: null,
+      autoEndAtHardCap: null,
+      campaignEndTime: null,
+      collectionMint: null,
+      crankReward: null,
+      creator: null,
+      depositEscrowInfosCapacity: null,
+      depositGracePeriod: null,
+      fundraisingStartTime: campaignEscrowAccount.campaignEndTime,
+      hardCap: null,
+      payoutPhases: null,
+      rewardTiers: null,
+      surplusPolicy: null,
+    };
+
+    const tx = await sdk.updateEscrowTx(
+      {
+        authority: authority.publicKey,
+        creator: creator.publicKey,
+        payer: authority.publicKey,
+        payoutWalletOwner: authority.publicKey,
+        treasuryMint: NATIVE_MINT,
+      },
+      {
+        campaignUuid: campaignEscrowAccount.campaignUuid,
+        updateCampaignEscrowInput,
+      }
+    );
+
+    await expectTransactionToFailWithErrorCode({
+      connection,
+      errorName: "InvalidFundraisingWindow",
+      signers: [authority],
+      transaction: tx,
+    });
+  });
 });
@@ -0,0 +1,84 @@
+import {
+  expectNumbersEqual,
+  expectPublicKeysEqual,
+  sleep,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
+import dayjs from "dayjs";
+import findDepositRecordPda from "sdk/pdas/findDepositRecordPda";
+import findVoteRecordPda from "sdk/pdas/findVoteRecordPda";
+import createCampaignEscrowForTest from "tests/utils/createCampaignEscrowForTest";
+import createDepositsForTest from "tests/utils/createDepositsForTest";
+import getConnectionAndSdkForTest from "tests/utils/getConnectionAndSdkForTest";
+import getPayoutPhasesForTest from "tests/utils/payout-phases/getPayoutPhasesForTest";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
+describe("Test VoteToVetoPayoutPhase instruction.", () => {
+  test("The NFT holder can vote to veto a payout phase.", async () => {
+    const { connection, authority, sdk } = await getConnectionAndSdkForTest();
+
+    const payoutPhases = getPayoutPhasesForTest().valid.find(
+      (testCase) => testCase.label === "Valid voting payout phase."
+    )!;
+    const setupResult = await createCampaignEscrowForTest({
+      authority,
+      connection,
+      sdk,
+      setupOptions: { payoutPhases },
+    });
+    const { campaignEscrowAccount, campaignUuid } = setupResult;
+
+    const depositAmountSol = 2;
+    const {
+      buyers: [nftHolder],
+      deposits: [{ depositor, mint }],
+    } = await createDepositsForTest({
+      authority,
+      campaignUuid,
+      connection,
+      depositAmounts: [depositAmountSol],
+      depositCurrency: campaignEscrowAccount.treasury.treasuryMint,
+      sdk,
+    });
+
+    const payoutPhaseIndex = 1;
+    const { votingConfig } =
+      campaignEscrowAccount.payoutPhases.payoutPhases[payoutPhaseIndex];
+    // Votes are only accepted before the payout time, so this cannot use the
+    // longer waitMinDelayForTimeBuffer delay.
+    await sleep(votingConfig!.votingStartTime.toNumber() - dayjs().unix() + 1);
+
+    const tx = await sdk.voteToVetoPayoutPhaseTx(
+      { depositor, mint, nftHolder: nftHolder.publicKey },
+      { campaignUuid, payoutPhaseIndex }
+    );
+    await sendTransactionForTest(connection, tx, [nftHolder]);
+
+    const vetoVotes = solToLamports(depositAmountSol);
+    const updatedCampaignEscrowAccount =
+      await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+    expectNumbersEqual(
+      updatedCampaignEscrowAccount.payoutPhases.payoutPhases[payoutPhaseIndex]
+        .votingConfig!.vetoVotes,
+      vetoVotes
+    );
+
+    const [depositRecord] = findDepositRecordPda(
+      depositor,
+      mint,
+      sdk.programId
+    );
+    const [voteRecord] = findVoteRecordPda(
+      depositRecord,
+      payoutPhaseIndex,
+      sdk.programId
+    );
+    const voteRecordAccount = await sdk.program.account.voteRecord.fetch(
+      voteRecord
+    );
+    expectPublicKeysEqual(voteRecordAccount.depositRecord, depositRecord);
+    expectNumbersEqual(voteRecordAccount.payoutPhaseIndex, payoutPhaseIndex);
+    expectPublicKeysEqual(voteRecordAccount.voter, nftHolder.publicKey);
+    expectNumbersEqual(voteRecordAccount.vetoVotes, vetoVotes);
+  });
+});
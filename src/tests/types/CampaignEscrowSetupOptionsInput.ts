@@ -1,12 +1,22 @@
 import { Keypair, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import CrankReward from "sdk/types/CrankReward";
+import RewardTierInput from "sdk/types/RewardTierInput";
+import SurplusPolicy from "sdk/types/SurplusPolicy";
 import PayoutPhasesTestInput from "tests/types/PayoutPhasesTestInput";
 
 type CampaignEscrowSetupOptionsInput = {
+  autoEndAtHardCap?: boolean;
   campaignUuid?: string;
+  crankReward?: CrankReward;
   creatorKeypair?: Keypair;
   enableDepositEscrowCurrencies?: number;
+  fundraisingStartTime?: BN;
+  hardCap?: BN;
   payoutPhases?: PayoutPhasesTestInput;
   payoutWalletOwner?: PublicKey;
+  rewardTiers?: Array<RewardTierInput>;
+  surplusPolicy?: SurplusPolicy;
   useNativeDepositEscrow?: boolean;
   useNativeTreasury?: boolean;
 };
@@ -1,8 +1,7 @@
-import NonVotingPayoutPhaseInput from "sdk/types/NonVotingPayoutPhaseInput";
+import PayoutPhaseInput from "sdk/types/PayoutPhaseInput";
 
 type PayoutPhasesTestInput = {
-  nonVotingPayoutPhases: Array<NonVotingPayoutPhaseInput>;
-  votingPayoutPhases: Array<never>; // TODO[@]: Will get filled in later.
+  payoutPhases: Array<PayoutPhaseInput>;
 };
 
 export default PayoutPhasesTestInput;
@@ -4,53 +4,63 @@ import getPayoutPhasesOrderedArray from "sdk/utils/getPayoutPhasesOrderedArray";
 describe("SDK unit tests.", () => {
   test("Test getPayoutPhasesOrderedArray.", () => {
     const payoutPhases = {
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
+          description: "Initial payout for campaign completion.",
+          isDeactivated: false,
           isPaidOut: false,
           isVetoedByAuthority: false,
-          sharedFields: {
-            description: "Initial payout for campaign completion.",
-            index: 0,
-            payoutBasisPoints: 50,
-            payoutTime: new BN(5),
-            refundDeadline: new BN(10),
-          },
+          paidAmount: new BN(0),
+          payoutBasisPoints: 50,
+          payoutTime: new BN(5),
+          refundDeadline: new BN(10),
+          stream: null,
+          stretchGoal: null,
+          votingConfig: null,
         },
-      ],
-      votingPayoutPhases: [
         {
+          description: "Second payout.",
+          isDeactivated: false,
           isPaidOut: false,
           isVetoedByAuthority: false,
-
-          sharedFields: {
-            description: "Initial payout for campaign completion.",
-            index: 2,
-            payoutBasisPoints: 25,
-            payoutTime: new BN(1),
-            refundDeadline: new BN(10),
+          paidAmount: new BN(0),
+          payoutBasisPoints: 25,
+          payoutTime: new BN(25),
+          refundDeadline: new BN(30),
+          stream: null,
+          stretchGoal: null,
+          votingConfig: {
+            vetoVotes: new BN(0),
+            voteBasisPointsVetoThreshold: new BN(50),
+            votingStartTime: new BN(20),
           },
-          vetoVotes: new BN(0),
-          voteBasisPointsVetoThreshold: 50,
-          votingStartTime: new BN(25),
         },
         {
+          description: "Final payout.",
+          isDeactivated: false,
           isPaidOut: false,
           isVetoedByAuthority: false,
-          sharedFields: {
-            description: "Initial payout for campaign completion.",
-            index: 1,
-            payoutBasisPoints: 25,
-            payoutTime: new BN(1),
-            refundDeadline: new BN(10),
+          paidAmount: new BN(0),
+          payoutBasisPoints: 25,
+          payoutTime: new BN(50),
+          refundDeadline: new BN(55),
+          stream: null,
+          stretchGoal: null,
+          votingConfig: {
+            vetoVotes: new BN(0),
+            voteBasisPointsVetoThreshold: new BN(50),
+            votingStartTime: new BN(45),
           },
-          vetoVotes: new BN(0),
-          voteBasisPointsVetoThreshold: 50,
-          votingStartTime: new BN(50),
         },
       ],
     };
     const result = getPayoutPhasesOrderedArray(payoutPhases);
     expect(result.length).toBe(3);
-    result.forEach((val, i) => expect(val.sharedFields.index).toBe(i));
+    result.forEach((val, i) =>
+      expect(val.payoutTime.toNumber()).toBe(
+        payoutPhases.payoutPhases[i].payoutTime.toNumber()
+      )
+    );
+    expect(result).not.toBe(payoutPhases.payoutPhases);
   });
 });
@@ -1,39 +1,67 @@
-import NonVotingPayoutPhaseInput from "sdk/types/NonVotingPayoutPhaseInput";
+import { expectNumbersEqual } from "@formfunction-hq/formfunction-program-shared";
+import PayoutPhaseInput from "sdk/types/PayoutPhaseInput";
 import PayoutPhases from "sdk/types/PayoutPhases";
 
 export default function comparePayoutPhasesForTest(
   payoutPhases: PayoutPhases,
-  nonVotingPayoutPhaseInput: Array<NonVotingPayoutPhaseInput>,
-  votingPayoutPhaseInput: Array<NonVotingPayoutPhaseInput>
+  payoutPhasesInput: Array<PayoutPhaseInput>
 ): void {
-  expect(
-    payoutPhases.votingPayoutPhases.length +
-      payoutPhases.nonVotingPayoutPhases.length
-  ).toBe(nonVotingPayoutPhaseInput.length + votingPayoutPhaseInput.length);
+  expect(payoutPhases.payoutPhases.length).toBe(payoutPhasesInput.length);
 
-  payoutPhases.nonVotingPayoutPhases.forEach((nonVotingPayoutPhase, index) => {
-    const payoutPhaseInput = nonVotingPayoutPhaseInput[index];
+  payoutPhases.payoutPhases.forEach((payoutPhase, index) => {
+    const payoutPhaseInput = payoutPhasesInput[index];
 
-    expect(nonVotingPayoutPhase.sharedFields.index).toBe(
-      payoutPhaseInput.sharedFields.index
+    expect(payoutPhase.description).toBe(payoutPhaseInput.description);
+    expect(payoutPhase.isDeactivated).toBe(false);
+    expect(payoutPhase.isPaidOut).toBe(false);
+    expect(payoutPhase.isVetoedByAuthority).toBe(false);
+    expect(payoutPhase.payoutBasisPoints).toBe(
+      payoutPhaseInput.payoutBasisPoints
     );
-    expect(nonVotingPayoutPhase.sharedFields.description).toBe(
-      payoutPhaseInput.sharedFields.description
+    expect(payoutPhase.payoutTime.toNumber()).toBe(
+      payoutPhaseInput.payoutTime.toNumber()
     );
-    expect(nonVotingPayoutPhase.isPaidOut).toBe(false);
-    expect(nonVotingPayoutPhase.isVetoedByAuthority).toBe(false);
-    expect(nonVotingPayoutPhase.sharedFields.payoutBasisPoints).toBe(
-      payoutPhaseInput.sharedFields.payoutBasisPoints
+    expect(payoutPhase.refundDeadline.toNumber()).toBe(
+      payoutPhaseInput.refundDeadline.toNumber()
     );
-    expect(nonVotingPayoutPhase.sharedFields.payoutTime.toNumber()).toBe(
-      payoutPhaseInput.sharedFields.payoutTime.toNumber()
-    );
-    expect(nonVotingPayoutPhase.sharedFields.refundDeadline.toNumber()).toBe(
-      payoutPhaseInput.sharedFields.refundDeadline.toNumber()
-    );
-  });
 
-  payoutPhases.votingPayoutPhases.forEach((_votingPayoutPhase, _index) => {
-    // TODO[@]: Fill in for voting payout phases.
+    const streamInput = payoutPhaseInput.stream;
+    if (streamInput == null) {
+      expect(payoutPhase.stream).toBe(null);
+    } else {
+      expectNumbersEqual(
+        payoutPhase.stream!.streamEndTime,
+        streamInput.streamEndTime
+      );
+      expect(payoutPhase.stream!.vetoTime).toBe(null);
+    }
+
+    const stretchGoalInput = payoutPhaseInput.stretchGoal;
+    if (stretchGoalInput == null) {
+      expect(payoutPhase.stretchGoal).toBe(null);
+    } else {
+      expect(payoutPhase.stretchGoal!.isActiveWhenReached).toBe(
+        stretchGoalInput.isActiveWhenReached
+      );
+      expectNumbersEqual(
+        payoutPhase.stretchGoal!.raiseThreshold,
+        stretchGoalInput.raiseThreshold
+      );
+    }
+
+    const votingConfigInput = payoutPhaseInput.votingConfig;
+    if (votingConfigInput == null) {
+      expect(payoutPhase.votingConfig).toBe(null);
+    } else {
+      expectNumbersEqual(payoutPhase.votingConfig!.vetoVotes, 0);
+      expectNumbersEqual(
+        payoutPhase.votingConfig!.voteBasisPointsVetoThreshold,
+        votingConfigInput.voteBasisPointsVetoThreshold
+      );
+      expectNumbersEqual(
+        payoutPhase.votingConfig!.votingStartTime,
+        votingConfigInput.votingStartTime
+      );
+    }
   });
 }
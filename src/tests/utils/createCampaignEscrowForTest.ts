@@ -90,14 +90,52 @@ export default async function createCampaignEscrowForTest(args: {
     createCampaignEscrowInput.campaignEndTime.toNumber()
   );
   expect(campaignEscrowAccount.payoutsReady).toBe(false);
+  expect(campaignEscrowAccount.version).toBe(1);
   expectNumbersEqual(campaignEscrowAccount.depositCount, 0);
   expectNumbersEqual(campaignEscrowAccount.processedDepositCount, 0);
   expectNumbersEqual(campaignEscrowAccount.treasury.totalFunds, 0);
+  expectPublicKeysEqual(
+    campaignEscrowAccount.collectionMint,
+    createCampaignEscrowInput.collectionMint
+  );
+  expect(campaignEscrowAccount.crankReward).toEqual(
+    createCampaignEscrowInput.crankReward
+  );
+  expect(campaignEscrowAccount.surplusPolicy).toEqual(
+    createCampaignEscrowInput.surplusPolicy
+  );
+  expectNumbersEqual(
+    campaignEscrowAccount.depositGracePeriod,
+    createCampaignEscrowInput.depositGracePeriod
+  );
+  expect(campaignEscrowAccount.fundraisingStartTime?.toNumber() ?? null).toBe(
+    createCampaignEscrowInput.fundraisingStartTime?.toNumber() ?? null
+  );
+  expect(campaignEscrowAccount.hardCap?.toNumber() ?? null).toBe(
+    createCampaignEscrowInput.hardCap?.toNumber() ?? null
+  );
+  expect(campaignEscrowAccount.autoEndAtHardCap).toBe(
+    createCampaignEscrowInput.autoEndAtHardCap
+  );
+  expect(campaignEscrowAccount.hardCapReachedAt).toBe(null);
+
+  expect(campaignEscrowAccount.rewardTiers.rewardTiers.length).toBe(
+    createCampaignEscrowInput.rewardTiers.length
+  );
+  campaignEscrowAccount.rewardTiers.rewardTiers.forEach((rewardTier, i) => {
+    const rewardTierInput = createCampaignEscrowInput.rewardTiers[i];
+    expectNumbersEqual(rewardTier.maxSupply, rewardTierInput.maxSupply);
+    expect(rewardTier.metadataUri).toBe(rewardTierInput.metadataUri);
+    expectNumbersEqual(
+      rewardTier.minDepositAmount,
+      rewardTierInput.minDepositAmount
+    );
+    expectNumbersEqual(rewardTier.soldCount, 0);
+  });
 
   comparePayoutPhasesForTest(
     campaignEscrowAccount.payoutPhases,
-    createCampaignEscrowInput.nonVotingPayoutPhases,
-    createCampaignEscrowInput.votingPayoutPhases
+    createCampaignEscrowInput.payoutPhases
   );
 
   expectNumbersEqual(campaignEscrowAccount.bump, bump);
@@ -23,6 +23,7 @@ export default async function createDepositForTest({
   depositAmount,
   depositCurrency,
   masterEditionMint,
+  rewardTierIndex,
   sdk,
 }: {
   buyer: Keypair;
@@ -31,6 +32,7 @@ export default async function createDepositForTest({
   depositAmount: number;
   depositCurrency: PublicKey;
   masterEditionMint: PublicKey;
+  rewardTierIndex?: number;
   sdk: CampaignTreasuryManagerSdk;
 }): Promise<void> {
   const startingCampaignEscrowAccount =
@@ -84,6 +86,7 @@ export default async function createDepositForTest({
     {
       campaignUuid,
       depositAmount: new BN(depositAmount),
+      rewardTierIndex,
     }
   );
 
@@ -116,6 +119,13 @@ export default async function createDepositForTest({
     ? await connection.getBalance(treasuryEscrow)
     : await getTokenBalance(connection, treasuryEscrow);
 
+  const endingCampaignEscrowAccount =
+    await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+  // Deposits in campaigns with a MatchingPool are matched into the treasury.
+  const matchedAmount = endingCampaignEscrowAccount.treasury.totalMatched.sub(
+    startingCampaignEscrowAccount.treasury.totalMatched
+  );
+
   // Assert ending balances for buyer are correct.
   const endingSolBalance = await connection.getBalance(buyer.publicKey);
 
@@ -144,7 +154,7 @@ export default async function createDepositForTest({
   if (depositMatchesTreasury) {
     expectNumbersEqual(
       endingTreasuryEscrowBalance - startingTreasuryEscrowBalance,
-      depositAmount
+      matchedAmount.add(new BN(depositAmount))
     );
   } else if (isDepositNative) {
     const endingDepositEscrowBalance = await connection.getBalance(
@@ -173,10 +183,9 @@ export default async function createDepositForTest({
   expectPublicKeysEqual(depositRecord.mint, masterEditionMint);
   expectPublicKeysEqual(depositRecord.depositEscrowMint, depositCurrency);
   expect(depositRecord.initialDepositAmount.toNumber()).toBe(depositAmount);
+  expect(depositRecord.rewardTierIndex).toBe(rewardTierIndex ?? null);
 
   // Assert escrow account changes are correct.
-  const endingCampaignEscrowAccount =
-    await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
   expectNumbersEqual(
     startingCampaignEscrowAccount.depositCount.add(new BN(1)),
     endingCampaignEscrowAccount.depositCount
@@ -187,7 +196,7 @@ export default async function createDepositForTest({
       endingCampaignEscrowAccount.treasury.totalFunds.sub(
         startingCampaignEscrowAccount.treasury.totalFunds
       ),
-      depositAmount
+      matchedAmount.add(new BN(depositAmount))
     );
     expect(depositRecord.depositProcessed).toBe(true);
     expectNumbersEqual(depositRecord.processedDepositAmount, depositAmount);
@@ -200,7 +209,7 @@ export default async function createDepositForTest({
       endingCampaignEscrowAccount.treasury.totalFunds.sub(
         startingCampaignEscrowAccount.treasury.totalFunds
       ),
-      0
+      matchedAmount
     );
     expect(depositRecord.depositProcessed).toBe(false);
     expectNumbersEqual(depositRecord.processedDepositAmount, 0);
@@ -3,14 +3,15 @@ import {
   fundSplTokenAtas,
   generateKeypairArray,
   isMintNative,
-  mintMasterEditionForTest,
   requestAirdrops,
   solToLamports,
   sumArray,
 } from "@formfunction-hq/formfunction-program-shared";
 import { Connection, Keypair, PublicKey } from "@solana/web3.js";
 import CampaignTreasuryManagerSdk from "sdk/CampaignTreasuryManagerSdk";
+import DepositRecordToClose from "sdk/types/DepositRecordToClose";
 import createDepositForTest from "tests/utils/createDepositForTest";
+import mintCampaignNftForTest from "tests/utils/mintCampaignNftForTest";
 
 export default async function createDepositsForTest({
   authority,
@@ -26,7 +27,11 @@ export default async function createDepositsForTest({
   depositAmounts: Array<number>;
   depositCurrency: PublicKey;
   sdk: CampaignTreasuryManagerSdk;
-}): Promise<{ totalDepositAmount: number }> {
+}): Promise<{
+  buyers: Array<Keypair>;
+  deposits: Array<DepositRecordToClose>;
+  totalDepositAmount: number;
+}> {
   const totalDepositAmount = solToLamports(sumArray(depositAmounts));
   const buyers = generateKeypairArray(depositAmounts.length);
 
@@ -36,9 +41,16 @@ export default async function createDepositsForTest({
   });
 
   const isDepositNative = isMintNative(depositCurrency);
+  const deposits: Array<DepositRecordToClose> = [];
 
   await forEachAsync(buyers, async (buyer, index) => {
-    const masterEditionMint = await mintMasterEditionForTest(buyer, connection);
+    const masterEditionMint = await mintCampaignNftForTest({
+      authority,
+      buyer,
+      campaignUuid,
+      connection,
+      sdk,
+    });
     const depositAmount = isDepositNative
       ? solToLamports(depositAmounts[index])
       : depositAmounts[index];
@@ -62,7 +74,9 @@ export default async function createDepositsForTest({
       masterEditionMint,
       sdk,
     });
+
+    deposits.push({ depositor: buyer.publicKey, mint: masterEditionMint });
   });
 
-  return { totalDepositAmount };
+  return { buyers, deposits, totalDepositAmount };
 }
@@ -1,9 +1,11 @@
 import {
   createSplToken,
+  mintMasterEditionForTest,
   range,
 } from "@formfunction-hq/formfunction-program-shared";
 import { NATIVE_MINT } from "@solana/spl-token";
 import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
 import CreateCampaignEscrowInput from "sdk/types/CreateCampaignEscrowInput";
 import CampaignEscrowSetupOptions from "tests/types/CampaignEscrowSetupOptions";
 import CampaignEscrowSetupOptionsInput from "tests/types/CampaignEscrowSetupOptionsInput";
@@ -55,9 +57,18 @@ export default async function getCampaignEscrowInputForTest(
     setupOptions?.payoutPhases ?? getPayoutPhasesForTest().valid[0];
   const { campaignUuid } = options;
   const createCampaignEscrowInput: CreateCampaignEscrowInput = {
+    autoEndAtHardCap: setupOptions?.autoEndAtHardCap ?? false,
     campaignEndTime: getSecondsAheadUnixTime(5),
-    nonVotingPayoutPhases: payoutPhasesInput.nonVotingPayoutPhases,
-    votingPayoutPhases: payoutPhasesInput.votingPayoutPhases,
+    // The authority is the collection update authority, which verifies the
+    // deposit NFTs in mintCampaignNftForTest.
+    collectionMint: await mintMasterEditionForTest(authority, connection),
+    crankReward: setupOptions?.crankReward ?? { none: {} },
+    depositGracePeriod: new BN(0),
+    fundraisingStartTime: setupOptions?.fundraisingStartTime ?? null,
+    hardCap: setupOptions?.hardCap ?? null,
+    payoutPhases: payoutPhasesInput.payoutPhases,
+    rewardTiers: setupOptions?.rewardTiers ?? [],
+    surplusPolicy: setupOptions?.surplusPolicy ?? { creator: {} },
   };
 
   return {
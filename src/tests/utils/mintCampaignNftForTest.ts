@@ -0,0 +1,58 @@
+import {
+  ixToTx,
+  mintMasterEditionForTest,
+} from "@formfunction-hq/formfunction-program-shared";
+import {
+  createSetAndVerifyCollectionInstruction,
+  PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+} from "@metaplex-foundation/mpl-token-metadata";
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import CampaignTreasuryManagerSdk from "sdk/CampaignTreasuryManagerSdk";
+import findTokenMetadataPda from "sdk/pdas/findTokenMetadataPda";
+import sendTransactionForTest from "tests/utils/sendTransactionForTest";
+
+/**
+ * Mints an NFT to the buyer which is a verified member of the campaign
+ * collection. The authority must be the collection update authority, which is
+ * the case for campaigns created with createCampaignEscrowForTest.
+ */
+export default async function mintCampaignNftForTest({
+  authority,
+  buyer,
+  campaignUuid,
+  connection,
+  sdk,
+}: {
+  authority: Keypair;
+  buyer: Keypair;
+  campaignUuid: string;
+  connection: Connection;
+  sdk: CampaignTreasuryManagerSdk;
+}): Promise<PublicKey> {
+  const { collectionMint } =
+    await sdk.fetchCampaignEscrowAccountWithCampaignUuid(campaignUuid);
+  const mint = await mintMasterEditionForTest(buyer, connection);
+
+  const [collectionMasterEditionAccount] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      collectionMint.toBuffer(),
+      Buffer.from("edition"),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+
+  const ix = createSetAndVerifyCollectionInstruction({
+    collection: findTokenMetadataPda(collectionMint)[0],
+    collectionAuthority: authority.publicKey,
+    collectionMasterEditionAccount,
+    collectionMint,
+    metadata: findTokenMetadataPda(mint)[0],
+    payer: authority.publicKey,
+    updateAuthority: buyer.publicKey,
+  });
+  await sendTransactionForTest(connection, ixToTx(ix), [authority, buyer]);
+
+  return mint;
+}
@@ -1,8 +1,12 @@
-import { percentToBasisPoints } from "@formfunction-hq/formfunction-program-shared";
+import {
+  percentToBasisPoints,
+  solToLamports,
+} from "@formfunction-hq/formfunction-program-shared";
 import BN from "bn.js";
+import PayoutPhaseInput from "sdk/types/PayoutPhaseInput";
 import PayoutPhasesTestInput from "tests/types/PayoutPhasesTestInput";
 import getSecondsAheadUnixTime from "tests/utils/getSecondsAheadUnixTime";
-import getDefaultNonVotingSharedFields from "tests/utils/payout-phases/getDefaultNonVotingSharedFields";
+import getDefaultPayoutPhaseInput from "tests/utils/payout-phases/getDefaultPayoutPhaseInput";
 import getSecondsAfterCampaignEndTime from "tests/utils/payout-phases/getSecondsAfterCampaignEndTime";
 
 type PayoutPhasesTestCases = Array<
@@ -11,29 +15,104 @@ type PayoutPhasesTestCases = Array<
   }
 >;
 
+// The second payout phase is replaced by the third once the raise reaches the
+// 5 SOL stretch goal threshold.
+function getStretchGoalPayoutPhases(
+  campaignEndTime: BN,
+  finalPayoutPercent: number
+): Array<PayoutPhaseInput> {
+  const raiseThreshold = new BN(solToLamports(5));
+  return [
+    {
+      ...getDefaultPayoutPhaseInput(campaignEndTime),
+      payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+    },
+    {
+      ...getDefaultPayoutPhaseInput(campaignEndTime),
+      description: "Final payout if the stretch goal is not reached.",
+      payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+      payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 10),
+      refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 13),
+      stretchGoal: { isActiveWhenReached: false, raiseThreshold },
+    },
+    {
+      ...getDefaultPayoutPhaseInput(campaignEndTime),
+      description: "Final payout if the stretch goal is reached.",
+      payoutBasisPoints: percentToBasisPoints(finalPayoutPercent).toNumber(),
+      payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 14),
+      refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 17),
+      stretchGoal: { isActiveWhenReached: true, raiseThreshold },
+    },
+  ];
+}
+
 function getValidTestCases(campaignEndTime: BN): PayoutPhasesTestCases {
   return [
     {
       label: "Default valid payout phases I.",
-      nonVotingPayoutPhases: [
+      payoutPhases: [getDefaultPayoutPhaseInput(campaignEndTime)],
+    },
+    {
+      label: "Default valid payout phases II.",
+      payoutPhases: [
         {
-          sharedFields: getDefaultNonVotingSharedFields(campaignEndTime),
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 4),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 7),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
-      label: "Default valid payout phases II.",
-      nonVotingPayoutPhases: [
+      label: "Valid multiple payout phases.",
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 4),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 7),
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+        },
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          description: "Final payout.",
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 10),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 13),
+        },
+      ],
+    },
+    {
+      label: "Valid voting payout phase.",
+      payoutPhases: [
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+        },
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          description: "Final payout, which supporters can vote to veto.",
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 10),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 13),
+          votingConfig: {
+            voteBasisPointsVetoThreshold: percentToBasisPoints(50),
+            votingStartTime: getSecondsAfterCampaignEndTime(campaignEndTime, 7),
+          },
+        },
+      ],
+    },
+    {
+      label: "Valid stretch goal payout phases.",
+      payoutPhases: getStretchGoalPayoutPhases(campaignEndTime, 50),
+    },
+    {
+      label: "Valid streaming payout phase.",
+      payoutPhases: [
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          description: "Payout streamed over six seconds.",
+          stream: {
+            streamEndTime: getSecondsAfterCampaignEndTime(campaignEndTime, 12),
           },
         },
       ],
-      votingPayoutPhases: [],
     },
   ];
 }
@@ -41,135 +120,172 @@ function getValidTestCases(campaignEndTime: BN): PayoutPhasesTestCases {
 function getInvalidTestCases(campaignEndTime: BN): PayoutPhasesTestCases {
   return [
     {
-      label: "Invalid initial payout phase index I.",
-      nonVotingPayoutPhases: [
+      label: "Invalid payout phase order.",
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            index: 1,
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+        },
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 5),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 8),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
-      label: "Invalid initial payout phase index II.",
-      nonVotingPayoutPhases: [
+      label: "Invalid total basis points across multiple payout phases.",
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            index: 10,
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(50).toNumber(),
+        },
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(40).toNumber(),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 10),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 13),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label: "Invalid initial basis points I.",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutBasisPoints: percentToBasisPoints(20).toNumber(),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(20).toNumber(),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label: "Invalid initial basis points II.",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutBasisPoints: percentToBasisPoints(101).toNumber(),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutBasisPoints: percentToBasisPoints(101).toNumber(),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label: "Invalid initial payout payout time.",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, -1),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 30),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, -1),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 30),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label:
         "Invalid initial payout payout time (invalid minimum buffer time).",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 1),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 30),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 1),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 30),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label:
         "Invalid initial payout payout time (invalid maximum buffer time).",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 25),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 30),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 25),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 30),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label:
         "Invalid initial payout refund deadline (refund deadline is before payout time).",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 5),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 5),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label:
         "Invalid initial payout refund deadline (invalid minimum buffer time).",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 7),
-          },
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 7),
         },
       ],
-      votingPayoutPhases: [],
     },
     {
       label:
         "Invalid initial payout refund deadline (invalid maximum buffer time).",
-      nonVotingPayoutPhases: [
+      payoutPhases: [
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
+          refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 25),
+        },
+      ],
+    },
+    {
+      label:
+        "Invalid voting payout phase (voting starts before the campaign end time).",
+      payoutPhases: [
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          votingConfig: {
+            voteBasisPointsVetoThreshold: percentToBasisPoints(50),
+            votingStartTime: getSecondsAfterCampaignEndTime(
+              campaignEndTime,
+              -1
+            ),
+          },
+        },
+      ],
+    },
+    {
+      label: "Invalid voting payout phase (voting starts at the payout time).",
+      payoutPhases: [
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          votingConfig: {
+            voteBasisPointsVetoThreshold: percentToBasisPoints(50),
+            votingStartTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
+          },
+        },
+      ],
+    },
+    {
+      label: "Invalid total basis points for a stretch goal outcome.",
+      payoutPhases: getStretchGoalPayoutPhases(campaignEndTime, 30),
+    },
+    {
+      label: "Invalid streaming payout phase (stream ends at the payout time).",
+      payoutPhases: [
+        {
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          stream: {
+            streamEndTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
+          },
+        },
+      ],
+    },
+    {
+      label: "Invalid voting payout phase (zero veto threshold).",
+      payoutPhases: [
         {
-          sharedFields: {
-            ...getDefaultNonVotingSharedFields(campaignEndTime),
-            payoutTime: getSecondsAfterCampaignEndTime(campaignEndTime, 6),
-            refundDeadline: getSecondsAfterCampaignEndTime(campaignEndTime, 25),
+          ...getDefaultPayoutPhaseInput(campaignEndTime),
+          votingConfig: {
+            voteBasisPointsVetoThreshold: new BN(0),
+            votingStartTime: getSecondsAfterCampaignEndTime(campaignEndTime, 3),
           },
         },
       ],
-      votingPayoutPhases: [],
     },
   ];
 }
@@ -3,7 +3,7 @@ import {
   getTokenBalance,
   isMintNative,
 } from "@formfunction-hq/formfunction-program-shared";
-import { Connection, Keypair } from "@solana/web3.js";
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
 import dayjs from "dayjs";
 import CampaignTreasuryManagerSdk from "sdk/CampaignTreasuryManagerSdk";
 import findTreasuryEscrowPda from "sdk/pdas/findTreasuryEscrowPda";
@@ -14,6 +14,7 @@ import sendTransactionForTest from "tests/utils/sendTransactionForTest";
 import waitMinDelayForTimeBuffer from "tests/utils/waitMinDelayForTimeBuffer";
 
 type PayoutResult = {
+  crankRewardAmount: number;
   payoutAmount: number;
 };
 
@@ -21,10 +22,12 @@ type PayoutResult = {
 export default async function payoutFundsForTest(args: {
   authority: Keypair;
   connection: Connection;
+  crankRewardReceiver?: PublicKey;
   sdk: CampaignTreasuryManagerSdk;
   setupResult: CampaignEscrowTestSetupResult;
 }): Promise<PayoutResult> {
-  const { authority, connection, sdk, setupResult } = args;
+  const { authority, connection, crankRewardReceiver, sdk, setupResult } =
+    args;
   const { campaignUuid } = setupResult;
 
   const [treasuryEscrow] = findTreasuryEscrowPda(
@@ -46,8 +49,21 @@ export default async function payoutFundsForTest(args: {
     ? await connection.getBalance(treasuryEscrow)
     : await getTokenBalance(connection, treasuryEscrow);
 
+  const getCrankRewardReceiverBalance = async () => {
+    if (crankRewardReceiver == null) {
+      return 0;
+    }
+
+    return isTreasuryNative
+      ? connection.getBalance(crankRewardReceiver)
+      : getTokenBalance(connection, crankRewardReceiver);
+  };
+  const startingCrankRewardReceiverBalance =
+    await getCrankRewardReceiverBalance();
+
   const transaction = await sdk.payOutFundsTx(
     {
+      crankRewardReceiver,
       payer: authority.publicKey,
     },
     {
@@ -63,10 +79,16 @@ export default async function payoutFundsForTest(args: {
     transaction,
   });
 
+  const [campaignEscrow] = await sdk.findCampaignEscrowPda(campaignUuid);
+  const campaignEscrowAccount = await sdk.fetchCampaignEscrowAccount(
+    campaignEscrow
+  );
   const payoutPhase = getCurrentActivePayoutPhase(
-    setupResult.campaignEscrowAccount.payoutPhases
+    campaignEscrowAccount.payoutPhases
   );
-  const payoutTime = payoutPhase!.sharedFields.payoutTime.toNumber();
+  const payoutPhaseIndex =
+    campaignEscrowAccount.payoutPhases.payoutPhases.indexOf(payoutPhase!);
+  const payoutTime = payoutPhase!.payoutTime.toNumber();
   const minDelay = payoutTime - dayjs().unix();
   await waitMinDelayForTimeBuffer(minDelay);
 
@@ -81,11 +103,35 @@ export default async function payoutFundsForTest(args: {
     : await getTokenBalance(connection, treasuryEscrow);
 
   const payoutAmount = startingTreasuryBalance - endingTreasuryBalance;
+  const crankRewardAmount =
+    (await getCrankRewardReceiverBalance()) -
+    startingCrankRewardReceiverBalance;
 
   expectNumbersEqual(
     payoutAmount,
-    endingPayoutWalletBalance - startingPayoutWalletBalance
+    endingPayoutWalletBalance - startingPayoutWalletBalance + crankRewardAmount
+  );
+
+  const updatedCampaignEscrowAccount = await sdk.fetchCampaignEscrowAccount(
+    campaignEscrow
+  );
+  expectNumbersEqual(
+    updatedCampaignEscrowAccount.treasury.totalPaidOut.sub(
+      campaignEscrowAccount.treasury.totalPaidOut
+    ),
+    payoutAmount
+  );
+  expectNumbersEqual(
+    updatedCampaignEscrowAccount.payoutPhases.payoutPhases[payoutPhaseIndex]
+      .paidAmount,
+    payoutAmount
+  );
+  expectNumbersEqual(
+    updatedCampaignEscrowAccount.treasury.totalCrankRewards.sub(
+      campaignEscrowAccount.treasury.totalCrankRewards
+    ),
+    crankRewardAmount
   );
 
-  return { payoutAmount };
+  return { crankRewardAmount, payoutAmount };
 }
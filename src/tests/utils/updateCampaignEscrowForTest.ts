@@ -64,10 +64,17 @@ export default async function updateCampaignEscrowForTest({
   );
 
   const {
+    autoEndAtHardCap,
     campaignEndTime,
+    collectionMint,
+    crankReward,
     creator,
-    nonVotingPayoutPhases,
-    votingPayoutPhases,
+    depositEscrowInfosCapacity,
+    depositGracePeriod,
+    fundraisingStartTime,
+    hardCap,
+    payoutPhases,
+    surplusPolicy,
   } = updateCampaignEscrowInput;
 
   if (updateCampaignEscrowInput.authority != null) {
@@ -87,11 +94,51 @@ export default async function updateCampaignEscrowForTest({
     );
   }
 
-  if (nonVotingPayoutPhases != null || votingPayoutPhases != null) {
+  if (payoutPhases != null) {
     comparePayoutPhasesForTest(
       updatedCampaignEscrow.payoutPhases,
-      nonVotingPayoutPhases ?? [],
-      votingPayoutPhases ?? []
+      payoutPhases
+    );
+  }
+
+  if (collectionMint != null) {
+    expectPublicKeysEqual(updatedCampaignEscrow.collectionMint, collectionMint);
+  }
+
+  if (crankReward != null) {
+    expect(updatedCampaignEscrow.crankReward).toEqual(crankReward);
+  }
+
+  if (surplusPolicy != null) {
+    expect(updatedCampaignEscrow.surplusPolicy).toEqual(surplusPolicy);
+  }
+
+  if (fundraisingStartTime != null) {
+    expectNumbersEqual(
+      updatedCampaignEscrow.fundraisingStartTime!,
+      fundraisingStartTime
+    );
+  }
+
+  if (depositGracePeriod != null) {
+    expectNumbersEqual(
+      updatedCampaignEscrow.depositGracePeriod,
+      depositGracePeriod
+    );
+  }
+
+  if (hardCap != null) {
+    expectNumbersEqual(updatedCampaignEscrow.hardCap!, hardCap);
+  }
+
+  if (autoEndAtHardCap != null) {
+    expect(updatedCampaignEscrow.autoEndAtHardCap).toBe(autoEndAtHardCap);
+  }
+
+  if (depositEscrowInfosCapacity != null) {
+    expectNumbersEqual(
+      updatedCampaignEscrow.depositEscrowInfosCapacity,
+      depositEscrowInfosCapacity
     );
   }
 